@@ -2,7 +2,9 @@
 // Pure functions: two datasets in, matched/unmatched/diff rows out.
 // No IO, no clap, no formatting.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::locale::NumberLocale;
 
 // ---------------------------------------------------------------------------
 // Options
@@ -21,6 +23,11 @@ pub struct DiffOptions {
     /// When None, the right key column is searched. When Some, this column is
     /// searched instead. Index into headers[].
     pub contains_col: Option<usize>,
+    /// Treat an empty cell as 0 when the other side is numeric, so a blank
+    /// and `0` compare equal. Opt-in: empty-vs-zero is sometimes meaningful.
+    pub empty_as_zero: bool,
+    /// Decimal and grouping separators for numeric comparison (`--locale`).
+    pub number_locale: NumberLocale,
 }
 
 /// Separator for composite key values. ASCII Unit Separator — won't appear in
@@ -70,6 +77,9 @@ pub struct DataRow {
     pub key_raw: String,
     pub key_norm: String,
     pub values: HashMap<String, String>,
+    /// 1-based row number in the source file (CSV record or sheet row),
+    /// counting the header and any skipped blank rows.
+    pub source_index: usize,
 }
 
 // ---------------------------------------------------------------------------
@@ -93,6 +103,14 @@ pub enum RowStatus {
 }
 
 impl RowStatus {
+    pub const ALL: [RowStatus; 5] = [
+        RowStatus::Matched,
+        RowStatus::OnlyLeft,
+        RowStatus::OnlyRight,
+        RowStatus::Diff,
+        RowStatus::Ambiguous,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             RowStatus::Matched => "matched",
@@ -110,6 +128,9 @@ pub struct DiffRow {
     pub key: String,
     pub left: Option<HashMap<String, String>>,
     pub right: Option<HashMap<String, String>>,
+    /// Source line of the left/right row (see `DataRow::source_index`); `None` when the row is absent on that side.
+    pub left_source_index: Option<usize>,
+    pub right_source_index: Option<usize>,
     pub diffs: Vec<ColumnDiff>,
     pub match_explain: Option<MatchExplain>,
     pub candidates: Option<Vec<Candidate>>,
@@ -179,6 +200,55 @@ pub struct DiffSummary {
     pub ambiguous: usize,
 }
 
+/// Why a diff run passed or failed, reported as `exit_reason` in `--json` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    Reconciled,
+    MissingRows,
+    ValueDiffs,
+    Ambiguous,
+}
+
+impl ExitReason {
+    pub const ALL: [ExitReason; 4] = [
+        ExitReason::Reconciled,
+        ExitReason::MissingRows,
+        ExitReason::ValueDiffs,
+        ExitReason::Ambiguous,
+    ];
+
+    /// The dominant failure category: missing rows, then value diffs (outside
+    /// tolerance unless `strict_exit`), then ambiguous matches. With
+    /// `baseline`, only_right rows are improvements and don't count as missing.
+    pub fn from_summary(s: &DiffSummary, strict_exit: bool, baseline: bool) -> Self {
+        let diff_count = if strict_exit { s.diff } else { s.diff_outside_tolerance };
+        if s.only_left > 0 || (!baseline && s.only_right > 0) {
+            ExitReason::MissingRows
+        } else if diff_count > 0 {
+            ExitReason::ValueDiffs
+        } else if s.ambiguous > 0 {
+            ExitReason::Ambiguous
+        } else {
+            ExitReason::Reconciled
+        }
+    }
+
+    /// Whether this reason fails the run (exit 1). Reported ambiguous
+    /// matches don't; `--on-ambiguous error` fails before any output.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, ExitReason::MissingRows | ExitReason::ValueDiffs)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::Reconciled => "reconciled",
+            ExitReason::MissingRows => "missing_rows",
+            ExitReason::ValueDiffs => "value_diffs",
+            ExitReason::Ambiguous => "ambiguous",
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -219,6 +289,26 @@ pub fn apply_key_transform(raw: &str, transform: KeyTransform) -> String {
     }
 }
 
+/// Pick a key column for `--key auto`: the leftmost column whose values are
+/// non-empty and unique (after `transform`) in both datasets.
+///
+/// Rows are data rows only (no header). Returns `None` when no column qualifies.
+pub fn auto_key_column(
+    left: &[Vec<String>],
+    right: &[Vec<String>],
+    num_cols: usize,
+    transform: KeyTransform,
+) -> Option<usize> {
+    let unique_non_empty = |rows: &[Vec<String>], col: usize| {
+        let mut seen = std::collections::HashSet::new();
+        rows.iter().all(|row| {
+            let key = apply_key_transform(row.get(col).map(String::as_str).unwrap_or(""), transform);
+            !key.is_empty() && seen.insert(key)
+        })
+    };
+    (0..num_cols).find(|&col| unique_non_empty(left, col) && unique_non_empty(right, col))
+}
+
 // ---------------------------------------------------------------------------
 // Numeric parsing for financial values
 // ---------------------------------------------------------------------------
@@ -264,15 +354,59 @@ pub fn parse_financial_number(s: &str) -> Option<f64> {
     Some(if is_negative { -value } else { value })
 }
 
+/// [`parse_financial_number`] for numbers written in `locale`: `(1.234,56 €)`
+/// is -1234.56 under `De`. `En` is exactly `parse_financial_number`.
+pub fn parse_financial_number_in(s: &str, locale: NumberLocale) -> Option<f64> {
+    if locale == NumberLocale::En {
+        return parse_financial_number(s);
+    }
+    let trimmed = s.trim();
+    let (is_negative, inner) = if trimmed.starts_with('(') && trimmed.ends_with(')') {
+        (true, &trimmed[1..trimmed.len() - 1])
+    } else {
+        (false, trimmed)
+    };
+    let cleaned: String = inner.chars().filter(|c| !matches!(c, '$' | '€' | '£')).collect();
+    let value = locale.parse(&cleaned)?;
+    if is_negative && value < 0.0 {
+        return None;
+    }
+    Some(if is_negative { -value } else { value })
+}
+
 // ---------------------------------------------------------------------------
 // Core reconciliation
 // ---------------------------------------------------------------------------
 
+/// Phase of `reconcile` reported to a progress callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconcilePhase {
+    /// Building the key index over the right side.
+    IndexRight,
+    /// Matching each left row against the index.
+    ScanLeft,
+}
+
+/// Rows between progress callbacks.
+pub const PROGRESS_INTERVAL: usize = 1000;
+
 pub fn reconcile(
     left_rows: &[DataRow],
     right_rows: &[DataRow],
     headers: &[String],
     options: &DiffOptions,
+) -> Result<DiffResult, DiffError> {
+    reconcile_with_progress(left_rows, right_rows, headers, options, |_, _, _| {})
+}
+
+/// `reconcile`, calling `progress(phase, done, total)` every
+/// `PROGRESS_INTERVAL` rows and once at the end of each phase.
+pub fn reconcile_with_progress(
+    left_rows: &[DataRow],
+    right_rows: &[DataRow],
+    headers: &[String],
+    options: &DiffOptions,
+    mut progress: impl FnMut(ReconcilePhase, usize, usize),
 ) -> Result<DiffResult, DiffError> {
     // 1. Check for duplicate keys.
     // Left duplicates are always an error (each left row is processed once).
@@ -288,37 +422,33 @@ pub fn reconcile(
     }
 
     // 2. Determine which columns to compare
-    let compare_cols: Vec<String> = match &options.compare_cols {
-        Some(indices) => indices
-            .iter()
-            .filter_map(|&i| headers.get(i).cloned())
-            .collect(),
-        None => headers
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| !options.key_cols.contains(i))
-            .map(|(_, h)| h.clone())
-            .collect(),
-    };
+    let compare_cols = resolve_compare_cols(headers, options);
 
     // 3. Build index on right side
     let mut right_index: HashMap<String, usize> = HashMap::new();
     for (i, row) in right_rows.iter().enumerate() {
         right_index.insert(row.key_norm.clone(), i);
+        if (i + 1) % PROGRESS_INTERVAL == 0 {
+            progress(ReconcilePhase::IndexRight, i + 1, right_rows.len());
+        }
     }
+    progress(ReconcilePhase::IndexRight, right_rows.len(), right_rows.len());
 
     let mut right_consumed = vec![false; right_rows.len()];
     let mut results = Vec::new();
     let mut ambiguous_keys = Vec::new();
 
     // 4. Match left rows against right
-    for left_row in left_rows {
+    for (left_idx, left_row) in left_rows.iter().enumerate() {
+        if left_idx > 0 && left_idx % PROGRESS_INTERVAL == 0 {
+            progress(ReconcilePhase::ScanLeft, left_idx, left_rows.len());
+        }
         match options.match_mode {
             MatchMode::Exact => {
                 if let Some(&right_idx) = right_index.get(&left_row.key_norm) {
                     right_consumed[right_idx] = true;
                     let right_row = &right_rows[right_idx];
-                    let diffs = compare_values(left_row, right_row, &compare_cols, options.tolerance);
+                    let diffs = compare_values(left_row, right_row, &compare_cols, options.tolerance, options.empty_as_zero, options.number_locale);
                     let status = if diffs.is_empty() {
                         RowStatus::Matched
                     } else {
@@ -329,6 +459,8 @@ pub fn reconcile(
                         key: left_row.key_norm.clone(),
                         left: Some(left_row.values.clone()),
                         right: Some(right_row.values.clone()),
+                        left_source_index: Some(left_row.source_index),
+                        right_source_index: Some(right_row.source_index),
                         diffs,
                         match_explain: None,
                         candidates: None,
@@ -339,6 +471,8 @@ pub fn reconcile(
                         key: left_row.key_norm.clone(),
                         left: Some(left_row.values.clone()),
                         right: None,
+                        left_source_index: Some(left_row.source_index),
+                        right_source_index: None,
                         diffs: Vec::new(),
                         match_explain: None,
                         candidates: None,
@@ -374,6 +508,8 @@ pub fn reconcile(
                             key: left_row.key_norm.clone(),
                             left: Some(left_row.values.clone()),
                             right: None,
+                            left_source_index: Some(left_row.source_index),
+                            right_source_index: None,
                             diffs: Vec::new(),
                             match_explain: Some(make_explain("contains", left_row, left_row)),
                             candidates: None,
@@ -382,7 +518,7 @@ pub fn reconcile(
                     1 => {
                         let (right_idx, right_row) = matches[0];
                         right_consumed[right_idx] = true;
-                        let diffs = compare_values(left_row, right_row, &compare_cols, options.tolerance);
+                        let diffs = compare_values(left_row, right_row, &compare_cols, options.tolerance, options.empty_as_zero, options.number_locale);
                         let status = if diffs.is_empty() {
                             RowStatus::Matched
                         } else {
@@ -393,6 +529,8 @@ pub fn reconcile(
                             key: left_row.key_norm.clone(),
                             left: Some(left_row.values.clone()),
                             right: Some(right_row.values.clone()),
+                            left_source_index: Some(left_row.source_index),
+                            right_source_index: Some(right_row.source_index),
                             diffs,
                             match_explain: Some(make_explain_pair("contains", left_row, right_row)),
                             candidates: None,
@@ -419,6 +557,8 @@ pub fn reconcile(
                                 key: left_row.key_norm.clone(),
                                 left: Some(left_row.values.clone()),
                                 right: None,
+                                left_source_index: Some(left_row.source_index),
+                                right_source_index: None,
                                 diffs: Vec::new(),
                                 match_explain: None,
                                 candidates: Some(candidates),
@@ -430,6 +570,8 @@ pub fn reconcile(
         }
     }
 
+    progress(ReconcilePhase::ScanLeft, left_rows.len(), left_rows.len());
+
     // 5. Any right rows not consumed → only_right
     for (i, right_row) in right_rows.iter().enumerate() {
         if !right_consumed[i] {
@@ -438,6 +580,8 @@ pub fn reconcile(
                 key: right_row.key_norm.clone(),
                 left: None,
                 right: Some(right_row.values.clone()),
+                left_source_index: None,
+                right_source_index: Some(right_row.source_index),
                 diffs: Vec::new(),
                 match_explain: None,
                 candidates: None,
@@ -468,10 +612,509 @@ pub fn reconcile(
     })
 }
 
+// ---------------------------------------------------------------------------
+// Streaming reconciliation (exact match only)
+// ---------------------------------------------------------------------------
+
+/// Incremental exact-match reconciler for `visigrid diff --stream`.
+///
+/// Borrows the right side and indexes it by key. Left rows are fed one at a
+/// time through `push_left` and are not retained, so the left file never has
+/// to fit in memory. Only their keys are kept, to reject a repeated left key
+/// as soon as it appears. Produces the same rows, in the same order, as
+/// `reconcile` in exact mode.
+pub struct StreamReconciler<'a> {
+    right_rows: &'a [DataRow],
+    right_index: HashMap<String, usize>,
+    right_consumed: Vec<bool>,
+    compare_cols: Vec<String>,
+    tolerance: f64,
+    empty_as_zero: bool,
+    number_locale: NumberLocale,
+    left_keys: HashSet<String>,
+    summary: DiffSummary,
+}
+
+impl<'a> StreamReconciler<'a> {
+    /// Index the right side. Fails on duplicate right keys, like `reconcile`.
+    pub fn new(right_rows: &'a [DataRow], headers: &[String], options: &DiffOptions) -> Result<Self, DiffError> {
+        debug_assert_eq!(options.match_mode, MatchMode::Exact, "streaming requires exact match");
+
+        let mut duplicates = Vec::new();
+        check_duplicates(right_rows, Side::Right, &mut duplicates);
+        if !duplicates.is_empty() {
+            return Err(DiffError::DuplicateKeys(duplicates));
+        }
+
+        let mut right_index: HashMap<String, usize> = HashMap::with_capacity(right_rows.len());
+        for (i, row) in right_rows.iter().enumerate() {
+            right_index.insert(row.key_norm.clone(), i);
+        }
+
+        let summary = DiffSummary {
+            right_rows: right_rows.len(),
+            ..DiffSummary::default()
+        };
+
+        Ok(StreamReconciler {
+            right_consumed: vec![false; right_rows.len()],
+            right_rows,
+            right_index,
+            compare_cols: resolve_compare_cols(headers, options),
+            tolerance: options.tolerance,
+            empty_as_zero: options.empty_as_zero,
+            number_locale: options.number_locale,
+            left_keys: HashSet::new(),
+            summary,
+        })
+    }
+
+    /// Match one left row against the right index and return its result row.
+    /// Fails on the first left key seen twice.
+    pub fn push_left(&mut self, left_row: DataRow) -> Result<DiffRow, DiffError> {
+        if !self.left_keys.insert(left_row.key_norm.clone()) {
+            return Err(DiffError::DuplicateKeys(vec![DuplicateKey {
+                side: Side::Left,
+                key: left_row.key_norm,
+                count: 2,
+            }]));
+        }
+        self.summary.left_rows += 1;
+
+        let row = match self.right_index.get(&left_row.key_norm) {
+            Some(&right_idx) => {
+                self.right_consumed[right_idx] = true;
+                let right_row = &self.right_rows[right_idx];
+                let diffs = compare_values(&left_row, right_row, &self.compare_cols, self.tolerance, self.empty_as_zero, self.number_locale);
+                let status = if diffs.is_empty() {
+                    self.summary.matched += 1;
+                    RowStatus::Matched
+                } else {
+                    self.summary.diff += 1;
+                    if diffs.iter().any(|d| !d.within_tolerance) {
+                        self.summary.diff_outside_tolerance += 1;
+                    }
+                    RowStatus::Diff
+                };
+                DiffRow {
+                    status,
+                    key: left_row.key_norm,
+                    left: Some(left_row.values),
+                    right: Some(right_row.values.clone()),
+                    left_source_index: Some(left_row.source_index),
+                    right_source_index: Some(right_row.source_index),
+                    diffs,
+                    match_explain: None,
+                    candidates: None,
+                }
+            }
+            None => {
+                self.summary.only_left += 1;
+                DiffRow {
+                    status: RowStatus::OnlyLeft,
+                    key: left_row.key_norm,
+                    left: Some(left_row.values),
+                    right: None,
+                    left_source_index: Some(left_row.source_index),
+                    right_source_index: None,
+                    diffs: Vec::new(),
+                    match_explain: None,
+                    candidates: None,
+                }
+            }
+        };
+        Ok(row)
+    }
+
+    /// Emit the unconsumed right rows (`only_right`) and the final summary.
+    pub fn finish(self) -> (Vec<DiffRow>, DiffSummary) {
+        let mut summary = self.summary;
+        let mut tail = Vec::new();
+        for (row, consumed) in self.right_rows.iter().zip(self.right_consumed) {
+            if !consumed {
+                summary.only_right += 1;
+                tail.push(DiffRow {
+                    status: RowStatus::OnlyRight,
+                    key: row.key_norm.clone(),
+                    left: None,
+                    right: Some(row.values.clone()),
+                    left_source_index: None,
+                    right_source_index: Some(row.source_index),
+                    diffs: Vec::new(),
+                    match_explain: None,
+                    candidates: None,
+                });
+            }
+        }
+        (tail, summary)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Three-way reconciliation (`diff3`)
+// ---------------------------------------------------------------------------
+
+/// How a cell moved from the common base to the two edited sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStatus {
+    Unchanged,
+    ChangedLeft,
+    ChangedRight,
+    /// Both sides made the same change.
+    ChangedBoth,
+    /// Both sides changed the value, to different results.
+    Conflict,
+}
+
+impl MergeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeStatus::Unchanged => "unchanged",
+            MergeStatus::ChangedLeft => "changed_left",
+            MergeStatus::ChangedRight => "changed_right",
+            MergeStatus::ChangedBoth => "changed_both",
+            MergeStatus::Conflict => "conflict",
+        }
+    }
+}
+
+/// One changed cell. A side's value is `None` when the row is absent there.
+#[derive(Debug, Clone)]
+pub struct MergeCell {
+    pub column: String,
+    pub status: MergeStatus,
+    pub base: Option<String>,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// A key with at least one changed cell.
+#[derive(Debug, Clone)]
+pub struct MergeRow {
+    pub key: String,
+    pub in_base: bool,
+    pub in_left: bool,
+    pub in_right: bool,
+    pub cells: Vec<MergeCell>,
+}
+
+/// Cell counts by `MergeStatus`, over every key and compared column.
+#[derive(Debug, Clone, Default)]
+pub struct Diff3Summary {
+    pub base_rows: usize,
+    pub left_rows: usize,
+    pub right_rows: usize,
+    pub unchanged: usize,
+    pub changed_left: usize,
+    pub changed_right: usize,
+    pub changed_both: usize,
+    pub conflicts: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diff3Result {
+    /// Keys with changes, in base order, then keys added by left, then by right.
+    pub rows: Vec<MergeRow>,
+    pub summary: Diff3Summary,
+}
+
+/// Duplicate keys in one of the three inputs (`input` is "base", "left" or "right").
+#[derive(Debug)]
+pub struct Diff3Error {
+    pub input: &'static str,
+    pub duplicates: Vec<DuplicateKey>,
+}
+
+/// Whether `col` differs between the two sides of a pairwise reconcile row.
+/// A row present on one side only counts as a change where it holds a value;
+/// diffs within tolerance don't count.
+fn pair_changed(row: Option<&DiffRow>, col: &str) -> bool {
+    let non_empty = |side: &Option<HashMap<String, String>>| {
+        side.as_ref().and_then(|v| v.get(col)).is_some_and(|v| !v.is_empty())
+    };
+    match row {
+        None => false,
+        Some(row) => match row.status {
+            RowStatus::Matched | RowStatus::Ambiguous => false,
+            RowStatus::Diff => row.diffs.iter().any(|d| d.column == col && !d.within_tolerance),
+            RowStatus::OnlyLeft => non_empty(&row.left),
+            RowStatus::OnlyRight => non_empty(&row.right),
+        },
+    }
+}
+
+/// Three-way reconcile: classify every cell of `left` and `right` against
+/// their common ancestor `base`. Runs `reconcile` pairwise (base→left,
+/// base→right, left→right) and reads the per-column diffs, so key
+/// transforms and numeric tolerance behave exactly as in a two-way diff.
+/// Exact key matching only.
+pub fn reconcile3(
+    base_rows: &[DataRow],
+    left_rows: &[DataRow],
+    right_rows: &[DataRow],
+    headers: &[String],
+    options: &DiffOptions,
+) -> Result<Diff3Result, Diff3Error> {
+    for (input, rows) in [("base", base_rows), ("left", left_rows), ("right", right_rows)] {
+        let mut duplicates = Vec::new();
+        check_duplicates(rows, Side::Left, &mut duplicates);
+        if !duplicates.is_empty() {
+            return Err(Diff3Error { input, duplicates });
+        }
+    }
+    let options = DiffOptions { match_mode: MatchMode::Exact, ..options.clone() };
+    let pair = |a: &[DataRow], b: &[DataRow]| {
+        reconcile(a, b, headers, &options).expect("duplicate keys are rejected above")
+    };
+    let base_left = pair(base_rows, left_rows);
+    let base_right = pair(base_rows, right_rows);
+    let left_right = pair(left_rows, right_rows);
+    let index = |result: &DiffResult| -> HashMap<String, usize> {
+        result.results.iter().enumerate().map(|(i, r)| (r.key.clone(), i)).collect()
+    };
+    let (bl_index, br_index, lr_index) = (index(&base_left), index(&base_right), index(&left_right));
+
+    let by_key = |rows: &[DataRow]| -> HashMap<String, usize> {
+        rows.iter().enumerate().map(|(i, r)| (r.key_norm.clone(), i)).collect()
+    };
+    let (base_by_key, left_by_key, right_by_key) = (by_key(base_rows), by_key(left_rows), by_key(right_rows));
+
+    // Keys in base order, then additions on the left, then on the right
+    let mut keys: Vec<&str> = Vec::new();
+    let mut seen: std::collections::HashSet<&str> = std::collections::HashSet::new();
+    for row in base_rows.iter().chain(left_rows).chain(right_rows) {
+        if seen.insert(row.key_norm.as_str()) {
+            keys.push(row.key_norm.as_str());
+        }
+    }
+
+    let compare_cols = resolve_compare_cols(headers, &options);
+    let mut summary = Diff3Summary {
+        base_rows: base_rows.len(),
+        left_rows: left_rows.len(),
+        right_rows: right_rows.len(),
+        ..Default::default()
+    };
+    let mut rows = Vec::new();
+    for key in keys {
+        let base = base_by_key.get(key).map(|&i| &base_rows[i]);
+        let left = left_by_key.get(key).map(|&i| &left_rows[i]);
+        let right = right_by_key.get(key).map(|&i| &right_rows[i]);
+        let bl = bl_index.get(key).map(|&i| &base_left.results[i]);
+        let br = br_index.get(key).map(|&i| &base_right.results[i]);
+        let lr = lr_index.get(key).map(|&i| &left_right.results[i]);
+
+        let mut cells = Vec::new();
+        for col in &compare_cols {
+            let changed_left = pair_changed(bl, col);
+            let changed_right = pair_changed(br, col);
+            let status = match (changed_left, changed_right) {
+                (false, false) => MergeStatus::Unchanged,
+                (true, false) => MergeStatus::ChangedLeft,
+                (false, true) => MergeStatus::ChangedRight,
+                (true, true) if pair_changed(lr, col) => MergeStatus::Conflict,
+                (true, true) => MergeStatus::ChangedBoth,
+            };
+            match status {
+                MergeStatus::Unchanged => {
+                    summary.unchanged += 1;
+                    continue;
+                }
+                MergeStatus::ChangedLeft => summary.changed_left += 1,
+                MergeStatus::ChangedRight => summary.changed_right += 1,
+                MergeStatus::ChangedBoth => summary.changed_both += 1,
+                MergeStatus::Conflict => summary.conflicts += 1,
+            }
+            let value = |row: Option<&DataRow>| row.map(|r| r.values.get(col).cloned().unwrap_or_default());
+            cells.push(MergeCell {
+                column: col.clone(),
+                status,
+                base: value(base),
+                left: value(left),
+                right: value(right),
+            });
+        }
+        if !cells.is_empty() {
+            rows.push(MergeRow {
+                key: key.to_string(),
+                in_base: base.is_some(),
+                in_left: left.is_some(),
+                in_right: right.is_some(),
+                cells,
+            });
+        }
+    }
+
+    Ok(Diff3Result { rows, summary })
+}
+
+// ---------------------------------------------------------------------------
+// Table output (`--out table`)
+// ---------------------------------------------------------------------------
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BRIGHT_RED: &str = "\x1b[91m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Rows listed per status group before the rest are summarized as "... N more".
+pub const TABLE_MAX_ROWS_PER_GROUP: usize = 50;
+
+/// A row with at least one diff outside tolerance (or a non-numeric diff).
+fn is_material(row: &DiffRow) -> bool {
+    row.diffs.iter().any(|d| !d.within_tolerance)
+}
+
+/// Whether `left` and `right` differ by at most `tolerance`. The comparison
+/// is epsilon-inclusive, preserving human-decimal boundary semantics under
+/// IEEE-754 float representation.
+pub fn within_tolerance(left: f64, right: f64, tolerance: f64) -> bool {
+    let delta = (left - right).abs();
+    let scale = 1.0_f64
+        .max(left.abs())
+        .max(right.abs())
+        .max(delta)
+        .max(tolerance);
+    let eps = f64::EPSILON * 16.0 * scale;
+    delta <= tolerance + eps
+}
+
+/// Trim float noise from a delta: at most 6 decimals, no trailing zeros.
+fn format_delta(delta: f64) -> String {
+    let s = format!("{:.6}", delta);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// One table line for a result row: a status marker, the key, and for value
+/// diffs each changed column as `col: left → right (Δ delta)`. `color` wraps
+/// the line in the status color (red only_left, green only_right, yellow
+/// within tolerance, bright red material).
+pub fn format_table_line(row: &DiffRow, color: bool) -> String {
+    let (marker, ansi) = match row.status {
+        RowStatus::OnlyLeft => ("-", RED),
+        RowStatus::OnlyRight => ("+", GREEN),
+        RowStatus::Diff if is_material(row) => ("~", BRIGHT_RED),
+        RowStatus::Diff => ("~", YELLOW),
+        RowStatus::Ambiguous => ("?", ""),
+        RowStatus::Matched => ("=", ""),
+    };
+
+    let mut line = format!("  {} {}", marker, row.key);
+    if !row.diffs.is_empty() {
+        let changes: Vec<String> = row
+            .diffs
+            .iter()
+            .map(|d| match d.delta {
+                Some(delta) => format!("{}: {} → {} (Δ {})", d.column, d.left, d.right, format_delta(delta)),
+                None => format!("{}: {} → {}", d.column, d.left, d.right),
+            })
+            .collect();
+        line.push_str("  ");
+        line.push_str(&changes.join(", "));
+    }
+
+    if color && !ansi.is_empty() {
+        format!("{}{}{}", ansi, line, RESET)
+    } else {
+        line
+    }
+}
+
+/// Status groups in table order; matched rows are counted but not listed.
+const TABLE_GROUPS: [&str; 5] = ["only_left", "only_right", "diff", "diff_within_tolerance", "ambiguous"];
+
+fn table_group(row: &DiffRow) -> Option<usize> {
+    match row.status {
+        RowStatus::OnlyLeft => Some(0),
+        RowStatus::OnlyRight => Some(1),
+        RowStatus::Diff if is_material(row) => Some(2),
+        RowStatus::Diff => Some(3),
+        RowStatus::Ambiguous => Some(4),
+        RowStatus::Matched => None,
+    }
+}
+
+/// Render results as a compact table grouped by status, each group headed by
+/// its count.
+pub fn format_table(result: &DiffResult, color: bool) -> String {
+    let mut table = TableBuilder::new(color);
+    for row in &result.results {
+        table.push(row);
+    }
+    table.finish(result.summary.matched)
+}
+
+/// `format_table` fed one row at a time, for `diff --stream`. Keeps only the
+/// lines each group will list, so memory stays bounded however many rows
+/// arrive.
+pub struct TableBuilder {
+    color: bool,
+    groups: [(usize, Vec<String>); TABLE_GROUPS.len()],
+}
+
+impl TableBuilder {
+    pub fn new(color: bool) -> Self {
+        TableBuilder { color, groups: Default::default() }
+    }
+
+    pub fn push(&mut self, row: &DiffRow) {
+        if let Some(group) = table_group(row) {
+            let (count, lines) = &mut self.groups[group];
+            *count += 1;
+            if lines.len() < TABLE_MAX_ROWS_PER_GROUP {
+                lines.push(format_table_line(row, self.color));
+            }
+        }
+    }
+
+    pub fn finish(self, matched: usize) -> String {
+        let mut out = String::new();
+        for ((count, lines), label) in self.groups.iter().zip(TABLE_GROUPS) {
+            if *count == 0 {
+                continue;
+            }
+            let header = format!("{}: {}", label, count);
+            if self.color {
+                out.push_str(&format!("{}{}{}\n", BOLD, header, RESET));
+            } else {
+                out.push_str(&header);
+                out.push('\n');
+            }
+            for line in lines {
+                out.push_str(line);
+                out.push('\n');
+            }
+            if *count > TABLE_MAX_ROWS_PER_GROUP {
+                out.push_str(&format!("  ... {} more\n", count - TABLE_MAX_ROWS_PER_GROUP));
+            }
+        }
+        out.push_str(&format!("matched: {}\n", matched));
+        out
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Column names to compare: the explicit `compare_cols`, or every non-key header.
+fn resolve_compare_cols(headers: &[String], options: &DiffOptions) -> Vec<String> {
+    match &options.compare_cols {
+        Some(indices) => indices
+            .iter()
+            .filter_map(|&i| headers.get(i).cloned())
+            .collect(),
+        None => headers
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !options.key_cols.contains(i))
+            .map(|(_, h)| h.clone())
+            .collect(),
+    }
+}
+
 fn check_duplicates(rows: &[DataRow], side: Side, out: &mut Vec<DuplicateKey>) {
     let mut counts: HashMap<&str, usize> = HashMap::new();
     for row in rows {
@@ -493,6 +1136,8 @@ fn compare_values(
     right: &DataRow,
     compare_cols: &[String],
     tolerance: f64,
+    empty_as_zero: bool,
+    locale: NumberLocale,
 ) -> Vec<ColumnDiff> {
     let mut diffs = Vec::new();
 
@@ -506,21 +1151,20 @@ fn compare_values(
         }
 
         // Try numeric comparison
-        let left_num = parse_financial_number(left_val);
-        let right_num = parse_financial_number(right_val);
+        let mut left_num = parse_financial_number_in(left_val, locale);
+        let mut right_num = parse_financial_number_in(right_val, locale);
+        if empty_as_zero {
+            if left_val.is_empty() && right_num.is_some() {
+                left_num = Some(0.0);
+            } else if right_val.is_empty() && left_num.is_some() {
+                right_num = Some(0.0);
+            }
+        }
 
         match (left_num, right_num) {
             (Some(l), Some(r)) => {
                 let delta = (l - r).abs();
-                // Epsilon-inclusive comparison: preserve human-decimal boundary
-                // semantics under IEEE-754 float representation.
-                let scale = 1.0_f64
-                    .max(l.abs())
-                    .max(r.abs())
-                    .max(delta)
-                    .max(tolerance);
-                let eps = f64::EPSILON * 16.0 * scale;
-                let within = delta <= tolerance + eps;
+                let within = within_tolerance(l, r, tolerance);
                 if !within || delta > 0.0 {
                     // Report diff if values aren't identical (even if within tolerance)
                     // but mark within_tolerance accordingly
@@ -610,6 +1254,171 @@ mod tests {
         assert_eq!(parse_financial_number("N/A"), None);
     }
 
+    fn rows(data: &[&[&str]]) -> Vec<Vec<String>> {
+        data.iter().map(|r| r.iter().map(|v| v.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn test_auto_key_picks_leftmost_unique_column() {
+        // A repeats on the right, B has a blank on the left; C is the id
+        let left = rows(&[&["east", "x", "1"], &["west", "", "2"]]);
+        let right = rows(&[&["east", "x", "2"], &["east", "y", "1"]]);
+        assert_eq!(auto_key_column(&left, &right, 3, KeyTransform::Trim), Some(2));
+
+        // Uniqueness is judged on transformed keys
+        let left = rows(&[&["A-1", "p"], &["A1", "q"]]);
+        let right = rows(&[&["B-2", "p"], &["B-3", "q"]]);
+        assert_eq!(auto_key_column(&left, &right, 2, KeyTransform::None), Some(0));
+        assert_eq!(auto_key_column(&left, &right, 2, KeyTransform::Alnum), Some(1));
+        assert_eq!(auto_key_column(&left, &right, 1, KeyTransform::Alnum), None);
+    }
+
+    fn data_row(key: &str, amount: &str) -> DataRow {
+        let values = [("key", key), ("amount", amount)]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        DataRow { key_raw: key.to_string(), key_norm: key.to_string(), values, source_index: 0 }
+    }
+
+    #[test]
+    fn test_reconcile_progress_is_monotonic_and_result_unchanged() {
+        let left: Vec<DataRow> = (0..2500).map(|i| data_row(&format!("K{}", i), "1")).collect();
+        let right: Vec<DataRow> = (500..3100).map(|i| data_row(&format!("K{}", i), "2")).collect();
+        let headers = vec!["key".to_string(), "amount".to_string()];
+        let options = DiffOptions {
+            key_cols: vec![0],
+            compare_cols: None,
+            match_mode: MatchMode::Exact,
+            key_transform: KeyTransform::None,
+            on_ambiguous: AmbiguityPolicy::Error,
+            tolerance: 0.0,
+            contains_col: None,
+            empty_as_zero: false,
+            number_locale: NumberLocale::En,
+        };
+
+        let mut calls: Vec<(ReconcilePhase, usize, usize)> = Vec::new();
+        let with_progress = reconcile_with_progress(&left, &right, &headers, &options, |phase, done, total| {
+            calls.push((phase, done, total));
+        })
+        .unwrap();
+        let plain = reconcile(&left, &right, &headers, &options).unwrap();
+
+        let done_for = |phase| calls.iter().filter(|c| c.0 == phase).map(|c| c.1).collect::<Vec<_>>();
+        assert_eq!(done_for(ReconcilePhase::IndexRight), vec![1000, 2000, 2600]);
+        assert_eq!(done_for(ReconcilePhase::ScanLeft), vec![1000, 2000, 2500]);
+        // Indexing finishes before scanning starts
+        let first_scan = calls.iter().position(|c| c.0 == ReconcilePhase::ScanLeft).unwrap();
+        assert!(calls[..first_scan].iter().all(|c| c.0 == ReconcilePhase::IndexRight));
+        assert!(calls.iter().all(|c| c.1 <= c.2));
+
+        let rows = |r: &DiffResult| r.results.iter().map(|d| (d.status, d.key.clone())).collect::<Vec<_>>();
+        assert_eq!(rows(&with_progress), rows(&plain));
+        assert_eq!(with_progress.summary.matched, 0);
+        assert_eq!(with_progress.summary.diff, 2000);
+        assert_eq!(with_progress.summary.only_left, 500);
+        assert_eq!(with_progress.summary.only_right, 600);
+    }
+
+    #[test]
+    fn test_stream_reconciler_rejects_duplicate_left_key_on_push() {
+        let right = vec![data_row("A", "1"), data_row("B", "2")];
+        let headers = vec!["key".to_string(), "amount".to_string()];
+        let options = DiffOptions {
+            key_cols: vec![0],
+            compare_cols: None,
+            match_mode: MatchMode::Exact,
+            key_transform: KeyTransform::None,
+            on_ambiguous: AmbiguityPolicy::Error,
+            tolerance: 0.0,
+            contains_col: None,
+            empty_as_zero: false,
+            number_locale: NumberLocale::En,
+        };
+
+        let mut reconciler = StreamReconciler::new(&right, &headers, &options).unwrap();
+        assert_eq!(reconciler.push_left(data_row("A", "1")).unwrap().status, RowStatus::Matched);
+        assert_eq!(reconciler.push_left(data_row("C", "3")).unwrap().status, RowStatus::OnlyLeft);
+        let Err(DiffError::DuplicateKeys(dups)) = reconciler.push_left(data_row("A", "9")) else {
+            panic!("second A must be rejected as soon as it is pushed");
+        };
+        assert_eq!((dups[0].side, dups[0].key.as_str()), (Side::Left, "A"));
+
+        let (tail, summary) = reconciler.finish();
+        assert_eq!(tail.iter().map(|r| r.key.as_str()).collect::<Vec<_>>(), vec!["B"]);
+        assert_eq!((summary.left_rows, summary.matched, summary.only_left, summary.only_right), (2, 1, 1, 1));
+    }
+
+    #[test]
+    fn test_exit_reason_precedence() {
+        let summary = |only_left, only_right, diff, outside, ambiguous| DiffSummary {
+            only_left, only_right, diff, diff_outside_tolerance: outside, ambiguous, ..Default::default()
+        };
+        let reason = |s: &DiffSummary| ExitReason::from_summary(s, false, false);
+
+        assert_eq!(reason(&summary(0, 0, 0, 0, 0)), ExitReason::Reconciled);
+        assert_eq!(reason(&summary(1, 0, 3, 3, 1)), ExitReason::MissingRows);
+        assert_eq!(reason(&summary(0, 0, 2, 1, 1)), ExitReason::ValueDiffs);
+        assert_eq!(reason(&summary(0, 0, 0, 0, 2)), ExitReason::Ambiguous);
+
+        // Within-tolerance diffs only count under --strict-exit
+        let within = summary(0, 0, 2, 0, 0);
+        assert_eq!(reason(&within), ExitReason::Reconciled);
+        assert_eq!(ExitReason::from_summary(&within, true, false), ExitReason::ValueDiffs);
+
+        // --baseline: only_right rows are not missing
+        let improved = summary(0, 2, 0, 0, 0);
+        assert_eq!(reason(&improved), ExitReason::MissingRows);
+        assert_eq!(ExitReason::from_summary(&improved, false, true), ExitReason::Reconciled);
+    }
+
+    #[test]
+    fn test_empty_as_zero() {
+        let cols = vec!["amount".to_string()];
+        let blank = data_row("K1", "");
+
+        // Off by default: blank vs 0 is a string diff
+        let diffs = compare_values(&data_row("K1", "0"), &blank, &cols, 0.0, false, NumberLocale::En);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].delta, None);
+        assert!(!diffs[0].within_tolerance);
+
+        // With the flag, either side's blank reads as zero
+        assert!(compare_values(&data_row("K1", "0"), &blank, &cols, 0.0, true, NumberLocale::En).is_empty());
+        assert!(compare_values(&blank, &data_row("K1", "0.00"), &cols, 0.0, true, NumberLocale::En).is_empty());
+
+        // Still goes through tolerance
+        let diffs = compare_values(&blank, &data_row("K1", "0.004"), &cols, 0.01, true, NumberLocale::En);
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs[0].within_tolerance);
+        let diffs = compare_values(&blank, &data_row("K1", "5"), &cols, 0.01, true, NumberLocale::En);
+        assert!(!diffs[0].within_tolerance);
+
+        // Non-numeric columns are unaffected
+        assert_eq!(compare_values(&blank, &data_row("K1", "n/a"), &cols, 0.0, true, NumberLocale::En).len(), 1);
+    }
+
+    #[test]
+    fn test_locale_numeric_compare() {
+        let cols = vec!["amount".to_string()];
+        let de = NumberLocale::De;
+
+        assert_eq!(parse_financial_number_in("1.234,56", de), Some(1234.56));
+        assert_eq!(parse_financial_number_in("(1.234,56 €)", de), Some(-1234.56));
+        assert_eq!(parse_financial_number_in("1,234.56", NumberLocale::En), Some(1234.56));
+
+        // Same value, different grouping: no diff under de
+        assert!(compare_values(&data_row("K1", "1.234,56"), &data_row("K1", "1234,56"), &cols, 0.0, false, de).is_empty());
+
+        let diffs = compare_values(&data_row("K1", "1.234,56"), &data_row("K1", "1234,57"), &cols, 0.0, false, de);
+        assert!((diffs[0].delta.unwrap() - 0.01).abs() < 1e-9);
+
+        // Under en the comma is grouping: 1.23456 vs 123457
+        let diffs = compare_values(&data_row("K1", "1.234,56"), &data_row("K1", "1234,57"), &cols, 0.0, false, NumberLocale::En);
+        assert!((diffs[0].delta.unwrap() - 123455.76544).abs() < 1e-6);
+    }
+
     #[test]
     fn test_key_transform_none() {
         assert_eq!(apply_key_transform("  INV-123  ", KeyTransform::None), "  INV-123  ");
@@ -625,4 +1434,138 @@ mod tests {
         assert_eq!(apply_key_transform("INV-123-AB", KeyTransform::Digits), "123");
         assert_eq!(apply_key_transform("100154662", KeyTransform::Digits), "100154662");
     }
+
+    fn table_row(status: RowStatus, key: &str, diffs: Vec<ColumnDiff>) -> DiffRow {
+        DiffRow {
+            status,
+            key: key.to_string(),
+            left: None,
+            right: None,
+            left_source_index: None,
+            right_source_index: None,
+            diffs,
+            match_explain: None,
+            candidates: None,
+        }
+    }
+
+    fn amount_diff(left: &str, right: &str, delta: f64, within_tolerance: bool) -> ColumnDiff {
+        ColumnDiff {
+            column: "amount".to_string(),
+            left: left.to_string(),
+            right: right.to_string(),
+            delta: Some(delta),
+            within_tolerance,
+        }
+    }
+
+    #[test]
+    fn test_table_line_colors_by_status() {
+        let only_left = table_row(RowStatus::OnlyLeft, "INV-1", vec![]);
+        assert_eq!(format_table_line(&only_left, true), "\x1b[31m  - INV-1\x1b[0m");
+        assert_eq!(format_table_line(&only_left, false), "  - INV-1");
+
+        let only_right = table_row(RowStatus::OnlyRight, "INV-2", vec![]);
+        assert_eq!(format_table_line(&only_right, true), "\x1b[32m  + INV-2\x1b[0m");
+
+        let within = table_row(RowStatus::Diff, "INV-3", vec![amount_diff("10.00", "10.001", 0.0010000000000012, true)]);
+        assert_eq!(
+            format_table_line(&within, true),
+            "\x1b[33m  ~ INV-3  amount: 10.00 → 10.001 (Δ 0.001)\x1b[0m"
+        );
+
+        let material = table_row(RowStatus::Diff, "INV-4", vec![amount_diff("10", "12", 2.0, false)]);
+        assert_eq!(
+            format_table_line(&material, true),
+            "\x1b[91m  ~ INV-4  amount: 10 → 12 (Δ 2)\x1b[0m"
+        );
+        assert_eq!(format_table_line(&material, false), "  ~ INV-4  amount: 10 → 12 (Δ 2)");
+    }
+
+    #[test]
+    fn test_table_groups_by_status_with_counts() {
+        let result = DiffResult {
+            results: vec![
+                table_row(RowStatus::Matched, "A", vec![]),
+                table_row(RowStatus::Diff, "B", vec![amount_diff("1", "3", 2.0, false)]),
+                table_row(RowStatus::OnlyRight, "C", vec![]),
+                table_row(RowStatus::OnlyLeft, "D", vec![]),
+                table_row(RowStatus::OnlyLeft, "E", vec![]),
+            ],
+            summary: DiffSummary { matched: 1, ..Default::default() },
+            ambiguous_keys: vec![],
+        };
+        assert_eq!(
+            format_table(&result, false),
+            "only_left: 2\n  - D\n  - E\nonly_right: 1\n  + C\ndiff: 1\n  ~ B  amount: 1 → 3 (Δ 2)\nmatched: 1\n"
+        );
+    }
+
+    fn diff3_options() -> DiffOptions {
+        DiffOptions {
+            key_cols: vec![0],
+            compare_cols: None,
+            match_mode: MatchMode::Exact,
+            key_transform: KeyTransform::Trim,
+            on_ambiguous: AmbiguityPolicy::Error,
+            tolerance: 0.0,
+            contains_col: None,
+            empty_as_zero: false,
+            number_locale: NumberLocale::En,
+        }
+    }
+
+    fn merge_statuses(result: &Diff3Result) -> Vec<(String, &'static str)> {
+        result.rows.iter()
+            .flat_map(|r| r.cells.iter().map(move |c| (r.key.clone(), c.status.as_str())))
+            .collect()
+    }
+
+    #[test]
+    fn test_reconcile3_classifies_each_cell() {
+        let headers = vec!["key".to_string(), "amount".to_string()];
+        let base = vec![data_row("A", "1"), data_row("B", "2"), data_row("C", "3"), data_row("D", "4"), data_row("E", "5")];
+        let left = vec![data_row("A", "1"), data_row("B", "20"), data_row("C", "3"), data_row("D", "40"), data_row("E", "50")];
+        let right = vec![data_row("A", "1"), data_row("B", "2"), data_row("C", "30"), data_row("D", "40"), data_row("E", "55")];
+
+        let result = reconcile3(&base, &left, &right, &headers, &diff3_options()).unwrap();
+        assert_eq!(
+            merge_statuses(&result),
+            vec![
+                ("B".to_string(), "changed_left"),
+                ("C".to_string(), "changed_right"),
+                ("D".to_string(), "changed_both"),
+                ("E".to_string(), "conflict"),
+            ]
+        );
+        let s = &result.summary;
+        assert_eq!((s.unchanged, s.changed_left, s.changed_right, s.changed_both, s.conflicts), (1, 1, 1, 1, 1));
+
+        let conflict = &result.rows[3].cells[0];
+        assert_eq!(conflict.column, "amount");
+        assert_eq!(
+            (conflict.base.as_deref(), conflict.left.as_deref(), conflict.right.as_deref()),
+            (Some("5"), Some("50"), Some("55"))
+        );
+    }
+
+    #[test]
+    fn test_reconcile3_added_and_deleted_rows() {
+        let headers = vec!["key".to_string(), "amount".to_string()];
+        let base = vec![data_row("A", "1"), data_row("B", "2")];
+        // Left deletes A and adds N; right edits the A that left deleted
+        let left = vec![data_row("B", "2"), data_row("N", "9")];
+        let right = vec![data_row("A", "10"), data_row("B", "2")];
+
+        let result = reconcile3(&base, &left, &right, &headers, &diff3_options()).unwrap();
+        assert_eq!(
+            merge_statuses(&result),
+            vec![("A".to_string(), "conflict"), ("N".to_string(), "changed_left")]
+        );
+        assert!(result.rows[0].in_base && !result.rows[0].in_left && result.rows[0].in_right);
+        assert_eq!(result.rows[0].cells[0].left, None);
+
+        let dup = reconcile3(&base, &left, &[data_row("A", "1"), data_row("A", "2")], &headers, &diff3_options());
+        assert_eq!(dup.unwrap_err().input, "right");
+    }
 }
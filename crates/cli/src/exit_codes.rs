@@ -187,14 +187,17 @@ pub fn session_exit_code(err: &SessionError) -> u8 {
         SessionError::AuthFailed(_) => EXIT_SESSION_AUTH,
         SessionError::IoError(_) => EXIT_SESSION_CONNECT, // Network I/O issues
         SessionError::ProtocolError(_) => EXIT_SESSION_PROTOCOL,
+        SessionError::Timeout { .. } => EXIT_SESSION_TIMEOUT,
+        SessionError::MessageTooLarge { .. } => EXIT_SESSION_PROTOCOL,
         SessionError::ServerError { code, .. } => {
-            match code.as_str() {
-                "auth_failed" => EXIT_SESSION_AUTH,
-                "protocol_mismatch" => EXIT_SESSION_PROTOCOL,
-                "writer_conflict" | "revision_mismatch" => EXIT_SESSION_CONFLICT,
-                "rate_limited" => EXIT_SESSION_CONFLICT, // Treat as temporary conflict
-                "formula_parse_error" | "invalid_reference" => EXIT_SESSION_INPUT,
-                "malformed_message" | "message_too_large" => EXIT_SESSION_PROTOCOL,
+            use crate::session::ProtocolErrorCode as Code;
+            match code {
+                Code::AuthFailed => EXIT_SESSION_AUTH,
+                Code::ProtocolMismatch => EXIT_SESSION_PROTOCOL,
+                Code::WriterConflict | Code::RevisionMismatch => EXIT_SESSION_CONFLICT,
+                Code::RateLimited => EXIT_SESSION_CONFLICT, // Treat as temporary conflict
+                Code::FormulaParseError | Code::InvalidReference | Code::ProtectedCell => EXIT_SESSION_INPUT,
+                Code::MalformedMessage | Code::MessageTooLarge => EXIT_SESSION_PROTOCOL,
                 _ => EXIT_ERROR, // Unknown server error
             }
         }
@@ -231,7 +234,13 @@ impl SessionErrorOutput {
                 ("protocol_error".to_string(), msg.clone(), None)
             }
             SessionError::ServerError { code, message, retry_after_ms } => {
-                (code.clone(), message.clone(), *retry_after_ms)
+                (code.to_string(), message.clone(), *retry_after_ms)
+            }
+            SessionError::Timeout { retry_after_ms, .. } => {
+                ("timeout".to_string(), err.to_string(), *retry_after_ms)
+            }
+            SessionError::MessageTooLarge { .. } => {
+                ("message_too_large".to_string(), err.to_string(), None)
             }
         };
 
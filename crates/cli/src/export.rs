@@ -2,8 +2,10 @@
 //!
 //! Currently supports:
 //! - `vgrid export truth --format dbt-seed` — write dbt-compatible seed CSVs + manifest
+//! - `--with-schema` — also write a dbt `schema.yml` with tests inferred from the data
 
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 
 use clap::Subcommand;
 use serde::Serialize;
@@ -28,10 +30,15 @@ Reads a truth_transactions.csv and produces:
 Or, if --daily-totals is provided directly, skips transaction processing
 and exports daily totals + manifest only.
 
+With --with-schema, also writes seeds/schema.yml with dbt tests inferred
+from a profile of each seed: not_null for columns without blanks, unique
+for the key column, accepted_values for low-cardinality text columns.
+
 Examples:
   vgrid export truth --transactions data/stripe_truth.csv
   vgrid export truth --transactions data/stripe_truth.csv --out seeds/
-  vgrid export truth --daily-totals data/daily_totals.csv --out seeds/")]
+  vgrid export truth --daily-totals data/daily_totals.csv --out seeds/
+  vgrid export truth --transactions data/stripe_truth.csv --with-schema")]
     Truth {
         /// Input truth_transactions.csv
         #[arg(long)]
@@ -45,6 +52,10 @@ Examples:
         #[arg(long, default_value = "seeds")]
         out: PathBuf,
 
+        /// Also write schema.yml with dbt tests inferred from the data
+        #[arg(long)]
+        with_schema: bool,
+
         /// Quiet mode
         #[arg(long, short = 'q')]
         quiet: bool,
@@ -57,8 +68,9 @@ pub fn cmd_export(cmd: ExportCommands) -> Result<(), CliError> {
             transactions,
             daily_totals,
             out,
+            with_schema,
             quiet,
-        } => cmd_export_truth(transactions, daily_totals, out, quiet),
+        } => cmd_export_truth(transactions, daily_totals, out, with_schema, quiet),
     }
 }
 
@@ -88,6 +100,7 @@ fn cmd_export_truth(
     transactions_path: Option<PathBuf>,
     daily_totals_path: Option<PathBuf>,
     out_dir: PathBuf,
+    with_schema: bool,
     quiet: bool,
 ) -> Result<(), CliError> {
     if transactions_path.is_none() && daily_totals_path.is_none() {
@@ -103,7 +116,7 @@ fn cmd_export_truth(
 
     if let Some(dt_path) = daily_totals_path {
         // Direct daily totals pass-through
-        return export_daily_totals_only(dt_path, out_dir, quiet);
+        return export_daily_totals_only(dt_path, out_dir, with_schema, quiet);
     }
 
     let tx_path = transactions_path.unwrap();
@@ -182,11 +195,24 @@ fn cmd_export_truth(
         CliError::io(format!("cannot write {}: {e}", manifest_out.display()))
     })?;
 
+    let schema_out = if with_schema {
+        let seeds = [
+            profile_seed(&tx_out, TRANSACTIONS_KEY)?,
+            profile_seed(&dt_out, None)?,
+        ];
+        Some(write_schema(&out_dir, &seeds)?)
+    } else {
+        None
+    };
+
     if !quiet {
         eprintln!("export: wrote {} transactions, {} daily totals", transactions.len(), totals.len());
         eprintln!("  {}", tx_out.display());
         eprintln!("  {}", dt_out.display());
         eprintln!("  {}", manifest_out.display());
+        if let Some(ref schema_out) = schema_out {
+            eprintln!("  {}", schema_out.display());
+        }
     }
 
     Ok(())
@@ -195,6 +221,7 @@ fn cmd_export_truth(
 fn export_daily_totals_only(
     dt_path: PathBuf,
     out_dir: PathBuf,
+    with_schema: bool,
     quiet: bool,
 ) -> Result<(), CliError> {
     let dt_bytes = std::fs::read(&dt_path).map_err(|e| {
@@ -245,15 +272,176 @@ fn export_daily_totals_only(
         CliError::io(format!("cannot write {}: {e}", manifest_out.display()))
     })?;
 
+    let schema_out = if with_schema {
+        Some(write_schema(&out_dir, &[profile_seed(&dt_out, None)?])?)
+    } else {
+        None
+    };
+
     if !quiet {
         eprintln!("export: wrote {} daily totals", totals.len());
         eprintln!("  {}", dt_out.display());
         eprintln!("  {}", manifest_out.display());
+        if let Some(ref schema_out) = schema_out {
+            eprintln!("  {}", schema_out.display());
+        }
     }
 
     Ok(())
 }
 
+// ── dbt schema inference ────────────────────────────────────────────
+
+/// Key column of truth_transactions.csv (daily totals have a composite key,
+/// which dbt's `unique` test can't express).
+const TRANSACTIONS_KEY: Option<&str> = Some("source_id");
+
+/// Text columns with at most this many distinct values get `accepted_values`.
+const ACCEPTED_VALUES_MAX: usize = 10;
+
+/// What a single pass over a seed CSV learned about one column.
+#[derive(Debug)]
+struct ColumnProfile {
+    name: String,
+    rows: usize,
+    nulls: usize,
+    /// Distinct non-blank values, or `None` once past `ACCEPTED_VALUES_MAX`.
+    values: Option<BTreeSet<String>>,
+    /// Every non-blank value seen so far was distinct.
+    all_distinct: bool,
+    /// Some non-blank value is neither a number nor a date.
+    has_text: bool,
+    seen: BTreeSet<String>,
+}
+
+impl ColumnProfile {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            rows: 0,
+            nulls: 0,
+            values: Some(BTreeSet::new()),
+            all_distinct: true,
+            has_text: false,
+            seen: BTreeSet::new(),
+        }
+    }
+
+    fn observe(&mut self, value: &str) {
+        self.rows += 1;
+        if value.is_empty() {
+            self.nulls += 1;
+            return;
+        }
+        if !self.seen.insert(value.to_string()) {
+            self.all_distinct = false;
+        }
+        if let Some(ref mut values) = self.values {
+            values.insert(value.to_string());
+            if values.len() > ACCEPTED_VALUES_MAX {
+                self.values = None;
+            }
+        }
+        if value.parse::<f64>().is_err()
+            && chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_err()
+        {
+            self.has_text = true;
+        }
+    }
+
+    /// dbt tests this column's data supports.
+    fn tests(&self, is_key: bool) -> Vec<String> {
+        let mut tests = Vec::new();
+        if self.rows > 0 && self.nulls == 0 {
+            tests.push("not_null".to_string());
+        }
+        if is_key && self.all_distinct {
+            tests.push("unique".to_string());
+        }
+        if let Some(ref values) = self.values {
+            // Skip columns where every row is its own category
+            if !is_key && self.has_text && values.len() < self.rows - self.nulls {
+                let quoted: Vec<String> = values
+                    .iter()
+                    .map(|v| format!("'{}'", v.replace('\'', "''")))
+                    .collect();
+                tests.push(format!(
+                    "accepted_values:\n              values: [{}]",
+                    quoted.join(", ")
+                ));
+            }
+        }
+        tests
+    }
+}
+
+/// A seed and the profile of its columns.
+struct SeedProfile {
+    name: String,
+    key: Option<String>,
+    columns: Vec<ColumnProfile>,
+}
+
+/// Profile every column of a headered CSV.
+fn profile_csv(data: &[u8]) -> Result<Vec<ColumnProfile>, String> {
+    let mut csv = csv::ReaderBuilder::new().has_headers(true).from_reader(data);
+    let mut columns: Vec<ColumnProfile> = csv
+        .headers()
+        .map_err(|e| format!("CSV parse error: {e}"))?
+        .iter()
+        .map(ColumnProfile::new)
+        .collect();
+
+    for result in csv.records() {
+        let record = result.map_err(|e| format!("CSV parse error: {e}"))?;
+        for (col, profile) in columns.iter_mut().enumerate() {
+            profile.observe(record.get(col).unwrap_or(""));
+        }
+    }
+    Ok(columns)
+}
+
+fn profile_seed(path: &Path, key: Option<&str>) -> Result<SeedProfile, CliError> {
+    let data = std::fs::read(path).map_err(|e| {
+        CliError::io(format!("cannot read {}: {e}", path.display()))
+    })?;
+    let columns = profile_csv(&data).map_err(|e| {
+        CliError::parse(format!("{}: {e}", path.display()))
+    })?;
+    Ok(SeedProfile {
+        name: path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+        key: key.map(String::from),
+        columns,
+    })
+}
+
+/// Render a dbt `schema.yml` (properties version 2) for the given seeds.
+fn render_schema(seeds: &[SeedProfile]) -> String {
+    let mut out = String::from("version: 2\n\nseeds:\n");
+    for seed in seeds {
+        out.push_str(&format!("  - name: {}\n    columns:\n", seed.name));
+        for column in &seed.columns {
+            out.push_str(&format!("      - name: {}\n", column.name));
+            let tests = column.tests(seed.key.as_deref() == Some(column.name.as_str()));
+            if !tests.is_empty() {
+                out.push_str("        tests:\n");
+                for test in tests {
+                    out.push_str(&format!("          - {}\n", test));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn write_schema(out_dir: &Path, seeds: &[SeedProfile]) -> Result<PathBuf, CliError> {
+    let schema_out = out_dir.join("schema.yml");
+    std::fs::write(&schema_out, render_schema(seeds)).map_err(|e| {
+        CliError::io(format!("cannot write {}: {e}", schema_out.display()))
+    })?;
+    Ok(schema_out)
+}
+
 // ── Transaction CSV reader ──────────────────────────────────────────
 
 /// Read truth_transactions.csv into TruthTransaction structs.
@@ -341,3 +529,77 @@ fn read_transactions_csv(data: &[u8]) -> Result<Vec<TruthTransaction>, CliError>
 
     Ok(transactions)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(csv: &str, key: Option<&str>) -> SeedProfile {
+        SeedProfile {
+            name: "seed".to_string(),
+            key: key.map(String::from),
+            columns: profile_csv(csv.as_bytes()).unwrap(),
+        }
+    }
+
+    fn tests_for(seed: &SeedProfile, column: &str) -> Vec<String> {
+        let profile = seed.columns.iter().find(|c| c.name == column).unwrap();
+        profile.tests(seed.key.as_deref() == Some(column))
+    }
+
+    #[test]
+    fn test_unique_key_column_gets_unique() {
+        let s = seed("source_id,currency\ntx_1,USD\ntx_2,USD\ntx_3,EUR\n", Some("source_id"));
+        assert_eq!(tests_for(&s, "source_id"), vec!["not_null", "unique"]);
+
+        let dupes = seed("source_id\ntx_1\ntx_1\n", Some("source_id"));
+        assert_eq!(tests_for(&dupes, "source_id"), vec!["not_null"]);
+    }
+
+    #[test]
+    fn test_column_with_nulls_gets_no_not_null() {
+        let s = seed("id,posted_at\n1,\n2,\n3,2024-01-05\n", None);
+        assert!(tests_for(&s, "posted_at").is_empty());
+        assert_eq!(tests_for(&s, "id"), vec!["not_null"]);
+    }
+
+    #[test]
+    fn test_low_cardinality_text_gets_accepted_values() {
+        let s = seed(
+            "direction,amount,occurred_at\ncredit,1.00,2024-01-01\ndebit,2.00,2024-01-01\ncredit,3.00,2024-01-02\n",
+            None,
+        );
+        assert_eq!(
+            tests_for(&s, "direction"),
+            vec!["not_null", "accepted_values:\n              values: ['credit', 'debit']"]
+        );
+        // Numbers and dates are never treated as categories
+        assert_eq!(tests_for(&s, "amount"), vec!["not_null"]);
+        assert_eq!(tests_for(&s, "occurred_at"), vec!["not_null"]);
+    }
+
+    #[test]
+    fn test_render_schema_yaml() {
+        let s = seed("source_id,note\ntx_1,it's\ntx_2,it's\n", Some("source_id"));
+        let yaml = render_schema(&[s]);
+        assert_eq!(
+            yaml,
+            concat!(
+                "version: 2\n",
+                "\n",
+                "seeds:\n",
+                "  - name: seed\n",
+                "    columns:\n",
+                "      - name: source_id\n",
+                "        tests:\n",
+                "          - not_null\n",
+                "          - unique\n",
+                "      - name: note\n",
+                "        tests:\n",
+                "          - not_null\n",
+                "          - accepted_values:\n",
+                "              values: ['it''s']\n",
+            )
+        );
+    }
+}
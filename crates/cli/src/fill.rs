@@ -1,11 +1,13 @@
-//! `vgrid fill` — load CSV data into a .sheet template.
+//! `vgrid fill` — load CSV or JSON data into a .sheet template.
 //!
-//! Strict CSV parsing for financial data: integers, exact 2-decimal
+//! Strict parsing for financial data: integers, exact 2-decimal
 //! amounts, and text. No auto-detection of dates, booleans, or
 //! percentages. Rejects currency symbols, commas in numbers, and
 //! formula injection (`=` prefix).
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use visigrid_io::native::{FillColumn, FillColumnType};
 
 use crate::sheet_ops::parse_cell_ref;
 use crate::CliError;
@@ -195,9 +197,17 @@ pub fn parse_strict_value(s: &str, row_num: usize, col_num: usize) -> Result<Str
 
 /// A row of strictly-parsed values, plus line context for error reporting.
 pub struct StrictRow {
+    /// 1-indexed CSV row (the header row, if any, is row 1)
+    pub row_num: usize,
     pub values: Vec<StrictValue>,
 }
 
+/// Strictly-parsed CSV: optional header names plus data rows.
+pub struct StrictCsv {
+    pub headers: Option<Vec<String>>,
+    pub rows: Vec<StrictRow>,
+}
+
 /// Parse an entire CSV file with strict financial rules.
 ///
 /// Uses `visigrid_io::csv::read_file_as_utf8` for encoding handling,
@@ -206,7 +216,7 @@ pub fn parse_strict_csv(
     path: &Path,
     delimiter: char,
     has_headers: bool,
-) -> Result<Vec<StrictRow>, CliError> {
+) -> Result<StrictCsv, CliError> {
     let content = visigrid_io::csv::read_file_as_utf8(path)
         .map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?;
 
@@ -216,6 +226,7 @@ pub fn parse_strict_csv(
         .flexible(true)
         .from_reader(content.as_bytes());
 
+    let mut headers = None;
     let mut rows = Vec::new();
 
     for (row_idx, result) in reader.records().enumerate() {
@@ -224,7 +235,8 @@ pub fn parse_strict_csv(
         })?;
 
         if row_idx == 0 && has_headers {
-            // Skip header row — template already has its own headers
+            // Header row is not filled — template already has its own headers
+            headers = Some(record.iter().map(|h| h.trim().to_string()).collect());
             continue;
         }
 
@@ -234,10 +246,187 @@ pub fn parse_strict_csv(
             .map(|(col_idx, field)| parse_strict_value(field, row_idx, col_idx))
             .collect();
 
-        rows.push(StrictRow { values: values? });
+        rows.push(StrictRow { row_num: row_idx + 1, values: values? });
     }
 
-    Ok(rows)
+    Ok(StrictCsv { headers, rows })
+}
+
+// ── Strict JSON parsing ─────────────────────────────────────────────
+
+/// Where `vgrid fill` reads its data from.
+pub enum FillSource {
+    Csv(PathBuf),
+    /// A JSON array of objects, one object per row.
+    Json(PathBuf),
+}
+
+/// Parse a JSON array of objects with the same strict value rules as CSV.
+///
+/// With `columns`, each object's keys are matched to the column names
+/// (case-insensitive) and missing keys leave the cell empty. Without, values
+/// are taken in each object's key order. Rows are numbered by record (1-based).
+pub fn parse_strict_json(path: &Path, columns: Option<&[String]>) -> Result<StrictCsv, CliError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?;
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| CliError::parse(format!("JSON parse error in {}: {}", path.display(), e)))?;
+    let records = value.as_array().ok_or_else(|| {
+        CliError::parse("JSON input must be an array of objects")
+    })?;
+
+    let mut rows = Vec::with_capacity(records.len());
+    for (record_idx, record) in records.iter().enumerate() {
+        let object = record.as_object().ok_or_else(|| {
+            CliError::parse(format!("record {} is not an object", record_idx + 1))
+        })?;
+
+        let fields: Vec<(usize, &String, &serde_json::Value)> = match columns {
+            Some(names) => {
+                let mut fields = Vec::with_capacity(object.len());
+                for (key, value) in object {
+                    let col_idx = names.iter().position(|n| n.eq_ignore_ascii_case(key)).ok_or_else(|| {
+                        CliError::parse(format!("unknown key {:?} in record {}", key, record_idx + 1))
+                            .with_hint(format!("template columns: {}", names.join(", ")))
+                    })?;
+                    fields.push((col_idx, key, value));
+                }
+                fields
+            }
+            None => object.iter().enumerate().map(|(i, (k, v))| (i, k, v)).collect(),
+        };
+
+        let width = columns.map_or(fields.len(), |names| names.len());
+        let mut values: Vec<StrictValue> = (0..width).map(|_| StrictValue::Empty).collect();
+        for (col_idx, key, value) in fields {
+            let text = match value {
+                serde_json::Value::Null => continue,
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Bool(b) => b.to_string(),
+                serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                    return Err(CliError::parse(format!(
+                        "non-scalar value for key {:?} in record {}: arrays and objects cannot fill a cell",
+                        key,
+                        record_idx + 1
+                    )));
+                }
+            };
+            values[col_idx] = parse_strict_value(&text, record_idx, col_idx)?;
+        }
+        // Trailing empties would widen the fill without setting anything
+        while matches!(values.last(), Some(StrictValue::Empty)) {
+            values.pop();
+        }
+
+        rows.push(StrictRow { row_num: record_idx + 1, values });
+    }
+
+    Ok(StrictCsv { headers: columns.map(|names| names.to_vec()), rows })
+}
+
+/// Header names for matching JSON keys: the template's fill schema for the
+/// sheet if it has one, else the non-empty cells in the row above the target.
+fn template_columns(
+    schema_columns: Option<&[FillColumn]>,
+    sheet: Option<&visigrid_engine::sheet::Sheet>,
+    target: &FillTarget,
+) -> Result<Vec<String>, CliError> {
+    if let Some(columns) = schema_columns {
+        return Ok(columns.iter().map(|c| c.name.clone()).collect());
+    }
+    let names: Vec<String> = match (sheet, target.row.checked_sub(1)) {
+        (Some(sheet), Some(header_row)) => (target.col..)
+            .map(|col| sheet.get_display(header_row, col).trim().to_string())
+            .take_while(|name| !name.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    };
+    if names.is_empty() {
+        return Err(CliError::args("--headers with --json-input needs template headers")
+            .with_hint("put column names in the row above --target, or embed a fill schema"));
+    }
+    Ok(names)
+}
+
+// ── Schema validation ───────────────────────────────────────────────
+
+fn column_type_name(kind: FillColumnType) -> &'static str {
+    match kind {
+        FillColumnType::Text => "text",
+        FillColumnType::Integer => "integer",
+        FillColumnType::Decimal => "decimal",
+        FillColumnType::Number => "number",
+    }
+}
+
+fn value_matches_type(value: &StrictValue, kind: FillColumnType) -> bool {
+    matches!(
+        (value, kind),
+        (StrictValue::Empty, _)
+            | (_, FillColumnType::Text)
+            | (StrictValue::Integer(_), FillColumnType::Integer | FillColumnType::Number)
+            | (StrictValue::Decimal(_), FillColumnType::Decimal | FillColumnType::Number)
+    )
+}
+
+fn strict_value_display(value: &StrictValue) -> String {
+    strict_value_to_string(value).unwrap_or_default()
+}
+
+/// Check parsed CSV against the template's expected columns.
+///
+/// Headers (when present) must match the schema names in order; every value
+/// must parse to its column's type. Fails on the first mismatch with its
+/// row/col location so upstream schema drift is caught before anything is written.
+pub fn validate_schema(csv: &StrictCsv, columns: &[FillColumn]) -> Result<(), CliError> {
+    let expected_names = || {
+        columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+    };
+
+    if let Some(ref headers) = csv.headers {
+        for col_idx in 0..headers.len().max(columns.len()) {
+            let message = match (columns.get(col_idx), headers.get(col_idx)) {
+                (Some(expected), Some(found)) if expected.name.eq_ignore_ascii_case(found) => continue,
+                (Some(expected), Some(found)) => format!("expected {:?}, found {:?}", expected.name, found),
+                (Some(expected), None) => format!("expected {:?}, found no column", expected.name),
+                (None, Some(found)) => format!("unexpected column {:?}", found),
+                (None, None) => unreachable!(),
+            };
+            return Err(CliError::parse(format!(
+                "header mismatch at row 1 col {}: {}",
+                col_idx + 1,
+                message
+            ))
+            .with_hint(format!("template expects columns: {}", expected_names())));
+        }
+    }
+
+    for row in &csv.rows {
+        if row.values.len() > columns.len() {
+            return Err(CliError::parse(format!(
+                "too many columns at row {} col {}: schema expects {}",
+                row.row_num,
+                columns.len() + 1,
+                columns.len()
+            ))
+            .with_hint(format!("template expects columns: {}", expected_names())));
+        }
+        for (col_idx, (value, column)) in row.values.iter().zip(columns).enumerate() {
+            if !value_matches_type(value, column.kind) {
+                return Err(CliError::parse(format!(
+                    "type mismatch at row {} col {} ({}): expected {}, found {:?}",
+                    row.row_num,
+                    col_idx + 1,
+                    column.name,
+                    column_type_name(column.kind),
+                    strict_value_display(value)
+                )));
+            }
+        }
+    }
+
+    Ok(())
 }
 
 // ── Fill execution ──────────────────────────────────────────────────
@@ -255,11 +444,12 @@ fn strict_value_to_string(v: &StrictValue) -> Option<String> {
 /// Execute `vgrid fill`.
 pub fn cmd_fill(
     template: std::path::PathBuf,
-    csv_path: std::path::PathBuf,
+    source: FillSource,
     target: String,
     headers: bool,
     clear: bool,
     out: std::path::PathBuf,
+    backup: Option<Option<PathBuf>>,
     delimiter: char,
     json: bool,
 ) -> Result<(), CliError> {
@@ -275,6 +465,8 @@ pub fn cmd_fill(
             template.display()
         )));
     }
+    // Held until the output is replaced: filling in place loads and saves the same file
+    let lock = native::lock_for_write(&out).map_err(CliError::io)?;
     let mut workbook = native::load_workbook(&template)
         .map_err(|e| CliError::io(format!("failed to load template: {}", e)))?;
 
@@ -293,18 +485,54 @@ pub fn cmd_fill(
         0
     };
 
-    // 5. Parse CSV (fail-fast before modifying workbook)
-    if !csv_path.exists() {
-        return Err(CliError::io(format!(
-            "CSV file not found: {}",
-            csv_path.display()
-        )));
-    }
-    let csv_rows = parse_strict_csv(&csv_path, delimiter, headers)?;
+    // 5. Parse input (fail-fast before modifying workbook)
+    let schema = native::load_fill_schema(&template)
+        .map_err(|e| CliError::parse(format!("failed to read template schema: {}", e)))?;
+    let sheet_name = workbook.sheet(sheet_idx).map(|s| s.name.clone()).unwrap_or_default();
+
+    let parsed = match &source {
+        FillSource::Csv(csv_path) => {
+            if !csv_path.exists() {
+                return Err(CliError::io(format!(
+                    "CSV file not found: {}",
+                    csv_path.display()
+                )));
+            }
+            let parsed = parse_strict_csv(csv_path, delimiter, headers)?;
+            if parsed.rows.is_empty() {
+                return Err(CliError::parse("CSV file is empty"));
+            }
+            parsed
+        }
+        FillSource::Json(json_path) => {
+            if !json_path.exists() {
+                return Err(CliError::io(format!(
+                    "JSON file not found: {}",
+                    json_path.display()
+                )));
+            }
+            let columns = if headers {
+                Some(template_columns(
+                    schema.get(&sheet_name).map(Vec::as_slice),
+                    workbook.sheet(sheet_idx),
+                    &fill_target,
+                )?)
+            } else {
+                None
+            };
+            let parsed = parse_strict_json(json_path, columns.as_deref())?;
+            if parsed.rows.is_empty() {
+                return Err(CliError::parse("JSON input is empty"));
+            }
+            parsed
+        }
+    };
 
-    if csv_rows.is_empty() {
-        return Err(CliError::parse("CSV file is empty"));
+    // Validate against the template's embedded schema, if it has one for this sheet
+    if let Some(columns) = schema.get(&sheet_name) {
+        validate_schema(&parsed, columns)?;
     }
+    let csv_rows = parsed.rows;
 
     // 6. Begin batch (defer recalc)
     workbook.begin_batch();
@@ -317,39 +545,35 @@ pub fn cmd_fill(
     // 8. Fill cells
     let base_row = fill_target.row;
     let base_col = fill_target.col;
-    let mut cells_set: usize = 0;
-
-    for (row_offset, csv_row) in csv_rows.iter().enumerate() {
-        for (col_offset, value) in csv_row.values.iter().enumerate() {
-            if let Some(s) = strict_value_to_string(value) {
-                workbook.set_cell_value_tracked(
-                    sheet_idx,
-                    base_row + row_offset,
-                    base_col + col_offset,
-                    &s,
-                );
-                cells_set += 1;
-            }
-        }
-    }
+    let cells = csv_rows.iter().enumerate().flat_map(|(row_offset, csv_row)| {
+        csv_row.values.iter().enumerate().filter_map(move |(col_offset, value)| {
+            strict_value_to_string(value)
+                .map(|s| (base_row + row_offset, base_col + col_offset, s))
+        })
+    });
+    let cells_set = workbook.set_cell_values_bulk_tracked(sheet_idx, cells);
 
-    // 9. End batch (incremental recalc)
+    // 9. End batch: recompute only the filled cells' dependents
     workbook.end_batch();
 
-    // 10. Full recalc (belt-and-suspenders)
-    workbook.recompute_full_ordered();
-
-    // 11. Compute fingerprint
+    // 10. Compute fingerprint
     let fingerprint = native::compute_semantic_fingerprint(&workbook);
 
-    // 12. Save workbook (atomic: write .tmp then rename)
-    let tmp_path = out.with_extension("sheet.tmp");
-    native::save_workbook(&workbook, &tmp_path)
-        .map_err(|e| CliError::io(format!("failed to save: {}", e)))?;
-    std::fs::rename(&tmp_path, &out)
-        .map_err(|e| CliError::io(format!("failed to rename tmp to output: {}", e)))?;
-
-    // 13. Print result
+    // 11. Save workbook (atomic: write .tmp then rename)
+    // Back up whatever is about to be replaced (the template itself when filling in place)
+    let backup_path = match &backup {
+        Some(dir) => crate::util::backup_file(&out, dir.as_deref())?,
+        None => None,
+    };
+    lock.replace(|tmp| {
+        native::write_workbook(&workbook, tmp)?;
+        // Carry the schema over so the output can serve as the next template
+        native::save_fill_schema(tmp, &schema)
+    })
+    .map_err(|e| CliError::io(format!("failed to save: {}", e)))?;
+    drop(lock);
+
+    // 12. Print result
     let row_count = csv_rows.len();
     let col_count = csv_rows.iter().map(|r| r.values.len()).max().unwrap_or(0);
 
@@ -361,12 +585,16 @@ pub fn cmd_fill(
             "cols": col_count,
             "fingerprint": fingerprint,
             "output": out.display().to_string(),
+            "backup": backup_path.as_ref().map(|p| p.display().to_string()),
         });
         println!("{}", serde_json::to_string(&result).unwrap());
     } else {
         eprintln!("Filled {} cells ({} rows x {} cols)", cells_set, row_count, col_count);
         eprintln!("Fingerprint: {}", fingerprint);
         eprintln!("Output: {}", out.display());
+        if let Some(path) = &backup_path {
+            eprintln!("Backup: {}", path.display());
+        }
     }
 
     Ok(())
@@ -506,6 +734,29 @@ mod tests {
         assert!(parse_strict_value("=1+1", 0, 0).is_err());
     }
 
+    #[test]
+    fn test_json_key_order_without_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.json");
+        std::fs::write(&path, r#"[{"b": "x", "a": 7, "c": null}, {"a": "1.50"}]"#).unwrap();
+
+        let parsed = parse_strict_json(&path, None).unwrap();
+        assert!(parsed.headers.is_none());
+        assert_eq!(parsed.rows[0].values, vec![StrictValue::Text("x".to_string()), StrictValue::Integer(7.0)]);
+        assert_eq!(parsed.rows[1].values, vec![StrictValue::Decimal(1.50)]);
+
+        let columns = vec!["a".to_string(), "b".to_string()];
+        std::fs::write(&path, r#"[{"B": "x", "a": 7}]"#).unwrap();
+        let parsed = parse_strict_json(&path, Some(&columns)).unwrap();
+        assert_eq!(parsed.rows[0].values, vec![StrictValue::Integer(7.0), StrictValue::Text("x".to_string())]);
+
+        std::fs::write(&path, r#"[{"z": 1}]"#).unwrap();
+        let Err(err) = parse_strict_json(&path, Some(&columns)) else {
+            panic!("unknown key should be rejected");
+        };
+        assert!(err.message.contains("unknown key \"z\" in record 1"), "{}", err.message);
+    }
+
     #[test]
     fn test_empty_value() {
         assert_eq!(parse_strict_value("", 0, 0).unwrap(), StrictValue::Empty);
@@ -0,0 +1,492 @@
+//! `--where` row filtering for `vgrid convert`.
+//!
+//! A filter is a boolean expression over simple clauses (`Status=Paid`,
+//! `Amount<0`, `Vendor~cloud`). Clauses combine with `AND` and `OR`
+//! (uppercase keywords), `AND` binding tighter than `OR`, and parentheses
+//! group:
+//!
+//! ```text
+//! --where '(Status=Paid OR Status=Refunded) AND Amount>0'
+//! ```
+//!
+//! Repeated `--where` flags are ANDed, so the one-clause-per-flag form
+//! keeps working unchanged. Values containing a keyword or parenthesis
+//! must be quoted (`Vendor="Smith AND Sons"`).
+
+use crate::sheet_ops::get_data_bounds;
+use crate::{col_letter, resolve_column_position, CliError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WhereOp {
+    Eq,
+    NotEq,
+    Lt,
+    Gt,
+    Contains,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WhereClause {
+    pub column: String, // lowercased
+    pub op: WhereOp,
+    pub value: String, // quote-stripped, trimmed
+}
+
+pub struct ResolvedWhere {
+    /// Position among all clauses, left to right (indexes skip counts)
+    pub id: usize,
+    pub col: usize,
+    pub op: WhereOp,
+    pub value: String,
+    /// RHS parsed as f64 (after lenient strip). None if not numeric.
+    pub numeric_value: Option<f64>,
+}
+
+/// A filter expression over clauses of type `T`.
+#[derive(Debug, PartialEq)]
+pub enum WhereExpr<T> {
+    Clause(T),
+    And(Vec<WhereExpr<T>>),
+    Or(Vec<WhereExpr<T>>),
+}
+
+impl<T> WhereExpr<T> {
+    /// Clauses in left-to-right order.
+    pub fn clauses(&self) -> Vec<&T> {
+        let mut out = Vec::new();
+        self.collect_clauses(&mut out);
+        out
+    }
+
+    fn collect_clauses<'a>(&'a self, out: &mut Vec<&'a T>) {
+        match self {
+            WhereExpr::Clause(c) => out.push(c),
+            WhereExpr::And(items) | WhereExpr::Or(items) => {
+                for item in items {
+                    item.collect_clauses(out);
+                }
+            }
+        }
+    }
+
+    /// Rebuild the tree with each clause mapped by `f`, left to right.
+    fn try_map<U, E>(&self, f: &mut impl FnMut(&T) -> Result<U, E>) -> Result<WhereExpr<U>, E> {
+        Ok(match self {
+            WhereExpr::Clause(c) => WhereExpr::Clause(f(c)?),
+            WhereExpr::And(items) => WhereExpr::And(items.iter().map(|i| i.try_map(f)).collect::<Result<_, _>>()?),
+            WhereExpr::Or(items) => WhereExpr::Or(items.iter().map(|i| i.try_map(f)).collect::<Result<_, _>>()?),
+        })
+    }
+}
+
+/// Strip `$`, `,`, whitespace, then parse as f64.
+pub fn lenient_parse_f64(s: &str) -> Option<f64> {
+    let stripped: String = s.chars().filter(|c| *c != '$' && *c != ',').collect();
+    stripped.trim().parse::<f64>().ok()
+}
+
+/// Parse every `--where` flag and AND them together.
+pub fn parse_where_args(exprs: &[String]) -> Result<WhereExpr<WhereClause>, CliError> {
+    let mut parsed = exprs.iter().map(|e| parse_where_expr(e)).collect::<Result<Vec<_>, _>>()?;
+    Ok(if parsed.len() == 1 { parsed.remove(0) } else { WhereExpr::And(parsed) })
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Clause(String),
+}
+
+/// Split a `--where` expression into clauses, keywords, and parentheses.
+/// Quoted text is never split. Parentheses that open inside a clause are
+/// part of its value, keywords within them included, and `)` only closes
+/// a group that is open: `Vendor=Acme (US)` is one clause.
+fn tokenize(expr: &str) -> Vec<Token> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut buf = String::new();
+    let mut quote: Option<char> = None;
+    // Open groups, and parentheses open inside the current clause's value
+    let mut groups = 0usize;
+    let mut value_parens = 0usize;
+
+    fn flush(buf: &mut String, tokens: &mut Vec<Token>) {
+        if !buf.trim().is_empty() {
+            tokens.push(Token::Clause(buf.trim().to_string()));
+        }
+        buf.clear();
+    }
+
+    let keyword_at = |i: usize, word: &str| -> bool {
+        let n = word.len();
+        let at_boundary = i == 0 || chars[i - 1].is_whitespace() || chars[i - 1] == ')';
+        let follows = chars.get(i + n).is_none_or(|c| c.is_whitespace() || *c == '(');
+        at_boundary && follows && chars[i..].iter().take(n).copied().eq(word.chars())
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            buf.push(c);
+            i += 1;
+            continue;
+        }
+        match c {
+            '"' | '\'' => {
+                quote = Some(c);
+                buf.push(c);
+            }
+            // An open paren only groups where a clause would start
+            '(' if buf.trim().is_empty() => {
+                tokens.push(Token::Open);
+                groups += 1;
+            }
+            '(' => {
+                value_parens += 1;
+                buf.push(c);
+            }
+            ')' if value_parens > 0 => {
+                value_parens -= 1;
+                buf.push(c);
+            }
+            ')' if groups > 0 => {
+                flush(&mut buf, &mut tokens);
+                tokens.push(Token::Close);
+                groups -= 1;
+            }
+            _ if value_parens == 0 && keyword_at(i, "AND") => {
+                flush(&mut buf, &mut tokens);
+                tokens.push(Token::And);
+                i += 3;
+                continue;
+            }
+            _ if value_parens == 0 && keyword_at(i, "OR") => {
+                flush(&mut buf, &mut tokens);
+                tokens.push(Token::Or);
+                i += 2;
+                continue;
+            }
+            _ => buf.push(c),
+        }
+        i += 1;
+    }
+    flush(&mut buf, &mut tokens);
+    tokens
+}
+
+/// Parse one `--where` value: clauses joined by `AND`/`OR`, with parentheses.
+pub fn parse_where_expr(expr: &str) -> Result<WhereExpr<WhereClause>, CliError> {
+    let tokens = tokenize(expr);
+    let mut pos = 0;
+    let parsed = parse_or(&tokens, &mut pos, expr)?;
+    match tokens.get(pos) {
+        None => Ok(parsed),
+        Some(Token::Close) => Err(CliError::args(format!("unbalanced ')' in --where {:?}", expr))),
+        Some(_) => Err(CliError::args(format!("expected AND or OR in --where {:?}", expr))
+            .with_hint("quote values that contain spaces around keywords: Vendor=\"Smith AND Sons\"")),
+    }
+}
+
+fn parse_or(tokens: &[Token], pos: &mut usize, expr: &str) -> Result<WhereExpr<WhereClause>, CliError> {
+    let mut items = vec![parse_and(tokens, pos, expr)?];
+    while tokens.get(*pos) == Some(&Token::Or) {
+        *pos += 1;
+        items.push(parse_and(tokens, pos, expr)?);
+    }
+    Ok(if items.len() == 1 { items.remove(0) } else { WhereExpr::Or(items) })
+}
+
+fn parse_and(tokens: &[Token], pos: &mut usize, expr: &str) -> Result<WhereExpr<WhereClause>, CliError> {
+    let mut items = vec![parse_primary(tokens, pos, expr)?];
+    while tokens.get(*pos) == Some(&Token::And) {
+        *pos += 1;
+        items.push(parse_primary(tokens, pos, expr)?);
+    }
+    Ok(if items.len() == 1 { items.remove(0) } else { WhereExpr::And(items) })
+}
+
+fn parse_primary(tokens: &[Token], pos: &mut usize, expr: &str) -> Result<WhereExpr<WhereClause>, CliError> {
+    match tokens.get(*pos) {
+        Some(Token::Clause(text)) => {
+            *pos += 1;
+            // A bare word next to AND/OR is most likely the rest of an unquoted value
+            if tokens.len() > 1 && !text.contains(['=', '<', '>', '~']) {
+                return Err(CliError::args(format!("{:?} is not a clause in --where {:?}", text, expr))
+                    .with_hint("AND and OR split clauses; quote values that contain them: Vendor=\"Smith AND Sons\""));
+            }
+            Ok(WhereExpr::Clause(parse_where(text)?))
+        }
+        Some(Token::Open) => {
+            *pos += 1;
+            let inner = parse_or(tokens, pos, expr)?;
+            if tokens.get(*pos) != Some(&Token::Close) {
+                return Err(CliError::args(format!("unbalanced '(' in --where {:?}", expr)));
+            }
+            *pos += 1;
+            Ok(inner)
+        }
+        _ => Err(CliError::args(format!("missing clause in --where {:?}", expr))
+            .with_hint("syntax: 'Status=Paid OR Status=Refunded', '(A=1 OR A=2) AND B>0'")),
+    }
+}
+
+/// Parse a single clause like `Status=Paid` or `Amount<0`.
+pub fn parse_where(expr: &str) -> Result<WhereClause, CliError> {
+    // Reject >= and <= with hint
+    if expr.contains(">=") {
+        return Err(CliError::args(format!("unsupported operator >= in {:?}", expr))
+            .with_hint("use an OR group: --where 'col>value OR col=value'"));
+    }
+    if expr.contains("<=") {
+        return Err(CliError::args(format!("unsupported operator <= in {:?}", expr))
+            .with_hint("use an OR group: --where 'col<value OR col=value'"));
+    }
+
+    // Try operators in order: != ~ = < >
+    let (col, op, raw_value) = if let Some(pos) = expr.find("!=") {
+        (&expr[..pos], WhereOp::NotEq, &expr[pos + 2..])
+    } else if let Some(pos) = expr.find('~') {
+        (&expr[..pos], WhereOp::Contains, &expr[pos + 1..])
+    } else if let Some(pos) = expr.find('=') {
+        (&expr[..pos], WhereOp::Eq, &expr[pos + 1..])
+    } else if let Some(pos) = expr.find('<') {
+        (&expr[..pos], WhereOp::Lt, &expr[pos + 1..])
+    } else if let Some(pos) = expr.find('>') {
+        (&expr[..pos], WhereOp::Gt, &expr[pos + 1..])
+    } else {
+        return Err(CliError::args(format!("no operator found in --where {:?}", expr))
+            .with_hint("syntax: 'Column=value', 'Column<number', 'Column~substring'"));
+    };
+
+    let col = col.trim();
+    if col.is_empty() {
+        return Err(CliError::args(format!("empty column name in --where {:?}", expr)));
+    }
+
+    // Strip one layer of surrounding quotes from value
+    let value = raw_value.trim();
+    let value = if value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')))
+    {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    };
+
+    Ok(WhereClause {
+        column: col.trim().to_lowercase(),
+        op,
+        value: value.to_string(),
+    })
+}
+
+/// Resolve `--where` columns by header name, then column letter, then 1-indexed
+/// number (the same order as `diff`'s `resolve_column`). Without `--headers`
+/// only positional references resolve; `ncols` bounds them.
+pub fn resolve_where_columns(
+    expr: &WhereExpr<WhereClause>,
+    canonical_headers: &[String],
+    has_headers: bool,
+    ncols: usize,
+) -> Result<WhereExpr<ResolvedWhere>, CliError> {
+    let headers: Vec<String> = canonical_headers
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+
+    let mut next_id = 0;
+    expr.try_map(&mut |clause: &WhereClause| {
+        let col_idx = headers.iter().position(|h| h == &clause.column)
+            .or_else(|| resolve_column_position(&clause.column, ncols));
+        match col_idx {
+            Some(idx) => {
+                next_id += 1;
+                Ok(ResolvedWhere {
+                    id: next_id - 1,
+                    col: idx,
+                    op: clause.op,
+                    value: clause.value.clone(),
+                    numeric_value: lenient_parse_f64(&clause.value),
+                })
+            }
+            None if !has_headers => {
+                Err(CliError::args(format!("--where column {:?} requires --headers", clause.column))
+                    .with_hint(format!(
+                        "add --headers to filter by name, or use a column letter or number (A..{}, 1..{})",
+                        col_letter(ncols.max(1) - 1),
+                        ncols.max(1),
+                    )))
+            }
+            None => {
+                let available: Vec<String> = canonical_headers
+                    .iter()
+                    .map(|h| h.trim().to_string())
+                    .filter(|h| !h.is_empty())
+                    .collect();
+                Err(CliError::args(format!("unknown column {:?}", clause.column))
+                    .with_hint(format!("available columns: {}", available.join(", "))))
+            }
+        }
+    })
+}
+
+/// Numeric comparison; a non-numeric cell fails the clause and is counted.
+fn compare_numeric(cell: &str, cond: &ResolvedWhere, skip_counts: &mut [usize], cmp: fn(f64, f64) -> bool) -> Option<bool> {
+    let rhs = cond.numeric_value?;
+    Some(match lenient_parse_f64(cell) {
+        Some(lhs) => cmp(lhs, rhs),
+        None => {
+            skip_counts[cond.id] += 1;
+            false
+        }
+    })
+}
+
+fn clause_matches(cell: &str, cond: &ResolvedWhere, skip_counts: &mut [usize]) -> bool {
+    match cond.op {
+        WhereOp::Contains => cell.to_lowercase().contains(&cond.value.to_lowercase()),
+        WhereOp::Lt => compare_numeric(cell, cond, skip_counts, |l, r| l < r).unwrap_or(false),
+        WhereOp::Gt => compare_numeric(cell, cond, skip_counts, |l, r| l > r).unwrap_or(false),
+        // Numeric equality when the RHS is a number, else case-insensitive string
+        WhereOp::Eq => compare_numeric(cell, cond, skip_counts, |l, r| l == r)
+            .unwrap_or_else(|| cell.eq_ignore_ascii_case(&cond.value)),
+        WhereOp::NotEq => compare_numeric(cell, cond, skip_counts, |l, r| l != r)
+            .unwrap_or_else(|| !cell.eq_ignore_ascii_case(&cond.value)),
+    }
+}
+
+fn row_matches(
+    sheet: &visigrid_engine::sheet::Sheet,
+    row: usize,
+    expr: &WhereExpr<ResolvedWhere>,
+    skip_counts: &mut [usize],
+) -> bool {
+    match expr {
+        WhereExpr::Clause(cond) => clause_matches(&sheet.get_display(row, cond.col), cond, skip_counts),
+        WhereExpr::And(items) => items.iter().all(|e| row_matches(sheet, row, e, skip_counts)),
+        WhereExpr::Or(items) => items.iter().any(|e| row_matches(sheet, row, e, skip_counts)),
+    }
+}
+
+/// Data rows from `data_start` that pass `expr`, plus per-clause counts of
+/// rows skipped because a numeric comparison met a non-numeric cell.
+pub fn filter_row_indices(
+    sheet: &visigrid_engine::sheet::Sheet,
+    expr: &WhereExpr<ResolvedWhere>,
+    data_start: usize,
+) -> (Vec<usize>, Vec<usize>) {
+    let (rows, _) = get_data_bounds(sheet);
+    let mut matched = Vec::new();
+    let mut skip_counts = vec![0usize; expr.clauses().len()];
+    for row in data_start..rows {
+        if row_matches(sheet, row, expr, &mut skip_counts) {
+            matched.push(row);
+        }
+    }
+    (matched, skip_counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "Id,Status,Amount\n\
+        1,Paid,100\n\
+        2,Refunded,-20\n\
+        3,Pending,50\n\
+        4,Paid,0\n\
+        5,Refunded,30\n";
+
+    fn matching_ids(exprs: &[&str]) -> Vec<String> {
+        let sheet = crate::parse_csv(CSV, b',', 0, 0).unwrap();
+        let headers: Vec<String> = (0..3).map(|c| sheet.get_display(0, c)).collect();
+        let exprs: Vec<String> = exprs.iter().map(|s| s.to_string()).collect();
+        let parsed = parse_where_args(&exprs).unwrap();
+        let resolved = resolve_where_columns(&parsed, &headers, true, 3).unwrap();
+        let (rows, _) = filter_row_indices(&sheet, &resolved, 1);
+        rows.into_iter().map(|r| sheet.get_display(r, 0)).collect()
+    }
+
+    fn clause(column: &str, op: WhereOp, value: &str) -> WhereExpr<WhereClause> {
+        WhereExpr::Clause(WhereClause { column: column.into(), op, value: value.into() })
+    }
+
+    #[test]
+    fn repeated_flags_and_together() {
+        assert_eq!(matching_ids(&["Status=Paid", "Amount>10"]), ["1"]);
+        assert_eq!(matching_ids(&["Status=Paid AND Amount>10"]), ["1"]);
+    }
+
+    #[test]
+    fn or_group_matches_either_value() {
+        assert_eq!(matching_ids(&["Status=Paid OR Status=Refunded"]), ["1", "2", "4", "5"]);
+        // An OR group ANDs with another flag
+        assert_eq!(matching_ids(&["Status=Paid OR Status=Refunded", "Amount>0"]), ["1", "5"]);
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parse_where_expr("Status=Paid OR Status=Refunded AND Amount>0").unwrap(),
+            WhereExpr::Or(vec![
+                clause("status", WhereOp::Eq, "Paid"),
+                WhereExpr::And(vec![
+                    clause("status", WhereOp::Eq, "Refunded"),
+                    clause("amount", WhereOp::Gt, "0"),
+                ]),
+            ]),
+        );
+        // Paid rows regardless of amount, Refunded only when positive
+        assert_eq!(matching_ids(&["Status=Paid OR Status=Refunded AND Amount>0"]), ["1", "4", "5"]);
+        // Parentheses override precedence
+        assert_eq!(matching_ids(&["(Status=Paid OR Status=Refunded) AND Amount>0"]), ["1", "5"]);
+        assert_eq!(matching_ids(&["Amount<0 OR (Status=Pending AND Id=3)"]), ["2", "3"]);
+    }
+
+    #[test]
+    fn keywords_inside_quotes_and_lowercase_are_values() {
+        assert_eq!(
+            parse_where_expr("Vendor=\"Smith AND Sons\" OR Vendor=Black and Decker").unwrap(),
+            WhereExpr::Or(vec![
+                clause("vendor", WhereOp::Eq, "Smith AND Sons"),
+                clause("vendor", WhereOp::Eq, "Black and Decker"),
+            ]),
+        );
+        assert_eq!(parse_where_expr("Name~\"(draft)\"").unwrap(), clause("name", WhereOp::Contains, "(draft)"));
+    }
+
+    #[test]
+    fn parentheses_inside_values_do_not_group() {
+        assert_eq!(parse_where_expr("Vendor=Acme (US)").unwrap(), clause("vendor", WhereOp::Eq, "Acme (US)"));
+        assert_eq!(
+            parse_where_expr("(Vendor=Acme (US) OR Vendor=Acme (EU AND UK)) AND Amount>0").unwrap(),
+            WhereExpr::And(vec![
+                WhereExpr::Or(vec![
+                    clause("vendor", WhereOp::Eq, "Acme (US)"),
+                    clause("vendor", WhereOp::Eq, "Acme (EU AND UK)"),
+                ]),
+                clause("amount", WhereOp::Gt, "0"),
+            ]),
+        );
+        // With no group open, ')' is value text
+        assert_eq!(parse_where_expr("Status=Paid)").unwrap(), clause("status", WhereOp::Eq, "Paid)"));
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        for bad in ["(Status=Paid", "Status=Paid OR", "AND Status=Paid", "()", "(Status=Paid))"] {
+            assert!(parse_where_expr(bad).is_err(), "{:?} should not parse", bad);
+        }
+        // An unquoted keyword inside a value leaves a clause without an operator
+        let err = parse_where_expr("Vendor=Smith AND Sons").unwrap_err();
+        assert!(err.hint.as_deref().is_some_and(|h| h.contains("quote")), "{:?}", err.hint);
+    }
+}
@@ -0,0 +1,134 @@
+// Fixed-width column layouts for `--widths` and `--colspec`.
+// Pure string functions; no IO, no sheet access.
+
+/// One column of a fixed-width layout: character offsets `start..end`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedColumn {
+    pub name: Option<String>,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Where each column sits on a line of fixed-width text.
+///
+/// Offsets count characters, not bytes, so a `ü` in one field does not shift
+/// the fields after it. Text past the last column is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedLayout {
+    columns: Vec<FixedColumn>,
+}
+
+impl FixedLayout {
+    /// `10,8,20`: consecutive columns of those widths, starting at offset 0.
+    pub fn from_widths(spec: &str) -> Result<Self, String> {
+        let mut columns = Vec::new();
+        let mut start = 0;
+        for part in spec.split(',') {
+            let part = part.trim();
+            let width: usize = part
+                .parse()
+                .map_err(|_| format!("invalid width {:?} in --widths", part))?;
+            if width == 0 {
+                return Err("column widths must be at least 1".to_string());
+            }
+            columns.push(FixedColumn { name: None, start, end: start + width });
+            start += width;
+        }
+        Ok(FixedLayout { columns })
+    }
+
+    /// `name:0-10,amount:10-18`: named columns at explicit offsets, end
+    /// exclusive. Columns may be listed in any order and may leave gaps.
+    pub fn from_colspec(spec: &str) -> Result<Self, String> {
+        let mut columns = Vec::new();
+        for part in spec.split(',') {
+            let part = part.trim();
+            let (name, range) = part
+                .rsplit_once(':')
+                .ok_or_else(|| format!("invalid column {:?} in --colspec (expected NAME:START-END)", part))?;
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(format!("column {:?} in --colspec has no name", part));
+            }
+            let offset = |s: &str| s.trim().parse::<usize>().ok();
+            let bounds = range.split_once('-').and_then(|(s, e)| offset(s).zip(offset(e)));
+            let (start, end) = match bounds {
+                Some((start, end)) if start < end => (start, end),
+                Some(_) => return Err(format!("column {:?} in --colspec ends before it starts", name)),
+                None => return Err(format!("invalid range {:?} for column {:?} in --colspec", range, name)),
+            };
+            columns.push(FixedColumn { name: Some(name.to_string()), start, end });
+        }
+        Ok(FixedLayout { columns })
+    }
+
+    pub fn columns(&self) -> &[FixedColumn] {
+        &self.columns
+    }
+
+    /// Column names, when every column has one (`--colspec`).
+    pub fn names(&self) -> Option<Vec<String>> {
+        self.columns.iter().map(|c| c.name.clone()).collect()
+    }
+
+    /// Slice one line into fields, trimming the padding around each. Short
+    /// lines give empty fields for the columns they don't reach.
+    pub fn split(&self, line: &str) -> Vec<String> {
+        let chars: Vec<char> = line.chars().collect();
+        self.columns
+            .iter()
+            .map(|c| {
+                let start = c.start.min(chars.len());
+                let end = c.end.min(chars.len());
+                chars[start..end].iter().collect::<String>().trim().to_string()
+            })
+            .collect()
+    }
+
+    /// Split every line of `content`. Blank lines are skipped, as the CSV
+    /// reader skips empty records.
+    pub fn rows<'a>(&'a self, content: &'a str) -> impl Iterator<Item = Vec<String>> + 'a {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| self.split(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widths_trim_trailing_padding() {
+        let layout = FixedLayout::from_widths("6,8,5").unwrap();
+        assert_eq!(layout.split("Alice    12.50NY   "), vec!["Alice", "12.50", "NY"]);
+        assert_eq!(layout.split("Bob      7    "), vec!["Bob", "7", ""]);
+        assert_eq!(layout.split("Zoë   1234567890"), vec!["Zoë", "12345678", "90"]);
+    }
+
+    #[test]
+    fn test_colspec_names_and_gaps() {
+        let layout = FixedLayout::from_colspec("name:0-6, amount:10-16").unwrap();
+        assert_eq!(layout.names(), Some(vec!["name".to_string(), "amount".to_string()]));
+        assert_eq!(layout.split("Alice XXXX  42.0  "), vec!["Alice", "42.0"]);
+        assert_eq!(FixedLayout::from_widths("3,4").unwrap().names(), None);
+    }
+
+    #[test]
+    fn test_rows_skip_blank_lines() {
+        let layout = FixedLayout::from_widths("3,3").unwrap();
+        let rows: Vec<_> = layout.rows("ab 12 \r\n   \n\ncd 34 \n").collect();
+        assert_eq!(rows, vec![vec!["ab", "12"], vec!["cd", "34"]]);
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        assert!(FixedLayout::from_widths("10,x").is_err());
+        assert!(FixedLayout::from_widths("10,0").is_err());
+        assert!(FixedLayout::from_colspec("name:10-5").is_err());
+        assert!(FixedLayout::from_colspec("0-10").is_err());
+        assert!(FixedLayout::from_colspec(":0-10").is_err());
+        assert!(FixedLayout::from_colspec("name:0").is_err());
+    }
+}
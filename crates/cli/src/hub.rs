@@ -3,6 +3,7 @@
 //! `vgrid login`              — store API token
 //! `vgrid publish`            — upload file, wait for check, print results
 //! `vgrid hub publish`        — publish verified .sheet with trust metadata
+//! `vgrid hub get`            — download a published revision, hash-verified
 //! `vgrid pipeline publish`   — import → verify → publish in one step
 
 use std::collections::HashMap;
@@ -14,7 +15,7 @@ use visigrid_hub_client::{
     AuthCredentials, save_auth,
     HubClient, HubError, CreateRevisionOptions, RunResult,
     AssertionInput, EngineMetadata,
-    hash_file,
+    hash_file, parse_tags,
 };
 
 use crate::{CliError, FormulaPolicy, OutputFormat};
@@ -108,6 +109,7 @@ pub fn cmd_publish(
     columns_added_policy: Option<String>,
     columns_removed_policy: Option<String>,
     strict: bool,
+    tag_specs: Vec<String>,
 ) -> Result<(), CliError> {
     // Validate inputs
     if !file.exists() {
@@ -117,6 +119,7 @@ pub fn cmd_publish(
             hint: None,
         });
     }
+    let tags = parse_tag_args(&tag_specs)?;
 
     let parts: Vec<&str> = repo.splitn(2, '/').collect();
     if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
@@ -252,6 +255,7 @@ pub fn cmd_publish(
         format: file_format.map(String::from),
         source_metadata,
         message: None,
+        tags: tags.clone(),
     };
     let (revision_id, upload_url, upload_headers) = client
         .create_revision(&dataset_id, &content_hash, byte_size, &opts)
@@ -274,11 +278,14 @@ pub fn cmd_publish(
     if !wait {
         let proof_url = client.proof_url(owner, slug, &revision_id);
         if json_output {
-            let out = serde_json::json!({
+            let mut out = serde_json::json!({
                 "run_id": revision_id,
                 "status": "processing",
                 "proof_url": proof_url,
             });
+            if !tags.is_empty() {
+                out["tags"] = serde_json::json!(tags);
+            }
             println!("{}", serde_json::to_string(&out).unwrap());
         } else {
             eprintln!("Revision #{} submitted (not waiting for results)", revision_id);
@@ -332,6 +339,8 @@ pub fn cmd_hub_publish(
     dry_run: bool,
     no_wait: bool,
     timeout: u64,
+    tag_specs: Vec<String>,
+    if_changed: bool,
 ) -> Result<(), CliError> {
     // ── Phase A: Local validation (no auth, no network) ────────────
 
@@ -364,6 +373,9 @@ pub fn cmd_hub_publish(
     let owner = parts[0];
     let slug = parts[1];
 
+    // 2b. Validate tags
+    let tags = parse_tag_args(&tag_specs)?;
+
     let filename = file.file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("data.sheet")
@@ -601,6 +613,7 @@ pub fn cmd_hub_publish(
                 "checks_attached": checks_json.is_some(),
                 "notes_attached": notes_text.is_some(),
                 "message": message,
+                "tags": tags,
                 "source_metadata": source_metadata,
             });
             println!("{}", serde_json::to_string_pretty(&out).unwrap());
@@ -617,6 +630,9 @@ pub fn cmd_hub_publish(
             eprintln!("  Notes:       {}", if notes_text.is_some() { "attached" } else { "none" });
             eprintln!("  Summary:     {}", if summary_json.is_some() { "attached" } else { "none" });
             eprintln!("  Message:     {}", message);
+            if !tags.is_empty() {
+                eprintln!("  Tags:        {}", format_tags(&tags));
+            }
         }
         return Ok(());
     }
@@ -684,6 +700,36 @@ pub fn cmd_hub_publish(
     let dataset_url = format!("{}/{}/{}", app_base, owner, slug);
 
     let status = client.get_dataset_status(&dataset_id).map_err(|e| hub_error(e))?;
+
+    // --if-changed: byte-identical content is a no-op, whatever metadata the
+    // latest revision carries. Same hash the server stores (hash_file).
+    if if_changed && status.has_content(&content_hash) {
+        let revision_url = status.current_revision_id.as_ref()
+            .map(|id| format!("{}/{}/{}/revisions/{}", app_base, owner, slug, id));
+        if json_output {
+            let out = serde_json::json!({
+                "schema_version": 1,
+                "ok": true,
+                "changed": false,
+                "repo": repo,
+                "content_hash": content_hash,
+                "revision_id": status.current_revision_id,
+                "dataset_url": dataset_url,
+                "revision_url": revision_url,
+            });
+            println!("{}", serde_json::to_string(&out).unwrap());
+        } else {
+            eprintln!("No changes — content matches current revision");
+            eprintln!("  Repo:        {}", repo);
+            eprintln!("  Hash:        {}", content_hash);
+            if let Some(ref id) = status.current_revision_id {
+                eprintln!("  Revision:    #{}", id);
+            }
+            eprintln!("  Dataset:     {}", dataset_url);
+        }
+        return Ok(());
+    }
+
     if let Some(ref sm) = status.source_metadata {
         if sm["type"].as_str() == Some("trust_pipeline")
             && sm["fingerprint"].as_str() == Some(&fingerprint)
@@ -723,6 +769,7 @@ pub fn cmd_hub_publish(
         format: Some("sheet".into()),
         source_metadata: Some(source_metadata),
         message: Some(message.clone()),
+        tags: tags.clone(),
         ..Default::default()
     };
     let (revision_id, upload_url, upload_headers) = client
@@ -757,6 +804,7 @@ pub fn cmd_hub_publish(
                 "stamped": has_stamp,
                 "stamp_matches": stamp_matches,
                 "locked": false,
+                "tags": tags,
                 "dataset_url": dataset_url,
                 "revision_url": revision_url,
             });
@@ -766,6 +814,9 @@ pub fn cmd_hub_publish(
             eprintln!("  Repo:        {}", repo);
             eprintln!("  Revision:    #{}", revision_id);
             eprintln!("  Fingerprint: {}", fingerprint);
+            if !tags.is_empty() {
+                eprintln!("  Tags:        {}", format_tags(&tags));
+            }
             eprintln!("  Dataset:     {}", dataset_url);
             eprintln!("  Revision:    {}", revision_url);
         }
@@ -791,6 +842,7 @@ pub fn cmd_hub_publish(
             "stamped": has_stamp,
             "stamp_matches": stamp_matches,
             "locked": false,
+            "tags": result.tags,
             "dataset_url": dataset_url,
             "revision_url": revision_url,
         });
@@ -806,6 +858,9 @@ pub fn cmd_hub_publish(
             eprintln!("  Stamp match: {}", if stamp_matches { "yes" } else { "NO — fingerprint drifted" });
         }
         eprintln!("  Locked:      no");
+        if !result.tags.is_empty() {
+            eprintln!("  Tags:        {}", format_tags(&result.tags));
+        }
         eprintln!("  Dataset:     {}", dataset_url);
         eprintln!("  Revision:    {}", revision_url);
         eprintln!();
@@ -814,6 +869,85 @@ pub fn cmd_hub_publish(
     Ok(())
 }
 
+// ── Hub Get (download a published revision) ─────────────────────
+
+pub fn cmd_hub_get(
+    repo: String,
+    revision: String,
+    output: PathBuf,
+    dataset: Option<String>,
+    json_output: bool,
+) -> Result<(), CliError> {
+    let parts: Vec<&str> = repo.splitn(2, '/').collect();
+    if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
+        return Err(CliError {
+            code: EXIT_USAGE,
+            message: format!("Invalid repo format: '{}' (expected owner/slug)", repo),
+            hint: Some("example: vgrid hub get acme/payments -o data.csv".into()),
+        });
+    }
+    let owner = parts[0];
+    let slug = parts[1];
+    let dataset_name = dataset.as_deref().unwrap_or(slug);
+
+    let client = HubClient::from_saved_auth().map_err(hub_error)?;
+
+    let datasets = client.list_datasets(owner, slug).map_err(|e| match &e {
+        HubError::Http(404, _) => CliError {
+            code: EXIT_HUB_NETWORK,
+            message: format!("Repository '{}/{}' not found", owner, slug),
+            hint: None,
+        },
+        HubError::Http(403, _) => CliError {
+            code: EXIT_HUB_NOT_AUTH,
+            message: format!("No permission to access '{}/{}'", owner, slug),
+            hint: Some("check your API token permissions or ask the repo owner for access".into()),
+        },
+        _ => hub_error(e),
+    })?;
+    let dataset_id = datasets.iter()
+        .find(|d| d.name == dataset_name)
+        .map(|d| d.id.clone())
+        .ok_or_else(|| CliError {
+            code: EXIT_HUB_VALIDATION,
+            message: format!("Dataset '{}' not found in '{}/{}'", dataset_name, owner, slug),
+            hint: Some("pass --dataset to pick another dataset in the repo".into()),
+        })?;
+
+    if !json_output { eprint!("Downloading {}/{}@{}... ", owner, slug, revision); }
+    let downloaded = client.download_revision(&dataset_id, &revision, &output).map_err(|e| match &e {
+        HubError::Http(404, _) => CliError {
+            code: EXIT_HUB_VALIDATION,
+            message: format!("Revision '{}' not found in '{}/{}'", revision, owner, slug),
+            hint: None,
+        },
+        _ => hub_error(e),
+    })?;
+
+    if json_output {
+        let out = serde_json::json!({
+            "repo": repo,
+            "dataset": dataset_name,
+            "revision_id": downloaded.revision_id,
+            "version": downloaded.version,
+            "content_hash": downloaded.content_hash,
+            "byte_size": downloaded.byte_size,
+            "output": output.display().to_string(),
+        });
+        println!("{}", serde_json::to_string_pretty(&out).unwrap());
+    } else {
+        eprintln!("verified");
+        eprintln!("  Revision:    {}", downloaded.revision_id);
+        if let Some(v) = downloaded.version {
+            eprintln!("  Version:     v{}", v);
+        }
+        eprintln!("  Hash:        {}", downloaded.content_hash);
+        eprintln!("  Wrote:       {} ({} bytes)", output.display(), downloaded.byte_size);
+    }
+
+    Ok(())
+}
+
 // ── Pipeline Publish (import → verify → hub publish) ─────────────
 
 pub fn cmd_pipeline_publish(
@@ -823,6 +957,7 @@ pub fn cmd_pipeline_publish(
     formulas: FormulaPolicy,
     stamp: Option<String>,
     checks_calc: Vec<String>,
+    checks_policy: sheet_ops::CalcPolicy,
     checks_file: Option<PathBuf>,
     delimiter: Option<String>,
     sheet_arg: Option<String>,
@@ -836,8 +971,8 @@ pub fn cmd_pipeline_publish(
 ) -> Result<(), CliError> {
     use std::collections::BTreeMap;
     use visigrid_io::native::{
-        compute_semantic_fingerprint, save_workbook, save_workbook_with_metadata,
-        save_semantic_verification, CellMetadata, SemanticVerification,
+        compute_semantic_fingerprint, lock_for_write, save_semantic_verification, write_workbook,
+        write_workbook_with_metadata, CellMetadata, SemanticVerification,
     };
 
     // ── 1. Validate inputs ────────────────────────────────────────────
@@ -975,7 +1110,8 @@ pub fn cmd_pipeline_publish(
         let extracted = workbook.sheet(idx)
             .ok_or_else(|| CliError::io("sheet not found"))?
             .clone();
-        workbook = visigrid_engine::workbook::Workbook::from_sheets(vec![extracted], 0);
+        // Keep cached values: cross-sheet refs can't resolve without the source sheets
+        workbook = visigrid_engine::workbook::Workbook::from_sheets_deferred(vec![extracted], 0);
     } else {
         selected_sheet_idx = 0;
     }
@@ -1015,15 +1151,10 @@ pub fn cmd_pipeline_publish(
 
         let lookup = visigrid_engine::workbook::WorkbookLookup::new(&workbook, sheet_id);
         let mut results: Vec<CalcResult> = Vec::new();
-        let mut any_error = false;
+        let mut any_failed = false;
 
         for expr_str in &checks_calc {
-            let with_eq = if expr_str.starts_with('=') {
-                expr_str.clone()
-            } else {
-                format!("={}", expr_str)
-            };
-            let resolved = sheet_ops::resolve_header_refs(&with_eq, &header_map);
+            let resolved = sheet_ops::resolve_header_refs(&sheet_ops::with_eq(expr_str), &header_map);
             let formula_str = sheet_ops::translate_column_refs(&resolved, start_row1, end_row1);
 
             let result = match visigrid_engine::formula::parser::parse(&formula_str) {
@@ -1032,7 +1163,6 @@ pub fn cmd_pipeline_publish(
                     let eval = visigrid_engine::formula::eval::evaluate(&bound, &lookup);
                     let display = eval.to_text();
                     let is_error = matches!(eval, visigrid_engine::formula::eval::EvalResult::Error(_));
-                    if is_error { any_error = true; }
                     let value_type = match &eval {
                         visigrid_engine::formula::eval::EvalResult::Number(_) => "number",
                         visigrid_engine::formula::eval::EvalResult::Text(_) => "text",
@@ -1046,25 +1176,32 @@ pub fn cmd_pipeline_publish(
                         value: display.clone(),
                         value_type: value_type.to_string(),
                         error: if is_error { Some(display) } else { None },
+                        failed: checks_policy.fails(value_type),
                     }
                 }
                 Err(e) => {
-                    any_error = true;
                     CalcResult {
                         expr: expr_str.clone(),
                         value: format!("#PARSE: {}", e),
                         value_type: "error".to_string(),
                         error: Some(e.to_string()),
+                        // A check that doesn't parse fails under any policy
+                        failed: true,
                     }
                 }
             };
+            if result.failed { any_failed = true; }
             results.push(result);
         }
 
-        if any_error {
+        if any_failed {
+            let failed: Vec<String> = results.iter()
+                .filter(|r| r.failed)
+                .map(|r| format!("{} = {:?} ({})", r.expr, r.value, r.value_type))
+                .collect();
             return Err(CliError {
                 code: EXIT_ERROR,
-                message: "check formula evaluation failed".into(),
+                message: format!("check formula evaluation failed: {}", failed.join(", ")),
                 hint: Some("fix the --checks-calc expressions and retry".into()),
             });
         }
@@ -1074,6 +1211,7 @@ pub fn cmd_pipeline_publish(
         let calc_output = CalcOutput {
             format: format_str.to_string(),
             sheet: workbook.sheet(0).map(|s| s.name.clone()).unwrap_or_default(),
+            policy: checks_policy,
             results,
         };
         Some(serde_json::to_value(&calc_output).unwrap())
@@ -1113,36 +1251,29 @@ pub fn cmd_pipeline_publish(
 
     if !json_output { eprint!("  Writing {}... ", sheet_path.display()); }
 
-    let temp_path = sheet_path.with_extension("sheet.tmp");
-
-    if metadata.is_empty() {
-        save_workbook(&workbook, &temp_path)
-            .map_err(|e| CliError::io(format!("failed to write: {}", e)))?;
-    } else {
-        save_workbook_with_metadata(&workbook, &metadata, &temp_path)
-            .map_err(|e| CliError::io(format!("failed to write: {}", e)))?;
-    }
-
     // Stamp (always stamp when --stamp is provided, default to repo slug if no explicit label)
-    let has_stamp;
-    let stamp_matches;
-    if let Some(ref label) = stamp {
-        let verification = SemanticVerification {
-            fingerprint: Some(fingerprint.clone()),
-            label: if label.is_empty() { None } else { Some(label.clone()) },
-            timestamp: Some(chrono::Utc::now().to_rfc3339()),
-        };
-        save_semantic_verification(&temp_path, &verification)
-            .map_err(|e| CliError::io(format!("failed to write stamp: {}", e)))?;
-        has_stamp = true;
-        stamp_matches = true; // Freshly stamped always matches
-    } else {
-        has_stamp = false;
-        stamp_matches = false;
-    }
+    let verification = stamp.as_ref().map(|label| SemanticVerification {
+        fingerprint: Some(fingerprint.clone()),
+        label: if label.is_empty() { None } else { Some(label.clone()) },
+        timestamp: Some(chrono::Utc::now().to_rfc3339()),
+    });
+    let has_stamp = verification.is_some();
+    let stamp_matches = has_stamp; // Freshly stamped always matches
 
-    std::fs::rename(&temp_path, &sheet_path)
-        .map_err(|e| CliError::io(format!("failed to rename: {}", e)))?;
+    lock_for_write(&sheet_path)
+        .and_then(|lock| lock.replace(|temp_path| {
+            if metadata.is_empty() {
+                write_workbook(&workbook, temp_path)?;
+            } else {
+                write_workbook_with_metadata(&workbook, &metadata, temp_path)?;
+            }
+            match &verification {
+                Some(verification) => save_semantic_verification(temp_path, verification)
+                    .map_err(|e| format!("failed to write stamp: {}", e)),
+                None => Ok(()),
+            }
+        }))
+        .map_err(|e| CliError::io(format!("failed to write: {}", e)))?;
 
     if !json_output { eprintln!("done"); }
 
@@ -1467,6 +1598,19 @@ fn days_to_ymd(mut days: u64) -> (u64, u64, u64) {
 
 // ── Helpers ─────────────────────────────────────────────────────────
 
+/// Parse `--tag key=value` flags, rejecting malformed tags before any network call.
+fn parse_tag_args(specs: &[String]) -> Result<std::collections::BTreeMap<String, String>, CliError> {
+    parse_tags(specs).map_err(|message| CliError {
+        code: EXIT_USAGE,
+        message,
+        hint: Some("example: --tag env=prod --tag period=2024Q4".into()),
+    })
+}
+
+fn format_tags(tags: &std::collections::BTreeMap<String, String>) -> String {
+    tags.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(", ")
+}
+
 fn print_human_result(r: &RunResult) {
     eprintln!();
     eprintln!("  Run:     #{}", r.run_id);
@@ -1495,6 +1639,23 @@ fn print_human_result(r: &RunResult) {
             }
         }
     }
+    if let Some(ref report) = r.check_report {
+        if !report.columns_added.is_empty() {
+            eprintln!("  Added:   {}", report.columns_added.join(", "));
+        }
+        if !report.columns_removed.is_empty() {
+            eprintln!("  Removed: {}", report.columns_removed.join(", "));
+        }
+        for change in &report.columns_type_changed {
+            match (&change.from, &change.to) {
+                (Some(from), Some(to)) => eprintln!("  Type:    {} ({} -> {})", change.column, from, to),
+                _ => eprintln!("  Type:    {} changed", change.column),
+            }
+        }
+        if !report.violations.is_empty() {
+            eprintln!("  Failed:  {}", report.violations.join(", "));
+        }
+    }
     if let Some(ref assertions) = r.assertions {
         for a in assertions {
             let origin_tag = if a.origin.as_deref() == Some("client") { " [client]" } else { "" };
@@ -1517,6 +1678,9 @@ fn print_human_result(r: &RunResult) {
             }
         }
     }
+    if !r.tags.is_empty() {
+        eprintln!("  Tags:    {}", format_tags(&r.tags));
+    }
     if let Some(ref hash) = r.content_hash {
         eprintln!("  Hash:    {}", hash);
     }
@@ -1727,5 +1891,10 @@ fn hub_error(e: HubError) -> CliError {
             message: msg,
             hint: None,
         },
+        e @ HubError::HashMismatch { .. } => CliError {
+            code: EXIT_HUB_CHECK_FAILED,
+            message: e.to_string(),
+            hint: Some("the stored artifact does not match what was published; nothing was written".into()),
+        },
     }
 }
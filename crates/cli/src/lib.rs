@@ -1 +1,3 @@
 pub mod diff;
+pub mod fixed;
+pub mod locale;
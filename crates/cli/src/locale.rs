@@ -0,0 +1,130 @@
+// Number locales for `--locale`: decimal and grouping separators.
+// Pure string functions; no IO, no sheet access.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// Separator conventions for reading and writing numbers.
+///
+/// `En` is the engine's own notation (`1,234.56`) and the default; with it
+/// every command behaves exactly as it does without `--locale`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum NumberLocale {
+    /// `1,234.56`
+    #[default]
+    En,
+    /// `1.234,56`
+    De,
+    /// `1 234,56` (space, no-break space or narrow no-break space grouping)
+    Fr,
+}
+
+impl NumberLocale {
+    pub fn decimal_separator(self) -> char {
+        match self {
+            NumberLocale::En => '.',
+            NumberLocale::De | NumberLocale::Fr => ',',
+        }
+    }
+
+    fn is_group_separator(self, c: char) -> bool {
+        match self {
+            NumberLocale::En => c == ',',
+            NumberLocale::De => c == '.',
+            NumberLocale::Fr => matches!(c, ' ' | '\u{a0}' | '\u{202f}'),
+        }
+    }
+
+    /// Rewrite a number written in this locale into engine notation:
+    /// `1.234,56` → `1234.56` under `De`.
+    ///
+    /// Returns `None` unless the whole string is a plain number here: an
+    /// optional sign, digits grouped in threes (or not at all), and an
+    /// optional decimal part. `12.5` is not a `De` number, so it is left for
+    /// the caller rather than guessed at.
+    pub fn canonicalize(self, s: &str) -> Option<String> {
+        let s = s.trim();
+        let (sign, body) = match s.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (int_part, frac_part) = match body.split_once(self.decimal_separator()) {
+            Some((int_part, frac)) => (int_part, Some(frac)),
+            None => (body, None),
+        };
+
+        let groups: Vec<&str> = int_part.split(|c| self.is_group_separator(c)).collect();
+        let digits = |g: &str| !g.is_empty() && g.bytes().all(|b| b.is_ascii_digit());
+        if !groups.iter().all(|g| digits(g)) {
+            return None;
+        }
+        if groups.len() > 1 && (groups[0].len() > 3 || groups[1..].iter().any(|g| g.len() != 3)) {
+            return None;
+        }
+
+        let mut out = format!("{}{}", sign, groups.concat());
+        if let Some(frac) = frac_part {
+            if !digits(frac) {
+                return None;
+            }
+            out.push('.');
+            out.push_str(frac);
+        }
+        Some(out)
+    }
+
+    /// Parse a number written in this locale.
+    pub fn parse(self, s: &str) -> Option<f64> {
+        self.canonicalize(s)?.parse().ok()
+    }
+
+    /// Write an engine-notation number (`1234.56`) with this locale's
+    /// decimal separator (`1234,56`). No grouping is added, so the output
+    /// reads back unambiguously. Anything that isn't a number passes through.
+    pub fn format<'a>(self, s: &'a str) -> Cow<'a, str> {
+        let is_number = s.parse::<f64>().is_ok_and(f64::is_finite);
+        if self == NumberLocale::En || !is_number || !s.contains('.') {
+            return Cow::Borrowed(s);
+        }
+        Cow::Owned(s.replace('.', &self.decimal_separator().to_string()))
+    }
+}
+
+impl fmt::Display for NumberLocale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NumberLocale::En => "en",
+            NumberLocale::De => "de",
+            NumberLocale::Fr => "fr",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de_parse_and_format() {
+        let de = NumberLocale::De;
+        assert_eq!(de.parse("1.234,56"), Some(1234.56));
+        assert_eq!(de.parse("-1.234.567,5"), Some(-1234567.5));
+        assert_eq!(de.parse("1234,56"), Some(1234.56));
+        assert_eq!(de.parse("1.234"), Some(1234.0));
+        assert_eq!(de.parse("12.5"), None, "bad grouping is not a de number");
+        assert_eq!(de.parse("1,2,3"), None);
+        assert_eq!(de.parse("abc"), None);
+        assert_eq!(de.format("1234.56"), "1234,56");
+        assert_eq!(de.format("-0.5"), "-0,5");
+        assert_eq!(de.format("42"), "42");
+        assert_eq!(de.format("v1.2"), "v1.2");
+    }
+
+    #[test]
+    fn test_fr_and_en() {
+        assert_eq!(NumberLocale::Fr.parse("1 234,56"), Some(1234.56));
+        assert_eq!(NumberLocale::Fr.parse("1\u{202f}234\u{202f}567"), Some(1234567.0));
+        assert_eq!(NumberLocale::En.parse("1,234.56"), Some(1234.56));
+        assert_eq!(NumberLocale::En.format("1234.56"), "1234.56");
+    }
+}
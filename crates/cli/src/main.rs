@@ -6,9 +6,11 @@ mod exit_codes;
 mod export;
 mod fetch;
 mod fill;
+mod filter;
 mod hub;
 mod parse;
 mod recon;
+mod remote;
 mod replay;
 mod scripts;
 mod session;
@@ -17,11 +19,14 @@ mod signing;
 mod tui;
 mod util;
 mod verify;
+mod view;
 
 use visigrid_cli::diff;
+use visigrid_cli::fixed::FixedLayout;
+use visigrid_cli::locale::NumberLocale;
 
 use std::collections::HashMap;
-use std::io::{self, Read, Write};
+use std::io::{self, BufRead, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
@@ -61,7 +66,11 @@ Examples:
   cat sales.csv | visigrid calc '=SUM(B:B)' -f csv
   cat data.csv | visigrid calc '=AVERAGE(A:A)' -f csv --headers
   echo '1,2,3' | visigrid calc '=SUM(A1:C1)' -f csv
-  cat matrix.csv | visigrid calc '=MMULT(A:B,D:E)' -f csv --spill csv")]
+  cat matrix.csv | visigrid calc '=MMULT(A:B,D:E)' -f csv --spill csv
+  cat matrix.csv | visigrid calc '=MMULT(A:B,D:E)' -f csv --spill xlsx --spill-out product.xlsx
+  cat rates.csv | visigrid calc '=VLOOKUP(\"EUR\",A:B,2,FALSE)' -f csv --fail-on-empty
+  cat ledger.csv | visigrid calc '=ROUND(SUM(B:B),2)' -f csv --round-mode half-even
+  cat umsatz.csv | visigrid calc '=SUM(B:B)' -f csv --delimiter ';' --locale de")]
     Calc {
         /// Formula to evaluate (must start with =)
         formula: String,
@@ -82,13 +91,40 @@ Examples:
         #[arg(long)]
         headers: bool,
 
-        /// Output format for array results (csv or json)
+        /// Output format for array results (csv, tsv, json, or xlsx)
         #[arg(long)]
         spill: Option<SpillFormat>,
 
+        /// Write array results to this file instead of stdout (required for --spill xlsx)
+        #[arg(long, value_name = "FILE", requires = "spill")]
+        spill_out: Option<PathBuf>,
+
         /// Machine-readable alias: implies --spill json for arrays, JSON scalar for single values
         #[arg(long)]
         json: bool,
+
+        /// With --json or --spill json, only canonical decimals (42, -3.5) become
+        /// JSON numbers; results like 1e3 or 007 stay strings
+        #[arg(long)]
+        strict_numbers: bool,
+
+        /// Exit with an evaluation error if the result is empty
+        #[arg(long)]
+        fail_on_empty: bool,
+
+        /// Exit with an evaluation error if the result is an error such as #N/A
+        /// (default: true; --fail-on-error=false prints the error value and exits 0;
+        /// a formula that doesn't parse always fails)
+        #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set, num_args = 0..=1, default_missing_value = "true")]
+        fail_on_error: bool,
+
+        /// How ROUND and MROUND break .5 ties
+        #[arg(long, value_enum, default_value = "half-up")]
+        round_mode: RoundMode,
+
+        /// Number notation for csv/tsv input and the printed result (de: 1.234,56)
+        #[arg(long, value_enum, default_value = "en")]
+        locale: NumberLocale,
     },
 
     /// Convert between file formats
@@ -101,9 +137,14 @@ Examples:
   visigrid convert data.csv -t csv --headers --where 'Status=Pending'
   visigrid convert data.csv -t csv --headers --where 'Amount<0'
   visigrid convert data.csv -t csv --headers --select 'Invoice,Total,Status'
-  visigrid convert data.csv -t csv --headers --select Invoice --select Total")]
+  visigrid convert data.csv -t csv --headers --select Invoice --select Total
+  visigrid convert data.csv -t jsonl-values --headers --select Id
+  visigrid convert report.csv -t csv --headers --fill-down 'Region,Category'
+  visigrid convert events.ndjson -t csv
+  visigrid convert ledger.txt -t csv --widths '10,8,20'
+  visigrid convert https://example.com/data.csv -t json")]
     Convert {
-        /// Input file (omit to read from stdin)
+        /// Input file or https:// URL (omit to read from stdin)
         input: Option<PathBuf>,
 
         /// Input format (required when reading from stdin)
@@ -130,8 +171,10 @@ Examples:
         #[arg(long)]
         headers: bool,
 
-        /// Filter rows (requires --headers). Repeatable.
-        /// Examples: 'Status=Pending', 'Amount<0', 'Vendor~cloud'
+        /// Filter rows by column name (requires --headers), letter, or 1-indexed number. Repeatable (ANDed).
+        /// Clauses combine with AND/OR and parentheses; AND binds tighter.
+        /// Examples: 'Status=Pending', 'Amount<0', 'Vendor~cloud', 'C>100',
+        /// '(Status=Paid OR Status=Refunded) AND Amount>0'
         #[arg(long, value_name = "EXPR")]
         r#where: Vec<String>,
 
@@ -145,13 +188,69 @@ Examples:
         #[arg(long, value_name = "OLD:NEW,...")]
         rename: Option<String>,
 
-        /// Suppress stderr notes (e.g. skipped-row counts)
+        /// Swap rows and columns before writing (not with --headers)
+        #[arg(long, conflicts_with = "headers")]
+        transpose: bool,
+
+        /// Forward-fill blank cells in these columns from the last non-blank value above
+        /// (requires --headers). Repeatable; comma-separated accepted. Applied before --where.
+        #[arg(long, value_name = "COLS")]
+        fill_down: Vec<String>,
+
+        /// With --fill-down, a fully blank row stops the fill instead of being filled through
+        #[arg(long, requires = "fill_down")]
+        fill_reset_on_blank_row: bool,
+
+        /// Keep these columns as text exactly as written in the source (requires --headers;
+        /// csv/tsv input). Ids like 00123 keep their leading zeros and stay JSON strings.
+        /// Repeatable; comma-separated accepted. Example: --text-columns 'zip,account'
+        #[arg(long, value_name = "COLS", conflicts_with = "transpose")]
+        text_columns: Vec<String>,
+
+        /// JSON output: only canonical decimals (42, -3.5) become JSON numbers.
+        /// Values like 1e3, 007 or 0x10 stay strings exactly as written in text input
+        /// (csv, tsv, lines, fixed-width, and string values in json/ndjson)
+        #[arg(long)]
+        strict_numbers: bool,
+
+        /// Suppress stderr notes and warnings (e.g. skipped-row counts, ragged rows)
         #[arg(long, short = 'q')]
         quiet: bool,
+
+        /// Source text encoding for csv/tsv/lines input (output is always UTF-8)
+        #[arg(long, value_name = "ENCODING")]
+        encoding: Option<InputEncoding>,
+
+        /// Number notation for csv/tsv input and csv/tsv/lines output (de: 1.234,56).
+        /// JSON output always uses JSON numbers
+        #[arg(long, value_enum, default_value = "en")]
+        locale: NumberLocale,
+
+        /// Read fixed-width input: column widths in characters. Example: --widths '10,8,20'
+        #[arg(long, value_name = "N,...", conflicts_with = "colspec")]
+        widths: Option<String>,
+
+        /// Read fixed-width input: named columns at character offsets (end exclusive).
+        /// The names become a header row. Example: --colspec 'name:0-10,amount:10-18'
+        #[arg(long, value_name = "NAME:START-END,...")]
+        colspec: Option<String>,
+
+        /// Allow downloading the input over plain http://
+        #[arg(long)]
+        allow_insecure: bool,
     },
 
     /// List all supported functions
-    ListFunctions,
+    ListFunctions {
+        /// Output JSON: name, category, arity, description, and whether the
+        /// function is volatile (nondeterministic)
+        #[arg(long)]
+        json: bool,
+
+        /// Group names under category headings, with a description for each
+        #[arg(long, conflicts_with = "json")]
+        by_category: bool,
+    },
 
     /// Open file in GUI
     Open {
@@ -211,24 +310,41 @@ Examples:
 Exit code 1 indicates material differences: missing rows or value diffs outside \
 tolerance. Within-tolerance diffs are reported but do not cause a non-zero exit.
 
+With --baseline, LEFT is the baseline and only regressions fail: rows missing \
+from RIGHT (only_left) and value diffs exit 1, while new rows in RIGHT \
+(only_right) are reported but exit 0.
+
 Examples:
   visigrid diff old.csv new.csv --key id
   visigrid diff old.csv new.csv --key name --tolerance 0.01
+  visigrid diff ledger.csv bank.csv --key id --tolerance 0.01 --empty-as-zero
   visigrid diff old.csv new.csv --key sku --out csv --output diffs.csv
   visigrid diff old.csv new.csv --key id --compare price,quantity
+  visigrid diff old.csv new.csv --key id --ignore updated_at,etag
   visigrid diff old.csv new.csv --key name --match contains
   visigrid diff stripe.csv qbo.csv --key effective_date --key amount_minor
   cat export.csv | visigrid diff - baseline.csv --key id
-  docker exec db dump | visigrid diff expected.csv - --key sku")]
+  docker exec db dump | visigrid diff expected.csv - --key sku
+  visigrid diff before.json after.ndjson --key id
+  visigrid diff huge.csv index.csv --key id --stream
+  visigrid diff baseline.csv today.csv --key id --baseline
+  visigrid diff huge.csv index.csv --key id --stream --summary-only
+  visigrid diff big.csv bigger.csv --key id --progress --output diffs.json
+  visigrid diff old.csv new.csv --key id --out table
+  visigrid diff old.csv new.csv --key auto
+  visigrid diff --print-schema > diff.schema.json")]
     Diff {
         /// Left dataset (file path, or - for stdin)
-        left: String,
+        #[arg(required_unless_present = "print_schema")]
+        left: Option<String>,
 
         /// Right dataset (file path, or - for stdin)
-        right: String,
+        #[arg(required_unless_present = "print_schema")]
+        right: Option<String>,
 
         /// Key column (name, letter, or 1-indexed number). Repeatable for composite keys.
-        #[arg(long, required = true)]
+        /// `auto` picks the leftmost column that is unique and non-empty in both files.
+        #[arg(long, required_unless_present = "print_schema")]
         key: Vec<String>,
 
         /// Matching mode (exact: keys must match exactly; contains: left key must be substring of right key)
@@ -243,10 +359,22 @@ Examples:
         #[arg(long)]
         compare: Option<String>,
 
+        /// Columns to leave out of the comparison (comma-separated); still echoed in output
+        #[arg(long, value_name = "COLS")]
+        ignore: Option<String>,
+
         /// Numeric tolerance (absolute)
         #[arg(long, default_value = "0")]
         tolerance: f64,
 
+        /// Treat an empty cell as 0 when the other side is numeric (blank vs 0 matches)
+        #[arg(long)]
+        empty_as_zero: bool,
+
+        /// Decimal/grouping separators for numeric comparison (de: 1.234,56)
+        #[arg(long, value_enum, default_value = "en")]
+        locale: NumberLocale,
+
         /// Policy for duplicate keys
         #[arg(long, default_value = "error")]
         on_duplicate: DiffDuplicatePolicy,
@@ -255,9 +383,9 @@ Examples:
         #[arg(long, default_value = "error")]
         on_ambiguous: DiffAmbiguousPolicy,
 
-        /// Output format
-        #[arg(long, alias = "format", default_value = "json")]
-        out: DiffOutputFormat,
+        /// Output format (default: table on a terminal, json when piped or with --output)
+        #[arg(long, alias = "format")]
+        out: Option<DiffOutputFormat>,
 
         /// Output file (default: stdout)
         #[arg(long)]
@@ -305,6 +433,11 @@ Examples:
         #[arg(long)]
         no_fail: bool,
 
+        /// Treat LEFT as a baseline: exit 1 only for regressions (only_left rows or
+        /// value diffs); new only_right rows are informational
+        #[arg(long, conflicts_with = "no_fail")]
+        baseline: bool,
+
         /// Export rows by status to CSV (repeatable: --export only_left:/tmp/unmatched.csv)
         #[arg(long, value_name = "STATUS:PATH")]
         export: Vec<String>,
@@ -316,6 +449,86 @@ Examples:
         /// Machine-readable alias: force --out json, suppress non-JSON stderr
         #[arg(long)]
         json: bool,
+
+        /// Stream the left side row-by-row instead of loading both files into memory.
+        /// Only the right side is indexed. Requires --match exact and CSV/TSV inputs.
+        #[arg(long)]
+        stream: bool,
+
+        /// Show a progress bar on stderr while indexing the right side and
+        /// scanning the left. Ignored when stderr is not a terminal; not
+        /// available with --stream.
+        #[arg(long)]
+        progress: bool,
+
+        /// Emit only the JSON summary (counts, tolerance, key) without per-row
+        /// results. Rows are still kept for --export.
+        #[arg(long)]
+        summary_only: bool,
+
+        /// Print the JSON Schema for the --json output contract and exit
+        #[arg(long, exclusive = true)]
+        print_schema: bool,
+    },
+
+    /// Three-way diff: classify each cell of two edited copies against their common base
+    #[command(after_help = "\
+Each compared cell is unchanged, changed_left, changed_right, changed_both (the \
+same edit on both sides), or conflict (both sides changed it differently). Rows \
+added or deleted on one side count as changes to every cell that holds a value. \
+Output is JSON with per-row changes and a separate conflicts list.
+
+Exit code 1 when any conflict is found (unless --no-fail).
+
+Examples:
+  vgrid diff3 base.csv ours.csv theirs.csv --key id
+  vgrid diff3 base.csv ours.csv theirs.csv --key id --tolerance 0.01 --output merge.json
+  vgrid diff3 base.csv ours.csv theirs.csv --key region --key sku --compare price")]
+    Diff3 {
+        /// Common ancestor dataset
+        base: PathBuf,
+
+        /// First edited copy
+        left: PathBuf,
+
+        /// Second edited copy
+        right: PathBuf,
+
+        /// Key column (name, letter, or 1-indexed number). Repeatable for composite keys.
+        #[arg(long, required = true)]
+        key: Vec<String>,
+
+        /// Key transform
+        #[arg(long, default_value = "trim")]
+        key_transform: DiffKeyTransform,
+
+        /// Columns to compare (comma-separated; omit for all non-key)
+        #[arg(long)]
+        compare: Option<String>,
+
+        /// Numeric tolerance (absolute); smaller changes count as unchanged
+        #[arg(long, default_value = "0")]
+        tolerance: f64,
+
+        /// Treat first row as data (generate A, B, C headers)
+        #[arg(long)]
+        no_headers: bool,
+
+        /// CSV delimiter
+        #[arg(long, default_value = ",")]
+        delimiter: char,
+
+        /// Output file (default: stdout)
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Exit 0 even when conflicts are found
+        #[arg(long)]
+        no_fail: bool,
+
+        /// Quiet mode - suppress stderr summary
+        #[arg(long, short = 'q')]
+        quiet: bool,
     },
 
     /// List running VisiGrid sessions
@@ -334,11 +547,37 @@ Examples:
 Examples:
   visigrid attach
   visigrid attach --session abc123
-  VISIGRID_SESSION_TOKEN=xxx visigrid attach --session abc123")]
+  VISIGRID_SESSION_TOKEN=xxx visigrid attach --session abc123
+  visigrid attach --token-file /run/secrets/visigrid-token
+  visigrid attach --interactive --sheet 1
+
+Token lookup order: --token-file, VISIGRID_SESSION_TOKEN, then the file named by \
+VISIGRID_SESSION_TOKEN_FILE.")]
     Attach {
-        /// Session ID (prefix match supported; auto-selects if only one session)
+        /// Session ID (prefix match supported; auto-selects the only session, or the
+        /// only one whose workbook is under the current directory)
         #[arg(long)]
         session: Option<String>,
+
+        /// Read the session token from a file (overrides VISIGRID_SESSION_TOKEN)
+        #[arg(long, value_name = "PATH")]
+        token_file: Option<PathBuf>,
+
+        /// Browse the live workbook in a read-only grid that refreshes as it changes
+        #[arg(long, short = 'i')]
+        interactive: bool,
+
+        /// Sheet index to browse (0-based)
+        #[arg(long, default_value = "0", requires = "interactive")]
+        sheet: usize,
+
+        /// Rows in the window fetched around the cursor
+        #[arg(long, default_value = "200", requires = "interactive")]
+        max_rows: usize,
+
+        /// Columns in the window fetched around the cursor
+        #[arg(long, default_value = "26", requires = "interactive")]
+        max_cols: usize,
     },
 
     /// Apply operations to a running session
@@ -348,12 +587,21 @@ Examples:
   visigrid apply --session abc123 ops.jsonl
   cat ops.jsonl | visigrid apply -
   visigrid apply --atomic --expected-revision 42 ops.jsonl
-  visigrid apply --wait --wait-timeout 30 ops.jsonl")]
+  visigrid apply --wait --wait-timeout 30 ops.jsonl
+  visigrid apply --request-timeout 300 big-recompute.jsonl
+  visigrid apply --chunk-size 5000 huge-import.jsonl
+  visigrid apply ./plan/ --ordered")]
     Apply {
-        /// Operations file (JSONL format, or - for stdin)
+        /// Operations file (JSONL format, or - for stdin), or a directory with --ordered
         ops: String,
 
-        /// Session ID (prefix match supported; auto-selects if only one session)
+        /// Apply every *.jsonl file in the OPS directory in filename order,
+        /// stopping at the first failure
+        #[arg(long)]
+        ordered: bool,
+
+        /// Session ID (prefix match supported; auto-selects the only session, or the
+        /// only one whose workbook is under the current directory)
         #[arg(long)]
         session: Option<String>,
 
@@ -372,6 +620,18 @@ Examples:
         /// Maximum time to wait for writer lease (seconds, default 30)
         #[arg(long, default_value = "30")]
         wait_timeout: u64,
+
+        /// Maximum time to wait for the server's reply (seconds, default 30)
+        #[arg(long, value_name = "SECS", default_value = "30")]
+        request_timeout: u64,
+
+        /// Send ops in batches of N per request (not atomic across batches)
+        #[arg(long, value_name = "N", conflicts_with = "atomic")]
+        chunk_size: Option<usize>,
+
+        /// Read the session token from a file (overrides VISIGRID_SESSION_TOKEN)
+        #[arg(long, value_name = "PATH")]
+        token_file: Option<PathBuf>,
     },
 
     /// Query cell state from a running session
@@ -384,7 +644,8 @@ Examples:
         /// Cell or range to inspect (e.g., A1, A1:B10, or 'workbook')
         range: String,
 
-        /// Session ID (prefix match supported; auto-selects if only one session)
+        /// Session ID (prefix match supported; auto-selects the only session, or the
+        /// only one whose workbook is under the current directory)
         #[arg(long)]
         session: Option<String>,
 
@@ -395,6 +656,10 @@ Examples:
         /// Output as JSON (default: human-readable table)
         #[arg(long)]
         json: bool,
+
+        /// Read the session token from a file (overrides VISIGRID_SESSION_TOKEN)
+        #[arg(long, value_name = "PATH")]
+        token_file: Option<PathBuf>,
     },
 
     /// Show session server statistics (health check)
@@ -404,13 +669,50 @@ Examples:
   visigrid stats --session abc123
   visigrid stats --json")]
     Stats {
-        /// Session ID (prefix match supported; auto-selects if only one session)
+        /// Session ID (prefix match supported; auto-selects the only session, or the
+        /// only one whose workbook is under the current directory)
         #[arg(long)]
         session: Option<String>,
 
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Read the session token from a file (overrides VISIGRID_SESSION_TOKEN)
+        #[arg(long, value_name = "PATH")]
+        token_file: Option<PathBuf>,
+    },
+
+    /// Run many session commands over one connection, read from stdin
+    #[command(after_help = "\
+Reads one command per line and prints one JSON result per line:
+
+  inspect <A1 | A1:B2 | workbook> [--sheet N]
+  apply <ops.jsonl> [--atomic] [--expected-revision N]
+  stats
+  quit
+
+Each result is {\"command\", \"ok\": true, \"result\"} or {\"command\", \"ok\": false, \
+\"error\", \"message\", \"exit_code\"}, where exit_code is what the standalone \
+command would exit with. A failed command does not stop the REPL; the process \
+exits with the first failure's code. A lost connection stops it immediately.
+
+Examples:
+  printf 'apply step1.jsonl\\ninspect A1:C3\\nstats\\n' | visigrid repl
+  visigrid repl --session abc123 < plan.txt")]
+    Repl {
+        /// Session ID (prefix match supported; auto-selects the only session, or the
+        /// only one whose workbook is under the current directory)
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Maximum time to wait for each reply (seconds, default 30)
+        #[arg(long, value_name = "SECS", default_value = "30")]
+        request_timeout: u64,
+
+        /// Read the session token from a file (overrides VISIGRID_SESSION_TOKEN)
+        #[arg(long, value_name = "PATH")]
+        token_file: Option<PathBuf>,
     },
 
     /// View a live session (read-only grid snapshot)
@@ -419,9 +721,12 @@ Examples:
   visigrid view
   visigrid view --range A1:K20
   visigrid view --session abc123 --sheet 1
-  visigrid view --follow")]
+  visigrid view --follow
+  visigrid view --range A1:F30 --out snapshot.txt
+  visigrid view --range A1:F30 --out snapshot.csv --out-format csv")]
     View {
-        /// Session ID (prefix match supported; auto-selects if only one session)
+        /// Session ID (prefix match supported; auto-selects the only session, or the
+        /// only one whose workbook is under the current directory)
         #[arg(long)]
         session: Option<String>,
 
@@ -440,6 +745,18 @@ Examples:
         /// Column width for display (default: 12)
         #[arg(long, default_value = "12")]
         width: usize,
+
+        /// Write the snapshot to a file instead of stdout
+        #[arg(long, value_name = "PATH", conflicts_with = "follow")]
+        out: Option<PathBuf>,
+
+        /// Snapshot format: aligned table, or the range's display values as CSV
+        #[arg(long, value_name = "FORMAT", default_value = "table", requires = "out")]
+        out_format: ViewOutFormat,
+
+        /// Read the session token from a file (overrides VISIGRID_SESSION_TOKEN)
+        #[arg(long, value_name = "PATH")]
+        token_file: Option<PathBuf>,
     },
 
     /// View a file in the terminal — CSV, TSV, XLSX, ODS, .sheet
@@ -461,6 +778,12 @@ Examples:
   visigrid peek data.csv --plain                 # print table to stdout
   visigrid peek data.csv --no-tui               # same as --plain
   visigrid peek data.csv --tui                  # force interactive (error if no TTY)
+  visigrid peek https://example.com/data.csv     # download, then view
+  visigrid peek data.csv --no-restore           # start at the top, don't remember position
+
+View state:
+  The interactive viewer remembers sheet, cursor, and scroll position per file and
+  restores them on the next open. Saved state is discarded once the file changes.
 
 TTY behavior:
   Default: interactive TUI when stdin+stdout are TTY, otherwise prints plain preview.
@@ -471,7 +794,7 @@ TTY behavior:
 Safety: preview is capped by row count (200k) and cell count (10M for xlsx/ods). \
 Use --force to override.")]
     Peek {
-        /// File to view
+        /// File to view, or an https:// URL to download first
         file: PathBuf,
         /// First row is column headers
         #[arg(long)]
@@ -482,7 +805,8 @@ Use --force to override.")]
         /// Sheet name or 0-based index for multi-sheet files
         #[arg(long)]
         sheet: Option<String>,
-        /// Maximum rows to load (default: 5000; use --max-rows 0 for all)
+        /// Rows to load up front (default: 5000; use --max-rows 0 for all).
+        /// The CSV/TSV viewer loads further rows as you scroll.
         #[arg(long, default_value = "5000")]
         max_rows: usize,
         /// Override safety limits (>200k rows or >10M cells in workbooks)
@@ -500,6 +824,13 @@ Use --force to override.")]
         /// Override delimiter: single char, or name (tab, comma, pipe, semicolon)
         #[arg(long)]
         delimiter: Option<String>,
+        /// Read the file as fixed-width text: column widths in characters (e.g. '10,8,20')
+        #[arg(long, value_name = "N,...", conflicts_with_all = ["colspec", "delimiter", "sheet"])]
+        widths: Option<String>,
+        /// Read the file as fixed-width text: named columns at character offsets,
+        /// end exclusive (e.g. 'name:0-10,amount:10-18')
+        #[arg(long, value_name = "NAME:START-END,...", conflicts_with_all = ["delimiter", "sheet"])]
+        colspec: Option<String>,
         /// Recompute formulas after import (xlsx/ods only; default: show cached values)
         #[arg(long)]
         recompute: bool,
@@ -512,6 +843,15 @@ Use --force to override.")]
         /// Output as machine-readable JSON (columns + rows)
         #[arg(long, conflicts_with_all = ["tui", "plain", "shape", "no_tui"])]
         json: bool,
+        /// Suppress stderr warnings (e.g. mixed line endings)
+        #[arg(long, short = 'q')]
+        quiet: bool,
+        /// Allow downloading the file over plain http://
+        #[arg(long)]
+        allow_insecure: bool,
+        /// Don't restore or save the last viewed position for this file
+        #[arg(long)]
+        no_restore: bool,
     },
 
     /// Authenticate with VisiGrid Hub
@@ -549,6 +889,7 @@ Examples:
   vgrid publish ./exports/data.csv --repo acme/payments --source-type dbt
   vgrid publish ./data.csv --repo acme/analytics --source-identity analytics.monthly_close
   vgrid publish ./data.csv --repo acme/payments --no-wait
+  vgrid publish ./data.csv --repo acme/payments --tag env=prod --tag period=2024Q4
   vgrid publish ./data.csv --repo acme/payments --output json")]
     Publish {
         /// File to publish (CSV, TSV)
@@ -625,38 +966,57 @@ Examples:
         /// Strict mode: all check policies set to fail
         #[arg(long)]
         strict: bool,
+
+        /// Label the revision (repeatable), e.g. --tag env=prod --tag period=2024Q4
+        #[arg(long = "tag", value_name = "KEY=VALUE")]
+        tags: Vec<String>,
     },
 
-    /// Fill a .sheet template with CSV data (strict financial parsing)
+    /// Fill a .sheet template with CSV or JSON data (strict financial parsing)
     #[command(after_help = "\
 Loads CSV data into a .sheet template at a target cell. Uses strict numeric \
 parsing: integers and exact 2-decimal amounts only. Rejects currency symbols, \
 commas in numbers, and formula injection. All other values are treated as text.
 
+--json-input reads an array of objects instead, one object per row. With \
+--headers, keys are matched to the template's column names (its fill schema, \
+or the row above --target); without, values fill in key order. The same \
+strict rules apply, so send amounts as strings (\"1250.50\") or integers.
+
+If the template embeds a fill schema for the target sheet, CSV headers (with \
+--headers) must match its column names and each value must parse to the \
+column's type (text, integer, decimal, number). Nothing is written on mismatch.
+
 Exit codes:
   0   Success
   2   Bad arguments (invalid target, missing flags)
   3   IO error (file not found, write failure)
-  4   Parse error (CSV format violation)
+  4   Parse error (CSV format violation, schema mismatch)
 
 Examples:
   vgrid fill model.sheet --csv data.csv --target tx!A1 --headers --out filled.sheet
   vgrid fill model.sheet --csv data.csv --target A1 --out filled.sheet
   vgrid fill model.sheet --csv data.csv --target tx!A1 --headers --clear --out filled.sheet
-  vgrid fill model.sheet --csv data.csv --target tx!A1 --headers --out filled.sheet --json")]
+  vgrid fill model.sheet --csv data.csv --target tx!A1 --headers --out filled.sheet --json
+  vgrid fill model.sheet --json-input rows.json --target tx!A2 --headers --out filled.sheet
+  vgrid fill model.sheet --csv data.csv --target tx!A1 --headers --out model.sheet --backup")]
     Fill {
         /// Input .sheet template file
         template: PathBuf,
 
         /// CSV file to load
-        #[arg(long)]
-        csv: PathBuf,
+        #[arg(long, required_unless_present = "json_input", conflicts_with = "json_input")]
+        csv: Option<PathBuf>,
+
+        /// JSON file to load: an array of objects, one per row
+        #[arg(long, value_name = "FILE")]
+        json_input: Option<PathBuf>,
 
         /// Target cell, sheet-prefixed (e.g., tx!A1)
         #[arg(long)]
         target: String,
 
-        /// First CSV row is headers
+        /// First CSV row is headers (JSON: match keys to template column names)
         #[arg(long)]
         headers: bool,
 
@@ -668,6 +1028,14 @@ Examples:
         #[arg(long)]
         out: PathBuf,
 
+        /// Copy the existing output to <out>.bak before replacing it
+        #[arg(long)]
+        backup: bool,
+
+        /// Write timestamped backups to this directory instead (implies --backup)
+        #[arg(long, value_name = "DIR")]
+        backup_dir: Option<PathBuf>,
+
         /// CSV delimiter (default: comma)
         #[arg(long, default_value = ",")]
         delimiter: char,
@@ -801,13 +1169,19 @@ Examples:
   visigrid sheet apply model.sheet --lua build.lua
   visigrid sheet apply model.sheet --lua build.lua --verify v1:42:abc123...
   visigrid sheet apply model.sheet --lua build.lua --dry-run
+  visigrid sheet apply model.sheet --lua build.lua --emit-patch patch.json
   visigrid sheet apply model.sheet --lua build.lua --json
+  visigrid sheet apply model.sheet --lua build.lua --protected A1:D1
+  visigrid sheet apply model.sheet --lua build.lua --check 'B7>=0' --check 'B9==SUM(B2:B6)'
+  visigrid sheet apply model.sheet --lua build.lua --backup-dir backups/
+  visigrid sheet apply model.sheet --lua build.lua --title \"Revenue Model\" --author finance
 
 The Lua script builds the sheet from scratch using:
   set(cell, value)     -- set cell value or formula
   clear(cell)          -- clear cell
   meta(target, table)  -- semantic metadata (affects fingerprint)
   style(target, table) -- presentation style (excluded from fingerprint)
+  protect(target)      -- reject session edits (excluded from fingerprint)
 
 Example Lua script:
   set(\"A1\", \"Revenue Model\")
@@ -836,9 +1210,43 @@ Example Lua script:
         #[arg(long)]
         dry_run: bool,
 
+        /// Write the cells the build set and cleared as a JSON ops file
+        /// (replayable with `visigrid apply`)
+        #[arg(long, value_name = "PATH")]
+        emit_patch: Option<PathBuf>,
+
+        /// Copy the existing output to <output>.bak before replacing it
+        #[arg(long)]
+        backup: bool,
+
+        /// Write timestamped backups to this directory instead (implies --backup)
+        #[arg(long, value_name = "DIR")]
+        backup_dir: Option<PathBuf>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Reject session edits to this cell or range (repeatable, e.g. A1:D1)
+        #[arg(long, value_name = "RANGE")]
+        protected: Vec<String>,
+
+        /// Assert a formula is TRUE after the build (repeatable, e.g. 'Summary!B7>=0').
+        /// Any failing check aborts before the output is written (exit 1)
+        #[arg(long, value_name = "EXPR")]
+        check: Vec<String>,
+
+        /// Workbook title (presentation metadata, excluded from fingerprint)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Workbook description (presentation metadata, excluded from fingerprint)
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Workbook author (presentation metadata, excluded from fingerprint)
+        #[arg(long)]
+        author: Option<String>,
     },
 
     /// Inspect cells/ranges in a spreadsheet file
@@ -854,14 +1262,20 @@ Examples:
   visigrid sheet inspect model.sheet A1 --json
   visigrid sheet inspect model.sheet A1 --include-style
   visigrid sheet inspect model.sheet --sheets --json
+  visigrid sheet inspect model.sheet --sheet summary --count --json
   visigrid sheet inspect model.sheet --sheet 1 A1:M100 --json
   visigrid sheet inspect model.sheet --sheet Forecast --non-empty --json
 
+Compare two versions (--diff-with):
+  visigrid sheet inspect model.sheet --diff-with prev.sheet
+  visigrid sheet inspect model.sheet --diff-with prev.sheet --json
+
 Formula evaluation (--calc):
   visigrid sheet inspect data.csv --calc \"SUM(A:A)\"
   visigrid sheet inspect data.csv --headers --calc \"SUM(Amount)\"
   visigrid sheet inspect data.csv --calc \"SUM(A:A)\" --calc \"AVERAGE(B:B)\"
-  visigrid sheet inspect data.xlsx --sheet Invoices --headers --calc \"SUM([WO Number])\"")]
+  visigrid sheet inspect data.xlsx --sheet Invoices --headers --calc \"SUM([WO Number])\"
+  visigrid sheet inspect data.csv --headers --calc \"VLOOKUP(1001,A:B,2,FALSE)\" --fail-on-empty")]
     Inspect {
         /// Path to spreadsheet file (.sheet, .xlsx, .csv, .tsv)
         file: PathBuf,
@@ -889,10 +1303,23 @@ Formula evaluation (--calc):
         #[arg(long)]
         include_style: bool,
 
+        /// Include cell comments/notes
+        #[arg(long)]
+        include_comments: bool,
+
+        /// Include the conditional formatting rules that cover each cell
+        #[arg(long)]
+        include_conditional: bool,
+
         /// Print only the cell's display value (single-cell target required)
         #[arg(long)]
         value: bool,
 
+        /// Report the sheet's non-empty cell count and bounds. Fast: .sheet
+        /// files are counted in SQLite without loading the workbook.
+        #[arg(long)]
+        count: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -909,20 +1336,56 @@ Formula evaluation (--calc):
         #[arg(long)]
         headers: bool,
 
+        /// Swap rows and columns of a range target's output (not with --headers)
+        #[arg(long, conflicts_with = "headers")]
+        transpose: bool,
+
         /// CSV field delimiter (single char or name: tab, comma, pipe, semicolon)
         #[arg(long)]
         delimiter: Option<String>,
 
+        /// Read the file as fixed-width text: column widths in characters (e.g. '10,8,20')
+        #[arg(long, value_name = "N,...", conflicts_with_all = ["colspec", "format"])]
+        widths: Option<String>,
+
+        /// Read the file as fixed-width text: named columns at character offsets, end
+        /// exclusive (e.g. 'name:0-10,amount:10-18'). The names become row 1
+        #[arg(long, value_name = "NAME:START-END,...", conflicts_with = "format")]
+        colspec: Option<String>,
+
         /// Evaluate formula(s) against the loaded data (repeatable).
         /// Output is always JSON. Exit 1 if any formula errors.
         #[arg(long)]
         calc: Vec<String>,
 
+        /// Exit 1 if any --calc result is empty (e.g. a blank cell)
+        #[arg(long)]
+        fail_on_empty: bool,
+
+        /// Exit 1 if any --calc result is an error such as #N/A (default: true;
+        /// --fail-on-error=false reports errors in the JSON without failing;
+        /// a --calc that doesn't parse always fails)
+        #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set, num_args = 0..=1, default_missing_value = "true")]
+        fail_on_error: bool,
+
+        /// How ROUND and MROUND break .5 ties (applies to --calc and recomputed formulas)
+        #[arg(long, value_enum, default_value = "half-up")]
+        round_mode: RoundMode,
+
         /// Lightweight mode: query SQLite directly without loading the full workbook.
         /// Ideal for server-side use. Skips formula recomputation and formatting.
         /// Only works with .sheet files.
         #[arg(long)]
         lightweight: bool,
+
+        /// Compare against an earlier .sheet file: report added, removed, and changed
+        /// cells per sheet and whether (and why) the semantic fingerprint changed
+        #[arg(long, value_name = "OTHER")]
+        diff_with: Option<PathBuf>,
+
+        /// Suppress stderr warnings (e.g. mixed line endings)
+        #[arg(long, short = 'q')]
+        quiet: bool,
     },
 
     /// Verify a .sheet file's semantic fingerprint
@@ -965,11 +1428,14 @@ Examples:
   vgrid sheet import data.xlsx report.sheet --sheet Revenue --formulas values
   vgrid sheet import data.csv report.sheet --headers
   vgrid sheet import data.csv report.sheet --delimiter semicolon
+  vgrid sheet import padded.xlsx report.sheet --trim-empty
   vgrid sheet import data.xlsx report.sheet --stamp \"Q4 Filing\"
-  vgrid sheet import data.xlsx report.sheet --verify v2:42:abc123...
+  vgrid sheet import data.xlsx report.sheet --verify v3:42:abc123...
   vgrid sheet import data.xlsx report.sheet --formulas keep --json
   vgrid sheet import data.xlsx report.sheet --formulas recalc --json
-  vgrid sheet import data.xlsx report.sheet --dry-run --json")]
+  vgrid sheet import data.xlsx report.sheet --dry-run --json
+  vgrid sheet import data.csv report.sheet --protected A1:F1
+  vgrid sheet import data.xlsx report.sheet --title \"Q4 Close\" --description \"GL export\"")]
     Import {
         /// Source file (.xlsx, .csv, .tsv)
         source: PathBuf,
@@ -1012,6 +1478,27 @@ Examples:
         /// CSV field delimiter
         #[arg(long)]
         delimiter: Option<String>,
+
+        /// Drop trailing rows and columns that display blank before computing
+        /// stats and fingerprint (interior blanks are kept)
+        #[arg(long)]
+        trim_empty: bool,
+
+        /// Reject session edits to this cell or range (repeatable, e.g. A1:F1)
+        #[arg(long, value_name = "RANGE")]
+        protected: Vec<String>,
+
+        /// Workbook title (presentation metadata, excluded from fingerprint)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Workbook description (presentation metadata, excluded from fingerprint)
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Workbook author (presentation metadata, excluded from fingerprint)
+        #[arg(long)]
+        author: Option<String>,
     },
 
     /// Upgrade a .sheet file to the latest schema (v9+).
@@ -1037,6 +1524,131 @@ Examples:
         #[arg(long)]
         json: bool,
     },
+
+    /// Salvage what can still be read from a damaged .sheet file into a new file
+    #[command(after_help = "\
+Runs SQLite's integrity check, copies every readable row into a fresh file,
+and reports what was recovered and what was lost. The damaged file is never
+modified. A last resort for a model whose save was interrupted.
+
+Examples:
+  vgrid sheet repair model.sheet                       # writes model.repaired.sheet
+  vgrid sheet repair model.sheet --out salvaged.sheet --json")]
+    Repair {
+        /// Path to the damaged .sheet file
+        file: PathBuf,
+
+        /// Where to write the repaired file [default: <file>.repaired.sheet]
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Output structured JSON result
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show which cells a cell depends on, or which cells depend on it
+    #[command(after_help = "\
+Examples:
+  vgrid sheet deps model.sheet B2 --downstream
+  vgrid sheet deps model.sheet C1 --upstream
+  vgrid sheet deps model.sheet B2 --sheet summary --json
+
+Both directions are transitive: --downstream lists everything that recomputes
+when the cell changes, --upstream everything the cell's value is built from.")]
+    Deps {
+        /// Path to the .sheet file
+        file: PathBuf,
+
+        /// Cell to query (e.g. B2)
+        cell: String,
+
+        /// Sheet by name or 0-based index (default: first sheet)
+        #[arg(long)]
+        sheet: Option<String>,
+
+        /// List cells that depend on the cell (default)
+        #[arg(long, conflicts_with = "upstream")]
+        downstream: bool,
+
+        /// List cells the cell depends on
+        #[arg(long)]
+        upstream: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Manage named ranges in a .sheet file
+    #[command(subcommand)]
+    Name(NameCommands),
+}
+
+/// Named range subcommands (`vgrid sheet name ...`).
+#[derive(Subcommand)]
+enum NameCommands {
+    /// Define a named range (cell or range, optionally sheet-qualified)
+    #[command(after_help = "\
+Examples:
+  vgrid sheet name add model.sheet Revenue 'summary!B2:B13'
+  vgrid sheet name add model.sheet TaxRate B1 --description \"Blended rate\"
+  vgrid sheet name add model.sheet Revenue 'summary!B2:B14' --force
+
+Named ranges are semantic: adding, changing, or removing one changes the
+fingerprint. A file whose stamp matched before the edit is re-stamped.")]
+    Add {
+        /// Path to .sheet file
+        file: PathBuf,
+
+        /// Name to define (letters, digits, underscores, dots; not a cell reference)
+        name: String,
+
+        /// Target cell or range, e.g. B2, B2:B13, summary!B2:B13, 'My Sheet'!A1
+        range: String,
+
+        /// Optional description stored with the name
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Redefine the name if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List named ranges (name, range, sheet)
+    #[command(after_help = "\
+Examples:
+  vgrid sheet name list model.sheet
+  vgrid sheet name list model.sheet --json")]
+    List {
+        /// Path to .sheet file
+        file: PathBuf,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Remove a named range
+    #[command(after_help = "\
+Examples:
+  vgrid sheet name remove model.sheet Revenue")]
+    Remove {
+        /// Path to .sheet file
+        file: PathBuf,
+
+        /// Name to remove (case-insensitive)
+        name: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -1049,6 +1661,8 @@ Examples:
   vgrid hub publish invoices.sheet --repo quarry/invoices --checks checks.json --json
   vgrid hub publish invoices.sheet --repo quarry/invoices --no-wait --json
   vgrid hub publish invoices.sheet --repo quarry/invoices --dry-run --json
+  vgrid hub publish invoices.sheet --repo quarry/invoices --tag env=prod --tag period=2024Q4
+  vgrid hub publish invoices.sheet --repo quarry/invoices --if-changed --json
   vgrid hub publish recon.sheet --repo haven/recon --summary stripe-mercury.summary.json")]
     Publish {
         /// .sheet file to publish
@@ -1093,18 +1707,54 @@ Examples:
         /// Poll timeout in seconds (default: 120)
         #[arg(long, default_value = "120")]
         timeout: u64,
+
+        /// Label the revision (repeatable), e.g. --tag env=prod
+        #[arg(long = "tag", value_name = "KEY=VALUE")]
+        tags: Vec<String>,
+
+        /// Skip the upload (exit 0) when the file's content hash matches the latest revision
+        #[arg(long)]
+        if_changed: bool,
     },
-}
 
-#[derive(Subcommand)]
-enum PipelineCommands {
-    /// Import, verify, and publish a source file in one step
+    /// Download a published revision, verifying its content hash
     #[command(after_help = "\
 Examples:
-  vgrid pipeline publish data.csv --repo quarry/invoices --headers
-  vgrid pipeline publish data.xlsx --repo quarry/invoices --stamp \"Q4 Filing\"
-  vgrid pipeline publish data.csv --repo quarry/invoices --headers --checks-calc \"SUM(Amount)\"
-  vgrid pipeline publish data.csv --repo quarry/invoices --headers --checks-file checks.json
+  vgrid hub get acme/payments --revision v5 -o data.csv
+  vgrid hub get acme/payments -o latest.sheet
+  vgrid hub get acme/payments --dataset refunds --revision 412 -o refunds.csv --json")]
+    Get {
+        /// Repository (owner/slug)
+        repo: String,
+
+        /// Revision id, version label (e.g. v5), or "latest"
+        #[arg(long, default_value = "latest")]
+        revision: String,
+
+        /// Output file path
+        #[arg(long, short = 'o')]
+        output: PathBuf,
+
+        /// Dataset name within the repo (default: the repo slug)
+        #[arg(long)]
+        dataset: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PipelineCommands {
+    /// Import, verify, and publish a source file in one step
+    #[command(after_help = "\
+Examples:
+  vgrid pipeline publish data.csv --repo quarry/invoices --headers
+  vgrid pipeline publish data.xlsx --repo quarry/invoices --stamp \"Q4 Filing\"
+  vgrid pipeline publish data.csv --repo quarry/invoices --headers --checks-calc \"SUM(Amount)\"
+  vgrid pipeline publish data.csv --repo quarry/invoices --headers --checks-calc \"VLOOKUP(1001,A:B,2,FALSE)\" --fail-on-empty
+  vgrid pipeline publish data.csv --repo quarry/invoices --headers --checks-file checks.json
   vgrid pipeline publish data.xlsx --repo quarry/invoices --formulas keep --json
   vgrid pipeline publish data.csv --repo quarry/invoices --headers --out report.sheet --dry-run --json")]
     Publish {
@@ -1131,6 +1781,16 @@ Examples:
         #[arg(long = "checks-calc")]
         checks_calc: Vec<String>,
 
+        /// Fail if any --checks-calc result is empty (e.g. a lookup that found nothing)
+        #[arg(long)]
+        fail_on_empty: bool,
+
+        /// Fail if any --checks-calc result is an error such as #N/A (default: true;
+        /// --fail-on-error=false records errors in the checks without failing;
+        /// a check that doesn't parse always fails)
+        #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set, num_args = 0..=1, default_missing_value = "true")]
+        fail_on_error: bool,
+
         /// Path to pre-computed checks JSON
         #[arg(long = "checks-file")]
         checks_file: Option<PathBuf>,
@@ -1214,6 +1874,7 @@ and a content-addressed diff hash.
 
 Examples:
   vgrid scripts run sum_columns model.sheet --plan
+  vgrid scripts run sum_columns model.sheet --plan --diff
   vgrid scripts run sum_columns model.sheet --apply
   vgrid scripts run sum_columns model.sheet --apply --json")]
     Run {
@@ -1227,6 +1888,16 @@ Examples:
         #[arg(long)]
         plan: bool,
 
+        /// With --plan: show each changed cell's current and planned value,
+        /// plus the fingerprint the file would have after --apply
+        #[arg(long, requires = "plan")]
+        diff: bool,
+
+        /// With --diff: numeric changes of at most this much (absolute) are
+        /// marked within tolerance
+        #[arg(long, default_value = "0", requires = "diff")]
+        tolerance: f64,
+
         /// Apply the patch and create a provenance run record
         #[arg(long)]
         apply: bool,
@@ -1310,22 +1981,64 @@ enum Format {
     Tsv,
     Json,
     Lines,
+    /// One typed JSON scalar per line from a single column (output only)
+    JsonlValues,
+    /// One JSON object per line, keys unioned into columns (input only)
+    Ndjson,
+    /// Fixed-width columns sliced by --widths or --colspec (input only)
+    Fixed,
     Xlsx,
     Sheet,
 }
 
+/// Text encodings `convert --encoding` can decode.
+#[derive(Clone, Copy, ValueEnum)]
+enum InputEncoding {
+    #[value(name = "utf-8", alias = "utf8")]
+    Utf8,
+    #[value(alias = "iso-8859-1")]
+    Latin1,
+    #[value(name = "windows-1252", alias = "cp1252")]
+    Windows1252,
+    /// Byte order from the BOM; little-endian without one
+    #[value(name = "utf-16", alias = "utf16")]
+    Utf16,
+}
+
+impl From<InputEncoding> for visigrid_io::csv::TextEncoding {
+    fn from(e: InputEncoding) -> Self {
+        match e {
+            InputEncoding::Utf8 => Self::Utf8,
+            InputEncoding::Latin1 => Self::Latin1,
+            InputEncoding::Windows1252 => Self::Windows1252,
+            InputEncoding::Utf16 => Self::Utf16,
+        }
+    }
+}
+
 #[derive(Clone, Copy, ValueEnum)]
 enum InspectFormat {
     Sheet,
     Xlsx,
     Csv,
     Tsv,
+    /// Chosen by --widths/--colspec, never by extension
+    #[value(skip)]
+    Fixed,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ViewOutFormat {
+    Table,
+    Csv,
 }
 
 #[derive(Clone, Copy, ValueEnum)]
 enum SpillFormat {
     Csv,
+    Tsv,
     Json,
+    Xlsx,
 }
 
 #[derive(Clone, Copy, ValueEnum)]
@@ -1338,6 +2051,24 @@ pub enum FormulaPolicy {
     Recalc,
 }
 
+/// Tie-breaking for ROUND and MROUND.
+#[derive(Clone, Copy, ValueEnum)]
+enum RoundMode {
+    /// Ties round away from zero, like Excel (default)
+    HalfUp,
+    /// Ties round to the even digit (banker's rounding)
+    HalfEven,
+}
+
+impl From<RoundMode> for visigrid_engine::formula::rounding::RoundMode {
+    fn from(mode: RoundMode) -> Self {
+        match mode {
+            RoundMode::HalfUp => Self::HalfUp,
+            RoundMode::HalfEven => Self::HalfEven,
+        }
+    }
+}
+
 #[derive(Clone, Copy, ValueEnum)]
 enum NullPolicy {
     Empty,
@@ -1393,6 +2124,8 @@ enum DiffAmbiguousPolicy {
 enum DiffOutputFormat {
     Json,
     Csv,
+    /// Compact table grouped by status, colored on a terminal
+    Table,
 }
 
 #[derive(Clone, Copy, ValueEnum)]
@@ -1409,213 +2142,6 @@ enum ExportSide {
     Both,
 }
 
-// ============================================================================
-// --where filtering types and helpers
-// ============================================================================
-
-#[derive(Clone, Copy)]
-enum WhereOp {
-    Eq,
-    NotEq,
-    Lt,
-    Gt,
-    Contains,
-}
-
-struct WhereClause {
-    column: String, // lowercased
-    op: WhereOp,
-    value: String, // quote-stripped, trimmed
-}
-
-struct ResolvedWhere {
-    col: usize,
-    op: WhereOp,
-    value: String,
-    /// RHS parsed as f64 (after lenient strip). None if not numeric.
-    numeric_value: Option<f64>,
-}
-
-/// Strip `$`, `,`, whitespace, then parse as f64.
-fn lenient_parse_f64(s: &str) -> Option<f64> {
-    let stripped: String = s.chars().filter(|c| *c != '$' && *c != ',').collect();
-    stripped.trim().parse::<f64>().ok()
-}
-
-fn parse_where(expr: &str) -> Result<WhereClause, CliError> {
-    // Reject >= and <= with hint
-    if expr.contains(">=") {
-        return Err(CliError::args(format!("unsupported operator >= in {:?}", expr))
-            .with_hint("use two clauses: --where 'col>value' --where 'col=value'"));
-    }
-    if expr.contains("<=") {
-        return Err(CliError::args(format!("unsupported operator <= in {:?}", expr))
-            .with_hint("use two clauses: --where 'col<value' --where 'col=value'"));
-    }
-
-    // Try operators in order: != ~ = < >
-    let (col, op, raw_value) = if let Some(pos) = expr.find("!=") {
-        (&expr[..pos], WhereOp::NotEq, &expr[pos + 2..])
-    } else if let Some(pos) = expr.find('~') {
-        (&expr[..pos], WhereOp::Contains, &expr[pos + 1..])
-    } else if let Some(pos) = expr.find('=') {
-        (&expr[..pos], WhereOp::Eq, &expr[pos + 1..])
-    } else if let Some(pos) = expr.find('<') {
-        (&expr[..pos], WhereOp::Lt, &expr[pos + 1..])
-    } else if let Some(pos) = expr.find('>') {
-        (&expr[..pos], WhereOp::Gt, &expr[pos + 1..])
-    } else {
-        return Err(CliError::args(format!("no operator found in --where {:?}", expr))
-            .with_hint("syntax: 'Column=value', 'Column<number', 'Column~substring'"));
-    };
-
-    let col = col.trim();
-    if col.is_empty() {
-        return Err(CliError::args(format!("empty column name in --where {:?}", expr)));
-    }
-
-    // Strip one layer of surrounding quotes from value
-    let value = raw_value.trim();
-    let value = if (value.starts_with('"') && value.ends_with('"'))
-        || (value.starts_with('\'') && value.ends_with('\''))
-    {
-        &value[1..value.len() - 1]
-    } else {
-        value
-    };
-
-    Ok(WhereClause {
-        column: col.trim().to_lowercase(),
-        op,
-        value: value.to_string(),
-    })
-}
-
-fn resolve_where_columns(
-    clauses: &[WhereClause],
-    canonical_headers: &[String],
-) -> Result<Vec<ResolvedWhere>, CliError> {
-    let headers: Vec<String> = canonical_headers
-        .iter()
-        .map(|h| h.trim().to_lowercase())
-        .collect();
-
-    let mut resolved = Vec::with_capacity(clauses.len());
-    for clause in clauses {
-        let col_idx = headers.iter().position(|h| h == &clause.column);
-        match col_idx {
-            Some(idx) => {
-                resolved.push(ResolvedWhere {
-                    col: idx,
-                    op: clause.op,
-                    value: clause.value.clone(),
-                    numeric_value: lenient_parse_f64(&clause.value),
-                });
-            }
-            None => {
-                let available: Vec<String> = canonical_headers
-                    .iter()
-                    .map(|h| h.trim().to_string())
-                    .filter(|h| !h.is_empty())
-                    .collect();
-                return Err(CliError::args(format!("unknown column {:?}", clause.column))
-                    .with_hint(format!("available columns: {}", available.join(", "))));
-            }
-        }
-    }
-    Ok(resolved)
-}
-
-fn row_matches(
-    sheet: &visigrid_engine::sheet::Sheet,
-    row: usize,
-    conditions: &[ResolvedWhere],
-    skip_counts: &mut [usize],
-) -> bool {
-    for (i, cond) in conditions.iter().enumerate() {
-        let cell = sheet.get_display(row, cond.col);
-        let matches = match cond.op {
-            WhereOp::Contains => cell.to_lowercase().contains(&cond.value.to_lowercase()),
-            WhereOp::Lt => {
-                if let Some(rhs) = cond.numeric_value {
-                    match lenient_parse_f64(&cell) {
-                        Some(lhs) => lhs < rhs,
-                        None => {
-                            skip_counts[i] += 1;
-                            false
-                        }
-                    }
-                } else {
-                    false
-                }
-            }
-            WhereOp::Gt => {
-                if let Some(rhs) = cond.numeric_value {
-                    match lenient_parse_f64(&cell) {
-                        Some(lhs) => lhs > rhs,
-                        None => {
-                            skip_counts[i] += 1;
-                            false
-                        }
-                    }
-                } else {
-                    false
-                }
-            }
-            WhereOp::Eq => {
-                if let Some(rhs) = cond.numeric_value {
-                    // Numeric equality
-                    match lenient_parse_f64(&cell) {
-                        Some(lhs) => lhs == rhs,
-                        None => {
-                            skip_counts[i] += 1;
-                            false
-                        }
-                    }
-                } else {
-                    // String equality (case-insensitive)
-                    cell.eq_ignore_ascii_case(&cond.value)
-                }
-            }
-            WhereOp::NotEq => {
-                if let Some(rhs) = cond.numeric_value {
-                    // Numeric not-equals
-                    match lenient_parse_f64(&cell) {
-                        Some(lhs) => lhs != rhs,
-                        None => {
-                            skip_counts[i] += 1;
-                            false
-                        }
-                    }
-                } else {
-                    // String not-equals (case-insensitive)
-                    !cell.eq_ignore_ascii_case(&cond.value)
-                }
-            }
-        };
-        if !matches {
-            return false;
-        }
-    }
-    true
-}
-
-fn filter_row_indices(
-    sheet: &visigrid_engine::sheet::Sheet,
-    conditions: &[ResolvedWhere],
-    header_row: usize,
-) -> (Vec<usize>, Vec<usize>) {
-    let (rows, _) = get_data_bounds(sheet);
-    let mut matched = Vec::new();
-    let mut skip_counts = vec![0usize; conditions.len()];
-    for row in (header_row + 1)..rows {
-        if row_matches(sheet, row, conditions, &mut skip_counts) {
-            matched.push(row);
-        }
-    }
-    (matched, skip_counts)
-}
-
 // ============================================================================
 // --select helpers
 // ============================================================================
@@ -1685,9 +2211,12 @@ fn parse_select_args(select_args: &[String]) -> Vec<String> {
         .collect()
 }
 
+/// Resolve column names (case-insensitive) to header indices; `flag` names
+/// the option in error messages.
 fn resolve_select_columns(
     select_names: &[String],
     canonical_headers: &[String],
+    flag: &str,
 ) -> Result<Vec<(usize, String)>, CliError> {
     // Build O(1) lookup: lowercased → (index, canonical name)
     let mut map: HashMap<String, (usize, String)> = HashMap::new();
@@ -1706,7 +2235,7 @@ fn resolve_select_columns(
             Some((idx, canonical)) => {
                 if !seen_indices.insert(*idx) {
                     return Err(CliError::args(
-                        format!("duplicate column in --select: \"{}\"", name)
+                        format!("duplicate column in {}: \"{}\"", flag, name)
                     ));
                 }
                 result.push((*idx, canonical.clone()));
@@ -1725,7 +2254,7 @@ fn resolve_select_columns(
                     String::new()
                 };
                 return Err(CliError::args(
-                    format!("unknown column in --select: \"{}\"", name)
+                    format!("unknown column in {}: \"{}\"", flag, name)
                 ).with_hint(format!("available columns: {}{}", available.join(", "), suffix)));
             }
         }
@@ -1766,7 +2295,7 @@ fn main() -> ExitCode {
             eprintln!("       visigrid --help for more information");
             Ok(())
         }
-        Some(Commands::ListFunctions) => cmd_list_functions(),
+        Some(Commands::ListFunctions { json, by_category }) => cmd_list_functions(json, by_category),
         Some(Commands::Convert {
             input,
             from,
@@ -1778,8 +2307,18 @@ fn main() -> ExitCode {
             r#where: where_clauses,
             select: select_args,
             rename,
+            transpose,
+            fill_down,
+            fill_reset_on_blank_row,
+            text_columns,
+            strict_numbers,
             quiet,
-        }) => cmd_convert(input, from, to, output, sheet, delimiter, headers, where_clauses, select_args, rename, quiet),
+            encoding,
+            locale,
+            widths,
+            colspec,
+            allow_insecure,
+        }) => cmd_convert(input, from, to, output, sheet, delimiter, headers, where_clauses, select_args, rename, transpose, fill_down, fill_reset_on_blank_row, text_columns, strict_numbers, quiet, encoding, locale, widths, colspec, allow_insecure),
         Some(Commands::Calc {
             formula,
             from,
@@ -1787,11 +2326,18 @@ fn main() -> ExitCode {
             delimiter,
             headers,
             spill,
+            spill_out,
             json,
+            strict_numbers,
+            fail_on_empty,
+            fail_on_error,
+            round_mode,
+            locale,
         }) => {
             // --json implies --spill json for array results
             let effective_spill = if json && spill.is_none() { Some(SpillFormat::Json) } else { spill };
-            cmd_calc(formula, from, into, delimiter, headers, effective_spill, json)
+            let policy = sheet_ops::CalcPolicy { fail_on_empty, fail_on_error };
+            cmd_calc(formula, from, into, delimiter, headers, effective_spill, spill_out, json, strict_numbers, policy, round_mode.into(), locale)
         }
         Some(Commands::Open { file }) => cmd_open(file),
         Some(Commands::Replay {
@@ -1814,7 +2360,10 @@ fn main() -> ExitCode {
             r#match,
             key_transform,
             compare,
+            ignore,
             tolerance,
+            empty_as_zero,
+            locale,
             on_duplicate: _,
             on_ambiguous,
             out,
@@ -1829,37 +2378,69 @@ fn main() -> ExitCode {
             save_ambiguous,
             contains_column,
             no_fail,
+            baseline,
             export,
             export_side,
             json,
+            stream,
+            progress,
+            summary_only,
+            print_schema,
         }) => {
-            // --json forces --out json and --quiet (logs to stderr only)
-            let effective_out = if json { DiffOutputFormat::Json } else { out };
-            let effective_quiet = quiet || json;
-            cmd_diff(
-                left, right, key, r#match, key_transform, compare, tolerance,
-                on_ambiguous, effective_out, output, summary, no_headers, header_row, delimiter,
-                stdin_format, strict_exit, effective_quiet, save_ambiguous, contains_column, no_fail,
-                export, export_side,
-            )
+            if print_schema {
+                cmd_diff_print_schema()
+            } else {
+                // Both are present unless --print-schema (clap enforces this)
+                let (left, right) = (left.unwrap_or_default(), right.unwrap_or_default());
+                // --json forces --out json and --quiet (logs to stderr only).
+                // Without --out, a terminal gets the table and everything else JSON.
+                let stdout_tty = output.is_none() && atty::is(atty::Stream::Stdout);
+                let effective_out = match (json, out) {
+                    (true, _) => DiffOutputFormat::Json,
+                    (false, Some(out)) => out,
+                    (false, None) if stdout_tty && !summary_only => DiffOutputFormat::Table,
+                    (false, None) => DiffOutputFormat::Json,
+                };
+                let effective_quiet = quiet || json;
+                cmd_diff(
+                    left, right, key, r#match, key_transform, compare, ignore, tolerance, empty_as_zero, locale,
+                    on_ambiguous, effective_out, output, summary, no_headers, header_row, delimiter,
+                    stdin_format, strict_exit, effective_quiet, save_ambiguous, contains_column, no_fail,
+                    baseline, export, export_side, stream, summary_only,
+                    progress,
+                )
+            }
+        }
+        Some(Commands::Diff3 { base, left, right, key, key_transform, compare, tolerance, no_headers, delimiter, output, no_fail, quiet }) => {
+            cmd_diff3(base, left, right, key, key_transform, compare, tolerance, no_headers, delimiter, output, no_fail, quiet)
         }
         Some(Commands::Sessions { json }) => cmd_sessions(json),
-        Some(Commands::Attach { session }) => cmd_attach(session),
-        Some(Commands::Apply { ops, session, atomic, expected_revision, wait, wait_timeout }) => {
-            cmd_apply(ops, session, atomic, expected_revision, wait, wait_timeout)
+        Some(Commands::Attach { session, token_file, interactive, sheet, max_rows, max_cols }) => {
+            if interactive {
+                cmd_attach_interactive(session, token_file, sheet, max_rows, max_cols)
+            } else {
+                cmd_attach(session, token_file)
+            }
+        }
+        Some(Commands::Apply { ops, ordered, session, atomic, expected_revision, wait, wait_timeout, request_timeout, chunk_size, token_file }) => {
+            cmd_apply(ops, ordered, session, atomic, expected_revision, wait, wait_timeout, request_timeout, chunk_size, token_file)
         }
-        Some(Commands::Inspect { range, session, sheet, json }) => cmd_inspect(range, session, sheet, json),
-        Some(Commands::Stats { session, json }) => cmd_stats(session, json),
-        Some(Commands::View { session, range, sheet, follow, width }) => {
-            cmd_view(session, range, sheet, follow, width)
+        Some(Commands::Inspect { range, session, sheet, json, token_file }) => cmd_inspect(range, session, sheet, json, token_file),
+        Some(Commands::Stats { session, json, token_file }) => cmd_stats(session, json, token_file),
+        Some(Commands::Repl { session, request_timeout, token_file }) => cmd_repl(session, request_timeout, token_file),
+        Some(Commands::View { session, range, sheet, follow, width, out, out_format, token_file }) => {
+            cmd_view(session, range, sheet, follow, width, out, out_format, token_file)
         }
         Some(Commands::Peek {
             file, headers, no_headers: _, sheet, max_rows,
-            force, width_scan_rows, shape, plain, delimiter, recompute,
-            no_tui, tui: force_tui, json,
-        }) => {
+            force, width_scan_rows, shape, plain, delimiter, widths, colspec, recompute,
+            no_tui, tui: force_tui, json, quiet, allow_insecure, no_restore,
+        }) => remote::resolve_input(file, allow_insecure).and_then(|(file, download)| {
+            // Downloads land in a fresh temp file each time: nothing to restore
+            let restore = !no_restore && download.is_none();
+            let layout = fixed_layout(widths.as_deref(), colspec.as_deref())?;
             if json {
-                cmd_peek_json(file, headers, sheet, max_rows, force, delimiter)
+                cmd_peek_json(file, headers, sheet, max_rows, force, delimiter, layout, quiet)
             } else {
                 // TTY detection: interactive only when stdin+stdout are TTY and not --no-tui
                 let stdin_tty = atty::is(atty::Stream::Stdin);
@@ -1876,31 +2457,44 @@ fn main() -> ExitCode {
                     } else {
                         stdin_tty && stdout_tty
                     };
-                    cmd_peek(file, headers, sheet, max_rows, force, width_scan_rows, shape, interactive, delimiter, recompute)
+                    cmd_peek(file, headers, sheet, max_rows, force, width_scan_rows, shape, interactive, delimiter, layout, recompute, quiet, restore)
                 }
             }
-        }
+        }),
         Some(Commands::Login { token, api_base }) => hub::cmd_login(token, api_base),
         Some(Commands::Fill {
-            template, csv, target, headers, clear, out, delimiter, json,
-        }) => fill::cmd_fill(template, csv, target, headers, clear, out, delimiter, json),
+            template, csv, json_input, target, headers, clear, out, backup, backup_dir, delimiter, json,
+        }) => {
+            let source = match (csv, json_input) {
+                (Some(path), _) => fill::FillSource::Csv(path),
+                (None, Some(path)) => fill::FillSource::Json(path),
+                (None, None) => unreachable!("clap requires --csv or --json-input"),
+            };
+            let backup = (backup || backup_dir.is_some()).then_some(backup_dir);
+            fill::cmd_fill(template, source, target, headers, clear, out, backup, delimiter, json)
+        }
         Some(Commands::Publish {
             file, repo, dataset, source_type, source_identity, query_hash,
             wait, no_wait, fail_on_check_failure, no_fail, output, assert_sum,
             assert_cell, reset_baseline, row_count_policy, columns_added_policy,
-            columns_removed_policy, strict,
+            columns_removed_policy, strict, tags,
         }) => hub::cmd_publish(
             file, repo, dataset, source_type, source_identity, query_hash,
             wait && !no_wait, fail_on_check_failure && !no_fail, output, assert_sum,
             assert_cell, reset_baseline, row_count_policy, columns_added_policy,
-            columns_removed_policy, strict,
+            columns_removed_policy, strict, tags,
         ),
         Some(Commands::Sheet(sheet_cmd)) => match sheet_cmd {
-            SheetCommands::Apply { output, lua, verify, stamp, dry_run, json } => {
-                cmd_sheet_apply(output, lua, verify, stamp, dry_run, json)
+            SheetCommands::Apply { output, lua, verify, stamp, dry_run, emit_patch, backup, backup_dir, json, protected, check, title, description, author } => {
+                let meta = workbook_meta_args(title, description, author);
+                let backup = (backup || backup_dir.is_some()).then_some(backup_dir);
+                cmd_sheet_apply(output, lua, verify, stamp, dry_run, emit_patch, backup, json, protected, check, meta)
             }
-            SheetCommands::Inspect { file, target, workbook, sheet, sheets, non_empty, include_style, value, json, ndjson, format, headers, delimiter, calc, lightweight } => {
-                cmd_sheet_inspect(file, target, workbook, sheet, sheets, non_empty, include_style, value, json, ndjson, format, headers, delimiter, calc, lightweight)
+            SheetCommands::Inspect { file, target, workbook, sheet, sheets, non_empty, include_style, include_comments, include_conditional, value, count, json, ndjson, format, headers, transpose, delimiter, widths, colspec, calc, fail_on_empty, fail_on_error, round_mode, lightweight, diff_with, quiet } => {
+                let policy = sheet_ops::CalcPolicy { fail_on_empty, fail_on_error };
+                fixed_layout(widths.as_deref(), colspec.as_deref()).and_then(|layout| {
+                    cmd_sheet_inspect(file, target, workbook, sheet, sheets, non_empty, include_style, include_comments, include_conditional, value, count, json, ndjson, format, headers, transpose, delimiter, layout, calc, policy, round_mode.into(), lightweight, diff_with, quiet)
+                })
             }
             SheetCommands::Verify { file, fingerprint } => {
                 cmd_sheet_verify(file, fingerprint)
@@ -1908,25 +2502,43 @@ fn main() -> ExitCode {
             SheetCommands::Fingerprint { file, json } => {
                 cmd_sheet_fingerprint(file, json)
             }
-            SheetCommands::Import { source, output, sheet, headers, formulas, nulls, stamp, verify, dry_run, json, delimiter } => {
-                cmd_sheet_import(source, output, sheet, headers, formulas, nulls, stamp, verify, dry_run, json, delimiter)
+            SheetCommands::Import { source, output, sheet, headers, formulas, nulls, stamp, verify, dry_run, json, delimiter, trim_empty, protected, title, description, author } => {
+                let meta = workbook_meta_args(title, description, author);
+                cmd_sheet_import(source, output, sheet, headers, formulas, nulls, stamp, verify, dry_run, json, delimiter, trim_empty, protected, meta)
             }
             SheetCommands::Upgrade { file, out, max_bytes, dry_run, json } => {
                 cmd_sheet_upgrade(file, out, max_bytes, dry_run, json)
             }
+            SheetCommands::Repair { file, out, json } => {
+                cmd_sheet_repair(file, out, json)
+            }
+            SheetCommands::Deps { file, cell, sheet, downstream: _, upstream, json } => {
+                cmd_sheet_deps(file, cell, sheet, upstream, json)
+            }
+            SheetCommands::Name(name_cmd) => match name_cmd {
+                NameCommands::Add { file, name, range, description, force, json } => {
+                    cmd_sheet_name_add(file, name, range, description, force, json)
+                }
+                NameCommands::List { file, json } => cmd_sheet_name_list(file, json),
+                NameCommands::Remove { file, name, json } => cmd_sheet_name_remove(file, name, json),
+            },
         }
         Some(Commands::Hub(hub_cmd)) => match hub_cmd {
-            HubCommands::Publish { file, repo, message, notes, checks, summary, lock, json, dry_run, no_wait, timeout } => {
-                hub::cmd_hub_publish(file, repo, message, notes, checks, summary, lock, json, dry_run, no_wait, timeout)
+            HubCommands::Publish { file, repo, message, notes, checks, summary, lock, json, dry_run, no_wait, timeout, tags, if_changed } => {
+                hub::cmd_hub_publish(file, repo, message, notes, checks, summary, lock, json, dry_run, no_wait, timeout, tags, if_changed)
+            }
+            HubCommands::Get { repo, revision, output, dataset, json } => {
+                hub::cmd_hub_get(repo, revision, output, dataset, json)
             }
         }
         Some(Commands::Pipeline(pipeline_cmd)) => match pipeline_cmd {
             PipelineCommands::Publish {
-                source, repo, headers, formulas, stamp, checks_calc, checks_file,
-                delimiter, sheet, message, notes, out, json, dry_run, no_wait, timeout,
+                source, repo, headers, formulas, stamp, checks_calc, fail_on_empty, fail_on_error,
+                checks_file, delimiter, sheet, message, notes, out, json, dry_run, no_wait, timeout,
             } => {
+                let checks_policy = sheet_ops::CalcPolicy { fail_on_empty, fail_on_error };
                 hub::cmd_pipeline_publish(
-                    source, repo, headers, formulas, stamp, checks_calc, checks_file,
+                    source, repo, headers, formulas, stamp, checks_calc, checks_policy, checks_file,
                     delimiter, sheet, message, notes, out, json, dry_run, no_wait, timeout,
                 )
             }
@@ -1949,8 +2561,8 @@ fn main() -> ExitCode {
             ScriptsCommands::List { file, json } => {
                 scripts::cmd_scripts_list(file, json)
             }
-            ScriptsCommands::Run { name, file, plan, apply, json } => {
-                scripts::cmd_scripts_run(name, file, plan, apply, json)
+            ScriptsCommands::Run { name, file, plan, diff, tolerance, apply, json } => {
+                scripts::cmd_scripts_run(name, file, plan, diff, tolerance, apply, json)
             }
         }
         Some(Commands::Runs(runs_cmd)) => match runs_cmd {
@@ -2175,18 +2787,20 @@ impl CliError {
                 Some("is VisiGrid GUI running with session server enabled?".to_string())
             }
             session::SessionError::AuthFailed(_) => {
-                Some("check VISIGRID_SESSION_TOKEN environment variable".to_string())
+                Some("check VISIGRID_SESSION_TOKEN (or --token-file / VISIGRID_SESSION_TOKEN_FILE)".to_string())
             }
-            session::SessionError::ServerError { code: err_code, .. }
-                if err_code == "writer_conflict" =>
-            {
+            session::SessionError::ServerError { code: session::ProtocolErrorCode::WriterConflict, .. } => {
                 Some("another client holds the write lease; retry later".to_string())
             }
-            session::SessionError::ServerError { code: err_code, .. }
-                if err_code == "revision_mismatch" =>
-            {
+            session::SessionError::ServerError { code: session::ProtocolErrorCode::RevisionMismatch, .. } => {
                 Some("workbook was modified; re-fetch and retry".to_string())
             }
+            session::SessionError::Timeout { .. } => {
+                Some("the request may still complete; check the revision before retrying, or raise --request-timeout".to_string())
+            }
+            session::SessionError::MessageTooLarge { .. } => {
+                Some("nothing was sent; split the ops into smaller batches with --chunk-size".to_string())
+            }
             _ => None,
         };
         Self { code, message: err.to_string(), hint }
@@ -2203,13 +2817,45 @@ impl CliError {
 // list-functions
 // ============================================================================
 
-fn cmd_list_functions() -> Result<(), CliError> {
-    let functions = visigrid_engine::formula::functions::list_functions();
+fn cmd_list_functions(json: bool, by_category: bool) -> Result<(), CliError> {
+    use visigrid_engine::formula::functions::{list_function_meta, FunctionCategory};
+
+    let functions = list_function_meta();
     let stdout = io::stdout();
     let mut handle = stdout.lock();
 
-    for name in functions {
-        writeln!(handle, "{}", name).map_err(|e| CliError::io(e.to_string()))?;
+    if json {
+        let entries: Vec<serde_json::Value> = functions.iter()
+            .map(|f| serde_json::json!({
+                "name": f.name,
+                "category": f.category.as_str(),
+                "arity": { "min": f.min_args, "max": f.max_args },
+                "description": f.description,
+                "volatile": f.is_volatile(),
+            }))
+            .collect();
+        let out = serde_json::to_string_pretty(&entries).map_err(|e| CliError::io(e.to_string()))?;
+        writeln!(handle, "{}", out).map_err(|e| CliError::io(e.to_string()))?;
+        return Ok(());
+    }
+
+    if by_category {
+        let width = functions.iter().map(|f| f.name.len()).max().unwrap_or(0);
+        for (i, category) in FunctionCategory::ALL.iter().enumerate() {
+            if i > 0 {
+                writeln!(handle).map_err(|e| CliError::io(e.to_string()))?;
+            }
+            writeln!(handle, "{}", category.label()).map_err(|e| CliError::io(e.to_string()))?;
+            for f in functions.iter().filter(|f| f.category == *category) {
+                writeln!(handle, "  {:<width$}  {}", f.name, f.description, width = width)
+                    .map_err(|e| CliError::io(e.to_string()))?;
+            }
+        }
+        return Ok(());
+    }
+
+    for f in functions {
+        writeln!(handle, "{}", f.name).map_err(|e| CliError::io(e.to_string()))?;
     }
 
     Ok(())
@@ -2230,14 +2876,26 @@ fn cmd_convert(
     where_clauses: Vec<String>,
     select_args: Vec<String>,
     rename: Option<String>,
+    transpose: bool,
+    fill_down_args: Vec<String>,
+    fill_reset_on_blank_row: bool,
+    text_column_args: Vec<String>,
+    strict_numbers: bool,
     quiet: bool,
+    encoding: Option<InputEncoding>,
+    locale: NumberLocale,
+    widths: Option<String>,
+    colspec: Option<String>,
+    allow_insecure: bool,
 ) -> Result<(), CliError> {
-
-    // Validate --where requires --headers
-    if !where_clauses.is_empty() && !headers {
-        return Err(CliError::args("--where requires --headers")
-            .with_hint("add --headers so column names can be resolved"));
-    }
+    // URL input: download first, then read the temp file like a local path
+    let (input, _download) = match input {
+        Some(path) => {
+            let (path, download) = remote::resolve_input(path, allow_insecure)?;
+            (Some(path), download)
+        }
+        None => (None, None),
+    };
 
     // Validate --select requires --headers
     if !select_args.is_empty() && !headers {
@@ -2245,6 +2903,18 @@ fn cmd_convert(
             .with_hint("add --headers so column names can be resolved"));
     }
 
+    // Validate --fill-down requires --headers
+    if !fill_down_args.is_empty() && !headers {
+        return Err(CliError::args("--fill-down requires --headers")
+            .with_hint("add --headers so column names can be resolved"));
+    }
+
+    // Validate --text-columns requires --headers
+    if !text_column_args.is_empty() && !headers {
+        return Err(CliError::args("--text-columns requires --headers")
+            .with_hint("add --headers so column names can be resolved"));
+    }
+
     // Validate --rename requires --headers
     if rename.is_some() && !headers {
         return Err(CliError::args("--rename requires --headers")
@@ -2257,14 +2927,18 @@ fn cmd_convert(
         None => vec![],
     };
 
-    // Determine input format
+    let layout = fixed_layout(widths.as_deref(), colspec.as_deref())?;
+
+    // Determine input format (--widths/--colspec imply fixed-width)
     let input_format = match (&input, from) {
+        (_, None) if layout.is_some() => Format::Fixed,
         (None, None) => return Err(CliError::args("stdin requires --from to specify the input format")
             .with_hint("vgrid convert --from csv -t json")),
         (None, Some(f)) => f,
         (Some(path), None) => infer_format(path)?,
         (Some(_), Some(f)) => f, // --from overrides extension
     };
+    check_fixed_layout(input_format, layout.as_ref())?;
 
     // Validate --sheet is only used with multi-sheet formats
     if sheet_arg.is_some() && !matches!(input_format, Format::Xlsx | Format::Sheet) {
@@ -2272,12 +2946,116 @@ fn cmd_convert(
             .with_hint("--sheet works with .sheet and .xlsx files"));
     }
 
-    // Read input into sheet (convert always starts at A1)
-    let mut sheet = match &input {
-        Some(path) => read_file(path, input_format, delimiter, sheet_arg.as_deref())?,
-        None => read_stdin(input_format, delimiter, 0, 0)?,
+    // Validate --encoding is only used with text formats
+    if encoding.is_some() && !matches!(input_format, Format::Csv | Format::Tsv | Format::Lines) {
+        return Err(CliError::args("--encoding is only supported for csv, tsv, and lines input")
+            .with_hint("json and ndjson must be UTF-8; xlsx and sheet carry their own encoding"));
+    }
+
+    // --text-columns re-reads fields from the source text, so it needs delimited input
+    if !text_column_args.is_empty() && !matches!(input_format, Format::Csv | Format::Tsv) {
+        return Err(CliError::args("--text-columns is only supported for csv and tsv input")
+            .with_hint("export the data as csv first"));
+    }
+    // Locale notation (1.234,56) is never canonical, so the two would fight over the same fields
+    if strict_numbers && locale != NumberLocale::En {
+        return Err(CliError::args("--strict-numbers cannot be combined with --locale")
+            .with_hint("--strict-numbers only accepts en notation such as 1234.56"));
+    }
+    let keep_source = matches!(input_format, Format::Csv | Format::Tsv);
+    let text_delimiter = if matches!(input_format, Format::Tsv) { b'\t' } else { delimiter as u8 };
+
+    // Read input into sheet (convert always starts at A1). Delimited input
+    // keeps its source text and delimiter, to check for ragged rows and to
+    // restore raw fields for --text-columns.
+    let mut source: Option<(String, u8)> = None;
+    let parse_text = |text: &str| match (&layout, input_format) {
+        (Some(layout), _) => Ok(parse_fixed(text, layout, strict_numbers)),
+        (None, Format::Json) => parse_json(text, 0, 0, strict_numbers),
+        (None, Format::Ndjson) => parse_ndjson(text, 0, 0, strict_numbers),
+        (None, Format::Lines) => parse_lines(text, 0, 0, strict_numbers),
+        (None, _) => parse_input(text, input_format, delimiter, 0, 0),
+    };
+    // Same as read_file, keeping the text: csv files have their delimiter sniffed
+    let file_delimiter = |text: &str| {
+        if matches!(input_format, Format::Tsv) { b'\t' } else { visigrid_io::csv::sniff_delimiter(text) }
+    };
+    let mut sheet = match (&input, encoding) {
+        (Some(path), Some(enc)) => {
+            let text = visigrid_io::csv::read_file_with_encoding(path, enc.into())
+                .map_err(CliError::io)?;
+            if keep_source {
+                let delim = file_delimiter(&text);
+                let sheet = visigrid_io::csv::import_from_string(&text, delim)
+                    .map_err(CliError::parse)?;
+                source = Some((text, delim));
+                sheet
+            } else {
+                parse_text(&text)?
+            }
+        }
+        (Some(path), None) if keep_source => {
+            let text = visigrid_io::csv::read_file_as_utf8(path).map_err(CliError::parse)?;
+            let delim = file_delimiter(&text);
+            let sheet = visigrid_io::csv::import_from_string(&text, delim)
+                .map_err(CliError::parse)?;
+            source = Some((text, delim));
+            sheet
+        }
+        (Some(path), None) if layout.is_some() => {
+            let text = visigrid_io::csv::read_file_as_utf8(path).map_err(CliError::parse)?;
+            parse_text(&text)?
+        }
+        (Some(path), None) if strict_numbers && matches!(input_format, Format::Json | Format::Ndjson | Format::Lines) => {
+            let text = std::fs::read_to_string(path).map_err(|e| CliError::io(e.to_string()))?;
+            parse_text(&text)?
+        }
+        (Some(path), None) => read_file(path, input_format, delimiter, sheet_arg.as_deref())?,
+        (None, Some(enc)) => {
+            let mut bytes = Vec::new();
+            io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|e| CliError::io(e.to_string()))?;
+            if bytes.is_empty() {
+                return Err(CliError::parse("no input received on stdin")
+                    .with_hint("cat file.csv | vgrid convert --from csv -t json --encoding windows-1252"));
+            }
+            let text = visigrid_io::csv::decode_bytes(&bytes, enc.into());
+            let sheet = parse_text(&text)?;
+            if keep_source {
+                source = Some((text, text_delimiter));
+            }
+            sheet
+        }
+        (None, None) => {
+            let text = read_stdin_text()?;
+            let sheet = parse_text(&text)?;
+            if keep_source {
+                source = Some((text, text_delimiter));
+            }
+            sheet
+        }
     };
 
+    if !quiet {
+        if let Some(ragged) = source.as_ref().and_then(|(text, delim)| visigrid_io::csv::ragged_rows(text, *delim)) {
+            eprintln!("warning: {}", ragged.note());
+        }
+    }
+
+    if let Some((text, delim)) = source.as_ref().filter(|_| locale != NumberLocale::En) {
+        apply_number_locale(&mut sheet, text, *delim, 0, 0, locale)?;
+    }
+
+    if let Some((text, delim)) = source.as_ref().filter(|_| strict_numbers) {
+        restore_non_canonical_numbers(&mut sheet, text, *delim)?;
+    }
+
+    if transpose {
+        let (rows, cols) = get_data_bounds(&sheet);
+        sheet = sheet_ops::transpose_sheet(&sheet, rows, cols);
+    }
+
     let (bounds_rows, bounds_cols) = get_data_bounds(&sheet);
 
     // Find the actual header row (first non-empty row)
@@ -2317,25 +3095,57 @@ fn cmd_convert(
         vec![]
     };
 
-    // Ambiguous header check (once, before --where or --select resolution)
-    if (!where_clauses.is_empty() || !select_args.is_empty()) && headers {
+    // Ambiguous header check (once, before --fill-down, --where, or --select resolution)
+    if (!where_clauses.is_empty() || !select_args.is_empty() || !fill_down_args.is_empty() || !text_column_args.is_empty()) && headers {
         check_ambiguous_headers(&canonical_headers)?;
     }
 
+    // Restore text columns from the source before anything reads their values
+    let text_cols: Vec<usize> = if let Some((text, delim)) = source.as_ref().filter(|_| !text_column_args.is_empty()) {
+        let names = parse_select_args(&text_column_args);
+        if names.is_empty() {
+            return Err(CliError::args("empty --text-columns list"));
+        }
+        let cols: Vec<usize> = resolve_select_columns(&names, &canonical_headers, "--text-columns")?
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+        restore_text_columns(&mut sheet, text, *delim, &cols, header_row)?;
+        cols
+    } else {
+        vec![]
+    };
+
+    // Forward-fill blank cells (after load, before --where)
+    if !fill_down_args.is_empty() {
+        let fill_names = parse_select_args(&fill_down_args);
+        if fill_names.is_empty() {
+            return Err(CliError::args("empty --fill-down list"));
+        }
+        let cols: Vec<usize> = resolve_select_columns(&fill_names, &canonical_headers, "--fill-down")?
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+        let filled = sheet_ops::fill_down(
+            &mut sheet, &cols, header_row + 1, bounds_rows, bounds_cols, fill_reset_on_blank_row,
+        );
+        if !quiet && filled > 0 {
+            eprintln!("note: {} blank cells filled down", filled);
+        }
+    }
+
     // Resolve and apply --where filters
     let row_filter = if !where_clauses.is_empty() {
-        let parsed: Vec<WhereClause> = where_clauses
-            .iter()
-            .map(|e| parse_where(e))
-            .collect::<Result<Vec<_>, _>>()?;
-        let resolved = resolve_where_columns(&parsed, &canonical_headers)?;
-        let (indices, skip_counts) = filter_row_indices(&sheet, &resolved, header_row);
+        let parsed = filter::parse_where_args(&where_clauses)?;
+        let resolved = filter::resolve_where_columns(&parsed, &canonical_headers, headers, bounds_cols)?;
+        let data_start = if headers { header_row + 1 } else { 0 };
+        let (indices, skip_counts) = filter::filter_row_indices(&sheet, &resolved, data_start);
 
         // Report unparseable cells to stderr (suppressed by --quiet)
         if !quiet {
-            for (i, &count) in skip_counts.iter().enumerate() {
+            for (clause, &count) in parsed.clauses().iter().zip(&skip_counts) {
                 if count > 0 {
-                    eprintln!("note: {} rows skipped ({} not numeric)", count, parsed[i].column);
+                    eprintln!("note: {} rows skipped ({} not numeric)", count, clause.column);
                 }
             }
         }
@@ -2351,17 +3161,34 @@ fn cmd_convert(
         if select_names.is_empty() {
             return Err(CliError::args("empty --select list"));
         }
-        let resolved = resolve_select_columns(&select_names, &canonical_headers)?;
+        let resolved = resolve_select_columns(&select_names, &canonical_headers, "--select")?;
         Some(resolved)
     } else {
         None
     };
 
+    // Delimited output writes numbers in --locale notation; JSON keeps JSON numbers
+    if locale != NumberLocale::En && matches!(to, Format::Csv | Format::Tsv | Format::Lines) {
+        sheet = localize_numbers(&sheet, locale, &text_cols);
+    }
+
+    // A .sheet file is written whole, keeping text cells as text
+    if let (Format::Sheet, Some(path)) = (to, output.as_ref()) {
+        if row_filter.is_some() || col_filter.is_some() {
+            return Err(CliError::args("--where and --select are not supported with -t sheet")
+                .with_hint("convert to csv first, then import the filtered file"));
+        }
+        return visigrid_io::native::save(&sheet, path)
+            .map_err(|e| CliError::io(format!("{}: {}", path.display(), e)));
+    }
+
     // Write output
     let output_bytes = write_format(
         &sheet, to, delimiter, headers, header_row,
         row_filter.as_deref(),
         col_filter.as_deref(),
+        &text_cols,
+        strict_numbers,
     )?;
 
     match output {
@@ -2379,11 +3206,24 @@ fn cmd_convert(
     Ok(())
 }
 
+/// Lowercased extension naming the input's data format, looking through `.gz`.
+///
+/// Compressed input is read transparently for delimited text only; a
+/// compressed workbook is rejected here rather than failing inside the parser.
+fn input_extension(path: &std::path::Path) -> Result<Option<String>, CliError> {
+    let ext = visigrid_io::csv::data_extension(path);
+    let compressed = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("gz"));
+    if compressed && !matches!(ext.as_deref(), Some("csv" | "tsv" | "tab" | "txt")) {
+        return Err(CliError::args(format!(
+            "gzip input is supported for CSV/TSV only, got {}",
+            path.display()
+        )).with_hint("name the file like data.csv.gz or data.tsv.gz"));
+    }
+    Ok(ext)
+}
+
 fn infer_inspect_format(path: &PathBuf) -> Result<InspectFormat, CliError> {
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(|e| e.to_lowercase());
+    let ext = input_extension(path)?;
 
     match ext.as_deref() {
         Some("csv") => Ok(InspectFormat::Csv),
@@ -2398,21 +3238,19 @@ fn infer_inspect_format(path: &PathBuf) -> Result<InspectFormat, CliError> {
 }
 
 fn infer_format(path: &PathBuf) -> Result<Format, CliError> {
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(|e| e.to_lowercase());
+    let ext = input_extension(path)?;
 
     match ext.as_deref() {
         Some("csv") => Ok(Format::Csv),
         Some("tsv") => Ok(Format::Tsv),
         Some("json") => Ok(Format::Json),
+        Some("ndjson") | Some("jsonl") => Ok(Format::Ndjson),
         Some("xlsx") | Some("xls") | Some("xlsb") | Some("ods") => Ok(Format::Xlsx),
         Some("sheet") => Ok(Format::Sheet),
         _ => Err(CliError::args(format!(
             "cannot infer format from extension {:?}",
             ext.as_deref().unwrap_or("(none)")
-        )).with_hint("use --from with one of: csv, tsv, json, xlsx, sheet")),
+        )).with_hint("use --from with one of: csv, tsv, json, ndjson, xlsx, sheet")),
     }
 }
 
@@ -2442,40 +3280,146 @@ fn read_file(path: &PathBuf, format: Format, _delimiter: char, sheet_arg: Option
         Format::Json => {
             let content = std::fs::read_to_string(path)
                 .map_err(|e| CliError::io(e.to_string()))?;
-            parse_json(&content, 0, 0)
+            parse_json(&content, 0, 0, false)
         }
         Format::Lines => {
             let content = std::fs::read_to_string(path)
                 .map_err(|e| CliError::io(e.to_string()))?;
-            parse_lines(&content, 0, 0)
+            parse_lines(&content, 0, 0, false)
         }
+        Format::Ndjson => {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| CliError::io(e.to_string()))?;
+            parse_ndjson(&content, 0, 0, false)
+        }
+        Format::JsonlValues => Err(jsonl_values_input_error()),
+        Format::Fixed => Err(fixed_layout_required_error()),
     }
 }
 
-fn read_stdin(format: Format, delimiter: char, into_row: usize, into_col: usize) -> Result<visigrid_engine::sheet::Sheet, CliError> {
-    let mut input = String::new();
-    io::stdin()
-        .read_to_string(&mut input)
-        .map_err(|e| CliError::io(e.to_string()))?;
+fn jsonl_values_input_error() -> CliError {
+    CliError::args("jsonl-values is an output-only format")
+        .with_hint("read the stream back with -f lines, or use -f json")
+}
 
-    if input.is_empty() {
-        return Err(CliError::parse("no input received on stdin")
-            .with_hint("cat file.csv | vgrid calc '=SUM(A:A)' --from csv"));
-    }
+fn ndjson_output_error() -> CliError {
+    CliError::args("ndjson is an input-only format")
+        .with_hint("use -t json for an array of objects, or -t jsonl-values for one column")
+}
 
-    match format {
-        Format::Csv => parse_csv(&input, delimiter as u8, into_row, into_col),
-        Format::Tsv => parse_csv(&input, b'\t', into_row, into_col),
-        Format::Json => parse_json(&input, into_row, into_col),
-        Format::Lines => parse_lines(&input, into_row, into_col),
-        Format::Xlsx | Format::Sheet => {
-            Err(CliError::args("xlsx and sheet formats require file input"))
-        }
+fn fixed_layout_required_error() -> CliError {
+    CliError::args("fixed-width input needs --widths or --colspec")
+        .with_hint("convert, peek, and sheet inspect read fixed-width text, e.g. --widths '10,8,20'")
+}
+
+/// Parse `--widths` / `--colspec` (clap keeps them mutually exclusive).
+fn fixed_layout(widths: Option<&str>, colspec: Option<&str>) -> Result<Option<FixedLayout>, CliError> {
+    let layout = match (widths, colspec) {
+        (Some(spec), _) => FixedLayout::from_widths(spec),
+        (None, Some(spec)) => FixedLayout::from_colspec(spec),
+        (None, None) => return Ok(None),
+    };
+    layout.map(Some).map_err(|e| {
+        CliError::args(e).with_hint("e.g. --widths '10,8,20' or --colspec 'name:0-10,amount:10-18'")
+    })
+}
+
+/// A layout is required for fixed-width input and meaningless for any other.
+fn check_fixed_layout(format: Format, layout: Option<&FixedLayout>) -> Result<(), CliError> {
+    match (format, layout) {
+        (Format::Fixed, None) => Err(fixed_layout_required_error()),
+        (Format::Fixed, Some(_)) | (_, None) => Ok(()),
+        (_, Some(_)) => Err(CliError::args("--widths and --colspec only apply to fixed-width input")
+            .with_hint("drop --from, or use --from fixed")),
     }
 }
 
-fn parse_csv(content: &str, delimiter: u8, into_row: usize, into_col: usize) -> Result<visigrid_engine::sheet::Sheet, CliError> {
-    use visigrid_engine::sheet::{Sheet, SheetId};
+/// Load one side of a diff from `path`, or stdin when `None`. Under a
+/// non-en `--locale`, csv/tsv fields keep their source spelling, so the
+/// comparison reads `1.000` in `locale` rather than as the en number 1.
+fn read_diff_side(
+    path: Option<&PathBuf>,
+    format: Format,
+    delimiter: char,
+    locale: NumberLocale,
+) -> Result<visigrid_engine::sheet::Sheet, CliError> {
+    let delimited = matches!(format, Format::Csv | Format::Tsv);
+    if locale == NumberLocale::En || !delimited {
+        return match path {
+            Some(p) => read_file(p, format, delimiter, None),
+            None => read_stdin(format, delimiter, 0, 0),
+        };
+    }
+
+    // Same delimiter choice as read_file (sniffed) and read_stdin (as given)
+    let (text, delim) = match path {
+        Some(p) => {
+            let text = visigrid_io::csv::read_file_as_utf8(p).map_err(CliError::parse)?;
+            let delim = if matches!(format, Format::Tsv) { b'\t' } else { visigrid_io::csv::sniff_delimiter(&text) };
+            (text, delim)
+        }
+        None => {
+            let text = read_stdin_text()?;
+            let delim = if matches!(format, Format::Tsv) { b'\t' } else { delimiter as u8 };
+            (text, delim)
+        }
+    };
+    let mut sheet = parse_csv(&text, delim, 0, 0)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delim)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(visigrid_io::csv::strip_bom(&text).as_bytes());
+    for (row_idx, result) in reader.records().enumerate() {
+        let record = result.map_err(|e| CliError::parse(format!("line {}: {}", row_idx + 1, e)))?;
+        for (col_idx, field) in record.iter().enumerate().filter(|(_, f)| !f.is_empty()) {
+            sheet.set_text(row_idx, col_idx, field);
+        }
+    }
+    Ok(sheet)
+}
+
+fn read_stdin(format: Format, delimiter: char, into_row: usize, into_col: usize) -> Result<visigrid_engine::sheet::Sheet, CliError> {
+    let input = read_stdin_text()?;
+    parse_input(&input, format, delimiter, into_row, into_col)
+}
+
+fn read_stdin_text() -> Result<String, CliError> {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| CliError::io(e.to_string()))?;
+
+    if input.is_empty() {
+        return Err(empty_stdin_error());
+    }
+    Ok(input)
+}
+
+fn empty_stdin_error() -> CliError {
+    CliError::parse("no input received on stdin")
+        .with_hint("cat file.csv | vgrid calc '=SUM(A:A)' --from csv")
+}
+
+fn parse_input(input: &str, format: Format, delimiter: char, into_row: usize, into_col: usize) -> Result<visigrid_engine::sheet::Sheet, CliError> {
+    match format {
+        Format::Csv => parse_csv(input, delimiter as u8, into_row, into_col),
+        Format::Tsv => parse_csv(input, b'\t', into_row, into_col),
+        Format::Json => parse_json(input, into_row, into_col, false),
+        Format::Lines => parse_lines(input, into_row, into_col, false),
+        Format::Ndjson => parse_ndjson(input, into_row, into_col, false),
+        Format::JsonlValues => Err(jsonl_values_input_error()),
+        Format::Fixed => Err(fixed_layout_required_error()),
+        Format::Xlsx | Format::Sheet => {
+            Err(CliError::args("xlsx and sheet formats require file input"))
+        }
+    }
+}
+
+fn parse_csv(content: &str, delimiter: u8, into_row: usize, into_col: usize) -> Result<visigrid_engine::sheet::Sheet, CliError> {
+    use visigrid_engine::sheet::{Sheet, SheetId};
+
+    let content = visigrid_io::csv::strip_bom(content);
 
     let mut reader = csv::ReaderBuilder::new()
         .delimiter(delimiter)
@@ -2491,12 +3435,100 @@ fn parse_csv(content: &str, delimiter: u8, into_row: usize, into_col: usize) ->
                 sheet.set_value(into_row + row_idx, into_col + col_idx, field);
             }
         }
+        // Grow past the initial grid so bounds (and A:A refs) cover every row
+        sheet.rows = sheet.rows.max(into_row + row_idx + 1);
+        sheet.cols = sheet.cols.max(into_col + record.len());
     }
 
     Ok(sheet)
 }
 
-fn parse_json(content: &str, into_row: usize, into_col: usize) -> Result<visigrid_engine::sheet::Sheet, CliError> {
+/// Put the source spelling of `cols` back into every row below `header_row`
+/// as text cells, undoing number detection (`00123` stays `00123`). The sheet
+/// must have been loaded from `content`, so records map one-to-one onto rows.
+fn restore_text_columns(
+    sheet: &mut visigrid_engine::sheet::Sheet,
+    content: &str,
+    delimiter: u8,
+    cols: &[usize],
+    header_row: usize,
+) -> Result<(), CliError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(visigrid_io::csv::strip_bom(content).as_bytes());
+
+    for (row_idx, result) in reader.records().enumerate().skip(header_row + 1) {
+        let record = result.map_err(|e| CliError::parse(format!("line {}: {}", row_idx + 1, e)))?;
+        for &col in cols {
+            if let Some(field) = record.get(col).filter(|f| !f.is_empty()) {
+                sheet.set_text(row_idx, col, field);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Re-read every field of `content` in `locale` notation and store the ones
+/// that are numbers there as numbers (`1.234,56` → 1234.56 under `de`).
+/// Other fields keep whatever the parser made of them. The sheet must have
+/// been loaded from `content` at (`into_row`, `into_col`).
+fn apply_number_locale(
+    sheet: &mut visigrid_engine::sheet::Sheet,
+    content: &str,
+    delimiter: u8,
+    into_row: usize,
+    into_col: usize,
+    locale: NumberLocale,
+) -> Result<(), CliError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(visigrid_io::csv::strip_bom(content).as_bytes());
+
+    for (row_idx, result) in reader.records().enumerate() {
+        let record = result.map_err(|e| CliError::parse(format!("line {}: {}", row_idx + 1, e)))?;
+        for (col_idx, field) in record.iter().enumerate() {
+            if let Some(canonical) = locale.canonicalize(field).filter(|c| c != field) {
+                sheet.set_value(into_row + row_idx, into_col + col_idx, &canonical);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Copy of `sheet` with numeric values and formula results written as
+/// `locale` text, for delimited output. `text_cols` are left as they are.
+fn localize_numbers(
+    sheet: &visigrid_engine::sheet::Sheet,
+    locale: NumberLocale,
+    text_cols: &[usize],
+) -> visigrid_engine::sheet::Sheet {
+    use visigrid_engine::cell::CellValue;
+
+    let localized: Vec<(usize, usize, String)> = sheet.cells_iter()
+        .filter(|((_, col), cell)| {
+            !text_cols.contains(col) && matches!(cell.value, CellValue::Number(_) | CellValue::Formula { .. })
+        })
+        .filter_map(|(&(row, col), _)| {
+            let display = sheet.get_display(row, col);
+            match locale.format(&display) {
+                std::borrow::Cow::Owned(text) => Some((row, col, text)),
+                std::borrow::Cow::Borrowed(_) => None,
+            }
+        })
+        .collect();
+
+    let mut out = sheet.clone();
+    for (row, col, text) in localized {
+        out.set_text(row, col, &text);
+    }
+    out
+}
+
+fn parse_json(content: &str, into_row: usize, into_col: usize, strict_numbers: bool) -> Result<visigrid_engine::sheet::Sheet, CliError> {
     use visigrid_engine::sheet::{Sheet, SheetId};
 
     let value: serde_json::Value = serde_json::from_str(content)
@@ -2512,35 +3544,9 @@ fn parse_json(content: &str, into_row: usize, into_col: usize) -> Result<visigri
 
             // Check if array of arrays or array of objects
             if let Some(serde_json::Value::Object(_)) = rows.first() {
-                // Array of objects - collect all keys lexicographically
-                let mut all_keys: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
-                for row in &rows {
-                    if let serde_json::Value::Object(obj) = row {
-                        for key in obj.keys() {
-                            all_keys.insert(key.clone());
-                        }
-                    }
-                }
-                let keys: Vec<String> = all_keys.into_iter().collect();
-
-                // Write header row
-                for (col, key) in keys.iter().enumerate() {
-                    sheet.set_value(into_row, into_col + col, key);
-                }
-
-                // Write data rows
-                for (row_idx, row) in rows.iter().enumerate() {
-                    if let serde_json::Value::Object(obj) = row {
-                        for (col, key) in keys.iter().enumerate() {
-                            if let Some(val) = obj.get(key) {
-                                let cell_value = json_value_to_string(val, row_idx + 1, key)?;
-                                if !cell_value.is_empty() {
-                                    sheet.set_value(into_row + row_idx + 1, into_col + col, &cell_value);
-                                }
-                            }
-                        }
-                    }
-                }
+                // Array of objects - non-object rows are left blank
+                let objects: Vec<_> = rows.iter().map(|row| row.as_object()).collect();
+                fill_json_objects(&mut sheet, &objects, into_row, into_col, strict_numbers)?;
             } else {
                 // Array of arrays
                 for (row_idx, row) in rows.iter().enumerate() {
@@ -2548,7 +3554,7 @@ fn parse_json(content: &str, into_row: usize, into_col: usize) -> Result<visigri
                         for (col_idx, val) in cols.iter().enumerate() {
                             let cell_value = json_value_to_string(val, row_idx, &col_idx.to_string())?;
                             if !cell_value.is_empty() {
-                                sheet.set_value(into_row + row_idx, into_col + col_idx, &cell_value);
+                                set_loaded_field(&mut sheet, into_row + row_idx, into_col + col_idx, &cell_value, strict_numbers && val.is_string());
                             }
                         }
                     } else {
@@ -2563,6 +3569,74 @@ fn parse_json(content: &str, into_row: usize, into_col: usize) -> Result<visigri
     Ok(sheet)
 }
 
+/// Write objects as a header row of lexicographically sorted keys (the union
+/// across all objects) followed by one data row per object.
+fn fill_json_objects(
+    sheet: &mut visigrid_engine::sheet::Sheet,
+    objects: &[Option<&serde_json::Map<String, serde_json::Value>>],
+    into_row: usize,
+    into_col: usize,
+    strict_numbers: bool,
+) -> Result<(), CliError> {
+    let keys: Vec<&String> = objects.iter()
+        .flatten()
+        .flat_map(|obj| obj.keys())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    // Write header row
+    for (col, key) in keys.iter().enumerate() {
+        sheet.set_value(into_row, into_col + col, key);
+    }
+
+    // Write data rows
+    for (row_idx, obj) in objects.iter().enumerate() {
+        let Some(obj) = obj else { continue };
+        for (col, key) in keys.iter().enumerate() {
+            if let Some(val) = obj.get(*key) {
+                let cell_value = json_value_to_string(val, row_idx + 1, key)?;
+                if !cell_value.is_empty() {
+                    set_loaded_field(sheet, into_row + row_idx + 1, into_col + col, &cell_value, strict_numbers && val.is_string());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse newline-delimited JSON: one object per line, blank lines ignored.
+fn parse_ndjson(content: &str, into_row: usize, into_col: usize, strict_numbers: bool) -> Result<visigrid_engine::sheet::Sheet, CliError> {
+    use visigrid_engine::sheet::{Sheet, SheetId};
+
+    let mut records = Vec::new();
+    for (line_idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(line).map_err(|e| {
+            // serde_json reports positions within the single line; report the stream line instead
+            let msg = e.to_string();
+            let msg = msg.rsplit_once(" at line ").map_or(msg.as_str(), |(m, _)| m);
+            CliError::parse(format!("line {}, column {}: JSON parse error: {}", line_idx + 1, e.column(), msg))
+        })?;
+        match value {
+            serde_json::Value::Object(obj) => records.push(obj),
+            _ => return Err(CliError::parse(format!("line {}: expected a JSON object", line_idx + 1))),
+        }
+    }
+    if records.is_empty() {
+        return Err(CliError::parse("empty input"));
+    }
+
+    let mut sheet = Sheet::new(SheetId(1), 1000, 26);
+    let objects: Vec<_> = records.iter().map(Some).collect();
+    fill_json_objects(&mut sheet, &objects, into_row, into_col, strict_numbers)?;
+    Ok(sheet)
+}
+
 fn json_value_to_string(val: &serde_json::Value, row: usize, key: &str) -> Result<String, CliError> {
     match val {
         serde_json::Value::Null => Ok(String::new()),
@@ -2575,7 +3649,7 @@ fn json_value_to_string(val: &serde_json::Value, row: usize, key: &str) -> Resul
     }
 }
 
-fn parse_lines(content: &str, into_row: usize, into_col: usize) -> Result<visigrid_engine::sheet::Sheet, CliError> {
+fn parse_lines(content: &str, into_row: usize, into_col: usize, strict_numbers: bool) -> Result<visigrid_engine::sheet::Sheet, CliError> {
     use visigrid_engine::sheet::{Sheet, SheetId};
 
     let lines: Vec<&str> = content.lines().collect();
@@ -2586,13 +3660,34 @@ fn parse_lines(content: &str, into_row: usize, into_col: usize) -> Result<visigr
     let mut sheet = Sheet::new(SheetId(1), 1000, 26);
     for (row, line) in lines.iter().enumerate() {
         if !line.is_empty() {
-            sheet.set_value(into_row + row, into_col, line);
+            set_loaded_field(&mut sheet, into_row + row, into_col, line, strict_numbers);
         }
     }
 
     Ok(sheet)
 }
 
+/// One row per non-blank line, one cell per layout column with its padding
+/// trimmed. `--colspec` names go in a header row above the data.
+fn parse_fixed(content: &str, layout: &FixedLayout, strict_numbers: bool) -> visigrid_engine::sheet::Sheet {
+    use visigrid_engine::sheet::{Sheet, SheetId};
+
+    let content = visigrid_io::csv::strip_bom(content);
+    let rows = layout.names().into_iter().chain(layout.rows(content));
+
+    let mut sheet = Sheet::new(SheetId(1), 1000, 26);
+    for (row_idx, fields) in rows.enumerate() {
+        for (col_idx, field) in fields.iter().enumerate() {
+            if !field.is_empty() {
+                set_loaded_field(&mut sheet, row_idx, col_idx, field, strict_numbers);
+            }
+        }
+        sheet.rows = sheet.rows.max(row_idx + 1);
+        sheet.cols = sheet.cols.max(fields.len());
+    }
+    sheet
+}
+
 fn write_format(
     sheet: &visigrid_engine::sheet::Sheet,
     format: Format,
@@ -2601,12 +3696,18 @@ fn write_format(
     header_row: usize,
     row_filter: Option<&[usize]>,
     col_filter: Option<&[(usize, String)]>,
+    text_cols: &[usize],
+    strict_numbers: bool,
 ) -> Result<Vec<u8>, CliError> {
     match format {
         Format::Csv => write_csv(sheet, delimiter as u8, headers, header_row, row_filter, col_filter),
         Format::Tsv => write_csv(sheet, b'\t', headers, header_row, row_filter, col_filter),
-        Format::Json => write_json(sheet, headers, header_row, row_filter, col_filter),
-        Format::Lines => write_lines(sheet, header_row, row_filter, col_filter),
+        Format::Json => write_json(sheet, headers, header_row, row_filter, col_filter, text_cols, strict_numbers),
+        Format::Lines => write_lines(sheet, headers, header_row, row_filter, col_filter),
+        Format::JsonlValues => write_jsonl_values(sheet, headers, header_row, row_filter, col_filter, text_cols, strict_numbers),
+        Format::Ndjson => Err(ndjson_output_error()),
+        Format::Fixed => Err(CliError::args("fixed is an input-only format")
+            .with_hint("use -t csv or -t tsv for delimited text")),
         Format::Xlsx => Err(CliError::format("xlsx export not yet implemented")
             .with_hint("use -t csv or -t json instead")),
         Format::Sheet => Err(CliError::format("sheet format cannot be written to stdout")
@@ -2627,9 +3728,16 @@ fn write_csv(
         .from_writer(Vec::new());
 
     let (rows, cols) = get_data_bounds(sheet);
+    // Sparse sheets: emit empty rows without looking up each cell
+    let occupied = sheet_ops::sparse_rows(sheet, rows, cols);
 
     // Helper: push columns for a given row into the record
     let push_row = |record: &mut Vec<String>, row: usize| {
+        if occupied.as_ref().is_some_and(|occ| !occ.contains(&row)) {
+            let width = col_filter.map_or(cols, |selected| selected.len());
+            record.resize(width, String::new());
+            return;
+        }
         match col_filter {
             Some(selected) => {
                 for (idx, _) in selected {
@@ -2646,8 +3754,8 @@ fn write_csv(
 
     match row_filter {
         Some(indices) => {
-            // Write header row + filtered data rows
-            if rows > 0 {
+            // Write header row (if any) + filtered data rows
+            if headers && rows > 0 {
                 let mut record: Vec<String> = Vec::new();
                 push_row(&mut record, header_row);
                 writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
@@ -2690,8 +3798,11 @@ fn write_json(
     header_row: usize,
     row_filter: Option<&[usize]>,
     col_filter: Option<&[(usize, String)]>,
+    text_cols: &[usize],
+    strict_numbers: bool,
 ) -> Result<Vec<u8>, CliError> {
     let (rows, cols) = get_data_bounds(sheet);
+    let cell_json = |row: usize, col: usize| cell_to_json_value(sheet, row, col, text_cols, strict_numbers);
 
     if headers && rows > 0 {
         let data_rows: Vec<usize> = match row_filter {
@@ -2719,8 +3830,7 @@ fn write_json(
             for row in data_rows {
                 let mut pairs = Vec::new();
                 for (col_idx, key) in &json_keys {
-                    let value = sheet.get_display(row, *col_idx);
-                    pairs.push((key.clone(), string_to_json_value(&value)));
+                    pairs.push((key.clone(), cell_json(row, *col_idx)));
                 }
                 rows_json.push(pairs);
             }
@@ -2770,8 +3880,7 @@ fn write_json(
             for row in data_rows {
                 let mut obj = serde_json::Map::new();
                 for (col, key) in header_names.iter().enumerate() {
-                    let value = sheet.get_display(row, col);
-                    obj.insert(key.clone(), string_to_json_value(&value));
+                    obj.insert(key.clone(), cell_json(row, col));
                 }
                 objects.push(obj);
             }
@@ -2784,18 +3893,13 @@ fn write_json(
         // Array of arrays (no col_filter since --select requires --headers)
         let mut rows_vec: Vec<Vec<serde_json::Value>> = Vec::new();
         let all_rows: Vec<usize> = match row_filter {
-            Some(indices) => {
-                let mut v = vec![header_row];
-                v.extend_from_slice(indices);
-                v
-            }
+            Some(indices) => indices.to_vec(),
             None => (0..rows).collect(),
         };
         for row in all_rows {
             let mut row_vec: Vec<serde_json::Value> = Vec::new();
             for col in 0..cols {
-                let value = sheet.get_display(row, col);
-                row_vec.push(string_to_json_value(&value));
+                row_vec.push(cell_json(row, col));
             }
             rows_vec.push(row_vec);
         }
@@ -2806,6 +3910,83 @@ fn write_json(
     }
 }
 
+/// JSON value of a cell's display string; `text_cols` are always strings.
+fn cell_to_json_value(sheet: &visigrid_engine::sheet::Sheet, row: usize, col: usize, text_cols: &[usize], strict_numbers: bool) -> serde_json::Value {
+    let display = sheet.get_display(row, col);
+    if text_cols.contains(&col) {
+        serde_json::Value::String(display)
+    } else if strict_numbers {
+        strict_string_to_json_value(&display)
+    } else {
+        string_to_json_value(&display)
+    }
+}
+
+/// Like `string_to_json_value`, but only canonical decimals become numbers
+/// (see `is_canonical_number`); `1e3`, `007` and `0x10` stay strings.
+fn strict_string_to_json_value(s: &str) -> serde_json::Value {
+    if s.parse::<f64>().is_ok() && !is_canonical_number(s) {
+        serde_json::Value::String(s.to_string())
+    } else {
+        string_to_json_value(s)
+    }
+}
+
+/// True for plain decimal notation: an optional `-`, an integer part without
+/// leading zeros, and an optional fraction (`0`, `42`, `-3.5`, `0.25`).
+/// Exponents, a leading `+`, hex, and padded integers like `007` are not.
+fn is_canonical_number(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let (int_part, frac_part) = match digits.split_once('.') {
+        Some((int_part, frac)) => (int_part, Some(frac)),
+        None => (digits, None),
+    };
+    let all_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    all_digits(int_part)
+        && (int_part == "0" || !int_part.starts_with('0'))
+        && frac_part.is_none_or(all_digits)
+}
+
+/// Put back, as text, every csv/tsv field that the loader read as a number
+/// but that is not written in canonical decimal notation (`1e3`, `007`).
+/// The sheet must have been loaded from `content` at A1.
+fn restore_non_canonical_numbers(
+    sheet: &mut visigrid_engine::sheet::Sheet,
+    content: &str,
+    delimiter: u8,
+) -> Result<(), CliError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(visigrid_io::csv::strip_bom(content).as_bytes());
+
+    for (row_idx, result) in reader.records().enumerate() {
+        let record = result.map_err(|e| CliError::parse(format!("line {}: {}", row_idx + 1, e)))?;
+        for (col_idx, field) in record.iter().enumerate() {
+            let is_number = matches!(
+                sheet.get_cell(row_idx, col_idx).value,
+                visigrid_engine::cell::CellValue::Number(_)
+            );
+            if is_number && !is_canonical_number(field) {
+                sheet.set_text(row_idx, col_idx, field);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `set_value` for one field of text input. With `strict_numbers`, a field
+/// that loads as a number but is not written canonically stays text, as
+/// `restore_non_canonical_numbers` does for csv/tsv.
+fn set_loaded_field(sheet: &mut visigrid_engine::sheet::Sheet, row: usize, col: usize, field: &str, strict_numbers: bool) {
+    sheet.set_value(row, col, field);
+    let is_number = matches!(sheet.get_cell(row, col).value, visigrid_engine::cell::CellValue::Number(_));
+    if strict_numbers && is_number && !is_canonical_number(field) {
+        sheet.set_text(row, col, field);
+    }
+}
+
 /// Convert a display string to a typed JSON value
 /// Numbers become JSON numbers, booleans become JSON booleans, rest are strings
 fn string_to_json_value(s: &str) -> serde_json::Value {
@@ -2832,6 +4013,7 @@ fn string_to_json_value(s: &str) -> serde_json::Value {
 
 fn write_lines(
     sheet: &visigrid_engine::sheet::Sheet,
+    headers: bool,
     header_row: usize,
     row_filter: Option<&[usize]>,
     col_filter: Option<&[(usize, String)]>,
@@ -2846,11 +4028,12 @@ fn write_lines(
     };
 
     let all_rows: Vec<usize> = match row_filter {
-        Some(indices) => {
+        Some(indices) if headers => {
             let mut v = vec![header_row];
             v.extend_from_slice(indices);
             v
         }
+        Some(indices) => indices.to_vec(),
         None => (0..rows).collect(),
     };
 
@@ -2863,6 +4046,40 @@ fn write_lines(
     Ok(output)
 }
 
+/// Like `write_lines`, but each cell becomes a typed JSON scalar and the
+/// header row (with --headers) is skipped, so the stream is data only.
+fn write_jsonl_values(
+    sheet: &visigrid_engine::sheet::Sheet,
+    headers: bool,
+    header_row: usize,
+    row_filter: Option<&[usize]>,
+    col_filter: Option<&[(usize, String)]>,
+    text_cols: &[usize],
+    strict_numbers: bool,
+) -> Result<Vec<u8>, CliError> {
+    let mut output = Vec::new();
+    let (rows, _) = get_data_bounds(sheet);
+
+    let output_col = match col_filter {
+        Some(selected) => selected[0].0,
+        None => 0,
+    };
+
+    let data_rows: Vec<usize> = match row_filter {
+        Some(indices) => indices.to_vec(),
+        None if headers => ((header_row + 1)..rows).collect(),
+        None => (0..rows).collect(),
+    };
+
+    for row in data_rows {
+        let value = cell_to_json_value(sheet, row, output_col, text_cols, strict_numbers);
+        serde_json::to_writer(&mut output, &value).map_err(|e| CliError::io(e.to_string()))?;
+        output.push(b'\n');
+    }
+
+    Ok(output)
+}
+
 fn get_data_bounds(sheet: &visigrid_engine::sheet::Sheet) -> (usize, usize) {
     sheet_ops::get_data_bounds(sheet)
 }
@@ -2878,39 +4095,64 @@ fn cmd_calc(
     delimiter: char,
     headers: bool,
     spill: Option<SpillFormat>,
+    spill_out: Option<PathBuf>,
     json: bool,
+    strict_numbers: bool,
+    policy: sheet_ops::CalcPolicy,
+    round_mode: visigrid_engine::formula::rounding::RoundMode,
+    locale: NumberLocale,
 ) -> Result<(), CliError> {
+    // xlsx is binary: it only goes to a file
+    if matches!(spill, Some(SpillFormat::Xlsx)) && spill_out.is_none() {
+        return Err(CliError::args("--spill xlsx cannot write to stdout")
+            .with_hint("add --spill-out result.xlsx"));
+    }
+
     // Parse --into cell reference
     let (into_row, into_col) = parse_cell_ref(&into)
         .ok_or_else(|| CliError::args(format!("invalid cell reference: {}", into)))?;
 
-    // Read stdin with offset
-    let mut sheet = read_stdin(from, delimiter, into_row, into_col)?;
-
-    // Get data bounds (relative to where we loaded)
-    let (data_rows, data_cols) = get_data_bounds(&sheet);
-
-    // If headers, the actual data starts one row after into_row
-    // Column refs like A:A should expand to A<start>:A<end> excluding header
-    let data_start_row = if headers { into_row + 2 } else { into_row + 1 }; // 1-indexed for formula
+    let mut stdin = io::stdin().lock();
+    if stdin.fill_buf().map_err(|e| CliError::io(e.to_string()))?.is_empty() {
+        return Err(empty_stdin_error());
+    }
 
-    // Translate column references like A:A to explicit ranges
-    let formula_str = if formula.starts_with('=') {
-        translate_column_refs(&formula, data_start_row, data_rows)
-    } else {
-        translate_column_refs(&format!("={}", formula), data_start_row, data_rows)
+    // Fast path: a lone SUM/COUNT/AVERAGE/MIN/MAX over one column streams
+    // straight off stdin instead of materializing a sheet
+    let stream_delimiter = match from {
+        Format::Csv => Some(delimiter as u8),
+        Format::Tsv => Some(b'\t'),
+        _ => None,
     };
+    let mut consumed = Vec::new();
+    if let Some(delim) = stream_delimiter.filter(|_| locale == NumberLocale::En) {
+        match sheet_ops::stream_aggregate(&mut stdin, delim, &formula, into_col, headers)? {
+            sheet_ops::StreamedAggregate::Value(n) => {
+                let result = visigrid_engine::sheet::number_display(n);
+                print_calc_scalar(&result, json, strict_numbers, locale);
+                return Ok(());
+            }
+            sheet_ops::StreamedAggregate::Fallback(seen) => consumed = seen,
+        }
+    }
+    stdin.read_to_end(&mut consumed).map_err(|e| CliError::io(e.to_string()))?;
+    let input = String::from_utf8(consumed)
+        .map_err(|_| CliError::io("stream did not contain valid UTF-8"))?;
 
-    // Put the formula in a cell outside the data area
-    let formula_row = data_rows;
-    let formula_col = data_cols;
-    sheet.set_value(formula_row, formula_col, &formula_str);
+    let mut sheet = parse_input(&input, from, delimiter, into_row, into_col)?;
+    sheet.set_round_mode(round_mode);
+    if let Some(delim) = stream_delimiter.filter(|_| locale != NumberLocale::En) {
+        apply_number_locale(&mut sheet, &input, delim, into_row, into_col, locale)?;
+    }
+    let (formula_row, formula_col) = sheet_ops::place_calc_formula(&mut sheet, &formula, into_row, into_col, headers)?;
 
     // Get the result
     let result = sheet.get_display(formula_row, formula_col);
 
-    // Check for error tokens
-    if result.starts_with('#') {
+    // Check for error tokens. A formula that doesn't parse always fails;
+    // --fail-on-error only governs errors from evaluation
+    let parse_failed = sheet.get_cell(formula_row, formula_col).value.formula_ast().is_none();
+    if result.starts_with('#') && (policy.fail_on_error || parse_failed) {
         // Formula error - print to stdout, diagnostic to stderr
         println!("{}", result);
         let hint = match result.as_str() {
@@ -2935,33 +4177,56 @@ fn cmd_calc(
             match spill {
                 None => {
                     return Err(CliError::eval(format!(
-                        "result is {}x{} array, use --spill csv or --spill json",
+                        "result is {}x{} array, use --spill csv, tsv, json, or xlsx",
                         spill_rows, spill_cols
                     )));
                 }
-                Some(SpillFormat::Csv) => {
-                    let csv_output = format_spill_csv(&sheet, formula_row, formula_col, spill_rows, spill_cols);
-                    print!("{}", csv_output);
+                Some(SpillFormat::Xlsx) => {
+                    let path = spill_out.as_deref().expect("checked above");
+                    write_spill_xlsx(&sheet, formula_row, formula_col, spill_rows, spill_cols, path)?;
                 }
-                Some(SpillFormat::Json) => {
-                    let json_output = format_spill_json(&sheet, formula_row, formula_col, spill_rows, spill_cols);
-                    println!("{}", json_output);
+                Some(format) => {
+                    let output = match format {
+                        SpillFormat::Csv => format_spill_csv(&sheet, formula_row, formula_col, spill_rows, spill_cols, locale),
+                        SpillFormat::Tsv => format_spill_tsv(&sheet, formula_row, formula_col, spill_rows, spill_cols, locale),
+                        _ => format_spill_json(&sheet, formula_row, formula_col, spill_rows, spill_cols, strict_numbers) + "\n",
+                    };
+                    match spill_out {
+                        Some(path) => std::fs::write(&path, output).map_err(|e| {
+                            CliError::io(format!("failed to write {}: {}", path.display(), e))
+                        })?,
+                        None => print!("{}", output),
+                    }
                 }
             }
             return Ok(());
         }
     }
 
+    if result.is_empty() && policy.fail_on_empty {
+        println!();
+        return Err(CliError::eval("formula returned an empty value")
+            .with_hint("check that the referenced cells contain data (--fail-on-empty is set)"));
+    }
+
     // Scalar result (or 1x1 array, which is treated as scalar)
+    print_calc_scalar(&result, json, strict_numbers, locale);
+
+    Ok(())
+}
+
+fn print_calc_scalar(result: &str, json: bool, strict_numbers: bool, locale: NumberLocale) {
     if json {
         // Machine mode: output JSON scalar value
-        let json_val = string_to_json_value(&result);
+        let json_val = if strict_numbers {
+            strict_string_to_json_value(result)
+        } else {
+            string_to_json_value(result)
+        };
         println!("{}", json_val);
     } else {
-        println!("{}", format_output_value(&result));
+        println!("{}", locale.format(&format_output_value(result)));
     }
-
-    Ok(())
 }
 
 fn parse_cell_ref(s: &str) -> Option<(usize, usize)> {
@@ -3074,14 +4339,39 @@ fn format_spill_csv(
     start_col: usize,
     rows: usize,
     cols: usize,
+    locale: NumberLocale,
+) -> String {
+    format_spill_delimited(sheet, start_row, start_col, rows, cols, ',', locale)
+}
+
+fn format_spill_tsv(
+    sheet: &visigrid_engine::sheet::Sheet,
+    start_row: usize,
+    start_col: usize,
+    rows: usize,
+    cols: usize,
+    locale: NumberLocale,
+) -> String {
+    format_spill_delimited(sheet, start_row, start_col, rows, cols, '\t', locale)
+}
+
+fn format_spill_delimited(
+    sheet: &visigrid_engine::sheet::Sheet,
+    start_row: usize,
+    start_col: usize,
+    rows: usize,
+    cols: usize,
+    delimiter: char,
+    locale: NumberLocale,
 ) -> String {
     let mut output = String::new();
 
     for r in 0..rows {
         for c in 0..cols {
-            let val = sheet.get_display(start_row + r, start_col + c);
+            let display = sheet.get_display(start_row + r, start_col + c);
+            let val = locale.format(&display);
             // RFC 4180 quoting
-            let needs_quote = val.contains(',') || val.contains('"') || val.contains('\n');
+            let needs_quote = val.contains(delimiter) || val.contains('"') || val.contains('\n');
             if needs_quote {
                 output.push('"');
                 output.push_str(&val.replace('"', "\"\""));
@@ -3090,7 +4380,7 @@ fn format_spill_csv(
                 output.push_str(&val);
             }
             if c < cols - 1 {
-                output.push(',');
+                output.push(delimiter);
             }
         }
         output.push('\n');
@@ -3105,6 +4395,7 @@ fn format_spill_json(
     start_col: usize,
     rows: usize,
     cols: usize,
+    strict_numbers: bool,
 ) -> String {
     let mut result: Vec<Vec<serde_json::Value>> = Vec::new();
 
@@ -3113,7 +4404,9 @@ fn format_spill_json(
         for c in 0..cols {
             let val = sheet.get_display(start_row + r, start_col + c);
             // Try to parse as number, otherwise string
-            if let Ok(n) = val.parse::<f64>() {
+            if strict_numbers && !is_canonical_number(&val) && val.parse::<f64>().is_ok() {
+                row_vec.push(serde_json::json!(val));
+            } else if let Ok(n) = val.parse::<f64>() {
                 row_vec.push(serde_json::json!(n));
             } else if val == "TRUE" {
                 row_vec.push(serde_json::json!(true));
@@ -3129,6 +4422,29 @@ fn format_spill_json(
     serde_json::to_string_pretty(&result).unwrap_or_else(|_| "[]".to_string())
 }
 
+/// Write the spilled values (not formulas) to a one-sheet workbook at `path`.
+fn write_spill_xlsx(
+    sheet: &visigrid_engine::sheet::Sheet,
+    start_row: usize,
+    start_col: usize,
+    rows: usize,
+    cols: usize,
+    path: &std::path::Path,
+) -> Result<(), CliError> {
+    use visigrid_engine::sheet::{Sheet, SheetId};
+
+    let mut out = Sheet::new_with_name(SheetId(1), rows, cols, "Sheet1");
+    for r in 0..rows {
+        for c in 0..cols {
+            out.set_value(r, c, &sheet.get_display(start_row + r, start_col + c));
+        }
+    }
+    let workbook = visigrid_engine::workbook::Workbook::from_sheets(vec![out], 0);
+    visigrid_io::xlsx::export(&workbook, path, None)
+        .map(|_| ())
+        .map_err(|e| CliError::io(format!("failed to write {}: {}", path.display(), e)))
+}
+
 // ============================================================================
 // open
 // ============================================================================
@@ -3185,7 +4501,10 @@ fn cmd_diff(
     match_mode: DiffMatchMode,
     key_transform: DiffKeyTransform,
     compare: Option<String>,
+    ignore: Option<String>,
     tolerance: f64,
+    empty_as_zero: bool,
+    locale: NumberLocale,
     on_ambiguous: DiffAmbiguousPolicy,
     out: DiffOutputFormat,
     output: Option<PathBuf>,
@@ -3199,8 +4518,12 @@ fn cmd_diff(
     save_ambiguous: Option<PathBuf>,
     contains_column: Option<String>,
     no_fail: bool,
+    baseline: bool,
     export_specs_raw: Vec<String>,
     export_side: ExportSide,
+    stream: bool,
+    summary_only: bool,
+    progress: bool,
 ) -> Result<(), CliError> {
     let left_is_stdin = left_arg == "-";
     let right_is_stdin = right_arg == "-";
@@ -3210,9 +4533,39 @@ fn cmd_diff(
             .with_hint("provide at least one file path: visigrid diff - file.csv --key id"));
     }
 
+    if summary_only && !matches!(out, DiffOutputFormat::Json) {
+        return Err(CliError::args("--summary-only requires --out json")
+            .with_hint("only the JSON output carries the summary; drop --out"));
+    }
+
+    // --key auto: the key column is chosen once both sides are loaded
+    let key_auto = key.iter().any(|k| k.eq_ignore_ascii_case("auto"));
+    if key_auto && key.len() > 1 {
+        return Err(CliError::args("--key auto cannot be combined with other --key columns"));
+    }
+    if key_auto && stream {
+        return Err(CliError::args("--key auto requires loading both files")
+            .with_hint("name the key column with --key when using --stream"));
+    }
+    if stream && progress {
+        return Err(CliError::args("--progress cannot be combined with --stream")
+            .with_hint("drop --stream to show progress, or drop --progress"));
+    }
+    if stream && save_ambiguous.is_some() {
+        return Err(CliError::args("--save-ambiguous cannot be combined with --stream")
+            .with_hint("ambiguous matches are collected only when both files are loaded; drop --stream"));
+    }
+
+    let show_progress = progress && !quiet && atty::is(atty::Stream::Stderr);
+
     // Parse export specs early so invalid specs fail fast
     let export_specs = parse_export_specs(&export_specs_raw)?;
 
+    // --summary-only: keep only the rows some --export still needs
+    let keep_row = |status: diff::RowStatus| {
+        !summary_only || export_specs.iter().any(|(s, _)| *s == status)
+    };
+
     // Resolve formats
     let left_path = if left_is_stdin { None } else { Some(PathBuf::from(&left_arg)) };
     let right_path = if right_is_stdin { None } else { Some(PathBuf::from(&right_arg)) };
@@ -3228,90 +4581,571 @@ fn cmd_diff(
             .with_hint("use --stdin-format to specify the format for stdin input"))
     };
 
-    // Load both sides
-    let (left_sheet, left_label) = if left_is_stdin {
-        let fmt = resolve_stdin_format(&right_path)?;
-        (read_stdin(fmt, delimiter, 0, 0)?, "stdin".to_string())
+    // Determine header row (0-indexed internally)
+    let hdr_row = if no_headers {
+        None
     } else {
-        let p = left_path.as_ref().unwrap();
-        let fmt = infer_format(p)?;
-        let label = p.display().to_string();
-        (read_file(p, fmt, delimiter, None)?, label)
+        Some(header_row.map(|h| h.saturating_sub(1)).unwrap_or(0))
     };
 
-    let (right_sheet, right_label) = if right_is_stdin {
-        let fmt = resolve_stdin_format(&left_path)?;
-        (read_stdin(fmt, delimiter, 0, 0)?, "stdin".to_string())
-    } else {
-        let p = right_path.as_ref().unwrap();
-        let fmt = infer_format(p)?;
-        let label = p.display().to_string();
-        (read_file(p, fmt, delimiter, None)?, label)
+    // Resolve key/compare columns and policies once headers are known
+    let key_specs: &[String] = if key_auto { &[] } else { &key };
+    let build_options = |headers: &[String], left_headers: &[String], right_headers: &[String]| {
+        build_diff_options(
+            key_specs, compare.as_deref(), ignore.as_deref(), contains_column.as_deref(), match_mode, key_transform,
+            on_ambiguous, tolerance, empty_as_zero, locale, headers, left_headers, right_headers, hdr_row.is_some(),
+        )
     };
 
-    let (left_bounds_rows, left_bounds_cols) = get_data_bounds(&left_sheet);
-    let (right_bounds_rows, right_bounds_cols) = get_data_bounds(&right_sheet);
-
-    if left_bounds_rows == 0 {
-        return Err(CliError { code: EXIT_DIFF_PARSE, message: format!("{}: empty or has no data rows", left_label), hint: None });
-    }
-    if right_bounds_rows == 0 {
-        return Err(CliError { code: EXIT_DIFF_PARSE, message: format!("{}: empty or has no data rows", right_label), hint: None });
-    }
+    // Build invocation string + structured args for JSON provenance
+    let invocation = {
+        let mut parts = vec![
+            "vgrid".to_string(),
+            "diff".to_string(),
+            shell_quote(&left_arg),
+            shell_quote(&right_arg),
+        ];
+        for k in &key {
+            parts.push("--key".to_string());
+            parts.push(shell_quote(k));
+        }
+        if match_mode != DiffMatchMode::Exact {
+            parts.push("--match".to_string());
+            parts.push(format!("{}", match_mode));
+        }
+        if key_transform != DiffKeyTransform::Trim {
+            parts.push("--key-transform".to_string());
+            parts.push(format!("{}", key_transform));
+        }
+        if let Some(ref cmp) = compare {
+            parts.push("--compare".to_string());
+            parts.push(shell_quote(cmp));
+        }
+        if let Some(ref ign) = ignore {
+            parts.push("--ignore".to_string());
+            parts.push(shell_quote(ign));
+        }
+        if tolerance != 0.0 {
+            parts.push("--tolerance".to_string());
+            parts.push(format!("{}", tolerance));
+        }
+        if empty_as_zero {
+            parts.push("--empty-as-zero".to_string());
+        }
+        if locale != NumberLocale::En {
+            parts.push("--locale".to_string());
+            parts.push(format!("{}", locale));
+        }
+        if matches!(on_ambiguous, DiffAmbiguousPolicy::Report) {
+            parts.push("--on-ambiguous".to_string());
+            parts.push("report".to_string());
+        }
+        if let Some(ref path) = output {
+            parts.push("--output".to_string());
+            parts.push(shell_quote(&path.display().to_string()));
+        }
+        if no_headers {
+            parts.push("--no-headers".to_string());
+        }
+        if let Some(hr) = header_row {
+            parts.push("--header-row".to_string());
+            parts.push(format!("{}", hr));
+        }
+        if delimiter != ',' {
+            parts.push("--delimiter".to_string());
+            parts.push(shell_quote(&delimiter.to_string()));
+        }
+        if let Some(name) = stdin_format.and_then(|fmt| fmt.to_possible_value()) {
+            parts.push("--stdin-format".to_string());
+            parts.push(name.get_name().to_string());
+        }
+        if strict_exit {
+            parts.push("--strict-exit".to_string());
+        }
+        if let Some(ref path) = save_ambiguous {
+            parts.push("--save-ambiguous".to_string());
+            parts.push(shell_quote(&path.display().to_string()));
+        }
+        if let Some(ref cc) = contains_column {
+            parts.push("--contains-column".to_string());
+            parts.push(shell_quote(cc));
+        }
+        if no_fail {
+            parts.push("--no-fail".to_string());
+        }
+        if baseline {
+            parts.push("--baseline".to_string());
+        }
+        for spec in &export_specs_raw {
+            parts.push("--export".to_string());
+            parts.push(shell_quote(spec));
+        }
+        if !export_specs_raw.is_empty() && !matches!(export_side, ExportSide::Left) {
+            parts.push("--export-side".to_string());
+            parts.push(export_side.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default());
+        }
+        if stream {
+            parts.push("--stream".to_string());
+        }
+        if progress {
+            parts.push("--progress".to_string());
+        }
+        if summary_only {
+            parts.push("--summary-only".to_string());
+        }
+        parts.join(" ")
+    };
 
-    // Determine header row (0-indexed internally)
-    let hdr_row = if no_headers {
-        None
-    } else {
-        Some(header_row.map(|h| h.saturating_sub(1)).unwrap_or(0))
+    let invocation_args = serde_json::json!({
+        "left": left_arg,
+        "right": right_arg,
+        "key": key,
+        "output": output.as_ref().map(|p| p.display().to_string()),
+        "tolerance": tolerance,
+        "match": format!("{}", match_mode),
+        "key_transform": format!("{}", key_transform),
+    });
+
+    if stream {
+        if match_mode != DiffMatchMode::Exact {
+            return Err(CliError::args("--stream requires --match exact")
+                .with_hint("substring matching scans every right row per left row; drop --stream"));
+        }
+        let left_fmt = match left_path {
+            Some(ref p) => infer_format(p)?,
+            None => resolve_stdin_format(&right_path)?,
+        };
+        let right_fmt = match right_path {
+            Some(ref p) => infer_format(p)?,
+            None => resolve_stdin_format(&left_path)?,
+        };
+        let left_reader = open_diff_stream(left_path.as_ref(), left_fmt, delimiter)?;
+        let right_reader = open_diff_stream(right_path.as_ref(), right_fmt, delimiter)?;
+        let left_label = left_path.as_ref().map(|p| p.display().to_string()).unwrap_or_else(|| "stdin".to_string());
+        let right_label = right_path.as_ref().map(|p| p.display().to_string()).unwrap_or_else(|| "stdin".to_string());
+
+        let mut sink = DiffStreamSink {
+            out,
+            output: output.as_deref(),
+            color: output.is_none() && atty::is(atty::Stream::Stdout),
+            include_results: !summary_only,
+            invocation: &invocation,
+            invocation_args: &invocation_args,
+            export_specs: &export_specs,
+            export_side,
+            quiet,
+            open: None,
+        };
+        let summary = diff_streaming(left_reader, right_reader, &left_label, &right_label, hdr_row, build_options, &mut sink)?;
+        let exit_reason = diff::ExitReason::from_summary(&summary, strict_exit, baseline);
+        sink.finish(&summary, exit_reason)?;
+
+        if !quiet && matches!(summary_mode, DiffSummaryMode::Stderr) {
+            report_diff_summary(&summary, &left_label, &right_label);
+        }
+        if !no_fail && exit_reason.is_failure() {
+            return Err(CliError { code: EXIT_EVAL_ERROR, message: String::new(), hint: None });
+        }
+        return Ok(());
+    }
+
+    let DiffRun { result, headers, right_rows, options, left_label, right_label } = {
+        // Load both sides
+        let (mut left_sheet, left_label, left_fmt) = if left_is_stdin {
+            let fmt = resolve_stdin_format(&right_path)?;
+            (read_diff_side(None, fmt, delimiter, locale)?, "stdin".to_string(), fmt)
+        } else {
+            let p = left_path.as_ref().unwrap();
+            let fmt = infer_format(p)?;
+            let label = p.display().to_string();
+            (read_diff_side(Some(p), fmt, delimiter, locale)?, label, fmt)
+        };
+
+        let (mut right_sheet, right_label, right_fmt) = if right_is_stdin {
+            let fmt = resolve_stdin_format(&left_path)?;
+            (read_diff_side(None, fmt, delimiter, locale)?, "stdin".to_string(), fmt)
+        } else {
+            let p = right_path.as_ref().unwrap();
+            let fmt = infer_format(p)?;
+            let label = p.display().to_string();
+            (read_diff_side(Some(p), fmt, delimiter, locale)?, label, fmt)
+        };
+
+        // JSON columns come from object keys, so line the two sides up by name
+        let is_json = |fmt: Format| matches!(fmt, Format::Json | Format::Ndjson);
+        if let Some(hr) = hdr_row.filter(|_| is_json(left_fmt) || is_json(right_fmt)) {
+            align_columns_by_header(&mut left_sheet, &mut right_sheet, hr);
+        }
+
+        let (left_bounds_rows, left_bounds_cols) = get_data_bounds(&left_sheet);
+        let (right_bounds_rows, right_bounds_cols) = get_data_bounds(&right_sheet);
+
+        if left_bounds_rows == 0 {
+            return Err(CliError { code: EXIT_DIFF_PARSE, message: format!("{}: empty or has no data rows", left_label), hint: None });
+        }
+        if right_bounds_rows == 0 {
+            return Err(CliError { code: EXIT_DIFF_PARSE, message: format!("{}: empty or has no data rows", right_label), hint: None });
+        }
+
+        // Extract headers
+        let max_cols = left_bounds_cols.max(right_bounds_cols);
+        let headers: Vec<String> = if let Some(hr) = hdr_row {
+            (0..max_cols)
+                .map(|c| {
+                    let lh = left_sheet.get_display(hr, c);
+                    if !lh.is_empty() {
+                        lh
+                    } else {
+                        right_sheet.get_display(hr, c)
+                    }
+                })
+                .collect()
+        } else {
+            // Generate A, B, C, ... headers
+            (0..max_cols).map(col_letter).collect()
+        };
+
+        // Extract per-side headers (for column validation when real headers exist)
+        let left_headers: Vec<String> = if let Some(hr) = hdr_row {
+            (0..left_bounds_cols).map(|c| left_sheet.get_display(hr, c)).collect()
+        } else {
+            (0..left_bounds_cols).map(col_letter).collect()
+        };
+        let right_headers: Vec<String> = if let Some(hr) = hdr_row {
+            (0..right_bounds_cols).map(|c| right_sheet.get_display(hr, c)).collect()
+        } else {
+            (0..right_bounds_cols).map(col_letter).collect()
+        };
+
+        let mut options = build_options(&headers, &left_headers, &right_headers)?;
+
+        // Extract data rows
+        let data_start = hdr_row.map(|h| h + 1).unwrap_or(0);
+
+        if key_auto {
+            let values = |sheet: &visigrid_engine::sheet::Sheet, rows: usize| -> Vec<Vec<String>> {
+                (data_start..rows)
+                    .map(|r| (0..max_cols).map(|c| sheet.get_display(r, c)).collect())
+                    .filter(|row: &Vec<String>| row.iter().any(|v| !v.is_empty()))
+                    .collect()
+            };
+            let left_values = values(&left_sheet, left_bounds_rows);
+            let right_values = values(&right_sheet, right_bounds_rows);
+            let col = diff::auto_key_column(&left_values, &right_values, max_cols, options.key_transform)
+                .ok_or_else(|| {
+                    CliError::args("--key auto: no column is unique and non-empty in both files")
+                        .with_hint("pass --key <column>, or repeat --key for a composite key")
+                })?;
+            if !quiet {
+                eprintln!("key: {} (column {}, auto-detected)", headers[col], col_letter(col));
+            }
+            options.key_cols = vec![col];
+        }
+        let left_rows = extract_data_rows(&left_sheet, data_start, left_bounds_rows, left_bounds_cols, &headers, &options);
+        let right_rows = extract_data_rows(&right_sheet, data_start, right_bounds_rows, right_bounds_cols, &headers, &options);
+
+        // Run reconciliation
+        let mut result = if show_progress {
+            diff::reconcile_with_progress(&left_rows, &right_rows, &headers, &options, draw_reconcile_progress)
+        } else {
+            diff::reconcile(&left_rows, &right_rows, &headers, &options)
+        }
+        .map_err(duplicate_keys_error)?;
+        result.results.retain(|row| keep_row(row.status));
+
+        DiffRun { result, headers, right_rows, options, left_label, right_label }
     };
 
-    // Extract headers
-    let max_cols = left_bounds_cols.max(right_bounds_cols);
-    let headers: Vec<String> = if let Some(hr) = hdr_row {
-        (0..max_cols)
-            .map(|c| {
-                let lh = left_sheet.get_display(hr, c);
-                if !lh.is_empty() {
-                    lh
-                } else {
-                    right_sheet.get_display(hr, c)
-                }
-            })
-            .collect()
-    } else {
-        // Generate A, B, C, ... headers
-        (0..max_cols).map(|c| col_letter(c)).collect()
+    let mode = options.match_mode;
+    let amb = options.on_ambiguous;
+
+    // Warn when using substring matching
+    if !quiet && mode == diff::MatchMode::Contains {
+        eprintln!("warning: using substring matching (--match contains); ensure keys are normalized");
+    }
+
+    // Save ambiguous matches to CSV (before error exit, so the file is always written)
+    if let Some(ref amb_path) = save_ambiguous {
+        if !result.ambiguous_keys.is_empty() {
+            write_ambiguous_csv(amb_path, &result.ambiguous_keys)?;
+            if !quiet {
+                eprintln!("ambiguous matches exported to: {}", amb_path.display());
+            }
+        }
+    }
+
+    // Write --export CSVs
+    for (status, path) in &export_specs {
+        let filtered: Vec<&diff::DiffRow> = result.results.iter()
+            .filter(|r| r.status == *status)
+            .collect();
+        write_export_csv(path, &filtered, &headers, export_side, &right_rows)?;
+        if !quiet {
+            eprintln!("exported {} {} rows to: {}", filtered.len(), status.as_str(), path.display());
+        }
+    }
+
+    // Check ambiguous error condition (--no-fail suppresses this exit)
+    if !no_fail && !result.ambiguous_keys.is_empty() && amb == diff::AmbiguityPolicy::Error {
+        let mut msg = String::from("ambiguous matches found:\n");
+        for ak in &result.ambiguous_keys {
+            msg.push_str(&format!("  key {:?} matches {} right rows:", ak.key, ak.candidates.len()));
+            for c in &ak.candidates {
+                msg.push_str(&format!(" {:?}(row {})", c.right_key_raw, c.right_row_index));
+            }
+            msg.push('\n');
+        }
+        return Err(CliError {
+            code: EXIT_DIFF_AMBIGUOUS,
+            message: msg.trim_end().to_string(),
+            hint: Some("use --on_ambiguous report to include ambiguous matches in output instead of failing".to_string()),
+        });
+    }
+
+    let exit_reason = diff::ExitReason::from_summary(&result.summary, strict_exit, baseline);
+
+    // Format output
+    let output_bytes = match out {
+        DiffOutputFormat::Json => format_diff_json(&result, &options, &headers, &summary_mode, &invocation, &invocation_args, exit_reason, !summary_only)?,
+        DiffOutputFormat::Csv => format_diff_csv(&result, &options)?,
+        DiffOutputFormat::Table => {
+            // Color only when writing straight to a terminal
+            let color = output.is_none() && atty::is(atty::Stream::Stdout);
+            diff::format_table(&result, color).into_bytes()
+        }
     };
 
-    // Extract per-side headers (for column validation when real headers exist)
-    let left_headers: Vec<String> = if let Some(hr) = hdr_row {
-        (0..left_bounds_cols).map(|c| left_sheet.get_display(hr, c)).collect()
-    } else {
-        (0..left_bounds_cols).map(|c| col_letter(c)).collect()
+    // Write output
+    match output {
+        Some(path) => {
+            std::fs::write(&path, &output_bytes)
+                .map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?;
+        }
+        None => {
+            io::stdout()
+                .write_all(&output_bytes)
+                .map_err(|e| CliError::io(e.to_string()))?;
+        }
+    }
+
+    // Write summary to stderr if requested (--quiet suppresses)
+    if !quiet && matches!(summary_mode, DiffSummaryMode::Stderr) {
+        report_diff_summary(&result.summary, &left_label, &right_label);
+    }
+
+    // Exit 1 for material differences: missing rows or diffs outside tolerance.
+    // Within-tolerance diffs are reported but do not cause a non-zero exit code.
+    // --strict-exit: any diff (even within tolerance) causes exit 1.
+    // --baseline: only_right rows are improvements, not failures.
+    // --no-fail: always exit 0 (parse/usage errors still exit non-zero).
+    if !no_fail && exit_reason.is_failure() {
+        return Err(CliError { code: EXIT_EVAL_ERROR, message: String::new(), hint: None });
+    }
+
+    Ok(())
+}
+
+/// The `--summary stderr` block: row counts per status.
+fn report_diff_summary(s: &diff::DiffSummary, left_label: &str, right_label: &str) {
+    eprintln!("left:  {} rows ({})", s.left_rows, left_label);
+    eprintln!("right: {} rows ({})", s.right_rows, right_label);
+    eprintln!("matched: {}", s.matched);
+    eprintln!("only_left: {}", s.only_left);
+    eprintln!("only_right: {}", s.only_right);
+    eprintln!("value_diff: {}", s.diff);
+    if s.diff > 0 && s.diff != s.diff_outside_tolerance {
+        eprintln!("value_diff_outside_tolerance: {}", s.diff_outside_tolerance);
+    }
+    if s.ambiguous > 0 {
+        eprintln!("ambiguous: {}", s.ambiguous);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_diff3(
+    base_path: PathBuf,
+    left_path: PathBuf,
+    right_path: PathBuf,
+    key: Vec<String>,
+    key_transform: DiffKeyTransform,
+    compare: Option<String>,
+    tolerance: f64,
+    no_headers: bool,
+    delimiter: char,
+    output: Option<PathBuf>,
+    no_fail: bool,
+    quiet: bool,
+) -> Result<(), CliError> {
+    if key.iter().any(|k| k.eq_ignore_ascii_case("auto")) {
+        return Err(CliError::args("diff3 does not support --key auto")
+            .with_hint("name the key column with --key"));
+    }
+
+    let mut sheets = Vec::with_capacity(3);
+    for path in [&base_path, &left_path, &right_path] {
+        let sheet = read_file(path, infer_format(path)?, delimiter, None)?;
+        let (rows, cols) = get_data_bounds(&sheet);
+        if rows == 0 {
+            return Err(CliError { code: EXIT_DIFF_PARSE, message: format!("{}: empty or has no data rows", path.display()), hint: None });
+        }
+        sheets.push((sheet, rows, cols));
+    }
+
+    // Merged headers: first non-empty name per column across base, left, right
+    let hdr_row = if no_headers { None } else { Some(0) };
+    let max_cols = sheets.iter().map(|(_, _, cols)| *cols).max().unwrap_or(0);
+    let side_headers = |sheet: &visigrid_engine::sheet::Sheet, cols: usize| -> Vec<String> {
+        match hdr_row {
+            Some(hr) => (0..cols).map(|c| sheet.get_display(hr, c)).collect(),
+            None => (0..cols).map(col_letter).collect(),
+        }
     };
-    let right_headers: Vec<String> = if let Some(hr) = hdr_row {
-        (0..right_bounds_cols).map(|c| right_sheet.get_display(hr, c)).collect()
-    } else {
-        (0..right_bounds_cols).map(|c| col_letter(c)).collect()
+    let headers: Vec<String> = match hdr_row {
+        Some(hr) => (0..max_cols)
+            .map(|c| {
+                sheets.iter()
+                    .map(|(sheet, _, _)| sheet.get_display(hr, c))
+                    .find(|h| !h.is_empty())
+                    .unwrap_or_default()
+            })
+            .collect(),
+        None => (0..max_cols).map(col_letter).collect(),
     };
+    let options = build_diff_options(
+        &key, compare.as_deref(), None, None, DiffMatchMode::Exact, key_transform,
+        DiffAmbiguousPolicy::Error, tolerance, false, NumberLocale::En, &headers,
+        &side_headers(&sheets[1].0, sheets[1].2), &side_headers(&sheets[2].0, sheets[2].2), hdr_row.is_some(),
+    )?;
+
+    let data_start = hdr_row.map(|h| h + 1).unwrap_or(0);
+    let rows: Vec<Vec<diff::DataRow>> = sheets.iter()
+        .map(|(sheet, rows, cols)| extract_data_rows(sheet, data_start, *rows, *cols, &headers, &options))
+        .collect();
+
+    let result = diff::reconcile3(&rows[0], &rows[1], &rows[2], &headers, &options).map_err(|err| {
+        let mut msg = format!("duplicate keys found in {}:\n", err.input);
+        for dup in &err.duplicates {
+            msg.push_str(&format!("  key {:?} appears {} times\n", dup.key, dup.count));
+        }
+        CliError {
+            code: EXIT_DIFF_DUPLICATE,
+            message: msg.trim_end().to_string(),
+            hint: Some("each key must be unique within its file; deduplicate or choose a different --key column".to_string()),
+        }
+    })?;
+
+    let cell_json = |cell: &diff::MergeCell| serde_json::json!({
+        "column": cell.column,
+        "status": cell.status.as_str(),
+        "base": cell.base,
+        "left": cell.left,
+        "right": cell.right,
+    });
+    let rows_json: Vec<serde_json::Value> = result.rows.iter().map(|row| serde_json::json!({
+        "key": row.key,
+        "in_base": row.in_base,
+        "in_left": row.in_left,
+        "in_right": row.in_right,
+        "cells": row.cells.iter().map(cell_json).collect::<Vec<_>>(),
+    })).collect();
+    let conflicts_json: Vec<serde_json::Value> = result.rows.iter()
+        .flat_map(|row| row.cells.iter()
+            .filter(|cell| cell.status == diff::MergeStatus::Conflict)
+            .map(move |cell| {
+                let mut c = cell_json(cell);
+                c["key"] = serde_json::json!(row.key);
+                c.as_object_mut().unwrap().remove("status");
+                c
+            }))
+        .collect();
+    let s = &result.summary;
+    let top = serde_json::json!({
+        "base": base_path.display().to_string(),
+        "left": left_path.display().to_string(),
+        "right": right_path.display().to_string(),
+        "key": options.key_cols.iter().map(|&c| headers.get(c).cloned().unwrap_or_default()).collect::<Vec<_>>(),
+        "tolerance": tolerance,
+        "summary": {
+            "base_rows": s.base_rows,
+            "left_rows": s.left_rows,
+            "right_rows": s.right_rows,
+            "unchanged": s.unchanged,
+            "changed_left": s.changed_left,
+            "changed_right": s.changed_right,
+            "changed_both": s.changed_both,
+            "conflicts": s.conflicts,
+        },
+        "rows": rows_json,
+        "conflicts": conflicts_json,
+    });
 
+    let mut bytes = serde_json::to_vec_pretty(&top).map_err(|e| CliError::io(e.to_string()))?;
+    bytes.push(b'\n');
+    match output {
+        Some(path) => std::fs::write(&path, &bytes)
+            .map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?,
+        None => io::stdout().write_all(&bytes).map_err(|e| CliError::io(e.to_string()))?,
+    }
+
+    if !quiet {
+        eprintln!("base:  {} rows ({})", s.base_rows, base_path.display());
+        eprintln!("left:  {} rows ({})", s.left_rows, left_path.display());
+        eprintln!("right: {} rows ({})", s.right_rows, right_path.display());
+        eprintln!("changed_left: {}", s.changed_left);
+        eprintln!("changed_right: {}", s.changed_right);
+        eprintln!("changed_both: {}", s.changed_both);
+        eprintln!("conflicts: {}", s.conflicts);
+    }
+
+    if !no_fail && s.conflicts > 0 {
+        return Err(CliError { code: EXIT_EVAL_ERROR, message: String::new(), hint: None });
+    }
+    Ok(())
+}
+
+/// Everything `cmd_diff` needs after the load + reconcile phase.
+struct DiffRun {
+    result: diff::DiffResult,
+    headers: Vec<String>,
+    right_rows: Vec<diff::DataRow>,
+    options: diff::DiffOptions,
+    left_label: String,
+    right_label: String,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_diff_options(
+    key: &[String],
+    compare: Option<&str>,
+    ignore: Option<&str>,
+    contains_column: Option<&str>,
+    match_mode: DiffMatchMode,
+    key_transform: DiffKeyTransform,
+    on_ambiguous: DiffAmbiguousPolicy,
+    tolerance: f64,
+    empty_as_zero: bool,
+    number_locale: NumberLocale,
+    headers: &[String],
+    left_headers: &[String],
+    right_headers: &[String],
+    has_headers: bool,
+) -> Result<diff::DiffOptions, CliError> {
     // Resolve key columns (against merged headers — key mismatches are self-correcting
     // because nothing matches, producing visible only_left/only_right results)
     let key_cols: Vec<usize> = key.iter()
-        .map(|k| resolve_column(k, &headers))
+        .map(|k| resolve_column(k, headers))
         .collect::<Result<Vec<_>, _>>()?;
 
     // Resolve compare columns
-    let compare_cols = match &compare {
+    let compare_cols = match compare {
         Some(spec) => {
             let mut cols = Vec::new();
             for part in spec.split(',') {
                 let part = part.trim();
-                cols.push(resolve_column(part, &headers)?);
+                cols.push(resolve_column(part, headers)?);
                 // When real headers exist, verify column name is on both sides
-                if hdr_row.is_some() {
-                    check_column_both_sides(part, &left_headers, &right_headers)?;
+                if has_headers {
+                    check_column_both_sides(part, left_headers, right_headers)?;
                 }
             }
             Some(cols)
@@ -3319,6 +5153,25 @@ fn cmd_diff(
         None => None,
     };
 
+    // --ignore narrows the compared set (explicit --compare, or every non-key
+    // column). Rows still carry every column, so ignored values are echoed.
+    let compare_cols = match ignore {
+        Some(spec) => {
+            let ignored = spec.split(',')
+                .map(|part| resolve_column(part.trim(), headers))
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(cols) = &compare_cols {
+                if let Some(&both) = ignored.iter().find(|c| cols.contains(c)) {
+                    return Err(CliError::args(format!("column {:?} is in both --compare and --ignore", headers[both]))
+                        .with_hint("list each column in one of them"));
+                }
+            }
+            let base = compare_cols.unwrap_or_else(|| (0..headers.len()).filter(|c| !key_cols.contains(c)).collect());
+            Some(base.into_iter().filter(|c| !ignored.contains(c)).collect())
+        }
+        None => compare_cols,
+    };
+
     // Convert match mode
     let mode = match match_mode {
         DiffMatchMode::Exact => diff::MatchMode::Exact,
@@ -3344,16 +5197,16 @@ fn cmd_diff(
 
     // Resolve --contains-column against right-side headers (that's the side it searches)
     let contains_col = match contains_column {
-        Some(ref spec) => {
+        Some(spec) => {
             if mode != diff::MatchMode::Contains {
                 return Err(CliError::args("--contains-column requires --match contains"));
             }
-            Some(resolve_column_on_side(spec, &right_headers, "right")?)
+            Some(resolve_column_on_side(spec, right_headers, "right")?)
         }
         None => None,
     };
 
-    let options = diff::DiffOptions {
+    Ok(diff::DiffOptions {
         key_cols,
         compare_cols,
         match_mode: mode,
@@ -3361,181 +5214,403 @@ fn cmd_diff(
         on_ambiguous: amb,
         tolerance,
         contains_col,
-    };
+        empty_as_zero,
+        number_locale,
+    })
+}
 
-    // Extract data rows
-    let data_start = hdr_row.map(|h| h + 1).unwrap_or(0);
-    let left_rows = extract_data_rows(&left_sheet, data_start, left_bounds_rows, left_bounds_cols, &headers, &options);
-    let right_rows = extract_data_rows(&right_sheet, data_start, right_bounds_rows, right_bounds_cols, &headers, &options);
+fn duplicate_keys_error(err: diff::DiffError) -> CliError {
+    let diff::DiffError::DuplicateKeys(dups) = err;
+    let mut msg = String::from("duplicate keys found:\n");
+    for dup in &dups {
+        msg.push_str(&format!("  {} key {:?} appears {} times\n", dup.side.as_str(), dup.key, dup.count));
+    }
+    CliError {
+        code: EXIT_DIFF_DUPLICATE,
+        message: msg.trim_end().to_string(),
+        hint: Some("each key must be unique within its file; deduplicate or choose a different --key column".to_string()),
+    }
+}
 
-    // Warn when using substring matching
-    if !quiet && mode == diff::MatchMode::Contains {
-        eprintln!("warning: using substring matching (--match contains); ensure keys are normalized");
+/// Redraw the `diff --progress` bar for one reconcile phase on stderr.
+fn draw_reconcile_progress(phase: diff::ReconcilePhase, done: usize, total: usize) {
+    const WIDTH: usize = 30;
+    let label = match phase {
+        diff::ReconcilePhase::IndexRight => "indexing right",
+        diff::ReconcilePhase::ScanLeft => "scanning left ",
+    };
+    let filled = (done * WIDTH).checked_div(total).unwrap_or(WIDTH);
+    eprint!("\r{} [{}{}] {}/{}", label, "#".repeat(filled), " ".repeat(WIDTH - filled), done, total);
+    if done >= total {
+        eprintln!();
     }
+}
 
-    // Run reconciliation
-    let result = match diff::reconcile(&left_rows, &right_rows, &headers, &options) {
-        Ok(r) => r,
-        Err(diff::DiffError::DuplicateKeys(dups)) => {
-            let mut msg = String::from("duplicate keys found:\n");
-            for dup in &dups {
-                msg.push_str(&format!("  {} key {:?} appears {} times\n", dup.side.as_str(), dup.key, dup.count));
-            }
-            return Err(CliError {
-                code: EXIT_DIFF_DUPLICATE,
-                message: msg.trim_end().to_string(),
-                hint: Some("each key must be unique within its file; deduplicate or choose a different --key column".to_string()),
-            });
-        }
+// ── diff --stream ───────────────────────────────────────────────────
+
+/// Open one side of `diff --stream` as a record reader.
+///
+/// CSV files sniff their delimiter from the first 64 KiB, matching
+/// `visigrid_io::csv::import`; CSV on stdin uses `--delimiter`, matching `parse_csv`.
+fn open_diff_stream(
+    path: Option<&PathBuf>,
+    format: Format,
+    delimiter: char,
+) -> Result<csv::Reader<Box<dyn Read>>, CliError> {
+    let mut source: Box<dyn Read> = match path {
+        Some(p) => visigrid_io::csv::open_decompressed(p)
+            .map_err(|e| CliError::io(format!("{}: {}", p.display(), e)))?,
+        None => Box::new(io::stdin()),
     };
 
-    // Save ambiguous matches to CSV (before error exit, so the file is always written)
-    if let Some(ref amb_path) = save_ambiguous {
-        if !result.ambiguous_keys.is_empty() {
-            write_ambiguous_csv(amb_path, &result.ambiguous_keys)?;
-            if !quiet {
-                eprintln!("ambiguous matches exported to: {}", amb_path.display());
-            }
+    let delim = match (format, path) {
+        (Format::Tsv, _) => b'\t',
+        (Format::Csv, None) => delimiter as u8,
+        (Format::Csv, Some(_)) => {
+            let mut sample = Vec::new();
+            (&mut source).take(64 * 1024).read_to_end(&mut sample)
+                .map_err(|e| CliError::io(e.to_string()))?;
+            let delim = visigrid_io::csv::sniff_delimiter(&String::from_utf8_lossy(&sample));
+            source = Box::new(io::Cursor::new(sample).chain(source));
+            delim
         }
-    }
+        _ => {
+            return Err(CliError::args("--stream requires CSV or TSV input")
+                .with_hint("convert first: vgrid convert data.xlsx -t csv -o data.csv"));
+        }
+    };
 
-    // Write --export CSVs
-    for (status, path) in &export_specs {
-        let filtered: Vec<&diff::DiffRow> = result.results.iter()
-            .filter(|r| r.status == *status)
-            .collect();
-        write_export_csv(path, &filtered, &headers, export_side, &right_rows)?;
-        if !quiet {
-            eprintln!("exported {} {} rows to: {}", filtered.len(), status.as_str(), path.display());
+    Ok(csv::ReaderBuilder::new()
+        .delimiter(delim)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(source))
+}
+
+/// Pull the next record and normalize it to display strings.
+///
+/// Fields go through a one-row scratch sheet so numbers render exactly as
+/// `Sheet::get_display` would after a full import. Returns None at EOF.
+fn next_display_record(
+    reader: &mut csv::Reader<Box<dyn Read>>,
+    record: &mut csv::StringRecord,
+    scratch: &mut visigrid_engine::sheet::Sheet,
+    label: &str,
+) -> Result<Option<Vec<String>>, CliError> {
+    let more = reader.read_record(record).map_err(|e| CliError {
+        code: EXIT_DIFF_PARSE,
+        message: format!("{}: {}", label, e),
+        hint: Some("--stream reads UTF-8 CSV/TSV only; drop --stream for other encodings".to_string()),
+    })?;
+    if !more {
+        return Ok(None);
+    }
+    let mut values = Vec::with_capacity(record.len());
+    for (c, field) in record.iter().enumerate() {
+        if field.is_empty() {
+            values.push(String::new());
+        } else {
+            scratch.set_value(0, c, field);
+            values.push(scratch.get_display(0, c));
+            scratch.clear_cell(0, c);
         }
     }
+    Ok(Some(values))
+}
 
-    // Check ambiguous error condition (--no-fail suppresses this exit)
-    if !no_fail && !result.ambiguous_keys.is_empty() && amb == diff::AmbiguityPolicy::Error {
-        let mut msg = String::from("ambiguous matches found:\n");
-        for ak in &result.ambiguous_keys {
-            msg.push_str(&format!("  key {:?} matches {} right rows:", ak.key, ak.candidates.len()));
-            for c in &ak.candidates {
-                msg.push_str(&format!(" {:?}(row {})", c.right_key_raw, c.right_row_index));
-            }
-            msg.push('\n');
-        }
-        return Err(CliError {
-            code: EXIT_DIFF_AMBIGUOUS,
-            message: msg.trim_end().to_string(),
-            hint: Some("use --on_ambiguous report to include ambiguous matches in output instead of failing".to_string()),
-        });
+/// Width of a record up to its last non-empty value (0 for a blank record).
+fn display_extent(values: &[String]) -> usize {
+    values.iter().rposition(|v| !v.is_empty()).map(|i| i + 1).unwrap_or(0)
+}
+
+fn data_row_from_values(
+    values: &[String],
+    width: usize,
+    headers: &[String],
+    options: &diff::DiffOptions,
+    source_index: usize,
+) -> diff::DataRow {
+    let get = |col: usize| values.get(col).cloned().unwrap_or_default();
+    let key_raw = diff::build_composite_key(&options.key_cols, get);
+    let key_norm = diff::build_composite_key(&options.key_cols, |col| {
+        diff::apply_key_transform(&get(col), options.key_transform)
+    });
+    let values = headers.iter()
+        .enumerate()
+        .filter(|(c, _)| *c < width)
+        .map(|(c, h)| (h.clone(), get(c)))
+        .collect();
+    diff::DataRow { key_raw, key_norm, values, source_index }
+}
+
+/// `diff --stream`: load and index the right side, then match the left side
+/// one record at a time, handing each result row to `sink` as it resolves.
+/// Only the right rows are held in memory.
+fn diff_streaming(
+    mut left_reader: csv::Reader<Box<dyn Read>>,
+    mut right_reader: csv::Reader<Box<dyn Read>>,
+    left_label: &str,
+    right_label: &str,
+    hdr_row: Option<usize>,
+    build_options: impl FnOnce(&[String], &[String], &[String]) -> Result<diff::DiffOptions, CliError>,
+    sink: &mut DiffStreamSink,
+) -> Result<diff::DiffSummary, CliError> {
+    use visigrid_engine::sheet::{Sheet, SheetId};
+
+    let mut scratch = Sheet::new(SheetId(1), 1, 256);
+    let mut record = csv::StringRecord::new();
+    let empty_err = |label: &str| CliError {
+        code: EXIT_DIFF_PARSE,
+        message: format!("{}: empty or has no data rows", label),
+        hint: None,
+    };
+
+    // Right side: read fully (it is the index)
+    let mut right_records = Vec::new();
+    while let Some(values) = next_display_record(&mut right_reader, &mut record, &mut scratch, right_label)? {
+        right_records.push(values);
+    }
+    let right_width = right_records.iter().map(|v| display_extent(v)).max().unwrap_or(0);
+    if right_width == 0 {
+        return Err(empty_err(right_label));
     }
 
-    // Build invocation string + structured args for JSON provenance
-    let invocation = {
-        let mut parts = vec![
-            "vgrid".to_string(),
-            "diff".to_string(),
-            shell_quote(&left_arg),
-            shell_quote(&right_arg),
-        ];
-        for k in &key {
-            parts.push("--key".to_string());
-            parts.push(shell_quote(k));
-        }
-        if match_mode != DiffMatchMode::Exact {
-            parts.push("--match".to_string());
-            parts.push(format!("{}", match_mode));
-        }
-        if key_transform != DiffKeyTransform::Trim {
-            parts.push("--key-transform".to_string());
-            parts.push(format!("{}", key_transform));
-        }
-        if let Some(ref cmp) = compare {
-            parts.push("--compare".to_string());
-            parts.push(shell_quote(cmp));
+    // Left side: read only through the header row (or the first record when headerless)
+    let mut left_head = Vec::new();
+    let head_len = hdr_row.map(|h| h + 1).unwrap_or(1);
+    while left_head.len() < head_len {
+        match next_display_record(&mut left_reader, &mut record, &mut scratch, left_label)? {
+            Some(values) => left_head.push(values),
+            None => break,
         }
-        if tolerance != 0.0 {
-            parts.push("--tolerance".to_string());
-            parts.push(format!("{}", tolerance));
-        }
-        if let Some(ref path) = output {
-            parts.push("--output".to_string());
-            parts.push(shell_quote(&path.display().to_string()));
-        }
-        if no_headers {
-            parts.push("--no-headers".to_string());
-        }
-        if let Some(hr) = header_row {
-            parts.push("--header-row".to_string());
-            parts.push(format!("{}", hr));
-        }
-        if delimiter != ',' {
-            parts.push("--delimiter".to_string());
-            parts.push(shell_quote(&delimiter.to_string()));
-        }
-        if let Some(ref cc) = contains_column {
-            parts.push("--contains-column".to_string());
-            parts.push(shell_quote(cc));
-        }
-        parts.join(" ")
+    }
+    let mut left_width = left_head.iter().map(|v| display_extent(v)).max().unwrap_or(0);
+
+    let max_cols = left_width.max(right_width);
+    let header_of = |rows: &[Vec<String>], hr: usize, c: usize| {
+        rows.get(hr).and_then(|r| r.get(c)).cloned().unwrap_or_default()
+    };
+    let (headers, left_headers, right_headers): (Vec<String>, Vec<String>, Vec<String>) = match hdr_row {
+        Some(hr) => (
+            (0..max_cols)
+                .map(|c| {
+                    let lh = header_of(&left_head, hr, c);
+                    if !lh.is_empty() { lh } else { header_of(&right_records, hr, c) }
+                })
+                .collect(),
+            (0..left_width).map(|c| header_of(&left_head, hr, c)).collect(),
+            (0..right_width).map(|c| header_of(&right_records, hr, c)).collect(),
+        ),
+        None => (
+            (0..max_cols).map(col_letter).collect(),
+            (0..left_width).map(col_letter).collect(),
+            (0..right_width).map(col_letter).collect(),
+        ),
     };
 
-    let invocation_args = serde_json::json!({
-        "left": left_arg,
-        "right": right_arg,
-        "key": key,
-        "output": output.as_ref().map(|p| p.display().to_string()),
-        "tolerance": tolerance,
-        "match": format!("{}", match_mode),
-        "key_transform": format!("{}", key_transform),
-    });
+    let options = build_options(&headers, &left_headers, &right_headers)?;
 
-    // Format output
-    let output_bytes = match out {
-        DiffOutputFormat::Json => format_diff_json(&result, &options, &headers, &summary_mode, &invocation, &invocation_args)?,
-        DiffOutputFormat::Csv => format_diff_csv(&result, &options)?,
-    };
+    let data_start = hdr_row.map(|h| h + 1).unwrap_or(0);
+    let right_rows: Vec<diff::DataRow> = right_records.iter()
+        .enumerate()
+        .skip(data_start)
+        .filter(|(_, v)| display_extent(v) > 0)
+        .map(|(i, v)| data_row_from_values(v, right_width, &headers, &options, i + 1))
+        .collect();
+    drop(right_records);
 
-    // Write output
-    match output {
-        Some(path) => {
-            std::fs::write(&path, &output_bytes)
-                .map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?;
+    let mut reconciler = diff::StreamReconciler::new(&right_rows, &headers, &options)
+        .map_err(duplicate_keys_error)?;
+
+    // Columns are fixed once output starts; a wider left row is reported once
+    let mut wide_row_reported = false;
+
+    // Headerless: the first record was only peeked for width — it is data
+    let mut pending: Vec<Vec<String>> = left_head.into_iter().skip(data_start).collect();
+    let mut left_index = data_start;
+    loop {
+        let values = if !pending.is_empty() {
+            pending.remove(0)
+        } else {
+            match next_display_record(&mut left_reader, &mut record, &mut scratch, left_label)? {
+                Some(values) => values,
+                None => break,
+            }
+        };
+        left_index += 1;
+        let extent = display_extent(&values);
+        if extent == 0 {
+            continue;
         }
-        None => {
-            io::stdout()
-                .write_all(&output_bytes)
-                .map_err(|e| CliError::io(e.to_string()))?;
+        if extent > headers.len() && !wide_row_reported {
+            if !sink.quiet {
+                eprintln!(
+                    "warning: {}: row {} has {} columns but the headers cover {}; extra columns are not compared",
+                    left_label, left_index, extent, headers.len()
+                );
+            }
+            wide_row_reported = true;
         }
+        left_width = left_width.max(extent);
+        let row = reconciler
+            .push_left(data_row_from_values(&values, left_width, &headers, &options, left_index))
+            .map_err(duplicate_keys_error)?;
+        sink.begin(&headers, &options)?;
+        sink.push(&row)?;
     }
 
-    // Write summary to stderr if requested (--quiet suppresses)
-    if !quiet && matches!(summary_mode, DiffSummaryMode::Stderr) {
-        let s = &result.summary;
-        eprintln!("left:  {} rows ({})", s.left_rows, left_label);
-        eprintln!("right: {} rows ({})", s.right_rows, right_label);
-        eprintln!("matched: {}", s.matched);
-        eprintln!("only_left: {}", s.only_left);
-        eprintln!("only_right: {}", s.only_right);
-        eprintln!("value_diff: {}", s.diff);
-        if s.diff > 0 && s.diff != s.diff_outside_tolerance {
-            eprintln!("value_diff_outside_tolerance: {}", s.diff_outside_tolerance);
+    if left_width == 0 {
+        return Err(empty_err(left_label));
+    }
+
+    let (tail, summary) = reconciler.finish();
+    sink.begin(&headers, &options)?;
+    for row in &tail {
+        sink.push(row)?;
+    }
+    Ok(summary)
+}
+
+/// Where `diff --stream` writes result rows: the output and every `--export`
+/// file are opened once the headers are known and written row by row. The
+/// table keeps only the lines it will list. JSON puts `results` ahead of
+/// `summary`, which is only known at the end.
+struct DiffStreamSink<'a> {
+    out: DiffOutputFormat,
+    output: Option<&'a Path>,
+    color: bool,
+    include_results: bool,
+    invocation: &'a str,
+    invocation_args: &'a serde_json::Value,
+    export_specs: &'a [(diff::RowStatus, PathBuf)],
+    export_side: ExportSide,
+    quiet: bool,
+    open: Option<DiffStreamOpen>,
+}
+
+/// The writers of a `DiffStreamSink`, created by `begin`.
+struct DiffStreamOpen {
+    headers: Vec<String>,
+    options: diff::DiffOptions,
+    body: DiffStreamBody,
+    exports: Vec<(diff::RowStatus, csv::Writer<std::io::BufWriter<std::fs::File>>, usize)>,
+}
+
+enum DiffStreamBody {
+    /// `rows` written so far; `None` when `--summary-only` omits results.
+    Json { writer: Box<dyn Write>, rows: Option<usize> },
+    Csv(csv::Writer<Box<dyn Write>>),
+    Table { writer: Box<dyn Write>, table: diff::TableBuilder },
+}
+
+impl DiffStreamSink<'_> {
+    /// Open the output and exports; later calls do nothing. Deferred to the
+    /// first row so an empty left side fails before anything is written.
+    fn begin(&mut self, headers: &[String], options: &diff::DiffOptions) -> Result<(), CliError> {
+        if self.open.is_some() {
+            return Ok(());
         }
-        if s.ambiguous > 0 {
-            eprintln!("ambiguous: {}", s.ambiguous);
+        let mut writer: Box<dyn Write> = match self.output {
+            Some(path) => Box::new(std::io::BufWriter::new(
+                std::fs::File::create(path).map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?,
+            )),
+            None => Box::new(std::io::BufWriter::new(io::stdout())),
+        };
+        let io_err = |e: io::Error| CliError::io(e.to_string());
+
+        let body = match self.out {
+            DiffOutputFormat::Json if self.include_results => {
+                let head = serde_json::json!({
+                    "contract_version": DIFF_CONTRACT_VERSION,
+                    "invocation": self.invocation,
+                    "invocation_args": self.invocation_args,
+                });
+                let head = serde_json::to_string_pretty(&head).map_err(|e| CliError::io(e.to_string()))?;
+                // Reopen the object to append the results array
+                write!(writer, "{},\n  \"results\": [", head.trim_end_matches("\n}")).map_err(io_err)?;
+                DiffStreamBody::Json { writer, rows: Some(0) }
+            }
+            DiffOutputFormat::Json => DiffStreamBody::Json { writer, rows: None },
+            DiffOutputFormat::Csv => {
+                let mut csv_writer = csv::WriterBuilder::new().from_writer(writer);
+                write_diff_csv_header(&mut csv_writer)?;
+                DiffStreamBody::Csv(csv_writer)
+            }
+            DiffOutputFormat::Table => DiffStreamBody::Table { writer, table: diff::TableBuilder::new(self.color) },
+        };
+
+        let mut exports = Vec::with_capacity(self.export_specs.len());
+        for (status, path) in self.export_specs {
+            let file = std::fs::File::create(path).map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?;
+            let mut export = csv::WriterBuilder::new().from_writer(std::io::BufWriter::new(file));
+            write_export_header(&mut export, headers, self.export_side)?;
+            exports.push((*status, export, 0));
         }
+
+        self.open = Some(DiffStreamOpen { headers: headers.to_vec(), options: options.clone(), body, exports });
+        Ok(())
     }
 
-    // Exit 1 for material differences: missing rows or diffs outside tolerance.
-    // Within-tolerance diffs are reported but do not cause a non-zero exit code.
-    // --strict-exit: any diff (even within tolerance) causes exit 1.
-    // --no-fail: always exit 0 (parse/usage errors still exit non-zero).
-    if !no_fail {
-        let s = &result.summary;
-        let diff_count = if strict_exit { s.diff } else { s.diff_outside_tolerance };
-        if s.only_left > 0 || s.only_right > 0 || diff_count > 0 {
-            return Err(CliError { code: EXIT_EVAL_ERROR, message: String::new(), hint: None });
+    fn push(&mut self, row: &diff::DiffRow) -> Result<(), CliError> {
+        let open = self.open.as_mut().expect("begin before push");
+        for (status, export, count) in &mut open.exports {
+            if *status == row.status {
+                write_export_row(export, row, &open.headers, self.export_side, &[])?;
+                *count += 1;
+            }
+        }
+        match &mut open.body {
+            DiffStreamBody::Json { writer, rows: Some(rows) } => {
+                let json = serde_json::to_string_pretty(&diff_row_json(row)).map_err(|e| CliError::io(e.to_string()))?;
+                let sep = if *rows == 0 { "" } else { "," };
+                write!(writer, "{}\n    {}", sep, json.replace('\n', "\n    ")).map_err(|e| CliError::io(e.to_string()))?;
+                *rows += 1;
+            }
+            DiffStreamBody::Json { rows: None, .. } => {}
+            DiffStreamBody::Csv(writer) => write_diff_csv_row(writer, row, &open.options)?,
+            DiffStreamBody::Table { table, .. } => table.push(row),
+        }
+        Ok(())
+    }
+
+    fn finish(self, summary: &diff::DiffSummary, exit_reason: diff::ExitReason) -> Result<(), CliError> {
+        let open = self.open.expect("begin before finish");
+        let io_err = |e: io::Error| CliError::io(e.to_string());
+        match open.body {
+            DiffStreamBody::Json { mut writer, rows: Some(rows) } => {
+                let tail = serde_json::to_string_pretty(&serde_json::json!({
+                    "summary": diff_summary_json(summary, &open.options, &open.headers),
+                    "exit_reason": exit_reason.as_str(),
+                }))
+                .map_err(|e| CliError::io(e.to_string()))?;
+                let close = if rows == 0 { "]" } else { "\n  ]" };
+                writeln!(writer, "{},{}", close, tail.trim_start_matches('{')).map_err(io_err)?;
+                writer.flush().map_err(io_err)?;
+            }
+            DiffStreamBody::Json { mut writer, rows: None } => {
+                let result = diff::DiffResult { results: Vec::new(), summary: summary.clone(), ambiguous_keys: Vec::new() };
+                let bytes = format_diff_json(
+                    &result, &open.options, &open.headers, &DiffSummaryMode::None,
+                    self.invocation, self.invocation_args, exit_reason, false,
+                )?;
+                writer.write_all(&bytes).map_err(io_err)?;
+                writer.flush().map_err(io_err)?;
+            }
+            DiffStreamBody::Csv(mut writer) => writer.flush().map_err(io_err)?,
+            DiffStreamBody::Table { mut writer, table } => {
+                writer.write_all(table.finish(summary.matched).as_bytes()).map_err(io_err)?;
+                writer.flush().map_err(io_err)?;
+            }
         }
-    }
 
-    Ok(())
+        for ((status, mut export, count), (_, path)) in open.exports.into_iter().zip(self.export_specs) {
+            export.flush().map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?;
+            if !self.quiet {
+                eprintln!("exported {} {} rows to: {}", count, status.as_str(), path.display());
+            }
+        }
+        Ok(())
+    }
 }
 
 fn resolve_column(spec: &str, headers: &[String]) -> Result<usize, CliError> {
@@ -3547,29 +5622,38 @@ fn resolve_column(spec: &str, headers: &[String]) -> Result<usize, CliError> {
         }
     }
 
+    if let Some(idx) = resolve_column_position(spec, headers.len()) {
+        return Ok(idx);
+    }
+
+    let available: Vec<&str> = headers.iter().map(|h| h.as_str()).collect();
+    Err(CliError::args(format!("unknown column: {:?}", spec))
+        .with_hint(format!("available columns: {}", available.join(", "))))
+}
+
+/// Resolve a column letter (A=0, B=1, ...) or 1-indexed number within `ncols`.
+fn resolve_column_position(spec: &str, ncols: usize) -> Option<usize> {
     // Try as column letter (A=0, B=1, ...)
-    if spec.chars().all(|c| c.is_ascii_alphabetic()) {
+    if !spec.is_empty() && spec.chars().all(|c| c.is_ascii_alphabetic()) {
         let upper = spec.to_uppercase();
         let mut col: usize = 0;
         for c in upper.chars() {
-            col = col * 26 + (c as usize - 'A' as usize + 1);
+            col = col.saturating_mul(26).saturating_add(c as usize - 'A' as usize + 1);
         }
         let idx = col - 1;
-        if idx < headers.len() {
-            return Ok(idx);
+        if idx < ncols {
+            return Some(idx);
         }
     }
 
     // Try as 1-indexed number
     if let Ok(n) = spec.parse::<usize>() {
-        if n >= 1 && n <= headers.len() {
-            return Ok(n - 1);
+        if n >= 1 && n <= ncols {
+            return Some(n - 1);
         }
     }
 
-    let available: Vec<&str> = headers.iter().map(|h| h.as_str()).collect();
-    Err(CliError::args(format!("unknown column: {:?}", spec))
-        .with_hint(format!("available columns: {}", available.join(", "))))
+    None
 }
 
 /// Like `resolve_column` but error messages name the side (e.g. "unknown right column").
@@ -3659,6 +5743,46 @@ fn col_letter(col: usize) -> String {
     result
 }
 
+/// Rebuild both sheets over one shared column order: the left header names,
+/// then the ones only the right has. JSON objects become columns in sorted
+/// key order, so a key missing from one file would otherwise shift every
+/// column after it. Sheets with blank or repeated header names are left alone.
+fn align_columns_by_header(
+    left: &mut visigrid_engine::sheet::Sheet,
+    right: &mut visigrid_engine::sheet::Sheet,
+    header_row: usize,
+) {
+    let header_names = |sheet: &visigrid_engine::sheet::Sheet| -> Option<Vec<String>> {
+        let (_, cols) = get_data_bounds(sheet);
+        let names: Vec<String> = (0..cols).map(|c| sheet.get_display(header_row, c)).collect();
+        let distinct: std::collections::HashSet<&String> = names.iter().collect();
+        (distinct.len() == names.len() && names.iter().all(|n| !n.is_empty())).then_some(names)
+    };
+    let (Some(left_names), Some(right_names)) = (header_names(left), header_names(right)) else {
+        return;
+    };
+    if left_names == right_names {
+        return;
+    }
+
+    let mut order = left_names.clone();
+    order.extend(right_names.iter().filter(|n| !left_names.contains(n)).cloned());
+
+    let remap = |sheet: &visigrid_engine::sheet::Sheet, names: &[String]| {
+        let mut out = visigrid_engine::sheet::Sheet::new(sheet.id, sheet.rows, sheet.cols.max(order.len()));
+        out.name = sheet.name.clone();
+        for (row, col) in sheet.occupied_cells() {
+            let Some(target) = names.get(col).and_then(|name| order.iter().position(|n| n == name)) else {
+                continue;
+            };
+            out.set_value(row, target, &sheet.get_raw(row, col));
+        }
+        out
+    };
+    *left = remap(left, &left_names);
+    *right = remap(right, &right_names);
+}
+
 fn extract_data_rows(
     sheet: &visigrid_engine::sheet::Sheet,
     data_start: usize,
@@ -3667,8 +5791,14 @@ fn extract_data_rows(
     headers: &[String],
     options: &diff::DiffOptions,
 ) -> Vec<diff::DataRow> {
+    // Sparse sheets: only visit rows that hold a value
+    let candidates: Vec<usize> = match sheet_ops::sparse_rows(sheet, bounds_rows, bounds_cols) {
+        Some(occupied) => occupied.range(data_start..).copied().collect(),
+        None => (data_start..bounds_rows).collect(),
+    };
+
     let mut rows = Vec::new();
-    for r in data_start..bounds_rows {
+    for r in candidates {
         // Skip blank rows
         let mut all_blank = true;
         for c in 0..bounds_cols {
@@ -3697,6 +5827,7 @@ fn extract_data_rows(
             key_raw,
             key_norm,
             values,
+            source_index: r + 1,
         });
     }
     rows
@@ -3704,6 +5835,148 @@ fn extract_data_rows(
 
 const DIFF_CONTRACT_VERSION: u32 = 1;
 
+/// JSON Schema (draft 2020-12) for the `diff --json` contract.
+///
+/// Mirrors `format_diff_json`; enum values come from the same types that
+/// produce them, so adding a status or match mode updates the schema too.
+fn diff_contract_schema() -> serde_json::Value {
+    use clap::ValueEnum;
+
+    let statuses: Vec<&str> = diff::RowStatus::ALL.iter().map(|s| s.as_str()).collect();
+    let exit_reasons: Vec<&str> = diff::ExitReason::ALL.iter().map(|r| r.as_str()).collect();
+    let match_modes: Vec<String> = DiffMatchMode::value_variants().iter().map(|m| m.to_string()).collect();
+    let key_transforms: Vec<String> = DiffKeyTransform::value_variants().iter().map(|t| t.to_string()).collect();
+    let count = serde_json::json!({ "type": "integer", "minimum": 0 });
+    let string = serde_json::json!({ "type": "string" });
+    let row_index = serde_json::json!({ "type": ["integer", "null"], "minimum": 1 });
+    let side_values = serde_json::json!({
+        "type": ["object", "null"],
+        "additionalProperties": { "type": "string" },
+    });
+
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("vgrid diff --json (contract_version {})", DIFF_CONTRACT_VERSION),
+        "type": "object",
+        "required": ["contract_version", "invocation", "invocation_args", "summary"],
+        "additionalProperties": false,
+        "properties": {
+            "contract_version": { "const": DIFF_CONTRACT_VERSION },
+            "invocation": string,
+            "invocation_args": {
+                "type": "object",
+                "required": ["left", "right", "key", "output", "tolerance", "match", "key_transform"],
+                "additionalProperties": false,
+                "properties": {
+                    "left": string,
+                    "right": string,
+                    "key": { "type": "array", "items": string },
+                    "output": { "type": ["string", "null"] },
+                    "tolerance": { "type": "number" },
+                    "match": { "enum": match_modes },
+                    "key_transform": { "enum": key_transforms },
+                },
+            },
+            "summary": {
+                "type": "object",
+                "required": [
+                    "left_rows", "right_rows", "matched", "only_left", "only_right", "diff",
+                    "diff_outside_tolerance", "ambiguous", "tolerance", "key", "match", "key_transform",
+                ],
+                "additionalProperties": false,
+                "properties": {
+                    "left_rows": count,
+                    "right_rows": count,
+                    "matched": count,
+                    "only_left": count,
+                    "only_right": count,
+                    "diff": count,
+                    "diff_outside_tolerance": count,
+                    "ambiguous": count,
+                    "tolerance": { "type": "number" },
+                    "key": string,
+                    "match": { "enum": match_modes },
+                    "key_transform": { "enum": key_transforms },
+                },
+            },
+            "exit_reason": {
+                "description": "Dominant failure category; missing_rows and value_diffs exit 1. Absent from output of older vgrid builds",
+                "enum": exit_reasons,
+            },
+            "results": {
+                "description": "Omitted with --summary-only",
+                "type": "array",
+                "items": { "$ref": "#/$defs/row" },
+            },
+        },
+        "$defs": {
+            "row": {
+                "type": "object",
+                "required": [
+                    "status", "key", "left", "right", "left_source_index", "right_source_index",
+                    "diffs", "match_explain", "candidates",
+                ],
+                "additionalProperties": false,
+                "properties": {
+                    "status": { "enum": statuses },
+                    "key": string,
+                    "left": side_values,
+                    "right": side_values,
+                    "left_source_index": row_index,
+                    "right_source_index": row_index,
+                    "diffs": {
+                        "type": ["array", "null"],
+                        "items": { "$ref": "#/$defs/column_diff" },
+                    },
+                    "match_explain": {
+                        "type": ["object", "null"],
+                        "required": ["mode", "left_key_raw", "right_key_raw", "left_key_norm", "right_key_norm"],
+                        "additionalProperties": false,
+                        "properties": {
+                            "mode": string,
+                            "left_key_raw": string,
+                            "right_key_raw": string,
+                            "left_key_norm": string,
+                            "right_key_norm": string,
+                        },
+                    },
+                    "candidates": {
+                        "type": ["array", "null"],
+                        "items": {
+                            "type": "object",
+                            "required": ["right_key_raw", "right_row_index"],
+                            "additionalProperties": false,
+                            "properties": {
+                                "right_key_raw": string,
+                                "right_row_index": count,
+                            },
+                        },
+                    },
+                },
+            },
+            "column_diff": {
+                "type": "object",
+                "required": ["column", "left", "right", "delta", "within_tolerance"],
+                "additionalProperties": false,
+                "properties": {
+                    "column": string,
+                    "left": string,
+                    "right": string,
+                    "delta": { "type": ["number", "null"] },
+                    "within_tolerance": { "type": "boolean" },
+                },
+            },
+        },
+    })
+}
+
+fn cmd_diff_print_schema() -> Result<(), CliError> {
+    let mut bytes = serde_json::to_vec_pretty(&diff_contract_schema()).map_err(|e| CliError::io(e.to_string()))?;
+    bytes.push(b'\n');
+    std::io::stdout().write_all(&bytes).map_err(|e| CliError::io(e.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn format_diff_json(
     result: &diff::DiffResult,
     options: &diff::DiffOptions,
@@ -3711,7 +5984,89 @@ fn format_diff_json(
     _summary_mode: &DiffSummaryMode,
     invocation: &str,
     invocation_args: &serde_json::Value,
+    exit_reason: diff::ExitReason,
+    include_results: bool,
 ) -> Result<Vec<u8>, CliError> {
+    let mut top = serde_json::json!({
+        "contract_version": DIFF_CONTRACT_VERSION,
+        "invocation": invocation,
+        "invocation_args": invocation_args,
+        "summary": diff_summary_json(&result.summary, options, headers),
+        "exit_reason": exit_reason.as_str(),
+    });
+    if include_results {
+        top["results"] = serde_json::json!(result.results.iter().map(diff_row_json).collect::<Vec<_>>());
+    }
+
+    let mut bytes = serde_json::to_vec_pretty(&top).map_err(|e| CliError::io(e.to_string()))?;
+    bytes.push(b'\n');
+    Ok(bytes)
+}
+
+/// One `results` entry of the diff JSON contract.
+fn diff_row_json(row: &diff::DiffRow) -> serde_json::Value {
+    let diffs_json = if row.diffs.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::json!(row.diffs.iter().map(|d| {
+            let mut m = serde_json::Map::new();
+            m.insert("column".to_string(), serde_json::json!(d.column));
+            m.insert("left".to_string(), serde_json::json!(d.left));
+            m.insert("right".to_string(), serde_json::json!(d.right));
+            m.insert("delta".to_string(), match d.delta {
+                Some(v) => serde_json::json!(v),
+                None => serde_json::Value::Null,
+            });
+            m.insert("within_tolerance".to_string(), serde_json::json!(d.within_tolerance));
+            serde_json::Value::Object(m)
+        }).collect::<Vec<_>>())
+    };
+
+    let explain_json = match &row.match_explain {
+        Some(e) => serde_json::json!({
+            "mode": e.mode,
+            "left_key_raw": e.left_key_raw,
+            "right_key_raw": e.right_key_raw,
+            "left_key_norm": e.left_key_norm,
+            "right_key_norm": e.right_key_norm,
+        }),
+        None => serde_json::Value::Null,
+    };
+
+    let candidates_json = match &row.candidates {
+        Some(cands) => serde_json::json!(cands.iter().map(|c| {
+            serde_json::json!({
+                "right_key_raw": c.right_key_raw,
+                "right_row_index": c.right_row_index,
+            })
+        }).collect::<Vec<_>>()),
+        None => serde_json::Value::Null,
+    };
+
+    let left_json = match &row.left {
+        Some(vals) => serde_json::json!(vals),
+        None => serde_json::Value::Null,
+    };
+    let right_json = match &row.right {
+        Some(vals) => serde_json::json!(vals),
+        None => serde_json::Value::Null,
+    };
+
+    serde_json::json!({
+        "status": row.status.as_str(),
+        "key": row.key,
+        "left": left_json,
+        "right": right_json,
+        "left_source_index": row.left_source_index,
+        "right_source_index": row.right_source_index,
+        "diffs": diffs_json,
+        "match_explain": explain_json,
+        "candidates": candidates_json,
+    })
+}
+
+/// The `summary` object of the diff JSON contract.
+fn diff_summary_json(summary: &diff::DiffSummary, options: &diff::DiffOptions, headers: &[String]) -> serde_json::Value {
     let key_name = options.key_cols.iter()
         .map(|&c| headers.get(c).cloned().unwrap_or_default())
         .collect::<Vec<_>>()
@@ -3727,153 +6082,85 @@ fn format_diff_json(
         diff::KeyTransform::Alnum => "alnum",
     };
 
-    // Build results array
-    let results_json: Vec<serde_json::Value> = result.results.iter().map(|row| {
-        let diffs_json = if row.diffs.is_empty() {
-            serde_json::Value::Null
-        } else {
-            serde_json::json!(row.diffs.iter().map(|d| {
-                let mut m = serde_json::Map::new();
-                m.insert("column".to_string(), serde_json::json!(d.column));
-                m.insert("left".to_string(), serde_json::json!(d.left));
-                m.insert("right".to_string(), serde_json::json!(d.right));
-                m.insert("delta".to_string(), match d.delta {
-                    Some(v) => serde_json::json!(v),
-                    None => serde_json::Value::Null,
-                });
-                m.insert("within_tolerance".to_string(), serde_json::json!(d.within_tolerance));
-                serde_json::Value::Object(m)
-            }).collect::<Vec<_>>())
-        };
-
-        let explain_json = match &row.match_explain {
-            Some(e) => serde_json::json!({
-                "mode": e.mode,
-                "left_key_raw": e.left_key_raw,
-                "right_key_raw": e.right_key_raw,
-                "left_key_norm": e.left_key_norm,
-                "right_key_norm": e.right_key_norm,
-            }),
-            None => serde_json::Value::Null,
-        };
-
-        let candidates_json = match &row.candidates {
-            Some(cands) => serde_json::json!(cands.iter().map(|c| {
-                serde_json::json!({
-                    "right_key_raw": c.right_key_raw,
-                    "right_row_index": c.right_row_index,
-                })
-            }).collect::<Vec<_>>()),
-            None => serde_json::Value::Null,
-        };
-
-        let left_json = match &row.left {
-            Some(vals) => serde_json::json!(vals),
-            None => serde_json::Value::Null,
-        };
-        let right_json = match &row.right {
-            Some(vals) => serde_json::json!(vals),
-            None => serde_json::Value::Null,
-        };
-
-        serde_json::json!({
-            "status": row.status.as_str(),
-            "key": row.key,
-            "left": left_json,
-            "right": right_json,
-            "diffs": diffs_json,
-            "match_explain": explain_json,
-            "candidates": candidates_json,
-        })
-    }).collect();
-
-    // Build top-level object
-    let summary_json = serde_json::json!({
-        "left_rows": result.summary.left_rows,
-        "right_rows": result.summary.right_rows,
-        "matched": result.summary.matched,
-        "only_left": result.summary.only_left,
-        "only_right": result.summary.only_right,
-        "diff": result.summary.diff,
-        "diff_outside_tolerance": result.summary.diff_outside_tolerance,
-        "ambiguous": result.summary.ambiguous,
+    serde_json::json!({
+        "left_rows": summary.left_rows,
+        "right_rows": summary.right_rows,
+        "matched": summary.matched,
+        "only_left": summary.only_left,
+        "only_right": summary.only_right,
+        "diff": summary.diff,
+        "diff_outside_tolerance": summary.diff_outside_tolerance,
+        "ambiguous": summary.ambiguous,
         "tolerance": options.tolerance,
         "key": key_name,
         "match": match_str,
         "key_transform": kt_str,
-    });
-
-    let top = serde_json::json!({
-        "contract_version": DIFF_CONTRACT_VERSION,
-        "invocation": invocation,
-        "invocation_args": invocation_args,
-        "summary": summary_json,
-        "results": results_json,
-    });
-
-    let mut bytes = serde_json::to_vec_pretty(&top).map_err(|e| CliError::io(e.to_string()))?;
-    bytes.push(b'\n');
-    Ok(bytes)
+    })
 }
 
 fn format_diff_csv(
     result: &diff::DiffResult,
     options: &diff::DiffOptions,
 ) -> Result<Vec<u8>, CliError> {
-    let match_str = match options.match_mode {
-        diff::MatchMode::Exact => "exact",
-        diff::MatchMode::Contains => "contains",
-    };
-
     let mut writer = csv::WriterBuilder::new().from_writer(Vec::new());
+    write_diff_csv_header(&mut writer)?;
+    for row in &result.results {
+        write_diff_csv_row(&mut writer, row, options)?;
+    }
+    writer.into_inner().map_err(|e| CliError::io(e.to_string()))
+}
 
-    // Header
-    writer.write_record(&[
+fn write_diff_csv_header<W: Write>(writer: &mut csv::Writer<W>) -> Result<(), CliError> {
+    writer.write_record([
         "status", "key", "column", "left_value", "right_value",
         "delta", "within_tolerance", "match_mode", "match_explain",
-    ]).map_err(|e| CliError::io(e.to_string()))?;
+    ]).map_err(|e| CliError::io(e.to_string()))
+}
 
-    for row in &result.results {
-        if row.status == diff::RowStatus::Diff && !row.diffs.is_empty() {
-            // One CSV row per column diff
-            for d in &row.diffs {
-                let explain = match &row.match_explain {
-                    Some(e) => format!("{} left={:?} right={:?}", e.mode, e.left_key_raw, e.right_key_raw),
-                    None => String::new(),
-                };
-                writer.write_record(&[
-                    row.status.as_str(),
-                    &row.key,
-                    &d.column,
-                    &d.left,
-                    &d.right,
-                    &d.delta.map(|v| format!("{}", v)).unwrap_or_default(),
-                    &d.within_tolerance.to_string(),
-                    match_str,
-                    &explain,
-                ]).map_err(|e| CliError::io(e.to_string()))?;
-            }
-        } else {
-            // One row for the key
-            let explain = match &row.match_explain {
-                Some(e) => format!("{} left={:?} right={:?}", e.mode, e.left_key_raw, e.right_key_raw),
-                None => String::new(),
-            };
-            writer.write_record(&[
+fn write_diff_csv_row<W: Write>(
+    writer: &mut csv::Writer<W>,
+    row: &diff::DiffRow,
+    options: &diff::DiffOptions,
+) -> Result<(), CliError> {
+    let match_str = match options.match_mode {
+        diff::MatchMode::Exact => "exact",
+        diff::MatchMode::Contains => "contains",
+    };
+    let explain = match &row.match_explain {
+        Some(e) => format!("{} left={:?} right={:?}", e.mode, e.left_key_raw, e.right_key_raw),
+        None => String::new(),
+    };
+
+    if row.status == diff::RowStatus::Diff && !row.diffs.is_empty() {
+        // One CSV row per column diff
+        for d in &row.diffs {
+            writer.write_record([
                 row.status.as_str(),
                 &row.key,
-                "",
-                "",
-                "",
-                "",
-                "",
+                &d.column,
+                &d.left,
+                &d.right,
+                &d.delta.map(|v| format!("{}", v)).unwrap_or_default(),
+                &d.within_tolerance.to_string(),
                 match_str,
                 &explain,
             ]).map_err(|e| CliError::io(e.to_string()))?;
         }
+    } else {
+        // One row for the key
+        writer.write_record([
+            row.status.as_str(),
+            &row.key,
+            "",
+            "",
+            "",
+            "",
+            "",
+            match_str,
+            &explain,
+        ]).map_err(|e| CliError::io(e.to_string()))?;
     }
-
-    writer.into_inner().map_err(|e| CliError::io(e.to_string()))
+    Ok(())
 }
 
 fn write_ambiguous_csv(path: &PathBuf, ambiguous_keys: &[diff::AmbiguousKey]) -> Result<(), CliError> {
@@ -3942,45 +6229,27 @@ fn write_export_csv(
     right_data_rows: &[diff::DataRow],
 ) -> Result<(), CliError> {
     let mut writer = csv::WriterBuilder::new().from_writer(Vec::new());
+    write_export_header(&mut writer, headers, side)?;
+    for row in rows {
+        write_export_row(&mut writer, row, headers, side, right_data_rows)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| CliError::io(e.to_string()))?;
+    std::fs::write(path, &bytes)
+        .map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?;
+
+    Ok(())
+}
 
+fn write_export_header<W: Write>(
+    writer: &mut csv::Writer<W>,
+    headers: &[String],
+    side: ExportSide,
+) -> Result<(), CliError> {
     match side {
         ExportSide::Left | ExportSide::Right => {
             // Header: just the original column names
-            writer.write_record(headers).map_err(|e| CliError::io(e.to_string()))?;
-
-            for row in rows {
-                // Determine which side's data to emit
-                let values = match (row.status, side) {
-                    // only_left always emits left data
-                    (diff::RowStatus::OnlyLeft, _) => row.left.as_ref(),
-                    // only_right always emits right data
-                    (diff::RowStatus::OnlyRight, _) => row.right.as_ref(),
-                    // For matched/diff/ambiguous: follow the requested side
-                    (_, ExportSide::Left) => row.left.as_ref(),
-                    (_, ExportSide::Right) => row.right.as_ref(),
-                    _ => unreachable!(),
-                };
-
-                // For ambiguous in left/right mode: one row per left key (not per candidate)
-                if row.status == diff::RowStatus::Ambiguous && matches!(side, ExportSide::Right) {
-                    // Right side for ambiguous: skip (no single right match)
-                    // Write left data instead as fallback
-                    if let Some(vals) = row.left.as_ref() {
-                        let record: Vec<&str> = headers.iter()
-                            .map(|h| vals.get(h).map(|s| s.as_str()).unwrap_or(""))
-                            .collect();
-                        writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
-                    }
-                    continue;
-                }
-
-                if let Some(vals) = values {
-                    let record: Vec<&str> = headers.iter()
-                        .map(|h| vals.get(h).map(|s| s.as_str()).unwrap_or(""))
-                        .collect();
-                    writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
-                }
-            }
+            writer.write_record(headers).map_err(|e| CliError::io(e.to_string()))
         }
         ExportSide::Both => {
             // Header: metadata + left headers + right_ prefixed headers
@@ -3998,92 +6267,130 @@ fn write_export_csv(
             for h in headers {
                 header_record.push(format!("right_{}", h));
             }
-            writer.write_record(&header_record).map_err(|e| CliError::io(e.to_string()))?;
-
-            for row in rows {
-                if row.status == diff::RowStatus::Ambiguous {
-                    // One row per candidate
-                    if let Some(ref candidates) = row.candidates {
-                        let candidate_count = candidates.len().to_string();
-                        for (ci, candidate) in candidates.iter().enumerate() {
-                            let mut record: Vec<String> = Vec::new();
-                            // Metadata
-                            record.push(row.status.as_str().to_string());
-                            record.push(row.key.clone());
-                            // _left_key_raw
-                            let left_key_raw = row.match_explain.as_ref()
-                                .map(|e| e.left_key_raw.as_str())
-                                .unwrap_or(&row.key);
-                            record.push(left_key_raw.to_string());
-                            // _right_key from candidate
-                            record.push(candidate.right_key_raw.clone());
-                            record.push(candidate_count.clone());
-                            record.push(ci.to_string());
-                            // Left columns
-                            if let Some(ref vals) = row.left {
-                                for h in headers {
-                                    record.push(vals.get(h).cloned().unwrap_or_default());
-                                }
-                            } else {
-                                for _ in headers { record.push(String::new()); }
-                            }
-                            // Right columns from the right data rows
-                            let right_vals = &right_data_rows[candidate.right_row_index].values;
+            writer.write_record(&header_record).map_err(|e| CliError::io(e.to_string()))
+        }
+    }
+}
+
+fn write_export_row<W: Write>(
+    writer: &mut csv::Writer<W>,
+    row: &diff::DiffRow,
+    headers: &[String],
+    side: ExportSide,
+    right_data_rows: &[diff::DataRow],
+) -> Result<(), CliError> {
+    match side {
+        ExportSide::Left | ExportSide::Right => {
+            // Determine which side's data to emit
+            let values = match (row.status, side) {
+                // only_left always emits left data
+                (diff::RowStatus::OnlyLeft, _) => row.left.as_ref(),
+                // only_right always emits right data
+                (diff::RowStatus::OnlyRight, _) => row.right.as_ref(),
+                // For matched/diff/ambiguous: follow the requested side
+                (_, ExportSide::Left) => row.left.as_ref(),
+                (_, ExportSide::Right) => row.right.as_ref(),
+                _ => unreachable!(),
+            };
+
+            // For ambiguous in left/right mode: one row per left key (not per candidate)
+            if row.status == diff::RowStatus::Ambiguous && matches!(side, ExportSide::Right) {
+                // Right side for ambiguous: skip (no single right match)
+                // Write left data instead as fallback
+                if let Some(vals) = row.left.as_ref() {
+                    let record: Vec<&str> = headers.iter()
+                        .map(|h| vals.get(h).map(|s| s.as_str()).unwrap_or(""))
+                        .collect();
+                    writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
+                }
+                return Ok(());
+            }
+
+            if let Some(vals) = values {
+                let record: Vec<&str> = headers.iter()
+                    .map(|h| vals.get(h).map(|s| s.as_str()).unwrap_or(""))
+                    .collect();
+                writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
+            }
+        }
+        ExportSide::Both => {
+            if row.status == diff::RowStatus::Ambiguous {
+                // One row per candidate
+                if let Some(ref candidates) = row.candidates {
+                    let candidate_count = candidates.len().to_string();
+                    for (ci, candidate) in candidates.iter().enumerate() {
+                        let mut record: Vec<String> = Vec::new();
+                        // Metadata
+                        record.push(row.status.as_str().to_string());
+                        record.push(row.key.clone());
+                        // _left_key_raw
+                        let left_key_raw = row.match_explain.as_ref()
+                            .map(|e| e.left_key_raw.as_str())
+                            .unwrap_or(&row.key);
+                        record.push(left_key_raw.to_string());
+                        // _right_key from candidate
+                        record.push(candidate.right_key_raw.clone());
+                        record.push(candidate_count.clone());
+                        record.push(ci.to_string());
+                        // Left columns
+                        if let Some(ref vals) = row.left {
                             for h in headers {
-                                record.push(right_vals.get(h).cloned().unwrap_or_default());
+                                record.push(vals.get(h).cloned().unwrap_or_default());
                             }
-                            writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
+                        } else {
+                            for _ in headers { record.push(String::new()); }
                         }
-                    }
-                } else {
-                    let mut record: Vec<String> = Vec::new();
-                    // Metadata
-                    record.push(row.status.as_str().to_string());
-                    record.push(row.key.clone());
-                    // _left_key_raw
-                    let left_key_raw = row.match_explain.as_ref()
-                        .map(|e| e.left_key_raw.as_str())
-                        .unwrap_or(&row.key);
-                    record.push(left_key_raw.to_string());
-                    // _right_key
-                    let right_key = row.match_explain.as_ref()
-                        .map(|e| e.right_key_raw.as_str())
-                        .unwrap_or(if row.right.is_some() { &row.key } else { "" });
-                    record.push(right_key.to_string());
-                    // _candidate_count
-                    let ccount = match row.status {
-                        diff::RowStatus::Matched | diff::RowStatus::Diff => "1".to_string(),
-                        _ => String::new(),
-                    };
-                    record.push(ccount);
-                    // _candidate_index (empty for non-ambiguous)
-                    record.push(String::new());
-                    // Left columns
-                    if let Some(ref vals) = row.left {
+                        // Right columns from the right data rows
+                        let right_vals = &right_data_rows[candidate.right_row_index].values;
                         for h in headers {
-                            record.push(vals.get(h).cloned().unwrap_or_default());
+                            record.push(right_vals.get(h).cloned().unwrap_or_default());
                         }
-                    } else {
-                        for _ in headers { record.push(String::new()); }
+                        writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
                     }
-                    // Right columns
-                    if let Some(ref vals) = row.right {
-                        for h in headers {
-                            record.push(vals.get(h).cloned().unwrap_or_default());
-                        }
-                    } else {
-                        for _ in headers { record.push(String::new()); }
+                }
+            } else {
+                let mut record: Vec<String> = Vec::new();
+                // Metadata
+                record.push(row.status.as_str().to_string());
+                record.push(row.key.clone());
+                // _left_key_raw
+                let left_key_raw = row.match_explain.as_ref()
+                    .map(|e| e.left_key_raw.as_str())
+                    .unwrap_or(&row.key);
+                record.push(left_key_raw.to_string());
+                // _right_key
+                let right_key = row.match_explain.as_ref()
+                    .map(|e| e.right_key_raw.as_str())
+                    .unwrap_or(if row.right.is_some() { &row.key } else { "" });
+                record.push(right_key.to_string());
+                // _candidate_count
+                let ccount = match row.status {
+                    diff::RowStatus::Matched | diff::RowStatus::Diff => "1".to_string(),
+                    _ => String::new(),
+                };
+                record.push(ccount);
+                // _candidate_index (empty for non-ambiguous)
+                record.push(String::new());
+                // Left columns
+                if let Some(ref vals) = row.left {
+                    for h in headers {
+                        record.push(vals.get(h).cloned().unwrap_or_default());
                     }
-                    writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
+                } else {
+                    for _ in headers { record.push(String::new()); }
+                }
+                // Right columns
+                if let Some(ref vals) = row.right {
+                    for h in headers {
+                        record.push(vals.get(h).cloned().unwrap_or_default());
+                    }
+                } else {
+                    for _ in headers { record.push(String::new()); }
                 }
+                writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
             }
         }
     }
-
-    let bytes = writer.into_inner().map_err(|e| CliError::io(e.to_string()))?;
-    std::fs::write(path, &bytes)
-        .map_err(|e| CliError::io(format!("{}: {}", path.display(), e)))?;
-
     Ok(())
 }
 
@@ -4115,7 +6422,7 @@ fn cmd_ai_doctor(json: bool, test: bool) -> Result<(), CliError> {
         AIConfigStatus::Ready => (AIDoctorStatus::Ready, None),
         AIConfigStatus::NotImplemented => (AIDoctorStatus::Ready, Some("provider not yet implemented".to_string())),
         AIConfigStatus::MissingKey => (AIDoctorStatus::Misconfigured, Some("missing_api_key".to_string())),
-        AIConfigStatus::Error => (AIDoctorStatus::Misconfigured, config.blocking_reason.clone()),
+        AIConfigStatus::Misconfigured | AIConfigStatus::Error => (AIDoctorStatus::Misconfigured, config.blocking_reason.clone()),
     };
 
     // Context policy from resolved config
@@ -4341,7 +6648,11 @@ fn cmd_replay(
         return Err(CliError::eval(format!(
             "cannot verify: script contains nondeterministic functions ({})",
             result.nondeterministic_found.join(", ")
-        )).with_hint("remove NOW(), TODAY(), RAND(), RANDBETWEEN() from formulas, or run without --verify"));
+        )).with_hint(format!(
+            "remove {} from formulas, or run without --verify",
+            visigrid_engine::formula::functions::list_volatile_functions()
+                .iter().map(|f| format!("{}()", f)).collect::<Vec<_>>().join(", ")
+        )));
     }
 
     // Print result summary (unless quiet)
@@ -4463,34 +6774,72 @@ fn cmd_sessions(json: bool) -> Result<(), CliError> {
     Ok(())
 }
 
-fn cmd_attach(session_id: Option<String>) -> Result<(), CliError> {
+fn cmd_attach(session_id: Option<String>, token_file: Option<PathBuf>) -> Result<(), CliError> {
     let discovery = resolve_session(session_id.as_deref())?;
-    let token = get_session_token()?;
+    let token = get_session_token(token_file.as_deref())?;
 
     let client = session::SessionClient::connect(&discovery, &token)
         .map_err(CliError::session)?;
 
     println!("Connected to session {}", discovery.session_id);
     println!("  Revision:     {}", client.revision());
-    println!("  Capabilities: {}", client.capabilities().join(", "));
+    println!("  Capabilities: {}", client.capabilities().names().join(", "));
     println!("  Workbook:     {}", discovery.workbook_title);
     if let Some(ref path) = discovery.workbook_path {
         println!("  Path:         {}", path.display());
     }
 
+    // Best effort: a failed bye only costs the server a graceful-close count
+    let _ = client.close();
     Ok(())
 }
 
+/// `attach --interactive`: the peek viewer over a live session window.
+fn cmd_attach_interactive(
+    session_id: Option<String>,
+    token_file: Option<PathBuf>,
+    sheet: usize,
+    max_rows: usize,
+    max_cols: usize,
+) -> Result<(), CliError> {
+    if !atty::is(atty::Stream::Stdout) {
+        return Err(CliError::args("--interactive requires a terminal")
+            .with_hint("use 'visigrid view --follow' to stream snapshots instead"));
+    }
+    if max_rows == 0 || max_cols == 0 {
+        return Err(CliError::args("--max-rows and --max-cols must be at least 1"));
+    }
+
+    let discovery = resolve_session(session_id.as_deref())?;
+    let token = get_session_token(token_file.as_deref())?;
+    let client = session::SessionClient::connect(&discovery, &token)
+        .map_err(CliError::session)?;
+
+    let session_id_str = discovery.session_id.to_string();
+    let label = format!("{} [{}]", discovery.workbook_title, &session_id_str[..8.min(session_id_str.len())]);
+    let feed = tui::live::LiveFeed::new(Box::new(client), label, sheet, max_rows, max_cols);
+    tui::run_live(feed).map_err(CliError::io)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn cmd_apply(
     ops_arg: String,
+    ordered: bool,
     session_id: Option<String>,
     atomic: bool,
     expected_revision: Option<u64>,
     wait: bool,
     wait_timeout: u64,
+    request_timeout: u64,
+    chunk_size: Option<usize>,
+    token_file: Option<PathBuf>,
 ) -> Result<(), CliError> {
     use std::time::{Duration, Instant};
 
+    if chunk_size == Some(0) {
+        return Err(CliError::args("--chunk-size must be at least 1"));
+    }
+
     // Safety guard: --wait without idempotency protection is a footgun
     if wait && !atomic && expected_revision.is_none() {
         return Err(CliError {
@@ -4501,43 +6850,45 @@ fn cmd_apply(
     }
 
     let discovery = resolve_session(session_id.as_deref())?;
-    let token = get_session_token()?;
-
-    // Read ops from file or stdin (before connecting, so we don't hold connection while reading)
-    let ops_json = if ops_arg == "-" {
-        let mut buf = String::new();
-        io::stdin().read_to_string(&mut buf)
-            .map_err(|e| CliError::io(format!("failed to read stdin: {}", e)))?;
-        buf
-    } else {
-        std::fs::read_to_string(&ops_arg)
-            .map_err(|e| CliError::io(format!("failed to read {}: {}", ops_arg, e)))?
-    };
-
-    // Parse ops - support both JSONL (one op per line) and JSON array
-    let ops: Vec<session::Op> = if ops_json.trim_start().starts_with('[') {
-        // JSON array format
-        serde_json::from_str(&ops_json)
-            .map_err(|e| CliError::parse(format!("failed to parse ops JSON: {}", e)))?
+    let token = get_session_token(token_file.as_deref())?;
+
+    // Read ops before connecting, so we don't hold the connection while reading.
+    // --ordered takes a directory of *.jsonl steps, applied in filename order.
+    let ops_path = PathBuf::from(&ops_arg);
+    let files = if ordered {
+        if !ops_path.is_dir() {
+            return Err(CliError::args("--ordered requires a directory of .jsonl files")
+                .with_hint(format!("{} is not a directory", ops_arg)));
+        }
+        session::read_ops_dir(&ops_path).map_err(CliError::parse)?
+    } else if ops_path.is_dir() {
+        return Err(CliError::args(format!("{} is a directory", ops_arg))
+            .with_hint("use --ordered to apply its *.jsonl files in filename order"));
     } else {
-        // JSONL format (one op per line)
-        ops_json
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .enumerate()
-            .map(|(i, line)| {
-                serde_json::from_str(line)
-                    .map_err(|e| CliError::parse(format!("line {}: {}", i + 1, e)))
-            })
-            .collect::<Result<Vec<_>, _>>()?
+        let ops_json = if ops_arg == "-" {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)
+                .map_err(|e| CliError::io(format!("failed to read stdin: {}", e)))?;
+            buf
+        } else {
+            std::fs::read_to_string(&ops_arg)
+                .map_err(|e| CliError::io(format!("failed to read {}: {}", ops_arg, e)))?
+        };
+        // Supports both JSONL (one op per line) and a JSON array
+        vec![session::parse_ops(ops_path, &ops_json).map_err(CliError::parse)?]
     };
 
-    if ops.is_empty() {
+    let total: usize = files.iter().map(|f| f.ops.len()).sum();
+    if total == 0 {
         eprintln!("No operations to apply");
         return Ok(());
     }
 
-    eprintln!("Applying {} operation(s)...", ops.len());
+    if ordered {
+        eprintln!("Applying {} operation(s) from {} file(s)...", total, files.len());
+    } else {
+        eprintln!("Applying {} operation(s)...", total);
+    }
 
     let deadline = if wait {
         Some(Instant::now() + Duration::from_secs(wait_timeout))
@@ -4548,72 +6899,100 @@ fn cmd_apply(
     // Connect once; reuse for retries (saves connection slots)
     let mut client = session::SessionClient::connect(&discovery, &token)
         .map_err(CliError::session)?;
+    client.set_request_timeout(Duration::from_secs(request_timeout))
+        .map_err(CliError::session)?;
 
-    // Retry loop for writer conflicts
-    loop {
-        let result = client.apply_ops(ops.clone(), atomic, expected_revision);
-
-        match result {
-            Ok(result) => {
-                if let Some(ref err) = result.error {
-                    eprintln!("Error at op {}: [{}] {}", err.op_index, err.code, err.message);
-                    if let Some(ref hint) = err.suggestion {
-                        eprintln!("  Suggestion: {}", hint);
-                    }
-                    eprintln!("Applied: {}/{}", result.applied, result.total);
-                    eprintln!("Revision: {}", result.revision);
-                    // Partial apply = exit 24 (EXIT_SESSION_PARTIAL)
-                    return Err(CliError {
-                        code: exit_codes::EXIT_SESSION_PARTIAL,
-                        message: "operation failed".to_string(),
-                        hint: None,
-                    });
-                }
-
-                println!("Applied: {}/{}", result.applied, result.total);
-                println!("Revision: {}", result.revision);
-                return Ok(());
-            }
-            Err(session::SessionError::ServerError { code, message, retry_after_ms }) if code == "writer_conflict" => {
-                if let Some(deadline) = deadline {
-                    if Instant::now() >= deadline {
-                        eprintln!("error: writer conflict (timeout after {}s)", wait_timeout);
-                        return Err(CliError {
-                            code: exit_codes::EXIT_SESSION_CONFLICT,
-                            message: format!("writer conflict: {}", message),
-                            hint: Some("another client holds the writer lease".to_string()),
-                        });
-                    }
+    // One idempotency key per chunk, reused by every retry of that chunk,
+    // so a resend after a reconnect can't apply twice
+    let run_id = uuid::Uuid::new_v4();
+    let mut chunk_index = 0usize;
+
+    let send = |ops: Vec<session::Op>, expected_revision: Option<u64>| {
+        let idempotency_key = format!("{}:{}", run_id, chunk_index);
+        chunk_index += 1;
+
+        // Retry loop for writer conflicts
+        loop {
+            match client.apply_ops(ops.clone(), atomic, expected_revision, Some(idempotency_key.clone())) {
+                Ok(result) => return Ok(result),
+                Err(session::SessionError::ServerError { code: code @ session::ProtocolErrorCode::WriterConflict, message, retry_after_ms }) => {
+                    if let Some(deadline) = deadline {
+                        if Instant::now() >= deadline {
+                            eprintln!("error: writer conflict (timeout after {}s)", wait_timeout);
+                            return Err(CliError {
+                                code: exit_codes::EXIT_SESSION_CONFLICT,
+                                message: format!("writer conflict: {}", message),
+                                hint: Some("another client holds the writer lease".to_string()),
+                            });
+                        }
 
-                    // Adaptive backoff: use server hint, clamp to [50ms, 2000ms], add jitter
-                    let base_ms = retry_after_ms.unwrap_or(1000).clamp(50, 2000);
-                    let jitter = (base_ms as f64 * 0.1 * rand_jitter()) as u64;
-                    let sleep_ms = base_ms + jitter;
+                        // Adaptive backoff: use server hint, clamp to [50ms, 2000ms], add jitter
+                        let base_ms = retry_after_ms.unwrap_or(1000).clamp(50, 2000);
+                        let jitter = (base_ms as f64 * 0.1 * rand_jitter()) as u64;
+                        let sleep_ms = base_ms + jitter;
 
-                    eprintln!("Writer conflict, retrying in {}ms...", sleep_ms);
-                    std::thread::sleep(Duration::from_millis(sleep_ms));
+                        eprintln!("Writer conflict, retrying in {}ms...", sleep_ms);
+                        std::thread::sleep(Duration::from_millis(sleep_ms));
+                        continue;
+                    } else {
+                        // No --wait, fail immediately
+                        return Err(CliError::session(session::SessionError::ServerError {
+                            code,
+                            message,
+                            retry_after_ms,
+                        }));
+                    }
+                }
+                Err(session::SessionError::ConnectionClosed) if wait => {
+                    // Connection dropped; reconnect and retry
+                    eprintln!("Connection lost, reconnecting...");
+                    client = session::SessionClient::connect(&discovery, &token)
+                        .map_err(CliError::session)?;
+                    client.set_request_timeout(Duration::from_secs(request_timeout))
+                        .map_err(CliError::session)?;
                     continue;
-                } else {
-                    // No --wait, fail immediately
-                    return Err(CliError::session(session::SessionError::ServerError {
-                        code,
-                        message,
-                        retry_after_ms,
-                    }));
+                }
+                Err(e) => {
+                    return Err(CliError::session(e));
                 }
             }
-            Err(session::SessionError::ConnectionClosed) if wait => {
-                // Connection dropped; reconnect and retry
-                eprintln!("Connection lost, reconnecting...");
-                client = session::SessionClient::connect(&discovery, &token)
-                    .map_err(CliError::session)?;
-                continue;
-            }
-            Err(e) => {
-                return Err(CliError::session(e));
-            }
         }
+    };
+
+    let progress = |index: usize, file: &session::OpsFile, applied: usize, revision: u64| {
+        if ordered {
+            eprintln!("[{}/{}] {}: {} op(s), applied {}/{}, revision {}",
+                index + 1, files.len(), file.path.display(), file.ops.len(), applied, total, revision);
+        }
+    };
+
+    let report = session::apply_ops_files(&files, chunk_size, expected_revision, send, progress)?;
+
+    if let Some(ref failure) = report.failure {
+        let err = &failure.error;
+        if ordered {
+            eprintln!("Error in {} line {}: [{}] {}",
+                files[failure.file_index].path.display(), failure.line, err.code, err.message);
+        } else {
+            eprintln!("Error at op {}: [{}] {}", failure.op_index, err.code, err.message);
+        }
+        if let Some(ref hint) = err.suggestion {
+            eprintln!("  Suggestion: {}", hint);
+        }
+        eprintln!("Applied: {}/{}", report.applied, report.total);
+        eprintln!("Revision: {}", report.revision);
+        // Partial apply = exit 24 (EXIT_SESSION_PARTIAL)
+        return Err(CliError {
+            code: exit_codes::EXIT_SESSION_PARTIAL,
+            message: "operation failed".to_string(),
+            hint: None,
+        });
     }
+
+    println!("Applied: {}/{}", report.applied, report.total);
+    println!("Revision: {}", report.revision);
+    let _ = client.close();
+    Ok(())
 }
 
 /// Simple jitter factor in range [-1.0, 1.0] using timestamp entropy.
@@ -4633,11 +7012,12 @@ fn cmd_inspect(
     session_id: Option<String>,
     sheet: usize,
     json: bool,
+    token_file: Option<PathBuf>,
 ) -> Result<(), CliError> {
     use visigrid_protocol::InspectResult;
 
     let discovery = resolve_session(session_id.as_deref())?;
-    let token = get_session_token()?;
+    let token = get_session_token(token_file.as_deref())?;
 
     let mut client = session::SessionClient::connect(&discovery, &token)
         .map_err(CliError::session)?;
@@ -4681,10 +7061,10 @@ fn cmd_inspect(
                     "text"
                 };
 
-                println!("{} = {}  ({})", range.to_uppercase(), info.display, cell_type);
+                println!("{} = {}  ({})", range.to_uppercase(), util::single_line(&info.display), cell_type);
 
                 if let Some(formula) = &info.formula {
-                    println!("Formula: {}", formula);
+                    println!("Formula: {}", util::single_line(formula));
                 }
             }
             InspectResult::Range { cells } => {
@@ -4695,11 +7075,7 @@ fn cmd_inspect(
                 } else {
                     // Simple column display - one cell per line with truncation
                     for (i, cell) in cells.iter().enumerate() {
-                        let display = if cell.display.len() > 40 {
-                            format!("{}…", &cell.display[..39])
-                        } else {
-                            cell.display.clone()
-                        };
+                        let display = util::truncate_display(&cell.display, 40);
 
                         let formula_marker = if cell.formula.is_some() { " [f]" } else { "" };
                         println!("  [{}] {}{}", i, display, formula_marker);
@@ -4714,12 +7090,13 @@ fn cmd_inspect(
         }
     }
 
+    let _ = client.close();
     Ok(())
 }
 
-fn cmd_stats(session_id: Option<String>, json: bool) -> Result<(), CliError> {
+fn cmd_stats(session_id: Option<String>, json: bool, token_file: Option<PathBuf>) -> Result<(), CliError> {
     let discovery = resolve_session(session_id.as_deref())?;
-    let token = get_session_token()?;
+    let token = get_session_token(token_file.as_deref())?;
 
     let mut client = session::SessionClient::connect(&discovery, &token)
         .map_err(CliError::session)?;
@@ -4741,8 +7118,31 @@ fn cmd_stats(session_id: Option<String>, json: bool) -> Result<(), CliError> {
         println!("Refused (limit):       {}", stats.connections_refused_limit);
         println!("Parse failures:        {}", stats.connections_closed_parse_failures);
         println!("Oversize messages:     {}", stats.connections_closed_oversize);
+        println!("Graceful closes:       {}", stats.connections_closed_graceful);
+        println!("Uptime:                {}s", stats.uptime_seconds);
+        println!("Revisions:             {}", stats.revision_count);
     }
 
+    let _ = client.close();
+    Ok(())
+}
+
+fn cmd_repl(session_id: Option<String>, request_timeout: u64, token_file: Option<PathBuf>) -> Result<(), CliError> {
+    let discovery = resolve_session(session_id.as_deref())?;
+    let token = get_session_token(token_file.as_deref())?;
+
+    let mut client = session::SessionClient::connect(&discovery, &token)
+        .map_err(CliError::session)?;
+    client.set_request_timeout(std::time::Duration::from_secs(request_timeout))
+        .map_err(CliError::session)?;
+
+    let code = session::run_repl(&mut client, io::stdin().lock(), io::stdout().lock())
+        .map_err(CliError::session)?;
+    let _ = client.close();
+    if code != 0 {
+        // Each failure was already reported on its own output line
+        return Err(CliError { code, message: String::new(), hint: None });
+    }
     Ok(())
 }
 
@@ -4763,12 +7163,26 @@ fn cmd_peek_json(
     max_rows: usize,
     force: bool,
     delimiter_override: Option<String>,
+    layout: Option<FixedLayout>,
+    quiet: bool,
 ) -> Result<(), CliError> {
-    let ext = file
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
+    let ext = input_extension(&file)?.unwrap_or_default();
+
+    // --widths/--colspec: fixed-width text, whatever the extension
+    if let Some(layout) = layout {
+        let effective_max = if max_rows == 0 && !force { PEEK_FORCE_CAP + 1 } else { max_rows };
+        let data = tui::data::load_fixed(&file, &layout, headers, effective_max, 0)
+            .map_err(|e| CliError::io(e))?;
+        if !quiet {
+            warn_import_notes(&file, &data.import_notes);
+        }
+        if max_rows == 0 && !force && data.num_rows > PEEK_FORCE_CAP {
+            return Err(CliError::args(format!(
+                "file has >{}k rows; use --force to override", PEEK_FORCE_CAP / 1000,
+            )));
+        }
+        return peek_json_output(&data);
+    }
 
     // .sheet files use a completely separate path
     if ext == "sheet" {
@@ -4822,6 +7236,9 @@ fn cmd_peek_json(
     let effective_max = if max_rows == 0 && !force { PEEK_FORCE_CAP + 1 } else { max_rows };
     let data = tui::data::load_csv(&file, delimiter, headers, effective_max, 0)
         .map_err(|e| CliError::io(e))?;
+    if !quiet {
+        warn_import_notes(&file, &data.import_notes);
+    }
 
     if max_rows == 0 && !force && data.num_rows > PEEK_FORCE_CAP {
         return Err(CliError::args(format!(
@@ -4832,6 +7249,13 @@ fn cmd_peek_json(
     peek_json_output(&data)
 }
 
+/// Print tolerated-input notes (e.g. mixed line endings) as stderr warnings.
+fn warn_import_notes(file: &std::path::Path, notes: &[String]) {
+    for note in notes {
+        eprintln!("warning: {}: {}", file.display(), note);
+    }
+}
+
 /// Write PeekData as JSON to stdout: `{"columns":[...], "rows":[[...],...]}`
 fn peek_json_output(data: &tui::data::PeekData) -> Result<(), CliError> {
     use serde_json::{json, Value};
@@ -4855,25 +7279,26 @@ fn cmd_peek(
     shape: bool,
     interactive: bool,
     delimiter_override: Option<String>,
+    layout: Option<FixedLayout>,
     recompute: bool,
+    quiet: bool,
+    restore: bool,
 ) -> Result<(), CliError> {
-    let ext = file
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
+    let ext = input_extension(&file)?.unwrap_or_default();
 
     // .sheet files use a completely separate path
-    if ext == "sheet" {
-        return cmd_peek_sheet(file, sheet, max_rows, force, width_scan_rows, shape, interactive);
+    if ext == "sheet" && layout.is_none() {
+        return cmd_peek_sheet(file, sheet, max_rows, force, width_scan_rows, shape, interactive, restore);
     }
 
     // xlsx/ods use the workbook import path
-    if ext == "xlsx" || ext == "ods" {
-        return cmd_peek_workbook(file, sheet, max_rows, force, width_scan_rows, shape, interactive, recompute);
+    if (ext == "xlsx" || ext == "ods") && layout.is_none() {
+        return cmd_peek_workbook(file, sheet, max_rows, force, width_scan_rows, shape, interactive, recompute, restore);
     }
 
-    let delimiter = if let Some(ref d) = delimiter_override {
+    let delimiter = if layout.is_some() {
+        0 // fixed-width: no delimiter
+    } else if let Some(ref d) = delimiter_override {
         parse_delimiter(d)?
     } else {
         match ext.as_str() {
@@ -4899,8 +7324,13 @@ fn cmd_peek(
         max_rows
     };
 
-    let data = tui::data::load_csv(&file, delimiter, headers, effective_max, width_scan_rows)
-        .map_err(|e| CliError::io(e))?;
+    let data = match &layout {
+        Some(layout) => tui::data::load_fixed(&file, layout, headers, effective_max, width_scan_rows),
+        None => tui::data::load_csv(&file, delimiter, headers, effective_max, width_scan_rows),
+    }.map_err(|e| CliError::io(e))?;
+    if !quiet {
+        warn_import_notes(&file, &data.import_notes);
+    }
 
     if max_rows == 0 && !force && data.num_rows > PEEK_FORCE_CAP {
         return Err(CliError::args(format!(
@@ -4924,7 +7354,14 @@ fn cmd_peek(
         .unwrap_or("unknown")
         .to_string();
 
-    tui::run(data, file_name).map_err(|e| CliError::io(e))
+    // Keep reading past --max-rows as the user scrolls (CSV/TSV only)
+    let backfill = data.total_rows.filter(|_| layout.is_none()).map(|_| {
+        let skip = data.num_rows + usize::from(data.has_headers);
+        tui::backfill::CsvBackfill::spawn(file.clone(), delimiter, skip)
+    });
+
+    let persist = if restore { tui::view_state::Persist::for_file(&file, false) } else { None };
+    tui::run(data, file_name, backfill, persist).map_err(CliError::io)
 }
 
 fn cmd_peek_sheet(
@@ -4935,6 +7372,7 @@ fn cmd_peek_sheet(
     width_scan_rows: usize,
     shape: bool,
     interactive: bool,
+    restore: bool,
 ) -> Result<(), CliError> {
     // Safety cap: same pattern as CSV/workbook paths
     let effective_max = if max_rows == 0 && !force {
@@ -4990,7 +7428,8 @@ fn cmd_peek_sheet(
         .unwrap_or("unknown")
         .to_string();
 
-    tui::run_multi(sheets, file_name, initial_sheet).map_err(|e| CliError::io(e))
+    let persist = if restore { tui::view_state::Persist::for_file(&file, sheet.is_some()) } else { None };
+    tui::run_multi(sheets, file_name, initial_sheet, persist).map_err(|e| CliError::io(e))
 }
 
 fn cmd_peek_workbook(
@@ -5002,6 +7441,7 @@ fn cmd_peek_workbook(
     shape: bool,
     interactive: bool,
     recompute: bool,
+    restore: bool,
 ) -> Result<(), CliError> {
     if recompute {
         eprintln!("peek: recompute enabled; may be slow on large workbooks");
@@ -5063,7 +7503,8 @@ fn cmd_peek_workbook(
         .unwrap_or("unknown")
         .to_string();
 
-    tui::run_multi(sheets, file_name, initial_sheet).map_err(|e| CliError::io(e))
+    let persist = if restore { tui::view_state::Persist::for_file(&file, sheet.is_some()) } else { None };
+    tui::run_multi(sheets, file_name, initial_sheet, persist).map_err(|e| CliError::io(e))
 }
 
 fn cmd_peek_workbook_shape(sheets: &[tui::data::SheetData], file: &std::path::Path, ext: &str) -> Result<(), CliError> {
@@ -5140,6 +7581,7 @@ fn cmd_peek_shape(data: &tui::data::PeekData, file: &std::path::Path) -> Result<
         b',' => "comma (CSV)",
         b';' => "semicolon",
         b'|' => "pipe",
+        0 => "none (fixed-width)",
         d => &format!("'{}' (0x{:02x})", d as char, d),
     };
 
@@ -5181,12 +7623,16 @@ fn cmd_peek_shape(data: &tui::data::PeekData, file: &std::path::Path) -> Result<
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_view(
     session_id: Option<String>,
     range: String,
     sheet: usize,
     follow: bool,
     col_width: usize,
+    out: Option<PathBuf>,
+    out_format: ViewOutFormat,
+    token_file: Option<PathBuf>,
 ) -> Result<(), CliError> {
     use std::time::Duration;
     use visigrid_protocol::InspectResult;
@@ -5201,7 +7647,7 @@ fn cmd_view(
         .ok_or_else(|| CliError::args(format!("invalid cell reference: {}", end)))?;
 
     let discovery = resolve_session(session_id.as_deref())?;
-    let token = get_session_token()?;
+    let token = get_session_token(token_file.as_deref())?;
 
     // Single connection, reused for follow mode
     let mut client = session::SessionClient::connect(&discovery, &token)
@@ -5216,7 +7662,8 @@ fn cmd_view(
         let result = client.inspect_range(sheet, start_row, start_col, end_row, end_col)
             .map_err(CliError::session)?;
 
-        // Skip redraw if revision unchanged (in follow mode)
+        // Skip redraw if revision unchanged (in follow mode). Structural
+        // edits bump it as well, so inserted rows and new sheets redraw too.
         if follow {
             if last_revision == Some(result.revision) {
                 std::thread::sleep(Duration::from_millis(500));
@@ -5228,27 +7675,29 @@ fn cmd_view(
             print!("\x1B[2J\x1B[H");
         }
 
-        // Print header
-        println!(
-            "Session: {}  Sheet: {}  Range: {}  Revision: {}",
-            short_id, sheet, range, result.revision
-        );
-        println!("{}", "─".repeat(60));
-
-        // Print grid
-        match result.result {
-            InspectResult::Range { cells } => {
-                // Cells are returned in row-major order
-                print_grid_from_cells(&cells, start_row, start_col, end_row, end_col, col_width);
-            }
-            InspectResult::Cell(info) => {
-                // Single cell - just print it
-                println!("{}: {}", range.to_uppercase(), info.display);
-            }
+        // Cells are returned in row-major order; a single cell comes back on its own
+        let (cells, single) = match result.result {
+            InspectResult::Range { cells } => (cells, false),
+            InspectResult::Cell(info) => (vec![info], true),
             InspectResult::Workbook(_) => {
                 return Err(CliError::args("view requires a cell range, not 'workbook'".to_string()));
             }
-        }
+        };
+
+        let snapshot = match out_format {
+            ViewOutFormat::Table => {
+                let header = view::table_header(short_id, sheet, &range, result.revision);
+                if single {
+                    // Single cell - just print it
+                    let display = cells.first().map(|c| c.display.as_str()).unwrap_or("");
+                    format!("{}{}: {}\n", header, range.to_uppercase(), display)
+                } else {
+                    header + &view::render_table(&cells, start_row, start_col, end_row, end_col, col_width)
+                }
+            }
+            ViewOutFormat::Csv => view::render_csv(&cells, start_row, start_col, end_row, end_col)?,
+        };
+        view::emit(&snapshot, out.as_deref())?;
 
         if follow {
             println!();
@@ -5259,65 +7708,12 @@ fn cmd_view(
         }
     }
 
+    let _ = client.close();
     Ok(())
 }
 
-/// Print a grid of cells in table format.
-/// Cells are assumed to be in row-major order.
-fn print_grid_from_cells(
-    cells: &[visigrid_protocol::CellInfo],
-    start_row: usize,
-    start_col: usize,
-    end_row: usize,
-    end_col: usize,
-    col_width: usize,
-) {
-    let num_cols = end_col - start_col + 1;
-
-    // Build a map of (row, col) -> display value from flat array
-    let mut grid: std::collections::HashMap<(usize, usize), &str> = std::collections::HashMap::new();
-    for (i, cell) in cells.iter().enumerate() {
-        let row = start_row + i / num_cols;
-        let col = start_col + i % num_cols;
-        grid.insert((row, col), &cell.display);
-    }
-
-    // Print column headers
-    print!("{:>5} ", ""); // Row number column
-    for col in start_col..=end_col {
-        let col_name = col_to_letter(col);
-        print!("{:^width$}", col_name, width = col_width);
-    }
-    println!();
-
-    // Print separator
-    print!("{:─>5}─", "");
-    for _ in start_col..=end_col {
-        print!("{:─>width$}", "", width = col_width);
-    }
-    println!();
-
-    // Print rows
-    for row in start_row..=end_row {
-        print!("{:>5} ", row + 1); // 1-indexed row numbers
-        for col in start_col..=end_col {
-            let value = grid.get(&(row, col)).map(|s| *s).unwrap_or("");
-            let display = truncate_display(value, col_width);
-            print!("{:>width$}", display, width = col_width);
-        }
-        println!();
-    }
-}
-
-fn truncate_display(s: &str, width: usize) -> String {
-    util::truncate_display(s, width)
-}
-
-fn col_to_letter(col: usize) -> String {
-    util::col_to_letter(col)
-}
-
-/// Resolve session by ID (prefix match), or auto-select if only one session.
+/// Resolve session by ID (prefix match), or auto-select the only session
+/// (or the only one whose workbook is under the current directory).
 fn resolve_session(session_id: Option<&str>) -> Result<session::DiscoveryFile, CliError> {
     let sessions = session::list_sessions()
         .map_err(|e| CliError::io(format!("failed to list sessions: {}", e)))?;
@@ -5335,21 +7731,24 @@ fn resolve_session(session_id: Option<&str>) -> Result<session::DiscoveryFile, C
                     .with_hint("use 'visigrid sessions' to list available sessions"))
         }
         None => {
-            if sessions.len() == 1 {
-                Ok(sessions.into_iter().next().unwrap())
-            } else {
-                Err(CliError::args(format!("{} sessions found; specify --session", sessions.len()))
-                    .with_hint("use 'visigrid sessions' to list available sessions"))
-            }
+            let count = sessions.len();
+            let cwd = std::env::current_dir().ok();
+            session::auto_select_session(sessions, cwd.as_deref()).ok_or_else(|| {
+                CliError::args(format!("{} sessions found; specify --session", count))
+                    .with_hint("use 'visigrid sessions' to list available sessions, or run from the workbook's directory")
+            })
         }
     }
 }
 
-/// Get session token from environment variable.
-fn get_session_token() -> Result<String, CliError> {
-    std::env::var("VISIGRID_SESSION_TOKEN")
-        .map_err(|_| CliError::args("VISIGRID_SESSION_TOKEN environment variable not set")
-            .with_hint("copy the token from VisiGrid GUI session panel and set: export VISIGRID_SESSION_TOKEN=xxx"))
+/// Get session token from --token-file, VISIGRID_SESSION_TOKEN, or VISIGRID_SESSION_TOKEN_FILE.
+fn get_session_token(token_file: Option<&Path>) -> Result<String, CliError> {
+    let env_token = std::env::var(session::TOKEN_ENV).ok();
+    let env_token_file = std::env::var_os(session::TOKEN_FILE_ENV).map(PathBuf::from);
+    session::resolve_token(token_file, env_token, env_token_file)
+        .map_err(CliError::args)?
+        .ok_or_else(|| CliError::args("VISIGRID_SESSION_TOKEN environment variable not set")
+            .with_hint("copy the token from VisiGrid GUI session panel and set: export VISIGRID_SESSION_TOKEN=xxx (or pass --token-file)"))
 }
 
 // =============================================================================
@@ -5357,18 +7756,29 @@ fn get_session_token() -> Result<String, CliError> {
 // =============================================================================
 
 /// Build a .sheet file from a Lua script.
+#[allow(clippy::too_many_arguments)]
 fn cmd_sheet_apply(
     output: PathBuf,
     lua_path: PathBuf,
     verify: Option<String>,
     stamp: Option<String>,
     dry_run: bool,
+    emit_patch: Option<PathBuf>,
+    backup: Option<Option<PathBuf>>,
     json: bool,
+    protected: Vec<String>,
+    checks: Vec<String>,
+    meta: visigrid_io::native::WorkbookMeta,
 ) -> Result<(), CliError> {
-    use visigrid_io::native::{compute_semantic_fingerprint, save_workbook_with_metadata, save_semantic_verification, SemanticVerification};
+    use visigrid_io::native::{compute_semantic_fingerprint, lock_for_write, write_workbook_with_metadata, save_semantic_verification, save_workbook_meta, SemanticVerification};
+
+    let checks = sheet_ops::parse_checks(&checks)?;
 
     // Execute the build script
-    let result = sheet_ops::execute_build_script(&lua_path, verify.as_deref())?;
+    let mut result = sheet_ops::execute_build_script(&lua_path, verify.as_deref())?;
+    if let Some(sheet) = result.workbook.sheet_mut(0) {
+        sheet_ops::protect_ranges(sheet, &protected)?;
+    }
 
     // Check verification if requested
     if let Some(verified) = result.verified {
@@ -5396,34 +7806,85 @@ fn cmd_sheet_apply(
         }
     }
 
+    // Post-build assertions: a failing check aborts before anything is written
+    let check_results = sheet_ops::evaluate_checks(&result.workbook, &checks);
+    let failed: Vec<&sheet_ops::CheckResult> = check_results.iter().filter(|c| !c.passed).collect();
+    if !failed.is_empty() {
+        if json {
+            let output_json = serde_json::json!({
+                "ok": false,
+                "error": "check_failed",
+                "checks": check_results,
+                "semantic_ops": result.semantic_ops,
+                "style_ops": result.style_ops,
+                "cells_changed": result.cells_changed,
+            });
+            println!("{}", serde_json::to_string_pretty(&output_json).unwrap());
+        } else {
+            for check in &failed {
+                eprintln!("Check failed: {}", check.check);
+                if check.operands.is_empty() {
+                    eprintln!("  Value: {}", check.value);
+                }
+                for (operand, value) in &check.operands {
+                    eprintln!("  {} = {}", operand, value);
+                }
+            }
+        }
+        return Err(CliError {
+            code: EXIT_EVAL_ERROR,
+            message: format!("{} of {} checks failed", failed.len(), check_results.len()),
+            hint: None,
+        });
+    }
+
     // Write output (unless dry-run)
     let stamped = stamp.is_some();
+    let mut backup_path = None;
     if !dry_run {
+        let lock = lock_for_write(&output).map_err(CliError::io)?;
+        if let Some(dir) = &backup {
+            backup_path = util::backup_file(&output, dir.as_deref())?;
+        }
+
         // Atomic write: write to temp file first, then rename
-        let temp_path = output.with_extension("sheet.tmp");
+        lock.replace(|temp_path| {
+            write_workbook_with_metadata(&result.workbook, &result.metadata, temp_path)
+                .map_err(|e| format!("failed to write temp file: {}", e))?;
+
+            // If --stamp was provided, write semantic verification info to the file
+            if let Some(label) = &stamp {
+                let semantic_fp = compute_semantic_fingerprint(&result.workbook);
+                let verification = SemanticVerification {
+                    fingerprint: Some(semantic_fp),
+                    label: if label.is_empty() { None } else { Some(label.clone()) },
+                    timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                };
+                save_semantic_verification(temp_path, &verification)
+                    .map_err(|e| format!("failed to write verification: {}", e))?;
+            }
 
-        save_workbook_with_metadata(&result.workbook, &result.metadata, &temp_path)
-            .map_err(|e| CliError::io(format!("failed to write temp file: {}", e)))?;
+            save_workbook_meta(temp_path, &meta)
+                .map_err(|e| format!("failed to write workbook metadata: {}", e))
+        })
+        .map_err(CliError::io)?;
+    }
 
-        // If --stamp was provided, write semantic verification info to the file
-        if let Some(label) = &stamp {
-            let semantic_fp = compute_semantic_fingerprint(&result.workbook);
-            let verification = SemanticVerification {
-                fingerprint: Some(semantic_fp),
-                label: if label.is_empty() { None } else { Some(label.clone()) },
-                timestamp: Some(chrono::Utc::now().to_rfc3339()),
-            };
-            save_semantic_verification(&temp_path, &verification)
-                .map_err(|e| CliError::io(format!("failed to write verification: {}", e)))?;
+    // The patch is an audit artifact, so it's written even on --dry-run
+    let patch_ops = match &emit_patch {
+        Some(path) => {
+            let patch = sheet_ops::build_patch(&result);
+            let text = serde_json::to_string_pretty(&patch).unwrap();
+            std::fs::write(path, text + "\n")
+                .map_err(|e| CliError::io(format!("failed to write patch {}: {}", path.display(), e)))?;
+            Some(patch.len())
         }
-
-        std::fs::rename(&temp_path, &output)
-            .map_err(|e| CliError::io(format!("failed to rename to output: {}", e)))?;
-    }
+        None => None,
+    };
 
     // Output result
     if json {
-        let output_json = serde_json::json!({
+        let mut output_json = serde_json::json!({
             "ok": true,
             "fingerprint": result.fingerprint.to_string(),
             "stamped": stamped,
@@ -5432,7 +7893,12 @@ fn cmd_sheet_apply(
             "cells_changed": result.cells_changed,
             "dry_run": dry_run,
             "output": if dry_run { None } else { Some(output.display().to_string()) },
+            "patch": emit_patch.as_ref().map(|p| p.display().to_string()),
+            "backup": backup_path.as_ref().map(|p| p.display().to_string()),
         });
+        if !check_results.is_empty() {
+            output_json["checks"] = serde_json::json!(check_results);
+        }
         println!("{}", serde_json::to_string_pretty(&output_json).unwrap());
     } else {
         if dry_run {
@@ -5451,11 +7917,34 @@ fn cmd_sheet_apply(
         println!("Semantic ops: {}", result.semantic_ops);
         println!("Style ops:    {}", result.style_ops);
         println!("Cells:        {}", result.cells_changed);
+        if let (Some(path), Some(n)) = (&emit_patch, patch_ops) {
+            println!("Patch:        {} ({} ops)", path.display(), n);
+        }
+        if let Some(path) = &backup_path {
+            println!("Backup:       {}", path.display());
+        }
+        if !check_results.is_empty() {
+            println!("Checks:       {} passed", check_results.len());
+        }
     }
 
     Ok(())
 }
 
+/// Build workbook metadata from `--title`/`--description`/`--author`,
+/// stamping `created_at` when any of them is given.
+fn workbook_meta_args(
+    title: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+) -> visigrid_io::native::WorkbookMeta {
+    let mut meta = visigrid_io::native::WorkbookMeta { title, description, author, ..Default::default() };
+    if !meta.is_empty() {
+        meta.created_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+    meta
+}
+
 /// Resolve a `--sheet` argument to (index, &Sheet).
 ///
 /// - `None` → sheet 0
@@ -5478,8 +7967,59 @@ fn resolve_sheet<'a>(
     }
 }
 
+/// Non-empty cell count and 1-based (max_row, max_col) bounds of a sheet.
+fn sheet_cell_counts(sheet: &visigrid_engine::sheet::Sheet) -> (usize, usize, usize) {
+    let mut non_empty_cells = 0usize;
+    let mut max_row = 0usize;
+    let mut max_col = 0usize;
+    for (&(r, c), cell) in sheet.cells_iter() {
+        if !cell.value.raw_display().is_empty() {
+            non_empty_cells += 1;
+            if r + 1 > max_row { max_row = r + 1; }
+            if c + 1 > max_col { max_col = c + 1; }
+        }
+    }
+    (non_empty_cells, max_row, max_col)
+}
+
+fn print_sheet_count(count: &sheet_ops::SheetCount, json: bool) {
+    if json {
+        println!("{}", serde_json::to_string(count).unwrap());
+    } else {
+        println!("{:?}: {} cells, {}x{}", count.sheet, count.non_empty_cells, count.max_row, count.max_col);
+    }
+}
+
 // ── Lightweight inspect helpers ─────────────────────────────────────────
 
+fn cmd_sheet_inspect_count_lightweight(file: &Path, sheet_arg: Option<&str>, json: bool) -> Result<(), CliError> {
+    let sheets = visigrid_io::native::inspect_sheets_lightweight(file)
+        .map_err(|e| CliError::io(format!("failed to inspect {}: {}", file.display(), e)))?;
+
+    let found = match sheet_arg {
+        None => sheets.first(),
+        Some(arg) => match arg.parse::<usize>() {
+            Ok(idx) => sheets.iter().find(|s| s.sheet_idx == idx),
+            Err(_) => {
+                let lower = arg.to_ascii_lowercase();
+                sheets.iter().find(|s| s.name.to_ascii_lowercase() == lower)
+            }
+        },
+    };
+    let s = found.ok_or_else(|| match sheet_arg {
+        Some(arg) => CliError::args(format!("sheet not found: {}", arg)),
+        None => CliError::io("no sheets in workbook"),
+    })?;
+
+    print_sheet_count(&sheet_ops::SheetCount {
+        sheet: s.name.clone(),
+        non_empty_cells: s.non_empty_cells,
+        max_row: s.max_row,
+        max_col: s.max_col,
+    }, json);
+    Ok(())
+}
+
 fn cmd_sheet_inspect_sheets_lightweight(file: &Path, json: bool, ndjson: bool) -> Result<(), CliError> {
     let sheets = visigrid_io::native::inspect_sheets_lightweight(file)
         .map_err(|e| CliError::io(format!("failed to inspect {}: {}", file.display(), e)))?;
@@ -5598,6 +8138,8 @@ fn cmd_sheet_inspect_range_lightweight(
                 format: None,
                 header: hdr,
                 column_name: col_name,
+                comment: None,
+                conditional_formats: Vec::new(),
             }
         })
         .collect();
@@ -5621,22 +8163,134 @@ fn cmd_sheet_inspect_range_lightweight(
             range: Some(target_str.to_uppercase()),
             cells: cell_results,
         };
-        println!("{}", serde_json::to_string_pretty(&result).unwrap());
-    } else {
-        let sheets = visigrid_io::native::inspect_sheets_lightweight(file)
-            .unwrap_or_default();
-        let sheet_name = sheets.iter()
-            .find(|s| s.sheet_idx == sheet_idx)
-            .map(|s| s.name.clone())
-            .unwrap_or_else(|| format!("Sheet{}", sheet_idx + 1));
+        println!("{}", serde_json::to_string_pretty(&result).unwrap());
+    } else {
+        let sheets = visigrid_io::native::inspect_sheets_lightweight(file)
+            .unwrap_or_default();
+        let sheet_name = sheets.iter()
+            .find(|s| s.sheet_idx == sheet_idx)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|| format!("Sheet{}", sheet_idx + 1));
+
+        println!("Sheet [{}] {:?}  range {}  ({} cells)",
+            sheet_idx, sheet_name, target_str.to_uppercase(), cell_results.len());
+        for cell in &cell_results {
+            let formula_marker = if cell.formula.is_some() { " [f]" } else { "" };
+            println!("  {} = {}{}", cell.cell, cell.value, formula_marker);
+        }
+    }
+    Ok(())
+}
+
+/// Workbook metadata block, or `None` when unset. An unreadable block is
+/// reported on stderr and left out rather than failing the inspect.
+fn load_workbook_meta_opt(file: &Path) -> Option<visigrid_io::native::WorkbookMeta> {
+    match visigrid_io::native::load_workbook_meta(file) {
+        Ok(meta) => Some(meta).filter(|m| !m.is_empty()),
+        Err(e) => {
+            eprintln!("warning: {}: cannot read workbook metadata: {}", file.display(), e);
+            None
+        }
+    }
+}
+
+fn print_workbook_meta(meta: Option<&visigrid_io::native::WorkbookMeta>) {
+    let Some(meta) = meta else { return };
+    if let Some(ref title) = meta.title {
+        println!("Title:       {}", util::single_line(title));
+    }
+    if let Some(ref description) = meta.description {
+        println!("Description: {}", util::single_line(description));
+    }
+    if let Some(ref author) = meta.author {
+        println!("Author:      {}", author);
+    }
+    if let Some(ref created_at) = meta.created_at {
+        println!("Created:     {}", created_at);
+    }
+    if !meta.tags.is_empty() {
+        println!("Tags:        {}", meta.tags.join(", "));
+    }
+}
+
+/// `sheet inspect FILE --diff-with OTHER`: semantic diff of two .sheet files,
+/// with OTHER as the baseline.
+fn cmd_sheet_inspect_diff(file: &Path, other: &Path, json: bool) -> Result<(), CliError> {
+    use visigrid_io::native::{compute_semantic_fingerprint, load_workbook, named_range_fingerprint_ops};
+
+    let load = |path: &Path| load_workbook(path)
+        .map_err(|e| CliError::io(format!("failed to load {}: {}", path.display(), e)));
+    let new = load(file)?;
+    let old = load(other)?;
+
+    let sheets = sheet_ops::diff_workbooks(&old, &new);
+    let old_fp = compute_semantic_fingerprint(&old);
+    let new_fp = compute_semantic_fingerprint(&new);
+
+    // Attribute a fingerprint change to the inputs it hashes
+    let mut reasons = Vec::new();
+    if old_fp != new_fp {
+        let cells_changed = sheets.iter().any(|d| {
+            !d.added.is_empty() || !d.removed.is_empty() || d.changed.iter().any(|c| c.kind != Some("result"))
+        });
+        if cells_changed {
+            reasons.push("cells");
+        }
+        if named_range_fingerprint_ops(&old) != named_range_fingerprint_ops(&new) {
+            reasons.push("named_ranges");
+        }
+        let iteration = |wb: &visigrid_engine::workbook::Workbook| {
+            (wb.iterative_enabled(), wb.iterative_max_iters(), wb.iterative_tolerance())
+        };
+        if iteration(&old) != iteration(&new) {
+            reasons.push("iteration_settings");
+        }
+    }
 
-        println!("Sheet [{}] {:?}  range {}  ({} cells)",
-            sheet_idx, sheet_name, target_str.to_uppercase(), cell_results.len());
-        for cell in &cell_results {
-            let formula_marker = if cell.formula.is_some() { " [f]" } else { "" };
-            println!("  {} = {}{}", cell.cell, cell.value, formula_marker);
+    let count = |f: fn(&sheet_ops::SheetDiff) -> usize| sheets.iter().map(f).sum::<usize>();
+    let (added, removed, changed) = (count(|d| d.added.len()), count(|d| d.removed.len()), count(|d| d.changed.len()));
+
+    if json {
+        let output = serde_json::json!({
+            "file": file.display().to_string(),
+            "diff_with": other.display().to_string(),
+            "fingerprint": {
+                "old": old_fp,
+                "new": new_fp,
+                "changed": old_fp != new_fp,
+                "reasons": reasons,
+            },
+            "summary": { "added": added, "removed": removed, "changed": changed },
+            "sheets": sheets,
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        return Ok(());
+    }
+
+    if old_fp == new_fp {
+        println!("Fingerprint: {} (unchanged)", new_fp);
+    } else {
+        println!("Fingerprint: {} -> {} ({})", old_fp, new_fp, reasons.join(", "));
+    }
+    println!("Cells:       {} added, {} removed, {} changed", added, removed, changed);
+    for diff in &sheets {
+        println!();
+        match diff.status {
+            "changed" => println!("{}", diff.sheet),
+            status => println!("{} ({})", diff.sheet, status),
+        }
+        let text = |v: &Option<String>| v.clone().unwrap_or_default();
+        for c in &diff.added {
+            println!("  + {:<8} {}", c.cell, text(&c.new));
+        }
+        for c in &diff.removed {
+            println!("  - {:<8} {}", c.cell, text(&c.old));
+        }
+        for c in &diff.changed {
+            println!("  ~ {:<8} {:<8} {} -> {}", c.cell, c.kind.unwrap_or_default(), text(&c.old), text(&c.new));
         }
     }
+
     Ok(())
 }
 
@@ -5651,6 +8305,8 @@ fn cmd_sheet_inspect_workbook_lightweight(file: &Path, json: bool) -> Result<(),
         format: None,
         path: Some(file.display().to_string()),
         import_notes: Some(vec!["lightweight mode: fingerprint skipped".to_string()]),
+        ragged_rows: None,
+        meta: load_workbook_meta_opt(file),
     };
 
     if json {
@@ -5659,6 +8315,7 @@ fn cmd_sheet_inspect_workbook_lightweight(file: &Path, json: bool) -> Result<(),
         println!("File:        {}", file.display());
         println!("Sheets:      {}", result.sheet_count);
         println!("Cells:       {}", result.cell_count);
+        print_workbook_meta(result.meta.as_ref());
         println!("Note:        lightweight mode (fingerprint skipped)");
     }
     Ok(())
@@ -5673,22 +8330,45 @@ fn cmd_sheet_inspect(
     sheets_mode: bool,
     non_empty: bool,
     include_style: bool,
+    include_comments: bool,
+    include_conditional: bool,
     value_only: bool,
+    count: bool,
     json: bool,
     ndjson: bool,
     format_override: Option<InspectFormat>,
     headers: bool,
+    transpose: bool,
     delimiter: Option<String>,
+    layout: Option<FixedLayout>,
     calc: Vec<String>,
+    calc_policy: sheet_ops::CalcPolicy,
+    round_mode: visigrid_engine::formula::rounding::RoundMode,
     lightweight: bool,
+    diff_with: Option<PathBuf>,
+    quiet: bool,
 ) -> Result<(), CliError> {
     // Phase A: Resolve format & validate
     let fmt = match format_override {
         Some(f) => f,
+        None if layout.is_some() => InspectFormat::Fixed,
         None => infer_inspect_format(&file)?,
     };
 
-    if sheet_arg.is_some() && matches!(fmt, InspectFormat::Csv | InspectFormat::Tsv) {
+    if let Some(other) = diff_with {
+        if target.is_some() || workbook_mode || sheets_mode || count || lightweight || value_only
+            || ndjson || sheet_arg.is_some() || !calc.is_empty() {
+            return Err(CliError::args("--diff-with compares whole workbooks")
+                .with_hint("it cannot be combined with a target, --sheet, --workbook, --sheets, --count, --calc, --value, --lightweight, or --ndjson"));
+        }
+        if !matches!(fmt, InspectFormat::Sheet) || !matches!(infer_inspect_format(&other)?, InspectFormat::Sheet) {
+            return Err(CliError::args("--diff-with only works with .sheet files")
+                .with_hint("import first: vgrid sheet import data.xlsx model.sheet"));
+        }
+        return cmd_sheet_inspect_diff(&file, &other, json);
+    }
+
+    if sheet_arg.is_some() && matches!(fmt, InspectFormat::Csv | InspectFormat::Tsv | InspectFormat::Fixed) {
         return Err(CliError::args("--sheet is not valid for CSV/TSV (single-sheet source)"));
     }
 
@@ -5706,9 +8386,22 @@ fn cmd_sheet_inspect(
         if include_style {
             return Err(CliError::args("--calc cannot be used with --include-style"));
         }
+        if include_comments {
+            return Err(CliError::args("--calc cannot be used with --include-comments"));
+        }
+        if include_conditional {
+            return Err(CliError::args("--calc cannot be used with --include-conditional"));
+        }
         if ndjson {
             return Err(CliError::args("--calc cannot be used with --ndjson"));
         }
+    } else if calc_policy.fail_on_empty {
+        return Err(CliError::args("--fail-on-empty requires --calc"));
+    }
+
+    if transpose && (target.is_none() || workbook_mode || sheets_mode || non_empty || count || lightweight || !calc.is_empty()) {
+        return Err(CliError::args("--transpose requires a dense range target (e.g. A1:C2)")
+            .with_hint("--transpose cannot be combined with --workbook, --sheets, --non-empty, --count, --lightweight, or --calc"));
     }
 
     if value_only {
@@ -5720,6 +8413,22 @@ fn cmd_sheet_inspect(
         }
     }
 
+    if count {
+        if target.is_some() || workbook_mode || sheets_mode || non_empty {
+            return Err(CliError::args("--count cannot be combined with a target, --workbook, --sheets, or --non-empty"));
+        }
+        if !calc.is_empty() || value_only || include_style || include_comments || include_conditional {
+            return Err(CliError::args("--count cannot be combined with --calc, --value, --include-style, --include-comments, or --include-conditional"));
+        }
+        if ndjson {
+            return Err(CliError::args("--count cannot be used with --ndjson (use --json)"));
+        }
+        // .sheet: count in SQLite, no load or recompute
+        if matches!(fmt, InspectFormat::Sheet) {
+            return cmd_sheet_inspect_count_lightweight(&file, sheet_arg.as_deref(), json);
+        }
+    }
+
     // Lightweight mode: query SQLite directly, skip full workbook load
     if lightweight {
         if !matches!(fmt, InspectFormat::Sheet) {
@@ -5731,6 +8440,12 @@ fn cmd_sheet_inspect(
         if include_style {
             return Err(CliError::args("--lightweight cannot be used with --include-style"));
         }
+        if include_comments {
+            return Err(CliError::args("--lightweight cannot be used with --include-comments"));
+        }
+        if include_conditional {
+            return Err(CliError::args("--lightweight cannot be used with --include-conditional"));
+        }
         if value_only {
             return Err(CliError::args("--lightweight cannot be used with --value"));
         }
@@ -5746,7 +8461,8 @@ fn cmd_sheet_inspect(
 
     // Phase B: Load workbook by format
     // Note: load_workbook() already calls rebuild_dep_graph() + recompute_full_ordered()
-    let (workbook, is_native, import_notes, formula_map) = match fmt {
+    let mut ragged_rows = None;
+    let (mut workbook, is_native, import_notes, formula_map) = match fmt {
         InspectFormat::Sheet => {
             let wb = visigrid_io::native::load_workbook(&file)
                 .map_err(|e| CliError::io(format!("failed to load {}: {}", file.display(), e)))?;
@@ -5766,26 +8482,39 @@ fn cmd_sheet_inspect(
             for w in &result.warnings { notes.push(w.clone()); }
             (wb, false, notes, result.formula_strings)
         }
-        InspectFormat::Csv => {
-            let sheet = if let Some(ref d) = delimiter {
-                let delim = parse_delimiter(d)?;
-                visigrid_io::csv::import_with_delimiter(&file, delim)
-                    .map_err(|e| CliError::parse(e))?
-            } else {
-                visigrid_io::csv::import(&file)
-                    .map_err(|e| CliError::parse(e))?
+        InspectFormat::Csv | InspectFormat::Tsv => {
+            let delim = match (fmt, delimiter.as_deref()) {
+                (InspectFormat::Tsv, _) => Some(b'\t'),
+                (_, Some(d)) => Some(parse_delimiter(d)?),
+                _ => None,
             };
-            let wb = visigrid_engine::workbook::Workbook::from_sheets(vec![sheet], 0);
-            (wb, false, vec![], HashMap::new())
-        }
-        InspectFormat::Tsv => {
-            let sheet = visigrid_io::csv::import_tsv(&file)
+            let (sheet, notes, ragged) = visigrid_io::csv::import_with_notes(&file, delim)
                 .map_err(|e| CliError::parse(e))?;
+            if !quiet {
+                warn_import_notes(&file, &notes);
+            }
+            ragged_rows = ragged;
             let wb = visigrid_engine::workbook::Workbook::from_sheets(vec![sheet], 0);
-            (wb, false, vec![], HashMap::new())
+            (wb, false, notes, HashMap::new())
+        }
+        InspectFormat::Fixed => {
+            let layout = layout.as_ref().ok_or_else(fixed_layout_required_error)?;
+            let text = visigrid_io::csv::read_file_as_utf8(&file).map_err(CliError::parse)?;
+            let notes: Vec<String> = visigrid_io::csv::line_ending_note(&text).into_iter().collect();
+            if !quiet {
+                warn_import_notes(&file, &notes);
+            }
+            let wb = visigrid_engine::workbook::Workbook::from_sheets(vec![parse_fixed(&text, layout, false)], 0);
+            (wb, false, notes, HashMap::new())
         }
     };
 
+    // Loading recomputed stored formulas half-up; redo them under another --round-mode
+    if round_mode != workbook.round_mode() {
+        workbook.set_round_mode(round_mode);
+        workbook.recompute_full_ordered();
+    }
+
     // --calc: evaluate formulas against loaded data, output JSON, early return
     if !calc.is_empty() {
         let (sheet_idx, sheet) = resolve_sheet(&workbook, sheet_arg.as_deref())?;
@@ -5802,40 +8531,17 @@ fn cmd_sheet_inspect(
         // Normalization: trim + to_ascii_lowercase. Duplicate keys are an error.
         let header_map: HashMap<String, String> = if headers {
             let (_, max_col) = get_data_bounds(sheet);
-            let mut map: HashMap<String, String> = HashMap::new();
-            let mut originals: HashMap<String, (String, usize)> = HashMap::new(); // key → (original, col)
-            for col_idx in 0..max_col {
-                let val = sheet.get_display(0, col_idx);
-                if !val.is_empty() {
-                    let key = val.trim().to_ascii_lowercase();
-                    let col_letter = col_to_letter(col_idx);
-                    let col_ref = format!("{}:{}", col_letter, col_letter);
-                    if let Some((prev_orig, prev_col)) = originals.get(&key) {
-                        return Err(CliError::args(format!(
-                            "ambiguous header: column {} ({:?}) and column {} ({:?}) both normalize to {:?}",
-                            col_to_letter(*prev_col), prev_orig, col_letter, val.trim(), key
-                        )));
-                    }
-                    originals.insert(key.clone(), (val.trim().to_string(), col_idx));
-                    map.insert(key, col_ref);
-                }
-            }
-            map
+            sheet_ops::header_column_map((0..max_col).map(|col| (col, sheet.get_display(0, col))))?
         } else {
             HashMap::new()
         };
 
         let lookup = visigrid_engine::workbook::WorkbookLookup::new(&workbook, sheet_id);
         let mut results: Vec<sheet_ops::CalcResult> = Vec::new();
-        let mut any_error = false;
+        let mut any_failed = false;
 
         for expr_str in &calc {
-            let with_eq = if expr_str.starts_with('=') {
-                expr_str.clone()
-            } else {
-                format!("={}", expr_str)
-            };
-            let resolved = resolve_header_refs(&with_eq, &header_map);
+            let resolved = resolve_header_refs(&sheet_ops::with_eq(expr_str), &header_map);
             let formula_str = translate_column_refs(&resolved, start_row1, end_row1);
 
             let result = match visigrid_engine::formula::parser::parse(&formula_str) {
@@ -5844,7 +8550,6 @@ fn cmd_sheet_inspect(
                     let eval = visigrid_engine::formula::eval::evaluate(&bound, &lookup);
                     let display = eval.to_text();
                     let is_error = matches!(eval, visigrid_engine::formula::eval::EvalResult::Error(_));
-                    if is_error { any_error = true; }
                     let value_type = match &eval {
                         visigrid_engine::formula::eval::EvalResult::Number(_) => "number",
                         visigrid_engine::formula::eval::EvalResult::Text(_) => "text",
@@ -5858,18 +8563,21 @@ fn cmd_sheet_inspect(
                         value: display.clone(),
                         value_type: value_type.to_string(),
                         error: if is_error { Some(display) } else { None },
+                        failed: calc_policy.fails(value_type),
                     }
                 }
                 Err(e) => {
-                    any_error = true;
                     sheet_ops::CalcResult {
                         expr: expr_str.clone(),
                         value: format!("#PARSE: {}", e),
                         value_type: "error".to_string(),
                         error: Some(e.to_string()),
+                        // A --calc that doesn't parse fails under any policy
+                        failed: true,
                     }
                 }
             };
+            if result.failed { any_failed = true; }
             results.push(result);
         }
 
@@ -5878,16 +8586,18 @@ fn cmd_sheet_inspect(
             InspectFormat::Xlsx => "xlsx",
             InspectFormat::Csv => "csv",
             InspectFormat::Tsv => "tsv",
+            InspectFormat::Fixed => "fixed",
         };
         let output = sheet_ops::CalcOutput {
             format: format_name.to_string(),
             sheet: sheet.name.clone(),
+            policy: calc_policy,
             results,
         };
 
         println!("{}", serde_json::to_string_pretty(&output).unwrap());
 
-        if any_error {
+        if any_failed {
             return Err(CliError { code: EXIT_EVAL_ERROR, message: String::new(), hint: None });
         }
         return Ok(());
@@ -5898,6 +8608,7 @@ fn cmd_sheet_inspect(
         InspectFormat::Xlsx => Some("xlsx"),
         InspectFormat::Csv => Some("csv"),
         InspectFormat::Tsv => Some("tsv"),
+        InspectFormat::Fixed => Some("fixed"),
         InspectFormat::Sheet => None,
     };
 
@@ -5911,24 +8622,38 @@ fn cmd_sheet_inspect(
         }
     };
 
+    // Comment for a cell, only when --include-comments is set
+    let comment_at = |sheet: &visigrid_engine::sheet::Sheet, row: usize, col: usize| {
+        if include_comments { sheet.comment(row, col).cloned() } else { None }
+    };
+
+    // Conditional formats covering a cell, only when --include-conditional is set
+    let conditional_at = |sheet: &visigrid_engine::sheet::Sheet, row: usize, col: usize| {
+        if include_conditional { sheet.conditional_formats_at(row, col).cloned().collect() } else { Vec::new() }
+    };
+
     // Build header names if --headers is active and output is JSON/NDJSON
     let use_headers = headers && (json || ndjson);
 
+    // --count: non-empty cells and bounds of one sheet
+    if count {
+        let (_, s) = resolve_sheet(&workbook, sheet_arg.as_deref())?;
+        let (non_empty_cells, max_row, max_col) = sheet_cell_counts(s);
+        print_sheet_count(&sheet_ops::SheetCount {
+            sheet: s.name.clone(),
+            non_empty_cells,
+            max_row,
+            max_col,
+        }, json);
+        return Ok(());
+    }
+
     // --sheets: list all sheets
     if sheets_mode {
         let mut entries = Vec::new();
         for i in 0..workbook.sheet_count() {
             let s = workbook.sheet(i).unwrap();
-            let mut non_empty_cells = 0usize;
-            let mut max_row = 0usize;
-            let mut max_col = 0usize;
-            for (&(r, c), cell) in s.cells_iter() {
-                if !cell.value.raw_display().is_empty() {
-                    non_empty_cells += 1;
-                    if r + 1 > max_row { max_row = r + 1; }
-                    if c + 1 > max_col { max_col = c + 1; }
-                }
-            }
+            let (non_empty_cells, max_row, max_col) = sheet_cell_counts(s);
             entries.push(sheet_ops::SheetListEntry {
                 index: i,
                 name: s.name.clone(),
@@ -5976,6 +8701,8 @@ fn cmd_sheet_inspect(
                 format: None,
                 path: None,
                 import_notes: None,
+                ragged_rows: None,
+                meta: load_workbook_meta_opt(&file),
             }
         } else {
             sheet_ops::WorkbookInspectResult {
@@ -5985,6 +8712,8 @@ fn cmd_sheet_inspect(
                 format: format_label.map(|s| s.to_string()),
                 path: Some(file.display().to_string()),
                 import_notes: if import_notes.is_empty() { None } else { Some(import_notes.clone()) },
+                ragged_rows: ragged_rows.clone(),
+                meta: None,
             }
         };
 
@@ -6000,6 +8729,7 @@ fn cmd_sheet_inspect(
             }
             println!("Sheets:      {}", result.sheet_count);
             println!("Cells:       {}", result.cell_count);
+            print_workbook_meta(result.meta.as_ref());
             if let Some(ref notes) = result.import_notes {
                 for note in notes {
                     println!("Note:        {}", note);
@@ -6024,10 +8754,10 @@ fn cmd_sheet_inspect(
             if raw_str.is_empty() { continue; }
             let display = sheet.get_display(row, col);
             let value_type = if is_native {
-                classify_value_type(&raw_str, &display)
+                classify_value_type(&cell.value, &display)
             } else {
                 // For foreign formats, check formula_map for formula classification
-                if formula_map.contains_key(&(idx, row, col)) { "formula" } else { classify_value_type(&raw_str, &display) }
+                if formula_map.contains_key(&(idx, row, col)) { "formula" } else { classify_value_type(&cell.value, &display) }
             };
             let formula = get_formula(sheet, idx, row, col);
 
@@ -6048,6 +8778,8 @@ fn cmd_sheet_inspect(
                 format: None,
                 header: hdr,
                 column_name: col_name,
+                comment: comment_at(sheet, row, col),
+                conditional_formats: conditional_at(sheet, row, col),
             }));
         }
         cells.sort_by_key(|((r, c), _)| (*r, *c));
@@ -6073,6 +8805,12 @@ fn cmd_sheet_inspect(
                 for cell in &result.cells {
                     let formula_marker = if cell.formula.is_some() { " [f]" } else { "" };
                     println!("  {} = {}{}", cell.cell, cell.value, formula_marker);
+                    if let Some(c) = &cell.comment {
+                        println!("      Comment: {}", sheet_ops::comment_label(c));
+                    }
+                    for cf in &cell.conditional_formats {
+                        println!("      Conditional: {}", sheet_ops::conditional_label(cf));
+                    }
                 }
             }
         }
@@ -6123,9 +8861,9 @@ fn cmd_sheet_inspect(
                 if raw.is_empty() { continue; }
                 let display = sheet.get_display(row, col);
                 let value_type = if is_native {
-                    classify_value_type(&raw, &display)
+                    classify_value_type(&sheet.get_cell(row, col).value, &display)
                 } else {
-                    if formula_map.contains_key(&(sheet_idx, row, col)) { "formula" } else { classify_value_type(&raw, &display) }
+                    if formula_map.contains_key(&(sheet_idx, row, col)) { "formula" } else { classify_value_type(&sheet.get_cell(row, col).value, &display) }
                 };
                 let formula = get_formula(sheet, sheet_idx, row, col);
                 let cell_result = enrich_headers(row, col, sheet_ops::CellInspectResult {
@@ -6136,6 +8874,8 @@ fn cmd_sheet_inspect(
                     format: None,
                     header: None,
                     column_name: None,
+                    comment: comment_at(sheet, row, col),
+                    conditional_formats: conditional_at(sheet, row, col),
                 });
                 cells.push(((row, col), cell_result));
             }
@@ -6164,12 +8904,17 @@ fn cmd_sheet_inspect(
                     for cell in &result.cells {
                         let formula_marker = if cell.formula.is_some() { " [f]" } else { "" };
                         println!("  {} = {}{}", cell.cell, cell.value, formula_marker);
+                        if let Some(c) = &cell.comment {
+                            println!("      Comment: {}", sheet_ops::comment_label(c));
+                        }
+                        for cf in &cell.conditional_formats {
+                            println!("      Conditional: {}", sheet_ops::conditional_label(cf));
+                        }
                     }
                 }
             }
         } else if start_row == end_row && start_col == end_col {
             // Single cell (dense)
-            let raw = sheet.get_raw(start_row, start_col);
             let display = sheet.get_display(start_row, start_col);
 
             if value_only {
@@ -6178,9 +8923,9 @@ fn cmd_sheet_inspect(
             }
 
             let value_type = if is_native {
-                classify_value_type(&raw, &display)
+                classify_value_type(&sheet.get_cell(start_row, start_col).value, &display)
             } else {
-                if formula_map.contains_key(&(sheet_idx, start_row, start_col)) { "formula" } else { classify_value_type(&raw, &display) }
+                if formula_map.contains_key(&(sheet_idx, start_row, start_col)) { "formula" } else { classify_value_type(&sheet.get_cell(start_row, start_col).value, &display) }
             };
             let formula = get_formula(sheet, sheet_idx, start_row, start_col);
 
@@ -6208,6 +8953,8 @@ fn cmd_sheet_inspect(
                 format: format_info,
                 header: None,
                 column_name: None,
+                comment: comment_at(sheet, start_row, start_col),
+                conditional_formats: conditional_at(sheet, start_row, start_col),
             });
 
             if json {
@@ -6217,6 +8964,12 @@ fn cmd_sheet_inspect(
                 if let Some(f) = &result.formula {
                     println!("Formula: {}", f);
                 }
+                if let Some(c) = &result.comment {
+                    println!("Comment: {}", sheet_ops::comment_label(c));
+                }
+                for cf in &result.conditional_formats {
+                    println!("Conditional: {}", sheet_ops::conditional_label(cf));
+                }
                 if include_style && is_native {
                     let cell_fmt = sheet.get_format(start_row, start_col);
                     if cell_fmt.bold { println!("Style: bold"); }
@@ -6228,35 +8981,51 @@ fn cmd_sheet_inspect(
             if value_only {
                 return Err(CliError::args("--value requires a single-cell target, not a range"));
             }
-            // Range (dense)
+            // Range (dense). With --transpose the range is walked column by
+            // column and each cell is reported at its swapped position.
+            let (height, width) = ((end_row + 1).saturating_sub(start_row), (end_col + 1).saturating_sub(start_col));
+            let (out_rows, out_cols) = if transpose { (width, height) } else { (height, width) };
             let mut cells = Vec::new();
-            for row in start_row..=end_row {
-                for col in start_col..=end_col {
-                    let raw = sheet.get_raw(row, col);
+            for out_row in start_row..start_row + out_rows {
+                for out_col in start_col..start_col + out_cols {
+                    let (row, col) = if transpose {
+                        (start_row + (out_col - start_col), start_col + (out_row - start_row))
+                    } else {
+                        (out_row, out_col)
+                    };
                     let display = sheet.get_display(row, col);
 
                     let value_type = if is_native {
-                        classify_value_type(&raw, &display)
+                        classify_value_type(&sheet.get_cell(row, col).value, &display)
                     } else {
-                        if formula_map.contains_key(&(sheet_idx, row, col)) { "formula" } else { classify_value_type(&raw, &display) }
+                        if formula_map.contains_key(&(sheet_idx, row, col)) { "formula" } else { classify_value_type(&sheet.get_cell(row, col).value, &display) }
                     };
                     let formula = get_formula(sheet, sheet_idx, row, col);
 
                     let cell_result = enrich_headers(row, col, sheet_ops::CellInspectResult {
-                        cell: sheet_ops::format_cell_ref(row, col),
+                        cell: sheet_ops::format_cell_ref(out_row, out_col),
                         value: display,
                         formula,
                         value_type: value_type.to_string(),
                         format: None,
                         header: None,
                         column_name: None,
+                        comment: comment_at(sheet, row, col),
+                        conditional_formats: conditional_at(sheet, row, col),
                     });
                     cells.push(cell_result);
                 }
             }
 
+            let range = if transpose {
+                format!("{}:{}",
+                    sheet_ops::format_cell_ref(start_row, start_col),
+                    sheet_ops::format_cell_ref((start_row + out_rows).saturating_sub(1), (start_col + out_cols).saturating_sub(1)))
+            } else {
+                target_str.to_uppercase()
+            };
             let result = sheet_ops::RangeInspectResult {
-                range: target_str.to_uppercase(),
+                range,
                 cells,
             };
 
@@ -6267,6 +9036,12 @@ fn cmd_sheet_inspect(
                 for cell in &result.cells {
                     let formula_marker = if cell.formula.is_some() { " [f]" } else { "" };
                     println!("  {} = {}{}", cell.cell, cell.value, formula_marker);
+                    if let Some(c) = &cell.comment {
+                        println!("      Comment: {}", sheet_ops::comment_label(c));
+                    }
+                    for cf in &cell.conditional_formats {
+                        println!("      Conditional: {}", sheet_ops::conditional_label(cf));
+                    }
                 }
             }
         }
@@ -6275,16 +9050,19 @@ fn cmd_sheet_inspect(
     Ok(())
 }
 
-/// Classify a cell value type from its raw and display strings.
-fn classify_value_type(raw: &str, display: &str) -> &'static str {
-    if raw.starts_with('=') {
-        "formula"
-    } else if display.parse::<f64>().is_ok() {
-        "number"
-    } else if display.is_empty() {
-        "empty"
-    } else {
-        "text"
+/// Classify a cell for `value_type`: typed cells report their kind (text
+/// stays text even when it reads as a number, like `00123`), and the display
+/// string decides between number, text and empty.
+fn classify_value_type(value: &visigrid_engine::cell::CellValue, display: &str) -> &'static str {
+    use visigrid_engine::cell::CellValue;
+    match value {
+        CellValue::Formula { .. } => "formula",
+        CellValue::Boolean(_) => "boolean",
+        CellValue::Error(_) => "error",
+        CellValue::Text(t) if !t.is_empty() => "text",
+        _ if display.parse::<f64>().is_ok() => "number",
+        _ if display.is_empty() => "empty",
+        _ => "text",
     }
 }
 
@@ -6366,6 +9144,70 @@ fn cmd_sheet_fingerprint(file: PathBuf, json: bool) -> Result<(), CliError> {
     Ok(())
 }
 
+fn cmd_sheet_deps(
+    file: PathBuf,
+    cell: String,
+    sheet: Option<String>,
+    upstream: bool,
+    json: bool,
+) -> Result<(), CliError> {
+    let fmt = infer_inspect_format(&file)?;
+    if !matches!(fmt, InspectFormat::Sheet) {
+        return Err(CliError::args("deps only works with .sheet files")
+            .with_hint("import first: vgrid sheet import data.xlsx model.sheet"));
+    }
+    let workbook = visigrid_io::native::load_workbook(&file)
+        .map_err(|e| CliError::io(format!("failed to load {}: {}", file.display(), e)))?;
+
+    let sheet_idx = match &sheet {
+        Some(arg) => sheet_ops::resolve_sheet_by_arg(&workbook, arg)?,
+        None => 0,
+    };
+    let sheet_id = workbook.sheet_id_at_idx(sheet_idx)
+        .ok_or_else(|| CliError::args("workbook has no sheets"))?;
+    let (row, col) = sheet_ops::parse_cell_ref(&cell.replace('$', ""))
+        .ok_or_else(|| CliError::args(format!("invalid cell reference: {}", cell))
+            .with_hint("use A1 notation, e.g. B2"))?;
+
+    let target = visigrid_engine::cell_id::CellId::new(sheet_id, row, col);
+    let graph = workbook.dep_graph();
+    let cells = if upstream {
+        graph.transitive_precedents(target)
+    } else {
+        graph.transitive_dependents(target)
+    };
+
+    // Qualify cells on other sheets with the sheet name
+    let sheet_name = |id| workbook.sheet_by_id(id).map(|s| s.name.clone()).unwrap_or_default();
+    let cell_label = |c: &visigrid_engine::cell_id::CellId| {
+        let r = sheet_ops::format_cell_ref(c.row, c.col);
+        if c.sheet == sheet_id { r } else { format!("{}!{}", sheet_name(c.sheet), r) }
+    };
+    let direction = if upstream { "upstream" } else { "downstream" };
+
+    if json {
+        let entries: Vec<serde_json::Value> = cells.iter().map(|c| serde_json::json!({
+            "sheet": sheet_name(c.sheet),
+            "cell": sheet_ops::format_cell_ref(c.row, c.col),
+        })).collect();
+        let output = serde_json::json!({
+            "sheet": sheet_name(sheet_id),
+            "cell": sheet_ops::format_cell_ref(row, col),
+            "direction": direction,
+            "count": cells.len(),
+            "cells": entries,
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if cells.is_empty() {
+        eprintln!("{}: no {} cells", sheet_ops::format_cell_ref(row, col), direction);
+    } else {
+        for c in &cells {
+            println!("{}", cell_label(c));
+        }
+    }
+    Ok(())
+}
+
 fn cmd_sheet_upgrade(
     file: PathBuf,
     out: Option<PathBuf>,
@@ -6455,6 +9297,305 @@ fn cmd_sheet_upgrade(
     Ok(())
 }
 
+fn cmd_sheet_repair(file: PathBuf, out: Option<PathBuf>, json: bool) -> Result<(), CliError> {
+    use visigrid_io::native::repair_sheet;
+
+    if !file.exists() {
+        return Err(CliError::io(format!("{}: no such file", file.display())));
+    }
+    let out = out.unwrap_or_else(|| file.with_extension("repaired.sheet"));
+    if out.exists() {
+        return Err(CliError::args(format!("{} already exists", out.display()))
+            .with_hint("pass --out to choose another path"));
+    }
+
+    let result = repair_sheet(&file, &out)
+        .map_err(|e| CliError::io(format!("repair failed: {}", e)))?;
+
+    if json {
+        let mut value = serde_json::to_value(&result).unwrap();
+        value["out"] = serde_json::json!(out.display().to_string());
+        value["clean"] = serde_json::json!(result.is_clean());
+        value["rows_lost"] = serde_json::json!(result.rows_lost());
+        println!("{}", serde_json::to_string(&value).unwrap());
+        return Ok(());
+    }
+
+    if result.integrity_errors.is_empty() {
+        eprintln!("{}: integrity check passed", file.display());
+    } else {
+        eprintln!("{}: integrity check found problems:", file.display());
+        for err in result.integrity_errors.iter().take(5) {
+            eprintln!("  {}", err.trim_start_matches("*** in database main ***\n"));
+        }
+    }
+    for table in &result.tables {
+        if table.read_errors == 0 && table.expected.is_none_or(|e| e == table.recovered) {
+            continue;
+        }
+        match table.expected {
+            Some(expected) => eprintln!(
+                "  {}: recovered {} of {} rows ({} lost)",
+                table.table, table.recovered, expected, expected.saturating_sub(table.recovered)
+            ),
+            None => eprintln!("  {}: recovered {} rows (total unknown)", table.table, table.recovered),
+        }
+    }
+    for table in &result.unreadable_tables {
+        eprintln!("  {}: unreadable, nothing recovered", table);
+    }
+    eprintln!(
+        "→ {} ({} sheet{}, {} cells)",
+        out.display(),
+        result.sheets,
+        if result.sheets == 1 { "" } else { "s" },
+        result.cells
+    );
+
+    Ok(())
+}
+
+// ── sheet name ──────────────────────────────────────────────────────
+
+/// Load a .sheet workbook for named-range editing, rejecting other formats.
+fn load_sheet_for_names(file: &PathBuf) -> Result<visigrid_engine::workbook::Workbook, CliError> {
+    require_sheet_for_names(file)?;
+    visigrid_io::native::load_workbook(file)
+        .map_err(|e| CliError::io(format!("failed to load {}: {}", file.display(), e)))
+}
+
+/// Like [`load_sheet_for_names`], but takes the file's write lock first and
+/// returns it, so an edit holds the lock from the load through the save.
+fn lock_sheet_for_names(
+    file: &PathBuf,
+) -> Result<(visigrid_io::native::WriteLock, visigrid_engine::workbook::Workbook), CliError> {
+    require_sheet_for_names(file)?;
+    let lock = visigrid_io::native::lock_for_write(file).map_err(CliError::io)?;
+    Ok((lock, load_sheet_for_names(file)?))
+}
+
+fn require_sheet_for_names(file: &PathBuf) -> Result<(), CliError> {
+    let fmt = infer_inspect_format(file)?;
+    if !matches!(fmt, InspectFormat::Sheet) {
+        return Err(CliError::args("named ranges can only be managed in .sheet files")
+            .with_hint("import first: vgrid sheet import data.xlsx model.sheet"));
+    }
+    Ok(())
+}
+
+/// Parse `B2`, `B2:B13`, `summary!B2:B13`, or `'My Sheet'!A1` into a named range.
+/// Unqualified references target the first sheet. `$` markers are ignored.
+fn parse_name_target(
+    workbook: &visigrid_engine::workbook::Workbook,
+    name: &str,
+    spec: &str,
+) -> Result<visigrid_engine::named_range::NamedRange, CliError> {
+    use visigrid_engine::named_range::NamedRange;
+
+    let invalid = || CliError::args(format!("invalid range: {:?}", spec))
+        .with_hint("expected a cell or range like B2, B2:B13, or summary!B2:B13");
+
+    let (sheet_part, ref_part) = match spec.rsplit_once('!') {
+        Some((sheet, r)) => (Some(sheet.trim().trim_matches('\'')), r),
+        None => (None, spec),
+    };
+    let sheet_idx = match sheet_part {
+        Some(arg) if !arg.is_empty() => sheet_ops::resolve_sheet_by_arg(workbook, arg)?,
+        Some(_) => return Err(invalid()),
+        None => 0,
+    };
+
+    let cleaned = ref_part.trim().replace('$', "");
+    let (sr, sc, er, ec) = sheet_ops::parse_target(&cleaned).ok_or_else(invalid)?;
+    let (sr, er) = (sr.min(er), sr.max(er));
+    let (sc, ec) = (sc.min(ec), sc.max(ec));
+
+    if cleaned.contains(':') {
+        Ok(NamedRange::range(name, sheet_idx, sr, sc, er, ec))
+    } else {
+        Ok(NamedRange::cell(name, sheet_idx, sr, sc))
+    }
+}
+
+/// Qualified display form of a named range target, e.g. `summary!B2:B13`.
+fn named_range_display(
+    workbook: &visigrid_engine::workbook::Workbook,
+    nr: &visigrid_engine::named_range::NamedRange,
+) -> (String, String) {
+    use visigrid_engine::named_range::NamedRangeTarget;
+
+    let sheet_idx = match nr.target {
+        NamedRangeTarget::Cell { sheet, .. } | NamedRangeTarget::Range { sheet, .. } => sheet,
+    };
+    let sheet_name = workbook.sheet_names().get(sheet_idx).map(|n| n.to_string()).unwrap_or_default();
+    (sheet_name, nr.reference_string())
+}
+
+/// Write the workbook's named ranges back to `file` atomically.
+///
+/// Copies the file to a unique temp file beside it, rewrites the named-range
+/// table there, and renames over the original. The caller holds the file's
+/// write lock from [`lock_sheet_for_names`]. Returns the
+/// new semantic fingerprint; if the file's stamp matched the fingerprint
+/// before the edit, the stamp is moved to the new fingerprint (a stamp that
+/// had already drifted is left alone).
+fn save_named_ranges_atomic(
+    file: &PathBuf,
+    fingerprint_before: &str,
+    workbook: &visigrid_engine::workbook::Workbook,
+) -> Result<(String, bool), CliError> {
+    use visigrid_io::native::{compute_semantic_fingerprint, load_semantic_verification, save_named_ranges, save_semantic_verification};
+
+    let fingerprint = compute_semantic_fingerprint(workbook);
+    let dir = file.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(std::path::Path::new("."));
+    let temp = tempfile::Builder::new()
+        .prefix(".vgrid-names-")
+        .suffix(".sheet.tmp")
+        .tempfile_in(dir)
+        .map_err(|e| CliError::io(format!("failed to create temp file: {}", e)))?;
+    let temp_path = temp.path().to_path_buf();
+
+    std::fs::copy(file, &temp_path)
+        .map_err(|e| CliError::io(format!("failed to write temp file: {}", e)))?;
+
+    save_named_ranges(&temp_path, workbook)
+        .map_err(|e| CliError::io(format!("failed to write named ranges: {}", e)))?;
+
+    let mut verification = load_semantic_verification(&temp_path).unwrap_or_default();
+    let restamped = verification.fingerprint.as_deref() == Some(fingerprint_before)
+        && fingerprint_before != fingerprint;
+    if restamped {
+        verification.fingerprint = Some(fingerprint.clone());
+        verification.timestamp = Some(chrono::Utc::now().to_rfc3339());
+        save_semantic_verification(&temp_path, &verification)
+            .map_err(|e| CliError::io(format!("failed to write verification: {}", e)))?;
+    }
+
+    // Dropping `temp` on any early return above removes the temp file.
+    temp.persist(file)
+        .map_err(|e| CliError::io(format!("failed to rename to output: {}", e.error)))?;
+
+    Ok((fingerprint, restamped))
+}
+
+fn cmd_sheet_name_add(
+    file: PathBuf,
+    name: String,
+    range: String,
+    description: Option<String>,
+    force: bool,
+    json: bool,
+) -> Result<(), CliError> {
+    let (_lock, mut workbook) = lock_sheet_for_names(&file)?;
+    let fingerprint_before = visigrid_io::native::compute_semantic_fingerprint(&workbook);
+
+    visigrid_engine::named_range::is_valid_name(&name)
+        .map_err(|e| CliError::args(format!("invalid name {:?}: {}", name, e)))?;
+
+    let replaced = workbook.get_named_range(&name).is_some();
+    if replaced && !force {
+        return Err(CliError::args(format!("name {:?} already exists", name))
+            .with_hint("use --force to redefine it, or `vgrid sheet name remove` first"));
+    }
+
+    let mut named_range = parse_name_target(&workbook, &name, &range)?;
+    named_range.description = description;
+    workbook.named_ranges_mut().set(named_range)
+        .map_err(|e| CliError::args(format!("invalid name {:?}: {}", name, e)))?;
+
+    let (fingerprint, restamped) = save_named_ranges_atomic(&file, &fingerprint_before, &workbook)?;
+
+    let nr = workbook.get_named_range(&name).unwrap();
+    let (sheet_name, reference) = named_range_display(&workbook, nr);
+    if json {
+        let output = serde_json::json!({
+            "ok": true,
+            "action": if replaced { "replaced" } else { "added" },
+            "name": nr.name,
+            "range": reference,
+            "sheet": sheet_name,
+            "fingerprint": fingerprint,
+            "restamped": restamped,
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("{} {} → {}!{}", if replaced { "Replaced" } else { "Added" }, nr.name, sheet_name, reference);
+        println!("Fingerprint:  {}", fingerprint);
+        if restamped {
+            println!("Stamped:      yes (updated)");
+        }
+    }
+    Ok(())
+}
+
+fn cmd_sheet_name_list(file: PathBuf, json: bool) -> Result<(), CliError> {
+    let workbook = load_sheet_for_names(&file)?;
+
+    let mut names = workbook.list_named_ranges();
+    names.sort_by_key(|nr| nr.name.to_lowercase());
+
+    if json {
+        let entries: Vec<serde_json::Value> = names.iter().map(|nr| {
+            let (sheet_name, reference) = named_range_display(&workbook, nr);
+            serde_json::json!({
+                "name": nr.name,
+                "range": reference,
+                "sheet": sheet_name,
+                "description": nr.description,
+            })
+        }).collect();
+        println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+    } else if names.is_empty() {
+        eprintln!("{}: no named ranges", file.display());
+    } else {
+        let width = names.iter().map(|nr| nr.name.len()).max().unwrap_or(0);
+        for nr in &names {
+            let (sheet_name, reference) = named_range_display(&workbook, nr);
+            println!("{:<width$}  {:<12}  {}", nr.name, reference, sheet_name, width = width);
+        }
+    }
+    Ok(())
+}
+
+fn cmd_sheet_name_remove(file: PathBuf, name: String, json: bool) -> Result<(), CliError> {
+    let (_lock, mut workbook) = lock_sheet_for_names(&file)?;
+    let fingerprint_before = visigrid_io::native::compute_semantic_fingerprint(&workbook);
+
+    let removed = match workbook.named_ranges_mut().remove(&name) {
+        Some(nr) => nr,
+        None => {
+            let mut available: Vec<String> = workbook.list_named_ranges().iter().map(|nr| nr.name.clone()).collect();
+            available.sort_by_key(|n| n.to_lowercase());
+            let hint = if available.is_empty() {
+                "the file defines no named ranges".to_string()
+            } else {
+                format!("available names: {}", available.join(", "))
+            };
+            return Err(CliError::args(format!("no named range {:?}", name)).with_hint(hint));
+        }
+    };
+
+    let (fingerprint, restamped) = save_named_ranges_atomic(&file, &fingerprint_before, &workbook)?;
+
+    if json {
+        let output = serde_json::json!({
+            "ok": true,
+            "action": "removed",
+            "name": removed.name,
+            "fingerprint": fingerprint,
+            "restamped": restamped,
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Removed {}", removed.name);
+        println!("Fingerprint:  {}", fingerprint);
+        if restamped {
+            println!("Stamped:      yes (updated)");
+        }
+    }
+    Ok(())
+}
+
 /// Infer source format for import, rejecting .sheet sources.
 fn infer_source_format(path: &PathBuf) -> Result<InspectFormat, CliError> {
     let fmt = infer_inspect_format(path)?;
@@ -6478,11 +9619,14 @@ fn cmd_sheet_import(
     dry_run: bool,
     json: bool,
     delimiter: Option<String>,
+    trim_empty: bool,
+    protected: Vec<String>,
+    meta: visigrid_io::native::WorkbookMeta,
 ) -> Result<(), CliError> {
     use std::collections::BTreeMap;
     use visigrid_io::native::{
-        compute_semantic_fingerprint, save_workbook, save_workbook_with_metadata,
-        save_semantic_verification, CellMetadata, SemanticVerification,
+        compute_semantic_fingerprint, lock_for_write, write_workbook, write_workbook_with_metadata,
+        save_semantic_verification, save_workbook_meta, CellMetadata, SemanticVerification,
     };
 
     // 1. Infer format (rejects .sheet)
@@ -6531,6 +9675,7 @@ fn cmd_sheet_import(
             (wb, visigrid_io::xlsx::ImportResult::default())
         }
         InspectFormat::Sheet => unreachable!(), // already rejected
+        InspectFormat::Fixed => unreachable!(), // never inferred from an extension
     };
 
     // 4. Select sheet (XLSX + --sheet)
@@ -6540,16 +9685,26 @@ fn cmd_sheet_import(
         let (idx, sheet) = resolve_sheet(&workbook, Some(arg))?;
         selected_sheet_idx = idx;
         sheet_name = sheet.name.clone();
-        // Extract selected sheet into a single-sheet workbook
+        // Extract selected sheet into a single-sheet workbook, keeping cached
+        // values (cross-sheet refs can't resolve without the source sheets)
         let extracted = sheet.clone();
-        workbook = visigrid_engine::workbook::Workbook::from_sheets(vec![extracted], 0);
+        workbook = visigrid_engine::workbook::Workbook::from_sheets_deferred(vec![extracted], 0);
     } else {
         selected_sheet_idx = 0;
         let (_, sheet) = resolve_sheet(&workbook, None)?;
         sheet_name = sheet.name.clone();
     }
 
-    // 5. Apply null policy
+    // 5. Trim trailing blank rows/cols (before the null policy fills them)
+    let trimmed_bounds = if trim_empty {
+        let sheet_mut = workbook.sheet_mut(0)
+            .ok_or_else(|| CliError::io("no sheets in workbook"))?;
+        Some(sheet_ops::trim_trailing_empty(sheet_mut))
+    } else {
+        None
+    };
+
+    // 5b. Apply null policy
     if matches!(nulls, NullPolicy::Error) {
         let sheet = workbook.sheet(0)
             .ok_or_else(|| CliError::io("no sheets in workbook"))?;
@@ -6604,6 +9759,7 @@ fn cmd_sheet_import(
     let metadata: CellMetadata = if matches!(formulas, FormulaPolicy::Keep) {
         import_result.formula_strings.iter()
             .filter(|((si, _, _), _)| *si == selected_sheet_idx)
+            .filter(|((_, r, c), _)| trimmed_bounds.is_none_or(|(rows, cols)| *r < rows && *c < cols))
             .map(|((_, r, c), f)| {
                 let ref_str = sheet_ops::format_cell_ref(*r, *c);
                 let mut map = BTreeMap::new();
@@ -6615,6 +9771,11 @@ fn cmd_sheet_import(
         BTreeMap::new()
     };
 
+    // Protection is persisted but never fingerprinted
+    if let Some(sheet) = workbook.sheet_mut(0) {
+        sheet_ops::protect_ranges(sheet, &protected)?;
+    }
+
     // 8. Compute fingerprint once
     let fingerprint = compute_semantic_fingerprint(&workbook);
 
@@ -6678,29 +9839,31 @@ fn cmd_sheet_import(
     }
 
     // 11. Atomic write
-    let temp_path = output.with_extension("sheet.tmp");
-
-    if metadata.is_empty() {
-        save_workbook(&workbook, &temp_path)
-            .map_err(|e| CliError::io(format!("failed to write temp file: {}", e)))?;
-    } else {
-        save_workbook_with_metadata(&workbook, &metadata, &temp_path)
-            .map_err(|e| CliError::io(format!("failed to write temp file: {}", e)))?;
-    }
-
     let stamped = stamp.is_some();
-    if let Some(ref label) = stamp {
-        let verification = SemanticVerification {
-            fingerprint: Some(fingerprint.clone()),
-            label: if label.is_empty() { None } else { Some(label.clone()) },
-            timestamp: Some(chrono::Utc::now().to_rfc3339()),
-        };
-        save_semantic_verification(&temp_path, &verification)
-            .map_err(|e| CliError::io(format!("failed to write verification: {}", e)))?;
-    }
+    let lock = lock_for_write(&output).map_err(CliError::io)?;
+    lock.replace(|temp_path| {
+        if metadata.is_empty() {
+            write_workbook(&workbook, temp_path)
+        } else {
+            write_workbook_with_metadata(&workbook, &metadata, temp_path)
+        }
+        .map_err(|e| format!("failed to write temp file: {}", e))?;
+
+        if let Some(ref label) = stamp {
+            let verification = SemanticVerification {
+                fingerprint: Some(fingerprint.clone()),
+                label: if label.is_empty() { None } else { Some(label.clone()) },
+                timestamp: Some(chrono::Utc::now().to_rfc3339()),
+            };
+            save_semantic_verification(temp_path, &verification)
+                .map_err(|e| format!("failed to write verification: {}", e))?;
+        }
 
-    std::fs::rename(&temp_path, &output)
-        .map_err(|e| CliError::io(format!("failed to rename to output: {}", e)))?;
+        save_workbook_meta(temp_path, &meta)
+            .map_err(|e| format!("failed to write workbook metadata: {}", e))
+    })
+    .map_err(CliError::io)?;
+    drop(lock);
 
     // 12. Output
     let summary = sheet_ops::ImportSummary {
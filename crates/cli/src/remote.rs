@@ -0,0 +1,269 @@
+//! URL inputs for `peek` and `convert`.
+//!
+//! A URL is downloaded to a temp file first, then read like any local path,
+//! so format inference and every reader work unchanged.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::CliError;
+
+/// Largest download accepted (100 MB).
+pub const MAX_DOWNLOAD_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Whole-request timeout for a download.
+pub const DOWNLOAD_TIMEOUT_SECS: u64 = 30;
+
+const USER_AGENT: &str = concat!("vgrid/", env!("CARGO_PKG_VERSION"));
+
+/// True if `input` is an `http://` or `https://` URL rather than a path.
+pub fn is_url(input: &Path) -> bool {
+    input.to_str().is_some_and(|s| {
+        let lower = s.to_ascii_lowercase();
+        lower.starts_with("https://") || lower.starts_with("http://")
+    })
+}
+
+/// Redirects followed before giving up (reqwest's default).
+const MAX_REDIRECTS: usize = 10;
+
+/// A downloaded input. The temp file is removed on drop.
+#[derive(Debug)]
+pub struct Download {
+    pub path: PathBuf,
+    _file: tempfile::NamedTempFile,
+}
+
+/// Pass local paths through; download URLs and return the temp file's path.
+/// Keep the `Download` alive while the path is in use.
+pub fn resolve_input(input: PathBuf, allow_insecure: bool) -> Result<(PathBuf, Option<Download>), CliError> {
+    if !is_url(&input) {
+        return Ok((input, None));
+    }
+    let download = download(&input.to_string_lossy(), allow_insecure)?;
+    Ok((download.path.clone(), Some(download)))
+}
+
+/// Download `url` with the default size cap and timeout.
+pub fn download(url: &str, allow_insecure: bool) -> Result<Download, CliError> {
+    download_with_limits(url, allow_insecure, MAX_DOWNLOAD_BYTES, Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+}
+
+/// Download `url` to a temp file named after the URL's last path segment, so
+/// the extension drives format inference. Without an extension, the
+/// Content-Type picks one.
+pub fn download_with_limits(
+    url: &str,
+    allow_insecure: bool,
+    max_bytes: u64,
+    timeout: Duration,
+) -> Result<Download, CliError> {
+    let parsed = url::Url::parse(url).map_err(|e| CliError::args(format!("invalid URL {}: {}", url, e)))?;
+    match parsed.scheme() {
+        "https" => {}
+        "http" if allow_insecure => {}
+        "http" => {
+            return Err(CliError::args(format!("refusing plain http URL: {}", url))
+                .with_hint("use https, or pass --allow-insecure"));
+        }
+        other => return Err(CliError::args(format!("unsupported URL scheme: {}", other))),
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .user_agent(USER_AGENT)
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if !redirect_allowed(attempt.url(), allow_insecure) {
+                let msg = format!("refusing redirect to {}", attempt.url());
+                attempt.error(msg)
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+        .map_err(|e| CliError::io(format!("failed to build HTTP client: {}", e)))?;
+    let response = client
+        .get(parsed.clone())
+        .send()
+        .map_err(|e| CliError::io(format!("failed to download {}: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(CliError::io(format!("failed to download {}: HTTP {}", url, response.status())));
+    }
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(too_large(url, max_bytes));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_ascii_lowercase);
+    let name = file_name_for(&parsed, content_type.as_deref());
+
+    let mut body = Vec::new();
+    response
+        .take(max_bytes + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| CliError::io(format!("failed to download {}: {}", url, e)))?;
+    if body.len() as u64 > max_bytes {
+        return Err(too_large(url, max_bytes));
+    }
+
+    // Random name, created exclusively: a file or symlink planted at a
+    // guessable path can't redirect the write
+    let mut file = tempfile::Builder::new()
+        .prefix("vgrid_download_")
+        .suffix(&format!("_{}", name))
+        .tempfile()
+        .map_err(|e| CliError::io(format!("failed to create temp file: {}", e)))?;
+    file.write_all(&body)
+        .and_then(|_| file.flush())
+        .map_err(|e| CliError::io(format!("failed to write {}: {}", file.path().display(), e)))?;
+    Ok(Download { path: file.path().to_path_buf(), _file: file })
+}
+
+/// Whether a redirect may go to `url`: https always, plain http only with
+/// `--allow-insecure`, so a redirect can't downgrade an https download.
+fn redirect_allowed(url: &url::Url, allow_insecure: bool) -> bool {
+    match url.scheme() {
+        "https" => true,
+        "http" => allow_insecure,
+        _ => false,
+    }
+}
+
+fn too_large(url: &str, max_bytes: u64) -> CliError {
+    CliError::io(format!("download exceeds {} MB: {}", max_bytes / (1024 * 1024), url))
+        .with_hint("download it yourself and pass the local file")
+}
+
+/// Temp file name: the URL's last path segment, with an extension from
+/// `content_type` when the segment has none.
+fn file_name_for(url: &url::Url, content_type: Option<&str>) -> String {
+    let segment = url
+        .path_segments()
+        .and_then(|mut s| s.next_back())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("download");
+    let segment: String = segment
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    if Path::new(&segment).extension().is_some() {
+        return segment;
+    }
+    let ext = match content_type.unwrap_or("") {
+        t if t.starts_with("text/csv") => "csv",
+        t if t.starts_with("text/tab-separated-values") => "tsv",
+        t if t.starts_with("application/json") => "json",
+        t if t.contains("spreadsheetml") => "xlsx",
+        _ => return segment,
+    };
+    format!("{}.{}", segment, ext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url(Path::new("https://example.com/data.csv")));
+        assert!(is_url(Path::new("HTTP://example.com/data.csv")));
+        assert!(!is_url(Path::new("data.csv")));
+        assert!(!is_url(Path::new("./https/data.csv")));
+    }
+
+    #[test]
+    fn test_download_csv_keeps_extension_and_parses() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/exports/data.csv");
+            then.status(200).body("name,amount\nalpha,10\nbeta,20\ngamma,30\n");
+        });
+
+        let download = download(&server.url("/exports/data.csv"), true).unwrap();
+        assert!(download.path.to_string_lossy().ends_with("data.csv"));
+
+        let sheet = visigrid_io::csv::import(&download.path).unwrap();
+        assert_eq!(sheet.get_display(0, 1), "amount");
+        assert_eq!(sheet.get_display(3, 0), "gamma");
+        assert_eq!(sheet.get_display(4, 0), "");
+
+        let path = download.path.clone();
+        drop(download);
+        assert!(!path.exists(), "temp file removed on drop");
+    }
+
+    #[test]
+    fn test_download_rejects_http_without_opt_in() {
+        let err = download("http://example.com/data.csv", false).unwrap_err();
+        assert!(err.message.contains("plain http"), "{}", err.message);
+        assert!(download("ftp://example.com/data.csv", true).is_err());
+    }
+
+    #[test]
+    fn test_redirect_rejects_scheme_downgrade() {
+        let https = url::Url::parse("https://example.com/data.csv").unwrap();
+        let http = url::Url::parse("http://example.com/data.csv").unwrap();
+        let file = url::Url::parse("file:///etc/passwd").unwrap();
+        assert!(redirect_allowed(&https, false));
+        assert!(!redirect_allowed(&http, false));
+        assert!(redirect_allowed(&http, true));
+        assert!(!redirect_allowed(&file, true));
+    }
+
+    #[test]
+    fn test_download_does_not_follow_planted_symlink() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.path("/data.csv");
+            then.status(200).body("a,b\n1,2\n");
+        });
+        // The old fixed name: a symlink there must not receive the body
+        let dir = tempfile::tempdir().unwrap();
+        let victim = dir.path().join("victim.txt");
+        std::fs::write(&victim, "untouched").unwrap();
+        let planted = std::env::temp_dir().join(format!("vgrid_download_{}_data.csv", std::process::id()));
+        #[cfg(unix)]
+        let _ = std::os::unix::fs::symlink(&victim, &planted);
+
+        let download = download(&server.url("/data.csv"), true).unwrap();
+        assert_ne!(download.path, planted);
+        assert!(download.path.to_string_lossy().ends_with("_data.csv"));
+        assert_eq!(std::fs::read_to_string(&download.path).unwrap(), "a,b\n1,2\n");
+        assert_eq!(std::fs::read_to_string(&victim).unwrap(), "untouched");
+        let _ = std::fs::remove_file(&planted);
+    }
+
+    #[test]
+    fn test_download_size_cap_and_status() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.path("/big.csv");
+            then.status(200).body("x".repeat(64));
+        });
+        server.mock(|when, then| {
+            when.path("/missing.csv");
+            then.status(404);
+        });
+
+        let err = download_with_limits(&server.url("/big.csv"), true, 16, Duration::from_secs(5)).unwrap_err();
+        assert!(err.message.contains("exceeds"), "{}", err.message);
+        let err = download(&server.url("/missing.csv"), true).unwrap_err();
+        assert!(err.message.contains("404"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_file_name_from_content_type() {
+        let url = url::Url::parse("https://example.com/api/export?id=7").unwrap();
+        assert_eq!(file_name_for(&url, Some("text/csv; charset=utf-8")), "export.csv");
+        assert_eq!(file_name_for(&url, None), "export");
+        let root = url::Url::parse("https://example.com/").unwrap();
+        assert_eq!(file_name_for(&root, Some("application/json")), "download.json");
+    }
+}
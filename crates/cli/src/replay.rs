@@ -14,13 +14,15 @@
 //!
 //! Scripts containing formulas with nondeterministic functions (NOW, TODAY,
 //! RAND, RANDBETWEEN) will fail with `--verify` since their output cannot
-//! be reliably reproduced.
+//! be reliably reproduced. The set comes from
+//! `visigrid_engine::formula::functions::is_volatile`.
 
 use std::cell::RefCell;
 use std::path::Path;
 use std::rc::Rc;
 
 use mlua::{Lua, Result as LuaResult, Table};
+use visigrid_engine::formula::functions::is_volatile;
 use visigrid_engine::sheet::Sheet;
 use visigrid_engine::workbook::Workbook;
 
@@ -29,9 +31,6 @@ use crate::{CliError, EXIT_EVAL_ERROR};
 /// Fingerprint format version. Increment on breaking changes to fingerprint computation.
 pub const FINGERPRINT_VERSION: &str = "v1";
 
-/// Functions that produce nondeterministic results and break verification.
-const NONDETERMINISTIC_FUNCTIONS: &[&str] = &["NOW", "TODAY", "RAND", "RANDBETWEEN"];
-
 /// Result of executing a provenance script.
 pub struct ReplayResult {
     /// The workbook after replay.
@@ -167,16 +166,55 @@ impl ReplayState {
         if !value.starts_with('=') {
             return;
         }
-        let upper = value.to_uppercase();
-        for func in NONDETERMINISTIC_FUNCTIONS {
-            // Check for function call pattern: FUNC( or FUNC (
-            if upper.contains(&format!("{}(", func)) || upper.contains(&format!("{} (", func)) {
-                if !self.nondeterministic_found.contains(&func.to_string()) {
-                    self.nondeterministic_found.push(func.to_string());
+        for func in volatile_calls(value) {
+            if !self.nondeterministic_found.contains(&func) {
+                self.nondeterministic_found.push(func);
+            }
+        }
+    }
+}
+
+/// Names of volatile functions called in a formula, uppercased, in order of
+/// appearance. A call is an identifier followed by optional spaces and `(`;
+/// string literals are skipped.
+fn volatile_calls(formula: &str) -> Vec<String> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' {
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '"' {
+                    // "" is an escaped quote inside the literal
+                    if chars.get(i + 1) == Some(&'"') {
+                        i += 2;
+                        continue;
+                    }
+                    break;
                 }
+                i += 1;
+            }
+            i += 1;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
             }
+            let name: String = chars[start..i].iter().collect::<String>().to_ascii_uppercase();
+            let mut j = i;
+            while j < chars.len() && chars[j] == ' ' {
+                j += 1;
+            }
+            if chars.get(j) == Some(&'(') && is_volatile(&name) {
+                found.push(name);
+            }
+        } else {
+            i += 1;
         }
     }
+    found
 }
 
 /// Execute a provenance script and return the result.
@@ -1039,6 +1077,19 @@ grid.set{ sheet=1, cell="A1", value="=TODAY()" }
         assert!(state.nondeterministic_found.contains(&"TODAY".to_string()));
     }
 
+    #[test]
+    fn test_nondeterministic_detection_uses_engine_list() {
+        use visigrid_engine::formula::functions::list_volatile_functions;
+
+        for func in list_volatile_functions() {
+            let formula = format!("=1+{}()", func.to_lowercase());
+            assert_eq!(volatile_calls(&formula), vec![func.to_string()], "{}", formula);
+        }
+        // Inside a string literal, or a longer name, is not a call
+        assert!(volatile_calls("=\"NOW()\"&A1").is_empty());
+        assert!(volatile_calls("=SNOW(1)+NOWHERE(2)").is_empty());
+    }
+
     #[test]
     fn test_deterministic_formula_not_flagged() {
         let script = r#"
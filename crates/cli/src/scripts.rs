@@ -15,6 +15,9 @@ use visigrid_io::scripting::{
     list_all_scripts, resolve_script,
 };
 
+use visigrid_engine::workbook::Workbook;
+
+use crate::diff::{self, ColumnDiff, DiffRow, RowStatus};
 use crate::CliError;
 
 /// Global scripts directory (~/.config/visigrid/scripts/)
@@ -126,6 +129,8 @@ pub fn cmd_scripts_run(
     name: String,
     file: PathBuf,
     plan: bool,
+    diff: bool,
+    tolerance: f64,
     apply: bool,
     json: bool,
 ) -> Result<(), CliError> {
@@ -140,6 +145,13 @@ pub fn cmd_scripts_run(
         return Err(CliError::io(format!("file not found: {}", file.display())));
     }
 
+    // --apply loads, modifies and saves the file: hold its write lock throughout
+    let lock = if apply {
+        Some(native::lock_for_write(&file).map_err(CliError::io)?)
+    } else {
+        None
+    };
+
     // Load attached scripts
     let attached = native::load_scripts(&file).unwrap_or_default();
 
@@ -200,6 +212,63 @@ pub fn cmd_scripts_run(
     let ops = borrowed.ops();
     let cells_read = borrowed.cells_read;
 
+    if plan && diff {
+        // Dry run against an in-memory copy: current vs planned per cell
+        drop(borrowed);
+        let ops_vec = sink.borrow_mut().take_ops();
+        let changes = apply_cli_ops(&mut workbook, &ops_vec);
+        workbook.rebuild_dep_graph();
+        workbook.recompute_full_ordered();
+        let fingerprint_after = native::compute_semantic_fingerprint(&workbook);
+        let rows = plan_diff_rows(&changes, tolerance);
+
+        if json {
+            let cells: Vec<serde_json::Value> = rows.iter().map(|row| {
+                let d = &row.diffs[0];
+                serde_json::json!({
+                    "cell": row.key,
+                    "kind": d.column,
+                    "current": d.left,
+                    "planned": d.right,
+                    "within_tolerance": d.within_tolerance,
+                })
+            }).collect();
+            let summary = serde_json::json!({
+                "script": name,
+                "hash": resolved.meta.hash,
+                "ops_count": ops_vec.len(),
+                "cells_changed": rows.len(),
+                "tolerance": tolerance,
+                "changes": cells,
+                "fingerprint_before": fingerprint_before,
+                "fingerprint_after": fingerprint_after,
+                "mode": "plan",
+            });
+            println!("{}", serde_json::to_string_pretty(&summary).unwrap_or_default());
+        } else {
+            let color = atty::is(atty::Stream::Stdout);
+            println!("Script: {} ({})", name, &resolved.meta.hash[..15.min(resolved.meta.hash.len())]);
+            println!("Mode:   plan (dry run)");
+            println!("Cells:  {} changed", rows.len());
+            println!("FP:     {} → {}", fingerprint_before, fingerprint_after);
+            if !rows.is_empty() {
+                println!();
+                for row in &rows {
+                    let mut shown = row.clone();
+                    for d in &mut shown.diffs {
+                        for v in [&mut d.left, &mut d.right] {
+                            if v.is_empty() {
+                                *v = "(empty)".to_string();
+                            }
+                        }
+                    }
+                    println!("{}", diff::format_table_line(&shown, color));
+                }
+            }
+        }
+        return Ok(());
+    }
+
     if plan {
         // Dry run: show what would change
         if json {
@@ -237,27 +306,7 @@ pub fn cmd_scripts_run(
     drop(borrowed);
 
     let active_sheet_index = workbook.active_sheet_index();
-    let mut changes = Vec::new();
-
-    for op in &ops_vec {
-        match op {
-            CliOp::SetValue { row, col, value } => {
-                let old = workbook.active_sheet().get_raw(*row, *col).to_string();
-                workbook.active_sheet_mut().set_value(*row, *col, value);
-                changes.push((*row, *col, old, value.clone()));
-            }
-            CliOp::SetFormula { row, col, formula } => {
-                let old = workbook.active_sheet().get_raw(*row, *col).to_string();
-                workbook.active_sheet_mut().set_value(*row, *col, formula);
-                changes.push((*row, *col, old, formula.clone()));
-            }
-            CliOp::Clear { row, col } => {
-                let old = workbook.active_sheet().get_raw(*row, *col).to_string();
-                workbook.active_sheet_mut().set_value(*row, *col, "");
-                changes.push((*row, *col, old, String::new()));
-            }
-        }
-    }
+    let changes = apply_cli_ops(&mut workbook, &ops_vec);
 
     // Recompute after changes
     workbook.rebuild_dep_graph();
@@ -345,8 +394,10 @@ pub fn cmd_scripts_run(
     let metadata = native::CellMetadata::new(); // Preserve existing metadata
     let scripts = native::load_scripts(&file).unwrap_or_default();
 
-    native::save_workbook_full(&workbook, &metadata, &scripts, &run_records, &file)
+    let lock = lock.expect("apply mode holds the write lock");
+    lock.save_workbook_full(&workbook, &metadata, &scripts, &run_records)
         .map_err(|e| CliError::io(format!("failed to save: {}", e)))?;
+    drop(lock);
 
     // Also re-save layout (save_workbook_full recreates the file)
     // Layout is handled separately — we skip it here since CLI doesn't manage layout
@@ -383,6 +434,71 @@ pub fn cmd_scripts_run(
     Ok(())
 }
 
+/// Apply script ops to the active sheet, returning `(row, col, old, new)`
+/// raw contents for each op in order.
+fn apply_cli_ops(workbook: &mut Workbook, ops: &[CliOp]) -> Vec<(usize, usize, String, String)> {
+    let mut changes = Vec::new();
+
+    for op in ops {
+        match op {
+            CliOp::SetValue { row, col, value } => {
+                let old = workbook.active_sheet().get_raw(*row, *col).to_string();
+                workbook.active_sheet_mut().set_value(*row, *col, value);
+                changes.push((*row, *col, old, value.clone()));
+            }
+            CliOp::SetFormula { row, col, formula } => {
+                let old = workbook.active_sheet().get_raw(*row, *col).to_string();
+                workbook.active_sheet_mut().set_value(*row, *col, formula);
+                changes.push((*row, *col, old, formula.clone()));
+            }
+            CliOp::Clear { row, col } => {
+                let old = workbook.active_sheet().get_raw(*row, *col).to_string();
+                workbook.active_sheet_mut().set_value(*row, *col, "");
+                changes.push((*row, *col, old, String::new()));
+            }
+        }
+    }
+
+    changes
+}
+
+/// Collapse applied changes to one diff row per cell (first old value, last
+/// new value), dropping cells that end up unchanged. Row-major order.
+/// Numeric changes of at most `tolerance` are marked within tolerance.
+fn plan_diff_rows(changes: &[(usize, usize, String, String)], tolerance: f64) -> Vec<DiffRow> {
+    let mut cells: std::collections::BTreeMap<(usize, usize), (&str, &str)> = std::collections::BTreeMap::new();
+    for (row, col, old, new) in changes {
+        cells.entry((*row, *col))
+            .and_modify(|e| e.1 = new.as_str())
+            .or_insert((old.as_str(), new.as_str()));
+    }
+
+    cells.into_iter()
+        .filter(|(_, (old, new))| old != new)
+        .map(|((row, col), (old, new))| {
+            let numbers = diff::parse_financial_number(old).zip(diff::parse_financial_number(new));
+            let delta = numbers.map(|(l, r)| (l - r).abs());
+            DiffRow {
+                status: RowStatus::Diff,
+                key: crate::sheet_ops::format_cell_ref(row, col),
+                left: None,
+                right: None,
+                left_source_index: None,
+                right_source_index: None,
+                diffs: vec![ColumnDiff {
+                    column: if new.starts_with('=') { "formula" } else { "value" }.to_string(),
+                    left: old.to_string(),
+                    right: new.to_string(),
+                    delta,
+                    within_tolerance: numbers.is_some_and(|(l, r)| diff::within_tolerance(l, r, tolerance)),
+                }],
+                match_explain: None,
+                candidates: None,
+            }
+        })
+        .collect()
+}
+
 // ============================================================================
 // runs list
 // ============================================================================
@@ -5,11 +5,13 @@
 //! - `attach` - Connect and show session info
 //! - `apply` - Apply operations to a session
 //! - `inspect` - Query cell state
+//! - `repl` - Run a stream of commands over one connection
 
+use std::collections::VecDeque;
 use std::fs;
-use std::io::{BufReader, BufWriter, Read, Write};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::net::TcpStream;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
@@ -20,11 +22,12 @@ use uuid::Uuid;
 pub use visigrid_protocol::{
     // Client messages
     ClientMessage, HelloMessage, ApplyOpsMessage, InspectMessage, PingMessage,
-    StatsMessage,
+    StatsMessage, SessionInfoMessage, ByeMessage, SubscribeMessage, UnsubscribeMessage,
     // Server messages
     ServerMessage, ApplyOpsResultMessage, InspectResultMessage, StatsResultMessage,
+    SessionInfoResultMessage, SubscribedMessage, UnsubscribedMessage, EventMessage,
     // Shared types
-    Op, InspectTarget,
+    Op, OpError, InspectTarget, Capabilities, ProtocolErrorCode,
     // Constants
     PROTOCOL_VERSION,
 };
@@ -174,18 +177,46 @@ pub fn find_session(id_prefix: &str) -> std::io::Result<Option<DiscoveryFile>> {
     }
 }
 
+/// Pick a session without `--session`: the only one running, or else the
+/// only one whose workbook is in `cwd` or below it. `None` leaves the choice
+/// to the user.
+pub fn auto_select_session(sessions: Vec<DiscoveryFile>, cwd: Option<&Path>) -> Option<DiscoveryFile> {
+    if sessions.len() == 1 {
+        return sessions.into_iter().next();
+    }
+    let cwd = cwd?;
+    let canonical = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    let cwd = canonical(cwd);
+    let mut matches = sessions.into_iter().filter(|s| {
+        s.workbook_path.as_deref().is_some_and(|path| canonical(path).starts_with(&cwd))
+    });
+    match (matches.next(), matches.next()) {
+        (Some(session), None) => Some(session),
+        _ => None,
+    }
+}
+
 // ============================================================================
 // Session Client
 // ============================================================================
 
+/// Default time to wait for a reply before giving up with `SessionError::Timeout`.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// A client connection to a VisiGrid session.
 pub struct SessionClient {
     reader: BufReader<TcpStream>,
     writer: BufWriter<TcpStream>,
     session_id: String,
     revision: u64,
-    capabilities: Vec<String>,
+    capabilities: Capabilities,
+    /// Server's advertised message size limit, if any.
+    max_message_bytes: Option<u64>,
     next_id: u64,
+    /// ID of the request currently awaiting a reply.
+    pending_id: String,
+    /// Subscription events that arrived while awaiting a reply.
+    events: VecDeque<EventMessage>,
 }
 
 impl SessionClient {
@@ -197,7 +228,7 @@ impl SessionClient {
             Duration::from_secs(5),
         ).map_err(|e| SessionError::ConnectionFailed(e.to_string()))?;
 
-        stream.set_read_timeout(Some(Duration::from_secs(30)))
+        stream.set_read_timeout(Some(DEFAULT_REQUEST_TIMEOUT))
             .map_err(|e| SessionError::ConnectionFailed(e.to_string()))?;
         stream.set_write_timeout(Some(Duration::from_secs(30)))
             .map_err(|e| SessionError::ConnectionFailed(e.to_string()))?;
@@ -211,8 +242,11 @@ impl SessionClient {
             writer,
             session_id: String::new(),
             revision: 0,
-            capabilities: Vec::new(),
+            capabilities: Capabilities::default(),
+            max_message_bytes: None,
             next_id: 1,
+            pending_id: String::new(),
+            events: VecDeque::new(),
         };
 
         // Send hello
@@ -222,6 +256,7 @@ impl SessionClient {
             version: env!("CARGO_PKG_VERSION").to_string(),
             token: token.to_string(),
             protocol_version: PROTOCOL_VERSION,
+            framing: None,
         });
         client.send(&hello)?;
 
@@ -232,6 +267,7 @@ impl SessionClient {
                 client.session_id = welcome.session_id;
                 client.revision = welcome.revision;
                 client.capabilities = welcome.capabilities;
+                client.max_message_bytes = welcome.max_message_bytes;
                 Ok(client)
             }
             ServerMessage::Error(err) => {
@@ -252,22 +288,44 @@ impl SessionClient {
     }
 
     /// Get server capabilities.
-    pub fn capabilities(&self) -> &[String] {
+    pub fn capabilities(&self) -> &Capabilities {
         &self.capabilities
     }
 
+    /// Get the server's message size limit (`None` if not advertised).
+    pub fn max_message_bytes(&self) -> Option<u64> {
+        self.max_message_bytes
+    }
+
+    /// Set how long to wait for each reply (default 30s).
+    ///
+    /// A request that outlives this fails with `SessionError::Timeout`. The
+    /// server may still complete it, so drop the connection afterwards.
+    pub fn set_request_timeout(&mut self, timeout: Duration) -> Result<(), SessionError> {
+        self.reader.get_ref().set_read_timeout(Some(timeout))
+            .map_err(|e| SessionError::IoError(e.to_string()))
+    }
+
     /// Apply operations to the session.
+    ///
+    /// Resending the same `idempotency_key` (e.g. after a reconnect) returns
+    /// the original result instead of applying twice.
+    ///
+    /// Fails locally with `SessionError::MessageTooLarge`, without sending,
+    /// if the request exceeds the server's advertised size limit.
     pub fn apply_ops(
         &mut self,
         ops: Vec<Op>,
         atomic: bool,
         expected_revision: Option<u64>,
+        idempotency_key: Option<String>,
     ) -> Result<ApplyOpsResultMessage, SessionError> {
         let msg = ClientMessage::ApplyOps(ApplyOpsMessage {
             id: self.next_request_id(),
             ops,
             atomic,
             expected_revision,
+            idempotency_key,
         });
         self.send(&msg)?;
 
@@ -394,15 +452,127 @@ impl SessionClient {
         }
     }
 
+    /// Re-query the session's identity and capabilities.
+    ///
+    /// Updates the cached revision and capabilities, so a long-lived client
+    /// sees changes made since `welcome`.
+    pub fn refresh_info(&mut self) -> Result<SessionInfoResultMessage, SessionError> {
+        let msg = ClientMessage::SessionInfo(SessionInfoMessage {
+            id: self.next_request_id(),
+        });
+        self.send(&msg)?;
+
+        let response = self.receive()?;
+        match response {
+            ServerMessage::SessionInfoResult(info) => {
+                self.session_id = info.session_id.clone();
+                self.revision = info.revision;
+                self.capabilities = info.capabilities.clone();
+                Ok(info)
+            }
+            ServerMessage::Error(err) => {
+                Err(SessionError::ServerError { code: err.code, message: err.message, retry_after_ms: err.retry_after_ms })
+            }
+            _ => Err(SessionError::ProtocolError("Unexpected response to session_info".into())),
+        }
+    }
+
+    /// Subscribe to event topics (`cells`, or `cells:<sheet>:<range>`).
+    /// Events are then read with `poll_event`.
+    pub fn subscribe(&mut self, topics: Vec<String>) -> Result<SubscribedMessage, SessionError> {
+        let msg = ClientMessage::Subscribe(SubscribeMessage {
+            id: self.next_request_id(),
+            topics,
+        });
+        self.send(&msg)?;
+
+        let response = self.receive()?;
+        match response {
+            ServerMessage::Subscribed(subscribed) => Ok(subscribed),
+            ServerMessage::Error(err) => {
+                Err(SessionError::ServerError { code: err.code, message: err.message, retry_after_ms: err.retry_after_ms })
+            }
+            _ => Err(SessionError::ProtocolError("Unexpected response to subscribe".into())),
+        }
+    }
+
+    /// Unsubscribe from event topics.
+    pub fn unsubscribe(&mut self, topics: Vec<String>) -> Result<UnsubscribedMessage, SessionError> {
+        let msg = ClientMessage::Unsubscribe(UnsubscribeMessage {
+            id: self.next_request_id(),
+            topics,
+        });
+        self.send(&msg)?;
+
+        let response = self.receive()?;
+        match response {
+            ServerMessage::Unsubscribed(unsubscribed) => Ok(unsubscribed),
+            ServerMessage::Error(err) => {
+                Err(SessionError::ServerError { code: err.code, message: err.message, retry_after_ms: err.retry_after_ms })
+            }
+            _ => Err(SessionError::ProtocolError("Unexpected response to unsubscribe".into())),
+        }
+    }
+
+    /// Next subscription event, without blocking. `None` if nothing has
+    /// arrived yet.
+    pub fn poll_event(&mut self) -> Result<Option<EventMessage>, SessionError> {
+        if let Some(event) = self.events.pop_front() {
+            return Ok(Some(event));
+        }
+        loop {
+            if self.reader.buffer().is_empty() {
+                let stream = self.reader.get_ref();
+                stream.set_nonblocking(true)
+                    .map_err(|e| SessionError::IoError(e.to_string()))?;
+                let ready = stream.peek(&mut [0u8; 1]);
+                stream.set_nonblocking(false)
+                    .map_err(|e| SessionError::IoError(e.to_string()))?;
+                match ready {
+                    Ok(0) => return Err(SessionError::ConnectionClosed),
+                    Ok(_) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(None),
+                    Err(e) => return Err(SessionError::IoError(e.to_string())),
+                }
+            }
+
+            let line = self.receive_line_bounded()?;
+            let msg: ServerMessage = serde_json::from_str(&line)
+                .map_err(|e| SessionError::ProtocolError(format!("Invalid JSON: {}", e)))?;
+            // Anything else is a late reply to a request already given up on
+            if let ServerMessage::Event(event) = msg {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    /// Close the connection gracefully.
+    ///
+    /// Sends `bye` so the server records a clean disconnect rather than a
+    /// dropped connection. No reply is expected.
+    pub fn close(mut self) -> Result<(), SessionError> {
+        let msg = ClientMessage::Bye(ByeMessage {
+            id: self.next_request_id(),
+        });
+        self.send(&msg)
+    }
+
     fn next_request_id(&mut self) -> String {
         let id = self.next_id;
         self.next_id += 1;
-        id.to_string()
+        self.pending_id = id.to_string();
+        self.pending_id.clone()
     }
 
     fn send(&mut self, msg: &ClientMessage) -> Result<(), SessionError> {
         let json = serde_json::to_string(msg)
             .map_err(|e| SessionError::ProtocolError(e.to_string()))?;
+        // The server disconnects on oversize lines; fail here with a usable error instead.
+        if let Some(limit) = self.max_message_bytes {
+            if json.len() as u64 > limit {
+                return Err(SessionError::MessageTooLarge { size: json.len() as u64, limit });
+            }
+        }
         writeln!(self.writer, "{}", json)
             .map_err(|e| SessionError::IoError(e.to_string()))?;
         self.writer.flush()
@@ -414,10 +584,33 @@ impl SessionClient {
     const MAX_LINE_BYTES: usize = 10 * 1024 * 1024;
 
     fn receive(&mut self) -> Result<ServerMessage, SessionError> {
-        let line = self.receive_line_bounded()?;
+        loop {
+            let line = self.receive_line_bounded()?;
 
-        serde_json::from_str(&line)
-            .map_err(|e| SessionError::ProtocolError(format!("Invalid JSON: {}", e)))
+            let msg: ServerMessage = serde_json::from_str(&line)
+                .map_err(|e| SessionError::ProtocolError(format!("Invalid JSON: {}", e)))?;
+
+            // Events interleave with replies; keep them for `poll_event`
+            if let ServerMessage::Event(event) = msg {
+                self.events.push_back(event);
+                continue;
+            }
+
+            // Interim timeout from the server: the request is still running.
+            // One for an earlier request we already gave up on is stale.
+            if let ServerMessage::Error(err) = &msg {
+                if err.code == ProtocolErrorCode::Timeout {
+                    if err.id != self.pending_id {
+                        continue;
+                    }
+                    return Err(SessionError::Timeout {
+                        request_id: err.id.clone(),
+                        retry_after_ms: err.retry_after_ms,
+                    });
+                }
+            }
+            return Ok(msg);
+        }
     }
 
     /// Read a line with bounded size to prevent memory exhaustion.
@@ -456,6 +649,12 @@ impl SessionClient {
                     // Retry on interrupt
                     continue;
                 }
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                    return Err(SessionError::Timeout {
+                        request_id: self.pending_id.clone(),
+                        retry_after_ms: None,
+                    });
+                }
                 Err(e) => {
                     return Err(SessionError::IoError(e.to_string()));
                 }
@@ -476,11 +675,24 @@ pub enum SessionError {
     IoError(String),
     ProtocolError(String),
     ServerError {
-        code: String,
+        code: ProtocolErrorCode,
         message: String,
         /// Retry hint from server (e.g., for writer_conflict, rate_limited).
         retry_after_ms: Option<u64>,
     },
+    /// No reply within the request timeout, or the server reported the
+    /// request as still running. The request was not lost, but its outcome
+    /// is unknown.
+    Timeout {
+        request_id: String,
+        /// Server's hint for when to check again (interim timeout only).
+        retry_after_ms: Option<u64>,
+    },
+    /// Request exceeds the server's `max_message_bytes`; it was never sent.
+    MessageTooLarge {
+        size: u64,
+        limit: u64,
+    },
 }
 
 impl std::fmt::Display for SessionError {
@@ -492,12 +704,378 @@ impl std::fmt::Display for SessionError {
             SessionError::IoError(msg) => write!(f, "I/O error: {}", msg),
             SessionError::ProtocolError(msg) => write!(f, "Protocol error: {}", msg),
             SessionError::ServerError { code, message, .. } => write!(f, "Server error [{}]: {}", code, message),
+            SessionError::Timeout { request_id, .. } => write!(f, "Request {} timed out", request_id),
+            SessionError::MessageTooLarge { size, limit } => {
+                write!(f, "Request is {} bytes, server accepts at most {}", size, limit)
+            }
         }
     }
 }
 
 impl std::error::Error for SessionError {}
 
+// ============================================================================
+// Token resolution
+// ============================================================================
+
+/// Environment variable holding the session token.
+pub const TOKEN_ENV: &str = "VISIGRID_SESSION_TOKEN";
+
+/// Environment variable naming a file that holds the session token.
+pub const TOKEN_FILE_ENV: &str = "VISIGRID_SESSION_TOKEN_FILE";
+
+/// Pick the session token by precedence: `--token-file`, then
+/// `VISIGRID_SESSION_TOKEN`, then the file named by `VISIGRID_SESSION_TOKEN_FILE`.
+///
+/// Returns `Ok(None)` when no source is configured. Token files are trimmed
+/// (secret managers often write a trailing newline) and must not be empty.
+pub fn resolve_token(
+    token_file: Option<&Path>,
+    env_token: Option<String>,
+    env_token_file: Option<PathBuf>,
+) -> Result<Option<String>, String> {
+    if let Some(path) = token_file {
+        return read_token_file(path).map(Some);
+    }
+    if let Some(token) = env_token {
+        return Ok(Some(token));
+    }
+    match env_token_file {
+        Some(path) => read_token_file(&path).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn read_token_file(path: &Path) -> Result<String, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read token file {}: {}", path.display(), e))?;
+    let token = raw.trim();
+    if token.is_empty() {
+        return Err(format!("token file {} is empty", path.display()));
+    }
+    Ok(token.to_string())
+}
+
+// ============================================================================
+// Ops files
+// ============================================================================
+
+/// Parsed ops from one input to `apply`.
+#[derive(Debug, Clone)]
+pub struct OpsFile {
+    pub path: PathBuf,
+    pub ops: Vec<Op>,
+    /// 1-based source line of each op, for error reports.
+    pub lines: Vec<usize>,
+}
+
+/// Parse ops as a JSON array or as JSONL (one op per line, blank lines skipped).
+///
+/// Errors carry the 1-based line number of the offending JSONL line.
+pub fn parse_ops(path: PathBuf, text: &str) -> Result<OpsFile, String> {
+    if text.trim_start().starts_with('[') {
+        let ops: Vec<Op> = serde_json::from_str(text)
+            .map_err(|e| format!("failed to parse ops JSON: {}", e))?;
+        let lines = vec![1; ops.len()];
+        return Ok(OpsFile { path, ops, lines });
+    }
+    let mut ops = Vec::new();
+    let mut lines = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        ops.push(serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e))?);
+        lines.push(i + 1);
+    }
+    Ok(OpsFile { path, ops, lines })
+}
+
+/// Read every `*.jsonl` file in `dir`, sorted by file name.
+///
+/// All files are parsed up front so a malformed later step fails before
+/// anything is applied.
+pub fn read_ops_dir(dir: &Path) -> Result<Vec<OpsFile>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("failed to read {}: {}", dir.display(), e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    paths.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+            parse_ops(path.clone(), &text).map_err(|e| format!("{}: {}", path.display(), e))
+        })
+        .collect()
+}
+
+/// Outcome of applying a sequence of ops files.
+#[derive(Debug)]
+pub struct ApplyFilesReport {
+    pub applied: usize,
+    pub total: usize,
+    pub revision: u64,
+    pub failure: Option<ApplyFailure>,
+}
+
+/// The op that stopped an apply.
+#[derive(Debug)]
+pub struct ApplyFailure {
+    pub file_index: usize,
+    /// 1-based source line within the file.
+    pub line: usize,
+    /// 0-based index across all files.
+    pub op_index: usize,
+    pub error: OpError,
+}
+
+/// Apply `files` in order, `chunk_len` ops per request, stopping at the first
+/// op error.
+///
+/// `send` performs one request (ops, expected revision). With an expected
+/// revision, each request must land on the revision the previous one produced.
+/// `on_file` runs after each file applies in full, with the cumulative op count
+/// and revision.
+pub fn apply_ops_files<E>(
+    files: &[OpsFile],
+    chunk_len: Option<usize>,
+    mut expected_revision: Option<u64>,
+    mut send: impl FnMut(Vec<Op>, Option<u64>) -> Result<ApplyOpsResultMessage, E>,
+    mut on_file: impl FnMut(usize, &OpsFile, usize, u64),
+) -> Result<ApplyFilesReport, E> {
+    let total = files.iter().map(|f| f.ops.len()).sum();
+    let mut report = ApplyFilesReport { applied: 0, total, revision: 0, failure: None };
+    let mut sent = 0;
+
+    for (file_index, file) in files.iter().enumerate() {
+        let chunk_len = chunk_len.unwrap_or(file.ops.len()).max(1);
+        let mut file_sent = 0;
+        for chunk in file.ops.chunks(chunk_len) {
+            let result = send(chunk.to_vec(), expected_revision)?;
+            report.applied += result.applied;
+            report.revision = result.revision;
+            if let Some(error) = result.error {
+                let line = file.lines.get(file_sent + error.op_index).copied().unwrap_or(0);
+                let op_index = sent + error.op_index;
+                report.failure = Some(ApplyFailure { file_index, line, op_index, error });
+                return Ok(report);
+            }
+            file_sent += chunk.len();
+            sent += chunk.len();
+            if expected_revision.is_some() {
+                expected_revision = Some(result.revision);
+            }
+        }
+        on_file(file_index, file, report.applied, report.revision);
+    }
+    Ok(report)
+}
+
+// ============================================================================
+// REPL: many commands over one connection
+// ============================================================================
+
+/// One line of `repl` input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplCommand {
+    /// `inspect <A1 | A1:B2 | workbook> [--sheet N]`
+    Inspect { target: String, sheet: usize },
+    /// `apply <ops.jsonl> [--atomic] [--expected-revision N]`
+    Apply { path: PathBuf, atomic: bool, expected_revision: Option<u64> },
+    /// `stats`
+    Stats,
+    /// `quit` or `exit`
+    Quit,
+}
+
+/// Parse one line of `repl` input. Blank lines and `#` comments are `None`.
+pub fn parse_repl_line(line: &str) -> Result<Option<ReplCommand>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let mut words = line.split_whitespace();
+    let verb = words.next().unwrap_or_default().to_ascii_lowercase();
+    let mut positional = Vec::new();
+    let mut sheet = 0;
+    let mut atomic = false;
+    let mut expected_revision = None;
+    while let Some(word) = words.next() {
+        match word {
+            "--sheet" if verb == "inspect" => {
+                sheet = words.next().and_then(|n| n.parse().ok())
+                    .ok_or("--sheet needs a sheet index")?;
+            }
+            "--atomic" if verb == "apply" => atomic = true,
+            "--expected-revision" if verb == "apply" => {
+                expected_revision = Some(words.next().and_then(|n| n.parse().ok())
+                    .ok_or("--expected-revision needs a revision number")?);
+            }
+            w if w.starts_with("--") => return Err(format!("{}: unknown option {}", verb, w)),
+            w => positional.push(w),
+        }
+    }
+
+    let cmd = match (verb.as_str(), positional.as_slice()) {
+        ("inspect", [target]) => ReplCommand::Inspect { target: target.to_string(), sheet },
+        ("inspect", _) => return Err("usage: inspect <A1 | A1:B2 | workbook> [--sheet N]".into()),
+        ("apply", ["-"]) => return Err("apply reads ops from a file; stdin carries the commands".into()),
+        ("apply", [path]) => ReplCommand::Apply { path: PathBuf::from(path), atomic, expected_revision },
+        ("apply", _) => return Err("usage: apply <ops.jsonl> [--atomic] [--expected-revision N]".into()),
+        ("stats", []) => ReplCommand::Stats,
+        ("quit" | "exit", []) => ReplCommand::Quit,
+        _ => return Err(format!("unknown command: {} (expected inspect, apply, stats, or quit)", line)),
+    };
+    Ok(Some(cmd))
+}
+
+/// Why a REPL command failed.
+enum ReplError {
+    /// The command failed; the connection is still usable.
+    Command { error: String, message: String, exit_code: u8 },
+    /// The connection is closed or in an unknown state; stop reading input.
+    Connection(SessionError),
+}
+
+impl From<SessionError> for ReplError {
+    fn from(err: SessionError) -> Self {
+        match err {
+            SessionError::ConnectionClosed
+            | SessionError::ConnectionFailed(_)
+            | SessionError::IoError(_)
+            | SessionError::ProtocolError(_)
+            | SessionError::Timeout { .. } => ReplError::Connection(err),
+            _ => {
+                let out = crate::exit_codes::SessionErrorOutput::from_session_error(&err);
+                ReplError::Command { error: out.error, message: out.message, exit_code: out.exit_code }
+            }
+        }
+    }
+}
+
+/// Read commands from `input` and run each over `client`, writing one JSON
+/// line per command to `out`:
+///
+/// `{"command": "...", "ok": true, "result": {...}}` or
+/// `{"command": "...", "ok": false, "error": "...", "message": "...", "exit_code": N}`.
+///
+/// A failed command does not stop the loop; its `exit_code` is what the
+/// standalone command would exit with. Returns the exit code of the first
+/// failed command (0 if none). A lost connection ends the loop with an error.
+pub fn run_repl(
+    client: &mut SessionClient,
+    input: impl BufRead,
+    mut out: impl Write,
+) -> Result<u8, SessionError> {
+    let run_id = Uuid::new_v4();
+    let mut applies = 0usize;
+    let mut first_failure = 0u8;
+
+    for line in input.lines() {
+        let line = line.map_err(|e| SessionError::IoError(e.to_string()))?;
+        let result = match parse_repl_line(&line) {
+            Ok(None) => continue,
+            Ok(Some(ReplCommand::Quit)) => break,
+            Ok(Some(cmd)) => run_repl_command(client, &cmd, &run_id, &mut applies),
+            Err(message) => Err(ReplError::Command {
+                error: "usage".to_string(),
+                message,
+                exit_code: crate::exit_codes::EXIT_USAGE,
+            }),
+        };
+        let (record, fatal) = match result {
+            Ok(value) => (serde_json::json!({ "command": line.trim(), "ok": true, "result": value }), None),
+            Err(ReplError::Command { error, message, exit_code }) => {
+                if first_failure == 0 {
+                    first_failure = exit_code;
+                }
+                (serde_json::json!({
+                    "command": line.trim(), "ok": false,
+                    "error": error, "message": message, "exit_code": exit_code,
+                }), None)
+            }
+            Err(ReplError::Connection(err)) => {
+                let record = serde_json::json!({
+                    "command": line.trim(), "ok": false,
+                    "error": crate::exit_codes::SessionErrorOutput::from_session_error(&err).error,
+                    "message": err.to_string(),
+                    "exit_code": crate::exit_codes::session_exit_code(&err),
+                });
+                (record, Some(err))
+            }
+        };
+        writeln!(out, "{}", record).and_then(|_| out.flush())
+            .map_err(|e| SessionError::IoError(e.to_string()))?;
+        if let Some(err) = fatal {
+            return Err(err);
+        }
+    }
+    Ok(first_failure)
+}
+
+fn run_repl_command(
+    client: &mut SessionClient,
+    cmd: &ReplCommand,
+    run_id: &Uuid,
+    applies: &mut usize,
+) -> Result<serde_json::Value, ReplError> {
+    match cmd {
+        ReplCommand::Inspect { target, sheet } => {
+            let result = if target.eq_ignore_ascii_case("workbook") {
+                client.inspect_workbook()?
+            } else {
+                let (sr, sc, er, ec) = crate::sheet_ops::parse_target(target).ok_or_else(|| ReplError::Command {
+                    error: "usage".to_string(),
+                    message: format!("invalid cell reference: {}", target),
+                    exit_code: crate::exit_codes::EXIT_USAGE,
+                })?;
+                if (sr, sc) == (er, ec) {
+                    client.inspect_cell(*sheet, sr, sc)?
+                } else {
+                    client.inspect_range(*sheet, sr, sc, er, ec)?
+                }
+            };
+            Ok(serde_json::to_value(&result).unwrap_or_default())
+        }
+        ReplCommand::Apply { path, atomic, expected_revision } => {
+            let input_error = |message: String| ReplError::Command {
+                error: "input".to_string(),
+                message,
+                exit_code: crate::exit_codes::EXIT_SESSION_INPUT,
+            };
+            let text = fs::read_to_string(path)
+                .map_err(|e| input_error(format!("failed to read {}: {}", path.display(), e)))?;
+            let file = parse_ops(path.clone(), &text).map_err(input_error)?;
+            // Same idempotency scheme as `apply`: one key per request
+            let key = format!("{}:{}", run_id, applies);
+            *applies += 1;
+            let result = client.apply_ops(file.ops.clone(), *atomic, *expected_revision, Some(key))?;
+            if let Some(err) = &result.error {
+                let line = file.lines.get(err.op_index).copied().unwrap_or(0);
+                return Err(ReplError::Command {
+                    error: err.code.to_string(),
+                    message: format!(
+                        "line {}: {} (applied {}/{}, revision {})",
+                        line, err.message, result.applied, result.total, result.revision
+                    ),
+                    exit_code: crate::exit_codes::EXIT_SESSION_PARTIAL,
+                });
+            }
+            Ok(serde_json::json!({
+                "applied": result.applied,
+                "total": result.total,
+                "revision": result.revision,
+            }))
+        }
+        ReplCommand::Stats => Ok(serde_json::to_value(client.stats()?).unwrap_or_default()),
+        ReplCommand::Quit => Ok(serde_json::Value::Null),
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -600,4 +1178,426 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), SessionError::ConnectionClosed));
     }
+
+    fn token_file(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_token_flag_beats_env_and_env_file() {
+        let flag = token_file("from-flag\n");
+        let env_file = token_file("from-env-file");
+        let token = resolve_token(
+            Some(flag.path()),
+            Some("from-env".to_string()),
+            Some(env_file.path().to_path_buf()),
+        ).unwrap();
+        assert_eq!(token.as_deref(), Some("from-flag"));
+    }
+
+    #[test]
+    fn test_token_env_beats_env_file() {
+        let env_file = token_file("from-env-file");
+        let token = resolve_token(
+            None,
+            Some("from-env".to_string()),
+            Some(env_file.path().to_path_buf()),
+        ).unwrap();
+        assert_eq!(token.as_deref(), Some("from-env"));
+    }
+
+    #[test]
+    fn test_token_env_file_fallback_trims() {
+        let env_file = token_file("  from-env-file \r\n");
+        let token = resolve_token(None, None, Some(env_file.path().to_path_buf())).unwrap();
+        assert_eq!(token.as_deref(), Some("from-env-file"));
+    }
+
+    #[test]
+    fn test_token_none_configured() {
+        assert_eq!(resolve_token(None, None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_token_file_errors() {
+        let empty = token_file(" \n");
+        let err = resolve_token(Some(empty.path()), None, None).unwrap_err();
+        assert!(err.contains("is empty"), "got: {}", err);
+
+        let missing = Path::new("/nonexistent/visigrid-token");
+        let err = resolve_token(Some(missing), Some("from-env".to_string()), None).unwrap_err();
+        assert!(err.contains("failed to read token file"), "got: {}", err);
+    }
+
+    fn discovered(n: u128, workbook: Option<&Path>) -> DiscoveryFile {
+        DiscoveryFile {
+            session_id: Uuid::from_u128(n),
+            port: 0,
+            pid: 0,
+            workbook_path: workbook.map(Path::to_path_buf),
+            workbook_title: "book".to_string(),
+            created_at: Utc::now(),
+            protocol_version: PROTOCOL_VERSION,
+        }
+    }
+
+    /// Create `rel` under `dir` (with parents) and return its path.
+    fn touch(dir: &Path, rel: &str) -> PathBuf {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, b"").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_auto_select_prefers_workbook_in_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let here = touch(dir.path(), "project/reports/q3.sheet");
+        let elsewhere = touch(dir.path(), "other/q3.sheet");
+        let sessions = vec![
+            discovered(1, Some(&elsewhere)),
+            discovered(2, Some(&here)),
+            discovered(3, None),
+        ];
+
+        let picked = auto_select_session(sessions.clone(), Some(&dir.path().join("project")));
+        assert_eq!(picked.map(|s| s.session_id), Some(Uuid::from_u128(2)));
+
+        // The workbook's own directory counts too
+        let picked = auto_select_session(sessions, Some(&dir.path().join("project/reports")));
+        assert_eq!(picked.map(|s| s.session_id), Some(Uuid::from_u128(2)));
+    }
+
+    #[test]
+    fn test_auto_select_ambiguous_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = touch(dir.path(), "project/a.sheet");
+        let b = touch(dir.path(), "project/sub/b.sheet");
+        let sessions = vec![discovered(1, Some(&a)), discovered(2, Some(&b))];
+
+        // Two workbooks under the cwd: the user has to choose
+        assert!(auto_select_session(sessions.clone(), Some(&dir.path().join("project"))).is_none());
+        // None under the cwd: same
+        let unrelated = tempfile::tempdir().unwrap();
+        assert!(auto_select_session(sessions, Some(unrelated.path())).is_none());
+        // A lone session is picked wherever its workbook is
+        let lone = vec![discovered(3, Some(&a))];
+        assert_eq!(auto_select_session(lone, Some(unrelated.path())).map(|s| s.session_id), Some(Uuid::from_u128(3)));
+    }
+
+    const MOCK_WELCOME: &str =
+        r#"{"type":"welcome","id":"1","session_id":"s","protocol_version":1,"revision":0,"capabilities":["apply"]}"#;
+
+    /// Accept one client, answer its hello, then hand each later request
+    /// line to `reply` (which may sleep or write anything).
+    fn mock_server(
+        reply: impl FnOnce(&str, &mut TcpStream) + Send + 'static,
+    ) -> (DiscoveryFile, std::thread::JoinHandle<()>) {
+        mock_server_with_welcome(MOCK_WELCOME, reply)
+    }
+
+    /// Like `mock_server`, but answers the hello with `welcome`.
+    fn mock_server_with_welcome(
+        welcome: &'static str,
+        reply: impl FnOnce(&str, &mut TcpStream) + Send + 'static,
+    ) -> (DiscoveryFile, std::thread::JoinHandle<()>) {
+        use std::io::BufRead;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            writeln!(stream, "{}", welcome).unwrap();
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            reply(&line, &mut stream);
+        });
+        let discovery = DiscoveryFile {
+            session_id: Uuid::nil(),
+            port,
+            pid: 0,
+            workbook_path: None,
+            workbook_title: "mock".to_string(),
+            created_at: Utc::now(),
+            protocol_version: PROTOCOL_VERSION,
+        };
+        (discovery, handle)
+    }
+
+    /// Accept one client, answer its hello, then pass each of the next
+    /// `requests` lines to `reply`.
+    fn mock_server_many(
+        requests: usize,
+        mut reply: impl FnMut(&str, &mut TcpStream) + Send + 'static,
+    ) -> (DiscoveryFile, std::thread::JoinHandle<()>) {
+        let (discovery, handle) = mock_server(move |first, stream| {
+            use std::io::BufRead;
+
+            reply(first, stream);
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            for _ in 1..requests {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                reply(&line, stream);
+            }
+        });
+        (discovery, handle)
+    }
+
+    #[test]
+    fn test_parse_repl_line() {
+        assert_eq!(parse_repl_line("  # comment").unwrap(), None);
+        assert_eq!(parse_repl_line("").unwrap(), None);
+        assert_eq!(
+            parse_repl_line("inspect B2:C4 --sheet 1").unwrap(),
+            Some(ReplCommand::Inspect { target: "B2:C4".into(), sheet: 1 })
+        );
+        assert_eq!(
+            parse_repl_line("apply ops.jsonl --atomic --expected-revision 7").unwrap(),
+            Some(ReplCommand::Apply { path: "ops.jsonl".into(), atomic: true, expected_revision: Some(7) })
+        );
+        assert_eq!(parse_repl_line("STATS").unwrap(), Some(ReplCommand::Stats));
+        assert_eq!(parse_repl_line("quit").unwrap(), Some(ReplCommand::Quit));
+        assert!(parse_repl_line("apply -").is_err());
+        assert!(parse_repl_line("inspect").is_err());
+        assert!(parse_repl_line("inspect A1 --atomic").is_err());
+        assert!(parse_repl_line("delete A1").is_err());
+    }
+
+    #[test]
+    fn test_repl_runs_commands_over_one_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let ops = dir.path().join("ops.jsonl");
+        fs::write(&ops, r#"{"op":"set_cell_value","sheet":0,"row":0,"col":0,"value":"42"}"#).unwrap();
+
+        // A single accepted connection serves both requests
+        let (discovery, server) = mock_server_many(2, |line, stream| {
+            if line.contains(r#""type":"apply_ops""#) {
+                assert!(line.contains(r#""id":"2""#), "request: {}", line);
+                writeln!(stream, r#"{{"type":"apply_ops_result","id":"2","applied":1,"total":1,"current_revision":1}}"#).unwrap();
+            } else {
+                assert!(line.contains(r#""type":"inspect""#), "request: {}", line);
+                assert!(line.contains(r#""id":"3""#), "request: {}", line);
+                writeln!(stream, r#"{{"type":"inspect_result","id":"3","revision":1,"result":{{"result":"cell","raw":"42","display":"42"}}}}"#).unwrap();
+            }
+        });
+        let mut client = SessionClient::connect(&discovery, "t").unwrap();
+
+        let input = format!("apply {}\n\ninspect A1\nbogus\n", ops.display());
+        let mut out = Vec::new();
+        let code = run_repl(&mut client, input.as_bytes(), &mut out).unwrap();
+        server.join().unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(out).unwrap()
+            .lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 3, "blank line produces no output");
+        assert_eq!(lines[0]["ok"], true);
+        assert_eq!(lines[0]["result"]["applied"], 1);
+        assert_eq!(lines[0]["result"]["revision"], 1);
+        assert_eq!(lines[1]["ok"], true);
+        assert_eq!(lines[1]["command"], "inspect A1");
+        assert_eq!(lines[1]["result"]["result"]["display"], "42");
+        assert_eq!(lines[2]["ok"], false);
+        assert_eq!(lines[2]["exit_code"], crate::exit_codes::EXIT_USAGE);
+        assert_eq!(code, crate::exit_codes::EXIT_USAGE, "exit code of the first failure");
+    }
+
+    #[test]
+    fn test_close_sends_bye() {
+        let (discovery, server) = mock_server(|line, _stream| {
+            let msg: ClientMessage = serde_json::from_str(line.trim()).unwrap();
+            assert!(matches!(msg, ClientMessage::Bye(ref b) if b.id == "2"), "request: {}", line);
+        });
+        let client = SessionClient::connect(&discovery, "t").unwrap();
+        client.close().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_apply_ops_dir_in_filename_order() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("02-totals.jsonl"),
+            "{\"op\":\"set_cell_formula\",\"row\":2,\"col\":0,\"formula\":\"=A1+A2\"}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("01-inputs.jsonl"),
+            "{\"op\":\"set_cell_value\",\"row\":0,\"col\":0,\"value\":\"1\"}\n\n\
+             {\"op\":\"set_cell_value\",\"row\":1,\"col\":0,\"value\":\"2\"}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "not ops").unwrap();
+
+        let files = read_ops_dir(dir.path()).unwrap();
+        let names: Vec<_> = files.iter().map(|f| f.path.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names, ["01-inputs.jsonl", "02-totals.jsonl"]);
+        assert_eq!(files[0].lines, [1, 3]);
+
+        // Fake server: each request bumps the revision and must see the previous one
+        let mut revision = 40;
+        let mut sent = Vec::new();
+        let mut progress = Vec::new();
+        let report = apply_ops_files(
+            &files,
+            None,
+            Some(40),
+            |ops, expected| {
+                assert_eq!(expected, Some(revision));
+                revision += 1;
+                let applied = ops.len();
+                sent.extend(ops);
+                Ok::<_, SessionError>(ApplyOpsResultMessage {
+                    id: "r".to_string(), applied, total: applied, revision, error: None,
+                })
+            },
+            |i, _, applied, rev| progress.push((i, applied, rev)),
+        )
+        .unwrap();
+
+        assert!(matches!(sent[0], Op::SetCellValue { row: 0, .. }));
+        assert!(matches!(sent[1], Op::SetCellValue { row: 1, .. }));
+        assert!(matches!(sent[2], Op::SetCellFormula { row: 2, .. }));
+        assert_eq!(progress, [(0, 2, 41), (1, 3, 42)]);
+        assert_eq!((report.applied, report.total, report.revision), (3, 3, 42));
+        assert!(report.failure.is_none());
+    }
+
+    #[test]
+    fn test_apply_ops_files_stops_at_failing_line() {
+        let first = parse_ops(PathBuf::from("a.jsonl"),
+            "{\"op\":\"clear_cell\",\"row\":0,\"col\":0}\n\n{\"op\":\"clear_cell\",\"row\":1,\"col\":0}\n").unwrap();
+        let second = parse_ops(PathBuf::from("b.jsonl"), "{\"op\":\"clear_cell\",\"row\":5,\"col\":0}\n").unwrap();
+        let mut calls = 0;
+        let report = apply_ops_files(
+            &[first, second],
+            None,
+            None,
+            |_, _| {
+                calls += 1;
+                Ok::<_, SessionError>(ApplyOpsResultMessage {
+                    id: "r".to_string(), applied: 1, total: 2, revision: 7,
+                    error: Some(OpError {
+                        code: ProtocolErrorCode::ProtectedCell,
+                        message: "protected".to_string(),
+                        op_index: 1,
+                        suggestion: None,
+                    }),
+                })
+            },
+            |_, _, _, _| panic!("no file completed"),
+        )
+        .unwrap();
+
+        assert_eq!(calls, 1, "later files are not sent");
+        let failure = report.failure.unwrap();
+        assert_eq!((failure.file_index, failure.line, failure.op_index), (0, 3, 1));
+        assert_eq!((report.applied, report.total), (1, 3));
+    }
+
+    #[test]
+    fn test_slow_reply_times_out() {
+        let (discovery, server) = mock_server(|_, _| {
+            std::thread::sleep(Duration::from_millis(500));
+        });
+        let mut client = SessionClient::connect(&discovery, "t").unwrap();
+        client.set_request_timeout(Duration::from_millis(100)).unwrap();
+
+        match client.apply_ops(Vec::new(), true, None, None) {
+            Err(SessionError::Timeout { request_id, retry_after_ms }) => {
+                assert_eq!(request_id, "2");
+                assert_eq!(retry_after_ms, None);
+            }
+            other => panic!("expected Timeout, got {:?}", other.map(|r| r.revision)),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_interim_timeout_error_matches_request_id() {
+        let (discovery, server) = mock_server(|line, stream| {
+            assert!(line.contains(r#""id":"2""#), "request: {}", line);
+            // A stale interim reply for an earlier request is skipped
+            writeln!(stream, r#"{{"type":"error","id":"1","code":"timeout","message":"m","retry_after_ms":10}}"#).unwrap();
+            writeln!(stream, r#"{{"type":"error","id":"2","code":"timeout","message":"m","retry_after_ms":2000}}"#).unwrap();
+        });
+        let mut client = SessionClient::connect(&discovery, "t").unwrap();
+
+        match client.apply_ops(Vec::new(), true, None, None) {
+            Err(SessionError::Timeout { request_id, retry_after_ms }) => {
+                assert_eq!(request_id, "2");
+                assert_eq!(retry_after_ms, Some(2000));
+            }
+            other => panic!("expected Timeout, got {:?}", other.map(|r| r.revision)),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_apply_sends_idempotency_key() {
+        let (discovery, server) = mock_server(|line, stream| {
+            assert!(line.contains(r#""idempotency_key":"run-1:0""#), "request: {}", line);
+            writeln!(stream, r#"{{"type":"apply_ops_result","id":"2","applied":0,"total":0,"current_revision":7}}"#).unwrap();
+        });
+        let mut client = SessionClient::connect(&discovery, "t").unwrap();
+
+        let result = client.apply_ops(Vec::new(), true, None, Some("run-1:0".to_string())).unwrap();
+        assert_eq!(result.revision, 7);
+        assert_eq!(client.revision(), 7);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_oversize_apply_rejected_before_sending() {
+        let (discovery, server) = mock_server_with_welcome(
+            r#"{"type":"welcome","id":"1","session_id":"s","protocol_version":1,"revision":0,"capabilities":["apply"],"max_message_bytes":256}"#,
+            |line, _| assert_eq!(line, "", "oversize apply reached the server"),
+        );
+        let mut client = SessionClient::connect(&discovery, "t").unwrap();
+        assert_eq!(client.max_message_bytes(), Some(256));
+
+        let ops = (0..20)
+            .map(|col| Op::SetCellValue { sheet: 0, row: 0, col, value: "x".repeat(20) })
+            .collect();
+        match client.apply_ops(ops, false, None, None) {
+            Err(SessionError::MessageTooLarge { size, limit }) => {
+                assert!(size > 256, "size: {}", size);
+                assert_eq!(limit, 256);
+            }
+            other => panic!("expected MessageTooLarge, got {:?}", other.map(|r| r.revision)),
+        }
+        drop(client);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_refresh_info_matches_welcome_and_current_revision() {
+        let (discovery, server) = mock_server(|line, stream| {
+            assert!(line.contains(r#""type":"session_info""#), "request: {}", line);
+            assert!(line.contains(r#""id":"2""#), "request: {}", line);
+            writeln!(
+                stream,
+                r#"{{"type":"session_info_result","id":"2","session_id":"s","protocol_version":1,"capabilities":["apply","stats"],"workbook_title":"mock","revision":7}}"#
+            )
+            .unwrap();
+        });
+        let mut client = SessionClient::connect(&discovery, "t").unwrap();
+        assert_eq!(client.revision(), 0);
+        assert!(!client.capabilities().stats);
+
+        let info = client.refresh_info().unwrap();
+        assert_eq!(info.session_id, client.session_id());
+        assert_eq!(info.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(info.workbook_title, discovery.workbook_title);
+        assert_eq!(info.revision, 7);
+        assert_eq!(client.revision(), 7);
+        assert!(client.capabilities().apply);
+        assert!(client.capabilities().stats);
+        server.join().unwrap();
+    }
 }
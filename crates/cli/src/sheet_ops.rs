@@ -8,14 +8,18 @@
 //! The Lua API provides an agent-friendly shim over the existing grid.* runtime.
 
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::Read;
 use std::path::Path;
 use std::rc::Rc;
 
 use mlua::{Lua, Result as LuaResult, Table, Value as LuaValue};
-use visigrid_engine::sheet::Sheet;
+use visigrid_engine::cell::CellValue;
+use visigrid_engine::sheet::{ProtectedRange, Sheet};
 use visigrid_engine::workbook::Workbook;
 
 use crate::replay::ReplayFingerprint;
+use crate::session::Op;
 use crate::{CliError, EXIT_EVAL_ERROR};
 
 /// Result of executing a sheet build script.
@@ -33,6 +37,8 @@ pub struct SheetApplyResult {
     pub verified: Option<bool>,
     /// Cells changed count.
     pub cells_changed: usize,
+    /// Cells the script set or cleared, as (sheet, row, col).
+    pub cells_touched: std::collections::HashSet<(usize, usize, usize)>,
     /// Semantic metadata (target -> {key: value}).
     pub metadata: CellMetadata,
 }
@@ -50,8 +56,8 @@ struct BuildState {
     semantic_ops: usize,
     /// Count of style operations (not hashed).
     style_ops: usize,
-    /// Track which cells were touched.
-    cells_touched: std::collections::HashSet<(usize, usize)>,
+    /// Track which cells were touched, as (sheet, row, col).
+    cells_touched: std::collections::HashSet<(usize, usize, usize)>,
     /// Semantic metadata (target -> {key: value}).
     /// BTreeMap for deterministic iteration order.
     metadata: CellMetadata,
@@ -104,6 +110,7 @@ impl BuildState {
 /// - `clear(cell)` — clear cell
 /// - `meta(target, table)` — set semantic metadata (affects fingerprint)
 /// - `style(target, table)` — set presentation style (excluded from fingerprint)
+/// - `protect(target)` — reject session edits to a range (excluded from fingerprint)
 pub fn execute_build_script(script_path: &Path, verify_fp: Option<&str>) -> Result<SheetApplyResult, CliError> {
     let script = std::fs::read_to_string(script_path)
         .map_err(|e| CliError::io(format!("Failed to read {}: {}", script_path.display(), e)))?;
@@ -121,7 +128,7 @@ pub fn execute_build_script(script_path: &Path, verify_fp: Option<&str>) -> Resu
         .map_err(|e| {
             let msg = format!("Lua error: {}", e);
             let hint = if msg.contains("attempt to call a nil value") {
-                Some("Available functions: set(cell, value), clear(cell), meta(target, table), style(target, table)".to_string())
+                Some("Available functions: set(cell, value), clear(cell), meta(target, table), style(target, table), protect(target)".to_string())
             } else {
                 None
             };
@@ -156,10 +163,34 @@ pub fn execute_build_script(script_path: &Path, verify_fp: Option<&str>) -> Resu
         fingerprint,
         verified,
         cells_changed: state.cells_touched.len(),
+        cells_touched: state.cells_touched.clone(),
         metadata: state.metadata.clone(),
     })
 }
 
+/// The concrete cell changes a build produced, as `apply` ops.
+///
+/// One op per touched or non-empty cell in (sheet, row, col) order:
+/// formulas as `set_cell_formula`, values as `set_cell_value`, and cells the
+/// script touched but left empty as `clear_cell`. Style and metadata are not
+/// part of the patch.
+pub fn build_patch(result: &SheetApplyResult) -> Vec<Op> {
+    let mut patch: BTreeMap<(usize, usize, usize), Op> = result.cells_touched.iter()
+        .map(|&(sheet, row, col)| ((sheet, row, col), Op::ClearCell { sheet, row, col }))
+        .collect();
+    for (sheet, s) in result.workbook.sheets().iter().enumerate() {
+        for (&(row, col), cell) in s.cells_iter() {
+            let op = match &cell.value {
+                CellValue::Empty => continue,
+                CellValue::Formula { source, .. } => Op::SetCellFormula { sheet, row, col, formula: source.clone() },
+                value => Op::SetCellValue { sheet, row, col, value: value.raw_display() },
+            };
+            patch.insert((sheet, row, col), op);
+        }
+    }
+    patch.into_values().collect()
+}
+
 /// Register the agent-friendly Lua API.
 fn register_agent_api(lua: &Lua, state: Rc<RefCell<BuildState>>) -> LuaResult<()> {
     let globals = lua.globals();
@@ -177,7 +208,7 @@ fn register_agent_api(lua: &Lua, state: Rc<RefCell<BuildState>>) -> LuaResult<()
             let mut state = state.borrow_mut();
             state.ensure_sheet(0);
             state.sheet_mut(0).set_value(row, col, &value_str);
-            state.cells_touched.insert((row, col));
+            state.cells_touched.insert((0, row, col));
             state.hash_semantic(&format!("set:{}:{}:{}", row, col, value_str));
 
             Ok(())
@@ -195,7 +226,7 @@ fn register_agent_api(lua: &Lua, state: Rc<RefCell<BuildState>>) -> LuaResult<()
             let mut state = state.borrow_mut();
             state.ensure_sheet(0);
             state.sheet_mut(0).set_value(row, col, "");
-            state.cells_touched.insert((row, col));
+            state.cells_touched.insert((0, row, col));
             state.hash_semantic(&format!("clear:{}:{}", row, col));
 
             Ok(())
@@ -275,6 +306,26 @@ fn register_agent_api(lua: &Lua, state: Rc<RefCell<BuildState>>) -> LuaResult<()
         globals.set("style", style_fn)?;
     }
 
+    // protect(target) — reject session edits to a cell or range (excluded from fingerprint)
+    // Examples: protect("A1:D1"), protect("B5")
+    {
+        let state = state.clone();
+        let protect_fn = lua.create_function(move |_, target: String| {
+            let (start_row, start_col, end_row, end_col) = parse_target(&target)
+                .ok_or_else(|| mlua::Error::external(format!("Invalid target: {}", target)))?;
+
+            let mut state = state.borrow_mut();
+            state.ensure_sheet(0);
+            state.sheet_mut(0).protect(ProtectedRange::new(start_row, start_col, end_row, end_col));
+
+            // Protection is access control, not content: counted with style ops
+            state.style_ops += 1;
+
+            Ok(())
+        })?;
+        globals.set("protect", protect_fn)?;
+    }
+
     // Also expose grid.* API for compatibility with existing scripts
     register_grid_api_compat(lua, state)?;
 
@@ -301,7 +352,7 @@ fn register_grid_api_compat(lua: &Lua, state: Rc<RefCell<BuildState>>) -> LuaRes
             let mut state = state.borrow_mut();
             state.ensure_sheet(sheet - 1);
             state.sheet_mut(sheet - 1).set_value(row, col, &value_str);
-            state.cells_touched.insert((row, col));
+            state.cells_touched.insert((sheet - 1, row, col));
             state.hash_semantic(&format!("set:{}:{}:{}:{}", sheet, row, col, value_str));
 
             Ok(())
@@ -331,7 +382,7 @@ fn register_grid_api_compat(lua: &Lua, state: Rc<RefCell<BuildState>>) -> LuaRes
                     .ok_or_else(|| mlua::Error::external(format!("Invalid cell reference: {}", cell)))?;
 
                 state.sheet_mut(sheet - 1).set_value(row, col, &value_str);
-                state.cells_touched.insert((row, col));
+                state.cells_touched.insert((sheet - 1, row, col));
                 op_parts.push(format!("{}:{}:{}", row, col, value_str));
             }
 
@@ -458,8 +509,110 @@ pub fn parse_cell_ref(s: &str) -> Option<(usize, usize)> {
     Some((row - 1, col))
 }
 
+/// Protect each `--protected` cell or range on `sheet`.
+pub fn protect_ranges(sheet: &mut Sheet, ranges: &[String]) -> Result<(), CliError> {
+    for range in ranges {
+        let (start_row, start_col, end_row, end_col) = parse_target(range).ok_or_else(|| {
+            CliError::args(format!("invalid --protected range '{}'", range))
+                .with_hint("use a cell or range like A1 or A1:D10")
+        })?;
+        sheet.protect(ProtectedRange::new(start_row, start_col, end_row, end_col));
+    }
+    Ok(())
+}
+
+/// A parsed `sheet apply --check` assertion.
+#[derive(Debug)]
+pub struct BuildCheck {
+    pub source: String,
+    expr: visigrid_engine::formula::parser::ParsedExpr,
+}
+
+/// Outcome of one `--check` against the built workbook.
+#[derive(Debug, serde::Serialize)]
+pub struct CheckResult {
+    pub check: String,
+    pub passed: bool,
+    /// What the check evaluated to (`TRUE`, `FALSE`, or an error/other value).
+    pub value: String,
+    /// For a comparison, each non-literal operand and its value, e.g.
+    /// `("Summary!B7", "-12")`, so a failure shows the number that broke it.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub operands: Vec<(String, String)>,
+}
+
+/// Parse `--check` expressions up front so a typo fails before the build runs.
+///
+/// Checks are formulas without the leading `=`. `==` and `!=` are accepted
+/// as spellings of `=` and `<>`.
+pub fn parse_checks(checks: &[String]) -> Result<Vec<BuildCheck>, CliError> {
+    checks.iter().map(|check| {
+        let formula = format!("={}", normalize_check_ops(check.trim_start_matches('=')));
+        let expr = visigrid_engine::formula::parser::parse(&formula).map_err(|e| {
+            CliError::args(format!("invalid --check {:?}: {}", check, e))
+                .with_hint("write a formula that evaluates to TRUE, e.g. --check 'Summary!B7>=0'")
+        })?;
+        Ok(BuildCheck { source: check.clone(), expr })
+    }).collect()
+}
+
+/// Rewrite `==` to `=` and `!=` to `<>` outside string literals.
+fn normalize_check_ops(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_string = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '"' {
+            in_string = !in_string;
+        } else if !in_string && (c == '=' || c == '!') && chars.peek() == Some(&'=') {
+            chars.next();
+            out.push_str(if c == '=' { "=" } else { "<>" });
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Evaluate each check against the first sheet of `workbook`. A check passes
+/// only when it evaluates to `TRUE`.
+pub fn evaluate_checks(workbook: &Workbook, checks: &[BuildCheck]) -> Vec<CheckResult> {
+    use visigrid_engine::formula::eval::{evaluate, EvalResult};
+    use visigrid_engine::formula::parser::{bind_expr, format_expr_inner, Expr, Op};
+    use visigrid_engine::workbook::WorkbookLookup;
+
+    let Some(sheet_id) = workbook.sheet_id_at_idx(0) else {
+        return Vec::new();
+    };
+    let lookup = WorkbookLookup::new(workbook, sheet_id);
+    let sheet_name = |id| workbook.sheet_by_id(id).map(|s| s.name.clone());
+
+    checks.iter().map(|check| {
+        let bound = bind_expr(&check.expr, |name| workbook.sheet_id_by_name(name));
+        let result = evaluate(&bound, &lookup);
+
+        let mut operands = Vec::new();
+        if let Expr::BinaryOp { op, left, right } = &bound {
+            if matches!(op, Op::Lt | Op::Gt | Op::Eq | Op::LtEq | Op::GtEq | Op::NotEq) {
+                for side in [left, right] {
+                    if !matches!(**side, Expr::Number(_) | Expr::Text(_) | Expr::Boolean(_)) {
+                        operands.push((format_expr_inner(side, sheet_name), evaluate(side, &lookup).to_text()));
+                    }
+                }
+            }
+        }
+
+        CheckResult {
+            check: check.source.clone(),
+            passed: matches!(result, EvalResult::Boolean(true)),
+            value: result.to_text(),
+            operands,
+        }
+    }).collect()
+}
+
 /// Parse a target (cell or range) into (start_row, start_col, end_row, end_col).
-fn parse_target(s: &str) -> Option<(usize, usize, usize, usize)> {
+pub fn parse_target(s: &str) -> Option<(usize, usize, usize, usize)> {
     if let Some(colon_idx) = s.find(':') {
         let start = &s[..colon_idx];
         let end = &s[colon_idx + 1..];
@@ -486,6 +639,48 @@ pub struct CellInspectResult {
     pub header: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub column_name: Option<String>,
+    /// Cell comment/note (only with --include-comments)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<visigrid_engine::sheet::CellComment>,
+    /// Conditional formats covering the cell (only with --include-conditional)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conditional_formats: Vec<visigrid_engine::conditional_format::ConditionalFormat>,
+}
+
+/// One-line text form of a comment: newlines escaped, author appended.
+pub fn comment_label(comment: &visigrid_engine::sheet::CellComment) -> String {
+    let text = comment.text.replace('\n', "\\n");
+    match &comment.author {
+        Some(author) => format!("{} ({})", text, author),
+        None => text,
+    }
+}
+
+/// One-line text form of a conditional format, e.g.
+/// `B2:B10 less_than 0 (font #9C0006)` or `C2:C10 color_scale #F8696B..#63BE7B`.
+pub fn conditional_label(cf: &visigrid_engine::conditional_format::ConditionalFormat) -> String {
+    use visigrid_engine::conditional_format::{color_to_hex, ConditionalRule};
+
+    let range = if cf.start == cf.end {
+        format_cell_ref(cf.start.0, cf.start.1)
+    } else {
+        format!("{}:{}", format_cell_ref(cf.start.0, cf.start.1), format_cell_ref(cf.end.0, cf.end.1))
+    };
+    match &cf.rule {
+        ConditionalRule::Compare { operator, values, style } => {
+            let mut parts = Vec::new();
+            if let Some(c) = style.font_color { parts.push(format!("font {}", color_to_hex(c))); }
+            if let Some(c) = style.background_color { parts.push(format!("fill {}", color_to_hex(c))); }
+            if style.bold { parts.push("bold".to_string()); }
+            if style.italic { parts.push("italic".to_string()); }
+            let style = if parts.is_empty() { String::new() } else { format!(" ({})", parts.join(", ")) };
+            format!("{} {} {}{}", range, operator.as_str(), values.join(" and "), style)
+        }
+        ConditionalRule::ColorScale { stops } => {
+            let colors: Vec<String> = stops.iter().map(|s| color_to_hex(s.color)).collect();
+            format!("{} color_scale {}", range, colors.join(".."))
+        }
+    }
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -520,6 +715,12 @@ pub struct WorkbookInspectResult {
     pub path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub import_notes: Option<Vec<String>>,
+    /// Delimited input only: rows whose field count differs from the header/modal count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ragged_rows: Option<visigrid_io::csv::RaggedRows>,
+    /// Title/description/author block (presentation only, not fingerprinted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<visigrid_io::native::WorkbookMeta>,
 }
 
 /// Wrapper for --calc output with metadata.
@@ -527,6 +728,7 @@ pub struct WorkbookInspectResult {
 pub struct CalcOutput {
     pub format: String,
     pub sheet: String,
+    pub policy: CalcPolicy,
     pub results: Vec<CalcResult>,
 }
 
@@ -538,6 +740,32 @@ pub struct CalcResult {
     pub value_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Whether this result fails the run under the active `CalcPolicy`.
+    pub failed: bool,
+}
+
+/// Which --calc outcomes count as failures (`--fail-on-empty`, `--fail-on-error`).
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct CalcPolicy {
+    pub fail_on_empty: bool,
+    pub fail_on_error: bool,
+}
+
+impl Default for CalcPolicy {
+    fn default() -> Self {
+        Self { fail_on_empty: false, fail_on_error: true }
+    }
+}
+
+impl CalcPolicy {
+    /// Whether a result of the given `value_type` fails under this policy.
+    pub fn fails(&self, value_type: &str) -> bool {
+        match value_type {
+            "empty" => self.fail_on_empty,
+            "error" => self.fail_on_error,
+            _ => false,
+        }
+    }
 }
 
 /// Result of a `sheet import` operation.
@@ -582,6 +810,15 @@ pub struct SheetListEntry {
     pub max_col: usize,
 }
 
+/// Non-empty cell count and bounds for one sheet, returned by `--count`.
+#[derive(Debug, serde::Serialize)]
+pub struct SheetCount {
+    pub sheet: String,
+    pub non_empty_cells: usize,
+    pub max_row: usize,
+    pub max_col: usize,
+}
+
 /// Sparse inspect result (non-empty cells only).
 #[derive(Debug, serde::Serialize)]
 pub struct SparseInspectResult {
@@ -605,9 +842,11 @@ pub fn compute_sheet_fingerprint(workbook: &Workbook) -> ReplayFingerprint {
 /// The fingerprint includes:
 /// - Cell values/formulas (set operations)
 /// - Semantic metadata (meta operations)
+/// - Named ranges (name operations)
 /// - Does NOT include style (presentation only)
 ///
-/// Order is deterministic: cells sorted by (row, col), then metadata sorted by target, then by key.
+/// Order is deterministic: cells sorted by (row, col), then metadata sorted by target, then by key,
+/// then named ranges sorted by name.
 pub fn compute_sheet_fingerprint_with_meta(workbook: &Workbook, metadata: &CellMetadata) -> ReplayFingerprint {
     let mut hasher = blake3::Hasher::new();
     let mut op_count = 0;
@@ -616,18 +855,18 @@ pub fn compute_sheet_fingerprint_with_meta(workbook: &Workbook, metadata: &CellM
     for sheet_idx in 0..workbook.sheet_count() {
         if let Some(sheet) = workbook.sheet(sheet_idx) {
             // Collect cells and sort for deterministic order
-            let mut cells: Vec<((usize, usize), String)> = Vec::new();
+            let mut cells: Vec<((usize, usize), &str, String)> = Vec::new();
             for (&(row, col), cell) in sheet.cells_iter() {
                 let raw = cell.value.raw_display();
                 if !raw.is_empty() {
-                    cells.push(((row, col), raw.to_string()));
+                    cells.push(((row, col), visigrid_io::native::fingerprint_verb(&cell.value), raw.to_string()));
                 }
             }
             // Sort by (row, col) for deterministic order
-            cells.sort_by_key(|((r, c), _)| (*r, *c));
+            cells.sort_by_key(|((r, c), _, _)| (*r, *c));
 
-            for ((row, col), value) in cells {
-                let op = format!("set:{}:{}:{}", row, col, value);
+            for ((row, col), verb, value) in cells {
+                let op = format!("{}:{}:{}:{}", verb, row, col, value);
                 hasher.update(op.as_bytes());
                 hasher.update(b"\n");
                 op_count += 1;
@@ -645,26 +884,238 @@ pub fn compute_sheet_fingerprint_with_meta(workbook: &Workbook, metadata: &CellM
         }
     }
 
+    for op in visigrid_io::native::named_range_fingerprint_ops(workbook) {
+        hasher.update(op.as_bytes());
+        hasher.update(b"\n");
+        op_count += 1;
+    }
+
     let hash = hasher.finalize();
     let bytes: [u8; 16] = hash.as_bytes()[0..16].try_into().unwrap();
     ReplayFingerprint::new(op_count, bytes)
 }
 
+/// One cell that differs between two workbooks.
+#[derive(Debug, serde::Serialize)]
+pub struct CellDiff {
+    pub cell: String,
+    /// For changed cells: "value" (literal edited), "formula" (formula text
+    /// edited or a value replaced by a formula), or "result" (same formula,
+    /// different computed value)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+}
+
+/// Cell differences within one sheet, matched by name across the workbooks.
+#[derive(Debug, serde::Serialize)]
+pub struct SheetDiff {
+    pub sheet: String,
+    /// "changed", "added" (only in the new workbook), or "removed"
+    pub status: &'static str,
+    pub added: Vec<CellDiff>,
+    pub removed: Vec<CellDiff>,
+    pub changed: Vec<CellDiff>,
+}
+
+impl SheetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare two workbooks cell by cell (raw values and formulas, plus the
+/// computed results of unchanged formulas). Sheets are matched by name,
+/// case-insensitively; only sheets with differences are returned.
+pub fn diff_workbooks(old: &Workbook, new: &Workbook) -> Vec<SheetDiff> {
+    fn sheets(wb: &Workbook) -> Vec<&Sheet> {
+        (0..wb.sheet_count()).filter_map(|i| wb.sheet(i)).collect()
+    }
+    fn find<'a>(list: &[&'a Sheet], name: &str) -> Option<&'a Sheet> {
+        list.iter().copied().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+    let (old_sheets, new_sheets) = (sheets(old), sheets(new));
+
+    let mut diffs: Vec<SheetDiff> = new_sheets.iter()
+        .map(|sheet| diff_sheets(find(&old_sheets, &sheet.name), Some(*sheet)))
+        .collect();
+    diffs.extend(old_sheets.iter()
+        .filter(|sheet| find(&new_sheets, &sheet.name).is_none())
+        .map(|sheet| diff_sheets(Some(*sheet), None)));
+    diffs.retain(|d| !d.is_empty());
+    diffs
+}
+
+fn diff_sheets(old: Option<&Sheet>, new: Option<&Sheet>) -> SheetDiff {
+    // (row, col) -> (raw, is_formula); BTreeMap keeps row-major order
+    let raw_cells = |sheet: Option<&Sheet>| -> BTreeMap<(usize, usize), (String, bool)> {
+        sheet.into_iter()
+            .flat_map(|s| s.cells_iter())
+            .filter_map(|(&pos, cell)| {
+                let raw = cell.value.raw_display();
+                let is_formula = matches!(cell.value, CellValue::Formula { .. });
+                (!raw.is_empty()).then_some((pos, (raw, is_formula)))
+            })
+            .collect()
+    };
+    let before = raw_cells(old);
+    let after = raw_cells(new);
+    let positions: std::collections::BTreeSet<(usize, usize)> = before.keys().chain(after.keys()).copied().collect();
+
+    let mut diff = SheetDiff {
+        sheet: new.or(old).map(|s| s.name.clone()).unwrap_or_default(),
+        status: match (old, new) {
+            (None, _) => "added",
+            (_, None) => "removed",
+            _ => "changed",
+        },
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for (row, col) in positions {
+        let cell = format_cell_ref(row, col);
+        match (before.get(&(row, col)), after.get(&(row, col))) {
+            (None, Some((raw, _))) => diff.added.push(CellDiff { cell, kind: None, old: None, new: Some(raw.clone()) }),
+            (Some((raw, _)), None) => diff.removed.push(CellDiff { cell, kind: None, old: Some(raw.clone()), new: None }),
+            (Some((a, a_formula)), Some((b, b_formula))) if a != b => {
+                let kind = if *a_formula || *b_formula { "formula" } else { "value" };
+                diff.changed.push(CellDiff { cell, kind: Some(kind), old: Some(a.clone()), new: Some(b.clone()) });
+            }
+            (Some((_, true)), Some(_)) => {
+                let (a, b) = (old.unwrap().get_display(row, col), new.unwrap().get_display(row, col));
+                if a != b {
+                    diff.changed.push(CellDiff { cell, kind: Some("result"), old: Some(a), new: Some(b) });
+                }
+            }
+            _ => {}
+        }
+    }
+    diff
+}
+
 /// Compute (rows, cols) non-empty data bounds of a sheet.
+///
+/// Walks occupied cells only, so a lone value far down the sheet doesn't
+/// cost a scan of every row and column above it.
 pub fn get_data_bounds(sheet: &Sheet) -> (usize, usize) {
     let mut max_row = 0;
     let mut max_col = 0;
-    for row in 0..sheet.rows {
-        for col in 0..sheet.cols {
-            if !sheet.get_display(row, col).is_empty() {
-                max_row = max_row.max(row + 1);
-                max_col = max_col.max(col + 1);
-            }
+    for (row, col) in sheet.occupied_cells() {
+        if row < sheet.rows && col < sheet.cols && !sheet.get_display(row, col).is_empty() {
+            max_row = max_row.max(row + 1);
+            max_col = max_col.max(col + 1);
         }
     }
     (max_row, max_col)
 }
 
+/// Occupied fraction of the bounding box below which row-wise writers
+/// should skip empty rows instead of visiting every cell.
+const SPARSE_DENSITY: f64 = 0.25;
+
+/// Rows within `rows` × `cols` that hold at least one value, when the sheet
+/// is sparse enough for skipping empty rows to pay off. `None` means the
+/// sheet is dense: visit every row.
+pub fn sparse_rows(sheet: &Sheet, rows: usize, cols: usize) -> Option<std::collections::BTreeSet<usize>> {
+    let occupied: Vec<(usize, usize)> = sheet.occupied_cells()
+        .into_iter()
+        .filter(|&(r, c)| r < rows && c < cols)
+        .collect();
+    if occupied.len() as f64 >= (rows * cols) as f64 * SPARSE_DENSITY {
+        return None;
+    }
+    Some(occupied.into_iter().map(|(r, _)| r).collect())
+}
+
+/// Clear trailing rows and columns whose cells have no content (styled
+/// empties, whitespace). Formulas count as content even when they evaluate
+/// to blank. Interior blank cells are left alone.
+///
+/// Returns the (rows, cols) bounds of the content that was kept.
+pub fn trim_trailing_empty(sheet: &mut Sheet) -> (usize, usize) {
+    let mut max_row = 0;
+    let mut max_col = 0;
+    let mut trailing = Vec::new();
+    for &(row, col) in sheet.cells_iter().map(|(pos, _)| pos) {
+        if !sheet.get_raw(row, col).trim().is_empty() {
+            max_row = max_row.max(row + 1);
+            max_col = max_col.max(col + 1);
+        }
+    }
+    for &(row, col) in sheet.cells_iter().map(|(pos, _)| pos) {
+        // Clearing a hidden merge cell would clear its origin instead
+        if (row >= max_row || col >= max_col) && !sheet.is_merge_hidden(row, col) {
+            trailing.push((row, col));
+        }
+    }
+    for (row, col) in trailing {
+        sheet.clear_cell(row, col);
+    }
+    (max_row, max_col)
+}
+
+/// Copy the `rows` × `cols` data area into a new sheet with rows and
+/// columns swapped: the cell at (r, c) lands at (c, r).
+///
+/// Formula cells carry their computed value, since their references would
+/// not survive the swap. Formats move with their cells.
+pub fn transpose_sheet(sheet: &Sheet, rows: usize, cols: usize) -> Sheet {
+    let mut out = Sheet::new(sheet.id, sheet.cols.max(cols), sheet.rows.max(rows));
+    out.name = sheet.name.clone();
+    for (row, col) in sheet.occupied_cells() {
+        if row >= rows || col >= cols {
+            continue;
+        }
+        let raw = sheet.get_raw(row, col);
+        let value = if raw.starts_with('=') { sheet.get_display(row, col) } else { raw };
+        out.set_value(col, row, &value);
+        out.set_format(col, row, sheet.get_format(row, col));
+    }
+    out
+}
+
+/// Forward-fill blank cells in `cols` over rows `start..rows`, like pandas
+/// `ffill`: each blank takes the last non-blank value above it in its column.
+///
+/// With `reset_on_blank_row`, a row blank across all `width` columns clears
+/// the carried values, so a fill never crosses it. Formula cells carry their
+/// computed value. Returns the number of cells filled.
+pub fn fill_down(
+    sheet: &mut Sheet,
+    cols: &[usize],
+    start: usize,
+    rows: usize,
+    width: usize,
+    reset_on_blank_row: bool,
+) -> usize {
+    let mut carried: Vec<Option<String>> = vec![None; cols.len()];
+    let mut filled = 0;
+    for row in start..rows {
+        if reset_on_blank_row && (0..width).all(|c| sheet.get_raw(row, c).trim().is_empty()) {
+            carried.iter_mut().for_each(|v| *v = None);
+            continue;
+        }
+        for (slot, &col) in carried.iter_mut().zip(cols) {
+            let raw = sheet.get_raw(row, col);
+            if raw.trim().is_empty() {
+                if let Some(value) = slot {
+                    sheet.set_value(row, col, value);
+                    filled += 1;
+                }
+            } else if raw.starts_with('=') {
+                *slot = Some(sheet.get_display(row, col));
+            } else {
+                *slot = Some(raw);
+            }
+        }
+    }
+    filled
+}
+
 /// Resolve sheet by arg (index or name, case-insensitive).
 pub fn resolve_sheet_by_arg(workbook: &Workbook, arg: &str) -> Result<usize, CliError> {
     if let Ok(idx) = arg.parse::<usize>() {
@@ -696,6 +1147,15 @@ pub fn resolve_sheet_by_arg(workbook: &Workbook, arg: &str) -> Result<usize, Cli
     )))
 }
 
+/// Prefix `=` onto a formula typed without one.
+pub fn with_eq(formula: &str) -> String {
+    if formula.starts_with('=') {
+        formula.to_string()
+    } else {
+        format!("={}", formula)
+    }
+}
+
 /// Resolve header names in formula expressions to column references.
 ///
 /// Supports `[Header Name]` bracket syntax and bare identifier matching.
@@ -765,52 +1225,288 @@ pub fn resolve_header_refs(formula: &str, header_map: &std::collections::HashMap
 }
 
 /// Translate column references (A:A) to bounded cell ranges (A1:A<max_row>).
+///
+/// Cell references (`A1`, `B2:B10`) and string literals pass through
+/// unchanged; only a whole-column ref standing on its own is rewritten, so
+/// `A:A` never matches inside `BA:AB`.
 pub fn translate_column_refs(formula: &str, start_row: usize, end_row: usize) -> String {
-    use std::collections::HashSet;
-    let mut result = formula.to_string();
-    let mut seen: HashSet<String> = HashSet::new();
     let chars: Vec<char> = formula.chars().collect();
+    let mut result = String::with_capacity(formula.len());
     let mut i = 0;
+
+    // `$`? letters at `at`; returns (dollar, letters, index after)
+    let letters_at = |at: usize| -> (bool, String, usize) {
+        let mut j = at;
+        let dollar = j < chars.len() && chars[j] == '$';
+        if dollar { j += 1; }
+        let mut col = String::new();
+        while j < chars.len() && chars[j].is_ascii_alphabetic() {
+            col.push(chars[j].to_ascii_uppercase());
+            j += 1;
+        }
+        (dollar, col, j)
+    };
+
     while i < chars.len() {
-        let dollar1 = if i < chars.len() && chars[i] == '$' { i += 1; true } else { false };
-        if i < chars.len() && chars[i].is_ascii_alphabetic() {
-            let mut col1 = String::new();
-            while i < chars.len() && chars[i].is_ascii_alphabetic() {
-                col1.push(chars[i].to_ascii_uppercase());
-                i += 1;
-            }
-            if i < chars.len() && chars[i] == ':' {
+        let c = chars[i];
+        // String literals are copied verbatim ("" is an escaped quote)
+        if c == '"' {
+            result.push(c);
+            i += 1;
+            while i < chars.len() {
+                result.push(chars[i]);
                 i += 1;
-                let dollar2 = if i < chars.len() && chars[i] == '$' { i += 1; true } else { false };
-                let mut col2 = String::new();
-                while i < chars.len() && chars[i].is_ascii_alphabetic() {
-                    col2.push(chars[i].to_ascii_uppercase());
-                    i += 1;
-                }
-                if !col2.is_empty() && (i >= chars.len() || !chars[i].is_ascii_digit()) {
-                    let pattern = format!(
-                        "{}{}:{}{}",
-                        if dollar1 { "$" } else { "" }, col1,
-                        if dollar2 { "$" } else { "" }, col2
-                    );
-                    if !seen.contains(&pattern) {
-                        seen.insert(pattern.clone());
-                        let replacement = format!(
-                            "{}{}{}:{}{}{}",
-                            if dollar1 { "$" } else { "" }, col1, start_row,
-                            if dollar2 { "$" } else { "" }, col2, end_row
-                        );
-                        result = result.replace(&pattern, &replacement);
+                if chars[i - 1] == '"' {
+                    if i < chars.len() && chars[i] == '"' {
+                        result.push('"');
+                        i += 1;
+                    } else {
+                        break;
                     }
                 }
             }
-        } else {
-            i += 1;
+            continue;
+        }
+        let at_boundary = i == 0 || !(chars[i - 1].is_ascii_alphanumeric() || chars[i - 1] == '_' || chars[i - 1] == '.');
+        if at_boundary && (c == '$' || c.is_ascii_alphabetic()) {
+            let (dollar1, col1, j) = letters_at(i);
+            if !col1.is_empty() && j < chars.len() && chars[j] == ':' {
+                let (dollar2, col2, k) = letters_at(j + 1);
+                let ends = k >= chars.len() || !(chars[k].is_ascii_alphanumeric() || chars[k] == '_' || chars[k] == '(');
+                if !col2.is_empty() && ends {
+                    result.push_str(&format!(
+                        "{}{}{}:{}{}{}",
+                        if dollar1 { "$" } else { "" }, col1, start_row,
+                        if dollar2 { "$" } else { "" }, col2, end_row
+                    ));
+                    i = k;
+                    continue;
+                }
+            }
         }
+        // Copy the whole identifier (or cell ref like A1) so we never start
+        // matching in the middle of one
+        if c.is_ascii_alphanumeric() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                result.push(chars[i]);
+                i += 1;
+            }
+            continue;
+        }
+        result.push(c);
+        i += 1;
     }
     result
 }
 
+/// Map normalized header names (trim + ASCII lowercase) to whole-column refs
+/// like `B:B`, for `resolve_header_refs`. Two headers that normalize to the
+/// same key are an error rather than a silent pick.
+pub fn header_column_map(
+    headers: impl IntoIterator<Item = (usize, String)>,
+) -> Result<std::collections::HashMap<String, String>, CliError> {
+    use std::collections::HashMap;
+
+    let mut map: HashMap<String, String> = HashMap::new();
+    let mut originals: HashMap<String, (String, usize)> = HashMap::new(); // key → (original, col)
+    for (col_idx, val) in headers {
+        if val.is_empty() {
+            continue;
+        }
+        let key = val.trim().to_ascii_lowercase();
+        let col_letter = crate::util::col_to_letter(col_idx);
+        if let Some((prev_orig, prev_col)) = originals.get(&key) {
+            return Err(CliError::args(format!(
+                "ambiguous header: column {} ({:?}) and column {} ({:?}) both normalize to {:?}",
+                crate::util::col_to_letter(*prev_col), prev_orig, col_letter, val.trim(), key
+            )));
+        }
+        originals.insert(key.clone(), (val.trim().to_string(), col_idx));
+        map.insert(key, format!("{}:{}", col_letter, col_letter));
+    }
+    Ok(map)
+}
+
+/// Place a `calc` formula next to data loaded at (`into_row`, `into_col`) and
+/// evaluate it. Header names resolve to their columns and whole-column refs are
+/// bounded to the data rows (below the header when `headers` is set).
+///
+/// Returns the (row, col) of the formula cell.
+pub fn place_calc_formula(
+    sheet: &mut Sheet,
+    formula: &str,
+    into_row: usize,
+    into_col: usize,
+    headers: bool,
+) -> Result<(usize, usize), CliError> {
+    let (data_rows, data_cols) = get_data_bounds(sheet);
+
+    let formula = with_eq(formula);
+    let resolved = if headers {
+        let names = (into_col..data_cols).map(|col| (col, sheet.get_display(into_row, col)));
+        resolve_header_refs(&formula, &header_column_map(names)?)
+    } else {
+        formula
+    };
+
+    // If headers, the actual data starts one row after into_row (1-indexed for formula)
+    let data_start_row = if headers { into_row + 2 } else { into_row + 1 };
+    let formula_str = translate_column_refs(&resolved, data_start_row, data_rows);
+
+    // Put the formula in a cell outside the data area
+    sheet.set_value(data_rows, data_cols, &formula_str);
+    Ok((data_rows, data_cols))
+}
+
+// ============================================================================
+// Streaming aggregates (calc fast path)
+// ============================================================================
+
+/// Single-column aggregates `calc` can compute straight off the CSV reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamAggregate {
+    Sum,
+    Count,
+    Average,
+    Min,
+    Max,
+}
+
+/// Recognize `=AGG(X:X)` — one aggregate over one whole column — and return
+/// the aggregate with its column index. Anything else is `None`.
+fn parse_stream_aggregate(formula: &str) -> Option<(StreamAggregate, usize)> {
+    let body = formula.trim().strip_prefix('=')?.trim();
+    let open = body.find('(')?;
+    let agg = match body[..open].trim().to_ascii_uppercase().as_str() {
+        "SUM" => StreamAggregate::Sum,
+        "COUNT" => StreamAggregate::Count,
+        "AVERAGE" | "AVG" => StreamAggregate::Average,
+        "MIN" => StreamAggregate::Min,
+        "MAX" => StreamAggregate::Max,
+        _ => return None,
+    };
+    let (from, to) = body[open + 1..].strip_suffix(')')?.split_once(':')?;
+    let from = from.trim().trim_start_matches('$');
+    let to = to.trim().trim_start_matches('$');
+    if !from.eq_ignore_ascii_case(to) || !from.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let (_, col) = parse_cell_ref(&format!("{}1", from))?;
+    Some((agg, col))
+}
+
+/// What `stream_aggregate` made of its input.
+#[derive(Debug, PartialEq)]
+pub enum StreamedAggregate {
+    /// The aggregate's value.
+    Value(f64),
+    /// The general path must handle it. Carries the bytes read so far, to be
+    /// replayed ahead of the rest of the input.
+    Fallback(Vec<u8>),
+}
+
+/// Reader that keeps a copy of everything read through it, so a fallback
+/// can replay input the CSV reader has already consumed.
+struct Recorded<R> {
+    inner: R,
+    seen: Vec<u8>,
+}
+
+impl<R: Read> Read for Recorded<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.seen.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+fn read_stream_record<R: Read>(
+    reader: &mut csv::Reader<R>,
+    record: &mut csv::StringRecord,
+    line: usize,
+) -> Result<bool, CliError> {
+    reader
+        .read_record(record)
+        .map_err(|e| CliError::parse(format!("line {}: {}", line, e)))
+}
+
+/// Evaluate a single-column `SUM`/`COUNT`/`AVERAGE`/`MIN`/`MAX` while reading
+/// CSV `input`, without building a `Sheet`. Fields are classified exactly as
+/// the sheet would store them.
+///
+/// Falls back when the general path must handle it: any other formula
+/// shape, a formula cell in the column, or an `AVERAGE` with no numbers.
+pub fn stream_aggregate<R: Read>(
+    input: R,
+    delimiter: u8,
+    formula: &str,
+    into_col: usize,
+    headers: bool,
+) -> Result<StreamedAggregate, CliError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .from_reader(Recorded { inner: input, seen: Vec::new() });
+    let fallback = |reader: csv::Reader<Recorded<R>>| Ok(StreamedAggregate::Fallback(reader.into_inner().seen));
+    let mut record = csv::StringRecord::new();
+    let mut line = 0;
+
+    let formula = with_eq(formula);
+    let resolved = if headers {
+        line += 1;
+        if !read_stream_record(&mut reader, &mut record, line)? {
+            return fallback(reader);
+        }
+        let names = record
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (into_col + i, visigrid_io::csv::strip_bom(f).trim().to_string()));
+        resolve_header_refs(&formula, &header_column_map(names)?)
+    } else {
+        formula
+    };
+
+    let Some((agg, col)) = parse_stream_aggregate(&resolved) else {
+        return fallback(reader);
+    };
+    let Some(field_idx) = col.checked_sub(into_col) else {
+        return fallback(reader);
+    };
+
+    let mut count = 0usize;
+    let mut sum = 0.0;
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+    while read_stream_record(&mut reader, &mut record, line + 1)? {
+        line += 1;
+        let mut field = record.get(field_idx);
+        if line == 1 && field_idx == 0 {
+            field = field.map(visigrid_io::csv::strip_bom);
+        }
+        match field.map(CellValue::from_input) {
+            Some(CellValue::Number(n)) => {
+                count += 1;
+                sum += n;
+                min = min.min(n);
+                max = max.max(n);
+            }
+            Some(CellValue::Formula { .. }) => return fallback(reader),
+            _ => {}
+        }
+    }
+
+    Ok(StreamedAggregate::Value(match agg {
+        StreamAggregate::Sum => sum,
+        StreamAggregate::Count => count as f64,
+        StreamAggregate::Average if count == 0 => return fallback(reader),
+        StreamAggregate::Average => sum / count as f64,
+        StreamAggregate::Min if count == 0 => 0.0,
+        StreamAggregate::Min => min,
+        StreamAggregate::Max if count == 0 => 0.0,
+        StreamAggregate::Max => max,
+    }))
+}
+
 /// Format a cell reference from (row, col).
 pub fn format_cell_ref(row: usize, col: usize) -> String {
     let mut col_str = String::new();
@@ -843,12 +1539,219 @@ mod tests {
         assert_eq!(parse_target("A1"), Some((0, 0, 0, 0)));
     }
 
+    #[test]
+    fn test_normalize_check_ops() {
+        assert_eq!(normalize_check_ops("Detail!B9==Summary!B2"), "Detail!B9=Summary!B2");
+        assert_eq!(normalize_check_ops("A1!=0"), "A1<>0");
+        assert_eq!(normalize_check_ops("A1>=0"), "A1>=0");
+        assert_eq!(normalize_check_ops("A1=\"a==b\""), "A1=\"a==b\"");
+    }
+
+    fn fill_fixture(rows: &[[&str; 3]]) -> Sheet {
+        use visigrid_engine::sheet::SheetId;
+
+        let mut sheet = Sheet::new(SheetId(1), 1000, 26);
+        for (r, row) in rows.iter().enumerate() {
+            for (c, v) in row.iter().enumerate() {
+                sheet.set_value(r, c, v);
+            }
+        }
+        sheet
+    }
+
+    #[test]
+    fn test_trim_trailing_empty_keeps_blank_formulas() {
+        let mut sheet = fill_fixture(&[
+            ["5", "=IF(A1>0,\"\",1)", " "],
+            ["6", "", " "],
+            [" ", " ", ""],
+        ]);
+        assert_eq!(sheet.get_display(0, 1), "");
+
+        assert_eq!(trim_trailing_empty(&mut sheet), (2, 2));
+        assert_eq!(sheet.get_raw(0, 1), "=IF(A1>0,\"\",1)");
+        assert_eq!(sheet.get_raw(0, 2), "");
+        assert_eq!(sheet.get_raw(2, 0), "");
+    }
+
+    fn column(sheet: &Sheet, col: usize, rows: usize) -> Vec<String> {
+        (0..rows).map(|r| sheet.get_display(r, col)).collect()
+    }
+
+    #[test]
+    fn test_fill_down_forward_fills_named_columns() {
+        let mut sheet = fill_fixture(&[
+            ["Region", "Category", "Amount"],
+            ["East", "A", "1"],
+            ["", "", "2"],
+            ["", "B", ""],
+            ["West", "", "4"],
+            ["", "", "5"],
+        ]);
+        let filled = fill_down(&mut sheet, &[0, 1], 1, 6, 3, false);
+        assert_eq!(filled, 6);
+        assert_eq!(column(&sheet, 0, 6), ["Region", "East", "East", "East", "West", "West"]);
+        assert_eq!(column(&sheet, 1, 6), ["Category", "A", "A", "B", "B", "B"]);
+        // Columns not named are left alone
+        assert_eq!(sheet.get_display(3, 2), "");
+    }
+
+    #[test]
+    fn test_fill_down_reset_on_blank_row() {
+        let rows = [
+            ["Region", "Category", "Amount"],
+            ["East", "A", "1"],
+            ["", "", "2"],
+            ["", "", ""],
+            ["", "", "3"],
+        ];
+
+        let mut sheet = fill_fixture(&rows);
+        fill_down(&mut sheet, &[0], 1, 5, 3, true);
+        assert_eq!(column(&sheet, 0, 5), ["Region", "East", "East", "", ""]);
+
+        // Without the reset, the fill runs through the blank row
+        let mut sheet = fill_fixture(&rows);
+        fill_down(&mut sheet, &[0], 1, 5, 3, false);
+        assert_eq!(column(&sheet, 0, 5), ["Region", "East", "East", "East", "East"]);
+    }
+
+    #[test]
+    fn test_transpose_sheet_round_trip() {
+        use visigrid_engine::sheet::SheetId;
+
+        let mut sheet = Sheet::new(SheetId(1), 1000, 26);
+        for (r, row) in [["a", "1"], ["b", "2"], ["c", "3"]].iter().enumerate() {
+            for (c, v) in row.iter().enumerate() {
+                sheet.set_value(r, c, v);
+            }
+        }
+        sheet.set_value(2, 1, "=1+2");
+
+        let t = transpose_sheet(&sheet, 3, 2);
+        assert_eq!(get_data_bounds(&t), (2, 3));
+        assert_eq!(t.get_display(0, 2), "c");
+        assert_eq!(t.get_display(1, 0), "1");
+        // Formulas are frozen to their value
+        assert_eq!(t.get_raw(1, 2), "3");
+
+        let back = transpose_sheet(&t, 2, 3);
+        assert_eq!(get_data_bounds(&back), (3, 2));
+        for r in 0..3 {
+            for c in 0..2 {
+                assert_eq!(back.get_display(r, c), sheet.get_display(r, c), "({}, {})", r, c);
+            }
+        }
+    }
+
     #[test]
     fn test_parse_target_range() {
         assert_eq!(parse_target("A1:B2"), Some((0, 0, 1, 1)));
         assert_eq!(parse_target("A1:D10"), Some((0, 0, 9, 3)));
     }
 
+    #[test]
+    fn test_translate_column_refs() {
+        // Whole-column refs are bounded, cell refs are left alone
+        assert_eq!(translate_column_refs("=SUM(A:A)", 2, 10), "=SUM(A2:A10)");
+        assert_eq!(translate_column_refs("=A1+A2", 2, 10), "=A1+A2");
+        assert_eq!(translate_column_refs("=SUM(A1:A5)", 2, 10), "=SUM(A1:A5)");
+        assert_eq!(translate_column_refs("=SUM(B2:B10)*A:A", 1, 4), "=SUM(B2:B10)*A1:A4");
+        assert_eq!(translate_column_refs("=SUM($A:$B)", 1, 4), "=SUM($A1:$B4)");
+        // A:A must not match inside another column range or a string literal
+        assert_eq!(translate_column_refs("=SUM(A:A)+SUM(BA:AB)", 1, 4), "=SUM(A1:A4)+SUM(BA1:AB4)");
+        assert_eq!(translate_column_refs("=COUNTIF(A:A,\"A:A\")", 1, 4), "=COUNTIF(A1:A4,\"A:A\")");
+    }
+
+    #[test]
+    fn test_data_bounds_sparse_sheet() {
+        use visigrid_engine::sheet::SheetId;
+
+        // Full Excel-sized grid: a dense rows × cols scan here would take minutes
+        let mut sheet = Sheet::new(SheetId(1), 1_048_576, 16_384);
+        sheet.set_value(0, 0, "id");
+        sheet.set_value(9_999, 25, "far");
+
+        let (rows, cols) = get_data_bounds(&sheet);
+        assert_eq!((rows, cols), (10_000, 26));
+        let occupied = sparse_rows(&sheet, rows, cols).expect("sheet is sparse");
+        assert_eq!(occupied.into_iter().collect::<Vec<_>>(), vec![0, 9_999]);
+
+        // Dense data: no row skipping
+        let mut dense = Sheet::new(SheetId(2), 100, 10);
+        for r in 0..4 {
+            for c in 0..2 {
+                dense.set_value(r, c, "1");
+            }
+        }
+        assert_eq!(get_data_bounds(&dense), (4, 2));
+        assert!(sparse_rows(&dense, 4, 2).is_none());
+    }
+
+    #[test]
+    fn test_stream_aggregate_matches_sheet_path() {
+        // Mixed column: plain and formatted numbers, percents, text, blanks
+        let mut csv = String::from("id,Amount,note\n");
+        for i in 0..5_000 {
+            let amount = match i % 7 {
+                0 => format!("{}.25", i),
+                1 => format!("\"${},{:03}.50\"", i / 1000 + 1, i % 1000),
+                2 => "n/a".to_string(),
+                3 => String::new(),
+                4 => format!("({})", i),
+                5 => format!("{}%", i % 100),
+                _ => format!("-{}", i * 3),
+            };
+            csv.push_str(&format!("{},{},row {}\n", i, amount, i));
+        }
+
+        for formula in ["=SUM(Amount)", "=COUNT(B:B)", "=AVERAGE(Amount)", "=MIN($B:$B)", "=max(amount)", "=SUM(A:A)"] {
+            let streamed = match stream_aggregate(csv.as_bytes(), b',', formula, 0, true).unwrap() {
+                StreamedAggregate::Value(n) => visigrid_engine::sheet::number_display(n),
+                StreamedAggregate::Fallback(_) => panic!("{} should take the fast path", formula),
+            };
+
+            let mut sheet = crate::parse_csv(&csv, b',', 0, 0).unwrap();
+            let (row, col) = place_calc_formula(&mut sheet, formula, 0, 0, true).unwrap();
+            assert_eq!(streamed, sheet.get_display(row, col), "{}", formula);
+        }
+    }
+
+    #[test]
+    fn test_stream_aggregate_falls_back() {
+        let csv = "a,b\n1,2\n3,=A2*2\n";
+        let streamed = |input: &str, formula: &str| stream_aggregate(input.as_bytes(), b',', formula, 0, true).unwrap();
+        let replayed = |input: &str, formula: &str| match streamed(input, formula) {
+            StreamedAggregate::Fallback(seen) => seen,
+            other => panic!("{} should fall back, got {:?}", formula, other),
+        };
+        // Not a single whole-column aggregate
+        replayed(csv, "=SUM(A:A)+1");
+        replayed(csv, "=SUM(A:B)");
+        replayed(csv, "=SUMIF(A:A,1)");
+        // A formula cell needs the engine
+        replayed(csv, "=SUM(b)");
+        // AVERAGE of nothing is an engine error
+        replayed("a\nx\n", "=AVERAGE(A:A)");
+        assert_eq!(streamed(csv, "SUM(a)"), StreamedAggregate::Value(4.0));
+
+        // Whatever was read comes back for the general path to replay
+        let mut input = replayed(csv, "=SUM(b)");
+        let mut rest = Vec::new();
+        std::io::Read::read_to_end(&mut &csv.as_bytes()[input.len()..], &mut rest).unwrap();
+        input.extend(rest);
+        assert_eq!(input, csv.as_bytes());
+    }
+
+    #[test]
+    fn test_stream_aggregate_strips_bom() {
+        let csv = "\u{feff}5\n7\n";
+        assert_eq!(
+            stream_aggregate(csv.as_bytes(), b',', "=SUM(A:A)", 0, false).unwrap(),
+            StreamedAggregate::Value(12.0)
+        );
+    }
+
     #[test]
     fn test_format_cell_ref() {
         assert_eq!(format_cell_ref(0, 0), "A1");
@@ -910,6 +1813,27 @@ style("A1", { bold = true })
         std::fs::remove_file(&path2).ok();
     }
 
+    #[test]
+    fn test_protect_excluded_from_fingerprint() {
+        let temp_dir = std::env::temp_dir();
+
+        let path1 = temp_dir.join("test_noprotect.lua");
+        std::fs::write(&path1, "set(\"A1\", \"Rate\")\n").unwrap();
+        let result1 = execute_build_script(&path1, None).unwrap();
+
+        let path2 = temp_dir.join("test_withprotect.lua");
+        std::fs::write(&path2, "set(\"A1\", \"Rate\")\nprotect(\"A1:B2\")\n").unwrap();
+        let result2 = execute_build_script(&path2, None).unwrap();
+
+        assert_eq!(result1.fingerprint, result2.fingerprint);
+        let sheet = result2.workbook.sheet(0).unwrap();
+        assert!(sheet.is_protected(1, 1));
+        assert!(!sheet.is_protected(0, 2));
+
+        std::fs::remove_file(&path1).ok();
+        std::fs::remove_file(&path2).ok();
+    }
+
     #[test]
     fn test_meta_included_in_fingerprint() {
         let script_without_meta = r#"
@@ -956,4 +1880,53 @@ grid.format{ sheet=1, range="A1", bold=true }
 
         std::fs::remove_file(&path).ok();
     }
+
+    #[test]
+    fn test_build_patch_replays_to_same_workbook() {
+        let script = r#"
+set("A1", "Revenue")
+set("B2", 10000)
+set("B3", "=B2*1.05")
+set("C1", "scratch")
+clear("C1")
+grid.set{ sheet=2, cell="A1", value="second" }
+"#;
+        let path = std::env::temp_dir().join("test_build_patch.lua");
+        std::fs::write(&path, script).unwrap();
+        let result = execute_build_script(&path, None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let patch = build_patch(&result);
+        assert_eq!(patch.len(), 5);
+        assert!(matches!(&patch[0], Op::SetCellValue { sheet: 0, row: 0, col: 0, value } if value == "Revenue"));
+        assert!(matches!(&patch[1], Op::ClearCell { sheet: 0, row: 0, col: 2 }));
+        assert!(matches!(&patch[3], Op::SetCellFormula { formula, .. } if formula == "=B2*1.05"));
+        assert!(matches!(&patch[4], Op::SetCellValue { sheet: 1, row: 0, col: 0, .. }));
+
+        // Replay through the parser and per-op semantics `apply` uses
+        let text = serde_json::to_string_pretty(&patch).unwrap();
+        let ops = crate::session::parse_ops("patch.json".into(), &text).unwrap().ops;
+        let mut replayed = Workbook::new();
+        for op in &ops {
+            let sheet = match op {
+                Op::SetCellValue { sheet, .. } | Op::SetCellFormula { sheet, .. } | Op::ClearCell { sheet, .. } => *sheet,
+                other => panic!("unexpected op in patch: {:?}", other),
+            };
+            while replayed.sheet_count() <= sheet {
+                let name = format!("Sheet{}", replayed.sheet_count() + 1);
+                replayed.add_sheet_named(&name);
+            }
+            match op {
+                Op::SetCellValue { row, col, value, .. } => replayed.set_cell_value_tracked(sheet, *row, *col, value),
+                Op::SetCellFormula { row, col, formula, .. } => replayed.set_cell_value_tracked(sheet, *row, *col, formula),
+                Op::ClearCell { row, col, .. } => replayed.clear_cell_tracked(sheet, *row, *col),
+                _ => unreachable!(),
+            }
+        }
+        replayed.recompute_full_ordered();
+
+        assert_eq!(compute_sheet_fingerprint(&replayed), compute_sheet_fingerprint(&result.workbook));
+        assert_eq!(replayed.sheet(0).unwrap().get_display(2, 1), "10500");
+        assert_eq!(replayed.sheet(1).unwrap().get_display(0, 0), "second");
+    }
 }
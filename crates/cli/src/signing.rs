@@ -100,7 +100,7 @@ pub fn load_or_generate_key(
 
 /// Sign a JSON payload and produce a `SignedEnvelope`.
 ///
-/// 1. Serialize payload to compact JSON bytes (deterministic)
+/// 1. Serialize payload to canonical JSON bytes (see `canonical_payload_bytes`)
 /// 2. BLAKE3 hash the payload bytes
 /// 3. Ed25519 sign the payload bytes
 /// 4. Base64 encode signature + public key
@@ -113,9 +113,7 @@ pub fn sign_payload(
 ) -> Result<SignedEnvelope, CliError> {
     let b64 = base64::engine::general_purpose::STANDARD;
 
-    // Compact JSON for deterministic signing
-    let payload_bytes = serde_json::to_vec(payload)
-        .map_err(|e| CliError::io(format!("payload serialization error: {e}")))?;
+    let payload_bytes = canonical_payload_bytes(payload).map_err(CliError::io)?;
 
     // BLAKE3 of payload bytes
     let payload_hash = blake3::hash(&payload_bytes);
@@ -134,6 +132,38 @@ pub fn sign_payload(
     })
 }
 
+/// The bytes a payload is signed over: compact JSON with object keys sorted
+/// at every level, so they don't depend on the order a reader keeps keys in.
+pub fn canonical_payload_bytes(payload: &serde_json::Value) -> Result<Vec<u8>, String> {
+    fn sorted(value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                serde_json::Value::Object(keys.into_iter().map(|k| (k.clone(), sorted(&map[k]))).collect())
+            }
+            serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(sorted).collect()),
+            other => other.clone(),
+        }
+    }
+    serde_json::to_vec(&sorted(payload)).map_err(|e| format!("payload serialization error: {e}"))
+}
+
+/// The payload bytes an envelope's signature covers: the canonical bytes, or,
+/// for envelopes signed before payloads were canonicalized, the compact bytes
+/// in the order the keys were read.
+pub fn signed_payload_bytes(envelope: &SignedEnvelope) -> Result<Vec<u8>, String> {
+    let canonical = canonical_payload_bytes(&envelope.payload)?;
+    if blake3::hash(&canonical).to_hex().as_str() == envelope.payload_blake3 {
+        return Ok(canonical);
+    }
+    let stored = serde_json::to_vec(&envelope.payload).map_err(|e| format!("payload serialization error: {e}"))?;
+    if blake3::hash(&stored).to_hex().as_str() == envelope.payload_blake3 {
+        return Ok(stored);
+    }
+    Ok(canonical)
+}
+
 /// BLAKE3 hash of a file's contents, returned as a hex string.
 pub fn hash_file_blake3(path: &Path) -> Result<String, CliError> {
     let bytes = std::fs::read(path)
@@ -171,9 +201,7 @@ pub fn verify_envelope_signature(envelope: &SignedEnvelope) -> Result<(), String
         .map_err(|_| "signature must be 64 bytes".to_string())?;
     let signature = ed25519_dalek::Signature::from_bytes(&sig_array);
 
-    // Recompute compact JSON of payload (same as signing)
-    let payload_bytes = serde_json::to_vec(&envelope.payload)
-        .map_err(|e| format!("payload serialization error: {e}"))?;
+    let payload_bytes = signed_payload_bytes(envelope)?;
 
     verifying_key
         .verify(&payload_bytes, &signature)
@@ -295,6 +323,35 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_signature_ignores_key_order() {
+        let (sk, vk) = test_keypair();
+        let payload = serde_json::json!({"b": 1, "a": {"d": [2, {"f": 3, "e": 4}], "c": 5}});
+        let mut envelope = sign_payload("order.v1", &payload, &sk, &vk).unwrap();
+        assert_eq!(canonical_payload_bytes(&payload).unwrap(), br#"{"a":{"c":5,"d":[2,{"e":4,"f":3}]},"b":1}"#);
+
+        envelope.payload = serde_json::json!({"a": {"c": 5, "d": [2, {"e": 4, "f": 3}]}, "b": 1});
+        verify_envelope_signature(&envelope).unwrap();
+    }
+
+    #[test]
+    fn test_envelope_signed_over_stored_order_verifies() {
+        // Envelopes written before payloads were canonicalized signed the compact bytes as built
+        let (sk, vk) = test_keypair();
+        let payload = serde_json::json!({"z": 1, "a": 2});
+        let bytes = serde_json::to_vec(&payload).unwrap();
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let envelope = SignedEnvelope {
+            schema: "legacy.v1".to_string(),
+            payload,
+            payload_blake3: blake3::hash(&bytes).to_hex().to_string(),
+            signature: b64.encode(sk.sign(&bytes).to_bytes()),
+            public_key: b64.encode(vk.to_bytes()),
+            key_id: key_id(&vk),
+        };
+        verify_envelope_signature(&envelope).unwrap();
+    }
+
     #[test]
     fn test_file_proof_roundtrip() {
         // Full flow: create a file, sign it, verify the envelope matches
@@ -331,7 +388,7 @@ mod tests {
         );
 
         // Verify payload_blake3 is consistent
-        let payload_bytes = serde_json::to_vec(&envelope.payload).unwrap();
+        let payload_bytes = canonical_payload_bytes(&envelope.payload).unwrap();
         let expected_payload_hash = blake3::hash(&payload_bytes).to_hex().to_string();
         assert_eq!(envelope.payload_blake3, expected_payload_hash);
 
@@ -0,0 +1,190 @@
+//! Lazy loading of CSV rows past the initial `--max-rows` window.
+//!
+//! A background thread keeps a streaming reader positioned after the last
+//! loaded record and hands back one chunk per request, so scrolling toward the end
+//! of the window pulls in more of the file without blocking the UI.
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+
+/// Data rows fetched per backfill request.
+pub const CHUNK_ROWS: usize = 5_000;
+
+/// Start fetching once the viewport is this many rows from the loaded end.
+pub const PREFETCH_MARGIN: usize = 500;
+
+/// Whether the viewport (`visible` rows from `offset`) is close enough to
+/// the end of the `loaded` rows to fetch more of the `total` in the file.
+pub fn needs_more_rows(offset: usize, visible: usize, loaded: usize, total: usize) -> bool {
+    loaded < total && offset + visible + PREFETCH_MARGIN >= loaded
+}
+
+/// Decode one record's fields as UTF-8, falling back to Windows-1252 for
+/// the whole record like `read_file_as_utf8` does for the whole file.
+fn decode_record(rec: &csv::ByteRecord) -> Vec<String> {
+    if let Ok(fields) = rec.iter().map(std::str::from_utf8).collect::<Result<Vec<_>, _>>() {
+        return fields.into_iter().map(str::to_string).collect();
+    }
+    rec.iter()
+        .map(|f| visigrid_io::csv::decode_bytes(f, visigrid_io::csv::TextEncoding::Windows1252))
+        .collect()
+}
+
+/// Handle to the background reader for one CSV/TSV file.
+pub struct CsvBackfill {
+    requests: Sender<usize>,
+    chunks: Receiver<Result<Vec<Vec<String>>, String>>,
+    pending: bool,
+    done: bool,
+}
+
+impl CsvBackfill {
+    /// Spawn a reader over `path` that skips the first `skip` records (the
+    /// header row, if any, plus the rows already loaded).
+    pub fn spawn(path: PathBuf, delimiter: u8, skip: usize) -> Self {
+        let (req_tx, req_rx) = mpsc::channel::<usize>();
+        let (chunk_tx, chunk_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            // One streaming reader for the life of the viewer: each chunk
+            // resumes where the last one stopped, without re-reading the file
+            let file = match visigrid_io::csv::open_decompressed(&path) {
+                Ok(f) => f,
+                Err(e) => {
+                    let _ = chunk_tx.send(Err(format!("failed to open {}: {}", path.display(), e)));
+                    return;
+                }
+            };
+            let mut rdr = csv::ReaderBuilder::new()
+                .delimiter(delimiter)
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(file);
+            let mut records = rdr.byte_records().skip(skip);
+
+            // Ends when the viewer drops its handle
+            while let Ok(n) = req_rx.recv() {
+                let chunk: Result<Vec<Vec<String>>, String> = records
+                    .by_ref()
+                    .take(n)
+                    .map(|r| {
+                        r.map(|rec| decode_record(&rec))
+                            .map_err(|e| format!("CSV parse error: {}", e))
+                    })
+                    .collect();
+                if chunk_tx.send(chunk).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            requests: req_tx,
+            chunks: chunk_rx,
+            pending: false,
+            done: false,
+        }
+    }
+
+    /// Ask for the next chunk unless one is already in flight.
+    pub fn request(&mut self) {
+        if self.pending || self.done {
+            return;
+        }
+        self.pending = self.requests.send(CHUNK_ROWS).is_ok();
+    }
+
+    /// Collect a finished chunk, if any. An empty chunk or an error means
+    /// the file is exhausted and no further requests are sent.
+    pub fn try_recv(&mut self) -> Option<Result<Vec<Vec<String>>, String>> {
+        match self.chunks.try_recv() {
+            Ok(chunk) => {
+                self.pending = false;
+                if !matches!(&chunk, Ok(rows) if !rows.is_empty()) {
+                    self.done = true;
+                }
+                Some(chunk)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.pending = false;
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::time::Duration;
+
+    #[test]
+    fn trigger_near_end_of_loaded_window() {
+        // 5,000 of 2M rows loaded, 40 visible
+        assert!(!needs_more_rows(0, 40, 5_000, 2_000_000));
+        assert!(!needs_more_rows(4_000, 40, 5_000, 2_000_000));
+        // Within the prefetch margin of the loaded end
+        assert!(needs_more_rows(4_460, 40, 5_000, 2_000_000));
+        assert!(needs_more_rows(4_960, 40, 5_000, 2_000_000));
+        // Everything loaded: never
+        assert!(!needs_more_rows(4_960, 40, 5_000, 5_000));
+        // Small window: fetch immediately
+        assert!(needs_more_rows(0, 40, 100, 2_000_000));
+    }
+
+    #[test]
+    fn chunks_continue_after_loaded_rows() {
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        let mut csv = String::from("id,val\n");
+        for i in 0..(CHUNK_ROWS + 20) {
+            csv.push_str(&format!("{},{}\n", i, i * 2));
+        }
+        f.write_all(csv.as_bytes()).unwrap();
+        f.flush().unwrap();
+
+        // Header + first 10 data rows already loaded
+        let mut backfill = CsvBackfill::spawn(f.path().to_path_buf(), b',', 11);
+        let recv = |b: &mut CsvBackfill| loop {
+            if let Some(chunk) = b.try_recv() {
+                return chunk.unwrap();
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        };
+
+        backfill.request();
+        backfill.request(); // ignored while in flight
+        let first = recv(&mut backfill);
+        assert_eq!(first.len(), CHUNK_ROWS);
+        assert_eq!(first[0], vec!["10", "20"]);
+
+        backfill.request();
+        let second = recv(&mut backfill);
+        assert_eq!(second.len(), 10);
+        assert_eq!(second[9], vec![(CHUNK_ROWS + 19).to_string(), ((CHUNK_ROWS + 19) * 2).to_string()]);
+
+        backfill.request();
+        assert!(recv(&mut backfill).is_empty());
+        assert!(backfill.done);
+    }
+
+    #[test]
+    fn windows_1252_rows_decode_like_the_initial_load() {
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        f.write_all(b"name,city\nAda,Paris\nZo\xeb,Z\xfcrich\n").unwrap();
+        f.flush().unwrap();
+
+        let mut backfill = CsvBackfill::spawn(f.path().to_path_buf(), b',', 2);
+        backfill.request();
+        let rows = loop {
+            if let Some(chunk) = backfill.try_recv() {
+                break chunk.unwrap();
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(rows, vec![vec!["Zoë".to_string(), "Zürich".to_string()]]);
+    }
+}
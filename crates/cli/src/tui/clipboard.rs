@@ -0,0 +1,77 @@
+//! System clipboard access for the viewer.
+//!
+//! Copies go through the platform's clipboard command when one is on PATH
+//! (pbcopy, wl-copy, xclip, xsel, clip.exe). Without one — over SSH or in a
+//! headless box — the text is sent as an OSC 52 escape, which terminals that
+//! support it forward to the local clipboard and others silently ignore.
+
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use base64::Engine;
+
+/// How a copy reached the clipboard, for the status line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMethod {
+    /// Piped to a clipboard command (the name is shown to the user)
+    Command(&'static str),
+    /// Sent to the terminal as an OSC 52 escape
+    Osc52,
+}
+
+/// Candidate clipboard commands, in order of preference.
+const COMMANDS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+/// Put `text` on the system clipboard.
+///
+/// Errors only when no clipboard command is available and stdout is not a
+/// terminal, so the caller can report it and carry on.
+pub fn copy(text: &str) -> Result<CopyMethod, String> {
+    for &(name, args) in COMMANDS {
+        if which::which(name).is_err() {
+            continue;
+        }
+        if pipe_to(name, args, text).is_ok() {
+            return Ok(CopyMethod::Command(name));
+        }
+    }
+
+    let mut out = io::stdout();
+    if !out.is_terminal() {
+        return Err("clipboard unavailable".to_string());
+    }
+    write!(out, "{}", osc52(text))
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("clipboard unavailable: {}", e))?;
+    Ok(CopyMethod::Osc52)
+}
+
+fn pipe_to(name: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(name)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{} exited with {}", name, status)))
+    }
+}
+
+/// OSC 52 "set clipboard" escape for `text`.
+fn osc52(text: &str) -> String {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    format!("\x1b]52;c;{}\x07", b64.encode(text))
+}
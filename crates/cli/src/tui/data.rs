@@ -1,11 +1,18 @@
+use std::collections::HashMap;
 use std::path::Path;
 
+use visigrid_cli::fixed::FixedLayout;
+use visigrid_engine::cell::NumberFormat;
+use visigrid_engine::sheet::Sheet;
+
 use crate::util;
 
 pub struct PeekData {
     /// Row-major cell data (already display-ready strings)
     pub rows: Vec<Vec<String>>,
-    /// Raw cell content (formulas as "=...", values as-is). Only populated for .sheet files.
+    /// Raw cell content (formulas as "=...", values as entered). Populated for
+    /// .sheet/xlsx files and live sessions; None for csv/tsv/fixed, whose cells
+    /// display as entered.
     pub raw: Option<Vec<Vec<String>>>,
     pub num_rows: usize,
     pub num_cols: usize,
@@ -21,6 +28,15 @@ pub struct PeekData {
     pub total_rows: Option<usize>,
     /// Detected delimiter
     pub delimiter: u8,
+    /// Input quirks tolerated while loading (e.g. mixed line endings)
+    pub import_notes: Vec<String>,
+    /// Frozen panes from the source sheet: rows pinned at the top and
+    /// columns pinned at the left while scrolling (0 = none)
+    pub frozen_rows: usize,
+    pub frozen_cols: usize,
+    /// Number format labels keyed by (row, col) in `rows`, for cells with a
+    /// non-General format. Only populated for .sheet/xlsx.
+    pub number_formats: HashMap<(usize, usize), String>,
 }
 
 impl PeekData {
@@ -34,6 +50,56 @@ impl PeekData {
         self.total_rows.unwrap_or(self.num_rows)
     }
 
+    /// Append rows fetched past the initial window. Rows wider than the
+    /// current column count add generated columns; short rows are padded.
+    pub fn append_rows(&mut self, mut rows: Vec<Vec<String>>) {
+        let widest = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        if widest > self.num_cols {
+            for c in self.num_cols..widest {
+                self.col_names.push(util::col_to_letter(c));
+                self.col_widths.push(3);
+            }
+            self.num_cols = widest;
+            for row in &mut self.rows {
+                row.resize(widest, String::new());
+            }
+        }
+        for row in &mut rows {
+            row.resize(self.num_cols, String::new());
+        }
+        self.rows.append(&mut rows);
+        self.num_rows = self.rows.len();
+        if self.total_rows.is_some_and(|t| self.num_rows >= t) {
+            self.total_rows = None;
+        }
+    }
+
+    /// Rectangle between two corner cells (either order) as TSV: cells
+    /// joined by tabs, rows by newlines. Tabs and line breaks inside a cell
+    /// become spaces so the shape survives pasting into a spreadsheet.
+    pub fn selection_tsv(&self, a: (usize, usize), b: (usize, usize)) -> String {
+        let (r0, r1) = (a.0.min(b.0), a.0.max(b.0).min(self.num_rows.saturating_sub(1)));
+        let (c0, c1) = (a.1.min(b.1), a.1.max(b.1).min(self.num_cols.saturating_sub(1)));
+        if self.num_rows == 0 || self.num_cols == 0 {
+            return String::new();
+        }
+        (r0..=r1)
+            .map(|r| {
+                (c0..=c1)
+                    .map(|c| {
+                        self.rows
+                            .get(r)
+                            .and_then(|row| row.get(c))
+                            .map(|v| v.replace("\r\n", " ").replace(['\t', '\n', '\r'], " "))
+                            .unwrap_or_default()
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     /// Compute column widths by scanning up to `scan_rows` data rows (0 = all).
     /// Always includes the header names in the scan.
     pub(crate) fn compute_widths(col_names: &[String], rows: &[Vec<String>], num_cols: usize, scan_rows: usize) -> Vec<usize> {
@@ -52,6 +118,123 @@ impl PeekData {
     }
 }
 
+/// Everything the cell-detail popup shows for one cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellDetail {
+    /// Cell address using file row numbers (e.g. "B12")
+    pub address: String,
+    /// Full display value, untruncated and with line breaks intact
+    pub value: String,
+    /// Inferred type: "empty", "number", "boolean" or "text"
+    pub value_type: &'static str,
+    pub formula: Option<String>,
+    pub number_format: Option<String>,
+}
+
+impl CellDetail {
+    /// Popup body wrapped to `width` display columns: the type, formula and
+    /// format (when present), a blank line, then the value.
+    pub fn lines(&self, width: usize) -> Vec<String> {
+        let mut lines = vec![format!("Type:   {}", self.value_type)];
+        if let Some(formula) = &self.formula {
+            lines.extend(wrap_text(&format!("Formula: {}", formula), width));
+        }
+        if let Some(fmt) = &self.number_format {
+            lines.extend(wrap_text(&format!("Format: {}", fmt), width));
+        }
+        lines.push(String::new());
+        lines.extend(wrap_text(&self.value, width));
+        lines
+    }
+}
+
+impl PeekData {
+    /// Detail for the cell at data row `row`, column `col`.
+    pub fn cell_detail(&self, row: usize, col: usize) -> CellDetail {
+        let value = self.rows.get(row).and_then(|r| r.get(col)).cloned().unwrap_or_default();
+        let raw = self.raw.as_ref().and_then(|raw| raw.get(row)).and_then(|r| r.get(col));
+        let formula = raw.filter(|raw| raw.starts_with('=')).cloned();
+        // Type the stored value rather than its formatted display ("$1,200.00")
+        let typed = match raw {
+            Some(raw) if formula.is_none() => raw.as_str(),
+            _ => value.as_str(),
+        };
+        CellDetail {
+            address: format!("{}{}", util::col_to_letter(col), self.file_row(row)),
+            value_type: infer_value_type(typed),
+            formula,
+            number_format: self.number_formats.get(&(row, col)).cloned(),
+            value,
+        }
+    }
+}
+
+fn infer_value_type(s: &str) -> &'static str {
+    let t = s.trim();
+    if t.is_empty() {
+        "empty"
+    } else if t.parse::<f64>().is_ok() {
+        "number"
+    } else if t.eq_ignore_ascii_case("true") || t.eq_ignore_ascii_case("false") {
+        "boolean"
+    } else {
+        "text"
+    }
+}
+
+/// Hard-wrap `s` to `width` display columns, keeping its own line breaks.
+pub(crate) fn wrap_text(s: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for src in s.replace("\r\n", "\n").split(['\n', '\r']) {
+        let mut line = String::new();
+        let mut used = 0;
+        for ch in src.chars() {
+            let cw = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
+            if used + cw > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            line.push(ch);
+            used += cw;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Human-readable label for a cell's number format (`None` for General).
+fn number_format_label(nf: &NumberFormat) -> Option<String> {
+    let label = match nf {
+        NumberFormat::General => return None,
+        NumberFormat::Number { decimals, thousands, .. } => format!(
+            "Number, {} decimals{}",
+            decimals,
+            if *thousands { ", thousands separator" } else { "" }
+        ),
+        NumberFormat::Currency { decimals, symbol, .. } => format!(
+            "Currency ({}), {} decimals",
+            symbol.as_deref().unwrap_or("$"),
+            decimals
+        ),
+        NumberFormat::Percent { decimals } => format!("Percent, {} decimals", decimals),
+        NumberFormat::Date { style } => format!("Date ({:?})", style),
+        NumberFormat::Time => "Time".to_string(),
+        NumberFormat::DateTime => "Date and time".to_string(),
+        NumberFormat::Custom(code) => format!("Custom \"{}\"", code),
+    };
+    Some(label)
+}
+
+/// Number format labels for formatted cells inside the loaded window.
+fn collect_number_formats(sheet: &Sheet, num_rows: usize, num_cols: usize) -> HashMap<(usize, usize), String> {
+    sheet
+        .cells_iter()
+        .filter(|(&(r, c), _)| r < num_rows && c < num_cols)
+        .filter_map(|(&pos, cell)| number_format_label(&cell.format.number_format).map(|label| (pos, label)))
+        .collect()
+}
+
 /// Load a CSV or TSV file into PeekData.
 ///
 /// `delimiter` is b',' for CSV or b'\t' for TSV.
@@ -67,6 +250,8 @@ pub fn load_csv(
 ) -> Result<PeekData, String> {
     let content = visigrid_io::csv::read_file_as_utf8(path)
         .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    let mut import_notes: Vec<String> = visigrid_io::csv::line_ending_note(&content).into_iter().collect();
+    import_notes.extend(visigrid_io::csv::ragged_rows(&content, delimiter).map(|r| r.note()));
     let mut rdr = csv::ReaderBuilder::new()
         .delimiter(delimiter)
         .has_headers(false)
@@ -95,6 +280,59 @@ pub fn load_csv(
         }
     }
 
+    Ok(peek_data_from_rows(all_rows, max_cols, total_count, capped, has_headers, width_scan_rows, delimiter, import_notes))
+}
+
+/// Load fixed-width text sliced by `layout`. `--colspec` names label the
+/// columns, and every line is data; otherwise `has_headers` takes the names
+/// from the first line as CSV does.
+pub fn load_fixed(
+    path: &Path,
+    layout: &FixedLayout,
+    has_headers: bool,
+    max_rows: usize,
+    width_scan_rows: usize,
+) -> Result<PeekData, String> {
+    let content = visigrid_io::csv::read_file_as_utf8(path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    let import_notes: Vec<String> = visigrid_io::csv::line_ending_note(&content).into_iter().collect();
+
+    let header_line = has_headers && layout.names().is_none();
+    let cap = if max_rows == 0 { usize::MAX } else { max_rows };
+    let row_limit = if header_line { cap.saturating_add(1) } else { cap };
+    let mut all_rows: Vec<Vec<String>> = Vec::new();
+    let mut total_count: usize = 0;
+    for row in layout.rows(visigrid_io::csv::strip_bom(&content)) {
+        total_count += 1;
+        if all_rows.len() < row_limit {
+            all_rows.push(row);
+        }
+    }
+    let capped = total_count > all_rows.len();
+    let max_cols = layout.columns().len();
+
+    // Fixed-width text has no delimiter; 0 marks it for --shape
+    let mut data = peek_data_from_rows(all_rows, max_cols, total_count, capped, header_line, width_scan_rows, 0, import_notes);
+    if let Some(names) = layout.names() {
+        data.col_widths = PeekData::compute_widths(&names, &data.rows, max_cols, width_scan_rows);
+        data.col_names = names;
+        data.has_headers = true;
+    }
+    Ok(data)
+}
+
+/// Split off the header row (if requested), pad short rows and size columns.
+#[allow(clippy::too_many_arguments)]
+fn peek_data_from_rows(
+    mut all_rows: Vec<Vec<String>>,
+    max_cols: usize,
+    total_count: usize,
+    capped: bool,
+    has_headers: bool,
+    width_scan_rows: usize,
+    delimiter: u8,
+    import_notes: Vec<String>,
+) -> PeekData {
     // Extract header row if requested
     let col_names: Vec<String>;
     let first_data_file_row: usize;
@@ -135,7 +373,7 @@ pub fn load_csv(
         None
     };
 
-    Ok(PeekData {
+    PeekData {
         rows,
         raw: None,
         num_rows,
@@ -146,7 +384,11 @@ pub fn load_csv(
         first_data_file_row,
         total_rows,
         delimiter,
-    })
+        import_notes,
+        frozen_rows: 0,
+        frozen_cols: 0,
+        number_formats: HashMap::new(),
+    }
 }
 
 /// A sheet within a workbook, extracted as display-ready data.
@@ -214,6 +456,10 @@ pub fn load_sheet(
                     first_data_file_row: 1,
                     total_rows: None,
                     delimiter: 0,
+                    import_notes: Vec::new(),
+                    frozen_rows: 0,
+                    frozen_cols: 0,
+                    number_formats: HashMap::new(),
                 },
             });
             continue;
@@ -263,6 +509,10 @@ pub fn load_sheet(
                 first_data_file_row: 1,
                 total_rows,
                 delimiter: 0,
+                import_notes: Vec::new(),
+                frozen_rows: sheet.frozen_rows,
+                frozen_cols: sheet.frozen_cols,
+                number_formats: collect_number_formats(sheet, num_rows, num_cols),
             },
         });
     }
@@ -342,6 +592,10 @@ pub fn load_workbook_peek(
                     first_data_file_row: 1,
                     total_rows: None,
                     delimiter: 0,
+                    import_notes: Vec::new(),
+                    frozen_rows: 0,
+                    frozen_cols: 0,
+                    number_formats: HashMap::new(),
                 },
             });
             continue;
@@ -362,14 +616,18 @@ pub fn load_workbook_peek(
 
         let effective_rows = total_rows_in_sheet.min(cap);
 
-        // Extract display values into row-major grid
+        // Extract display values and raw formulas into row-major grids
         let mut rows: Vec<Vec<String>> = Vec::with_capacity(effective_rows);
+        let mut raw_rows: Vec<Vec<String>> = Vec::with_capacity(effective_rows);
         for r in 0..effective_rows {
             let mut row = Vec::with_capacity(num_cols);
+            let mut raw_row = Vec::with_capacity(num_cols);
             for c in 0..num_cols {
                 row.push(sheet.get_display(r, c));
+                raw_row.push(sheet.get_raw(r, c));
             }
             rows.push(row);
+            raw_rows.push(raw_row);
         }
 
         let num_rows = rows.len();
@@ -386,7 +644,7 @@ pub fn load_workbook_peek(
             name,
             data: PeekData {
                 rows,
-                raw: None,
+                raw: Some(raw_rows),
                 num_rows,
                 num_cols,
                 col_widths,
@@ -395,6 +653,10 @@ pub fn load_workbook_peek(
                 first_data_file_row: 1,
                 total_rows,
                 delimiter: 0,
+                import_notes: Vec::new(),
+                frozen_rows: sheet.frozen_rows,
+                frozen_cols: sheet.frozen_cols,
+                number_formats: collect_number_formats(sheet, num_rows, num_cols),
             },
         });
     }
@@ -414,6 +676,44 @@ mod tests {
         f
     }
 
+    #[test]
+    fn cell_detail_long_multiline_value() {
+        let long = format!("Line one\n{}\nend", "x".repeat(25));
+        let f = write_csv(&format!("desc,n\n\"{}\",42\n", long));
+        let data = load_csv(f.path(), b',', true, 0, 0).unwrap();
+
+        let detail = data.cell_detail(0, 0);
+        assert_eq!(detail.address, "A2");
+        assert_eq!(detail.value, long);
+        assert_eq!(detail.value_type, "text");
+        assert_eq!(detail.formula, None);
+        assert_eq!(detail.number_format, None);
+        // Line breaks are kept and the long line wraps at the popup width
+        assert_eq!(
+            detail.lines(10),
+            vec!["Type:   text", "", "Line one", "xxxxxxxxxx", "xxxxxxxxxx", "xxxxx", "end"]
+        );
+
+        assert_eq!(data.cell_detail(0, 1).value_type, "number");
+    }
+
+    #[test]
+    fn selection_tsv_rectangle() {
+        let f = write_csv("id,name,note,qty\n1,alpha,\"two\nlines\",10\n2,beta,\"a\tb\",20\n3,gamma,,30\n");
+        let data = load_csv(f.path(), b',', true, 0, 0).unwrap();
+
+        // Corners given bottom-right first; embedded line breaks and tabs flatten
+        assert_eq!(
+            data.selection_tsv((1, 2), (0, 1)),
+            "alpha\ttwo lines\nbeta\ta b"
+        );
+        // Single cell, and an empty cell inside the rectangle
+        assert_eq!(data.selection_tsv((2, 3), (2, 3)), "30");
+        assert_eq!(data.selection_tsv((2, 1), (2, 3)), "gamma\t\t30");
+        // Corners past the loaded data are clamped
+        assert_eq!(data.selection_tsv((2, 0), (9, 9)), "3\tgamma\t\t30");
+    }
+
     #[test]
     fn ragged_rows_padded() {
         let f = write_csv("a,b,c\n1,2\n3\n");
@@ -461,6 +761,25 @@ mod tests {
         assert_eq!(data.total_data_rows(), 100);
     }
 
+    #[test]
+    fn append_rows_extends_window() {
+        let f = write_csv("h1,h2\n1,2\n3,4\n5,6,7\n");
+        let mut data = load_csv(f.path(), b',', true, 1, 0).unwrap();
+        assert_eq!(data.total_rows, Some(3));
+
+        data.append_rows(vec![vec!["3".into(), "4".into()]]);
+        assert_eq!(data.num_rows, 2);
+        assert_eq!(data.total_rows, Some(3));
+
+        // A wider row grows the grid; everything loaded clears the truncation
+        data.append_rows(vec![vec!["5".into(), "6".into(), "7".into()]]);
+        assert_eq!(data.num_cols, 3);
+        assert_eq!(data.col_names, vec!["h1", "h2", "C"]);
+        assert_eq!(data.rows[0], vec!["1", "2", ""]);
+        assert_eq!(data.total_rows, None);
+        assert_eq!(data.total_data_rows(), 3);
+    }
+
     #[test]
     fn tsv_delimiter() {
         let f = write_csv("a\tb\tc\n1\t2\t3\n");
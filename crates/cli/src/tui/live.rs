@@ -0,0 +1,382 @@
+//! Live session data for the viewer (`vgrid attach --interactive`).
+//!
+//! The viewer shows a window of one sheet fetched with `inspect_range`. The
+//! window follows the cursor: moving near one of its edges re-fetches a
+//! window centred on the cursor, so the whole sheet can be browsed a window
+//! at a time.
+//!
+//! The feed subscribes to `cells:<sheet>:<range>` for the window on screen
+//! and re-fetches only when the session reports a change there. Structural
+//! edits (`sheet_structure_changed`: rows or columns inserted or deleted,
+//! sheets added or removed) and dropped events re-fetch too, since either
+//! can move what the window shows.
+
+use std::collections::HashMap;
+
+use visigrid_protocol::{CellInfo, CellRange, EventMessage, EventPayload, InspectResult, InspectResultMessage};
+
+use super::data::PeekData;
+use crate::session::SessionClient;
+use crate::sheet_ops;
+use crate::util;
+
+/// Source of range snapshots and change events: the session client, or a
+/// canned one in tests.
+pub trait RangeSource {
+    fn inspect_range(
+        &mut self,
+        sheet: usize,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> Result<InspectResultMessage, String>;
+
+    fn subscribe(&mut self, topic: String) -> Result<(), String>;
+
+    fn unsubscribe(&mut self, topic: String) -> Result<(), String>;
+
+    /// Next change event, without blocking.
+    fn poll_event(&mut self) -> Result<Option<EventMessage>, String>;
+}
+
+impl RangeSource for SessionClient {
+    fn inspect_range(
+        &mut self,
+        sheet: usize,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> Result<InspectResultMessage, String> {
+        SessionClient::inspect_range(self, sheet, start_row, start_col, end_row, end_col).map_err(|e| e.to_string())
+    }
+
+    fn subscribe(&mut self, topic: String) -> Result<(), String> {
+        SessionClient::subscribe(self, vec![topic]).map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    fn unsubscribe(&mut self, topic: String) -> Result<(), String> {
+        SessionClient::unsubscribe(self, vec![topic]).map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    fn poll_event(&mut self) -> Result<Option<EventMessage>, String> {
+        SessionClient::poll_event(self).map_err(|e| e.to_string())
+    }
+}
+
+/// A sheet window kept in step with a live session.
+pub struct LiveFeed {
+    source: Box<dyn RangeSource>,
+    label: String,
+    sheet: usize,
+    rows: usize,
+    cols: usize,
+    /// Top-left cell (row, col) of the window.
+    origin: (usize, usize),
+    /// Subscription topic covering the window last fetched.
+    topic: Option<String>,
+    revision: Option<u64>,
+}
+
+impl LiveFeed {
+    /// Follow a `rows` x `cols` window of `sheet`, starting at A1. `label`
+    /// names the session in the title bar.
+    pub fn new(source: Box<dyn RangeSource>, label: String, sheet: usize, rows: usize, cols: usize) -> Self {
+        Self {
+            source,
+            label,
+            sheet,
+            rows: rows.max(1),
+            cols: cols.max(1),
+            origin: (0, 0),
+            topic: None,
+            revision: None,
+        }
+    }
+
+    /// Title bar text: the session label and the revision on screen.
+    pub fn title(&self) -> String {
+        match self.revision {
+            Some(rev) => format!("{} sheet {} @ rev {}", self.label, self.sheet, rev),
+            None => format!("{} sheet {}", self.label, self.sheet),
+        }
+    }
+
+    /// Top-left cell (row, col) of the window the last fetch returned.
+    pub fn origin(&self) -> (usize, usize) {
+        self.origin
+    }
+
+    fn window(&self) -> CellRange {
+        let (r1, c1) = self.origin;
+        CellRange { sheet: self.sheet, r1, c1, r2: r1 + self.rows - 1, c2: c1 + self.cols - 1 }
+    }
+
+    /// Fetch the window now, subscribing to changes in it first so none
+    /// made during the fetch are missed.
+    pub fn fetch(&mut self) -> Result<PeekData, String> {
+        let window = self.window();
+        let topic = format!(
+            "cells:{}:{}:{}",
+            self.sheet,
+            sheet_ops::format_cell_ref(window.r1, window.c1),
+            sheet_ops::format_cell_ref(window.r2, window.c2),
+        );
+        if self.topic.as_deref() != Some(topic.as_str()) {
+            if let Some(old) = self.topic.take() {
+                self.source.unsubscribe(old)?;
+            }
+            self.source.subscribe(topic.clone())?;
+            self.topic = Some(topic);
+        }
+
+        let result = self.source.inspect_range(self.sheet, window.r1, window.c1, window.r2, window.c2)?;
+        self.revision = Some(result.revision);
+        let cells = match result.result {
+            InspectResult::Range { cells } => cells,
+            InspectResult::Cell(info) => vec![info],
+            InspectResult::Workbook(_) => return Err("unexpected workbook result for a range".to_string()),
+        };
+        Ok(peek_data_from_cells(&cells, self.origin, self.rows, self.cols))
+    }
+
+    /// Re-fetch if the session reported a change in the window, or move the
+    /// window when `cursor` (an absolute row, col) nears one of its edges.
+    /// `None` when the data on screen is still current.
+    pub fn poll(&mut self, cursor: (usize, usize)) -> Result<Option<PeekData>, String> {
+        let mut stale = false;
+        while let Some(event) = self.source.poll_event()? {
+            stale |= self.affects_window(&event);
+        }
+
+        let origin = (
+            follow(self.origin.0, self.rows, cursor.0),
+            follow(self.origin.1, self.cols, cursor.1),
+        );
+        if origin != self.origin {
+            self.origin = origin;
+            stale = true;
+        }
+
+        if stale {
+            self.fetch().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn affects_window(&self, event: &EventMessage) -> bool {
+        match &event.payload {
+            EventPayload::CellsChanged { ranges } => ranges.iter().any(|r| r.sheet == self.sheet && r.overlaps(&self.window())),
+            EventPayload::SheetStructureChanged { .. } | EventPayload::EventsDropped { .. } => true,
+        }
+    }
+}
+
+/// Start of a `len`-long window (now at `start`) that keeps `pos` away from
+/// its edges: unchanged while `pos` is at least a quarter window from both,
+/// otherwise recentred on `pos`.
+fn follow(start: usize, len: usize, pos: usize) -> usize {
+    let margin = len / 4;
+    let near_start = start > 0 && pos < start + margin;
+    let near_end = pos + margin >= start + len;
+    if near_start || near_end {
+        pos.saturating_sub(len / 2)
+    } else {
+        start
+    }
+}
+
+/// Build viewer data from row-major `cells` covering `rows` x `cols` from
+/// `origin`. Row numbers and column letters are the sheet's, not the window's.
+pub fn peek_data_from_cells(cells: &[CellInfo], origin: (usize, usize), rows: usize, cols: usize) -> PeekData {
+    let cell = |r: usize, c: usize| cells.get(r * cols + c);
+
+    let display: Vec<Vec<String>> = (0..rows)
+        .map(|r| (0..cols).map(|c| cell(r, c).map(|i| i.display.clone()).unwrap_or_default()).collect())
+        .collect();
+    let raw: Vec<Vec<String>> = (0..rows)
+        .map(|r| {
+            (0..cols)
+                .map(|c| cell(r, c).map(|i| i.formula.clone().unwrap_or_else(|| i.raw.clone())).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    let col_names: Vec<String> = (origin.1..origin.1 + cols).map(util::col_to_letter).collect();
+    let col_widths = PeekData::compute_widths(&col_names, &display, cols, 0);
+
+    PeekData {
+        rows: display,
+        raw: Some(raw),
+        num_rows: rows,
+        num_cols: cols,
+        col_widths,
+        col_names,
+        has_headers: false,
+        first_data_file_row: origin.0 + 1,
+        total_rows: None,
+        delimiter: b',',
+        import_notes: Vec::new(),
+        frozen_rows: 0,
+        frozen_cols: 0,
+        number_formats: HashMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    /// What a `Canned` source was asked for.
+    #[derive(Default)]
+    struct Log {
+        ranges: Vec<(usize, usize, usize, usize, usize)>,
+        topics: Vec<String>,
+    }
+
+    /// Replies with queued (revision, cells) snapshots and events, recording
+    /// each request.
+    struct Canned {
+        replies: VecDeque<(u64, Vec<CellInfo>)>,
+        events: Events,
+        log: Rc<RefCell<Log>>,
+    }
+
+    /// Events the test pushes for the feed to poll.
+    type Events = Rc<RefCell<VecDeque<EventMessage>>>;
+
+    impl RangeSource for Canned {
+        fn inspect_range(
+            &mut self,
+            sheet: usize,
+            start_row: usize,
+            start_col: usize,
+            end_row: usize,
+            end_col: usize,
+        ) -> Result<InspectResultMessage, String> {
+            self.log.borrow_mut().ranges.push((sheet, start_row, start_col, end_row, end_col));
+            let (revision, cells) = self.replies.pop_front().ok_or("session closed")?;
+            Ok(InspectResultMessage { id: "r".into(), revision, result: InspectResult::Range { cells } })
+        }
+
+        fn subscribe(&mut self, topic: String) -> Result<(), String> {
+            self.log.borrow_mut().topics.push(format!("+{}", topic));
+            Ok(())
+        }
+
+        fn unsubscribe(&mut self, topic: String) -> Result<(), String> {
+            self.log.borrow_mut().topics.push(format!("-{}", topic));
+            Ok(())
+        }
+
+        fn poll_event(&mut self) -> Result<Option<EventMessage>, String> {
+            Ok(self.events.borrow_mut().pop_front())
+        }
+    }
+
+    fn canned(replies: Vec<(u64, Vec<CellInfo>)>) -> (Canned, Events, Rc<RefCell<Log>>) {
+        let events = Rc::new(RefCell::new(VecDeque::new()));
+        let log = Rc::new(RefCell::new(Log::default()));
+        let source = Canned { replies: replies.into(), events: events.clone(), log: log.clone() };
+        (source, events, log)
+    }
+
+    fn changed(revision: u64, sheet: usize, r1: usize, c1: usize, r2: usize, c2: usize) -> EventMessage {
+        EventMessage {
+            topic: "cells".into(),
+            revision,
+            payload: EventPayload::CellsChanged { ranges: vec![CellRange { sheet, r1, c1, r2, c2 }] },
+        }
+    }
+
+    fn info(raw: &str, display: &str) -> CellInfo {
+        let formula = raw.starts_with('=').then(|| raw.to_string());
+        CellInfo { raw: raw.into(), display: display.into(), formula }
+    }
+
+    /// A 3x3 window with values in A1:B2 and the rest empty.
+    fn window(b2: &str) -> Vec<CellInfo> {
+        let mut cells = vec![info("", ""); 9];
+        cells[0] = info("Item", "Item");
+        cells[1] = info("Total", "Total");
+        cells[3] = info("Widgets", "Widgets");
+        cells[4] = info("=SUM(C5:C9)", b2);
+        cells
+    }
+
+    #[test]
+    fn feed_refetches_on_change_events_in_the_window() {
+        let (source, events, log) = canned(vec![(7, window("40")), (8, window("55"))]);
+        let mut feed = LiveFeed::new(Box::new(source), "session abc".into(), 1, 3, 3);
+
+        let data = feed.fetch().unwrap();
+        assert_eq!((data.num_rows, data.num_cols), (3, 3));
+        assert_eq!(data.col_names, ["A", "B", "C"]);
+        assert_eq!(data.rows[1], ["Widgets", "40", ""]);
+        assert_eq!(data.cell_detail(1, 1).formula.as_deref(), Some("=SUM(C5:C9)"));
+        assert_eq!(feed.title(), "session abc sheet 1 @ rev 7");
+        assert_eq!(log.borrow().topics, ["+cells:1:A1:C3"]);
+
+        // No events: nothing is requested
+        assert!(feed.poll((0, 0)).unwrap().is_none());
+        // A change on another sheet doesn't touch the window
+        events.borrow_mut().push_back(changed(8, 0, 0, 0, 0, 0));
+        assert!(feed.poll((0, 0)).unwrap().is_none());
+        assert_eq!(log.borrow().ranges.len(), 1);
+
+        // A change inside it re-fetches
+        events.borrow_mut().push_back(changed(8, 1, 1, 1, 1, 1));
+        let data = feed.poll((0, 0)).unwrap().expect("updated snapshot");
+        assert_eq!(data.rows[1][1], "55");
+        assert_eq!(feed.title(), "session abc sheet 1 @ rev 8");
+        assert_eq!(log.borrow().ranges, vec![(1, 0, 0, 2, 2); 2]);
+
+        // A dropped session surfaces as an error
+        events.borrow_mut().push_back(EventMessage {
+            topic: "cells".into(),
+            revision: 9,
+            payload: EventPayload::EventsDropped { dropped_count: 3, current_revision: 9 },
+        });
+        assert_eq!(feed.poll((0, 0)).err().as_deref(), Some("session closed"));
+    }
+
+    #[test]
+    fn window_follows_the_cursor() {
+        let (source, _events, log) = canned(vec![(1, Vec::new()), (1, Vec::new()), (1, Vec::new())]);
+        let mut feed = LiveFeed::new(Box::new(source), "s".into(), 0, 100, 8);
+        feed.fetch().unwrap();
+
+        // Well inside the window: stays put
+        assert!(feed.poll((50, 3)).unwrap().is_none());
+
+        // Near the bottom edge: recentred on the cursor, labelled with sheet rows
+        let data = feed.poll((80, 3)).unwrap().expect("moved window");
+        assert_eq!(feed.origin(), (30, 0));
+        assert_eq!(data.file_row(0), 31);
+        assert_eq!(data.col_names[0], "A");
+
+        // Back toward the top
+        feed.poll((35, 3)).unwrap().expect("moved window");
+        assert_eq!(feed.origin(), (0, 0));
+
+        let log = log.borrow();
+        assert_eq!(log.ranges, [(0, 0, 0, 99, 7), (0, 30, 0, 129, 7), (0, 0, 0, 99, 7)]);
+        assert_eq!(
+            log.topics,
+            ["+cells:0:A1:H100", "-cells:0:A1:H100", "+cells:0:A31:H130", "-cells:0:A31:H130", "+cells:0:A1:H100"]
+        );
+    }
+
+    #[test]
+    fn missing_cells_are_blank() {
+        let data = peek_data_from_cells(&[info("x", "x")], (4, 2), 2, 2);
+        assert_eq!((data.num_rows, data.num_cols), (2, 2));
+        assert_eq!(data.rows, [["x", ""], ["", ""]]);
+        assert_eq!(data.col_names, ["C", "D"]);
+        assert_eq!(data.file_row(1), 6);
+    }
+}
@@ -1,4 +1,9 @@
+pub mod backfill;
+mod clipboard;
 pub mod data;
+pub mod live;
+mod scrollbar;
+pub mod view_state;
 
 use std::io::{self, stdout, Write};
 use std::time::Duration;
@@ -18,7 +23,14 @@ use ratatui::{
 };
 
 use crate::util;
+use backfill::CsvBackfill;
 use data::{PeekData, SheetData};
+use live::LiveFeed;
+use view_state::{Persist, ViewState};
+
+/// Cells reserved for the vertical scrollbar (right edge) and the
+/// horizontal scrollbar (bottom line of the grid).
+const SCROLLBAR_SIZE: u16 = 1;
 
 struct TuiApp {
     /// All sheets (for .sheet files) or a single sheet (for CSV)
@@ -32,14 +44,24 @@ struct TuiApp {
     file_name: String,
     should_quit: bool,
     show_help: bool,
+    /// Scroll offset of the open cell-detail popup (`None` = closed)
+    detail_scroll: Option<usize>,
     /// Width of the row-number gutter, computed from max file row number
     row_num_width: usize,
     /// Whether this is a multi-sheet workbook
     multi_sheet: bool,
+    /// Background reader for rows past the loaded window (single-sheet CSV only)
+    backfill: Option<CsvBackfill>,
+    /// Live session the data is refreshed from (`attach --interactive`)
+    live: Option<LiveFeed>,
+    /// Fixed corner of the range selection; the cursor is the other corner
+    selection_anchor: Option<(usize, usize)>,
+    /// One-shot message for the status line (cleared on the next key)
+    status_message: Option<String>,
 }
 
 impl TuiApp {
-    fn new(data: PeekData, file_name: String) -> Self {
+    fn new(data: PeekData, file_name: String, backfill: Option<CsvBackfill>) -> Self {
         let row_num_width = Self::compute_row_num_width(&data);
         Self {
             sheets: vec![SheetData {
@@ -54,8 +76,13 @@ impl TuiApp {
             file_name,
             should_quit: false,
             show_help: false,
+            detail_scroll: None,
             row_num_width,
             multi_sheet: false,
+            backfill,
+            live: None,
+            selection_anchor: None,
+            status_message: None,
         }
     }
 
@@ -73,8 +100,13 @@ impl TuiApp {
             file_name,
             should_quit: false,
             show_help: false,
+            detail_scroll: None,
             row_num_width,
             multi_sheet: multi,
+            backfill: None,
+            live: None,
+            selection_anchor: None,
+            status_message: None,
         }
     }
 
@@ -92,11 +124,40 @@ impl TuiApp {
         &self.sheets[self.active_sheet].data
     }
 
+    fn view_state(&self) -> ViewState {
+        ViewState {
+            active_sheet: self.active_sheet,
+            cursor_row: self.cursor_row,
+            cursor_col: self.cursor_col,
+            scroll_row: self.scroll_row,
+            scroll_col: self.scroll_col,
+        }
+    }
+
+    /// Move to a saved position, clamped to what is loaded now.
+    /// With `sheet_pinned`, a position on a different sheet is ignored.
+    fn restore_view_state(&mut self, state: &ViewState, sheet_pinned: bool) {
+        if state.active_sheet >= self.sheets.len()
+            || (sheet_pinned && state.active_sheet != self.active_sheet)
+        {
+            return;
+        }
+        self.switch_sheet(state.active_sheet);
+        let data = self.data();
+        let max_row = data.num_rows.saturating_sub(1);
+        let max_col = data.num_cols.saturating_sub(1);
+        self.cursor_row = state.cursor_row.min(max_row);
+        self.cursor_col = state.cursor_col.min(max_col);
+        self.scroll_row = state.scroll_row.min(self.cursor_row);
+        self.scroll_col = state.scroll_col.min(self.cursor_col);
+    }
+
     fn switch_sheet(&mut self, idx: usize) {
         if idx >= self.sheets.len() || idx == self.active_sheet {
             return;
         }
         self.active_sheet = idx;
+        self.selection_anchor = None;
         self.cursor_row = 0;
         self.cursor_col = 0;
         self.scroll_row = 0;
@@ -123,15 +184,49 @@ impl TuiApp {
     }
 
     fn handle_key(&mut self, key: KeyEvent) {
+        self.status_message = None;
         if self.show_help {
             // Any key dismisses help
             self.show_help = false;
             return;
         }
 
+        if let Some(scroll) = self.detail_scroll {
+            // Detail popup: scroll keys move within it, anything else closes it
+            self.detail_scroll = match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Some(scroll.saturating_sub(1)),
+                KeyCode::Down | KeyCode::Char('j') => Some(scroll + 1),
+                KeyCode::PageUp => Some(scroll.saturating_sub(10)),
+                KeyCode::PageDown => Some(scroll + 10),
+                KeyCode::Home | KeyCode::Char('g') => Some(0),
+                KeyCode::End | KeyCode::Char('G') => Some(usize::MAX),
+                _ => None,
+            };
+            return;
+        }
+
+        // Shift+arrows extend the range selection; other keys drop it
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let extend = match key.code {
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => shift,
+            _ => false,
+        };
+        if extend {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some((self.cursor_row, self.cursor_col));
+            }
+        } else if key.code != KeyCode::Char('y') {
+            if key.code == KeyCode::Esc && self.selection_anchor.take().is_some() {
+                return;
+            }
+            self.selection_anchor = None;
+        }
+
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
             KeyCode::Char('?') => self.show_help = true,
+            KeyCode::Char('y') => self.copy_selection(),
+            KeyCode::Enter | KeyCode::Char(' ') => self.detail_scroll = Some(0),
             KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
             KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
             KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
@@ -191,6 +286,94 @@ impl TuiApp {
         }
     }
 
+    /// Corners of the selected rectangle as ((top, left), (bottom, right)).
+    /// Without a selection this is just the cursor cell.
+    fn selection(&self) -> ((usize, usize), (usize, usize)) {
+        let (ar, ac) = self.selection_anchor.unwrap_or((self.cursor_row, self.cursor_col));
+        (
+            (ar.min(self.cursor_row), ac.min(self.cursor_col)),
+            (ar.max(self.cursor_row), ac.max(self.cursor_col)),
+        )
+    }
+
+    /// Copy the selection (or the cursor cell) to the clipboard as TSV and
+    /// report the outcome on the status line.
+    fn copy_selection(&mut self) {
+        let data = self.data();
+        if data.num_rows == 0 || data.num_cols == 0 {
+            return;
+        }
+        let (top_left, bottom_right) = self.selection();
+        let tsv = data.selection_tsv(top_left, bottom_right);
+        let rows = bottom_right.0 - top_left.0 + 1;
+        let cols = bottom_right.1 - top_left.1 + 1;
+        self.status_message = Some(match clipboard::copy(&tsv) {
+            Ok(clipboard::CopyMethod::Command(name)) => {
+                format!("copied {}x{} cells ({})", rows, cols, name)
+            }
+            Ok(clipboard::CopyMethod::Osc52) => {
+                format!("copied {}x{} cells (terminal clipboard)", rows, cols)
+            }
+            Err(e) => format!("copy failed: {}", e),
+        });
+    }
+
+    /// Append any finished backfill chunk, and request the next one when
+    /// the viewport nears the end of the loaded rows. A failed chunk stops
+    /// the backfill and is reported on the status line.
+    fn poll_backfill(&mut self, visible_rows: usize) {
+        let Some(backfill) = self.backfill.as_mut() else {
+            return;
+        };
+        let data = &mut self.sheets[self.active_sheet].data;
+        match backfill.try_recv() {
+            Some(Ok(rows)) => {
+                data.append_rows(rows);
+                self.row_num_width = Self::compute_row_num_width(data);
+            }
+            Some(Err(e)) => {
+                self.status_message = Some(format!("loading more rows failed: {}", e));
+            }
+            None => {}
+        }
+        if backfill::needs_more_rows(self.scroll_row, visible_rows, data.num_rows, data.total_data_rows()) {
+            backfill.request();
+        }
+    }
+
+    /// Swap in the live session's data when it changed or the window moved
+    /// to follow the cursor, keeping the cursor on the same sheet cell.
+    fn poll_live(&mut self) {
+        let Some(live) = self.live.as_mut() else {
+            return;
+        };
+        let (row0, col0) = live.origin();
+        match live.poll((row0 + self.cursor_row, col0 + self.cursor_col)) {
+            Ok(Some(data)) => {
+                let (new_row0, new_col0) = live.origin();
+                let rebase = |(r, c): (usize, usize)| {
+                    (
+                        (row0 + r).saturating_sub(new_row0).min(data.num_rows.saturating_sub(1)),
+                        (col0 + c).saturating_sub(new_col0).min(data.num_cols.saturating_sub(1)),
+                    )
+                };
+                self.file_name = live.title();
+                (self.cursor_row, self.cursor_col) = rebase((self.cursor_row, self.cursor_col));
+                self.selection_anchor = self.selection_anchor.map(rebase);
+                self.scroll_row = (row0 + self.scroll_row).saturating_sub(new_row0);
+                self.scroll_col = (col0 + self.scroll_col).saturating_sub(new_col0);
+                self.row_num_width = Self::compute_row_num_width(&data);
+                self.sheets[self.active_sheet].data = data;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                // Keep the last snapshot on screen; stop polling a dead session
+                self.file_name = format!("{} (disconnected: {})", live.title(), e);
+                self.live = None;
+            }
+        }
+    }
+
     fn move_cursor(&mut self, drow: i32, dcol: i32) {
         let data = self.data();
         if data.num_rows == 0 || data.num_cols == 0 {
@@ -220,16 +403,27 @@ impl TuiApp {
     }
 
     fn ensure_visible(&mut self, visible_rows: usize, area_width: u16) {
-        if self.cursor_row < self.scroll_row {
-            self.scroll_row = self.cursor_row;
-        }
-        if visible_rows > 0 && self.cursor_row >= self.scroll_row + visible_rows {
-            self.scroll_row = self.cursor_row - visible_rows + 1;
+        // Frozen rows/cols are always drawn; scrolling covers the rest
+        let (frozen_rows, frozen_cols) = self.frozen();
+        let scroll_rows = visible_rows.saturating_sub(frozen_rows);
+        self.scroll_row = self.scroll_row.max(frozen_rows);
+        self.scroll_col = self.scroll_col.max(frozen_cols);
+
+        if self.cursor_row >= frozen_rows {
+            if self.cursor_row < self.scroll_row {
+                self.scroll_row = self.cursor_row;
+            }
+            if scroll_rows > 0 && self.cursor_row >= self.scroll_row + scroll_rows {
+                self.scroll_row = self.cursor_row - scroll_rows + 1;
+            }
         }
 
-        let available = (area_width as usize).saturating_sub(self.row_num_width + 1);
+        let available = self.grid_available(area_width).saturating_sub(self.frozen_width());
         let vis_cols = self.visible_columns(self.scroll_col, available);
 
+        if self.cursor_col < frozen_cols {
+            return;
+        }
         if self.cursor_col < self.scroll_col {
             self.scroll_col = self.cursor_col;
         }
@@ -252,6 +446,37 @@ impl TuiApp {
         }
     }
 
+    /// Popup area for the cell-detail view: most of the screen, centered.
+    fn detail_rect(area: Rect) -> Rect {
+        let width = (area.width * 4 / 5).max(40).min(area.width);
+        let height = (area.height * 4 / 5).max(8).min(area.height);
+        Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        )
+    }
+
+    /// Keep the detail popup's scroll offset within its wrapped content.
+    fn clamp_detail_scroll(&mut self, area: Rect) {
+        if let Some(scroll) = self.detail_scroll {
+            let popup = Self::detail_rect(area);
+            let body_rows = popup.height.saturating_sub(2) as usize;
+            let lines = self
+                .data()
+                .cell_detail(self.cursor_row, self.cursor_col)
+                .lines(popup.width.saturating_sub(2) as usize)
+                .len();
+            self.detail_scroll = Some(scroll.min(lines.saturating_sub(body_rows)));
+        }
+    }
+
+    /// Width left for cell columns after the row gutter and vertical scrollbar.
+    fn grid_available(&self, area_width: u16) -> usize {
+        (area_width as usize).saturating_sub(self.row_num_width + 1 + SCROLLBAR_SIZE as usize)
+    }
+
     fn visible_columns(&self, start_col: usize, available: usize) -> Vec<usize> {
         let data = self.data();
         let mut cols = Vec::new();
@@ -267,6 +492,29 @@ impl TuiApp {
         cols
     }
 
+    /// Frozen (rows, cols) of the active sheet, clamped to its loaded size.
+    fn frozen(&self) -> (usize, usize) {
+        let data = self.data();
+        (data.frozen_rows.min(data.num_rows), data.frozen_cols.min(data.num_cols))
+    }
+
+    /// Width taken by the frozen columns, including separators.
+    fn frozen_width(&self) -> usize {
+        let data = self.data();
+        (0..self.frozen().1)
+            .map(|c| data.col_widths.get(c).copied().unwrap_or(3) + 1)
+            .sum()
+    }
+
+    /// Columns to draw: the frozen ones, then those scrolled into view.
+    fn display_columns(&self, available: usize) -> Vec<usize> {
+        let frozen_cols = self.frozen().1;
+        let mut cols: Vec<usize> = (0..frozen_cols).collect();
+        let rest = available.saturating_sub(self.frozen_width());
+        cols.extend(self.visible_columns(self.scroll_col.max(frozen_cols), rest));
+        cols
+    }
+
     /// Column letter for index, using col_names if they look like headers, else generated.
     fn col_letter(&self, c: usize) -> String {
         util::col_to_letter(c)
@@ -300,6 +548,9 @@ impl TuiApp {
             self.draw_status(frame, chunks[2]);
         }
 
+        if self.detail_scroll.is_some() {
+            self.draw_detail(frame, area);
+        }
         if self.show_help {
             self.draw_help(frame, area);
         }
@@ -372,12 +623,14 @@ impl TuiApp {
             return;
         }
 
-        let grid_available =
-            (area.width as usize).saturating_sub(self.row_num_width + 1);
-        let vis_cols = self.visible_columns(self.scroll_col, grid_available);
+        let grid_available = self.grid_available(area.width);
+        let vis_cols = self.display_columns(grid_available);
+        let (frozen_rows, frozen_cols) = self.frozen();
+        let ((sel_top, sel_left), (sel_bottom, sel_right)) = self.selection();
+        let has_selection = self.selection_anchor.is_some();
 
         let header_height: u16 = 1;
-        let data_height = area.height.saturating_sub(header_height);
+        let data_height = area.height.saturating_sub(header_height + SCROLLBAR_SIZE);
 
         // Header line
         let gutter_blank = " ".repeat(self.row_num_width);
@@ -407,12 +660,13 @@ impl TuiApp {
 
         // Data lines
         let visible_rows = data_height as usize;
-        let end_row = (self.scroll_row + visible_rows).min(data.num_rows);
+        let start_row = self.scroll_row.max(frozen_rows);
+        let end_row = (start_row + visible_rows.saturating_sub(frozen_rows)).min(data.num_rows);
 
         let mut lines: Vec<Line> = Vec::with_capacity(visible_rows + 1);
         lines.push(Line::from(header_spans));
 
-        for r in self.scroll_row..end_row {
+        for r in (0..frozen_rows.min(visible_rows)).chain(start_row..end_row) {
             let row_data = &data.rows[r];
             let is_cursor_row = r == self.cursor_row;
             let file_row = data.file_row(r);
@@ -435,11 +689,17 @@ impl TuiApp {
                 let w = data.col_widths.get(c).copied().unwrap_or(3);
                 let display = util::pad_right(&util::truncate_display(value, w), w);
 
+                let in_selection = has_selection
+                    && (sel_top..=sel_bottom).contains(&r)
+                    && (sel_left..=sel_right).contains(&c);
+
                 let style = if is_cursor_row && c == self.cursor_col {
                     Style::default()
                         .fg(Color::Black)
                         .bg(Color::White)
                         .add_modifier(Modifier::BOLD)
+                } else if in_selection {
+                    Style::default().fg(Color::Black).bg(Color::Cyan)
                 } else if is_cursor_row {
                     Style::default().fg(Color::White)
                 } else if c == self.cursor_col {
@@ -451,11 +711,77 @@ impl TuiApp {
                 spans.push(Span::styled(format!("{} ", display), style));
             }
 
+            // Underline the last frozen row to mark the pane boundary
+            if r + 1 == frozen_rows {
+                for span in &mut spans {
+                    span.style = span.style.add_modifier(Modifier::UNDERLINED);
+                }
+            }
+
             lines.push(Line::from(spans));
         }
 
         let para = Paragraph::new(lines);
         frame.render_widget(para, area);
+
+        let vbar = Rect::new(
+            area.right().saturating_sub(SCROLLBAR_SIZE),
+            area.y + header_height,
+            SCROLLBAR_SIZE,
+            data_height,
+        );
+        self.draw_vertical_scrollbar(frame, vbar, visible_rows);
+
+        let hbar = Rect::new(
+            area.x + self.row_num_width as u16 + 1,
+            area.bottom().saturating_sub(SCROLLBAR_SIZE),
+            grid_available as u16,
+            SCROLLBAR_SIZE,
+        );
+        self.draw_horizontal_scrollbar(frame, hbar, vis_cols.len() - frozen_cols);
+    }
+
+    /// Right-edge scrollbar over all file rows. When the file was truncated,
+    /// the part of the track past the loaded rows is drawn dotted.
+    fn draw_vertical_scrollbar(&self, frame: &mut Frame, area: Rect, visible_rows: usize) {
+        let data = self.data();
+        let track = area.height as usize;
+        let total = data.total_data_rows();
+        let Some(thumb) = scrollbar::thumb(self.scroll_row, visible_rows, total, track) else {
+            return;
+        };
+        let loaded = scrollbar::loaded_extent(data.num_rows, total, track);
+
+        let lines: Vec<Line> = (0..track)
+            .map(|i| {
+                let span = if i >= thumb.start && i < thumb.start + thumb.len {
+                    Span::styled("█", Style::default().fg(Color::Cyan))
+                } else if i < loaded {
+                    Span::styled("│", Style::default().fg(Color::DarkGray))
+                } else {
+                    Span::styled("┆", Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM))
+                };
+                Line::from(span)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    /// Bottom scrollbar over columns, shown only when columns overflow.
+    fn draw_horizontal_scrollbar(&self, frame: &mut Frame, area: Rect, visible_cols: usize) {
+        let track = area.width as usize;
+        let Some(thumb) = scrollbar::thumb(self.scroll_col, visible_cols, self.data().num_cols, track) else {
+            return;
+        };
+        let spans = vec![
+            Span::styled("─".repeat(thumb.start), Style::default().fg(Color::DarkGray)),
+            Span::styled("█".repeat(thumb.len), Style::default().fg(Color::Cyan)),
+            Span::styled(
+                "─".repeat(track - thumb.start - thumb.len),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
     }
 
     fn draw_status(&self, frame: &mut Frame, area: Rect) {
@@ -477,9 +803,8 @@ impl TuiApp {
         let total = data.total_data_rows();
 
         // Column locator: show visible column range
-        let grid_available =
-            (area.width as usize).saturating_sub(self.row_num_width + 1);
-        let vis_cols = self.visible_columns(self.scroll_col, grid_available);
+        let grid_available = self.grid_available(area.width);
+        let vis_cols = self.display_columns(grid_available);
         let col_range = if vis_cols.is_empty() {
             String::new()
         } else {
@@ -507,7 +832,23 @@ impl TuiApp {
             .map(|raw| format!("  {}", raw))
             .unwrap_or_default();
 
-        let left = format!(" {}{} = {:?}{}{}", col_name, file_row, cell_value, formula_info, sheet_info);
+        let left = if let Some(msg) = &self.status_message {
+            format!(" {}", msg)
+        } else if self.selection_anchor.is_some() {
+            let ((r0, c0), (r1, c1)) = self.selection();
+            format!(
+                " {}{}:{}{} selected ({}x{})  y: copy{}",
+                util::col_to_letter(c0),
+                data.file_row(r0),
+                util::col_to_letter(c1),
+                data.file_row(r1),
+                r1 - r0 + 1,
+                c1 - c0 + 1,
+                sheet_info
+            )
+        } else {
+            format!(" {}{} = {:?}{}{}", col_name, file_row, cell_value, formula_info, sheet_info)
+        };
         let right = format!(
             "Row {}/{}  {}  ?: help ",
             file_row, total, col_range
@@ -525,6 +866,40 @@ impl TuiApp {
         frame.render_widget(para, area);
     }
 
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let popup = Self::detail_rect(area);
+        let detail = self.data().cell_detail(self.cursor_row, self.cursor_col);
+        let body_rows = popup.height.saturating_sub(2) as usize;
+        let all = detail.lines(popup.width.saturating_sub(2) as usize);
+        let scroll = self.detail_scroll.unwrap_or(0);
+
+        let lines: Vec<Line> = all
+            .iter()
+            .skip(scroll)
+            .take(body_rows)
+            .map(|s| Line::from(Span::styled(s.as_str(), Style::default().fg(Color::White))))
+            .collect();
+
+        let title = if all.len() > body_rows {
+            format!(" {}  ({}-{} of {} lines) ", detail.address, scroll + 1, (scroll + body_rows).min(all.len()), all.len())
+        } else {
+            format!(" {} ", detail.address)
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(title)
+            .title_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        frame.render_widget(Clear, popup);
+        frame.render_widget(Paragraph::new(lines).block(block), popup);
+    }
+
     fn draw_help(&self, frame: &mut Frame, area: Rect) {
         let mut help_lines = vec![
             "",
@@ -536,6 +911,12 @@ impl TuiApp {
             "  End  / G          Last row",
             "  0                 First column",
             "  $                 Last column",
+            "",
+            "  Selection",
+            "  ---------",
+            "  Shift+arrows      Extend selection",
+            "  y                 Copy as TSV",
+            "  Esc               Clear selection",
         ];
 
         if self.multi_sheet {
@@ -555,6 +936,7 @@ impl TuiApp {
             "",
             "  General",
             "  -------",
+            "  Enter / Space     Cell details",
             "  q / Esc           Quit",
             "  ?                 Toggle this help",
             "",
@@ -605,18 +987,48 @@ impl TuiApp {
 }
 
 /// Run the interactive TUI viewer for a single CSV/TSV file.
-pub fn run(data: PeekData, file_name: String) -> Result<(), String> {
-    let app = TuiApp::new(data, file_name);
-    run_app(app)
+///
+/// With `backfill`, rows past the loaded window are fetched as the user
+/// scrolls toward them. With `persist`, the last position in the file is
+/// restored on open and saved on exit.
+pub fn run(
+    data: PeekData,
+    file_name: String,
+    backfill: Option<CsvBackfill>,
+    persist: Option<Persist>,
+) -> Result<(), String> {
+    let app = TuiApp::new(data, file_name, backfill);
+    run_app(app, persist)
 }
 
 /// Run the interactive TUI viewer for a multi-sheet .sheet workbook.
-pub fn run_multi(sheets: Vec<SheetData>, file_name: String, initial_sheet: usize) -> Result<(), String> {
+pub fn run_multi(
+    sheets: Vec<SheetData>,
+    file_name: String,
+    initial_sheet: usize,
+    persist: Option<Persist>,
+) -> Result<(), String> {
     let app = TuiApp::new_multi(sheets, file_name, initial_sheet);
-    run_app(app)
+    run_app(app, persist)
 }
 
-fn run_app(mut app: TuiApp) -> Result<(), String> {
+/// Run the viewer over a live session window, refreshing as it changes.
+/// Read-only: nothing is sent to the session but `inspect` and
+/// `subscribe` requests.
+pub fn run_live(mut feed: LiveFeed) -> Result<(), String> {
+    let data = feed.fetch()?;
+    let mut app = TuiApp::new(data, feed.title(), None);
+    app.live = Some(feed);
+    run_app(app, None)
+}
+
+fn run_app(mut app: TuiApp, persist: Option<Persist>) -> Result<(), String> {
+    if let Some(p) = &persist {
+        if let Some(state) = view_state::load(&p.dir, &p.file) {
+            app.restore_view_state(&state, p.sheet_pinned);
+        }
+    }
+
     terminal::enable_raw_mode()
         .map_err(|e| format!("failed to enable raw mode: {}", e))?;
     stdout()
@@ -641,9 +1053,12 @@ fn run_app(mut app: TuiApp) -> Result<(), String> {
             .size()
             .map(|s| Rect::new(0, 0, s.width, s.height))
             .unwrap_or_default();
-        let chrome = if app.multi_sheet { 4u16 } else { 3u16 };
+        let chrome = (if app.multi_sheet { 4u16 } else { 3u16 }) + SCROLLBAR_SIZE;
         let visible_rows = term_size.height.saturating_sub(chrome) as usize;
         app.ensure_visible(visible_rows, term_size.width);
+        app.clamp_detail_scroll(term_size);
+        app.poll_backfill(visible_rows);
+        app.poll_live();
 
         terminal
             .draw(|frame| app.draw(frame))
@@ -664,6 +1079,11 @@ fn run_app(mut app: TuiApp) -> Result<(), String> {
         }
     }
 
+    // Best effort: failing to remember the position must not fail the viewer
+    if let Some(p) = &persist {
+        let _ = view_state::save(&p.dir, &p.file, &app.view_state());
+    }
+
     Ok(())
 }
 
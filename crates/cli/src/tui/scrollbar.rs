@@ -0,0 +1,83 @@
+//! Scrollbar geometry for the peek viewer.
+//!
+//! Pure math over `PeekData` offsets; rendering lives in `tui::mod`.
+
+/// Scrollbar thumb position along a track, in terminal cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thumb {
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Thumb for a viewport of `visible` items starting at `offset`, out of
+/// `total` items, drawn on a track `track` cells long.
+///
+/// Returns `None` when everything fits (no scrollbar needed) or the track
+/// is empty. The thumb is at least one cell, and sits flush with the end of
+/// the track once the viewport reaches the last item.
+pub fn thumb(offset: usize, visible: usize, total: usize, track: usize) -> Option<Thumb> {
+    if track == 0 || total <= visible {
+        return None;
+    }
+    let len = (track * visible).div_ceil(total).clamp(1, track);
+    let start = if offset + visible >= total {
+        track - len
+    } else {
+        (track * offset / total).min(track - len)
+    };
+    Some(Thumb { start, len })
+}
+
+/// Number of track cells covering the `loaded` prefix of `total` items.
+///
+/// When peek truncates a file (`--max-rows`), cells past this point stand
+/// for rows that exist in the file but were not loaded.
+pub fn loaded_extent(loaded: usize, total: usize, track: usize) -> usize {
+    if total == 0 || loaded >= total {
+        return track;
+    }
+    (track * loaded).div_ceil(total).min(track)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_thumb_when_everything_fits() {
+        assert_eq!(thumb(0, 20, 20, 10), None);
+        assert_eq!(thumb(0, 20, 5, 10), None);
+        assert_eq!(thumb(0, 5, 100, 0), None);
+    }
+
+    #[test]
+    fn thumb_size_proportional_to_viewport() {
+        // 20 of 200 rows on a 20-cell track: 2-cell thumb at the top
+        assert_eq!(thumb(0, 20, 200, 20), Some(Thumb { start: 0, len: 2 }));
+        // Halfway down
+        assert_eq!(thumb(100, 20, 200, 20), Some(Thumb { start: 10, len: 2 }));
+        // Last page is flush with the bottom
+        assert_eq!(thumb(180, 20, 200, 20), Some(Thumb { start: 18, len: 2 }));
+    }
+
+    #[test]
+    fn thumb_never_smaller_than_one_cell() {
+        // 200k rows, 40 visible, 40-cell track
+        let t = thumb(0, 40, 200_000, 40).unwrap();
+        assert_eq!(t, Thumb { start: 0, len: 1 });
+        let t = thumb(100_000, 40, 200_000, 40).unwrap();
+        assert_eq!(t, Thumb { start: 20, len: 1 });
+        let t = thumb(199_960, 40, 200_000, 40).unwrap();
+        assert_eq!(t, Thumb { start: 39, len: 1 });
+    }
+
+    #[test]
+    fn loaded_extent_marks_truncation() {
+        // 1,000 of 4,000 rows loaded on a 20-cell track
+        assert_eq!(loaded_extent(1_000, 4_000, 20), 5);
+        // Everything loaded: whole track
+        assert_eq!(loaded_extent(500, 500, 20), 20);
+        // A sliver loaded still shows one cell
+        assert_eq!(loaded_extent(1, 200_000, 20), 1);
+    }
+}
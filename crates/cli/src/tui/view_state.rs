@@ -0,0 +1,130 @@
+//! Per-file view state for the peek viewer.
+//!
+//! On exit the viewer records where the user was (sheet, cursor, scroll) in
+//! a small JSON file under the user state directory, keyed by the file's
+//! absolute path. The entry also stores the file's mtime: if the file has
+//! changed since, the saved position no longer means anything and is
+//! discarded on load.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+/// Where the user left off in one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewState {
+    pub active_sheet: usize,
+    pub cursor_row: usize,
+    pub cursor_col: usize,
+    pub scroll_row: usize,
+    pub scroll_col: usize,
+}
+
+/// Which file's state the viewer restores on open and saves on exit.
+pub struct Persist {
+    pub dir: PathBuf,
+    pub file: PathBuf,
+    /// `--sheet` was given: a saved position on a different sheet is ignored
+    pub sheet_pinned: bool,
+}
+
+impl Persist {
+    /// Persist state for `file` in the default directory, if one exists.
+    pub fn for_file(file: &Path, sheet_pinned: bool) -> Option<Self> {
+        Some(Self { dir: default_dir()?, file: file.to_path_buf(), sheet_pinned })
+    }
+}
+
+/// On-disk entry: the state plus what it was recorded against.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    path: PathBuf,
+    mtime_ns: u128,
+    state: ViewState,
+}
+
+/// Default location for saved view state (`<state dir>/visigrid/peek`).
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|d| d.join("visigrid").join("peek"))
+}
+
+/// Saved state for `file`, if any was recorded against its current mtime.
+pub fn load(dir: &Path, file: &Path) -> Option<ViewState> {
+    let (path, mtime_ns) = identity(file)?;
+    let text = fs::read_to_string(entry_path(dir, &path)).ok()?;
+    let entry: Entry = serde_json::from_str(&text).ok()?;
+    (entry.path == path && entry.mtime_ns == mtime_ns).then_some(entry.state)
+}
+
+/// Record `state` for `file`, replacing any previous entry.
+pub fn save(dir: &Path, file: &Path, state: &ViewState) -> Result<(), String> {
+    let (path, mtime_ns) = identity(file)
+        .ok_or_else(|| format!("cannot stat {}", file.display()))?;
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let target = entry_path(dir, &path);
+    let entry = Entry { path, mtime_ns, state: state.clone() };
+    let json = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    fs::write(&target, json).map_err(|e| format!("failed to write {}: {}", target.display(), e))
+}
+
+/// Absolute path and modification time (ns since epoch) of `file`.
+fn identity(file: &Path) -> Option<(PathBuf, u128)> {
+    let path = fs::canonicalize(file).ok()?;
+    let mtime = fs::metadata(&path).ok()?.modified().ok()?;
+    let mtime_ns = mtime.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    Some((path, mtime_ns))
+}
+
+/// One entry per file, named by a hash of its absolute path.
+fn entry_path(dir: &Path, path: &Path) -> PathBuf {
+    let hash = blake3::hash(path.to_string_lossy().as_bytes());
+    dir.join(format!("{}.json", &hash.to_hex()[..32]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn sample() -> ViewState {
+        ViewState { active_sheet: 1, cursor_row: 420, cursor_col: 3, scroll_row: 400, scroll_col: 2 }
+    }
+
+    #[test]
+    fn save_then_load_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data.csv");
+        fs::write(&file, "a,b\n1,2\n").unwrap();
+        let state_dir = dir.path().join("state");
+
+        assert_eq!(load(&state_dir, &file), None);
+        save(&state_dir, &file, &sample()).unwrap();
+        assert_eq!(load(&state_dir, &file), Some(sample()));
+
+        // Different spellings of the same path share one entry
+        let other = dir.path().join(".").join("data.csv");
+        assert_eq!(load(&state_dir, &other), Some(sample()));
+    }
+
+    #[test]
+    fn changed_mtime_discards_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data.csv");
+        fs::write(&file, "a,b\n1,2\n").unwrap();
+        let state_dir = dir.path().join("state");
+        save(&state_dir, &file, &sample()).unwrap();
+
+        let later = SystemTime::now() + Duration::from_secs(60);
+        fs::File::options().write(true).open(&file).unwrap().set_modified(later).unwrap();
+        assert_eq!(load(&state_dir, &file), None);
+
+        // Saving again records against the new mtime
+        save(&state_dir, &file, &ViewState::default()).unwrap();
+        assert_eq!(load(&state_dir, &file), Some(ViewState::default()));
+    }
+}
@@ -1,15 +1,69 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
 use unicode_width::UnicodeWidthStr;
 
 use crate::CliError;
 
+/// Copy `file` aside before it is replaced: to `<file>.bak` next to it, or,
+/// with `dir`, to a timestamped `<name>.<UTC time>.bak` inside `dir` so
+/// repeated runs keep every version. The timestamp has millisecond precision,
+/// and a `-<n>` suffix is added rather than overwrite an existing backup.
+/// Returns None if `file` doesn't exist yet.
+pub(crate) fn backup_file(file: &Path, dir: Option<&Path>) -> Result<Option<PathBuf>, CliError> {
+    if !file.exists() {
+        return Ok(None);
+    }
+    let name = file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let copy_failed = |backup: &Path, e: std::io::Error| {
+        CliError::io(format!("failed to back up {} to {}: {}", file.display(), backup.display(), e))
+    };
+    let Some(dir) = dir else {
+        let backup = file.with_file_name(format!("{}.bak", name));
+        std::fs::copy(file, &backup).map_err(|e| copy_failed(&backup, e))?;
+        return Ok(Some(backup));
+    };
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| CliError::io(format!("failed to create backup dir {}: {}", dir.display(), e)))?;
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    for n in 0.. {
+        let backup = match n {
+            0 => dir.join(format!("{}.{}.bak", name, stamp)),
+            n => dir.join(format!("{}.{}-{}.bak", name, stamp, n)),
+        };
+        let mut out = match std::fs::OpenOptions::new().write(true).create_new(true).open(&backup) {
+            Ok(out) => out,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(copy_failed(&backup, e)),
+        };
+        std::fs::File::open(file)
+            .and_then(|mut src| std::io::copy(&mut src, &mut out))
+            .map_err(|e| copy_failed(&backup, e))?;
+        return Ok(Some(backup));
+    }
+    unreachable!("backup name counter exhausted")
+}
+
 /// Display width of a string, accounting for CJK double-width, emoji, etc.
 pub(crate) fn display_width(s: &str) -> usize {
     UnicodeWidthStr::width(s)
 }
 
+/// Replace embedded line breaks with a visible `␤` so a cell renders on one line.
+/// Multiline cells (common in xlsx imports) would otherwise break table alignment.
+pub(crate) fn single_line(s: &str) -> Cow<'_, str> {
+    if !s.contains(['\n', '\r']) {
+        return Cow::Borrowed(s);
+    }
+    Cow::Owned(s.replace("\r\n", "\u{2424}").replace(['\n', '\r'], "\u{2424}"))
+}
+
 /// Truncate a string to fit within `width` display columns, adding ".." if truncated.
 /// Uses Unicode display width so CJK/emoji alignment stays correct.
+/// Line breaks are rendered as `␤` (see `single_line`).
 pub(crate) fn truncate_display(s: &str, width: usize) -> String {
+    let s = &*single_line(s);
     if width < 3 {
         // Just return the first char if it fits, else empty
         for ch in s.chars() {
@@ -46,6 +100,7 @@ pub(crate) fn truncate_display(s: &str, width: usize) -> String {
 /// Pad or truncate a string to exactly `width` display columns.
 /// If shorter, right-pads with spaces. If longer, truncates with "..".
 pub(crate) fn pad_right(s: &str, width: usize) -> String {
+    let s = &*single_line(s);
     let sw = UnicodeWidthStr::width(s);
     if sw > width {
         truncate_display(s, width)
@@ -139,6 +194,17 @@ mod tests {
         assert!(display_width(&t) <= 6);
     }
 
+    #[test]
+    fn newline_cell_renders_single_line() {
+        assert_eq!(single_line("plain"), "plain");
+        assert_eq!(single_line("a\r\nb\nc"), "a\u{2424}b\u{2424}c");
+
+        let row = pad_right("line one\nline two", 12);
+        assert!(!row.contains('\n'));
+        assert_eq!(display_width(&row), 12);
+        assert_eq!(truncate_display("x\ny", 10), "x\u{2424}y");
+    }
+
     #[test]
     fn pad_right_short() {
         assert_eq!(pad_right("ab", 5), "ab   ");
@@ -162,4 +228,18 @@ mod tests {
         assert_eq!(col_to_letter(27), "AB");
         assert_eq!(col_to_letter(701), "ZZ");
     }
+
+    #[test]
+    fn backups_in_the_same_instant_keep_every_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("model.sheet");
+        let backups = dir.path().join("backups");
+        let mut written = Vec::new();
+        for version in ["v1", "v2", "v3"] {
+            std::fs::write(&file, version).unwrap();
+            written.push(backup_file(&file, Some(&backups)).unwrap().unwrap());
+        }
+        let contents: Vec<String> = written.iter().map(|p| std::fs::read_to_string(p).unwrap()).collect();
+        assert_eq!(contents, ["v1", "v2", "v3"]);
+    }
 }
@@ -34,7 +34,12 @@ Examples:
   vgrid verify totals truth.csv warehouse.csv --tolerance 0.01
   vgrid verify totals truth.csv warehouse.csv --output verify.json
   vgrid verify totals truth.csv warehouse.csv --diff diffs.csv
-  vgrid verify totals truth.csv warehouse.csv --sign --proof proof.json")]
+  vgrid verify totals truth.csv warehouse.csv --sign --proof proof.json
+  vgrid verify totals truth.csv warehouse.csv --certificate cert.json
+
+A certificate (vgrid.recon_certificate.v1) records the inputs' BLAKE3 hashes,
+settings, summary counts, outcome, timestamp, and tool version, signed with
+Ed25519. Check it later with `vgrid verify proof cert.json`.")]
     Totals {
         /// Truth daily totals CSV (the external source of truth)
         truth: PathBuf,
@@ -70,6 +75,10 @@ Examples:
         #[arg(long)]
         proof: Option<PathBuf>,
 
+        /// Write a signed reconciliation certificate JSON to file
+        #[arg(long)]
+        certificate: Option<PathBuf>,
+
         /// Path to signing key (default: ~/.config/vgrid/proof_key.json, or VGRID_SIGNING_KEY_PATH env)
         #[arg(long, env = "VGRID_SIGNING_KEY_PATH")]
         signing_key: Option<PathBuf>,
@@ -79,7 +88,8 @@ Examples:
     #[command(after_help = "\
 Reads a proof.json and checks:
   1. Ed25519 signature matches the embedded public key
-  2. Schema is supported (vgrid.totals_proof.v1 or vgrid.file_proof.v1)
+  2. Schema is supported (vgrid.totals_proof.v1, vgrid.file_proof.v1, or
+     vgrid.recon_certificate.v1)
   3. Referenced files still have matching BLAKE3 hashes (with --check-files or --file)
 
 For file proofs (vgrid.file_proof.v1), use --file to verify the signed file's
@@ -128,6 +138,7 @@ pub fn cmd_verify(cmd: VerifyCommands) -> Result<(), CliError> {
             quiet,
             sign,
             proof,
+            certificate,
             signing_key,
         } => cmd_verify_totals(
             truth,
@@ -139,6 +150,7 @@ pub fn cmd_verify(cmd: VerifyCommands) -> Result<(), CliError> {
             quiet,
             sign || proof.is_some(),
             proof,
+            certificate,
             signing_key,
         ),
         VerifyCommands::Proof {
@@ -222,6 +234,7 @@ fn cmd_verify_totals(
     quiet: bool,
     sign: bool,
     proof_path: Option<PathBuf>,
+    certificate_path: Option<PathBuf>,
     signing_key_path: Option<PathBuf>,
 ) -> Result<(), CliError> {
     // Convert tolerance from currency units to micro-units
@@ -454,6 +467,28 @@ fn cmd_verify_totals(
         }
     }
 
+    // Sign and write certificate
+    if let Some(cert_out) = &certificate_path {
+        let certificate = build_certificate(&result, &truth_bytes, &warehouse_bytes);
+        let payload = serde_json::to_value(&certificate)
+            .map_err(|e| CliError::io(format!("certificate JSON error: {e}")))?;
+        let (signing_key, verifying_key) = signing::load_or_generate_key(&signing_key_path)?;
+        let signed = signing::sign_payload(
+            visigrid_recon::certificate::CERTIFICATE_SCHEMA,
+            &payload,
+            &signing_key,
+            &verifying_key,
+        )?;
+        let cert_json = serde_json::to_string_pretty(&signed)
+            .map_err(|e| CliError::io(format!("certificate JSON error: {e}")))?;
+        std::fs::write(cert_out, cert_json)
+            .map_err(|e| CliError::io(format!("cannot write {}: {e}", cert_out.display())))?;
+
+        if !quiet {
+            eprintln!("  certificate:        {} (key {})", cert_out.display(), signed.key_id);
+        }
+    }
+
     if has_material_mismatch {
         Err(CliError {
             code: exit_codes::EXIT_ERROR,
@@ -610,6 +645,44 @@ fn sign_proof(
     })
 }
 
+// ── Reconciliation certificate ──────────────────────────────────────
+
+fn build_certificate(
+    result: &VerifyResult,
+    truth_bytes: &[u8],
+    warehouse_bytes: &[u8],
+) -> visigrid_recon::ReconCertificate {
+    use std::collections::BTreeMap;
+    use visigrid_recon::certificate::{CertificateInput, CertificateSigner};
+
+    let s = &result.summary;
+    visigrid_recon::ReconCertificate {
+        kind: "verify_totals".to_string(),
+        signer: CertificateSigner {
+            name: "vgrid".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+        issued_at: chrono::Utc::now().to_rfc3339(),
+        inputs: vec![
+            CertificateInput::from_bytes("truth", &result.truth_file, truth_bytes),
+            CertificateInput::from_bytes("warehouse", &result.warehouse_file, warehouse_bytes),
+        ],
+        config: BTreeMap::from([
+            ("tolerance_micro".to_string(), serde_json::json!(result.tolerance_micro)),
+            ("fail_on_count_mismatch".to_string(), serde_json::json!(result.fail_on_count_mismatch)),
+        ]),
+        summary: BTreeMap::from([
+            ("truth_rows".to_string(), s.truth_rows as u64),
+            ("warehouse_rows".to_string(), s.warehouse_rows as u64),
+            ("matched".to_string(), s.matched as u64),
+            ("mismatched".to_string(), s.mismatched as u64),
+            ("only_in_truth".to_string(), s.only_in_truth as u64),
+            ("only_in_warehouse".to_string(), s.only_in_warehouse as u64),
+        ]),
+        status: result.status.to_string(),
+    }
+}
+
 // ── Proof verification ──────────────────────────────────────────────
 
 #[derive(Debug, Serialize)]
@@ -670,7 +743,8 @@ fn cmd_verify_proof_envelope(
     let mut all_pass = true;
 
     // Check 1: Schema
-    let schema_ok = envelope.schema == "vgrid.file_proof.v1";
+    let schema_ok = envelope.schema == "vgrid.file_proof.v1"
+        || envelope.schema == visigrid_recon::certificate::CERTIFICATE_SCHEMA;
     checks.push(ProofCheck {
         name: "schema".to_string(),
         status: if schema_ok { "pass" } else { "fail" },
@@ -700,8 +774,7 @@ fn cmd_verify_proof_envelope(
     }
 
     // Check 3: Payload BLAKE3 consistency
-    let payload_bytes = serde_json::to_vec(&envelope.payload)
-        .map_err(|e| CliError::io(format!("payload serialization error: {e}")))?;
+    let payload_bytes = signing::signed_payload_bytes(&envelope).map_err(CliError::io)?;
     let actual_payload_hash = blake3::hash(&payload_bytes).to_hex().to_string();
     let payload_hash_ok = actual_payload_hash == envelope.payload_blake3;
     checks.push(ProofCheck {
@@ -0,0 +1,166 @@
+//! Rendering for `vgrid view` snapshots.
+//!
+//! Snapshots are rendered to a string first, then printed or written to the
+//! `--out` file, so a captured file is byte-identical to the terminal output.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use visigrid_protocol::CellInfo;
+
+use crate::util::{col_to_letter, truncate_display};
+use crate::CliError;
+
+/// Map flat row-major `cells` onto (row, col) for the given range.
+fn cell_grid(
+    cells: &[CellInfo],
+    start_row: usize,
+    start_col: usize,
+    end_col: usize,
+) -> HashMap<(usize, usize), &str> {
+    let num_cols = end_col - start_col + 1;
+    cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| ((start_row + i / num_cols, start_col + i % num_cols), cell.display.as_str()))
+        .collect()
+}
+
+/// Render cells as an aligned table with column letters and row numbers.
+/// Cells are assumed to be in row-major order.
+pub(crate) fn render_table(
+    cells: &[CellInfo],
+    start_row: usize,
+    start_col: usize,
+    end_row: usize,
+    end_col: usize,
+    col_width: usize,
+) -> String {
+    let grid = cell_grid(cells, start_row, start_col, end_col);
+    let mut out = String::new();
+
+    // Column headers
+    let _ = write!(out, "{:>5} ", ""); // Row number column
+    for col in start_col..=end_col {
+        let _ = write!(out, "{:^width$}", col_to_letter(col), width = col_width);
+    }
+    out.push('\n');
+
+    // Separator
+    let _ = write!(out, "{:─>5}─", "");
+    for _ in start_col..=end_col {
+        let _ = write!(out, "{:─>width$}", "", width = col_width);
+    }
+    out.push('\n');
+
+    // Rows
+    for row in start_row..=end_row {
+        let _ = write!(out, "{:>5} ", row + 1); // 1-indexed row numbers
+        for col in start_col..=end_col {
+            let value = grid.get(&(row, col)).copied().unwrap_or("");
+            let _ = write!(out, "{:>width$}", truncate_display(value, col_width), width = col_width);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Session header and rule printed above the table.
+pub(crate) fn table_header(short_id: &str, sheet: usize, range: &str, revision: u64) -> String {
+    format!(
+        "Session: {}  Sheet: {}  Range: {}  Revision: {}\n{}\n",
+        short_id, sheet, range, revision, "─".repeat(60)
+    )
+}
+
+/// Send a rendered snapshot to `out`, or to stdout when `None`.
+pub(crate) fn emit(snapshot: &str, out: Option<&Path>) -> Result<(), CliError> {
+    match out {
+        Some(path) => std::fs::write(path, snapshot)
+            .map_err(|e| CliError::io(format!("failed to write {}: {}", path.display(), e))),
+        None => {
+            print!("{}", snapshot);
+            Ok(())
+        }
+    }
+}
+
+/// Render cells as CSV: one record per row of the range, display values only.
+pub(crate) fn render_csv(
+    cells: &[CellInfo],
+    start_row: usize,
+    start_col: usize,
+    end_row: usize,
+    end_col: usize,
+) -> Result<String, CliError> {
+    let grid = cell_grid(cells, start_row, start_col, end_col);
+    let mut writer = csv::WriterBuilder::new().from_writer(Vec::new());
+    for row in start_row..=end_row {
+        let record: Vec<&str> = (start_col..=end_col)
+            .map(|col| grid.get(&(row, col)).copied().unwrap_or(""))
+            .collect();
+        writer.write_record(&record).map_err(|e| CliError::io(e.to_string()))?;
+    }
+    let bytes = writer.into_inner().map_err(|e| CliError::io(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| CliError::io(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(values: &[&str]) -> Vec<CellInfo> {
+        values
+            .iter()
+            .map(|v| CellInfo { raw: v.to_string(), display: v.to_string(), formula: None })
+            .collect()
+    }
+
+    #[test]
+    fn table_layout() {
+        // B2:C3
+        let out = render_table(&cells(&["a", "1", "b", "a long value"]), 1, 1, 2, 2, 6);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "        B     C   ");
+        assert_eq!(lines[1], "──────────────────");
+        assert_eq!(lines[2], "    2      a     1");
+        assert_eq!(lines[3], "    3      ba lo..");
+        assert!(out.ends_with('\n'));
+    }
+
+    #[test]
+    fn csv_keeps_full_values() {
+        let out = render_csv(&cells(&["name", "note", "x", "a, \"quoted\" value"]), 0, 0, 1, 1).unwrap();
+        assert_eq!(out, "name,note\nx,\"a, \"\"quoted\"\" value\"\n");
+    }
+
+    #[test]
+    fn csv_pads_short_cell_list() {
+        // Fewer cells than the range: the rest render empty
+        let out = render_csv(&cells(&["1"]), 0, 0, 1, 1).unwrap();
+        assert_eq!(out, "1,\n,\n");
+    }
+
+    #[test]
+    fn out_file_matches_stdout_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.txt");
+        let snapshot = table_header("abcd1234", 0, "A1:C1", 7)
+            + &render_table(&cells(&["10", "20", "30"]), 0, 0, 0, 2, 8);
+        emit(&snapshot, Some(&path)).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, snapshot);
+        assert_eq!(
+            written.lines().collect::<Vec<_>>(),
+            [
+                "Session: abcd1234  Sheet: 0  Range: A1:C1  Revision: 7",
+                &"─".repeat(60),
+                "         A       B       C    ",
+                "──────────────────────────────",
+                "    1       10      20      30",
+            ]
+        );
+    }
+}
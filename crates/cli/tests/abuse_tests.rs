@@ -153,7 +153,7 @@ fn test_09_formula_injection() {
 }
 
 // ── Test #10: Missing column detection ──
-// (Note: vgrid fill doesn't validate column names — it just fills cells.
+// (Note: without an embedded schema, vgrid fill doesn't validate column names — it just fills cells.
 // Missing amount_minor means the summary formulas compute wrong values.
 // The right check is that the SUMIF formulas produce 0.)
 
@@ -179,6 +179,87 @@ fn test_10_missing_column_still_fills() {
     eprintln!("TEST #10 PASS: fill succeeds, formulas return 0 for missing column data");
 }
 
+// ── Test #10b: Template schema catches drift ──
+
+/// Copy the shared template and embed a fill schema for Sheet1.
+fn schema_template(dir: &Path) -> std::path::PathBuf {
+    use visigrid_io::native::{FillColumn, FillColumnType, FillSchema};
+
+    ensure_template();
+    let path = dir.join("schema-template.sheet");
+    std::fs::copy(template_path(), &path).unwrap();
+
+    let column = |name: &str, kind| FillColumn { name: name.to_string(), kind };
+    let mut schema = FillSchema::new();
+    schema.insert("Sheet1".to_string(), vec![
+        column("effective_date", FillColumnType::Text),
+        column("posted_date", FillColumnType::Text),
+        column("amount_minor", FillColumnType::Integer),
+        column("currency", FillColumnType::Text),
+        column("type", FillColumnType::Text),
+        column("source", FillColumnType::Text),
+        column("source_id", FillColumnType::Text),
+        column("group_id", FillColumnType::Text),
+        column("description", FillColumnType::Text),
+        column("amount", FillColumnType::Decimal),
+    ]);
+    native::save_fill_schema(&path, &schema).unwrap();
+    path
+}
+
+#[test]
+fn test_10b_schema_matching_csv_fills() {
+    let dir = tempfile::tempdir().unwrap();
+    let template = schema_template(dir.path());
+    let out = dir.path().join("out.sheet");
+    let output = vgrid()
+        .args(["fill", template.to_str().unwrap(),
+            "--csv", csv_path("balanced.csv").to_str().unwrap(),
+            "--target", "Sheet1!A2", "--headers", "--clear",
+            "--out", out.to_str().unwrap()])
+        .output().unwrap();
+    assert!(output.status.success(), "fill should succeed: {}", String::from_utf8_lossy(&output.stderr));
+
+    // Output keeps the schema so it can be refilled
+    let schema = native::load_fill_schema(&out).unwrap();
+    assert_eq!(schema["Sheet1"].len(), 10);
+}
+
+#[test]
+fn test_10c_schema_type_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let template = schema_template(dir.path());
+    let out = dir.path().join("out.sheet");
+    let output = vgrid()
+        .args(["fill", template.to_str().unwrap(),
+            "--csv", csv_path("schema-type-mismatch.csv").to_str().unwrap(),
+            "--target", "Sheet1!A2", "--headers", "--clear",
+            "--out", out.to_str().unwrap()])
+        .output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(4), "should be a parse error: {}", stderr);
+    assert!(stderr.contains("type mismatch at row 2 col 10 (amount): expected decimal, found \"n/a\""),
+        "error should locate the bad value: {}", stderr);
+    assert!(!out.exists(), "nothing should be written on mismatch");
+}
+
+#[test]
+fn test_10d_schema_header_drift() {
+    let dir = tempfile::tempdir().unwrap();
+    let template = schema_template(dir.path());
+    let out = dir.path().join("out.sheet");
+    let output = vgrid()
+        .args(["fill", template.to_str().unwrap(),
+            "--csv", csv_path("missing-column.csv").to_str().unwrap(),
+            "--target", "Sheet1!A2", "--headers", "--clear",
+            "--out", out.to_str().unwrap()])
+        .output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("header mismatch at row 1 col 3: expected \"amount_minor\", found \"currency\""),
+        "error: {}", stderr);
+}
+
 // ── Test #11a: Stale rows WITHOUT --clear ──
 
 #[test]
@@ -352,6 +433,115 @@ fn test_balanced_fill_variance_zero() {
         "charges should be 150000 (100000 + 50000), got {:?}", charges);
 }
 
+// ── Test: JSON input fills by header match ──
+
+#[test]
+fn test_json_fill_matches_headers() {
+    ensure_template();
+    let dir = tempfile::tempdir().unwrap();
+    let json = dir.path().join("rows.json");
+    // Keys out of template order, some omitted; amounts as strings or integers
+    std::fs::write(&json, r#"[
+        {"type": "charge", "amount_minor": 100000, "effective_date": "2026-01-15", "amount": "1000.00"},
+        {"type": "charge", "amount_minor": 50000, "description": "Gadget sale", "amount": "500.00"},
+        {"type": "fee", "amount_minor": -4350, "source_id": null, "amount": "-43.50"},
+        {"TYPE": "payout", "amount_minor": -145650, "amount": "-1456.50"}
+    ]"#).unwrap();
+    let out = dir.path().join("out.sheet");
+
+    let result = vgrid()
+        .args(["fill", template_path().to_str().unwrap(),
+            "--json-input", json.to_str().unwrap(),
+            "--target", "Sheet1!A2", "--headers", "--clear",
+            "--out", out.to_str().unwrap(), "--json"])
+        .output().unwrap();
+    assert!(result.status.success(), "fill: {}", String::from_utf8_lossy(&result.stderr));
+    let report: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(report["rows"], 4);
+
+    assert!(matches!(load_and_read_cell(&out, "summary", 6, 1), Value::Number(n) if n == 0.0));
+    assert!(matches!(load_and_read_cell(&out, "summary", 1, 1), Value::Number(n) if n == 150000.0));
+    assert!(matches!(load_and_read_cell(&out, "Sheet1", 2, 8), Value::Text(ref s) if s == "Gadget sale"));
+    assert!(matches!(load_and_read_cell(&out, "Sheet1", 1, 9), Value::Number(n) if n == 1000.0));
+}
+
+// ── Test: --backup keeps the pre-fill file ──
+
+#[test]
+fn test_fill_in_place_with_backup() {
+    ensure_template();
+    let dir = tempfile::tempdir().unwrap();
+    let model = dir.path().join("model.sheet");
+    std::fs::copy(template_path(), &model).unwrap();
+    let original = std::fs::read(&model).unwrap();
+    let json = dir.path().join("rows.json");
+    std::fs::write(&json, r#"[{"type": "charge", "amount_minor": 100000, "amount": "1000.00"}]"#).unwrap();
+
+    let fill = |extra: &[&str]| {
+        let result = vgrid()
+            .args(["fill", model.to_str().unwrap(),
+                "--json-input", json.to_str().unwrap(),
+                "--target", "Sheet1!A2", "--headers",
+                "--out", model.to_str().unwrap(), "--json"])
+            .args(extra)
+            .output().unwrap();
+        assert!(result.status.success(), "fill: {}", String::from_utf8_lossy(&result.stderr));
+        serde_json::from_slice::<serde_json::Value>(&result.stdout).unwrap()
+    };
+
+    // --backup: <file>.bak holds the pre-fill bytes; the original is replaced
+    let report = fill(&["--backup"]);
+    let backup = dir.path().join("model.sheet.bak");
+    assert_eq!(report["backup"], backup.display().to_string());
+    assert_eq!(std::fs::read(&backup).unwrap(), original, "backup must match the pre-fill file");
+    assert!(matches!(load_and_read_cell(&model, "summary", 1, 1), Value::Number(n) if n == 100000.0));
+
+    // --backup-dir: timestamped copy of the file as it was before this run
+    let filled = std::fs::read(&model).unwrap();
+    let backups = dir.path().join("backups");
+    let report = fill(&["--backup-dir", backups.to_str().unwrap()]);
+    let entries: Vec<_> = std::fs::read_dir(&backups).unwrap().map(|e| e.unwrap().path()).collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(report["backup"], entries[0].display().to_string());
+    let name = entries[0].file_name().unwrap().to_string_lossy().into_owned();
+    assert!(name.starts_with("model.sheet.") && name.ends_with(".bak"), "name: {}", name);
+    assert_eq!(std::fs::read(&entries[0]).unwrap(), filled);
+
+    // Without --backup nothing extra is written
+    let report = fill(&[]);
+    assert!(report["backup"].is_null());
+}
+
+#[test]
+fn test_json_fill_rejects_non_scalar_value() {
+    ensure_template();
+    let dir = tempfile::tempdir().unwrap();
+    let json = dir.path().join("rows.json");
+    std::fs::write(&json, r#"[{"type": "charge", "amount_minor": {"value": 100}}]"#).unwrap();
+    let out = dir.path().join("out.sheet");
+
+    let result = vgrid()
+        .args(["fill", template_path().to_str().unwrap(),
+            "--json-input", json.to_str().unwrap(),
+            "--target", "Sheet1!A2", "--headers",
+            "--out", out.to_str().unwrap()])
+        .output().unwrap();
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert_eq!(result.status.code(), Some(4), "should be a parse error: {}", stderr);
+    assert!(stderr.contains("non-scalar value for key \"amount_minor\" in record 1"), "error: {}", stderr);
+    assert!(!out.exists(), "nothing should be written");
+
+    // Strict numeric rules still apply to JSON strings
+    std::fs::write(&json, r#"[{"amount": "$10.00"}]"#).unwrap();
+    let result = vgrid()
+        .args(["fill", template_path().to_str().unwrap(),
+            "--json-input", json.to_str().unwrap(),
+            "--target", "Sheet1!A2", "--headers",
+            "--out", out.to_str().unwrap()])
+        .output().unwrap();
+    assert!(String::from_utf8_lossy(&result.stderr).contains("currency symbol"));
+}
+
 // ── Test: --clear preserves formulas on data sheets ──
 // Regression test: clear_sheet used to wipe ALL cells including formulas.
 // The stripe-qbo-recon template has XLOOKUP/IF formulas in columns J-L
@@ -429,3 +619,38 @@ fn test_clear_preserves_formulas() {
 
     eprintln!("TEST PASS: --clear preserves formulas, summary computes correctly");
 }
+
+// ── Test: fill's incremental recalc saves the same values as a full recompute ──
+// fill recomputes only the filled cells' dependents. The cached formula
+// values it saves must match what a full recompute of the same file gives.
+
+#[test]
+fn test_fill_saves_values_matching_full_recompute() {
+    let out = tempfile::NamedTempFile::new().unwrap();
+    let result = vgrid()
+        .args(["fill", recon_template_path().to_str().unwrap(),
+            "--csv", recon_csv_path("stripe.csv").to_str().unwrap(),
+            "--target", "stripe!A2", "--headers", "--clear",
+            "--out", out.path().to_str().unwrap()])
+        .output().unwrap();
+    assert!(result.status.success(), "fill failed: {}", String::from_utf8_lossy(&result.stderr));
+
+    // load_workbook recomputes everything; save that as the reference
+    let full = native::load_workbook(out.path()).expect("load filled workbook");
+    let reference = tempfile::NamedTempFile::new().unwrap();
+    native::save_workbook(&full, reference.path()).expect("save reference");
+
+    let cached = |path: &Path, sheet_idx: usize| {
+        let sheet = full.sheet(sheet_idx).unwrap();
+        native::inspect_range_lightweight(path, sheet_idx, 0, 0, sheet.rows - 1, sheet.cols - 1)
+            .expect("inspect saved values")
+            .into_iter()
+            .map(|c| ((c.row, c.col), c.value))
+            .collect::<Vec<_>>()
+    };
+    for sheet_idx in 0..full.sheet_count() {
+        let filled = cached(out.path(), sheet_idx);
+        assert!(!filled.is_empty());
+        assert_eq!(filled, cached(reference.path(), sheet_idx), "sheet {} values differ", sheet_idx);
+    }
+}
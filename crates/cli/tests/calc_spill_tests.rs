@@ -0,0 +1,56 @@
+// Integration tests for `vgrid calc --spill` array output.
+// Run with: cargo test -p visigrid-cli --test calc_spill_tests -- --nocapture
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn calc(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vgrid"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .arg("calc")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn vgrid calc");
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().expect("vgrid calc")
+}
+
+#[test]
+fn spill_tsv_is_tab_separated() {
+    // TRANSPOSE reads cells as numbers, so spill the text from an array constant
+    let output = calc(&["={\"Smith, J\",3;2,4}", "-f", "csv", "--spill", "tsv"], "1\n");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    // Commas need no quoting in TSV
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Smith, J\t3\n2\t4\n");
+}
+
+#[test]
+fn spill_xlsx_to_stdout_is_rejected() {
+    let output = calc(&["=TRANSPOSE(A1:B2)", "-f", "csv", "--spill", "xlsx"], "1,2\n3,4\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--spill-out"));
+}
+
+#[test]
+fn spill_xlsx_writes_values_to_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("product.xlsx");
+    let output = calc(
+        &["=MMULT(A1:B2,A1:B2)", "-f", "csv", "--spill", "xlsx", "--spill-out", out.to_str().unwrap()],
+        "1,2\n3,4\n",
+    );
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.stdout.is_empty());
+
+    let (workbook, _) = visigrid_io::xlsx::import(&out).unwrap();
+    let sheet = &workbook.sheets()[0];
+    let values: Vec<String> = [(0, 0), (0, 1), (1, 0), (1, 1)]
+        .iter()
+        .map(|&(r, c)| sheet.get_display(r, c))
+        .collect();
+    assert_eq!(values, ["7", "10", "15", "22"]);
+}
@@ -0,0 +1,115 @@
+// Integration tests for `vgrid fetch sql`.
+// Run with: cargo test -p visigrid-cli --test fetch_sql
+
+use std::process::Command;
+
+fn vgrid() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_vgrid"));
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd.env_remove("DATABASE_URL");
+    cmd
+}
+
+const INVOICES: &str = "\
+WITH invoices(id, customer, zip, total) AS (
+    VALUES (1, 'Acme', '02134', 120.5), (2, 'Globex', '10001', 80), (3, 'Initech', NULL, 42)
+)
+SELECT id, customer, zip, total FROM invoices WHERE total >= :min ORDER BY id";
+
+#[test]
+fn in_memory_query_writes_csv() {
+    let output = vgrid()
+        .args(["fetch", "sql", "--dsn", "sqlite::memory:", "--query", INVOICES, "--param", "min=50"])
+        .output()
+        .expect("failed to run vgrid");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "id,customer,zip,total\n1,Acme,02134,120.5\n2,Globex,10001,80\n",
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Fetched 2 rows"));
+}
+
+#[test]
+fn json_keeps_text_columns_as_strings() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("invoices.json");
+    let output = vgrid()
+        .args(["fetch", "sql", "--dsn", "sqlite::memory:", "--query", INVOICES, "--param", "min=0", "--quiet"])
+        .arg("-o")
+        .arg(&out)
+        .output()
+        .expect("failed to run vgrid");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.stderr.is_empty(), "--quiet: {}", String::from_utf8_lossy(&output.stderr));
+
+    // Format comes from the --out extension
+    let rows: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    let rows = rows.as_array().expect("array of row objects");
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["zip"], "02134", "text stays a string with its leading zero");
+    assert_eq!(rows[0]["total"], 120.5);
+    assert_eq!(rows[1]["id"], 2);
+}
+
+#[test]
+fn missing_param_exits_2() {
+    let output = vgrid()
+        .args(["fetch", "sql", "--dsn", "sqlite::memory:", "--query", INVOICES])
+        .output()
+        .expect("failed to run vgrid");
+
+    assert_eq!(output.status.code(), Some(2), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(":min has no value"));
+}
+
+#[test]
+fn write_statement_is_rejected() {
+    let output = vgrid()
+        .args(["fetch", "sql", "--dsn", "sqlite::memory:", "--query", "CREATE TABLE t (x)"])
+        .output()
+        .expect("failed to run vgrid");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("read-only"));
+}
+
+#[test]
+fn bad_query_exits_52() {
+    let output = vgrid()
+        .args(["fetch", "sql", "--dsn", "sqlite::memory:", "--query", "SELECT * FROM missing_table"])
+        .output()
+        .expect("failed to run vgrid");
+
+    assert_eq!(output.status.code(), Some(52), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no such table"));
+}
+
+#[test]
+fn unreachable_server_exits_54() {
+    // Nothing listens on port 1, so the connection is refused right away
+    for dsn in ["postgres://vgrid@127.0.0.1:1/ledger", "mysql://vgrid@127.0.0.1:1/ledger"] {
+        let output = vgrid()
+            .args(["fetch", "sql", "--dsn", dsn, "--query", INVOICES, "--param", "min=0"])
+            .output()
+            .expect("failed to run vgrid");
+
+        assert_eq!(output.status.code(), Some(54), "{}: {}", dsn, String::from_utf8_lossy(&output.stderr));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("cannot connect to"));
+    }
+}
+
+#[test]
+fn server_params_are_checked_before_connecting() {
+    for dsn in ["postgres://vgrid@127.0.0.1:1/ledger", "mariadb://vgrid@127.0.0.1:1/ledger"] {
+        let output = vgrid()
+            .args(["fetch", "sql", "--dsn", dsn, "--query", INVOICES])
+            .output()
+            .expect("failed to run vgrid");
+
+        assert_eq!(output.status.code(), Some(2), "{}: {}", dsn, String::from_utf8_lossy(&output.stderr));
+        assert!(String::from_utf8_lossy(&output.stderr).contains(":min has no value"));
+    }
+}
@@ -0,0 +1,178 @@
+// Integration tests for `vgrid hub publish` against a mock hub server.
+// Run with: cargo test -p visigrid-cli --test hub_publish_tests -- --nocapture
+
+use std::path::Path;
+use std::process::Command;
+
+use httpmock::prelude::*;
+
+fn vgrid(config_home: &Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_vgrid"));
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    // Saved auth is read from $XDG_CONFIG_HOME/visigrid/auth.json
+    cmd.env("XDG_CONFIG_HOME", config_home);
+    cmd
+}
+
+#[test]
+fn hub_publish_if_changed_skips_identical_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+
+    let auth_dir = dir.path().join("visigrid");
+    std::fs::create_dir_all(&auth_dir).unwrap();
+    std::fs::write(
+        auth_dir.join("auth.json"),
+        serde_json::json!({ "token": "tok", "api_base": server.base_url() }).to_string(),
+    ).unwrap();
+
+    let csv = dir.path().join("payments.csv");
+    std::fs::write(&csv, "id,amount\n1,100\n2,250\n").unwrap();
+    let sheet = dir.path().join("payments.sheet");
+    let status = vgrid(dir.path())
+        .args(["sheet", "import", csv.to_str().unwrap(), sheet.to_str().unwrap()])
+        .status()
+        .expect("sheet import");
+    assert!(status.success());
+    let content_hash = visigrid_hub_client::hash_file(&sheet).unwrap();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/desktop/repos/acme/payments/datasets");
+        then.status(200).json_body(serde_json::json!([{ "id": 7, "name": "payments" }]));
+    });
+    let mut dataset_status = server.mock(|when, then| {
+        when.method(GET).path("/api/desktop/datasets/7/status");
+        then.status(200).json_body(serde_json::json!({
+            "current_revision_id": 12,
+            "content_hash": "blake3:0000",
+        }));
+    });
+    let create = server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/desktop/datasets/7/revisions")
+            .json_body_includes(serde_json::json!({ "content_hash": content_hash }).to_string());
+        then.status(200).json_body(serde_json::json!({
+            "revision_id": 13,
+            "upload_url": server.url("/upload/13"),
+        }));
+    });
+    let upload = server.mock(|when, then| {
+        when.method(PUT).path("/upload/13");
+        then.status(200);
+    });
+    server.mock(|when, then| {
+        when.method(POST).path("/api/desktop/revisions/13/complete");
+        then.status(200).json_body(serde_json::json!({}));
+    });
+
+    let publish = |dir: &Path| {
+        vgrid(dir)
+            .args(["hub", "publish", sheet.to_str().unwrap(), "--repo", "acme/payments",
+                   "--if-changed", "--no-wait", "--json"])
+            .output()
+            .expect("hub publish")
+    };
+
+    // First publish: latest revision holds other content, so upload
+    let output = publish(dir.path());
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let out: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(out["revision_id"], "13");
+    create.assert_calls(1);
+    upload.assert_calls(1);
+
+    // Second publish: the server now records this file's hash
+    dataset_status.delete();
+    dataset_status = server.mock(|when, then| {
+        when.method(GET).path("/api/desktop/datasets/7/status");
+        then.status(200).json_body(serde_json::json!({
+            "current_revision_id": 13,
+            "content_hash": content_hash,
+        }));
+    });
+
+    let output = publish(dir.path());
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let out: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(out["changed"], false);
+    assert_eq!(out["revision_id"], "13");
+    dataset_status.assert_calls(1);
+    create.assert_calls(1);
+    upload.assert_calls(1);
+}
+
+#[test]
+fn publish_failed_check_reports_details() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+
+    let auth_dir = dir.path().join("visigrid");
+    std::fs::create_dir_all(&auth_dir).unwrap();
+    std::fs::write(
+        auth_dir.join("auth.json"),
+        serde_json::json!({ "token": "tok", "api_base": server.base_url() }).to_string(),
+    ).unwrap();
+
+    let csv = dir.path().join("payments.csv");
+    std::fs::write(&csv, "id,amount,new_fee\n1,100,2\n").unwrap();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/desktop/repos/acme/payments/datasets");
+        then.status(200).json_body(serde_json::json!([{ "id": 7, "name": "payments.csv" }]));
+    });
+    server.mock(|when, then| {
+        when.method(POST).path("/api/desktop/datasets/7/revisions");
+        then.status(200).json_body(serde_json::json!({
+            "revision_id": 21,
+            "upload_url": server.url("/upload/21"),
+        }));
+    });
+    server.mock(|when, then| {
+        when.method(PUT).path("/upload/21");
+        then.status(200);
+    });
+    server.mock(|when, then| {
+        when.method(POST).path("/api/desktop/revisions/21/complete");
+        then.status(200).json_body(serde_json::json!({}));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/api/desktop/repos/acme/payments/runs");
+        then.status(200).json_body(serde_json::json!({
+            "runs": [{
+                "id": 21,
+                "version": 4,
+                "status": "verified",
+                "check_status": "fail",
+                "diff_summary": { "row_count_change": -49, "col_count_change": 1 },
+                "check_report": {
+                    "row_count_change": -49,
+                    "col_count_change": 1,
+                    "columns_added": ["new_fee"],
+                    "columns_removed": [],
+                    "columns_type_changed": [{ "column": "amount", "from": "number", "to": "text" }],
+                    "violations": ["row_count", "columns_added"],
+                },
+            }],
+        }));
+    });
+
+    let output = vgrid(dir.path())
+        .args(["publish", csv.to_str().unwrap(), "--repo", "acme/payments", "--output", "json"])
+        .output()
+        .expect("publish");
+    assert_eq!(output.status.code(), Some(41), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let out: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(out["check_status"], "fail");
+    let report = &out["check_report"];
+    assert_eq!(report["status"], "fail");
+    assert_eq!(report["row_count_change"], -49);
+    assert_eq!(report["col_count_change"], 1);
+    assert_eq!(report["columns_added"], serde_json::json!(["new_fee"]));
+    assert_eq!(report["columns_removed"], serde_json::json!([]));
+    assert_eq!(
+        report["columns_type_changed"],
+        serde_json::json!([{ "column": "amount", "from": "number", "to": "text" }]),
+    );
+    assert_eq!(report["violations"], serde_json::json!(["row_count", "columns_added"]));
+}
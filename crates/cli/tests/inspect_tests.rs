@@ -238,6 +238,57 @@ fn inspect_csv_single_cell() {
     std::fs::remove_file(&csv).ok();
 }
 
+#[test]
+fn inspect_csv_typed_boolean_and_error_cells() {
+    let csv = csv_fixture("typed_cells", "Flag,Note,Check\nTRUE,true,#N/A\n");
+    for (cell_ref, value, value_type) in [("A2", "TRUE", "boolean"), ("B2", "true", "text"), ("C2", "#N/A", "error")] {
+        let output = vgrid()
+            .args(["sheet", "inspect", csv.to_str().unwrap(), cell_ref, "--json"])
+            .output()
+            .expect("csv typed cell");
+
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        let cell: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+        assert_eq!(cell["value"], value, "{cell_ref}");
+        assert_eq!(cell["value_type"], value_type, "{cell_ref}");
+    }
+    std::fs::remove_file(&csv).ok();
+}
+
+#[test]
+fn convert_text_columns_survive_sheet_round_trip() {
+    let csv = csv_fixture("text_columns_sheet", "zip,flag,qty\n00123,TRUE,7\n");
+    let sheet = std::env::temp_dir().join("vgrid_test_text_columns.sheet");
+    std::fs::remove_file(&sheet).ok();
+    let output = vgrid()
+        .args(["convert", csv.to_str().unwrap(), "-t", "sheet", "--headers", "--text-columns", "zip,flag",
+            "-o", sheet.to_str().unwrap()])
+        .output()
+        .expect("convert --text-columns to .sheet");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    for (cell_ref, value, value_type) in [("A2", "00123", "text"), ("B2", "TRUE", "text"), ("C2", "7", "number")] {
+        let output = vgrid()
+            .args(["sheet", "inspect", sheet.to_str().unwrap(), cell_ref, "--json"])
+            .output()
+            .expect("inspect converted .sheet");
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        let cell: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+        assert_eq!(cell["value"], value, "{cell_ref}");
+        assert_eq!(cell["value_type"], value_type, "{cell_ref}");
+    }
+
+    let output = vgrid()
+        .args(["convert", sheet.to_str().unwrap(), "-t", "csv"])
+        .output()
+        .expect("convert .sheet back to csv");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "zip,flag,qty\n00123,TRUE,7\n");
+
+    std::fs::remove_file(&csv).ok();
+    std::fs::remove_file(&sheet).ok();
+}
+
 #[test]
 fn inspect_csv_sheets_single_entry() {
     let csv = csv_fixture("sheets_single", "a,b\n1,2\n");
@@ -255,6 +306,96 @@ fn inspect_csv_sheets_single_entry() {
     std::fs::remove_file(&csv).ok();
 }
 
+#[test]
+fn inspect_csv_strips_bom_from_first_header() {
+    let csv = csv_fixture("bom", "\u{feff}Name,Age\nAlice,30\n");
+    let output = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "A1", "--json"])
+        .output()
+        .expect("csv with BOM");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let cell: serde_json::Value = serde_json::from_str(
+        &String::from_utf8_lossy(&output.stdout)
+    ).expect("valid JSON");
+    assert_eq!(cell["value"], "Name", "BOM must not prefix the first header");
+    std::fs::remove_file(&csv).ok();
+}
+
+#[test]
+fn mixed_line_endings_warn_on_stderr() {
+    let csv = csv_fixture("mixed_endings", "Name,Age\r\nAlice,30\nBob,25\r\n");
+
+    for args in [
+        vec!["sheet", "inspect", csv.to_str().unwrap(), "A2", "--value"],
+        vec!["peek", csv.to_str().unwrap(), "--plain"],
+    ] {
+        let output = vgrid().args(&args).output().expect("mixed line endings");
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("warning:") && stderr.contains("mixed line endings (2 CRLF, 1 LF)"),
+            "{:?} should warn: {}", args, stderr);
+
+        // --quiet suppresses the warning
+        let mut quiet = args.clone();
+        quiet.push("--quiet");
+        let output = vgrid().args(&quiet).output().expect("mixed line endings --quiet");
+        assert!(output.status.success());
+        assert!(output.stderr.is_empty(), "--quiet should suppress: {}",
+            String::from_utf8_lossy(&output.stderr));
+    }
+    std::fs::remove_file(&csv).ok();
+}
+
+#[test]
+fn ragged_rows_warn_and_report() {
+    // Line 3 is one field short, line 5 one field long
+    let csv = csv_fixture("ragged", "id,name,qty\n1,a,5\n2,b\n3,c,7\n4,d,8,extra\n");
+    let expected = "2 ragged rows (expected 3 fields; first at line 3)";
+
+    for args in [
+        vec!["sheet", "inspect", csv.to_str().unwrap(), "A2", "--value"],
+        vec!["peek", csv.to_str().unwrap(), "--plain"],
+        vec!["convert", csv.to_str().unwrap(), "-t", "csv"],
+    ] {
+        let output = vgrid().args(&args).output().expect("ragged rows");
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("warning:") && stderr.contains(expected),
+            "{:?} should warn: {}", args, stderr);
+
+        let mut quiet = args.clone();
+        quiet.push("--quiet");
+        let output = vgrid().args(&quiet).output().expect("ragged rows --quiet");
+        assert!(output.status.success());
+        assert!(output.stderr.is_empty(), "--quiet should suppress: {}",
+            String::from_utf8_lossy(&output.stderr));
+    }
+
+    // Workbook mode carries the structured diagnostic
+    let output = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "--json", "--quiet"])
+        .output()
+        .expect("ragged rows --json");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(json["ragged_rows"]["expected_fields"], 3);
+    assert_eq!(json["ragged_rows"]["count"], 2);
+    assert_eq!(json["ragged_rows"]["first_line"], 3);
+    std::fs::remove_file(&csv).ok();
+
+    // Rectangular files report nothing
+    let csv = csv_fixture("not_ragged", "id,name\n1,a\n2,b\n");
+    let output = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "--json"])
+        .output()
+        .expect("rectangular --json");
+    assert!(output.stderr.is_empty(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert!(json.get("ragged_rows").is_none());
+    std::fs::remove_file(&csv).ok();
+}
+
 #[test]
 fn inspect_csv_headers_column_name() {
     let csv = csv_fixture("headers", "Name,Age,City\nAlice,30,Paris\nBob,25,London\n");
@@ -333,6 +474,55 @@ fn inspect_tsv_non_empty() {
     std::fs::remove_file(&tsv).ok();
 }
 
+// ===========================================================================
+// Fixed-width Tests
+// ===========================================================================
+
+// Right-aligned amounts, trailing-space padding, and a trailing blank line
+const FIXED_LEDGER: &str = "Alice       12.5NY  \nBob            7CA  \nCarol       1234TX  \n\n";
+
+fn fixed_fixture(name: &str, content: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("vgrid_test_{}.txt", name));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn inspect_fixed_widths_trims_padding() {
+    let txt = fixed_fixture("fixed_widths", FIXED_LEDGER);
+    let output = vgrid()
+        .args(["sheet", "inspect", txt.to_str().unwrap(), "A2:C2", "--widths", "8,8,4", "--json"])
+        .output()
+        .expect("fixed --widths");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let result: serde_json::Value = serde_json::from_str(
+        &String::from_utf8_lossy(&output.stdout)
+    ).expect("valid JSON");
+    let cells: Vec<(&str, &str)> = result["cells"].as_array().unwrap()
+        .iter().map(|c| (c["cell"].as_str().unwrap(), c["value"].as_str().unwrap())).collect();
+    assert_eq!(cells, vec![("A2", "Bob"), ("B2", "7"), ("C2", "CA")]);
+    std::fs::remove_file(&txt).ok();
+}
+
+#[test]
+fn inspect_fixed_colspec_header_calc() {
+    let txt = fixed_fixture("fixed_colspec", FIXED_LEDGER);
+    let output = vgrid()
+        .args(["sheet", "inspect", txt.to_str().unwrap(),
+               "--colspec", "name:0-8,amount:8-16", "--headers", "--calc", "SUM(amount)"])
+        .output()
+        .expect("fixed --colspec --calc");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let result: serde_json::Value = serde_json::from_str(
+        &String::from_utf8_lossy(&output.stdout)
+    ).expect("valid JSON");
+    assert_eq!(result["format"], "fixed");
+    assert_eq!(result["results"][0]["value"], "1253.5");
+    std::fs::remove_file(&txt).ok();
+}
+
 // ===========================================================================
 // XLSX Tests
 // ===========================================================================
@@ -674,6 +864,93 @@ fn inspect_calc_empty_sheet_with_headers() {
     std::fs::remove_file(&csv).ok();
 }
 
+// ===========================================================================
+// --fail-on-empty / --fail-on-error Policy Tests
+// ===========================================================================
+
+fn run_calc_policy(name: &str, expr: &str, extra: &[&str]) -> (bool, serde_json::Value) {
+    let csv = csv_fixture(name, "Code,Rate\nUSD,1\nEUR,0.9\n");
+    let mut args = vec!["sheet", "inspect", csv.to_str().unwrap(), "--headers", "--calc", expr];
+    args.extend_from_slice(extra);
+    let output = vgrid().args(&args).output().expect("calc policy");
+    std::fs::remove_file(&csv).ok();
+    let result: serde_json::Value = serde_json::from_str(
+        &String::from_utf8_lossy(&output.stdout)
+    ).expect("stdout should be valid JSON");
+    (output.status.success(), result)
+}
+
+#[test]
+fn inspect_calc_empty_passes_by_default() {
+    let (ok, result) = run_calc_policy("policy_empty_default", "B10", &[]);
+    assert!(ok, "empty result should not fail without --fail-on-empty");
+    assert_eq!(result["policy"]["fail_on_empty"], false);
+    assert_eq!(result["results"][0]["value_type"], "empty");
+    assert_eq!(result["results"][0]["failed"], false);
+}
+
+#[test]
+fn inspect_calc_fail_on_empty() {
+    let (ok, result) = run_calc_policy("policy_empty_fail", "B10", &["--fail-on-empty"]);
+    assert!(!ok, "empty result should fail with --fail-on-empty");
+    assert_eq!(result["policy"]["fail_on_empty"], true);
+    assert_eq!(result["results"][0]["value_type"], "empty");
+    assert_eq!(result["results"][0]["failed"], true);
+}
+
+#[test]
+fn inspect_calc_na_fails_by_default() {
+    let (ok, result) = run_calc_policy("policy_na_default", "VLOOKUP(\"GBP\",A:B,2,FALSE)", &[]);
+    assert!(!ok, "#N/A should fail by default");
+    assert_eq!(result["policy"]["fail_on_error"], true);
+    assert_eq!(result["results"][0]["value"], "#N/A");
+    assert_eq!(result["results"][0]["failed"], true);
+}
+
+#[test]
+fn inspect_calc_na_reported_with_fail_on_error_false() {
+    let (ok, result) = run_calc_policy(
+        "policy_na_off", "VLOOKUP(\"GBP\",A:B,2,FALSE)", &["--fail-on-error=false"],
+    );
+    assert!(ok, "#N/A should not fail with --fail-on-error=false");
+    assert_eq!(result["policy"]["fail_on_error"], false);
+    assert_eq!(result["results"][0]["value"], "#N/A");
+    assert_eq!(result["results"][0]["error"], "#N/A");
+    assert_eq!(result["results"][0]["failed"], false);
+}
+
+#[test]
+fn inspect_calc_parse_error_fails_with_fail_on_error_false() {
+    let (ok, result) = run_calc_policy("policy_parse_off", "SUM(B2:B3", &["--fail-on-error=false"]);
+    assert!(!ok, "a --calc that doesn't parse should fail under any policy");
+    assert_eq!(result["policy"]["fail_on_error"], false);
+    assert!(result["results"][0]["value"].as_str().unwrap().starts_with("#PARSE"));
+    assert_eq!(result["results"][0]["failed"], true);
+}
+
+#[test]
+fn inspect_calc_fail_on_empty_only_flags_failing_check() {
+    let (ok, result) = run_calc_policy(
+        "policy_mixed", "VLOOKUP(\"EUR\",A:B,2,FALSE)", &["--calc", "B10", "--fail-on-empty"],
+    );
+    assert!(!ok);
+    assert_eq!(result["results"][0]["value"], "0.9");
+    assert_eq!(result["results"][0]["failed"], false);
+    assert_eq!(result["results"][1]["failed"], true);
+}
+
+#[test]
+fn inspect_fail_on_empty_requires_calc() {
+    let csv = csv_fixture("policy_no_calc", "a\n1\n");
+    let output = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "A1", "--fail-on-empty"])
+        .output()
+        .expect("fail-on-empty without calc");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--fail-on-empty requires --calc"));
+    std::fs::remove_file(&csv).ok();
+}
+
 // ===========================================================================
 // Semantic Calc Tests (header-name references)
 // ===========================================================================
@@ -893,6 +1170,56 @@ fn inspect_calc_ambiguous_header_errors() {
     std::fs::remove_file(&csv).ok();
 }
 
+#[test]
+fn inspect_calc_cell_refs() {
+    // Explicit cell refs pass through untouched; whole-column refs still expand
+    let csv = csv_fixture("calc_cell_refs", "Amount,Tax\n100,10\n200,20\n300,30\n400,40\n500,50\n");
+    let output = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "--headers",
+               "--calc", "A2+A3",
+               "--calc", "SUM(A2:A6)",
+               "--calc", "B2*12",
+               "--calc", "SUM(A:A)-SUM(A2:A3)",
+               "--calc", "SUM(A:B)"])
+        .output()
+        .expect("calc cell refs");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let result: serde_json::Value = serde_json::from_str(
+        &String::from_utf8_lossy(&output.stdout)
+    ).expect("valid JSON");
+    let values: Vec<&str> = result["results"].as_array().unwrap()
+        .iter().map(|r| r["value"].as_str().unwrap()).collect();
+    assert_eq!(values, vec!["300", "1500", "120", "1200", "1650"]);
+    std::fs::remove_file(&csv).ok();
+}
+
+#[test]
+fn inspect_range_transpose() {
+    // A1:C2 (3 columns x 2 rows) reads back as A1:B3
+    let csv = csv_fixture("range_transpose", "a,b,c\n1,2,3\n");
+    let output = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "A1:C2", "--transpose", "--json"])
+        .output()
+        .expect("inspect --transpose");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let result: serde_json::Value = serde_json::from_str(
+        &String::from_utf8_lossy(&output.stdout)
+    ).expect("valid JSON");
+    assert_eq!(result["range"], "A1:B3");
+    let cells: Vec<(&str, &str)> = result["cells"].as_array().unwrap()
+        .iter().map(|c| (c["cell"].as_str().unwrap(), c["value"].as_str().unwrap())).collect();
+    assert_eq!(cells, vec![("A1", "a"), ("B1", "1"), ("A2", "b"), ("B2", "2"), ("A3", "c"), ("B3", "3")]);
+
+    let output = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "A1:C2", "--transpose", "--headers"])
+        .output()
+        .expect("inspect --transpose --headers");
+    assert_eq!(output.status.code(), Some(2), "--transpose with --headers is a usage error");
+    std::fs::remove_file(&csv).ok();
+}
+
 // ===========================================================================
 // Import Tests
 // ===========================================================================
@@ -963,6 +1290,132 @@ fn import_csv_fingerprint_stable() {
     std::fs::remove_file(&out2).ok();
 }
 
+#[test]
+fn import_workbook_meta_shown_in_inspect_outside_fingerprint() {
+    let csv = csv_fixture("import_meta", "A,B\n1,2\n");
+    let plain = sheet_output("meta_plain");
+    let titled = sheet_output("meta_titled");
+
+    let r1 = vgrid()
+        .args(["sheet", "import", csv.to_str().unwrap(), plain.to_str().unwrap(), "--json"])
+        .output().expect("import plain");
+    let r2 = vgrid()
+        .args(["sheet", "import", csv.to_str().unwrap(), titled.to_str().unwrap(), "--json",
+               "--title", "Q4 Close", "--description", "GL export", "--author", "finance"])
+        .output().expect("import with metadata");
+    assert!(r1.status.success());
+    assert!(r2.status.success(), "stderr: {}", String::from_utf8_lossy(&r2.stderr));
+
+    let j1: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&r1.stdout)).unwrap();
+    let j2: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&r2.stdout)).unwrap();
+    assert_eq!(j1["fingerprint"], j2["fingerprint"], "metadata must not affect the fingerprint");
+
+    let inspect = vgrid()
+        .args(["sheet", "inspect", titled.to_str().unwrap(), "--workbook", "--json"])
+        .output().expect("inspect --workbook");
+    assert!(inspect.status.success());
+    let result: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&inspect.stdout)).unwrap();
+    assert_eq!(result["meta"]["title"], "Q4 Close");
+    assert_eq!(result["meta"]["description"], "GL export");
+    assert_eq!(result["meta"]["author"], "finance");
+    assert!(result["meta"]["created_at"].is_string());
+
+    // No flags, no metadata block
+    let inspect = vgrid()
+        .args(["sheet", "inspect", plain.to_str().unwrap(), "--workbook", "--json"])
+        .output().expect("inspect --workbook");
+    let result: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&inspect.stdout)).unwrap();
+    assert!(result.get("meta").is_none());
+
+    // Unreadable metadata is reported, and the inspect still succeeds
+    let conn = rusqlite::Connection::open(&titled).unwrap();
+    conn.execute("UPDATE meta SET value = '{' WHERE key = 'workbook_meta'", []).unwrap();
+    drop(conn);
+    let inspect = vgrid()
+        .args(["sheet", "inspect", titled.to_str().unwrap(), "--workbook", "--json"])
+        .output().expect("inspect --workbook");
+    assert!(inspect.status.success(), "stderr: {}", String::from_utf8_lossy(&inspect.stderr));
+    let result: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&inspect.stdout)).unwrap();
+    assert!(result.get("meta").is_none());
+    assert!(String::from_utf8_lossy(&inspect.stderr).contains("cannot read workbook metadata"),
+        "stderr: {}", String::from_utf8_lossy(&inspect.stderr));
+
+    std::fs::remove_file(&csv).ok();
+    std::fs::remove_file(&plain).ok();
+    std::fs::remove_file(&titled).ok();
+}
+
+#[test]
+fn import_trim_empty_drops_trailing_padding() {
+    use visigrid_engine::workbook::Workbook;
+
+    // Same data three ways: clean, padded with 50 rows and a trailing column
+    // of styled empty cells (real cells, blank on screen), and with a
+    // trailing formula that evaluates to blank. The interior blank (B2) stays.
+    enum Pad { None, Styled, BlankFormula }
+    let build = |pad: Pad| {
+        let mut wb = Workbook::new();
+        let sheet = wb.sheet_mut(0).unwrap();
+        sheet.set_value(0, 0, "A");
+        sheet.set_value(0, 1, "B");
+        sheet.set_value(1, 0, "1");
+        sheet.set_value(2, 0, "3");
+        sheet.set_value(2, 1, "4");
+        match pad {
+            Pad::None => {}
+            Pad::Styled => {
+                let bold = visigrid_engine::cell::CellFormat { bold: true, ..Default::default() };
+                for r in 0..53 {
+                    sheet.set_format(r, 2, bold.clone());
+                }
+                for r in 3..53 {
+                    sheet.set_format(r, 0, bold.clone());
+                }
+            }
+            Pad::BlankFormula => sheet.set_value(0, 2, "=IF(A2>0,\"\",1)"),
+        }
+        wb
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let clean = dir.path().join("clean.xlsx");
+    let padded = dir.path().join("padded.xlsx");
+    let formula = dir.path().join("formula.xlsx");
+    visigrid_io::xlsx::export(&build(Pad::None), &clean, None).unwrap();
+    visigrid_io::xlsx::export(&build(Pad::Styled), &padded, None).unwrap();
+    visigrid_io::xlsx::export(&build(Pad::BlankFormula), &formula, None).unwrap();
+    let out = dir.path().join("out.sheet");
+
+    let import = |src: &std::path::Path, trim: bool| {
+        let mut args = vec![
+            "sheet", "import", src.to_str().unwrap(), out.to_str().unwrap(),
+            "--formulas", "recalc", "--json",
+        ];
+        if trim {
+            args.push("--trim-empty");
+        }
+        let output = vgrid().args(&args).output().expect("import");
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_str::<serde_json::Value>(&String::from_utf8_lossy(&output.stdout)).unwrap()
+    };
+
+    let trimmed = import(&padded, true);
+    assert_eq!(trimmed["rows"], 3);
+    assert_eq!(trimmed["cols"], 2);
+    assert_eq!(trimmed["cells"], 5);
+
+    let reference = import(&clean, false);
+    assert_eq!(trimmed["fingerprint"], reference["fingerprint"],
+        "trimmed padded import must fingerprint like the clean import");
+    assert_eq!(import(&clean, true)["fingerprint"], reference["fingerprint"]);
+
+    // A formula is content even when it displays blank: trimming keeps it
+    let untrimmed = import(&formula, false);
+    let kept = import(&formula, true);
+    assert_eq!(kept["cells"], untrimmed["cells"]);
+    assert_eq!(kept["fingerprint"], untrimmed["fingerprint"]);
+    assert_ne!(kept["fingerprint"], reference["fingerprint"]);
+}
+
 #[test]
 fn import_xlsx_sheet_selects_correct_sheet() {
     let out = sheet_output("xlsx_sheet_select");
@@ -1655,3 +2108,175 @@ fn parse_cell_ref(s: &str) -> (usize, usize) {
     let row: usize = row_str.parse().unwrap();
     (row - 1, col)
 }
+
+#[test]
+fn import_xlsx_comment_survives_sheet_and_shows_in_inspect() {
+    use visigrid_engine::sheet::{CellComment, Sheet, SheetId};
+    use visigrid_engine::workbook::Workbook;
+
+    let mut sheet = Sheet::new(SheetId(1), 50, 10);
+    sheet.set_value(0, 0, "Revenue");
+    sheet.set_value(0, 1, "1200");
+    sheet.set_comment(0, 1, Some(CellComment {
+        text: "Q3 restated".to_string(),
+        author: Some("Dana".to_string()),
+    }));
+    let xlsx = std::env::temp_dir().join("vgrid_test_import_comment.xlsx");
+    visigrid_io::xlsx::export(&Workbook::from_sheets(vec![sheet], 0), &xlsx, None).unwrap();
+    let out = sheet_output("comment");
+
+    let r = vgrid()
+        .args(["sheet", "import", xlsx.to_str().unwrap(), out.to_str().unwrap(), "--json"])
+        .output().expect("import xlsx");
+    assert!(r.status.success(), "stderr: {}", String::from_utf8_lossy(&r.stderr));
+
+    let inspect = vgrid()
+        .args(["sheet", "inspect", out.to_str().unwrap(), "B1", "--include-comments", "--json"])
+        .output().expect("inspect --include-comments");
+    assert!(inspect.status.success(), "stderr: {}", String::from_utf8_lossy(&inspect.stderr));
+    let cell: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&inspect.stdout)).unwrap();
+    assert_eq!(cell["value"], "1200");
+    assert_eq!(cell["comment"]["text"], "Q3 restated");
+    assert_eq!(cell["comment"]["author"], "Dana");
+
+    let inspect = vgrid()
+        .args(["sheet", "inspect", out.to_str().unwrap(), "--non-empty", "--include-comments"])
+        .output().expect("inspect --non-empty --include-comments");
+    let stdout = String::from_utf8_lossy(&inspect.stdout);
+    assert!(stdout.contains("      Comment: Q3 restated (Dana)"), "got: {}", stdout);
+
+    // Comments stay out of the output unless asked for
+    let inspect = vgrid()
+        .args(["sheet", "inspect", out.to_str().unwrap(), "B1", "--json"])
+        .output().expect("inspect");
+    let cell: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&inspect.stdout)).unwrap();
+    assert!(cell.get("comment").is_none());
+
+    std::fs::remove_file(&xlsx).ok();
+    std::fs::remove_file(&out).ok();
+}
+
+#[test]
+fn import_xlsx_conditional_format_shows_in_inspect() {
+    use visigrid_engine::conditional_format::{CompareOperator, ConditionalFormat, ConditionalRule, ConditionalStyle};
+    use visigrid_engine::sheet::{Sheet, SheetId};
+    use visigrid_engine::workbook::Workbook;
+
+    let mut sheet = Sheet::new(SheetId(1), 50, 10);
+    sheet.set_value(0, 0, "Balance");
+    sheet.set_value(1, 0, "-40");
+    sheet.set_value(2, 0, "75");
+    sheet.add_conditional_format(ConditionalFormat::new(1, 0, 2, 0, ConditionalRule::Compare {
+        operator: CompareOperator::LessThan,
+        values: vec!["0".to_string()],
+        style: ConditionalStyle { font_color: Some([0x9C, 0x00, 0x06, 255]), ..Default::default() },
+    }));
+    let xlsx = std::env::temp_dir().join("vgrid_test_import_conditional.xlsx");
+    visigrid_io::xlsx::export(&Workbook::from_sheets(vec![sheet], 0), &xlsx, None).unwrap();
+    let out = sheet_output("conditional");
+
+    let r = vgrid()
+        .args(["sheet", "import", xlsx.to_str().unwrap(), out.to_str().unwrap(), "--json"])
+        .output().expect("import xlsx");
+    assert!(r.status.success(), "stderr: {}", String::from_utf8_lossy(&r.stderr));
+
+    let inspect = vgrid()
+        .args(["sheet", "inspect", out.to_str().unwrap(), "A2", "--include-conditional", "--json"])
+        .output().expect("inspect --include-conditional");
+    assert!(inspect.status.success(), "stderr: {}", String::from_utf8_lossy(&inspect.stderr));
+    let cell: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&inspect.stdout)).unwrap();
+    let rules = cell["conditional_formats"].as_array().expect("conditional_formats array");
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0]["rule"]["type"], "compare");
+    assert_eq!(rules[0]["rule"]["operator"], "less_than");
+    assert_eq!(rules[0]["rule"]["values"][0], "0");
+    assert_eq!(rules[0]["rule"]["style"]["font_color"], "#9C0006");
+
+    let inspect = vgrid()
+        .args(["sheet", "inspect", out.to_str().unwrap(), "A2", "--include-conditional"])
+        .output().expect("inspect --include-conditional");
+    let stdout = String::from_utf8_lossy(&inspect.stdout);
+    assert!(stdout.contains("Conditional: A2:A3 less_than 0 (font #9C0006)"), "got: {}", stdout);
+
+    // Header cell is outside the rule's range; nothing shown unless asked for
+    for extra in [&["A1", "--include-conditional"][..], &["A2"][..]] {
+        let inspect = vgrid()
+            .args(["sheet", "inspect", out.to_str().unwrap(), "--json"])
+            .args(extra)
+            .output().expect("inspect");
+        let cell: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&inspect.stdout)).unwrap();
+        assert!(cell.get("conditional_formats").is_none());
+    }
+
+    std::fs::remove_file(&xlsx).ok();
+    std::fs::remove_file(&out).ok();
+}
+
+// ---------------------------------------------------------------------------
+// --count: matches a full sparse inspect
+// ---------------------------------------------------------------------------
+
+/// (non_empty_cells, max_row, max_col) from a full `--non-empty --json` inspect.
+fn full_inspect_counts(file: &Path, sheet: Option<&str>) -> (u64, u64, u64) {
+    let sheet_args = sheet.map(|s| vec!["--sheet", s]).unwrap_or_default();
+    let output = vgrid()
+        .args(["sheet", "inspect", file.to_str().unwrap(), "--non-empty", "--json"])
+        .args(sheet_args)
+        .output()
+        .expect("full inspect");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_str(&String::from_utf8_lossy(&output.stdout)).unwrap();
+    let cells = json["cells"].as_array().unwrap();
+    let refs: Vec<(usize, usize)> = cells.iter().map(|c| parse_cell_ref(c["cell"].as_str().unwrap())).collect();
+    let max_row = refs.iter().map(|(r, _)| r + 1).max().unwrap_or(0);
+    let max_col = refs.iter().map(|(_, c)| c + 1).max().unwrap_or(0);
+    (cells.len() as u64, max_row as u64, max_col as u64)
+}
+
+fn count_json(file: &Path, extra: &[&str]) -> serde_json::Value {
+    let output = vgrid()
+        .args(["sheet", "inspect", file.to_str().unwrap(), "--count", "--json"])
+        .args(extra)
+        .output()
+        .expect("inspect --count");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_str(&String::from_utf8_lossy(&output.stdout)).unwrap()
+}
+
+#[test]
+fn inspect_count_sheet_matches_full_inspect() {
+    let template = template_path();
+    for (sheet, name) in [("0", "Sheet1"), ("summary", "summary")] {
+        let count = count_json(&template, &["--sheet", sheet]);
+        let (cells, max_row, max_col) = full_inspect_counts(&template, Some(sheet));
+        assert_eq!(count["sheet"], name);
+        assert_eq!(count["non_empty_cells"], cells, "sheet {}", sheet);
+        assert_eq!(count["max_row"], max_row, "sheet {}", sheet);
+        assert_eq!(count["max_col"], max_col, "sheet {}", sheet);
+    }
+}
+
+#[test]
+fn inspect_count_csv_from_bounds() {
+    let csv = csv_fixture("count", "Name,Age,City\nAlice,30,\nBob,,Paris\n");
+    let count = count_json(&csv, &[]);
+    assert_eq!(count["non_empty_cells"], 7);
+    assert_eq!(count["max_row"], 3);
+    assert_eq!(count["max_col"], 3);
+    let (cells, _, _) = full_inspect_counts(&csv, None);
+    assert_eq!(count["non_empty_cells"], cells);
+
+    let text = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "--count"])
+        .output()
+        .expect("inspect --count");
+    assert!(text.status.success());
+    assert!(String::from_utf8_lossy(&text.stdout).contains("7 cells, 3x3"));
+
+    let conflict = vgrid()
+        .args(["sheet", "inspect", csv.to_str().unwrap(), "--count", "A1"])
+        .output()
+        .expect("inspect --count A1");
+    assert!(!conflict.status.success());
+    std::fs::remove_file(&csv).ok();
+}
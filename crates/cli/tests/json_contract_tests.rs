@@ -311,6 +311,98 @@ fn diff_json_no_diffs_produces_valid_json() {
     std::fs::remove_file(&right).ok();
 }
 
+#[test]
+fn diff_json_reports_source_row_numbers() {
+    let left = std::env::temp_dir().join("vgrid_diff_json_rows_l.csv");
+    let right = std::env::temp_dir().join("vgrid_diff_json_rows_r.csv");
+    // Title row above the header and a blank row in the data: row numbers
+    // are file rows, so both count.
+    std::fs::write(&left, "Report,\nid,amount\n1,100\n,\n2,200\n3,300\n").unwrap();
+    std::fs::write(&right, "Report,\nid,amount\n2,250\n1,100\n4,400\n").unwrap();
+
+    for stream in [false, true] {
+        let mut args = vec![
+            "diff", left.to_str().unwrap(), right.to_str().unwrap(),
+            "--key", "id", "--header-row", "2", "--json",
+        ];
+        if stream {
+            args.push("--stream");
+        }
+        let output = vgrid().args(&args).output().expect("vgrid diff --json");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let val = assert_single_json(&stdout);
+
+        let rows: std::collections::HashMap<String, (serde_json::Value, serde_json::Value)> = val["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| (
+                r["key"].as_str().unwrap().to_string(),
+                (r["left_source_index"].clone(), r["right_source_index"].clone()),
+            ))
+            .collect();
+
+        let expect = |key: &str, l: serde_json::Value, r: serde_json::Value| {
+            assert_eq!(rows[key], (l, r), "key {key} (stream={stream})");
+        };
+        expect("1", serde_json::json!(3), serde_json::json!(4));
+        expect("2", serde_json::json!(5), serde_json::json!(3));
+        expect("3", serde_json::json!(6), serde_json::Value::Null);
+        expect("4", serde_json::Value::Null, serde_json::json!(5));
+    }
+
+    std::fs::remove_file(&left).ok();
+    std::fs::remove_file(&right).ok();
+}
+
+#[test]
+fn diff_summary_only_omits_results() {
+    let left = std::env::temp_dir().join("vgrid_diff_summary_only_l.csv");
+    let right = std::env::temp_dir().join("vgrid_diff_summary_only_r.csv");
+    let export = std::env::temp_dir().join("vgrid_diff_summary_only_export.csv");
+    std::fs::write(&left, "id,amount\n1,100\n2,200\n3,300\n").unwrap();
+    std::fs::write(&right, "id,amount\n1,100\n2,250\n4,400\n").unwrap();
+
+    for stream in [false, true] {
+        let mut args = vec![
+            "diff", left.to_str().unwrap(), right.to_str().unwrap(),
+            "--key", "id", "--json", "--summary-only",
+            "--export", &format!("only_left:{}", export.display()),
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+        if stream {
+            args.push("--stream".to_string());
+        }
+        let output = vgrid().args(&args).output().expect("vgrid diff --summary-only");
+
+        // Material diffs still fail the run
+        assert_eq!(output.status.code(), Some(1), "stream={stream}\nstderr: {}",
+            String::from_utf8_lossy(&output.stderr));
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let val = assert_single_json(&stdout);
+        let obj = val.as_object().unwrap();
+        assert!(!obj.contains_key("results"), "summary-only must omit results (stream={stream})");
+        let summary = &obj["summary"];
+        assert_eq!(summary["matched"], 1);
+        assert_eq!(summary["diff"], 1);
+        assert_eq!(summary["only_left"], 1);
+        assert_eq!(summary["only_right"], 1);
+        assert_eq!(summary["key"], "id");
+
+        // --export still gets its rows
+        let exported = std::fs::read_to_string(&export).unwrap();
+        assert_eq!(exported.lines().count(), 2, "header + one only_left row: {exported}");
+        assert!(exported.contains("3,300"));
+    }
+
+    std::fs::remove_file(&left).ok();
+    std::fs::remove_file(&right).ok();
+    std::fs::remove_file(&export).ok();
+}
+
 #[test]
 fn diff_json_suppresses_stderr_banners() {
     let left = std::env::temp_dir().join("vgrid_diff_json_quiet_l.csv");
@@ -334,6 +426,307 @@ fn diff_json_suppresses_stderr_banners() {
     std::fs::remove_file(&right).ok();
 }
 
+#[test]
+fn diff_json_exit_reason_matches_failure_category() {
+    let left = std::env::temp_dir().join("vgrid_diff_reason_l.csv");
+    let right = std::env::temp_dir().join("vgrid_diff_reason_r.csv");
+
+    // (left, right, extra args, exit_reason, exit code)
+    let cases: [(&str, &str, &[&str], &str, i32); 6] = [
+        ("id,amt\n1,10\n2,20\n", "id,amt\n1,10\n2,20\n", &[], "reconciled", 0),
+        // Missing rows outrank value diffs
+        ("id,amt\n1,10\n2,20\n3,30\n", "id,amt\n1,10\n2,25\n", &[], "missing_rows", 1),
+        ("id,amt\n1,10\n2,20\n", "id,amt\n1,10\n2,25\n", &[], "value_diffs", 1),
+        // Within tolerance passes unless --strict-exit
+        ("id,amt\n1,10\n", "id,amt\n1,10.005\n", &["--tolerance", "0.01"], "reconciled", 0),
+        ("id,amt\n1,10\n", "id,amt\n1,10.005\n", &["--tolerance", "0.01", "--strict-exit"], "value_diffs", 1),
+        // Reported ambiguity doesn't fail the run. The unclaimed candidates are
+        // only_right rows, which --baseline accepts.
+        ("id,amt\nA1,10\n", "id,amt\nXA1,10\nYA1,10\n",
+            &["--match", "contains", "--on-ambiguous", "report", "--baseline"], "ambiguous", 0),
+    ];
+
+    for (l, r, extra, reason, code) in cases {
+        std::fs::write(&left, l).unwrap();
+        std::fs::write(&right, r).unwrap();
+        let mut args = vec!["diff", left.to_str().unwrap(), right.to_str().unwrap(), "--key", "id", "--json"];
+        args.extend_from_slice(extra);
+        let output = vgrid().args(&args).output().expect("vgrid diff --json");
+
+        let val = assert_single_json(&String::from_utf8_lossy(&output.stdout));
+        assert_eq!(val["exit_reason"], reason, "{extra:?}: {}", val["summary"]);
+        assert_eq!(output.status.code(), Some(code), "{extra:?}\nstderr: {}",
+            String::from_utf8_lossy(&output.stderr));
+    }
+
+    std::fs::remove_file(&left).ok();
+    std::fs::remove_file(&right).ok();
+}
+
+#[test]
+fn diff_locale_de_compares_source_numbers() {
+    let dir = tempfile::tempdir().unwrap();
+    let left = dir.path().join("left.csv");
+    let right = dir.path().join("right.csv");
+    // Under en rules "1.000" is 1 and "1.500" is 1.5; in de they are 1000 and 1500
+    std::fs::write(&left, "id;amount\n1;1.000\n2;1.500\n3;2,5\n").unwrap();
+    std::fs::write(&right, "id;amount\n1;1000\n2;1.500,00\n3;2,75\n").unwrap();
+
+    let output = vgrid()
+        .args(["diff", left.to_str().unwrap(), right.to_str().unwrap(), "--key", "id", "--locale", "de", "--json"])
+        .output()
+        .expect("vgrid diff --locale de");
+    assert_eq!(output.status.code(), Some(1), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let val = assert_single_json(&String::from_utf8_lossy(&output.stdout));
+    let summary = &val["summary"];
+    assert_eq!(summary["matched"], 2, "{summary}");
+    assert_eq!(summary["diff"], 1, "{summary}");
+
+    let results = val["results"].as_array().unwrap();
+    let row = results.iter().find(|r| r["key"] == "3").unwrap();
+    let diffs = row["diffs"].as_array().unwrap();
+    assert_eq!(diffs[0]["column"], "amount");
+    assert_eq!(diffs[0]["delta"], 0.25);
+}
+
+#[test]
+fn diff_json_invocation_lists_behavior_flags() {
+    let dir = tempfile::tempdir().unwrap();
+    let left = dir.path().join("left.csv");
+    let right = dir.path().join("right.csv");
+    let export = dir.path().join("only_left.csv");
+    std::fs::write(&left, "id,amt,note\n1,10,a\n").unwrap();
+    std::fs::write(&right, "id,amt,note\n1,10,b\n").unwrap();
+    let export_spec = format!("only_left:{}", export.display());
+
+    let output = vgrid()
+        .args([
+            "diff", left.to_str().unwrap(), right.to_str().unwrap(), "--key", "id", "--json",
+            "--ignore", "note", "--strict-exit", "--baseline", "--progress",
+            "--export", &export_spec, "--export-side", "both",
+        ])
+        .output()
+        .expect("vgrid diff --json");
+    let val = assert_single_json(&String::from_utf8_lossy(&output.stdout));
+    let invocation = val["invocation"].as_str().unwrap();
+    for flag in ["--ignore note", "--strict-exit", "--baseline", "--progress", "--export ", "--export-side both"] {
+        assert!(invocation.contains(flag), "{flag} missing from {invocation}");
+    }
+}
+
+#[test]
+fn diff_json_and_ndjson_inputs_match_by_object_key() {
+    let left = std::env::temp_dir().join("vgrid_diff_objects_l.json");
+    let right = std::env::temp_dir().join("vgrid_diff_objects_r.ndjson");
+    // The right side adds a "note" key, which sorts between "name" and
+    // "price": columns must still pair up by key, not by position.
+    std::fs::write(&left, r#"[
+        {"id": "1", "name": "Widget", "price": 10},
+        {"id": "2", "name": "Gadget", "price": 20},
+        {"id": "3", "name": "Doohickey", "price": 30}
+    ]"#).unwrap();
+    std::fs::write(&right, concat!(
+        r#"{"price": 25, "id": "2", "name": "Gadget", "note": "repriced"}"#, "\n",
+        r#"{"name": "Widget", "id": "1", "price": 10}"#, "\n",
+        r#"{"id": "4", "name": "Thingamajig", "price": 40}"#, "\n",
+    )).unwrap();
+
+    let args = |left: &str, right: &str| -> Vec<String> {
+        ["diff", left, right, "--key", "id", "--compare", "name,price", "--json"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    };
+    let output = vgrid()
+        .args(args(left.to_str().unwrap(), right.to_str().unwrap()))
+        .output()
+        .expect("vgrid diff json ndjson");
+    assert_eq!(output.status.code(), Some(1), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let val = assert_single_json(&String::from_utf8_lossy(&output.stdout));
+    let summary = &val["summary"];
+    assert_eq!(summary["matched"], 1, "{summary}");
+    assert_eq!(summary["diff"], 1, "{summary}");
+    assert_eq!(summary["only_left"], 1, "{summary}");
+    assert_eq!(summary["only_right"], 1, "{summary}");
+
+    let results = val["results"].as_array().unwrap();
+    let row = |key: &str| results.iter().find(|r| r["key"] == key).unwrap_or_else(|| panic!("key {key}"));
+    assert_eq!(row("1")["status"], "matched");
+    let diffs = row("2")["diffs"].as_array().unwrap();
+    assert_eq!(diffs.len(), 1, "{diffs:?}");
+    assert_eq!(diffs[0]["column"], "price");
+    assert_eq!(diffs[0]["delta"], 5.0);
+    assert_eq!(row("2")["right"]["note"], "repriced");
+    assert_eq!(row("3")["status"], "only_left");
+    assert_eq!(row("4")["status"], "only_right");
+
+    // Same comparison with the NDJSON side on stdin
+    let mut child = vgrid()
+        .args(args(left.to_str().unwrap(), "-"))
+        .args(["--stdin-format", "ndjson"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("vgrid diff stdin ndjson");
+    use std::io::Write;
+    child.stdin.take().unwrap().write_all(&std::fs::read(&right).unwrap()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    let val = assert_single_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(val["summary"]["diff"], 1, "{}", val["summary"]);
+    assert_eq!(val["summary"]["matched"], 1, "{}", val["summary"]);
+
+    std::fs::remove_file(&left).ok();
+    std::fs::remove_file(&right).ok();
+}
+
+/// Minimal JSON Schema check covering the keywords the diff schema uses.
+/// Returns the JSON pointer of the first violation.
+fn schema_violation(schema: &serde_json::Value, root: &serde_json::Value, val: &serde_json::Value, path: &str) -> Option<String> {
+    if let Some(r) = schema["$ref"].as_str() {
+        let target = root.pointer(r.trim_start_matches('#')).expect("$ref target");
+        return schema_violation(target, root, val, path);
+    }
+    if let Some(c) = schema.get("const") {
+        if c != val {
+            return Some(format!("{path}: expected const {c}, got {val}"));
+        }
+    }
+    if let Some(options) = schema["enum"].as_array() {
+        if !options.contains(val) {
+            return Some(format!("{path}: {val} not in enum"));
+        }
+    }
+    if let Some(ty) = schema.get("type") {
+        let types: Vec<&str> = match ty {
+            serde_json::Value::String(t) => vec![t.as_str()],
+            other => other.as_array().unwrap().iter().map(|t| t.as_str().unwrap()).collect(),
+        };
+        let ok = types.iter().any(|t| match *t {
+            "object" => val.is_object(),
+            "array" => val.is_array(),
+            "string" => val.is_string(),
+            "number" => val.is_number(),
+            "integer" => val.is_u64() || val.is_i64(),
+            "boolean" => val.is_boolean(),
+            "null" => val.is_null(),
+            other => panic!("unsupported type {other}"),
+        });
+        if !ok {
+            return Some(format!("{path}: {val} is not {ty}"));
+        }
+    }
+    if let (Some(min), Some(n)) = (schema["minimum"].as_f64(), val.as_f64()) {
+        if n < min {
+            return Some(format!("{path}: {n} < minimum {min}"));
+        }
+    }
+    if let Some(obj) = val.as_object() {
+        for req in schema["required"].as_array().into_iter().flatten() {
+            if !obj.contains_key(req.as_str().unwrap()) {
+                return Some(format!("{path}: missing required {req}"));
+            }
+        }
+        for (k, v) in obj {
+            let sub = match schema["properties"].get(k) {
+                Some(sub) => sub,
+                None => match &schema["additionalProperties"] {
+                    serde_json::Value::Bool(false) => return Some(format!("{path}: unexpected property {k}")),
+                    serde_json::Value::Null | serde_json::Value::Bool(true) => continue,
+                    sub => sub,
+                },
+            };
+            if let Some(e) = schema_violation(sub, root, v, &format!("{path}/{k}")) {
+                return Some(e);
+            }
+        }
+    }
+    if let (Some(items), Some(arr)) = (schema.get("items"), val.as_array()) {
+        for (i, v) in arr.iter().enumerate() {
+            if let Some(e) = schema_violation(items, root, v, &format!("{path}/{i}")) {
+                return Some(e);
+            }
+        }
+    }
+    None
+}
+
+#[test]
+fn diff_json_validates_against_printed_schema() {
+    let output = vgrid().args(["diff", "--print-schema"]).output().expect("vgrid diff --print-schema");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let schema = assert_single_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(schema["properties"]["contract_version"]["const"], 1);
+
+    let left = std::env::temp_dir().join("vgrid_diff_schema_l.csv");
+    let right = std::env::temp_dir().join("vgrid_diff_schema_r.csv");
+    std::fs::write(&left, "id,amount,memo\n1,100,a\n2,200,b\n3,300,c\n").unwrap();
+    std::fs::write(&right, "id,amount,memo\nINV-1,100,a\nINV-2,250.5,b\nINV-4,400,d\n").unwrap();
+    let (l, r) = (left.to_str().unwrap(), right.to_str().unwrap());
+
+    // Exact (every row unmatched), contains (match_explain, numeric deltas),
+    // and summary-only shapes must all conform.
+    let runs: [&[&str]; 3] = [
+        &["diff", l, r, "--key", "id", "--json"],
+        &["diff", l, r, "--key", "id", "--match", "contains", "--tolerance", "0.01", "--json"],
+        &["diff", l, r, "--key", "id", "--json", "--summary-only"],
+    ];
+    for args in runs {
+        let output = vgrid().args(args).output().expect("vgrid diff --json");
+        let val = assert_single_json(&String::from_utf8_lossy(&output.stdout));
+        if let Some(e) = schema_violation(&schema, &schema, &val, "") {
+            panic!("{args:?} output violates schema: {e}\n{val:#}");
+        }
+    }
+
+    // The check itself must reject drift
+    let mut bad = assert_single_json(&String::from_utf8_lossy(
+        &vgrid().args(runs[0]).output().unwrap().stdout,
+    ));
+    bad["results"][0]["status"] = serde_json::json!("changed");
+    assert!(schema_violation(&schema, &schema, &bad, "").is_some());
+
+    // Output from builds before exit_reason still conforms to contract_version 1
+    let mut older = assert_single_json(&String::from_utf8_lossy(
+        &vgrid().args(runs[0]).output().unwrap().stdout,
+    ));
+    older.as_object_mut().unwrap().remove("exit_reason");
+    assert_eq!(schema_violation(&schema, &schema, &older, ""), None);
+
+    std::fs::remove_file(&left).ok();
+    std::fs::remove_file(&right).ok();
+}
+
+// ===========================================================================
+// vgrid list-functions --json
+// ===========================================================================
+
+#[test]
+fn list_functions_json_has_metadata() {
+    let output = vgrid()
+        .args(["list-functions", "--json"])
+        .output()
+        .expect("vgrid list-functions --json");
+
+    assert!(output.status.success());
+    let val = assert_single_json(&String::from_utf8_lossy(&output.stdout));
+    let entries = val.as_array().expect("array of functions");
+    assert!(entries.len() > 100);
+
+    let vlookup = entries.iter().find(|e| e["name"] == "VLOOKUP").expect("VLOOKUP listed");
+    assert_eq!(vlookup["category"], "lookup");
+    assert_eq!(vlookup["arity"], serde_json::json!({"min": 3, "max": 4}));
+    assert_eq!(vlookup["volatile"], false);
+    assert!(vlookup["description"].as_str().is_some_and(|d| !d.is_empty()));
+
+    let sum = entries.iter().find(|e| e["name"] == "SUM").expect("SUM listed");
+    assert_eq!(sum["arity"]["max"], serde_json::Value::Null, "SUM is variadic");
+
+    let pmt = entries.iter().find(|e| e["name"] == "PMT").expect("PMT listed");
+    assert_eq!(pmt["category"], "financial");
+}
+
 // ===========================================================================
 // Cross-cutting: stdout must be ONLY JSON (no color codes, no banners)
 // ===========================================================================
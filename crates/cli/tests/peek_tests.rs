@@ -309,3 +309,117 @@ fn peek_unsupported_extension_error() {
     assert!(stderr.contains("xlsx"), "error should mention xlsx as supported, got: {}", stderr);
     assert!(stderr.contains("ods"), "error should mention ods as supported, got: {}", stderr);
 }
+
+// ---------------------------------------------------------------------------
+// gzip-compressed CSV reads transparently (peek --shape, convert)
+// ---------------------------------------------------------------------------
+
+fn write_gzip(path: &Path, content: &str) {
+    use std::io::Write;
+    let file = std::fs::File::create(path).expect("create gz file");
+    let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    encoder.write_all(content.as_bytes()).expect("write gz");
+    encoder.finish().expect("finish gz");
+}
+
+#[test]
+fn peek_csv_gz_shape() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let path = dir.path().join("data.csv.gz");
+    write_gzip(&path, "id,amount\n1,10\n2,20\n3,30\n");
+
+    let output = vgrid()
+        .args(["peek", path.to_str().unwrap(), "--headers", "--shape"])
+        .output()
+        .expect("vgrid peek data.csv.gz --shape");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("rows:       3"), "got: {}", stdout);
+    assert!(stdout.contains("cols:       2"), "got: {}", stdout);
+    assert!(stdout.contains("comma (CSV)"), "got: {}", stdout);
+}
+
+#[test]
+fn convert_csv_gz_to_json() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let path = dir.path().join("data.csv.gz");
+    write_gzip(&path, "id,amount\n1,10\n2,20\n");
+
+    let output = vgrid()
+        .args(["convert", path.to_str().unwrap(), "-t", "json", "--headers"])
+        .output()
+        .expect("vgrid convert data.csv.gz -t json");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let rows: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(rows, serde_json::json!([{"amount": 10, "id": 1}, {"amount": 20, "id": 2}]));
+}
+
+#[test]
+fn peek_gz_workbook_rejected() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let path = dir.path().join("data.xlsx.gz");
+    write_gzip(&path, "not a workbook");
+
+    let output = vgrid()
+        .args(["peek", path.to_str().unwrap(), "--plain"])
+        .output()
+        .expect("vgrid peek data.xlsx.gz");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("CSV/TSV only"));
+}
+
+#[test]
+fn peek_fixed_width_json() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let path = dir.path().join("ledger.txt");
+    std::fs::write(&path, "Alice       12.5NY  \nBob            7CA  \n").unwrap();
+
+    let output = vgrid()
+        .args(["peek", path.to_str().unwrap(), "--colspec", "name:0-8,amount:8-16,state:16-20", "--json"])
+        .output()
+        .expect("vgrid peek ledger.txt --colspec --json");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let data: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(data["columns"], serde_json::json!(["name", "amount", "state"]));
+    assert_eq!(data["rows"], serde_json::json!([["Alice", 12.5, "NY"], ["Bob", 7, "CA"]]));
+}
+
+#[test]
+fn peek_fixed_width_colspec_headers_keeps_first_line() {
+    // --colspec names the columns, so --headers must not eat the first line
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let path = dir.path().join("ledger.txt");
+    std::fs::write(&path, "Alice       12.5NY  \nBob            7CA  \n").unwrap();
+
+    let output = vgrid()
+        .args(["peek", path.to_str().unwrap(), "--colspec", "name:0-8,amount:8-16,state:16-20", "--headers", "--json"])
+        .output()
+        .expect("vgrid peek ledger.txt --colspec --headers --json");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let data: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(data["columns"], serde_json::json!(["name", "amount", "state"]));
+    assert_eq!(data["rows"], serde_json::json!([["Alice", 12.5, "NY"], ["Bob", 7, "CA"]]));
+}
+
+#[test]
+fn peek_fixed_width_shape() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let path = dir.path().join("ledger.dat");
+    std::fs::write(&path, "Alice       12.5NY  \nBob            7CA  \n").unwrap();
+
+    let output = vgrid()
+        .args(["peek", path.to_str().unwrap(), "--widths", "8,8,4", "--shape"])
+        .output()
+        .expect("vgrid peek ledger.dat --widths --shape");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("rows:       2"), "got: {}", stdout);
+    assert!(stdout.contains("cols:       3"), "got: {}", stdout);
+    assert!(stdout.contains("none (fixed-width)"), "got: {}", stdout);
+}
@@ -5,6 +5,7 @@ use std::collections::{BTreeMap, HashSet};
 
 use proptest::prelude::*;
 use visigrid_cli::diff::*;
+use visigrid_cli::locale::NumberLocale;
 
 // ---------------------------------------------------------------------------
 // Config
@@ -77,6 +78,7 @@ fn make_row(key: &str, amount: &str, label: &str, qty: &str) -> DataRow {
         key_raw: key.to_string(),
         key_norm: key.to_string(),
         values,
+        source_index: 0,
     }
 }
 
@@ -374,6 +376,8 @@ fn exact_opts(tolerance: f64) -> DiffOptions {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     }
 }
 
@@ -386,6 +390,8 @@ fn contains_opts(policy: AmbiguityPolicy) -> DiffOptions {
         on_ambiguous: policy,
         tolerance: 0.0,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     }
 }
 
@@ -820,6 +826,22 @@ proptest! {
 }
 
 // Rejection tests for parse_financial_number
+#[test]
+fn source_index_follows_rows_into_results() {
+    let mut left = vec![make_row("A", "1", "x", "1"), make_row("B", "2", "x", "1")];
+    let mut right = vec![make_row("B", "3", "x", "1"), make_row("C", "4", "x", "1")];
+    left[0].source_index = 2;
+    left[1].source_index = 4;
+    right[0].source_index = 7;
+    right[1].source_index = 8;
+
+    let result = reconcile(&left, &right, &headers(), &exact_opts(0.0)).unwrap();
+    let by_key = build_key_map(&result);
+    assert_eq!((by_key["A"].left_source_index, by_key["A"].right_source_index), (Some(2), None));
+    assert_eq!((by_key["B"].left_source_index, by_key["B"].right_source_index), (Some(4), Some(7)));
+    assert_eq!((by_key["C"].left_source_index, by_key["C"].right_source_index), (None, Some(8)));
+}
+
 #[test]
 fn financial_number_rejects_alpha() {
     assert_eq!(parse_financial_number("hello"), None);
@@ -1235,6 +1257,7 @@ fn make_row_transformed(
         key_raw: key_raw.to_string(),
         key_norm,
         values,
+        source_index: 0,
     }
 }
 
@@ -1257,6 +1280,8 @@ fn combo_exact_digits_tolerance() {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance: 1.0,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![
         make_row_transformed("INV-001", t, "$101.00", "", ""),
@@ -1288,6 +1313,8 @@ fn combo_exact_digits_tolerance_zero() {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance: 0.0,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![
         make_row_transformed("INV-001", t, "$100.50", "", ""),
@@ -1319,6 +1346,8 @@ fn combo_contains_digits_ambiguity_error() {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance: 0.01,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![make_row_transformed("12", t, "$100.00", "", "")];
     let right = vec![
@@ -1352,6 +1381,8 @@ fn combo_contains_digits_ambiguity_report() {
         on_ambiguous: AmbiguityPolicy::Report,
         tolerance: 0.01,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![make_row_transformed("12", t, "$100.00", "", "")];
     let right = vec![
@@ -1391,6 +1422,8 @@ fn combo_contains_trim_tolerance() {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance: 1.0,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![make_row_transformed("  INV  ", t, "$101.00", "", "")];
     let right = vec![make_row_transformed(" PREFIX-INV-SUFFIX ", t, "100.00", "", "")];
@@ -1418,6 +1451,8 @@ fn combo_exact_compare_cols_tolerance() {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance: 1.0,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![
         // K1: amount diff within tolerance, label/qty differ (but not compared)
@@ -1456,6 +1491,8 @@ fn combo_exact_tolerance_boundary() {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance: 0.25,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![
         make_row("K1", "100.50", "", ""), // delta=0.25 → exactly at boundary
@@ -1496,6 +1533,8 @@ fn combo_mixed_type_with_tolerance() {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance: 1.0, // generous, but shouldn't matter for non-numeric
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![
         make_row("K1", "1234.56", "", ""), // numeric vs non-numeric
@@ -1565,6 +1604,8 @@ fn tolerance_boundary_ieee754_summary() {
         on_ambiguous: AmbiguityPolicy::Error,
         tolerance: 0.01,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let left = vec![
         make_row("K1", "100.50", "", ""),      // boundary: delta ≈ 0.01 → within
@@ -1645,6 +1686,161 @@ fn contract_version_guard() {
     assert!(obj.contains_key("results"), "results key must exist");
 }
 
+// ===========================================================================
+// Streaming: --stream must produce the same results as the default path
+// ===========================================================================
+
+/// Run a golden diff fixture (args.txt) and return (exit code, parsed JSON).
+fn run_diff_fixture(name: &str, extra: &[&str]) -> (Option<i32>, serde_json::Value) {
+    let dir = format!("{}/../../tests/cli/diff/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let args_txt = std::fs::read_to_string(format!("{dir}/args.txt")).expect("read args.txt");
+    let mut args: Vec<String> = args_txt
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.replace("{DIR}", &dir))
+        .collect();
+    args.extend(extra.iter().map(|s| s.to_string()));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vgrid"))
+        .args(&args)
+        .output()
+        .expect("failed to run vgrid");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|e| panic!("{name}: invalid JSON ({e}); stderr: {}",
+            String::from_utf8_lossy(&output.stderr)));
+    (output.status.code(), json)
+}
+
+#[test]
+fn stream_matches_default_on_fixtures() {
+    for name in [
+        "contract-json-schema",
+        "blank_row_skip",
+        "exact_only_left",
+        "exact_only_right",
+        "currency_parsing",
+        "exact_diff_tolerance_fail",
+        "compare-positional-headerless",
+    ] {
+        let (default_code, default_json) = run_diff_fixture(name, &[]);
+        let (stream_code, stream_json) = run_diff_fixture(name, &["--stream"]);
+
+        assert_eq!(default_code, stream_code, "{name}: exit code differs");
+        assert_eq!(default_json["summary"], stream_json["summary"], "{name}: summary differs");
+        assert_eq!(default_json["results"], stream_json["results"], "{name}: results differ");
+    }
+}
+
+#[test]
+fn stream_csv_and_table_match_default() {
+    let left = std::env::temp_dir().join("vgrid_stream_formats_l.csv");
+    let right = std::env::temp_dir().join("vgrid_stream_formats_r.csv");
+    std::fs::write(&left, "id,amount\n1,100\n2,200\n3,300\n").unwrap();
+    std::fs::write(&right, "id,amount\n2,250\n1,100\n4,400\n").unwrap();
+
+    let run = |out: &str, stream: bool| {
+        let mut args = vec!["diff", left.to_str().unwrap(), right.to_str().unwrap(), "--key", "id", "--out", out, "--summary", "none"];
+        if stream {
+            args.push("--stream");
+        }
+        std::process::Command::new(env!("CARGO_BIN_EXE_vgrid")).args(&args).output().expect("failed to run vgrid")
+    };
+    for out in ["csv", "table"] {
+        let (default, streamed) = (run(out, false), run(out, true));
+        assert_eq!(default.status.code(), streamed.status.code(), "{out}: exit code differs");
+        assert_eq!(
+            String::from_utf8_lossy(&default.stdout),
+            String::from_utf8_lossy(&streamed.stdout),
+            "{out}: output differs"
+        );
+    }
+
+    std::fs::remove_file(&left).ok();
+    std::fs::remove_file(&right).ok();
+}
+
+#[test]
+fn stream_fails_on_duplicate_left_key() {
+    let left = std::env::temp_dir().join("vgrid_stream_dup_l.csv");
+    let right = std::env::temp_dir().join("vgrid_stream_dup_r.csv");
+    std::fs::write(&left, "id,amount\n1,100\n2,200\n1,300\n").unwrap();
+    std::fs::write(&right, "id,amount\n1,100\n2,200\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vgrid"))
+        .args(["diff", left.to_str().unwrap(), right.to_str().unwrap(), "--key", "id", "--out", "json", "--stream"])
+        .output()
+        .expect("failed to run vgrid");
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("left key \"1\""));
+
+    std::fs::remove_file(&left).ok();
+    std::fs::remove_file(&right).ok();
+}
+
+#[test]
+fn stream_rejects_contains_mode() {
+    let base = concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/cli/diff/contract-json-schema");
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vgrid"))
+        .args([
+            "diff",
+            &format!("{base}/left.csv"),
+            &format!("{base}/right.csv"),
+            "--key", "id",
+            "--match", "contains",
+            "--stream",
+        ])
+        .output()
+        .expect("failed to run vgrid");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--stream requires --match exact"));
+}
+
+#[test]
+fn stream_rejects_progress_and_save_ambiguous() {
+    let base = concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/cli/diff/contract-json-schema");
+    let ambiguous = std::env::temp_dir().join("vgrid_stream_ambiguous.csv");
+    for (extra, message) in [
+        (vec!["--progress"], "--progress cannot be combined with --stream"),
+        (vec!["--save-ambiguous", ambiguous.to_str().unwrap()], "--save-ambiguous cannot be combined with --stream"),
+    ] {
+        let left = format!("{base}/left.csv");
+        let right = format!("{base}/right.csv");
+        let mut args = vec!["diff", &left, &right, "--key", "id", "--stream"];
+        args.extend(extra);
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_vgrid"))
+            .args(&args)
+            .output()
+            .expect("failed to run vgrid");
+        assert_eq!(output.status.code(), Some(2), "{message}");
+        assert!(String::from_utf8_lossy(&output.stderr).contains(message));
+    }
+    assert!(!ambiguous.exists());
+}
+
+#[test]
+fn stream_warns_on_left_columns_beyond_headers() {
+    let left = std::env::temp_dir().join("vgrid_stream_wide_l.csv");
+    let right = std::env::temp_dir().join("vgrid_stream_wide_r.csv");
+    std::fs::write(&left, "id,amount\n1,100\n2,200,extra\n").unwrap();
+    std::fs::write(&right, "id,amount\n1,100\n2,200\n").unwrap();
+
+    let run = |quiet: bool| {
+        let mut args = vec!["diff", left.to_str().unwrap(), right.to_str().unwrap(), "--key", "id", "--out", "json", "--stream"];
+        if quiet {
+            args.push("--quiet");
+        }
+        std::process::Command::new(env!("CARGO_BIN_EXE_vgrid")).args(&args).output().expect("failed to run vgrid")
+    };
+    let output = run(false);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("row 3 has 3 columns but the headers cover 2"), "stderr: {stderr}");
+    assert!(!String::from_utf8_lossy(&run(true).stderr).contains("warning"));
+
+    std::fs::remove_file(&left).ok();
+    std::fs::remove_file(&right).ok();
+}
+
 // ===========================================================================
 // Contains mode: duplicate right keys, contains-column, alnum transform
 // ===========================================================================
@@ -1675,6 +1871,8 @@ fn contains_tolerates_duplicate_right_keys() {
         on_ambiguous: AmbiguityPolicy::Report,
         tolerance: 0.0,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
 
     let result = reconcile(&left, &right, &headers, &options);
@@ -1711,6 +1909,7 @@ fn contains_column_searches_different_column() {
             key_raw: "123".to_string(),
             key_norm: "123".to_string(),
             values,
+            source_index: 0,
         }
     }];
 
@@ -1724,6 +1923,7 @@ fn contains_column_searches_different_column() {
             key_raw: "R1".to_string(),
             key_norm: "R1".to_string(),
             values,
+            source_index: 0,
         }
     }];
 
@@ -1736,6 +1936,8 @@ fn contains_column_searches_different_column() {
         on_ambiguous: AmbiguityPolicy::Report,
         tolerance: 0.0,
         contains_col: None,
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let result = reconcile(&left, &right, &headers, &options_no_col).unwrap();
     assert_eq!(result.summary.only_left, 1, "without contains_col, key 123 should not match R1");
@@ -1749,6 +1951,8 @@ fn contains_column_searches_different_column() {
         on_ambiguous: AmbiguityPolicy::Report,
         tolerance: 0.0,
         contains_col: Some(1), // description column
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
     let result = reconcile(&left, &right, &headers, &options_with_col).unwrap();
     assert_eq!(result.summary.matched + result.summary.diff, 1, "with contains_col=description, should find a match");
@@ -1790,6 +1994,7 @@ fn contains_reports_ambiguous_when_multiple_right_rows_match() {
             key_raw: "123".to_string(),
             key_norm: "123".to_string(),
             values,
+            source_index: 0,
         }
     }];
 
@@ -1804,6 +2009,7 @@ fn contains_reports_ambiguous_when_multiple_right_rows_match() {
             key_raw: id.to_string(),
             key_norm: id.to_string(),
             values,
+            source_index: 0,
         }
     };
 
@@ -1820,6 +2026,8 @@ fn contains_reports_ambiguous_when_multiple_right_rows_match() {
         on_ambiguous: AmbiguityPolicy::Report,
         tolerance: 0.0,
         contains_col: Some(1), // search description column
+        empty_as_zero: false,
+        number_locale: NumberLocale::En,
     };
 
     let result = reconcile(&left, &right, &headers, &options).unwrap();
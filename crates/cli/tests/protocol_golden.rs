@@ -15,7 +15,8 @@ use serde_json::Value;
 
 // Use the shared protocol types
 use visigrid_protocol::{
-    ClientMessage, ServerMessage, EventPayload, InspectResult, InspectTarget,
+    Capabilities, Capability, ClientMessage, ServerMessage, EventPayload, InspectResult, InspectTarget,
+    ProtocolErrorCode, StructureChange,
 };
 
 /// Find the protocol_golden directory relative to workspace root.
@@ -73,13 +74,36 @@ fn test_hello_ok() {
     match server_msg {
         ServerMessage::Welcome(welcome) => {
             assert_eq!(welcome.protocol_version, 1);
-            assert!(welcome.capabilities.contains(&"apply".to_string()));
-            assert!(welcome.capabilities.contains(&"inspect".to_string()));
+            assert!(welcome.capabilities.supports(Capability::Apply));
+            assert!(welcome.capabilities.supports(Capability::Inspect));
+            assert!(welcome.capabilities.supports(Capability::Subscribe));
+            assert!(!welcome.capabilities.supports(Capability::Stats));
+            assert_eq!(welcome.max_message_bytes, None, "older servers omit the limit");
         }
         _ => panic!("Expected Welcome message"),
     }
 }
 
+#[test]
+fn test_hello_max_message() {
+    let lines = load_golden_lines("hello_max_message.jsonl");
+    assert_eq!(lines.len(), 2, "hello_max_message.jsonl should have 2 lines");
+
+    let server_msg: ServerMessage = serde_json::from_str(&lines[1])
+        .expect("Failed to deserialize welcome message");
+    match server_msg {
+        ServerMessage::Welcome(welcome) => {
+            assert_eq!(welcome.max_message_bytes, Some(10 * 1024 * 1024));
+        }
+        _ => panic!("Expected Welcome message"),
+    }
+
+    // The field survives a round trip and is omitted when unknown.
+    let value: Value = serde_json::from_str(&lines[1]).unwrap();
+    let reparsed: ServerMessage = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&reparsed).unwrap(), value);
+}
+
 #[test]
 fn test_apply_ops_ok() {
     let lines = load_golden_lines("apply_ops_ok.jsonl");
@@ -188,14 +212,18 @@ fn test_errors() {
 
         match server_msg {
             ServerMessage::Error(err) => {
-                assert!(!err.code.is_empty(), "Error code should not be empty");
+                assert!(!err.code.as_str().is_empty(), "Error code should not be empty");
+                assert!(
+                    !matches!(err.code, ProtocolErrorCode::Other(_)),
+                    "golden error code {:?} should be a known ProtocolErrorCode", err.code
+                );
                 assert!(!err.message.is_empty(), "Error message should not be empty");
 
                 // Specific checks for errors with retry_after_ms
-                if err.code == "rate_limited" {
+                if err.code == ProtocolErrorCode::RateLimited {
                     assert!(err.retry_after_ms.is_some(), "rate_limited should have retry_after_ms");
                 }
-                if err.code == "writer_conflict" {
+                if err.code == ProtocolErrorCode::WriterConflict {
                     assert!(err.retry_after_ms.is_some(), "writer_conflict should have retry_after_ms");
                 }
             }
@@ -262,6 +290,20 @@ fn test_ping_pong() {
     assert!(matches!(server_msg, ServerMessage::Pong(_)));
 }
 
+#[test]
+fn test_bye() {
+    let lines = load_golden_lines("bye.jsonl");
+    assert_eq!(lines.len(), 1, "bye.jsonl should have 1 line");
+
+    // Client-only; the server closes the connection without replying
+    let client_msg: ClientMessage = serde_json::from_str(&lines[0])
+        .expect("Failed to deserialize bye message");
+    match client_msg {
+        ClientMessage::Bye(bye) => assert_eq!(bye.id, "req-9"),
+        other => panic!("expected bye, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_stats() {
     let lines = load_golden_lines("stats.jsonl");
@@ -278,9 +320,88 @@ fn test_stats() {
     match server_msg {
         ServerMessage::StatsResult(stats) => {
             assert!(stats.active_connections > 0);
+            assert_eq!(stats.uptime_seconds, 3600);
+            assert_eq!(stats.revision_count, 57);
         }
         _ => panic!("Expected StatsResult message"),
     }
+
+    // Results from servers that predate uptime/revision_count still parse
+    let legacy: serde_json::Value = serde_json::from_str(&lines[1]).unwrap();
+    let mut legacy = legacy.as_object().unwrap().clone();
+    legacy.remove("uptime_seconds");
+    legacy.remove("revision_count");
+    match serde_json::from_value::<ServerMessage>(serde_json::Value::Object(legacy)) {
+        Ok(ServerMessage::StatsResult(stats)) => {
+            assert_eq!(stats.uptime_seconds, 0);
+            assert_eq!(stats.revision_count, 0);
+        }
+        other => panic!("Expected StatsResult message, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_session_info() {
+    let lines = load_golden_lines("session_info.jsonl");
+    assert_eq!(lines.len(), 6, "session_info.jsonl should have 6 lines");
+
+    let welcome = match serde_json::from_str::<ServerMessage>(&lines[1]) {
+        Ok(ServerMessage::Welcome(welcome)) => welcome,
+        other => panic!("Expected Welcome message, got {:?}", other),
+    };
+    let applied_revision = match serde_json::from_str::<ServerMessage>(&lines[3]) {
+        Ok(ServerMessage::ApplyOpsResult(result)) => result.revision,
+        other => panic!("Expected ApplyOpsResult message, got {:?}", other),
+    };
+
+    // Line 5: SessionInfo request
+    let client_msg: ClientMessage = serde_json::from_str(&lines[4])
+        .expect("Failed to deserialize session_info message");
+    assert!(matches!(client_msg, ClientMessage::SessionInfo(_)));
+
+    // Line 6: identity matches the welcome, revision is current
+    match serde_json::from_str::<ServerMessage>(&lines[5]) {
+        Ok(ServerMessage::SessionInfoResult(info)) => {
+            assert_eq!(info.id, "req-3");
+            assert_eq!(info.session_id, welcome.session_id);
+            assert_eq!(info.protocol_version, welcome.protocol_version);
+            assert_eq!(info.capabilities, welcome.capabilities);
+            assert_eq!(info.workbook_title, "Budget.vgrid");
+            assert_eq!(info.revision, applied_revision);
+            assert!(info.revision > welcome.revision);
+        }
+        other => panic!("Expected SessionInfoResult message, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_apply_ops_idempotent() {
+    let lines = load_golden_lines("apply_ops_idempotent.jsonl");
+    assert_eq!(lines.len(), 6, "apply_ops_idempotent.jsonl should have 6 lines");
+
+    let apply = |line: &str| match serde_json::from_str::<ClientMessage>(line) {
+        Ok(ClientMessage::ApplyOps(apply)) => apply,
+        other => panic!("Expected ApplyOps message, got {:?}", other),
+    };
+    let result = |line: &str| match serde_json::from_str::<ServerMessage>(line) {
+        Ok(ServerMessage::ApplyOpsResult(result)) => result,
+        other => panic!("Expected ApplyOpsResult message, got {:?}", other),
+    };
+
+    // Lines 3 and 5: a retry resends the same key under a new request id
+    let (first, retry) = (apply(&lines[2]), apply(&lines[4]));
+    assert!(first.idempotency_key.is_some());
+    assert_eq!(first.idempotency_key, retry.idempotency_key);
+    assert_ne!(first.id, retry.id);
+
+    // Lines 4 and 6: the retry gets the original result; revision doesn't move
+    let (original, replayed) = (result(&lines[3]), result(&lines[5]));
+    assert_eq!(replayed.id, retry.id);
+    assert_eq!(replayed.applied, original.applied);
+    assert_eq!(replayed.revision, original.revision);
+
+    // Without a key the field is omitted entirely
+    assert!(!load_golden_lines("apply_ops_ok.jsonl")[0].contains("idempotency_key"));
 }
 
 #[test]
@@ -291,7 +412,7 @@ fn test_writer_conflict() {
     // Should contain an error with writer_conflict code
     for line in &lines {
         if let Ok(ServerMessage::Error(err)) = serde_json::from_str::<ServerMessage>(line) {
-            if err.code == "writer_conflict" {
+            if err.code == ProtocolErrorCode::WriterConflict {
                 assert!(err.retry_after_ms.is_some());
                 return;
             }
@@ -316,6 +437,45 @@ fn test_events_dropped() {
     panic!("events_dropped.jsonl should contain an EventsDropped event");
 }
 
+#[test]
+fn test_sheet_structure_changed() {
+    let lines = load_golden_lines("sheet_structure_changed.jsonl");
+    assert_eq!(lines.len(), 4, "sheet_structure_changed.jsonl should have 4 lines");
+
+    // Line 1: insert_rows op
+    let client_msg: ClientMessage = serde_json::from_str(&lines[0])
+        .expect("Failed to deserialize apply_ops message");
+    match client_msg {
+        ClientMessage::ApplyOps(apply) => match &apply.ops[..] {
+            [Op::InsertRows { sheet, at, count }] => assert_eq!((*sheet, *at, *count), (0, 2, 3)),
+            other => panic!("Expected one InsertRows op, got {:?}", other),
+        },
+        _ => panic!("Expected ApplyOps message"),
+    }
+
+    // Lines 3-4: structure events, round-tripped exactly
+    let expected = [
+        (0, StructureChange::InsertedRows { at: 2, count: 3 }),
+        (1, StructureChange::AddedSheet),
+    ];
+    for (line, (expected_sheet, expected_change)) in lines[2..].iter().zip(expected) {
+        let server_msg: ServerMessage = serde_json::from_str(line)
+            .expect("Failed to deserialize event message");
+        let ServerMessage::Event(event) = &server_msg else {
+            panic!("Expected Event message");
+        };
+        assert_eq!(event.topic, "cells");
+        match &event.payload {
+            EventPayload::SheetStructureChanged { sheet, change } => {
+                assert_eq!(*sheet, expected_sheet);
+                assert_eq!(change, &expected_change);
+            }
+            _ => panic!("Expected SheetStructureChanged payload"),
+        }
+        assert_eq!(serde_json::to_string(&server_msg).unwrap(), *line);
+    }
+}
+
 /// Meta-test: Ensure all golden vector files are valid JSON.
 #[test]
 fn test_all_golden_files_valid_json() {
@@ -323,14 +483,18 @@ fn test_all_golden_files_valid_json() {
         "hello_ok.jsonl",
         "hello_protocol_mismatch.jsonl",
         "apply_ops_ok.jsonl",
+        "apply_ops_idempotent.jsonl",
         "errors.jsonl",
         "inspect.jsonl",
         "ping_pong.jsonl",
         "stats.jsonl",
+        "session_info.jsonl",
         "subscribe_events.jsonl",
         "subscribe_events_large_paste.jsonl",
         "writer_conflict.jsonl",
         "events_dropped.jsonl",
+        "sheet_structure_changed.jsonl",
+        "bye.jsonl",
     ];
 
     for filename in &golden_files {
@@ -414,7 +578,8 @@ fn test_round_trip_apply_ops() {
 // Rule: Keep #[serde(rename = "...")] and field order stable.
 
 use visigrid_protocol::{
-    HelloMessage, ApplyOpsMessage, InspectMessage, Op, ErrorMessage,
+    HelloMessage, ApplyOpsMessage, InspectMessage, Op, ErrorMessage, SessionInfoMessage,
+    SessionInfoResultMessage,
 };
 
 /// Byte-exact test for ClientMessage::Hello serialization.
@@ -430,6 +595,7 @@ fn test_client_hello_byte_exact() {
         version: "1.0.0".to_string(),
         token: "dGVzdC10b2tlbi1mb3ItZ29sZGVuLXZlY3RvcnM=".to_string(),
         protocol_version: 1,
+        framing: None,
     });
 
     let serialized = serde_json::to_string(&msg).expect("serialization failed");
@@ -472,6 +638,7 @@ fn test_client_apply_ops_byte_exact() {
         ],
         atomic: true,
         expected_revision: Some(42),
+        idempotency_key: None,
     });
 
     let serialized = serde_json::to_string(&msg).expect("serialization failed");
@@ -543,7 +710,7 @@ fn test_server_error_byte_exact() {
 
     let msg = ServerMessage::Error(ErrorMessage {
         id: "req-10".to_string(),
-        code: "writer_conflict".to_string(),
+        code: ProtocolErrorCode::WriterConflict,
         message: "Write lease held by another connection".to_string(),
         retry_after_ms: Some(5000),
     });
@@ -559,3 +726,260 @@ fn test_server_error_byte_exact() {
         golden, serialized
     );
 }
+
+/// Byte-exact test for ClientMessage::ApplyOps with an idempotency key.
+#[test]
+fn test_client_apply_ops_idempotency_key_byte_exact() {
+    let golden = load_golden_lines("apply_ops_idempotent.jsonl")[2].clone();
+
+    let msg = ClientMessage::ApplyOps(ApplyOpsMessage {
+        id: "req-2".to_string(),
+        ops: vec![Op::SetCellValue {
+            sheet: 0,
+            row: 0,
+            col: 0,
+            value: "Paid".to_string(),
+        }],
+        atomic: true,
+        expected_revision: None,
+        idempotency_key: Some("6ba7b810-9dad-11d1-80b4-00c04fd430c8:0".to_string()),
+    });
+
+    assert_eq!(
+        serde_json::to_string(&msg).unwrap(),
+        golden,
+        "\nByte-exact serialization mismatch for ApplyOps! Check idempotency_key placement."
+    );
+}
+
+/// Byte-exact test for the session_info request and its result.
+#[test]
+fn test_session_info_byte_exact() {
+    let lines = load_golden_lines("session_info.jsonl");
+
+    let request = ClientMessage::SessionInfo(SessionInfoMessage {
+        id: "req-3".to_string(),
+    });
+    assert_eq!(serde_json::to_string(&request).unwrap(), lines[4]);
+
+    let result = ServerMessage::SessionInfoResult(SessionInfoResultMessage {
+        id: "req-3".to_string(),
+        session_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+        protocol_version: 1,
+        capabilities: [Capability::Apply, Capability::Inspect, Capability::Subscribe].into_iter().collect(),
+        workbook_title: "Budget.vgrid".to_string(),
+        revision: 43,
+    });
+    assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        lines[5],
+        "\nByte-exact serialization mismatch for SessionInfoResult! Check field order."
+    );
+}
+
+// =============================================================================
+// Typed Capabilities
+// =============================================================================
+
+/// The golden welcome's string list deserializes into typed flags and
+/// reserializes to the identical array.
+#[test]
+fn test_capabilities_round_trip_lossless() {
+    let golden: Value = serde_json::from_str(&load_golden_lines("hello_ok.jsonl")[1]).unwrap();
+    let original = golden["capabilities"].clone();
+
+    let caps: Capabilities = serde_json::from_value(original.clone()).unwrap();
+    assert!(caps.apply && caps.inspect && caps.subscribe);
+    assert!(!caps.stats);
+    assert!(caps.other.is_empty());
+
+    assert_eq!(serde_json::to_value(&caps).unwrap(), original);
+}
+
+/// Names this crate does not know survive a round-trip; `apply_ops` is read as `apply`.
+#[test]
+fn test_capabilities_unknown_and_alias() {
+    let caps: Capabilities = serde_json::from_str(r#"["apply_ops","inspect","follow","stats"]"#).unwrap();
+    assert!(caps.supports(Capability::Apply));
+    assert!(caps.supports(Capability::Stats));
+    assert!(!caps.supports(Capability::Subscribe));
+    assert_eq!(caps.other, vec!["follow".to_string()]);
+
+    let serialized = serde_json::to_string(&caps).unwrap();
+    assert_eq!(serialized, r#"["apply","inspect","stats","follow"]"#);
+    let reparsed: Capabilities = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(reparsed, caps);
+
+    let built: Capabilities = [Capability::Subscribe, Capability::Apply].into_iter().collect();
+    assert_eq!(built.names(), vec!["apply".to_string(), "subscribe".to_string()]);
+}
+
+// =============================================================================
+// Typed Error Codes
+// =============================================================================
+
+/// Every known code serializes as its wire string and parses back to itself.
+#[test]
+fn test_error_codes_round_trip() {
+    for code in ProtocolErrorCode::ALL {
+        let json = serde_json::to_string(&code).unwrap();
+        assert_eq!(json, format!("\"{}\"", code.as_str()));
+        let back: ProtocolErrorCode = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, code);
+        assert_eq!(code.as_str().parse::<ProtocolErrorCode>().unwrap(), code);
+    }
+    assert_eq!(ProtocolErrorCode::WriterConflict.as_str(), "writer_conflict");
+    assert_eq!(ProtocolErrorCode::RevisionMismatch.to_string(), "revision_mismatch");
+}
+
+/// Codes from a newer server survive a round trip through this client.
+#[test]
+fn test_error_code_unknown_preserved() {
+    let line = r#"{"type":"error","id":"req-1","code":"quota_exceeded","message":"m"}"#;
+    let msg: ServerMessage = serde_json::from_str(line).unwrap();
+    let ServerMessage::Error(err) = &msg else {
+        panic!("expected error message");
+    };
+    assert_eq!(err.code, ProtocolErrorCode::Other("quota_exceeded".to_string()));
+    assert_eq!(serde_json::to_string(&msg).unwrap(), line);
+
+    // An unknown code never shadows a known one
+    assert_eq!("timeout".parse::<ProtocolErrorCode>().unwrap(), ProtocolErrorCode::Timeout);
+}
+
+/// set_comment: author is omitted when unset and sheet defaults to 0.
+#[test]
+fn test_set_comment_op_wire_format() {
+    let op = Op::SetComment {
+        sheet: 0,
+        row: 3,
+        col: 1,
+        text: "Check this".to_string(),
+        author: Some("Ana".to_string()),
+    };
+    assert_eq!(
+        serde_json::to_string(&op).unwrap(),
+        r#"{"op":"set_comment","sheet":0,"row":3,"col":1,"text":"Check this","author":"Ana"}"#
+    );
+
+    let parsed: Op = serde_json::from_str(r#"{"op":"set_comment","row":0,"col":0,"text":""}"#).unwrap();
+    let Op::SetComment { sheet, text, author, .. } = &parsed else {
+        panic!("expected set_comment");
+    };
+    assert_eq!((*sheet, text.as_str(), author), (0, "", &None));
+    assert_eq!(
+        serde_json::to_string(&parsed).unwrap(),
+        r#"{"op":"set_comment","sheet":0,"row":0,"col":0,"text":""}"#
+    );
+}
+
+/// set_conditional_format: unused style fields and empty lists are omitted.
+#[test]
+fn test_set_conditional_format_op_wire_format() {
+    let op = Op::SetConditionalFormat {
+        sheet: 0,
+        start_row: 1,
+        start_col: 1,
+        end_row: 9,
+        end_col: 1,
+        rule: "less_than".to_string(),
+        values: vec!["0".to_string()],
+        font_color: Some("#9C0006".to_string()),
+        background_color: None,
+        bold: None,
+        italic: None,
+        colors: vec![],
+    };
+    assert_eq!(
+        serde_json::to_string(&op).unwrap(),
+        r##"{"op":"set_conditional_format","sheet":0,"start_row":1,"start_col":1,"end_row":9,"end_col":1,"rule":"less_than","values":["0"],"font_color":"#9C0006"}"##
+    );
+
+    let parsed: Op = serde_json::from_str(
+        r##"{"op":"set_conditional_format","start_row":0,"start_col":2,"end_row":9,"end_col":2,"rule":"color_scale","colors":["#F8696B","#63BE7B"]}"##,
+    ).unwrap();
+    let Op::SetConditionalFormat { sheet, rule, values, colors, .. } = &parsed else {
+        panic!("expected set_conditional_format");
+    };
+    assert_eq!((*sheet, rule.as_str(), values.len(), colors.len()), (0, "color_scale", 0, 2));
+}
+
+// =============================================================================
+// Framing
+// =============================================================================
+
+use visigrid_protocol::{read_frame, write_frame, Framing};
+
+/// Every golden message survives a write/read through both framings unchanged.
+#[test]
+fn test_golden_lines_round_trip_through_both_framings() {
+    let lines = load_golden_lines("hello_ok.jsonl");
+    for framing in [Framing::Jsonl, Framing::LengthPrefixed] {
+        let mut wire = Vec::new();
+        for line in &lines {
+            write_frame(&mut wire, framing, line.as_bytes()).unwrap();
+        }
+
+        let mut reader = std::io::Cursor::new(wire);
+        for line in &lines {
+            let frame = read_frame(&mut reader, framing, 1024).unwrap().expect("frame");
+            assert_eq!(String::from_utf8(frame).unwrap(), *line, "{:?}", framing);
+        }
+        assert!(read_frame(&mut reader, framing, 1024).unwrap().is_none(), "{:?}: clean close", framing);
+    }
+}
+
+/// A pretty-printed message carries raw newlines: only length-prefixed framing takes it.
+#[test]
+fn test_length_prefixed_frame_carries_newlines() {
+    let msg: ServerMessage = serde_json::from_str(&load_golden_lines("hello_ok.jsonl")[1]).unwrap();
+    let pretty = serde_json::to_vec_pretty(&msg).unwrap();
+    assert!(pretty.contains(&b'\n'));
+
+    let err = write_frame(&mut Vec::new(), Framing::Jsonl, &pretty).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let mut wire = Vec::new();
+    write_frame(&mut wire, Framing::LengthPrefixed, &pretty).unwrap();
+    assert_eq!(&wire[..4], &(pretty.len() as u32).to_be_bytes());
+
+    let frame = read_frame(&mut std::io::Cursor::new(wire), Framing::LengthPrefixed, 1 << 20).unwrap().unwrap();
+    let back: ServerMessage = serde_json::from_slice(&frame).unwrap();
+    assert!(matches!(back, ServerMessage::Welcome(_)));
+}
+
+/// Oversize and truncated frames are errors, not partial messages.
+#[test]
+fn test_read_frame_limits_and_truncation() {
+    for framing in [Framing::Jsonl, Framing::LengthPrefixed] {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, framing, &[b'x'; 64]).unwrap();
+
+        let err = read_frame(&mut std::io::Cursor::new(wire.clone()), framing, 32).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "{:?}", framing);
+
+        wire.truncate(wire.len() - 2);
+        let err = read_frame(&mut std::io::Cursor::new(wire), framing, 1024).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof, "{:?}", framing);
+    }
+}
+
+/// Length-prefixed framing needs both the hello request and the server capability.
+#[test]
+fn test_framing_negotiation() {
+    let hello: ClientMessage = serde_json::from_str(
+        r#"{"type":"hello","id":"1","client":"c","version":"1","token":"t","protocol_version":1,"framing":"length_prefixed"}"#,
+    ).unwrap();
+    let ClientMessage::Hello(hello) = hello else { panic!("expected hello") };
+    assert_eq!(hello.framing, Some(Framing::LengthPrefixed));
+
+    // Not a known capability yet: it round-trips through `other`
+    let caps: Capabilities = serde_json::from_str(r#"["apply","length_prefixed"]"#).unwrap();
+    assert_eq!(caps.other, vec!["length_prefixed".to_string()]);
+    assert_eq!(serde_json::to_string(&caps).unwrap(), r#"["apply","length_prefixed"]"#);
+    assert_eq!(Framing::negotiated(hello.framing, &caps), Framing::LengthPrefixed);
+
+    let old_server: Capabilities = serde_json::from_str(r#"["apply","inspect"]"#).unwrap();
+    assert_eq!(Framing::negotiated(hello.framing, &old_server), Framing::Jsonl);
+    assert_eq!(Framing::negotiated(None, &caps), Framing::Jsonl);
+}
@@ -0,0 +1,108 @@
+// Integration tests for `vgrid scripts run`.
+// Run with: cargo test -p visigrid-cli --test scripts_tests -- --nocapture
+
+use std::path::PathBuf;
+use std::process::Command;
+
+fn vgrid() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_vgrid"));
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd
+}
+
+/// Build model.sheet (A1=10, A2=20, B1=A1+A2) with a project script `bump`
+/// that changes A1, adds a formula in C1, and rewrites A2 with its own value.
+fn model_with_script(dir: &tempfile::TempDir) -> PathBuf {
+    let lua = dir.path().join("build.lua");
+    std::fs::write(&lua, "set(\"A1\", 10)\nset(\"A2\", 20)\nset(\"B1\", \"=A1+A2\")\n").unwrap();
+    let file = dir.path().join("model.sheet");
+    let output = vgrid()
+        .args(["sheet", "apply", file.to_str().unwrap(), "--lua", lua.to_str().unwrap()])
+        .output()
+        .expect("vgrid sheet apply");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let scripts = dir.path().join(".visigrid/scripts");
+    std::fs::create_dir_all(&scripts).unwrap();
+    std::fs::write(
+        scripts.join("bump.lua"),
+        "sheet:set(\"A1\", 15)\nsheet:set(\"C1\", \"=B1*2\")\nsheet:set(\"A2\", 20)\n",
+    ).unwrap();
+    file
+}
+
+#[test]
+fn plan_diff_shows_current_and_planned_values() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = model_with_script(&dir);
+    let before = std::fs::read(&file).unwrap();
+
+    let json_out = vgrid()
+        .args(["scripts", "run", "bump", file.to_str().unwrap(), "--plan", "--diff", "--json"])
+        .output()
+        .expect("vgrid scripts run --plan --diff --json");
+    assert!(json_out.status.success(), "stderr: {}", String::from_utf8_lossy(&json_out.stderr));
+    let plan: serde_json::Value = serde_json::from_slice(&json_out.stdout).expect("valid JSON");
+    assert_eq!(plan["ops_count"], 3);
+    assert_eq!(plan["changes"], serde_json::json!([
+        { "cell": "A1", "kind": "value", "current": "10", "planned": "15", "within_tolerance": false },
+        { "cell": "C1", "kind": "formula", "current": "", "planned": "=B1*2", "within_tolerance": false },
+    ]));
+    let fp_before = plan["fingerprint_before"].as_str().unwrap();
+    let fp_after = plan["fingerprint_after"].as_str().unwrap();
+    assert_ne!(fp_before, fp_after);
+
+    let text_out = vgrid()
+        .args(["scripts", "run", "bump", file.to_str().unwrap(), "--plan", "--diff"])
+        .output()
+        .expect("vgrid scripts run --plan --diff");
+    assert!(text_out.status.success());
+    let text = String::from_utf8_lossy(&text_out.stdout);
+    let body: Vec<&str> = text.lines().skip(1).collect(); // first line carries the script hash
+    assert_eq!(body, [
+        "Mode:   plan (dry run)",
+        "Cells:  2 changed",
+        &format!("FP:     {} → {}", fp_before, fp_after),
+        "",
+        "  ~ A1  value: 10 → 15 (Δ 5)",
+        "  ~ C1  formula: (empty) → =B1*2",
+    ]);
+
+    // A plan never touches the file, and --apply lands on the planned fingerprint
+    assert_eq!(std::fs::read(&file).unwrap(), before);
+    let apply = vgrid()
+        .args(["scripts", "run", "bump", file.to_str().unwrap(), "--apply", "--json"])
+        .output()
+        .expect("vgrid scripts run --apply");
+    assert!(apply.status.success(), "stderr: {}", String::from_utf8_lossy(&apply.stderr));
+    let applied: serde_json::Value = serde_json::from_slice(&apply.stdout).unwrap();
+    assert_eq!(applied["fingerprint_after"], fp_after);
+}
+
+#[test]
+fn plan_diff_marks_changes_within_tolerance() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = model_with_script(&dir);
+
+    let output = vgrid()
+        .args(["scripts", "run", "bump", file.to_str().unwrap(), "--plan", "--diff", "--tolerance", "5", "--json"])
+        .output()
+        .expect("vgrid scripts run --plan --diff --tolerance");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(plan["tolerance"], 5.0);
+    // A1 moves by exactly 5; the formula in C1 is not numeric
+    assert_eq!(plan["changes"][0]["within_tolerance"], true);
+    assert_eq!(plan["changes"][1]["within_tolerance"], false);
+}
+
+#[test]
+fn diff_requires_plan() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = model_with_script(&dir);
+    let output = vgrid()
+        .args(["scripts", "run", "bump", file.to_str().unwrap(), "--apply", "--diff"])
+        .output()
+        .expect("vgrid scripts run --apply --diff");
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,82 @@
+// Integration tests for `vgrid sheet apply --check`.
+// Run with: cargo test -p visigrid-cli --test sheet_apply_check_tests -- --nocapture
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn vgrid() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_vgrid"));
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd
+}
+
+const MODEL: &str = r#"
+set("A1", "Revenue")
+set("B1", 100)
+set("A2", "Costs")
+set("B2", 130)
+set("A3", "Margin")
+set("B3", "=B1-B2")
+set("B4", "=SUM(B1:B2)")
+"#;
+
+/// Build `model.sheet` in `dir` with the given extra args.
+fn apply(dir: &tempfile::TempDir, extra: &[&str]) -> (Output, PathBuf) {
+    let script = dir.path().join("model.lua");
+    std::fs::write(&script, MODEL).unwrap();
+    let out = dir.path().join("model.sheet");
+    let output = vgrid()
+        .args(["sheet", "apply", out.to_str().unwrap(), "--lua", script.to_str().unwrap()])
+        .args(extra)
+        .output()
+        .expect("vgrid sheet apply");
+    (output, out)
+}
+
+#[test]
+fn passing_checks_write_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let (output, out) = apply(&dir, &["--check", "B4==B1+B2", "--check", "B1>0"]);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(out.exists());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Checks:       2 passed"));
+}
+
+#[test]
+fn failing_check_aborts_with_actual_value() {
+    let dir = tempfile::tempdir().unwrap();
+    let (output, out) = apply(&dir, &["--check", "B1>0", "--check", "B3>=0"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!out.exists(), "a failed check must not write the output");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Check failed: B3>=0"), "stderr: {}", stderr);
+    assert!(stderr.contains("B3 = -30"), "stderr: {}", stderr);
+    assert!(!stderr.contains("Check failed: B1>0"), "stderr: {}", stderr);
+    assert!(stderr.contains("1 of 2 checks failed"), "stderr: {}", stderr);
+}
+
+#[test]
+fn failing_check_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let (output, _) = apply(&dir, &["--check", "B3!=-30", "--json"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["error"], "check_failed");
+    assert_eq!(json["checks"][0]["check"], "B3!=-30");
+    assert_eq!(json["checks"][0]["passed"], false);
+    assert_eq!(json["checks"][0]["value"], "FALSE");
+}
+
+#[test]
+fn unparseable_check_is_usage_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let (output, out) = apply(&dir, &["--check", "B1>=("]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!out.exists());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid --check"));
+}
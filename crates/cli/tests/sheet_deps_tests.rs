@@ -0,0 +1,81 @@
+// Integration tests for `vgrid sheet deps`.
+// Run with: cargo test -p visigrid-cli --test sheet_deps_tests -- --nocapture
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn vgrid() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_vgrid"));
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd
+}
+
+/// Build a sheet with the chain A1 -> B1 -> C1 plus an unrelated D1 = A2.
+fn chain_sheet(dir: &tempfile::TempDir) -> PathBuf {
+    let lua = dir.path().join("build.lua");
+    std::fs::write(&lua, r#"
+set("A1", 10)
+set("B1", "=A1*2")
+set("C1", "=B1+1")
+set("A2", 5)
+set("D1", "=A2")
+"#).unwrap();
+    let out = dir.path().join("model.sheet");
+    let output = vgrid()
+        .args(["sheet", "apply", out.to_str().unwrap(), "--lua", lua.to_str().unwrap()])
+        .output()
+        .expect("vgrid sheet apply");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    out
+}
+
+fn deps(file: &Path, args: &[&str]) -> Vec<String> {
+    let output = vgrid()
+        .args(["sheet", "deps", file.to_str().unwrap()])
+        .args(args)
+        .output()
+        .expect("vgrid sheet deps");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).lines().map(String::from).collect()
+}
+
+#[test]
+fn deps_chain_both_directions() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = chain_sheet(&dir);
+
+    assert_eq!(deps(&file, &["A1", "--downstream"]), ["B1", "C1"]);
+    assert_eq!(deps(&file, &["A1"]), ["B1", "C1"], "downstream is the default");
+    assert_eq!(deps(&file, &["B1", "--downstream"]), ["C1"]);
+    assert!(deps(&file, &["C1", "--downstream"]).is_empty());
+
+    assert_eq!(deps(&file, &["C1", "--upstream"]), ["A1", "B1"]);
+    assert_eq!(deps(&file, &["B1", "--upstream"]), ["A1"]);
+    assert!(deps(&file, &["A1", "--upstream"]).is_empty());
+}
+
+#[test]
+fn deps_json_shape() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = chain_sheet(&dir);
+
+    let out = deps(&file, &["a1", "--json"]).join("\n");
+    let val: serde_json::Value = serde_json::from_str(&out).expect("valid JSON");
+    assert_eq!(val["cell"], "A1");
+    assert_eq!(val["direction"], "downstream");
+    assert_eq!(val["count"], 2);
+    assert_eq!(val["cells"][1], serde_json::json!({ "sheet": "Sheet1", "cell": "C1" }));
+}
+
+#[test]
+fn deps_rejects_bad_cell() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = chain_sheet(&dir);
+
+    let output = vgrid()
+        .args(["sheet", "deps", file.to_str().unwrap(), "not-a-cell"])
+        .output()
+        .expect("vgrid sheet deps");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid cell reference"));
+}
@@ -0,0 +1,99 @@
+// Integration tests for `vgrid sheet inspect --diff-with`.
+// Run with: cargo test -p visigrid-cli --test sheet_inspect_diff_tests -- --nocapture
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn vgrid() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_vgrid"));
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd
+}
+
+/// Build `<name>.sheet` in `dir` from a Lua script.
+fn build(dir: &tempfile::TempDir, name: &str, lua: &str) -> PathBuf {
+    let script = dir.path().join(format!("{}.lua", name));
+    std::fs::write(&script, lua).unwrap();
+    let out = dir.path().join(format!("{}.sheet", name));
+    let output = vgrid()
+        .args(["sheet", "apply", out.to_str().unwrap(), "--lua", script.to_str().unwrap()])
+        .output()
+        .expect("vgrid sheet apply");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    out
+}
+
+fn diff_json(file: &Path, other: &Path) -> serde_json::Value {
+    let output = vgrid()
+        .args(["sheet", "inspect", file.to_str().unwrap(), "--diff-with", other.to_str().unwrap(), "--json"])
+        .output()
+        .expect("vgrid sheet inspect --diff-with");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).expect("valid JSON")
+}
+
+const BASE: &str = r#"
+set("A1", 10)
+set("A2", 5)
+set("B1", "=A1*2")
+"#;
+
+#[test]
+fn diff_reports_value_change_and_recomputed_result() {
+    let dir = tempfile::tempdir().unwrap();
+    let prev = build(&dir, "prev", BASE);
+    let model = build(&dir, "model", &BASE.replace(r#"set("A1", 10)"#, r#"set("A1", 12)"#));
+
+    let val = diff_json(&model, &prev);
+    assert_eq!(val["fingerprint"]["changed"], true);
+    assert_eq!(val["fingerprint"]["reasons"], serde_json::json!(["cells"]));
+    assert_eq!(val["summary"], serde_json::json!({ "added": 0, "removed": 0, "changed": 2 }));
+
+    let changed = &val["sheets"][0]["changed"];
+    assert_eq!(changed[0], serde_json::json!({ "cell": "A1", "kind": "value", "old": "10", "new": "12" }));
+    // B1's formula is untouched; only its computed result moved
+    assert_eq!(changed[1], serde_json::json!({ "cell": "B1", "kind": "result", "old": "20", "new": "24" }));
+}
+
+#[test]
+fn diff_reports_formula_change_distinctly() {
+    let dir = tempfile::tempdir().unwrap();
+    let prev = build(&dir, "prev", BASE);
+    let model = build(&dir, "model", &format!("{}set(\"C1\", \"total\")\n", BASE.replace("=A1*2", "=A1*3")));
+
+    let val = diff_json(&model, &prev);
+    let sheet = &val["sheets"][0];
+    assert_eq!(sheet["status"], "changed");
+    assert_eq!(sheet["changed"].as_array().unwrap().len(), 1);
+    assert_eq!(sheet["changed"][0]["cell"], "B1");
+    assert_eq!(sheet["changed"][0]["kind"], "formula");
+    assert_eq!(sheet["changed"][0]["old"], "=A1*2");
+    assert_eq!(sheet["changed"][0]["new"], "=A1*3");
+    assert_eq!(sheet["added"], serde_json::json!([{ "cell": "C1", "new": "total" }]));
+    assert_eq!(sheet["removed"], serde_json::json!([]));
+}
+
+#[test]
+fn diff_identical_files_is_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let prev = build(&dir, "prev", BASE);
+    let model = build(&dir, "model", BASE);
+
+    let val = diff_json(&model, &prev);
+    assert_eq!(val["fingerprint"]["changed"], false);
+    assert_eq!(val["fingerprint"]["reasons"], serde_json::json!([]));
+    assert_eq!(val["sheets"], serde_json::json!([]));
+}
+
+#[test]
+fn diff_with_rejects_target() {
+    let dir = tempfile::tempdir().unwrap();
+    let prev = build(&dir, "prev", BASE);
+
+    let output = vgrid()
+        .args(["sheet", "inspect", prev.to_str().unwrap(), "A1", "--diff-with", prev.to_str().unwrap()])
+        .output()
+        .expect("vgrid sheet inspect");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--diff-with"));
+}
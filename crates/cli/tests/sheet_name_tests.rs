@@ -0,0 +1,176 @@
+// Integration tests for `vgrid sheet name add/list/remove`.
+// Run with: cargo test -p visigrid-cli --test sheet_name_tests -- --nocapture
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn vgrid() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_vgrid"));
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd
+}
+
+/// Copy the two-sheet recon template (Sheet1, summary) into a temp dir.
+fn template_copy(dir: &tempfile::TempDir) -> PathBuf {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/abuse/templates/recon-template.sheet");
+    let dst = dir.path().join("model.sheet");
+    std::fs::copy(&src, &dst).unwrap();
+    dst
+}
+
+fn list_names(file: &Path) -> Vec<serde_json::Value> {
+    let output = vgrid()
+        .args(["sheet", "name", "list", file.to_str().unwrap(), "--json"])
+        .output()
+        .expect("vgrid sheet name list");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).expect("valid JSON array")
+}
+
+fn fingerprint(file: &Path) -> String {
+    let output = vgrid()
+        .args(["sheet", "fingerprint", file.to_str().unwrap()])
+        .output()
+        .expect("vgrid sheet fingerprint");
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+// ---------------------------------------------------------------------------
+// add → list → remove round-trip
+// ---------------------------------------------------------------------------
+
+#[test]
+fn name_add_list_remove_roundtrip() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = template_copy(&dir);
+    let fp_before = fingerprint(&file);
+
+    assert!(list_names(&file).is_empty(), "template starts with no names");
+
+    let output = vgrid()
+        .args(["sheet", "name", "add", file.to_str().unwrap(), "Revenue", "summary!B2:B13", "--json"])
+        .output()
+        .expect("vgrid sheet name add");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let added: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(added["action"], "added");
+    assert_eq!(added["range"], "B2:B13");
+    assert_eq!(added["sheet"], "summary");
+
+    let output = vgrid()
+        .args(["sheet", "name", "add", file.to_str().unwrap(), "TaxRate", "$A$1", "--description", "Blended rate"])
+        .output()
+        .expect("vgrid sheet name add (unqualified)");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let names = list_names(&file);
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[0]["name"], "Revenue");
+    assert_eq!(names[0]["range"], "B2:B13");
+    assert_eq!(names[0]["sheet"], "summary");
+    assert_eq!(names[1]["name"], "TaxRate");
+    assert_eq!(names[1]["range"], "A1");
+    assert_eq!(names[1]["sheet"], "Sheet1");
+    assert_eq!(names[1]["description"], "Blended rate");
+
+    // Named ranges are semantic
+    assert_ne!(fingerprint(&file), fp_before, "adding names must change the fingerprint");
+
+    for name in ["revenue", "TaxRate"] {
+        let output = vgrid()
+            .args(["sheet", "name", "remove", file.to_str().unwrap(), name])
+            .output()
+            .expect("vgrid sheet name remove");
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    assert!(list_names(&file).is_empty());
+    assert_eq!(fingerprint(&file), fp_before, "removing all names restores the fingerprint");
+    assert!(!file.with_extension("sheet.tmp").exists(), "temp file must not be left behind");
+}
+
+#[test]
+fn name_add_existing_requires_force() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = template_copy(&dir);
+
+    let add = |range: &str, force: bool| {
+        let mut cmd = vgrid();
+        cmd.args(["sheet", "name", "add", file.to_str().unwrap(), "Revenue", range]);
+        if force {
+            cmd.arg("--force");
+        }
+        cmd.output().expect("vgrid sheet name add")
+    };
+
+    assert!(add("summary!B2:B13", false).status.success());
+
+    let output = add("summary!B2:B14", false);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+
+    assert!(add("summary!B2:B14", true).status.success());
+    assert_eq!(list_names(&file)[0]["range"], "B2:B14");
+}
+
+// ---------------------------------------------------------------------------
+// Rejections: invalid range, unknown sheet, invalid name, unknown name
+// ---------------------------------------------------------------------------
+
+#[test]
+fn name_add_rejects_invalid_range() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = template_copy(&dir);
+
+    for range in ["B2:", "not a range", "summary!", "nosuch!A1"] {
+        let output = vgrid()
+            .args(["sheet", "name", "add", file.to_str().unwrap(), "Revenue", range])
+            .output()
+            .expect("vgrid sheet name add");
+        assert_eq!(output.status.code(), Some(2), "range {:?} should be a usage error", range);
+    }
+
+    let output = vgrid()
+        .args(["sheet", "name", "add", file.to_str().unwrap(), "A1", "B2"])
+        .output()
+        .expect("vgrid sheet name add");
+    assert_eq!(output.status.code(), Some(2), "a cell reference is not a valid name");
+
+    assert!(list_names(&file).is_empty(), "rejected adds must not modify the file");
+}
+
+#[test]
+fn name_remove_unknown_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = template_copy(&dir);
+
+    let output = vgrid()
+        .args(["sheet", "name", "remove", file.to_str().unwrap(), "Missing"])
+        .output()
+        .expect("vgrid sheet name remove");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no named range"));
+}
+
+#[test]
+fn name_add_fails_while_file_is_locked() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = template_copy(&dir);
+    let held = visigrid_io::native::lock_for_write(&file).unwrap();
+
+    let output = vgrid()
+        .args(["sheet", "name", "add", file.to_str().unwrap(), "Revenue", "B2"])
+        .output()
+        .expect("vgrid sheet name add");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("locked by another process"));
+
+    drop(held);
+    assert!(list_names(&file).is_empty(), "a locked add must not modify the file");
+    let leftovers: Vec<_> = std::fs::read_dir(dir.path()).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|n| n.ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "temp files left behind: {:?}", leftovers);
+}
@@ -0,0 +1,90 @@
+// Integration tests for `vgrid verify proof` on reconciliation certificates.
+// Run with: cargo test -p visigrid-cli --test verify_proof_tests -- --nocapture
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+const TOTALS: &str = "\
+date,currency,source_account,total_gross,total_fee,total_net,transaction_count
+2026-01-01,USD,acct_1,100.00,3.20,96.80,4
+";
+
+fn vgrid(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vgrid"))
+        .args(args)
+        .output()
+        .expect("run vgrid")
+}
+
+fn path(p: &Path) -> &str {
+    p.to_str().unwrap()
+}
+
+/// Write matching truth/warehouse totals and a signed certificate for them.
+fn write_certificate(dir: &Path) -> std::path::PathBuf {
+    let truth = dir.join("truth.csv");
+    let warehouse = dir.join("warehouse.csv");
+    let cert = dir.join("cert.json");
+    let key = dir.join("key.json");
+    std::fs::write(&truth, TOTALS).unwrap();
+    std::fs::write(&warehouse, TOTALS).unwrap();
+
+    let output = vgrid(&[
+        "verify", "totals", path(&truth), path(&warehouse),
+        "--certificate", path(&cert), "--signing-key", path(&key), "-q",
+    ]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    cert
+}
+
+fn verify_proof(cert: &Path) -> (Option<i32>, serde_json::Value) {
+    let output = vgrid(&["verify", "proof", path(cert), "--json"]);
+    let result = serde_json::from_slice(&output.stdout).expect("verify proof --json output");
+    (output.status.code(), result)
+}
+
+#[test]
+fn certificate_verifies() {
+    let dir = tempfile::tempdir().unwrap();
+    let cert = write_certificate(dir.path());
+
+    let (code, result) = verify_proof(&cert);
+    assert_eq!(code, Some(0), "{result:#}");
+    assert_eq!(result["status"], "pass");
+    let checks = result["checks"].as_array().unwrap();
+    for name in ["schema", "signature", "payload_blake3"] {
+        let check = checks.iter().find(|c| c["name"] == name).unwrap();
+        assert_eq!(check["status"], "pass", "{name}: {check}");
+    }
+}
+
+#[test]
+fn certificate_verifies_with_payload_keys_reordered() {
+    let dir = tempfile::tempdir().unwrap();
+    let cert = write_certificate(dir.path());
+
+    // Rewrite the payload with its keys in reverse order; the signature covers canonical bytes
+    let mut envelope: serde_json::Value = serde_json::from_slice(&std::fs::read(&cert).unwrap()).unwrap();
+    let payload = envelope["payload"].as_object().unwrap().clone();
+    envelope["payload"] = serde_json::Value::Object(payload.into_iter().rev().collect());
+    std::fs::write(&cert, serde_json::to_string_pretty(&envelope).unwrap()).unwrap();
+
+    let (code, result) = verify_proof(&cert);
+    assert_eq!(code, Some(0), "{result:#}");
+}
+
+#[test]
+fn altered_certificate_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let cert = write_certificate(dir.path());
+
+    let mut envelope: serde_json::Value = serde_json::from_slice(&std::fs::read(&cert).unwrap()).unwrap();
+    envelope["payload"]["summary"]["mismatched"] = serde_json::json!(3);
+    std::fs::write(&cert, serde_json::to_string_pretty(&envelope).unwrap()).unwrap();
+
+    let (code, result) = verify_proof(&cert);
+    assert_eq!(code, Some(1));
+    assert_eq!(result["status"], "fail");
+    let signature = result["checks"].as_array().unwrap().iter().find(|c| c["name"] == "signature").unwrap();
+    assert_eq!(signature["status"], "fail");
+}
@@ -144,6 +144,8 @@ pub struct ResolvedAIConfig {
     pub model: String,
     /// Endpoint for Local provider (resolved with default)
     pub endpoint: Option<String>,
+    /// Health check path for Local provider (resolved with default)
+    pub health_path: Option<String>,
     /// Privacy mode setting
     pub privacy_mode: bool,
     /// Whether AI can propose cell changes
@@ -171,6 +173,8 @@ pub enum AIConfigStatus {
     NotImplemented,
     /// Provider is configured but API key is missing
     MissingKey,
+    /// Provider settings are incomplete (e.g., Local with an empty endpoint)
+    Misconfigured,
     /// Configuration error (e.g., keychain access failed)
     Error,
 }
@@ -182,6 +186,7 @@ impl AIConfigStatus {
             Self::Ready => "ready",
             Self::NotImplemented => "not_implemented",
             Self::MissingKey => "missing_key",
+            Self::Misconfigured => "misconfigured",
             Self::Error => "error",
         }
     }
@@ -212,6 +217,7 @@ impl ResolvedAIConfig {
                 provider,
                 model: String::new(),
                 endpoint: None,
+                health_path: None,
                 privacy_mode: settings.privacy_mode,
                 allow_proposals: settings.allow_proposals,
                 api_key: None,
@@ -225,11 +231,14 @@ impl ResolvedAIConfig {
         // Resolve model (use default if not specified)
         let model = settings.effective_model().to_string();
 
-        // Resolve endpoint (for Local provider)
-        let endpoint = if matches!(provider, AIProvider::Local) {
-            Some(settings.effective_endpoint().to_string())
+        // Resolve endpoint and health path (for Local provider)
+        let (endpoint, health_path) = if matches!(provider, AIProvider::Local) {
+            (
+                Some(settings.effective_endpoint().trim().to_string()),
+                Some(settings.effective_health_path().to_string()),
+            )
         } else {
-            None
+            (None, None)
         };
 
         // Get API key if needed
@@ -256,10 +265,16 @@ impl ResolvedAIConfig {
 
         // Determine final status:
         // 1. If key is missing, that's the blocking issue
-        // 2. If key is present but no capabilities implemented, status is NotImplemented
-        // 3. If key is present and capabilities exist, status is Ready
+        // 2. If Local has no endpoint to talk to, it is misconfigured
+        // 3. If key is present but no capabilities implemented, status is NotImplemented
+        // 4. If key is present and capabilities exist, status is Ready
         let (status, blocking_reason) = if let Some(s) = key_status {
             (s, key_reason)
+        } else if endpoint.as_deref() == Some("") {
+            (
+                AIConfigStatus::Misconfigured,
+                Some("Local provider needs an endpoint (ai.endpoint)".to_string()),
+            )
         } else if !capabilities.any_implemented() {
             (
                 AIConfigStatus::NotImplemented,
@@ -276,6 +291,7 @@ impl ResolvedAIConfig {
             provider,
             model,
             endpoint,
+            health_path,
             privacy_mode: settings.privacy_mode,
             allow_proposals: settings.allow_proposals,
             api_key,
@@ -305,6 +321,30 @@ impl ResolvedAIConfig {
     pub fn provider_name(&self) -> &'static str {
         self.provider.name()
     }
+
+    /// OpenAI-compatible chat completions URL for the effective provider.
+    /// For Local this is derived from the configured endpoint.
+    pub fn chat_completions_url(&self) -> Option<String> {
+        match self.provider {
+            crate::settings::AIProvider::Local => self
+                .endpoint
+                .as_deref()
+                .filter(|e| !e.is_empty())
+                .map(|e| format!("{}/v1/chat/completions", e.trim_end_matches('/'))),
+            provider => provider.chat_completions_url().map(str::to_string),
+        }
+    }
+
+    /// Health check URL for the Local provider's endpoint
+    pub fn health_url(&self) -> Option<String> {
+        let endpoint = self.endpoint.as_deref().filter(|e| !e.is_empty())?;
+        let path = self.health_path.as_deref().unwrap_or("/v1/models");
+        Some(format!(
+            "{}/{}",
+            endpoint.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        ))
+    }
 }
 
 // ============================================================================
@@ -342,7 +382,7 @@ pub type AITestResult = ValidationResult;
 impl ResolvedAIConfig {
     /// Validate the AI configuration.
     /// This checks credentials and basic reachability, NOT feature functionality.
-    /// For Local provider: pings Ollama endpoint.
+    /// For Local provider: pings the endpoint's health path.
     /// For cloud providers: confirms key is present (no network call).
     pub fn validate_config(&self) -> ValidationResult {
         use crate::settings::AIProvider;
@@ -354,7 +394,7 @@ impl ResolvedAIConfig {
             AIConfigStatus::MissingKey => {
                 ValidationResult::Invalid("No API key configured".to_string())
             }
-            AIConfigStatus::Error => {
+            AIConfigStatus::Misconfigured | AIConfigStatus::Error => {
                 ValidationResult::Invalid(
                     self.blocking_reason.clone().unwrap_or_else(|| "Configuration error".to_string())
                 )
@@ -371,9 +411,10 @@ impl ResolvedAIConfig {
             AIConfigStatus::Ready => {
                 match self.provider {
                     AIProvider::Local => {
-                        // Try to reach Ollama endpoint
-                        let endpoint = self.endpoint.as_deref().unwrap_or("http://localhost:11434");
-                        let url = format!("{}/api/tags", endpoint);
+                        // Try to reach the endpoint's health path
+                        let Some(url) = self.health_url() else {
+                            return ValidationResult::Invalid("No endpoint configured".to_string());
+                        };
 
                         // Simple HTTP check with timeout using curl
                         match std::process::Command::new("curl")
@@ -383,9 +424,9 @@ impl ResolvedAIConfig {
                             Ok(output) => {
                                 let code = String::from_utf8_lossy(&output.stdout);
                                 if code.trim() == "200" {
-                                    ValidationResult::Valid("Ollama reachable".to_string())
+                                    ValidationResult::Valid(format!("Endpoint reachable ({})", url))
                                 } else {
-                                    ValidationResult::Invalid(format!("Ollama returned HTTP {}", code.trim()))
+                                    ValidationResult::Invalid(format!("{} returned HTTP {}", url, code.trim()))
                                 }
                             }
                             Err(e) => {
@@ -518,6 +559,41 @@ mod tests {
         env::remove_var("VISIGRID_TESTPROVIDER_KEY");
     }
 
+    #[test]
+    fn test_local_provider_ready_with_endpoint() {
+        use crate::settings::{AIProvider, AISettings};
+
+        let settings = AISettings {
+            provider: AIProvider::Local,
+            endpoint: Some("http://127.0.0.1:8080/".to_string()),
+            ..Default::default()
+        };
+        let config = ResolvedAIConfig::from_settings(&settings);
+        assert_eq!(config.status, AIConfigStatus::Ready);
+        assert!(config.api_key.is_none());
+        assert_eq!(
+            config.chat_completions_url().as_deref(),
+            Some("http://127.0.0.1:8080/v1/chat/completions")
+        );
+        assert_eq!(config.health_url().as_deref(), Some("http://127.0.0.1:8080/v1/models"));
+    }
+
+    #[test]
+    fn test_local_provider_misconfigured_without_endpoint() {
+        use crate::settings::{AIProvider, AISettings};
+
+        let settings = AISettings {
+            provider: AIProvider::Local,
+            endpoint: Some("  ".to_string()),
+            ..Default::default()
+        };
+        let config = ResolvedAIConfig::from_settings(&settings);
+        assert_eq!(config.status, AIConfigStatus::Misconfigured);
+        assert!(config.blocking_reason.is_some());
+        assert!(config.chat_completions_url().is_none());
+        assert!(!config.validate_config().is_valid());
+    }
+
     #[test]
     fn test_key_lookup_missing() {
         let lookup = get_api_key("nonexistent_provider_xyz");
@@ -1,8 +1,14 @@
 // Omarchy theme integration
 // Reads the current Omarchy theme and maps it to VisiGrid colors
+//
+// Entry points for the TUI and GUI:
+//   detect()                     -> Option<OmarchyEnv>   (None when not on Omarchy)
+//   apply_theme(theme, enabled)  -> overwrite a ThemeColors with the Omarchy palette
+// `apply_theme`, `load_theme` and `ThemeManager::from_settings` honor
+// `Settings::omarchy_enabled` through `detect_enabled()`.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use serde::Deserialize;
@@ -10,8 +16,11 @@ use serde::Deserialize;
 use crate::theme::ThemeColors;
 use crate::Color;
 
+/// Location of the active theme, relative to $HOME
+const CURRENT_DIR: &str = ".config/omarchy/current";
+
 /// Omarchy colors.toml structure
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct OmarchyColors {
     pub accent: String,
     pub cursor: String,
@@ -41,9 +50,7 @@ pub struct OmarchyColors {
 impl OmarchyColors {
     /// Path to current Omarchy theme colors
     pub fn config_path() -> Option<PathBuf> {
-        let home = std::env::var("HOME").ok()?;
-        let path = PathBuf::from(home)
-            .join(".config/omarchy/current/theme/colors.toml");
+        let path = colors_path(&home_dir()?);
         if path.exists() {
             Some(path)
         } else {
@@ -53,7 +60,10 @@ impl OmarchyColors {
 
     /// Load colors from the current Omarchy theme
     pub fn load() -> Option<Self> {
-        let path = Self::config_path()?;
+        Self::load_from(&Self::config_path()?)
+    }
+
+    fn load_from(path: &Path) -> Option<Self> {
         let content = fs::read_to_string(path).ok()?;
         toml::from_str(&content).ok()
     }
@@ -112,39 +122,199 @@ impl OmarchyColors {
     }
 }
 
+/// A detected Omarchy desktop: the active theme's palette and where it lives.
+#[derive(Debug, Clone)]
+pub struct OmarchyEnv {
+    /// `~/.config/omarchy/current`
+    pub current_dir: PathBuf,
+    /// Contents of `theme.name`, if present
+    pub theme_name: Option<String>,
+    pub colors: OmarchyColors,
+}
+
+impl OmarchyEnv {
+    /// VisiGrid colors derived from the Omarchy palette
+    pub fn theme_colors(&self) -> ThemeColors {
+        self.colors.to_theme_colors()
+    }
+
+    /// Overwrite `theme` with the Omarchy palette
+    pub fn apply_theme(&self, theme: &mut ThemeColors) {
+        *theme = self.theme_colors();
+    }
+
+    /// Display name: the Omarchy theme name, or "System"
+    pub fn display_name(&self) -> String {
+        self.theme_name.clone().unwrap_or_else(|| "System".into())
+    }
+
+    /// Most recent modification time of `colors.toml` and `theme.name`,
+    /// for polling theme switches
+    pub fn mtime(&self) -> Option<SystemTime> {
+        mtime_in(&self.current_dir)
+    }
+}
+
+fn mtime_in(current_dir: &Path) -> Option<SystemTime> {
+    let colors_mtime = fs::metadata(colors_path_in(current_dir)).ok()?.modified().ok()?;
+    let name_mtime = fs::metadata(current_dir.join("theme.name"))
+        .ok()
+        .and_then(|m| m.modified().ok());
+
+    // Return the most recent modification time
+    match name_mtime {
+        Some(t) if t > colors_mtime => Some(t),
+        _ => Some(colors_mtime),
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var("HOME").ok().map(PathBuf::from)
+}
+
+fn colors_path(home: &Path) -> PathBuf {
+    colors_path_in(&home.join(CURRENT_DIR))
+}
+
+fn colors_path_in(current_dir: &Path) -> PathBuf {
+    current_dir.join("theme").join("colors.toml")
+}
+
+/// Detect Omarchy under `$HOME`. `None` when there is no readable theme.
+pub fn detect() -> Option<OmarchyEnv> {
+    detect_in(&home_dir()?)
+}
+
+/// Detect Omarchy under an explicit home directory.
+pub fn detect_in(home: &Path) -> Option<OmarchyEnv> {
+    let current_dir = home.join(CURRENT_DIR);
+    let colors = OmarchyColors::load_from(&colors_path_in(&current_dir))?;
+    let theme_name = fs::read_to_string(current_dir.join("theme.name"))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some(OmarchyEnv { current_dir, theme_name, colors })
+}
+
+/// `detect()`, unless the user turned the integration off in settings
+/// (`"theme.omarchy": false`).
+pub fn detect_enabled(enabled: bool) -> Option<OmarchyEnv> {
+    if enabled {
+        detect()
+    } else {
+        None
+    }
+}
+
+/// Apply the Omarchy palette to `theme` if running on Omarchy and the
+/// integration is `enabled` (`Settings::omarchy_enabled`).
+/// Returns whether the theme was changed.
+pub fn apply_theme(theme: &mut ThemeColors, enabled: bool) -> bool {
+    match detect_enabled(enabled) {
+        Some(env) => {
+            env.apply_theme(theme);
+            true
+        }
+        None => false,
+    }
+}
+
 /// Check if running on Omarchy (has theme config)
 pub fn is_omarchy() -> bool {
     OmarchyColors::config_path().is_some()
 }
 
 /// Load theme from Omarchy, falling back to default dark theme
-pub fn load_theme() -> ThemeColors {
-    OmarchyColors::load()
-        .map(|c| c.to_theme_colors())
+/// (also when the integration is not `enabled`)
+pub fn load_theme(enabled: bool) -> ThemeColors {
+    detect_enabled(enabled)
+        .map(|env| env.theme_colors())
         .unwrap_or_else(ThemeColors::dark)
 }
 
 /// Get current Omarchy theme name
 pub fn current_theme_name() -> Option<String> {
-    let home = std::env::var("HOME").ok()?;
-    let path = PathBuf::from(home)
-        .join(".config/omarchy/current/theme.name");
+    let path = home_dir()?.join(CURRENT_DIR).join("theme.name");
     fs::read_to_string(path).ok().map(|s| s.trim().to_string())
 }
 
 /// Get the modification time of the theme files
 pub fn theme_mtime() -> Option<SystemTime> {
-    let colors_path = OmarchyColors::config_path()?;
-    let colors_mtime = fs::metadata(&colors_path).ok()?.modified().ok()?;
+    mtime_in(&home_dir()?.join(CURRENT_DIR))
+}
 
-    // Also check theme.name file
-    let home = std::env::var("HOME").ok()?;
-    let name_path = PathBuf::from(home).join(".config/omarchy/current/theme.name");
-    let name_mtime = fs::metadata(&name_path).ok()?.modified().ok();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Return the most recent modification time
-    match name_mtime {
-        Some(t) if t > colors_mtime => Some(t),
-        _ => Some(colors_mtime),
+    const COLORS: &str = r##"
+accent = "#7aa2f7"
+cursor = "#c0caf5"
+foreground = "#c0caf5"
+background = "#1a1b26"
+selection_foreground = "#c0caf5"
+selection_background = "#7aa2f7"
+color0 = "#15161e"
+color1 = "#f7768e"
+color2 = "#9ece6a"
+color3 = "#e0af68"
+color4 = "#7aa2f7"
+color5 = "#bb9af7"
+color6 = "#7dcfff"
+color7 = "#a9b1d6"
+color8 = "#414868"
+color9 = "#f7768e"
+color10 = "#9ece6a"
+color11 = "#e0af68"
+color12 = "#7aa2f7"
+color13 = "#bb9af7"
+color14 = "#7dcfff"
+color15 = "#c0caf5"
+"##;
+
+    fn temp_home(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("visigrid-omarchy-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_detect_none_without_omarchy() {
+        let home = temp_home("none");
+        assert!(detect_in(&home).is_none());
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn test_detect_disabled_returns_none() {
+        assert!(detect_enabled(false).is_none());
+    }
+
+    #[test]
+    fn test_apply_theme_disabled_leaves_theme() {
+        let mut theme = ThemeColors::light();
+        assert!(!apply_theme(&mut theme, false));
+        assert_eq!(theme.bg_dark, ThemeColors::light().bg_dark);
+    }
+
+    #[test]
+    fn test_apply_theme_from_detected_palette() {
+        let home = temp_home("palette");
+        let current = home.join(CURRENT_DIR);
+        fs::create_dir_all(current.join("theme")).unwrap();
+        fs::write(current.join("theme/colors.toml"), COLORS).unwrap();
+        fs::write(current.join("theme.name"), "tokyo-night\n").unwrap();
+
+        let env = detect_in(&home).expect("omarchy theme detected");
+        assert_eq!(env.display_name(), "tokyo-night");
+        assert!(env.mtime().is_some());
+
+        let mut theme = ThemeColors::light();
+        env.apply_theme(&mut theme);
+        assert_eq!(theme.accent, ThemeColors::hex_to_color("#7aa2f7").unwrap());
+        assert_eq!(theme.bg_dark, ThemeColors::hex_to_color("#1a1b26").unwrap());
+
+        fs::remove_dir_all(&home).unwrap();
     }
 }
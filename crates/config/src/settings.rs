@@ -1,11 +1,20 @@
 // Application settings
 // Loaded from ~/.config/visigrid/settings.json
+//
+// Precedence: environment (VISIGRID_<SECTION>_<KEY>) > settings file > default.
+// e.g. VISIGRID_FILE_RECENT_FILES_LIMIT=5 overrides "file.recentFilesLimit",
+// VISIGRID_AI_PROVIDER=openai overrides "ai": { "provider" }.
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use crate::theme::ThemeSource;
 
+/// Prefix for environment variable overrides.
+pub const ENV_PREFIX: &str = "VISIGRID_";
+
 /// AI provider selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -13,7 +22,7 @@ pub enum AIProvider {
     /// AI features disabled (default)
     #[default]
     None,
-    /// Local model via Ollama
+    /// Local or self-hosted OpenAI-compatible server (Ollama, llama.cpp, LM Studio)
     Local,
     /// OpenAI API
     #[serde(rename = "openai")]
@@ -63,7 +72,7 @@ impl AIProvider {
         match self {
             AIProvider::None => ProviderCapabilities::none(),
             // TODO: Enable as providers are implemented
-            AIProvider::Local => ProviderCapabilities::none(),      // Phase 1: implement Ollama client
+            AIProvider::Local => ProviderCapabilities::insert_and_analyze(), // OpenAI-compatible endpoint
             AIProvider::OpenAI => ProviderCapabilities::insert_and_analyze(), // Implemented: Insert Formula + Analyze
             AIProvider::Anthropic => ProviderCapabilities::none(),  // Phase 1: implement Anthropic client
             AIProvider::Gemini => ProviderCapabilities::insert_and_analyze(), // OpenAI-compatible
@@ -72,7 +81,9 @@ impl AIProvider {
     }
 
     /// Returns the OpenAI-compatible chat completions endpoint for this provider.
-    /// Returns None for providers that don't use this API format.
+    /// Returns None for providers that don't use this API format, and for Local,
+    /// whose URL depends on the configured endpoint
+    /// (see `ResolvedAIConfig::chat_completions_url`).
     pub fn chat_completions_url(&self) -> Option<&'static str> {
         match self {
             AIProvider::OpenAI => Some("https://api.openai.com/v1/chat/completions"),
@@ -157,9 +168,13 @@ pub struct AISettings {
     /// Privacy mode: minimize data sent to AI
     pub privacy_mode: bool,
 
-    /// Custom endpoint for Local provider (Ollama URL)
+    /// Base URL of the Local provider's OpenAI-compatible server
     pub endpoint: Option<String>,
 
+    /// Health check path on the Local endpoint, pinged by `ai doctor --test`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_path: Option<String>,
+
     /// Allow AI to propose cell changes (gated feature)
     pub allow_proposals: bool,
 
@@ -179,6 +194,7 @@ impl Default for AISettings {
             model: String::new(), // Empty = use provider default
             privacy_mode: true,   // Privacy first
             endpoint: None,
+            health_path: None,
             allow_proposals: false, // Sidecar stance: no edits by default
             last_key_test: None,
             last_key_test_result: None,
@@ -200,6 +216,11 @@ impl AISettings {
     pub fn effective_endpoint(&self) -> &str {
         self.endpoint.as_deref().unwrap_or("http://localhost:11434")
     }
+
+    /// Get the effective health check path for Local provider
+    pub fn effective_health_path(&self) -> &str {
+        self.health_path.as_deref().unwrap_or("/v1/models")
+    }
 }
 
 /// Keyboard modifier style preference (primarily for macOS users)
@@ -265,6 +286,10 @@ pub struct Settings {
     #[serde(rename = "theme.source")]
     pub theme_source: ThemeSource,
 
+    /// Follow the Omarchy desktop theme when the source is Auto or System
+    #[serde(rename = "theme.omarchy", default = "default_true")]
+    pub omarchy_enabled: bool,
+
     // Keyboard
     #[serde(rename = "keyboard.modifierStyle")]
     pub modifier_style: ModifierStyle,
@@ -304,6 +329,7 @@ impl Default for Settings {
             vim_mode: false,
             // Theme
             theme_source: ThemeSource::Auto,
+            omarchy_enabled: true,
             // Keyboard
             modifier_style: ModifierStyle::default(),
             // Terminal
@@ -324,8 +350,19 @@ impl Settings {
         config_dir.join("ai_config.json")
     }
 
-    /// Load settings from disk, falling back to defaults
+    /// Load settings from disk with environment overrides applied.
+    /// Bad override values are reported and skipped.
     pub fn load() -> Self {
+        let mut settings = Self::load_file();
+        if let Err(e) = settings.apply_env_overrides(std::env::vars()) {
+            eprintln!("Ignoring invalid settings override:\n{}", e);
+        }
+        settings
+    }
+
+    /// Load settings from disk only, falling back to defaults.
+    /// Use this before `save()` so environment overrides aren't persisted.
+    pub fn load_file() -> Self {
         let path = Self::config_path();
 
         if !path.exists() {
@@ -359,6 +396,52 @@ impl Settings {
         }
     }
 
+    /// Layer `VISIGRID_<SECTION>_<KEY>` variables over these settings.
+    ///
+    /// Each value is coerced to the setting's current JSON type (number,
+    /// bool, string; enums by their settings-file name). Variables that
+    /// don't name a setting are ignored. Invalid values are skipped and
+    /// returned together as the error, one `NAME=value: reason` per line.
+    pub fn apply_env_overrides<I>(&mut self, vars: I) -> Result<(), String>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let vars: HashMap<String, String> = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect();
+        if vars.is_empty() {
+            return Ok(());
+        }
+
+        let mut tree = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        let mut errors = Vec::new();
+        for (path, name) in env_override_names(&tree) {
+            let Some(raw) = vars.get(&name) else { continue };
+            let mut candidate = tree.clone();
+            let slot = path.iter().fold(&mut candidate, |v, key| &mut v[key.as_str()]);
+            match coerce_env_value(slot, raw) {
+                Ok(value) => *slot = value,
+                Err(e) => {
+                    errors.push(format!("{}={}: {}", name, raw, e));
+                    continue;
+                }
+            }
+            // Validate the whole struct so enum names are checked too
+            match serde_json::from_value::<Settings>(candidate.clone()) {
+                Ok(_) => tree = candidate,
+                Err(e) => errors.push(format!("{}={}: {}", name, raw, e)),
+            }
+        }
+
+        *self = serde_json::from_value(tree).map_err(|e| e.to_string())?;
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
     /// Save current settings to disk
     pub fn save(&self) -> Result<(), String> {
         let path = Self::config_path();
@@ -409,6 +492,9 @@ impl Settings {
     "ui.showStatusBar": true,
     "ui.showSheetTabs": true,
 
+    // Theme: set "theme.omarchy" to false to ignore the Omarchy desktop theme
+    "theme.omarchy": true,
+
     // Keyboard (macOS only: "platform" = Cmd, "ctrl" = Ctrl)
     "keyboard.modifierStyle": "platform",
 
@@ -434,3 +520,124 @@ impl Settings {
         Self::config_path().to_string_lossy().to_string()
     }
 }
+
+/// Every overridable setting as (JSON path, env var name).
+///
+/// Top-level `section.key` entries and keys of nested sections (`ai`) both
+/// map to `VISIGRID_<SECTION>_<KEY>`, with camelCase split into words.
+fn env_override_names(tree: &Value) -> Vec<(Vec<String>, String)> {
+    let mut names = Vec::new();
+    let Some(top) = tree.as_object() else { return names };
+    for (key, value) in top {
+        match value.as_object() {
+            Some(section) => {
+                for sub in section.keys() {
+                    names.push((vec![key.clone(), sub.clone()], env_var_name(key, sub)));
+                }
+            }
+            None => {
+                let (section, name) = key.split_once('.').unwrap_or(("", key));
+                names.push((vec![key.clone()], env_var_name(section, name)));
+            }
+        }
+    }
+    names
+}
+
+/// `("grid", "defaultColumnWidth")` -> `VISIGRID_GRID_DEFAULT_COLUMN_WIDTH`
+fn env_var_name(section: &str, key: &str) -> String {
+    let mut name = String::from(ENV_PREFIX);
+    if !section.is_empty() {
+        name.push_str(&section.to_ascii_uppercase());
+        name.push('_');
+    }
+    for (i, c) in key.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            name.push('_');
+        }
+        name.push(if c == '.' || c == '-' { '_' } else { c.to_ascii_uppercase() });
+    }
+    name
+}
+
+/// Parse an env value into the JSON type the setting currently holds.
+fn coerce_env_value(current: &Value, raw: &str) -> Result<Value, String> {
+    let raw = raw.trim();
+    match current {
+        Value::Bool(_) => match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" => Ok(Value::Bool(true)),
+            "false" | "0" => Ok(Value::Bool(false)),
+            _ => Err("expected true or false".to_string()),
+        },
+        Value::Number(n) if n.is_u64() => raw.parse::<u64>().map(Value::from)
+            .map_err(|_| "expected a non-negative integer".to_string()),
+        Value::Number(n) if n.is_i64() => raw.parse::<i64>().map(Value::from)
+            .map_err(|_| "expected an integer".to_string()),
+        Value::Number(_) => raw.parse::<f64>().ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| "expected a number".to_string()),
+        Value::String(_) => Ok(Value::String(raw.to_string())),
+        // Unset optional setting: empty clears it, JSON scalars keep their type
+        Value::Null if raw.is_empty() => Ok(Value::Null),
+        Value::Null => Ok(serde_json::from_str::<Value>(raw)
+            .ok()
+            .filter(|v| v.is_number() || v.is_boolean())
+            .unwrap_or_else(|| Value::String(raw.to_string()))),
+        Value::Array(_) | Value::Object(_) => Err("not overridable from the environment".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_env_override_names() {
+        assert_eq!(env_var_name("grid", "defaultColumnWidth"), "VISIGRID_GRID_DEFAULT_COLUMN_WIDTH");
+        assert_eq!(env_var_name("ai", "privacy_mode"), "VISIGRID_AI_PRIVACY_MODE");
+    }
+
+    #[test]
+    fn test_env_overrides_numeric_and_enum() {
+        let mut settings = Settings::default();
+        settings.apply_env_overrides(vars(&[
+            ("VISIGRID_FILE_RECENT_FILES_LIMIT", "5"),
+            ("VISIGRID_GRID_ROW_HEIGHT", "30.5"),
+            ("VISIGRID_FILE_AUTO_SAVE_INTERVAL", "60"),
+            ("VISIGRID_KEYBOARD_MODIFIER_STYLE", "ctrl"),
+            ("VISIGRID_AI_PROVIDER", "openai"),
+            ("VISIGRID_SESSION_TOKEN", "not-a-setting"),
+        ]))
+        .unwrap();
+
+        assert_eq!(settings.recent_files_limit, 5);
+        assert_eq!(settings.row_height, 30.5);
+        assert_eq!(settings.auto_save_interval, Some(60));
+        assert_eq!(settings.modifier_style, ModifierStyle::Ctrl);
+        assert_eq!(settings.ai.provider, AIProvider::OpenAI);
+    }
+
+    #[test]
+    fn test_env_overrides_reject_bad_values() {
+        let mut settings = Settings::default();
+        let err = settings
+            .apply_env_overrides(vars(&[
+                ("VISIGRID_FILE_RECENT_FILES_LIMIT", "lots"),
+                ("VISIGRID_AI_PROVIDER", "skynet"),
+                ("VISIGRID_UI_SHOW_STATUS_BAR", "false"),
+            ]))
+            .unwrap_err();
+
+        assert!(err.contains("VISIGRID_FILE_RECENT_FILES_LIMIT=lots: expected a non-negative integer"), "{}", err);
+        assert!(err.contains("VISIGRID_AI_PROVIDER=skynet: unknown variant"), "{}", err);
+        // Bad values keep the file/default value; good ones still apply
+        assert_eq!(settings.recent_files_limit, 10);
+        assert_eq!(settings.ai.provider, AIProvider::None);
+        assert!(!settings.show_status_bar);
+    }
+}
@@ -203,17 +203,31 @@ pub struct ThemeManager {
     current: ThemeColors,
     current_name: String,
     omarchy_mtime: Option<std::time::SystemTime>,
+    omarchy_enabled: bool,
 }
 
 impl ThemeManager {
     /// Create a new theme manager with the given source
     pub fn new(source: ThemeSource) -> Self {
-        let (current, current_name, omarchy_mtime) = Self::load_theme(&source);
+        Self::with_omarchy(source, true)
+    }
+
+    /// Create a theme manager from the user's settings (`theme.source` and
+    /// `theme.omarchy`)
+    pub fn from_settings(settings: &crate::settings::Settings) -> Self {
+        Self::with_omarchy(settings.theme_source.clone(), settings.omarchy_enabled)
+    }
+
+    /// Create a theme manager, with the Omarchy integration on or off
+    /// (`Settings::omarchy_enabled`). When off, Auto and System use the dark theme.
+    pub fn with_omarchy(source: ThemeSource, omarchy_enabled: bool) -> Self {
+        let (current, current_name, omarchy_mtime) = Self::load_theme(&source, omarchy_enabled);
         ThemeManager {
             source,
             current,
             current_name,
             omarchy_mtime,
+            omarchy_enabled,
         }
     }
 
@@ -235,7 +249,7 @@ impl ThemeManager {
     /// Set theme source and reload
     pub fn set_source(&mut self, source: ThemeSource) {
         self.source = source;
-        let (theme, name, mtime) = Self::load_theme(&self.source);
+        let (theme, name, mtime) = Self::load_theme(&self.source, self.omarchy_enabled);
         self.current = theme;
         self.current_name = name;
         self.omarchy_mtime = mtime;
@@ -243,14 +257,14 @@ impl ThemeManager {
 
     /// Check if Omarchy theme changed and reload if needed
     pub fn check_omarchy_update(&mut self) -> bool {
-        if !matches!(self.source, ThemeSource::Auto | ThemeSource::System) {
+        if !self.omarchy_enabled || !matches!(self.source, ThemeSource::Auto | ThemeSource::System) {
             return false;
         }
 
         let current_mtime = crate::omarchy::theme_mtime();
         if current_mtime != self.omarchy_mtime {
             self.omarchy_mtime = current_mtime;
-            let (theme, name, _) = Self::load_theme(&self.source);
+            let (theme, name, _) = Self::load_theme(&self.source, self.omarchy_enabled);
             self.current = theme;
             self.current_name = name;
             true
@@ -261,39 +275,29 @@ impl ThemeManager {
 
     /// Reload current theme
     pub fn reload(&mut self) {
-        let (theme, name, mtime) = Self::load_theme(&self.source);
+        let (theme, name, mtime) = Self::load_theme(&self.source, self.omarchy_enabled);
         self.current = theme;
         self.current_name = name;
         self.omarchy_mtime = mtime;
     }
 
     /// Load theme from source
-    fn load_theme(source: &ThemeSource) -> (ThemeColors, String, Option<std::time::SystemTime>) {
+    fn load_theme(source: &ThemeSource, omarchy_enabled: bool) -> (ThemeColors, String, Option<std::time::SystemTime>) {
         match source {
             ThemeSource::Auto => {
                 // Try Omarchy first, then fall back to dark
-                if crate::omarchy::is_omarchy() {
-                    let theme = crate::omarchy::load_theme();
-                    let name = crate::omarchy::current_theme_name()
-                        .unwrap_or_else(|| "System".into());
-                    let mtime = crate::omarchy::theme_mtime();
-                    (theme, name, mtime)
-                } else {
-                    (ThemeColors::dark(), "Dark".into(), None)
+                match crate::omarchy::detect_enabled(omarchy_enabled) {
+                    Some(env) => (env.theme_colors(), env.display_name(), env.mtime()),
+                    None => (ThemeColors::dark(), "Dark".into(), None),
                 }
             }
             ThemeSource::Dark => (ThemeColors::dark(), "Dark".into(), None),
             ThemeSource::Light => (ThemeColors::light(), "Light".into(), None),
             ThemeSource::System => {
-                if crate::omarchy::is_omarchy() {
-                    let theme = crate::omarchy::load_theme();
-                    let name = crate::omarchy::current_theme_name()
-                        .unwrap_or_else(|| "System".into());
-                    let mtime = crate::omarchy::theme_mtime();
-                    (theme, name, mtime)
-                } else {
+                match crate::omarchy::detect_enabled(omarchy_enabled) {
+                    Some(env) => (env.theme_colors(), env.display_name(), env.mtime()),
                     // Fall back to dark if no system theme
-                    (ThemeColors::dark(), "Dark (no system theme)".into(), None)
+                    None => (ThemeColors::dark(), "Dark (no system theme)".into(), None),
                 }
             }
             ThemeSource::Custom(path) => {
@@ -414,6 +418,18 @@ mod tests {
         assert!((color.b - 0.965).abs() < 0.01);
     }
 
+    #[test]
+    fn test_from_settings_with_omarchy_off_uses_dark() {
+        let settings = crate::settings::Settings {
+            theme_source: ThemeSource::Auto,
+            omarchy_enabled: false,
+            ..Default::default()
+        };
+        let manager = ThemeManager::from_settings(&settings);
+        assert_eq!(manager.name(), "Dark");
+        assert_eq!(manager.theme().bg_dark, ThemeColors::dark().bg_dark);
+    }
+
     #[test]
     fn test_dark_theme() {
         let theme = ThemeColors::dark();
@@ -459,10 +459,64 @@ pub enum CellValue {
     Empty,
     Text(String),
     Number(f64),
+    Boolean(bool),
+    Error(ErrKind),
     #[serde(skip)]
     Formula { source: String, ast: Option<ParsedExpr> },
 }
 
+/// Error codes a cell can hold as a literal value (e.g. `#N/A` typed in or
+/// imported), as opposed to an error produced by evaluating a formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrKind {
+    Null,
+    Div0,
+    Value,
+    Ref,
+    Name,
+    Num,
+    Na,
+    Spill,
+    Calc,
+    Cycle,
+}
+
+impl ErrKind {
+    const ALL: [ErrKind; 10] = [
+        ErrKind::Null,
+        ErrKind::Div0,
+        ErrKind::Value,
+        ErrKind::Ref,
+        ErrKind::Name,
+        ErrKind::Num,
+        ErrKind::Na,
+        ErrKind::Spill,
+        ErrKind::Calc,
+        ErrKind::Cycle,
+    ];
+
+    /// The error code as shown in a cell (`#DIV/0!`, `#N/A`, ...).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrKind::Null => "#NULL!",
+            ErrKind::Div0 => "#DIV/0!",
+            ErrKind::Value => "#VALUE!",
+            ErrKind::Ref => "#REF!",
+            ErrKind::Name => "#NAME?",
+            ErrKind::Num => "#NUM!",
+            ErrKind::Na => "#N/A",
+            ErrKind::Spill => "#SPILL!",
+            ErrKind::Calc => "#CALC!",
+            ErrKind::Cycle => "#CYCLE!",
+        }
+    }
+
+    /// Parse an exact error code. Anything else (including `#hashtag`) is `None`.
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == s)
+    }
+}
+
 impl Default for CellValue {
     fn default() -> Self {
         CellValue::Empty
@@ -581,6 +635,11 @@ pub fn serial_1904_to_1900(serial_1904: f64) -> f64 {
     serial_1904 + 1462.0
 }
 
+/// TRUE/FALSE as shown in a cell.
+pub fn bool_display(b: bool) -> &'static str {
+    if b { "TRUE" } else { "FALSE" }
+}
+
 /// Format an f64 for display/editing with limited significant digits.
 ///
 /// Float64 has ~15-17 significant decimal digits of precision. We limit
@@ -1068,6 +1127,17 @@ impl CellValue {
             return CellValue::Number(num);
         }
 
+        // Only the canonical spellings are typed; "true" or "#n/a" stay text
+        // so lowercase CSV values round-trip unchanged.
+        match trimmed {
+            "TRUE" => return CellValue::Boolean(true),
+            "FALSE" => return CellValue::Boolean(false),
+            _ => {}
+        }
+        if let Some(kind) = ErrKind::parse(trimmed) {
+            return CellValue::Error(kind);
+        }
+
         CellValue::Text(trimmed.to_string())
     }
 
@@ -1076,13 +1146,15 @@ impl CellValue {
             CellValue::Empty => String::new(),
             CellValue::Text(s) => s.clone(),
             CellValue::Number(n) => format_f64_display(*n),
+            CellValue::Boolean(b) => bool_display(*b).to_string(),
+            CellValue::Error(kind) => kind.as_str().to_string(),
             CellValue::Formula { source, .. } => source.clone(),
         }
     }
 
     /// Check if this cell contains a cycle error (#CYCLE!).
     pub fn is_cycle_error(&self) -> bool {
-        matches!(self, CellValue::Text(s) if s == "#CYCLE!")
+        matches!(self, CellValue::Error(ErrKind::Cycle))
     }
 
     /// Format a number according to the specified format
@@ -1153,6 +1225,8 @@ impl CellValue {
             CellValue::Empty => String::new(),
             CellValue::Text(s) => s.clone(),
             CellValue::Number(n) => Self::format_number(*n, &format.number_format),
+            CellValue::Boolean(b) => bool_display(*b).to_string(),
+            CellValue::Error(kind) => kind.as_str().to_string(),
             CellValue::Formula { source, .. } => source.clone(),
         }
     }
@@ -1161,6 +1235,7 @@ impl CellValue {
         match self {
             CellValue::Number(n) => *n,
             CellValue::Text(s) => s.parse().unwrap_or(0.0),
+            CellValue::Boolean(b) => if *b { 1.0 } else { 0.0 },
             _ => 0.0,
         }
     }
@@ -2007,4 +2082,19 @@ mod tests {
         }
         assert!(matches!(CellValue::from_input("ACME, Inc."), CellValue::Text(_)));
     }
+
+    #[test]
+    fn test_from_input_booleans_and_errors() {
+        assert!(matches!(CellValue::from_input("TRUE"), CellValue::Boolean(true)));
+        assert!(matches!(CellValue::from_input(" FALSE "), CellValue::Boolean(false)));
+        assert!(matches!(CellValue::from_input("#N/A"), CellValue::Error(ErrKind::Na)));
+        assert!(matches!(CellValue::from_input("#DIV/0!"), CellValue::Error(ErrKind::Div0)));
+        // Non-canonical spellings stay text
+        assert!(matches!(CellValue::from_input("true"), CellValue::Text(_)));
+        assert!(matches!(CellValue::from_input("#hashtag"), CellValue::Text(_)));
+
+        assert_eq!(CellValue::Boolean(true).raw_display(), "TRUE");
+        assert_eq!(CellValue::Error(ErrKind::Ref).raw_display(), "#REF!");
+        assert!(CellValue::Error(ErrKind::Cycle).is_cycle_error());
+    }
 }
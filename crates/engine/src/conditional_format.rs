@@ -0,0 +1,277 @@
+//! Conditional formatting rules
+//!
+//! A rule restyles the cells of a range based on their values: a comparison
+//! ("highlight if < 0") applies a font/fill style to the cells that pass it,
+//! and a color scale shades each cell between two or three colored stops.
+//!
+//! Presentation only — not part of the semantic fingerprint. Rules are
+//! persisted in the .sheet file and never change a cell's value. The grid
+//! doesn't render them yet; they round-trip through import, export, and
+//! `sheet inspect`.
+
+use serde::{Deserialize, Serialize};
+
+/// A conditional format: one rule applied to a rectangular range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalFormat {
+    /// Top-left corner (row, col)
+    pub start: (usize, usize),
+    /// Bottom-right corner (row, col)
+    pub end: (usize, usize),
+    pub rule: ConditionalRule,
+}
+
+impl ConditionalFormat {
+    pub fn new(start_row: usize, start_col: usize, end_row: usize, end_col: usize, rule: ConditionalRule) -> Self {
+        Self {
+            start: (start_row.min(end_row), start_col.min(end_col)),
+            end: (start_row.max(end_row), start_col.max(end_col)),
+            rule,
+        }
+    }
+
+    /// Whether (row, col) is inside this rule's range
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        row >= self.start.0 && row <= self.end.0 && col >= self.start.1 && col <= self.end.1
+    }
+}
+
+/// What a conditional format checks and how it styles matching cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConditionalRule {
+    /// Apply `style` to cells whose value compares true against `values`
+    /// (one operand, or two for between/not_between). Operands are kept as
+    /// formula text, e.g. `0`, `$B$1`, or `"Closed"`.
+    Compare {
+        operator: CompareOperator,
+        values: Vec<String>,
+        style: ConditionalStyle,
+    },
+    /// Shade cell backgrounds along two or three stops, lowest first.
+    ColorScale { stops: Vec<ColorStop> },
+}
+
+impl ConditionalRule {
+    /// Short name of the rule kind, as used in JSON (`compare`, `color_scale`).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConditionalRule::Compare { .. } => "compare",
+            ConditionalRule::ColorScale { .. } => "color_scale",
+        }
+    }
+
+    /// A color scale from the range's lowest to highest value. Three colors
+    /// put the middle stop at the 50th percentile, as Excel does.
+    pub fn color_scale(colors: &[[u8; 4]]) -> Option<Self> {
+        let stops = match colors {
+            [min, max] => vec![ColorStop::min(*min), ColorStop::max(*max)],
+            [min, mid, max] => vec![
+                ColorStop::min(*min),
+                ColorStop { kind: StopKind::Percentile, value: Some("50".to_string()), color: *mid },
+                ColorStop::max(*max),
+            ],
+            _ => return None,
+        };
+        Some(ConditionalRule::ColorScale { stops })
+    }
+}
+
+/// Comparison used by a [`ConditionalRule::Compare`] rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareOperator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Between,
+    NotBetween,
+}
+
+impl CompareOperator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompareOperator::Equal => "equal",
+            CompareOperator::NotEqual => "not_equal",
+            CompareOperator::GreaterThan => "greater_than",
+            CompareOperator::GreaterThanOrEqual => "greater_than_or_equal",
+            CompareOperator::LessThan => "less_than",
+            CompareOperator::LessThanOrEqual => "less_than_or_equal",
+            CompareOperator::Between => "between",
+            CompareOperator::NotBetween => "not_between",
+        }
+    }
+
+    /// Parse the snake_case name used in JSON and ops.
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "equal" => CompareOperator::Equal,
+            "not_equal" => CompareOperator::NotEqual,
+            "greater_than" => CompareOperator::GreaterThan,
+            "greater_than_or_equal" => CompareOperator::GreaterThanOrEqual,
+            "less_than" => CompareOperator::LessThan,
+            "less_than_or_equal" => CompareOperator::LessThanOrEqual,
+            "between" => CompareOperator::Between,
+            "not_between" => CompareOperator::NotBetween,
+            _ => return None,
+        })
+    }
+
+    /// Number of operands the comparison takes.
+    pub fn operand_count(&self) -> usize {
+        match self {
+            CompareOperator::Between | CompareOperator::NotBetween => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Style applied to cells that pass a comparison. Unset fields leave the
+/// cell's own format alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConditionalStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "hex_color_opt")]
+    pub font_color: Option<[u8; 4]>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "hex_color_opt")]
+    pub background_color: Option<[u8; 4]>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+}
+
+/// One stop of a color scale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColorStop {
+    pub kind: StopKind,
+    /// Threshold for number/percent/percentile/formula stops
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(with = "hex_color")]
+    pub color: [u8; 4],
+}
+
+impl ColorStop {
+    pub fn min(color: [u8; 4]) -> Self {
+        ColorStop { kind: StopKind::Min, value: None, color }
+    }
+
+    pub fn max(color: [u8; 4]) -> Self {
+        ColorStop { kind: StopKind::Max, value: None, color }
+    }
+}
+
+/// Where a color-scale stop sits in the range's values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopKind {
+    Min,
+    Max,
+    Number,
+    Percent,
+    Percentile,
+    Formula,
+}
+
+/// Colors as `#RRGGBB`, or `#RRGGBBAA` when not fully opaque.
+pub fn color_to_hex(c: [u8; 4]) -> String {
+    if c[3] == 255 {
+        format!("#{:02X}{:02X}{:02X}", c[0], c[1], c[2])
+    } else {
+        format!("#{:02X}{:02X}{:02X}{:02X}", c[0], c[1], c[2], c[3])
+    }
+}
+
+/// Parse `#RRGGBB` or `#RRGGBBAA` (the `#` is optional).
+pub fn color_from_hex(s: &str) -> Option<[u8; 4]> {
+    let s = s.trim().trim_start_matches('#');
+    if !s.is_ascii() || (s.len() != 6 && s.len() != 8) {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&s[i..i + 2], 16).ok();
+    let alpha = if s.len() == 8 { byte(6)? } else { 255 };
+    Some([byte(0)?, byte(2)?, byte(4)?, alpha])
+}
+
+mod hex_color {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(c: &[u8; 4], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&super::color_to_hex(*c))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<[u8; 4], D::Error> {
+        let s = String::deserialize(d)?;
+        super::color_from_hex(&s).ok_or_else(|| serde::de::Error::custom(format!("invalid color {:?}", s)))
+    }
+}
+
+mod hex_color_opt {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(c: &Option<[u8; 4]>, s: S) -> Result<S::Ok, S::Error> {
+        match c {
+            Some(c) => super::hex_color::serialize(c, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<[u8; 4]>, D::Error> {
+        match Option::<String>::deserialize(d)? {
+            Some(s) => super::color_from_hex(&s)
+                .map(Some)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid color {:?}", s))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_json_uses_hex_colors() {
+        let rule = ConditionalRule::Compare {
+            operator: CompareOperator::LessThan,
+            values: vec!["0".to_string()],
+            style: ConditionalStyle { font_color: Some([0x9C, 0, 0x06, 255]), ..Default::default() },
+        };
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(
+            json,
+            r##"{"type":"compare","operator":"less_than","values":["0"],"style":{"font_color":"#9C0006"}}"##
+        );
+        assert_eq!(serde_json::from_str::<ConditionalRule>(&json).unwrap(), rule);
+    }
+
+    #[test]
+    fn test_color_scale_stops() {
+        let red = [0xF8, 0x69, 0x6B, 255];
+        let green = [0x63, 0xBE, 0x7B, 255];
+        let Some(ConditionalRule::ColorScale { stops }) = ConditionalRule::color_scale(&[red, green]) else {
+            panic!("expected a color scale");
+        };
+        assert_eq!(stops, vec![ColorStop::min(red), ColorStop::max(green)]);
+        assert!(ConditionalRule::color_scale(&[red]).is_none());
+    }
+
+    #[test]
+    fn test_hex_color_parsing() {
+        assert_eq!(color_from_hex("#FF0000"), Some([255, 0, 0, 255]));
+        assert_eq!(color_from_hex("00ff0080"), Some([0, 255, 0, 128]));
+        assert_eq!(color_from_hex("#F00"), None);
+        assert_eq!(color_to_hex([0, 255, 0, 128]), "#00FF0080");
+    }
+
+    #[test]
+    fn test_range_contains() {
+        let cf = ConditionalFormat::new(9, 2, 1, 1, ConditionalRule::color_scale(&[[0; 4], [255; 4]]).unwrap());
+        assert_eq!((cf.start, cf.end), ((1, 1), (9, 2)));
+        assert!(cf.contains(5, 2));
+        assert!(!cf.contains(0, 1));
+    }
+}
@@ -65,6 +65,43 @@ impl DepGraph {
             .flat_map(|s| s.iter().copied())
     }
 
+    /// Returns every cell upstream of this cell: its precedents, their
+    /// precedents, and so on. Excludes the cell itself (even in a cycle).
+    ///
+    /// Sorted by (sheet, row, col) for deterministic output.
+    pub fn transitive_precedents(&self, cell: CellId) -> Vec<CellId> {
+        Self::reachable(&self.preds, cell)
+    }
+
+    /// Returns every cell downstream of this cell — everything that would
+    /// recompute if it changed. Excludes the cell itself (even in a cycle).
+    ///
+    /// Sorted by (sheet, row, col) for deterministic output.
+    pub fn transitive_dependents(&self, cell: CellId) -> Vec<CellId> {
+        Self::reachable(&self.succs, cell)
+    }
+
+    /// DFS over one edge direction, collecting all reachable cells.
+    fn reachable(edges: &FxHashMap<CellId, FxHashSet<CellId>>, start: CellId) -> Vec<CellId> {
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![start];
+
+        while let Some(current) = stack.pop() {
+            if let Some(next) = edges.get(&current) {
+                for &n in next {
+                    if visited.insert(n) {
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+
+        visited.remove(&start);
+        let mut cells: Vec<CellId> = visited.into_iter().collect();
+        cells.sort_by_key(|c| (c.sheet.raw(), c.row, c.col));
+        cells
+    }
+
     /// Number of precedents for a cell. O(1) — reads FxHashSet::len().
     pub fn precedent_count(&self, cell: CellId) -> usize {
         self.preds.get(&cell).map_or(0, |s| s.len())
@@ -738,6 +775,46 @@ mod tests {
         graph.assert_consistent();
     }
 
+    #[test]
+    fn test_transitive_queries_follow_chain() {
+        // A1 -> B1 -> C1 (B1 = A1, C1 = B1), plus an unrelated D1 = A2
+        let mut graph = DepGraph::new();
+        let a1 = cell(1, 0, 0);
+        let b1 = cell(1, 0, 1);
+        let c1 = cell(1, 0, 2);
+        let a2 = cell(1, 1, 0);
+        let d1 = cell(1, 0, 3);
+
+        graph.replace_edges(b1, set(&[a1]));
+        graph.replace_edges(c1, set(&[b1]));
+        graph.replace_edges(d1, set(&[a2]));
+
+        assert_eq!(graph.transitive_dependents(a1), vec![b1, c1]);
+        assert_eq!(graph.transitive_dependents(b1), vec![c1]);
+        assert!(graph.transitive_dependents(c1).is_empty());
+
+        assert_eq!(graph.transitive_precedents(c1), vec![a1, b1]);
+        assert_eq!(graph.transitive_precedents(b1), vec![a1]);
+        assert!(graph.transitive_precedents(a1).is_empty());
+
+        // Direct queries still return one hop only
+        assert_eq!(graph.dependents(a1).collect::<Vec<_>>(), vec![b1]);
+    }
+
+    #[test]
+    fn test_transitive_queries_terminate_on_cycle() {
+        // A1 = B1, B1 = A1
+        let mut graph = DepGraph::new();
+        let a1 = cell(1, 0, 0);
+        let b1 = cell(1, 0, 1);
+
+        graph.replace_edges(a1, set(&[b1]));
+        graph.replace_edges(b1, set(&[a1]));
+
+        assert_eq!(graph.transitive_dependents(a1), vec![b1]);
+        assert_eq!(graph.transitive_precedents(a1), vec![b1]);
+    }
+
     #[test]
     fn test_single_edge() {
         // B1 = A1
@@ -3,6 +3,11 @@
 //! These events enable the session server to notify clients of changes
 //! without polling. They're also used by the test harness to verify
 //! invariants about event ordering and revision boundaries.
+//!
+//! Embedders that don't run the session server can observe edits directly
+//! with `Workbook::subscribe()`, which returns a channel of `CellChange`s.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
 
 use crate::cell_id::CellId;
 
@@ -63,6 +68,66 @@ pub struct RevisionChangedEvent {
     pub previous: u64,
 }
 
+/// A single cell edit, delivered to `Workbook::subscribe()` receivers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellChange {
+    /// Revision that includes this edit. In manual calculation mode the
+    /// revision does not advance, so this is the revision at edit time.
+    pub revision: u64,
+    /// The edited cell.
+    pub cell: CellId,
+}
+
+/// Fan-out of `CellChange`s to channel subscribers.
+///
+/// Receivers that have been dropped are pruned on the next publish. Cloning
+/// yields an empty set: a cloned workbook is a separate document and its
+/// edits are not reported to the original's subscribers.
+#[derive(Default)]
+pub struct Subscribers {
+    senders: Vec<Sender<CellChange>>,
+}
+
+impl Subscribers {
+    /// Register a new subscriber.
+    pub fn subscribe(&mut self) -> Receiver<CellChange> {
+        let (tx, rx) = channel();
+        self.senders.push(tx);
+        rx
+    }
+
+    /// Send one `CellChange` per cell, in order, to every live subscriber.
+    pub fn publish(&mut self, revision: u64, cells: &[CellId]) {
+        if self.senders.is_empty() {
+            return;
+        }
+        self.senders.retain(|tx| {
+            cells.iter().all(|&cell| tx.send(CellChange { revision, cell }).is_ok())
+        });
+    }
+
+    /// Number of registered subscribers (including not-yet-pruned dropped ones).
+    pub fn len(&self) -> usize {
+        self.senders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+    }
+}
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl std::fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscribers").field("count", &self.senders.len()).finish()
+    }
+}
+
 /// Callback type for receiving workbook events.
 pub type EventCallback = Box<dyn FnMut(WorkbookEvent) + Send>;
 
@@ -160,4 +225,18 @@ mod tests {
         assert_eq!(collector.cells_changed().len(), 1);
         assert_eq!(collector.revision_changed().len(), 1);
     }
+
+    #[test]
+    fn test_subscribers_prune_dropped_receivers() {
+        let mut subs = Subscribers::default();
+        let kept = subs.subscribe();
+        drop(subs.subscribe());
+        assert_eq!(subs.len(), 2);
+
+        let cell = CellId::new(SheetId(1), 0, 0);
+        subs.publish(1, &[cell]);
+        assert_eq!(subs.len(), 1);
+        assert_eq!(kept.try_recv(), Ok(CellChange { revision: 1, cell }));
+        assert!(subs.clone().is_empty(), "clones start without subscribers");
+    }
 }
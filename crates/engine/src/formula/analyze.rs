@@ -52,11 +52,20 @@ fn walk_expr<S, F: FnMut(&str)>(expr: &Expr<S>, visitor: &mut F) {
             walk_expr(left, visitor);
             walk_expr(right, visitor);
         }
+        Expr::Array(rows) => {
+            for element in rows.iter().flatten() {
+                walk_expr(element, visitor);
+            }
+        }
+        Expr::ImplicitIntersection(inner) => {
+            walk_expr(inner, visitor);
+        }
         // Leaf nodes - no functions to visit
         Expr::Number(_) |
         Expr::Text(_) |
         Expr::Boolean(_) |
         Expr::CellRef { .. } |
+        Expr::SpillRef { .. } |
         Expr::Range { .. } |
         Expr::NamedRange(_) |
         Expr::Empty => {}
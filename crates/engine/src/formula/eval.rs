@@ -2,6 +2,7 @@
 
 use crate::sheet::{SheetId, SheetRef};
 use super::parser::{BoundExpr, Expr, Op};
+use super::rounding::RoundMode;
 
 /// Result of resolving a named range
 #[derive(Debug, Clone)]
@@ -56,6 +57,17 @@ pub trait CellLookup {
         None
     }
 
+    /// Dimensions (rows, cols) of the array spilled from an anchor cell on the
+    /// current sheet. Returns None if the cell is not a spill anchor.
+    fn get_spill_extent(&self, _row: usize, _col: usize) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Dimensions of the array spilled from an anchor cell on another sheet.
+    fn get_spill_extent_sheet(&self, _sheet_id: SheetId, _row: usize, _col: usize) -> Option<(usize, usize)> {
+        None
+    }
+
     /// Get a cell's typed value from the current sheet.
     /// Returns Value::Empty for empty/missing cells.
     /// Default: falls back to get_text() + parse (backward compat).
@@ -84,6 +96,12 @@ pub trait CellLookup {
     fn try_custom_function(&self, _name: &str, _args: &[EvalArg]) -> Option<EvalResult> {
         None
     }
+
+    /// How ROUND and MROUND break ties.
+    /// Default: half up, like Excel.
+    fn round_mode(&self) -> RoundMode {
+        RoundMode::HalfUp
+    }
 }
 
 /// A lookup that wraps another CellLookup and adds named range resolution
@@ -131,6 +149,14 @@ impl<'a, L: CellLookup, F: Fn(&str) -> Option<NamedRangeResolution>> CellLookup
         self.inner.get_merge_start_sheet(sheet_id, row, col)
     }
 
+    fn get_spill_extent(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        self.inner.get_spill_extent(row, col)
+    }
+
+    fn get_spill_extent_sheet(&self, sheet_id: SheetId, row: usize, col: usize) -> Option<(usize, usize)> {
+        self.inner.get_spill_extent_sheet(sheet_id, row, col)
+    }
+
     fn get_cell_value(&self, row: usize, col: usize) -> Value {
         self.inner.get_cell_value(row, col)
     }
@@ -138,6 +164,10 @@ impl<'a, L: CellLookup, F: Fn(&str) -> Option<NamedRangeResolution>> CellLookup
     fn try_custom_function(&self, name: &str, args: &[EvalArg]) -> Option<EvalResult> {
         self.inner.try_custom_function(name, args)
     }
+
+    fn round_mode(&self) -> RoundMode {
+        self.inner.round_mode()
+    }
 }
 
 /// A lookup wrapper that provides current cell context for ROW()/COLUMN()
@@ -186,6 +216,14 @@ impl<'a, L: CellLookup> CellLookup for LookupWithContext<'a, L> {
         self.inner.get_merge_start_sheet(sheet_id, row, col)
     }
 
+    fn get_spill_extent(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        self.inner.get_spill_extent(row, col)
+    }
+
+    fn get_spill_extent_sheet(&self, sheet_id: SheetId, row: usize, col: usize) -> Option<(usize, usize)> {
+        self.inner.get_spill_extent_sheet(sheet_id, row, col)
+    }
+
     fn get_cell_value(&self, row: usize, col: usize) -> Value {
         self.inner.get_cell_value(row, col)
     }
@@ -193,6 +231,10 @@ impl<'a, L: CellLookup> CellLookup for LookupWithContext<'a, L> {
     fn try_custom_function(&self, name: &str, args: &[EvalArg]) -> Option<EvalResult> {
         self.inner.try_custom_function(name, args)
     }
+
+    fn round_mode(&self) -> RoundMode {
+        self.inner.round_mode()
+    }
 }
 
 // =============================================================================
@@ -529,18 +571,25 @@ pub fn evaluate<L: CellLookup>(expr: &BoundExpr, lookup: &L) -> EvalResult {
         Expr::Number(n) => EvalResult::Number(*n),
         Expr::Text(s) => EvalResult::Text(s.clone()),
         Expr::Boolean(b) => EvalResult::Boolean(*b),
+        Expr::Array(rows) => {
+            // Array constant: elements are literals, so evaluation cannot fail
+            let data = rows.iter()
+                .map(|row| row.iter().map(|el| evaluate(el, lookup).to_value()).collect())
+                .collect();
+            EvalResult::Array(Array2D::from_vec(data))
+        }
         Expr::CellRef { sheet, col, row, .. } => {
             // Get cell value, potentially from another sheet.
             // Redirect hidden merge cells to origin so =B1 returns the
             // merge origin's value when B1 is hidden inside a merge.
-            let text = match sheet {
+            let (text, at) = match sheet {
                 SheetRef::Current => {
                     let (r, c) = lookup.get_merge_start(*row, *col).unwrap_or((*row, *col));
-                    lookup.get_text(r, c)
+                    (lookup.get_text(r, c), (None, r, c))
                 }
                 SheetRef::Id(sheet_id) => {
                     let (r, c) = lookup.get_merge_start_sheet(*sheet_id, *row, *col).unwrap_or((*row, *col));
-                    lookup.get_text_sheet(*sheet_id, r, c)
+                    (lookup.get_text_sheet(*sheet_id, r, c), (Some(*sheet_id), r, c))
                 }
                 SheetRef::RefError { .. } => return EvalResult::Error("#REF!".to_string()),
             };
@@ -551,10 +600,16 @@ pub fn evaluate<L: CellLookup>(expr: &BoundExpr, lookup: &L) -> EvalResult {
                 EvalResult::Error(text)
             } else if let Ok(n) = text.parse::<f64>() {
                 EvalResult::Number(n)
-            } else if text.to_uppercase() == "TRUE" {
-                EvalResult::Boolean(true)
-            } else if text.to_uppercase() == "FALSE" {
-                EvalResult::Boolean(false)
+            } else if text.eq_ignore_ascii_case("TRUE") || text.eq_ignore_ascii_case("FALSE") {
+                // Ask for the typed value: a text cell reading "TRUE" stays text
+                let typed = match at {
+                    (None, r, c) => lookup.get_cell_value(r, c),
+                    (Some(sheet_id), r, c) => lookup.get_value_sheet(sheet_id, r, c),
+                };
+                match typed {
+                    Value::Boolean(b) => EvalResult::Boolean(b),
+                    _ => EvalResult::Text(text),
+                }
             } else {
                 EvalResult::Text(text)
             }
@@ -563,6 +618,44 @@ pub fn evaluate<L: CellLookup>(expr: &BoundExpr, lookup: &L) -> EvalResult {
             // Ranges can't be evaluated directly, only within functions
             EvalResult::Error("#VALUE! Array arithmetic not supported".to_string())
         }
+        Expr::SpillRef { sheet, col, row, .. } => {
+            let extent = match sheet {
+                SheetRef::Current => lookup.get_spill_extent(*row, *col),
+                SheetRef::Id(sheet_id) => lookup.get_spill_extent_sheet(*sheet_id, *row, *col),
+                SheetRef::RefError { .. } => return EvalResult::Error("#REF!".to_string()),
+            };
+            // Referencing a cell that does not spill is a #REF! in Excel too
+            let Some((rows, cols)) = extent else {
+                return EvalResult::Error("#REF!".to_string());
+            };
+            let data = (0..rows)
+                .map(|r| (0..cols)
+                    .map(|c| evaluate(&cell_ref(sheet, row + r, col + c), lookup).to_value())
+                    .collect())
+                .collect();
+            EvalResult::Array(Array2D::from_vec(data))
+        }
+        Expr::ImplicitIntersection(inner) => match inner.as_ref() {
+            Expr::Range { sheet, start_col, start_row, end_col, end_row, .. } => {
+                // Pick the cell in the formula's row (single column) or column (single row)
+                let (r, c) = match lookup.current_cell() {
+                    _ if start_row == end_row && start_col == end_col => (*start_row, *start_col),
+                    Some((cur_row, _)) if start_col == end_col && (*start_row..=*end_row).contains(&cur_row) => {
+                        (cur_row, *start_col)
+                    }
+                    Some((_, cur_col)) if start_row == end_row && (*start_col..=*end_col).contains(&cur_col) => {
+                        (*start_row, cur_col)
+                    }
+                    _ => return EvalResult::Error("#VALUE!".to_string()),
+                };
+                evaluate(&cell_ref(sheet, r, c), lookup)
+            }
+            _ => match evaluate(inner, lookup) {
+                // Arrays (spill refs, array-returning functions) collapse to their first element
+                EvalResult::Array(arr) => EvalResult::from_value(&arr.top_left()),
+                other => other,
+            },
+        },
         Expr::NamedRange(name) => {
             // Resolve the named range and evaluate
             match lookup.resolve_named_range(name) {
@@ -693,6 +786,17 @@ pub fn evaluate<L: CellLookup>(expr: &BoundExpr, lookup: &L) -> EvalResult {
                                 _ => return EvalResult::Error("Cannot compare booleans with < > <= >=".to_string()),
                             }
                         }
+                        // Booleans never equal text and sort after it, as in Excel
+                        (EvalResult::Boolean(_), EvalResult::Text(_)) | (EvalResult::Text(_), EvalResult::Boolean(_)) => {
+                            let left_is_bool = matches!(left_result, EvalResult::Boolean(_));
+                            match op {
+                                Op::Lt | Op::LtEq => !left_is_bool,
+                                Op::Gt | Op::GtEq => left_is_bool,
+                                Op::Eq => false,
+                                Op::NotEq => true,
+                                _ => unreachable!(),
+                            }
+                        }
                         // Mixed type comparisons - convert to common type
                         _ => {
                             // Try numeric comparison first
@@ -738,6 +842,11 @@ pub fn evaluate<L: CellLookup>(expr: &BoundExpr, lookup: &L) -> EvalResult {
 
 
 /// Evaluate function arguments into typed EvalArg values for custom function hooks.
+/// A relative single-cell reference, for evaluating cells inside a range or spill.
+fn cell_ref(sheet: &SheetRef, row: usize, col: usize) -> BoundExpr {
+    Expr::CellRef { sheet: sheet.clone(), col, row, col_abs: false, row_abs: false }
+}
+
 fn eval_function_args<L: CellLookup>(args: &[BoundExpr], lookup: &L) -> Vec<EvalArg> {
     args.iter().map(|arg| {
         match arg {
@@ -802,6 +911,7 @@ mod tests {
     struct TestLookup {
         cells: [[String; 10]; 10],
         named_ranges: std::collections::HashMap<String, NamedRangeResolution>,
+        round_mode: RoundMode,
     }
 
     impl TestLookup {
@@ -809,6 +919,7 @@ mod tests {
             Self {
                 cells: Default::default(),
                 named_ranges: std::collections::HashMap::new(),
+                round_mode: RoundMode::HalfUp,
             }
         }
 
@@ -843,6 +954,10 @@ mod tests {
         fn resolve_named_range(&self, name: &str) -> Option<NamedRangeResolution> {
             self.named_ranges.get(&name.to_lowercase()).cloned()
         }
+
+        fn round_mode(&self) -> RoundMode {
+            self.round_mode
+        }
     }
 
     #[test]
@@ -1420,6 +1535,38 @@ mod tests {
         assert_eq!(result, EvalResult::Number(100.0));
     }
 
+    #[test]
+    fn test_iferror_catches_div_zero_ifna_does_not() {
+        let mut lookup = TestLookup::new();
+        lookup.set(0, 0, "10");
+        lookup.set(0, 1, "0");
+
+        let expr = parse_and_bind(r#"=IFERROR(A1/B1, "n/a")"#);
+        assert_eq!(evaluate(&expr, &lookup), EvalResult::Text("n/a".to_string()));
+
+        // IFNA only catches #N/A; #DIV/0! passes through
+        let expr = parse_and_bind(r#"=IFNA(A1/B1, "n/a")"#);
+        assert_eq!(evaluate(&expr, &lookup), EvalResult::Error("#DIV/0!".to_string()));
+
+        // IFERROR also catches #N/A
+        let expr = parse_and_bind(r#"=IFERROR(VLOOKUP("X", A1:B1, 2, FALSE), -1)"#);
+        assert_eq!(evaluate(&expr, &lookup), EvalResult::Number(-1.0));
+    }
+
+    #[test]
+    fn test_iferror_clean_value_passes_through() {
+        let mut lookup = TestLookup::new();
+        lookup.set(0, 0, "10");
+        lookup.set(0, 1, "4");
+
+        // The fallback is only evaluated on error, so its own error never surfaces
+        let expr = parse_and_bind("=IFERROR(A1/B1, 1/0)");
+        assert_eq!(evaluate(&expr, &lookup), EvalResult::Number(2.5));
+
+        let expr = parse_and_bind("=IFNA(A1/B1, 1/0)");
+        assert_eq!(evaluate(&expr, &lookup), EvalResult::Number(2.5));
+    }
+
     #[test]
     fn test_isna_with_na_error() {
         let mut lookup = TestLookup::new();
@@ -1559,6 +1706,207 @@ mod tests {
         assert_eq!(result, EvalResult::Text("Bob".to_string()));
     }
 
+    // =========================================================================
+    // INDEX / MATCH tests
+    // =========================================================================
+
+    /// A1:C4: a header row, then SKU / price / stock
+    fn index_match_lookup() -> TestLookup {
+        let mut lookup = TestLookup::new();
+        for (r, row) in [["SKU", "Price", "Stock"], ["A-1", "9.5", "40"], ["B-2", "12", "0"], ["C-3", "7.25", "15"]]
+            .iter()
+            .enumerate()
+        {
+            for (c, v) in row.iter().enumerate() {
+                lookup.set(r, c, v);
+            }
+        }
+        lookup
+    }
+
+    #[test]
+    fn test_match_exact() {
+        let lookup = index_match_lookup();
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+        assert_eq!(eval(r#"=MATCH("b-2", A1:A4, 0)"#), EvalResult::Number(3.0));
+        assert_eq!(eval(r#"=MATCH("Stock", A1:C1, 0)"#), EvalResult::Number(3.0));
+        assert_eq!(eval("=MATCH(0, C1:C4, 0)"), EvalResult::Number(3.0));
+        assert_eq!(eval(r#"=MATCH("Z-9", A1:A4, 0)"#), EvalResult::Error("#N/A".to_string()));
+        // 2D lookup range
+        assert_eq!(eval(r#"=MATCH("A-1", A1:B4, 0)"#), EvalResult::Error("#N/A".to_string()));
+    }
+
+    #[test]
+    fn test_match_approximate_ascending() {
+        // Commission tiers in A1:A4 = [0, 1000, 5000, 10000]
+        let mut lookup = TestLookup::new();
+        for (r, v) in ["0", "1000", "5000", "10000"].iter().enumerate() {
+            lookup.set(r, 0, v);
+        }
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+        assert_eq!(eval("=MATCH(4999, A1:A4, 1)"), EvalResult::Number(2.0));
+        assert_eq!(eval("=MATCH(5000, A1:A4)"), EvalResult::Number(3.0));
+        assert_eq!(eval("=MATCH(250000, A1:A4, 1)"), EvalResult::Number(4.0));
+        assert_eq!(eval("=MATCH(-1, A1:A4, 1)"), EvalResult::Error("#N/A".to_string()));
+    }
+
+    #[test]
+    fn test_match_approximate_descending() {
+        let mut lookup = TestLookup::new();
+        for (r, v) in ["100", "50", "10"].iter().enumerate() {
+            lookup.set(r, 0, v);
+        }
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+        assert_eq!(eval("=MATCH(60, A1:A3, -1)"), EvalResult::Number(1.0));
+        assert_eq!(eval("=MATCH(50, A1:A3, -1)"), EvalResult::Number(2.0));
+        assert_eq!(eval("=MATCH(5, A1:A3, -1)"), EvalResult::Number(3.0));
+        assert_eq!(eval("=MATCH(101, A1:A3, -1)"), EvalResult::Error("#N/A".to_string()));
+    }
+
+    #[test]
+    fn test_index_2d_single_cell() {
+        let lookup = index_match_lookup();
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+        assert_eq!(eval("=INDEX(A1:C4, 3, 2)"), EvalResult::Number(12.0));
+        assert_eq!(eval("=INDEX(A1:C4, 4, 1)"), EvalResult::Text("C-3".to_string()));
+        // Single row: the one index picks a column
+        assert_eq!(eval("=INDEX(A1:C1, 2)"), EvalResult::Text("Price".to_string()));
+        assert_eq!(eval("=INDEX(A1:C4, 5, 1)"), EvalResult::Error("#REF!".to_string()));
+        assert_eq!(eval("=INDEX(A1:C4, 1, 4)"), EvalResult::Error("#REF!".to_string()));
+    }
+
+    #[test]
+    fn test_index_whole_column_and_row() {
+        let lookup = index_match_lookup();
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+
+        let column = Array2D::from_vec(vec![
+            vec![Value::Text("Price".to_string())],
+            vec![Value::Number(9.5)],
+            vec![Value::Number(12.0)],
+            vec![Value::Number(7.25)],
+        ]);
+        assert_eq!(eval("=INDEX(A1:C4, 0, 2)"), EvalResult::Array(column));
+
+        let row = Array2D::from_vec(vec![vec![
+            Value::Text("B-2".to_string()),
+            Value::Number(12.0),
+            Value::Number(0.0),
+        ]]);
+        assert_eq!(eval("=INDEX(A1:C4, 3, 0)"), EvalResult::Array(row.clone()));
+        // 2D with col_num omitted: the whole row
+        assert_eq!(eval("=INDEX(A1:C4, 3)"), EvalResult::Array(row));
+    }
+
+    #[test]
+    fn test_index_match_combined() {
+        let lookup = index_match_lookup();
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+        assert_eq!(eval(r#"=INDEX(B1:B4, MATCH("C-3", A1:A4, 0))"#), EvalResult::Number(7.25));
+        // Two-way lookup: row by SKU, column by header
+        assert_eq!(
+            eval(r#"=INDEX(A1:C4, MATCH("A-1", A1:A4, 0), MATCH("Stock", A1:C1, 0))"#),
+            EvalResult::Number(40.0)
+        );
+        assert_eq!(eval(r#"=INDEX(B1:B4, MATCH("Z-9", A1:A4, 0))"#), EvalResult::Error("#N/A".to_string()));
+    }
+
+    // =========================================================================
+    // Array constant tests
+    // =========================================================================
+
+    #[test]
+    fn test_array_constant_1d() {
+        let lookup = TestLookup::new();
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+        let row = Array2D::from_vec(vec![vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)]]);
+        assert_eq!(eval("={1,2,3}"), EvalResult::Array(row));
+        assert_eq!(eval("=SUM({1,2,3})"), EvalResult::Number(6.0));
+        // Text elements are skipped like text cells in a range
+        assert_eq!(eval("=SUM({1,\"x\",3})"), EvalResult::Number(4.0));
+        assert_eq!(eval("=COUNTA({1,\"x\",TRUE})"), EvalResult::Number(3.0));
+    }
+
+    #[test]
+    fn test_array_constant_2d() {
+        let lookup = TestLookup::new();
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+        let matrix = Array2D::from_vec(vec![
+            vec![Value::Number(1.0), Value::Number(2.0)],
+            vec![Value::Number(3.0), Value::Number(4.0)],
+        ]);
+        assert_eq!(eval("={1,2;3,4}"), EvalResult::Array(matrix));
+        assert_eq!(eval("=MAX({1,2;3,4})"), EvalResult::Number(4.0));
+        // Scalar context coerces to the top-left element
+        assert_eq!(eval("={5,6;7,8}+1"), EvalResult::Number(6.0));
+    }
+
+    #[test]
+    fn test_array_constant_mmult() {
+        let mut lookup = TestLookup::new();
+        lookup.set(0, 0, "1");
+        lookup.set(1, 0, "2");
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &lookup);
+
+        let product = Array2D::from_vec(vec![
+            vec![Value::Number(19.0), Value::Number(22.0)],
+            vec![Value::Number(43.0), Value::Number(50.0)],
+        ]);
+        assert_eq!(eval("=MMULT({1,2;3,4},{5,6;7,8})"), EvalResult::Array(product));
+
+        // Literal times a range (A1:A2 is a 2x1 column)
+        let column = Array2D::from_vec(vec![vec![Value::Number(5.0)], vec![Value::Number(11.0)]]);
+        assert_eq!(eval("=MMULT({1,2;3,4},A1:A2)"), EvalResult::Array(column));
+        assert_eq!(eval("=SUM(MMULT({1,2;3,4},{5,6;7,8}))"), EvalResult::Number(134.0));
+
+        assert!(matches!(eval("=MMULT({1,2,3},{1,2})"), EvalResult::Error(e) if e.starts_with("#VALUE!")));
+        assert!(matches!(eval("=MMULT({1,\"a\"},{1;2})"), EvalResult::Error(e) if e == "#VALUE!"));
+    }
+
+    // =========================================================================
+    // Spill range (A1#) and implicit intersection (@)
+    // =========================================================================
+
+    #[test]
+    fn test_spill_ref_feeds_sum() {
+        use crate::sheet::Sheet;
+
+        let mut sheet = Sheet::new(SheetId(1), 10, 10);
+        sheet.set_value(0, 0, "=SEQUENCE(4)");
+        sheet.set_value(0, 2, "=SUM(A1#)");
+        assert_eq!(sheet.get_display(0, 2), "10");
+
+        let eval = |f: &str| evaluate(&parse_and_bind(f), &sheet);
+        let column = Array2D::from_vec((1..=4).map(|n| vec![Value::Number(n as f64)]).collect());
+        assert_eq!(eval("=A1#"), EvalResult::Array(column));
+        assert_eq!(eval("=@A1#"), EvalResult::Number(1.0));
+        // A cell that does not spill has no spill range
+        assert_eq!(eval("=C1#"), EvalResult::Error("#REF!".to_string()));
+    }
+
+    #[test]
+    fn test_implicit_intersection() {
+        let mut lookup = TestLookup::new();
+        for (r, v) in ["10", "20", "30"].iter().enumerate() {
+            lookup.set(r, 0, v);
+        }
+        lookup.set(0, 1, "7");
+
+        // A column range picks the formula's own row
+        let expr = parse_and_bind("=@A1:A3*2");
+        assert_eq!(evaluate(&expr, &LookupWithContext::new(&lookup, 1, 3)), EvalResult::Number(40.0));
+        // Outside the range's rows there is nothing to intersect
+        assert!(matches!(evaluate(&expr, &LookupWithContext::new(&lookup, 5, 3)), EvalResult::Error(e) if e == "#VALUE!"));
+
+        // A row range picks the formula's own column
+        let expr = parse_and_bind("=@A1:B1");
+        assert_eq!(evaluate(&expr, &LookupWithContext::new(&lookup, 4, 1)), EvalResult::Number(7.0));
+
+        // Scalars pass through; arrays collapse to their first element
+        assert_eq!(evaluate(&parse_and_bind("=@A2"), &lookup), EvalResult::Number(20.0));
+        assert_eq!(evaluate(&parse_and_bind("=@{5,6;7,8}"), &lookup), EvalResult::Number(5.0));
+    }
+
     // =========================================================================
     // SPARKLINE tests
     // =========================================================================
@@ -2545,6 +2893,63 @@ mod tests {
         }
     }
 
+    // ── MEDIAN / PERCENTILE / RANK ───────────────────────────────
+
+    #[test]
+    fn test_median_odd_and_even_counts() {
+        let mut lookup = TestLookup::new();
+        for (row, v) in ["7", "1", "3", "5"].iter().enumerate() {
+            lookup.set(row, 0, v);
+        }
+        // Odd: 1, 3, 7 → 3
+        let result = evaluate(&parse_and_bind("=MEDIAN(A1:A3)"), &lookup);
+        assert_eq!(result, EvalResult::Number(3.0));
+        // Even: 1, 3, 5, 7 → 4
+        let result = evaluate(&parse_and_bind("=MEDIAN(A1:A4)"), &lookup);
+        assert_eq!(result, EvalResult::Number(4.0));
+    }
+
+    #[test]
+    fn test_percentile_interpolates() {
+        let mut lookup = TestLookup::new();
+        for (row, v) in ["10", "40", "20", "30"].iter().enumerate() {
+            lookup.set(row, 0, v);
+        }
+        // Excel: PERCENTILE({10,20,30,40}, 0.95) = 38.5
+        let result = evaluate(&parse_and_bind("=PERCENTILE(A1:A4,0.95)"), &lookup);
+        match result {
+            EvalResult::Number(n) => assert!((n - 38.5).abs() < 1e-9, "PERCENTILE = {}, expected 38.5", n),
+            _ => panic!("Expected Number, got {:?}", result),
+        }
+        // Endpoints are min and max
+        assert_eq!(evaluate(&parse_and_bind("=PERCENTILE.INC(A1:A4,0)"), &lookup), EvalResult::Number(10.0));
+        assert_eq!(evaluate(&parse_and_bind("=PERCENTILE(A1:A4,1)"), &lookup), EvalResult::Number(40.0));
+        // k outside [0, 1] → #NUM!
+        match evaluate(&parse_and_bind("=PERCENTILE(A1:A4,1.5)"), &lookup) {
+            EvalResult::Error(e) => assert_eq!(e, "#NUM!"),
+            other => panic!("Expected #NUM!, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rank_ties_and_order() {
+        let mut lookup = TestLookup::new();
+        for (row, v) in ["50", "80", "80", "20"].iter().enumerate() {
+            lookup.set(row, 0, v);
+        }
+        // Descending (default): 80, 80 tie at 1; 50 is 3rd
+        assert_eq!(evaluate(&parse_and_bind("=RANK(80,A1:A4)"), &lookup), EvalResult::Number(1.0));
+        assert_eq!(evaluate(&parse_and_bind("=RANK(50,A1:A4)"), &lookup), EvalResult::Number(3.0));
+        // Ascending: 20 is 1st, the 80s tie at 3
+        assert_eq!(evaluate(&parse_and_bind("=RANK(80,A1:A4,1)"), &lookup), EvalResult::Number(3.0));
+        assert_eq!(evaluate(&parse_and_bind("=RANK.EQ(20,A1:A4,1)"), &lookup), EvalResult::Number(1.0));
+        // Value not in range → #N/A
+        match evaluate(&parse_and_bind("=RANK(99,A1:A4)"), &lookup) {
+            EvalResult::Error(e) => assert_eq!(e, "#N/A"),
+            other => panic!("Expected #N/A, got {:?}", other),
+        }
+    }
+
     // ── Blank cell comparison semantics (Excel-compat) ──────────
 
     #[test]
@@ -2681,4 +3086,82 @@ mod tests {
         let result = evaluate(&expr, &lookup);
         assert_eq!(result, EvalResult::Number(1.0));
     }
+
+    fn eval_number(formula: &str) -> f64 {
+        match evaluate(&parse_and_bind(formula), &TestLookup::new()) {
+            EvalResult::Number(n) => n,
+            other => panic!("{} gave {:?}", formula, other),
+        }
+    }
+
+    fn eval_text(formula: &str) -> String {
+        match evaluate(&parse_and_bind(formula), &TestLookup::new()) {
+            EvalResult::Text(s) => s,
+            other => panic!("{} gave {:?}", formula, other),
+        }
+    }
+
+    #[test]
+    fn test_text_percent_format() {
+        assert_eq!(eval_text("=TEXT(0.1234,\"0.00%\")"), "12.34%");
+        assert_eq!(eval_text("=TEXT(0.5,\"0%\")"), "50%");
+        assert_eq!(eval_text("=\"Margin: \"&TEXT(0.075,\"0.0%\")"), "Margin: 7.5%");
+    }
+
+    #[test]
+    fn test_text_thousands_format() {
+        assert_eq!(eval_text("=TEXT(1234567.891,\"#,##0.00\")"), "1,234,567.89");
+        assert_eq!(eval_text("=TEXT(1234567,\"#,##0\")"), "1,234,567");
+        // Non-numeric text passes through; errors propagate
+        assert_eq!(eval_text("=TEXT(\"n/a\",\"#,##0\")"), "n/a");
+        assert!(matches!(evaluate(&parse_and_bind("=TEXT(1/0,\"0.00\")"), &TestLookup::new()), EvalResult::Error(_)));
+    }
+
+    #[test]
+    fn test_text_date_format() {
+        assert_eq!(eval_text("=TEXT(DATE(2024,3,5),\"yyyy-mm-dd\")"), "2024-03-05");
+        assert_eq!(eval_text("=TEXT(45356,\"yyyy-mm-dd\")"), "2024-03-05");
+    }
+
+    #[test]
+    fn test_round_half_up_vs_half_even_at_boundary() {
+        assert_eq!(eval_number("=ROUND(2.5,0)"), 3.0);
+        assert_eq!(eval_number("=ROUND(3.5,0)"), 4.0);
+        assert_eq!(eval_number("=ROUND(-2.5,0)"), -3.0);
+        assert_eq!(eval_number("=ROUND(2.675,2)"), 2.68);
+        assert_eq!(eval_number("=ROUND(0.125,2)"), 0.13);
+        assert_eq!(eval_number("=MROUND(0.25,0.1)"), 0.3);
+
+        let half_even = |formula: &str| {
+            let lookup = TestLookup { round_mode: RoundMode::HalfEven, ..TestLookup::new() };
+            match evaluate(&parse_and_bind(formula), &lookup) {
+                EvalResult::Number(n) => n,
+                other => panic!("{} gave {:?}", formula, other),
+            }
+        };
+        assert_eq!(half_even("=ROUND(2.5,0)"), 2.0);
+        assert_eq!(half_even("=ROUND(3.5,0)"), 4.0);
+        assert_eq!(half_even("=ROUND(-2.5,0)"), -2.0);
+        assert_eq!(half_even("=ROUND(-3.5,0)"), -4.0);
+        assert_eq!(half_even("=ROUND(2.675,2)"), 2.68);
+        assert_eq!(half_even("=ROUND(0.125,2)"), 0.12);
+        assert_eq!(half_even("=ROUND(0.1251,2)"), 0.13);
+        assert_eq!(half_even("=MROUND(0.25,0.1)"), 0.2);
+        // ROUNDUP/ROUNDDOWN ignore the tie mode
+        assert_eq!(half_even("=ROUNDUP(2.5,0)"), 3.0);
+    }
+
+    #[test]
+    fn test_roundup_rounddown_negative() {
+        assert_eq!(eval_number("=ROUNDUP(-3.141,2)"), -3.15);
+        assert_eq!(eval_number("=ROUNDDOWN(-3.149,2)"), -3.14);
+        assert_eq!(eval_number("=ROUNDUP(1234,-2)"), 1300.0);
+        assert_eq!(eval_number("=ROUNDDOWN(0.1+0.2,1)"), 0.3);
+        assert_eq!(eval_number("=ROUNDUP(0.1+0.2,1)"), 0.3);
+        assert_eq!(eval_number("=MROUND(-7.5,-5)"), -10.0);
+        assert!(matches!(
+            evaluate(&parse_and_bind("=MROUND(5,-2)"), &TestLookup::new()),
+            EvalResult::Error(e) if e == "#NUM!"
+        ));
+    }
 }
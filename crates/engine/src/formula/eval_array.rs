@@ -1,7 +1,7 @@
-// Array/spill functions: SEQUENCE, TRANSPOSE, FILTER, UNIQUE, SORT, SPARKLINE
+// Array/spill functions: SEQUENCE, TRANSPOSE, FILTER, UNIQUE, SORT, MMULT, SPARKLINE
 
 use super::eval::{evaluate, CellLookup, EvalResult, Value, Array2D};
-use super::eval_helpers::{collect_numbers, value_compare};
+use super::eval_helpers::{collect_numbers, get_text_for_sheet, value_compare};
 use super::parser::{BoundExpr, Expr};
 
 pub(crate) fn try_evaluate<L: CellLookup>(
@@ -345,6 +345,38 @@ pub(crate) fn try_evaluate<L: CellLookup>(
             EvalResult::Array(array)
         }
 
+        "MMULT" => {
+            // MMULT(array1, array2)
+            // Matrix product; array1's column count must equal array2's row count
+            if args.len() != 2 {
+                return Some(EvalResult::Error("MMULT requires exactly 2 arguments".to_string()));
+            }
+
+            let (a_rows, a_cols, a) = match numeric_matrix(&args[0], lookup) {
+                Ok(m) => m,
+                Err(e) => return Some(EvalResult::Error(e)),
+            };
+            let (b_rows, b_cols, b) = match numeric_matrix(&args[1], lookup) {
+                Ok(m) => m,
+                Err(e) => return Some(EvalResult::Error(e)),
+            };
+
+            if a_cols != b_rows {
+                return Some(EvalResult::Error(format!(
+                    "#VALUE! MMULT dimension mismatch: {}x{} times {}x{}", a_rows, a_cols, b_rows, b_cols
+                )));
+            }
+
+            let mut array = Array2D::new(a_rows, b_cols);
+            for r in 0..a_rows {
+                for c in 0..b_cols {
+                    let sum: f64 = (0..a_cols).map(|k| a[r * a_cols + k] * b[k * b_cols + c]).sum();
+                    array.set(r, c, Value::Number(sum));
+                }
+            }
+            EvalResult::Array(array)
+        }
+
         "SPARKLINE" => {
             // SPARKLINE(data_range, [type])
             // Creates a Unicode mini-chart from numeric data
@@ -405,3 +437,43 @@ pub(crate) fn try_evaluate<L: CellLookup>(
     };
     Some(result)
 }
+
+/// Read an argument as a dense numeric matrix (rows, cols, row-major values).
+/// Accepts ranges, array results (including array constants), and scalars (1x1).
+/// Any non-numeric element is a #VALUE! error, matching MMULT semantics.
+fn numeric_matrix<L: CellLookup>(arg: &BoundExpr, lookup: &L) -> Result<(usize, usize, Vec<f64>), String> {
+    if let Expr::Range { sheet, start_col, start_row, end_col, end_row, .. } = arg {
+        let (min_row, max_row) = (*start_row.min(end_row), *start_row.max(end_row));
+        let (min_col, max_col) = (*start_col.min(end_col), *start_col.max(end_col));
+        let mut data = Vec::with_capacity((max_row - min_row + 1) * (max_col - min_col + 1));
+        for r in min_row..=max_row {
+            for c in min_col..=max_col {
+                let text = get_text_for_sheet(lookup, sheet, r, c)?;
+                match text.parse::<f64>() {
+                    Ok(n) => data.push(n),
+                    Err(_) => return Err("#VALUE!".to_string()),
+                }
+            }
+        }
+        return Ok((max_row - min_row + 1, max_col - min_col + 1, data));
+    }
+
+    match evaluate(arg, lookup) {
+        EvalResult::Array(arr) => {
+            let mut data = Vec::with_capacity(arr.rows() * arr.cols());
+            for r in 0..arr.rows() {
+                for c in 0..arr.cols() {
+                    match arr.get(r, c) {
+                        Some(Value::Number(n)) => data.push(*n),
+                        Some(Value::Error(e)) => return Err(e.clone()),
+                        _ => return Err("#VALUE!".to_string()),
+                    }
+                }
+            }
+            Ok((arr.rows(), arr.cols(), data))
+        }
+        EvalResult::Number(n) => Ok((1, 1, vec![n])),
+        EvalResult::Error(e) => Err(e),
+        _ => Err("#VALUE!".to_string()),
+    }
+}
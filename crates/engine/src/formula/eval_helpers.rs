@@ -244,6 +244,19 @@ pub(crate) fn collect_numbers<L: CellLookup>(args: &[BoundExpr], lookup: &L) ->
             }
             _ => {
                 let result = evaluate(arg, lookup);
+                if let EvalResult::Array(arr) = &result {
+                    // Arrays behave like ranges: numbers are collected, text/empty skipped
+                    for r in 0..arr.rows() {
+                        for c in 0..arr.cols() {
+                            match arr.get(r, c) {
+                                Some(Value::Number(n)) => values.push(*n),
+                                Some(Value::Error(e)) => return Err(e.clone()),
+                                _ => {}
+                            }
+                        }
+                    }
+                    continue;
+                }
                 match result.to_number() {
                     Ok(n) => values.push(n),
                     Err(e) => return Err(e),
@@ -332,9 +345,17 @@ pub(crate) fn collect_all_values<L: CellLookup>(args: &[BoundExpr], lookup: &L)
                     }
                 }
             }
-            _ => {
-                values.push(evaluate(arg, lookup));
-            }
+            _ => match evaluate(arg, lookup) {
+                EvalResult::Array(arr) => {
+                    for r in 0..arr.rows() {
+                        for c in 0..arr.cols() {
+                            let v = arr.get(r, c).cloned().unwrap_or(Value::Empty);
+                            values.push(EvalResult::from_value(&v));
+                        }
+                    }
+                }
+                result => values.push(result),
+            },
         }
     }
 
@@ -1,8 +1,10 @@
 // Lookup/reference functions: VLOOKUP, XLOOKUP, HLOOKUP, INDEX, MATCH,
 // ROW, COLUMN, ROWS, COLUMNS
 
-use super::eval::{evaluate, CellLookup, EvalResult};
-use super::eval_helpers::get_text_for_sheet;
+use std::cmp::Ordering;
+
+use super::eval::{evaluate, Array2D, CellLookup, EvalResult, Value};
+use super::eval_helpers::{get_text_for_sheet, value_compare};
 use super::parser::{BoundExpr, Expr};
 use crate::sheet::SheetRef;
 
@@ -395,183 +397,121 @@ pub(crate) fn try_evaluate<L: CellLookup>(
             }
         }
         "INDEX" => {
-            // INDEX(range, row_num, [col_num])
+            // INDEX(array, row_num, [col_num]) - a 0 index returns the whole column/row
             if args.len() < 2 || args.len() > 3 {
                 return Some(EvalResult::Error("INDEX requires 2 or 3 arguments".to_string()));
             }
-            let (range, idx_sheet) = match &args[0] {
-                Expr::Range { sheet, start_col, start_row, end_col, end_row, .. } => ((*start_row, *start_col, *end_row, *end_col), sheet.clone()),
-                Expr::CellRef { sheet, col, row, .. } => ((*row, *col, *row, *col), sheet.clone()),
-                _ => return Some(EvalResult::Error("INDEX requires a range as first argument".to_string())),
+            let source = match vector_source(&args[0], lookup, "INDEX requires a range as first argument") {
+                Ok(s) => s,
+                Err(e) => return Some(EvalResult::Error(e)),
             };
-            let row_num = match evaluate(&args[1], lookup).to_number() {
-                Ok(n) => n as usize,
+            let (num_rows, num_cols) = source.dims();
+
+            let index_arg = |arg: &BoundExpr| -> Result<usize, String> {
+                match evaluate(arg, lookup).to_number() {
+                    Ok(n) if n < 0.0 => Err("#VALUE!".to_string()),
+                    Ok(n) => Ok(n as usize),
+                    Err(e) => Err(e),
+                }
+            };
+            let first = match index_arg(&args[1]) {
+                Ok(n) => n,
                 Err(e) => return Some(EvalResult::Error(e)),
             };
-            let col_num = if args.len() == 3 {
-                match evaluate(&args[2], lookup).to_number() {
-                    Ok(n) => n as usize,
+            let (row_num, col_num) = if args.len() == 3 {
+                match index_arg(&args[2]) {
+                    Ok(n) => (first, n),
                     Err(e) => return Some(EvalResult::Error(e)),
                 }
+            } else if num_rows == 1 {
+                // A single row: the one index picks a column
+                (1, first)
+            } else if num_cols == 1 {
+                (first, 1)
             } else {
-                1
+                // 2D with no col_num: the whole row
+                (first, 0)
             };
 
-            let (min_row, min_col, max_row, max_col) = (range.0.min(range.2), range.1.min(range.3), range.0.max(range.2), range.1.max(range.3));
-            let num_rows = max_row - min_row + 1;
-            let num_cols = max_col - min_col + 1;
-
-            if row_num < 1 || row_num > num_rows || col_num < 1 || col_num > num_cols {
+            if row_num > num_rows || col_num > num_cols {
                 return Some(EvalResult::Error("#REF!".to_string()));
             }
 
-            let target_row = min_row + row_num - 1;
-            let target_col = min_col + col_num - 1;
-            let result_text = match get_text_for_sheet(lookup, &idx_sheet, target_row, target_col) {
-                Ok(t) => t,
-                Err(e) => return Some(EvalResult::Error(e)),
-            };
+            let rows: Vec<usize> = if row_num == 0 { (0..num_rows).collect() } else { vec![row_num - 1] };
+            let cols: Vec<usize> = if col_num == 0 { (0..num_cols).collect() } else { vec![col_num - 1] };
 
-            if result_text.is_empty() {
-                EvalResult::Empty
-            } else if let Ok(n) = result_text.parse::<f64>() {
-                EvalResult::Number(n)
+            if rows.len() == 1 && cols.len() == 1 {
+                EvalResult::from_value(&source.get(rows[0], cols[0]))
             } else {
-                EvalResult::Text(result_text)
+                let mut array = Array2D::new(rows.len(), cols.len());
+                for (i, &r) in rows.iter().enumerate() {
+                    for (j, &c) in cols.iter().enumerate() {
+                        array.set(i, j, source.get(r, c));
+                    }
+                }
+                EvalResult::Array(array)
             }
         }
         "MATCH" => {
             // MATCH(search_key, range, [match_type])
+            //   1 (default): largest value <= key, range sorted ascending
+            //   0:           first exact match
+            //  -1:           smallest value >= key, range sorted descending
             if args.len() < 2 || args.len() > 3 {
                 return Some(EvalResult::Error("MATCH requires 2 or 3 arguments".to_string()));
             }
-            let search_key = evaluate(&args[0], lookup);
-            let (range, match_sheet) = match &args[1] {
-                Expr::Range { sheet, start_col, start_row, end_col, end_row, .. } => ((*start_row, *start_col, *end_row, *end_col), sheet),
-                _ => return Some(EvalResult::Error("MATCH requires a range as second argument".to_string())),
+            let search_key = match evaluate(&args[0], lookup) {
+                EvalResult::Error(e) => return Some(EvalResult::Error(e)),
+                other => lookup_key(other.to_value()),
+            };
+            let source = match vector_source(&args[1], lookup, "MATCH requires a range as second argument") {
+                Ok(s) => s,
+                Err(e) => return Some(EvalResult::Error(e)),
             };
             let match_type = if args.len() == 3 {
                 match evaluate(&args[2], lookup).to_number() {
-                    Ok(n) => n as i32,
+                    Ok(n) if n > 0.0 => 1,
+                    Ok(n) if n < 0.0 => -1,
+                    Ok(_) => 0,
                     Err(_) => 1,
                 }
             } else {
                 1
             };
 
-            let (min_row, min_col, max_row, max_col) = (range.0.min(range.2), range.1.min(range.3), range.0.max(range.2), range.1.max(range.3));
-
-            // Determine if it's a row vector or column vector
-            let is_row = min_row == max_row;
-            let search_text = search_key.to_text().to_lowercase();
-            let search_num = search_key.to_number().ok();
-
-            let mut found_pos: Option<usize> = None;
-
-            let mget = |r: usize, c: usize| -> String {
-                match get_text_for_sheet(lookup, match_sheet, r, c) {
-                    Ok(t) => t,
-                    Err(_) => String::new(),
-                }
+            // The lookup range must be a single row or column
+            let (num_rows, num_cols) = source.dims();
+            let values: Vec<Value> = if num_rows == 1 {
+                (0..num_cols).map(|c| source.get(0, c)).collect()
+            } else if num_cols == 1 {
+                (0..num_rows).map(|r| source.get(r, 0)).collect()
+            } else {
+                return Some(EvalResult::Error("#N/A".to_string()));
             };
 
-            if is_row {
-                // Search horizontally
-                if match_type == 0 {
-                    // Exact match
-                    for (i, c) in (min_col..=max_col).enumerate() {
-                        let cell_text = mget(min_row, c);
-                        let cell_lower = cell_text.to_lowercase();
-                        if let (Some(sn), Ok(cn)) = (search_num, cell_text.parse::<f64>()) {
-                            if (sn - cn).abs() < f64::EPSILON {
-                                found_pos = Some(i + 1);
-                                break;
-                            }
-                        } else if cell_lower == search_text {
-                            found_pos = Some(i + 1);
+            let found_pos = match match_type {
+                0 => values.iter().position(|v| lookup_eq(v, &search_key)),
+                _ => {
+                    // Sorted scan: the last comparable value on the near side of the key,
+                    // stopping at the first one past it (what a binary search finds)
+                    let mut best = None;
+                    for (i, v) in values.iter().enumerate() {
+                        let Some(ord) = lookup_cmp(v, &search_key) else { continue };
+                        let past = if match_type == 1 { ord == Ordering::Greater } else { ord == Ordering::Less };
+                        if past {
                             break;
                         }
-                    }
-                } else if match_type == 1 {
-                    // Largest value <= search_key
-                    if let Some(sn) = search_num {
-                        let mut best_pos: Option<usize> = None;
-                        let mut best_val = f64::NEG_INFINITY;
-                        for (i, c) in (min_col..=max_col).enumerate() {
-                            if let Ok(cn) = mget(min_row, c).parse::<f64>() {
-                                if cn <= sn && cn > best_val {
-                                    best_val = cn;
-                                    best_pos = Some(i + 1);
-                                }
-                            }
-                        }
-                        found_pos = best_pos;
-                    }
-                } else {
-                    // Smallest value >= search_key
-                    if let Some(sn) = search_num {
-                        let mut best_pos: Option<usize> = None;
-                        let mut best_val = f64::INFINITY;
-                        for (i, c) in (min_col..=max_col).enumerate() {
-                            if let Ok(cn) = mget(min_row, c).parse::<f64>() {
-                                if cn >= sn && cn < best_val {
-                                    best_val = cn;
-                                    best_pos = Some(i + 1);
-                                }
-                            }
-                        }
-                        found_pos = best_pos;
-                    }
-                }
-            } else {
-                // Search vertically
-                if match_type == 0 {
-                    for (i, r) in (min_row..=max_row).enumerate() {
-                        let cell_text = mget(r, min_col);
-                        let cell_lower = cell_text.to_lowercase();
-                        if let (Some(sn), Ok(cn)) = (search_num, cell_text.parse::<f64>()) {
-                            if (sn - cn).abs() < f64::EPSILON {
-                                found_pos = Some(i + 1);
-                                break;
-                            }
-                        } else if cell_lower == search_text {
-                            found_pos = Some(i + 1);
+                        best = Some(i);
+                        if ord == Ordering::Equal {
                             break;
                         }
                     }
-                } else if match_type == 1 {
-                    if let Some(sn) = search_num {
-                        let mut best_pos: Option<usize> = None;
-                        let mut best_val = f64::NEG_INFINITY;
-                        for (i, r) in (min_row..=max_row).enumerate() {
-                            if let Ok(cn) = mget(r, min_col).parse::<f64>() {
-                                if cn <= sn && cn > best_val {
-                                    best_val = cn;
-                                    best_pos = Some(i + 1);
-                                }
-                            }
-                        }
-                        found_pos = best_pos;
-                    }
-                } else {
-                    if let Some(sn) = search_num {
-                        let mut best_pos: Option<usize> = None;
-                        let mut best_val = f64::INFINITY;
-                        for (i, r) in (min_row..=max_row).enumerate() {
-                            if let Ok(cn) = mget(r, min_col).parse::<f64>() {
-                                if cn >= sn && cn < best_val {
-                                    best_val = cn;
-                                    best_pos = Some(i + 1);
-                                }
-                            }
-                        }
-                        found_pos = best_pos;
-                    }
+                    best
                 }
-            }
+            };
 
             match found_pos {
-                Some(pos) => EvalResult::Number(pos as f64),
+                Some(pos) => EvalResult::Number((pos + 1) as f64),
                 None => EvalResult::Error("#N/A".to_string()),
             }
         }
@@ -639,3 +579,93 @@ pub(crate) fn try_evaluate<L: CellLookup>(
     };
     Some(result)
 }
+
+/// Cells INDEX and MATCH read from: a sheet range or an evaluated array.
+enum VectorSource<'a, L: CellLookup> {
+    Range { lookup: &'a L, sheet: SheetRef, min_row: usize, min_col: usize, rows: usize, cols: usize },
+    Array(Array2D),
+}
+
+impl<L: CellLookup> VectorSource<'_, L> {
+    fn dims(&self) -> (usize, usize) {
+        match self {
+            VectorSource::Range { rows, cols, .. } => (*rows, *cols),
+            VectorSource::Array(arr) => (arr.rows(), arr.cols()),
+        }
+    }
+
+    /// Value at a 0-based offset within the source.
+    fn get(&self, row: usize, col: usize) -> Value {
+        match self {
+            VectorSource::Range { lookup, sheet, min_row, min_col, .. } => {
+                match get_text_for_sheet(*lookup, sheet, min_row + row, min_col + col) {
+                    Ok(text) if text.is_empty() => Value::Empty,
+                    Ok(text) => match text.parse::<f64>() {
+                        Ok(n) => Value::Number(n),
+                        Err(_) => Value::Text(text),
+                    },
+                    Err(e) => Value::Error(e),
+                }
+            }
+            VectorSource::Array(arr) => arr.get(row, col).cloned().unwrap_or(Value::Empty),
+        }
+    }
+}
+
+/// Resolve a range, cell, or array-valued argument into a `VectorSource`,
+/// failing with `not_range` for any other scalar.
+fn vector_source<'a, L: CellLookup>(
+    arg: &BoundExpr, lookup: &'a L, not_range: &str,
+) -> Result<VectorSource<'a, L>, String> {
+    let (sheet, r0, c0, r1, c1) = match arg {
+        Expr::Range { sheet, start_col, start_row, end_col, end_row, .. } => (sheet, *start_row, *start_col, *end_row, *end_col),
+        Expr::CellRef { sheet, col, row, .. } => (sheet, *row, *col, *row, *col),
+        _ => {
+            return match evaluate(arg, lookup) {
+                EvalResult::Array(arr) => Ok(VectorSource::Array(arr)),
+                EvalResult::Error(e) => Err(e),
+                _ => Err(not_range.to_string()),
+            };
+        }
+    };
+    if let SheetRef::RefError { .. } = sheet {
+        return Err("#REF!".to_string());
+    }
+    Ok(VectorSource::Range {
+        lookup,
+        sheet: sheet.clone(),
+        min_row: r0.min(r1),
+        min_col: c0.min(c1),
+        rows: r0.max(r1) - r0.min(r1) + 1,
+        cols: c0.max(c1) - c0.min(c1) + 1,
+    })
+}
+
+/// Normalize a MATCH key: numeric text compares as a number, like cell contents.
+fn lookup_key(key: Value) -> Value {
+    match key {
+        Value::Text(s) => match s.parse::<f64>() {
+            Ok(n) => Value::Number(n),
+            Err(_) => Value::Text(s),
+        },
+        other => other,
+    }
+}
+
+/// Exact MATCH equality: same type, text compared case-insensitively.
+fn lookup_eq(value: &Value, key: &Value) -> bool {
+    match (value, key) {
+        (Value::Number(a), Value::Number(b)) => (a - b).abs() < f64::EPSILON,
+        _ => lookup_cmp(value, key) == Some(Ordering::Equal),
+    }
+}
+
+/// Order `value` against `key` when they are comparable (same type, non-empty).
+fn lookup_cmp(value: &Value, key: &Value) -> Option<Ordering> {
+    match (value, key) {
+        (Value::Number(_), Value::Number(_))
+        | (Value::Text(_), Value::Text(_))
+        | (Value::Boolean(_), Value::Boolean(_)) => Some(value_compare(value, key)),
+        _ => None,
+    }
+}
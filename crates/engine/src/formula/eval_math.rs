@@ -1,9 +1,10 @@
-// Math functions: SUM, AVERAGE, MIN, MAX, COUNT, COUNTA, ABS, ROUND, INT, MOD,
+// Math functions: SUM, AVERAGE, MIN, MAX, COUNT, COUNTA, ABS, ROUND, MROUND, INT, MOD,
 // POWER, SQRT, CEILING, FLOOR, PRODUCT, MEDIAN, SUMPRODUCT
 
 use super::eval::{evaluate, CellLookup, EvalResult, NamedRangeResolution};
 use super::eval_helpers::{collect_numbers, collect_all_values};
 use super::parser::{BoundExpr, Expr};
+use super::rounding::{mround, round_decimal, Rule};
 
 pub(crate) fn try_evaluate<L: CellLookup>(
     name: &str, args: &[BoundExpr], lookup: &L,
@@ -77,9 +78,9 @@ pub(crate) fn try_evaluate<L: CellLookup>(
                 Err(e) => EvalResult::Error(e),
             }
         }
-        "ROUND" => {
+        "ROUND" | "ROUNDUP" | "ROUNDDOWN" => {
             if args.is_empty() || args.len() > 2 {
-                return Some(EvalResult::Error("ROUND requires 1 or 2 arguments".to_string()));
+                return Some(EvalResult::Error(format!("{} requires 1 or 2 arguments", name)));
             }
             let value = match evaluate(&args[0], lookup).to_number() {
                 Ok(n) => n,
@@ -93,56 +94,29 @@ pub(crate) fn try_evaluate<L: CellLookup>(
             } else {
                 0
             };
-            let factor = 10_f64.powi(decimals);
-            EvalResult::Number((value * factor).round() / factor)
+            let rule = match name {
+                "ROUNDUP" => Rule::Up,
+                "ROUNDDOWN" => Rule::Down,
+                _ => lookup.round_mode().into(),
+            };
+            EvalResult::Number(round_decimal(value, decimals, rule))
         }
-        "ROUNDUP" => {
-            if args.is_empty() || args.len() > 2 {
-                return Some(EvalResult::Error("ROUNDUP requires 1 or 2 arguments".to_string()));
+        "MROUND" => {
+            if args.len() != 2 {
+                return Some(EvalResult::Error("MROUND requires exactly 2 arguments".to_string()));
             }
-            let value = match evaluate(&args[0], lookup).to_number() {
+            let number = match evaluate(&args[0], lookup).to_number() {
                 Ok(n) => n,
                 Err(e) => return Some(EvalResult::Error(e)),
             };
-            let decimals = if args.len() == 2 {
-                match evaluate(&args[1], lookup).to_number() {
-                    Ok(n) => n as i32,
-                    Err(e) => return Some(EvalResult::Error(e)),
-                }
-            } else {
-                0
-            };
-            let factor = 10_f64.powi(decimals);
-            let result = if value >= 0.0 {
-                (value * factor).ceil() / factor
-            } else {
-                (value * factor).floor() / factor
-            };
-            EvalResult::Number(result)
-        }
-        "ROUNDDOWN" => {
-            if args.is_empty() || args.len() > 2 {
-                return Some(EvalResult::Error("ROUNDDOWN requires 1 or 2 arguments".to_string()));
-            }
-            let value = match evaluate(&args[0], lookup).to_number() {
+            let multiple = match evaluate(&args[1], lookup).to_number() {
                 Ok(n) => n,
                 Err(e) => return Some(EvalResult::Error(e)),
             };
-            let decimals = if args.len() == 2 {
-                match evaluate(&args[1], lookup).to_number() {
-                    Ok(n) => n as i32,
-                    Err(e) => return Some(EvalResult::Error(e)),
-                }
-            } else {
-                0
-            };
-            let factor = 10_f64.powi(decimals);
-            let result = if value >= 0.0 {
-                (value * factor).floor() / factor
-            } else {
-                (value * factor).ceil() / factor
-            };
-            EvalResult::Number(result)
+            match mround(number, multiple, lookup.round_mode()) {
+                Ok(n) => EvalResult::Number(n),
+                Err(e) => EvalResult::Error(e),
+            }
         }
         "TRUNC" => {
             if args.is_empty() || args.len() > 2 {
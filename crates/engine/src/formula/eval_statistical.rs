@@ -1,5 +1,5 @@
 // Statistical functions: STDEV, STDEV.S, STDEV.P, STDEVP, VAR, VAR.S, VAR.P,
-// VARP, RAND, RANDBETWEEN
+// VARP, PERCENTILE, PERCENTILE.INC, RANK, RANK.EQ, RAND, RANDBETWEEN
 
 use super::eval::{evaluate, CellLookup, EvalResult};
 use super::eval_helpers::collect_numbers;
@@ -77,6 +77,58 @@ pub(crate) fn try_evaluate<L: CellLookup>(
                 Err(e) => EvalResult::Error(e),
             }
         }
+        "PERCENTILE" | "PERCENTILE.INC" => {
+            // Inclusive percentile: linear interpolation between closest ranks
+            if args.len() != 2 {
+                return Some(EvalResult::Error(format!("{} requires exactly 2 arguments", name)));
+            }
+            let mut vals = match collect_numbers(&args[..1], lookup) {
+                Ok(vals) => vals,
+                Err(e) => return Some(EvalResult::Error(e)),
+            };
+            let k = match evaluate(&args[1], lookup).to_number() {
+                Ok(n) => n,
+                Err(e) => return Some(EvalResult::Error(e)),
+            };
+            if vals.is_empty() || !(0.0..=1.0).contains(&k) {
+                return Some(EvalResult::Error("#NUM!".to_string()));
+            }
+            vals.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            let pos = k * (vals.len() - 1) as f64;
+            let lo = pos.floor() as usize;
+            let hi = (lo + 1).min(vals.len() - 1);
+            EvalResult::Number(vals[lo] + (pos - lo as f64) * (vals[hi] - vals[lo]))
+        }
+        "RANK" | "RANK.EQ" => {
+            // RANK(value, range, [order]): order 0/omitted = descending, else ascending.
+            // Ties share the highest rank (1 + count strictly better).
+            if args.len() < 2 || args.len() > 3 {
+                return Some(EvalResult::Error(format!("{} requires 2-3 arguments", name)));
+            }
+            let value = match evaluate(&args[0], lookup).to_number() {
+                Ok(n) => n,
+                Err(e) => return Some(EvalResult::Error(e)),
+            };
+            let vals = match collect_numbers(&args[1..2], lookup) {
+                Ok(vals) => vals,
+                Err(e) => return Some(EvalResult::Error(e)),
+            };
+            let ascending = if args.len() > 2 {
+                match evaluate(&args[2], lookup).to_number() {
+                    Ok(n) => n != 0.0,
+                    Err(e) => return Some(EvalResult::Error(e)),
+                }
+            } else {
+                false
+            };
+            if !vals.contains(&value) {
+                return Some(EvalResult::Error("#N/A".to_string()));
+            }
+            let better = vals.iter()
+                .filter(|&&v| if ascending { v < value } else { v > value })
+                .count();
+            EvalResult::Number((better + 1) as f64)
+        }
         "RAND" => {
             if !args.is_empty() {
                 return Some(EvalResult::Error("RAND takes no arguments".to_string()));
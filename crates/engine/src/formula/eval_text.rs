@@ -3,6 +3,7 @@
 
 use super::eval::{evaluate, CellLookup, EvalResult};
 use super::parser::{BoundExpr, Expr};
+use crate::cell::{CellValue, NumberFormat};
 
 pub(crate) fn try_evaluate<L: CellLookup>(
     name: &str, args: &[BoundExpr], lookup: &L,
@@ -138,21 +139,22 @@ pub(crate) fn try_evaluate<L: CellLookup>(
             if args.len() != 2 {
                 return Some(EvalResult::Error("TEXT requires exactly 2 arguments".to_string()));
             }
-            let value = match evaluate(&args[0], lookup).to_number() {
-                Ok(n) => n,
-                Err(e) => return Some(EvalResult::Error(e)),
-            };
-            let format = evaluate(&args[1], lookup).to_text();
-            // Simple format support
-            let result = if format.contains("0.") {
-                let decimals = format.matches('0').count().saturating_sub(1);
-                format!("{:.1$}", value, decimals)
-            } else if format.contains('%') {
-                format!("{}%", (value * 100.0) as i64)
-            } else {
-                format!("{}", value)
+            let value = evaluate(&args[0], lookup);
+            if let EvalResult::Error(e) = value {
+                return Some(EvalResult::Error(e));
+            }
+            let format = match evaluate(&args[1], lookup) {
+                EvalResult::Error(e) => return Some(EvalResult::Error(e)),
+                other => other.to_text(),
             };
-            EvalResult::Text(result)
+            // Same renderer as a cell formatted with this code; dates are
+            // serials, so date codes work too. Non-numeric text passes through.
+            match value.to_number() {
+                Ok(n) => EvalResult::Text(CellValue::format_number(n, &NumberFormat::Custom(format))),
+                // Error values stored as text (from cell lookup) still propagate
+                Err(e) if e == value.to_text() => EvalResult::Error(e),
+                Err(_) => EvalResult::Text(value.to_text()),
+            }
         }
         "VALUE" => {
             if args.len() != 1 {
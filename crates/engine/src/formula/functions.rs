@@ -17,30 +17,281 @@ const FUNCTION_NAMES: &[&str] = &[
     "IF", "IFERROR", "IFNA", "IFS", "INDEX", "INT", "IPMT", "IRR",
     "ISBLANK", "ISERROR", "ISNA", "ISNUMBER", "ISTEXT",
     "LEFT", "LEN", "LN", "LOG", "LOG10", "LOWER",
-    "MATCH", "MAX", "MEDIAN", "MID", "MIN", "MINUTE", "MOD", "MONTH",
+    "MATCH", "MAX", "MEDIAN", "MID", "MIN", "MINUTE", "MMULT", "MOD", "MONTH", "MROUND",
     "NORM.S.DIST", "NORMSDIST", "NOT", "NOW", "NPV",
     "OR",
-    "PI", "PMT", "POWER", "PPMT", "PRODUCT", "PV",
-    "RADIANS", "RAND", "RANDBETWEEN", "REPT", "RIGHT", "ROUND", "ROUNDDOWN", "ROUNDUP", "ROW", "ROWS",
-    "SECOND", "SEQUENCE", "SIN", "SORT", "SPARKLINE", "SQRT", "STDEV", "SUBSTITUTE", "SUM", "SUMIF", "SUMIFS", "SUMPRODUCT", "SWITCH",
+    "PERCENTILE", "PI", "PMT", "POWER", "PPMT", "PRODUCT", "PV",
+    "RADIANS", "RAND", "RANDBETWEEN", "RANK", "REPT", "RIGHT", "ROUND", "ROUNDDOWN", "ROUNDUP", "ROW", "ROWS",
+    "SECOND", "SEQUENCE", "SIN", "SORT", "SPARKLINE", "SQRT", "STDEV", "STDEV.P", "STDEV.S", "STDEVP", "SUBSTITUTE", "SUM", "SUMIF", "SUMIFS", "SUMPRODUCT", "SWITCH",
     "TAN", "TEXT", "TEXTJOIN", "TODAY", "TRANSPOSE", "TRIM", "TRUNC",
     "UNIQUE", "UPPER",
-    "VALUE", "VAR", "VLOOKUP",
+    "VALUE", "VAR", "VAR.P", "VAR.S", "VARP", "VLOOKUP",
     "WEEKDAY",
     "XLOOKUP",
     "YEAR",
 ];
 
+/// Functions whose result can change without any input changing, sorted
+/// alphabetically. Replay verification, fingerprinting, and the UI all read
+/// this list; add new volatile functions here.
+const VOLATILE_FUNCTIONS: &[&str] = &["NOW", "RAND", "RANDBETWEEN", "TODAY"];
+
+/// What a function is for, to group listings by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FunctionCategory {
+    Math,
+    Statistical,
+    Conditional,
+    Logical,
+    Lookup,
+    Text,
+    DateTime,
+    Financial,
+    Trigonometry,
+    Array,
+}
+
+impl FunctionCategory {
+    /// Every category, in listing order.
+    pub const ALL: &'static [FunctionCategory] = &[
+        FunctionCategory::Math,
+        FunctionCategory::Statistical,
+        FunctionCategory::Conditional,
+        FunctionCategory::Logical,
+        FunctionCategory::Lookup,
+        FunctionCategory::Text,
+        FunctionCategory::DateTime,
+        FunctionCategory::Financial,
+        FunctionCategory::Trigonometry,
+        FunctionCategory::Array,
+    ];
+
+    /// Stable snake_case name, for JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FunctionCategory::Math => "math",
+            FunctionCategory::Statistical => "statistical",
+            FunctionCategory::Conditional => "conditional",
+            FunctionCategory::Logical => "logical",
+            FunctionCategory::Lookup => "lookup",
+            FunctionCategory::Text => "text",
+            FunctionCategory::DateTime => "date_time",
+            FunctionCategory::Financial => "financial",
+            FunctionCategory::Trigonometry => "trigonometry",
+            FunctionCategory::Array => "array",
+        }
+    }
+
+    /// Heading for human-readable listings.
+    pub fn label(self) -> &'static str {
+        match self {
+            FunctionCategory::Math => "Math",
+            FunctionCategory::Statistical => "Statistical",
+            FunctionCategory::Conditional => "Conditional",
+            FunctionCategory::Logical => "Logical",
+            FunctionCategory::Lookup => "Lookup & reference",
+            FunctionCategory::Text => "Text",
+            FunctionCategory::DateTime => "Date & time",
+            FunctionCategory::Financial => "Financial",
+            FunctionCategory::Trigonometry => "Trigonometry",
+            FunctionCategory::Array => "Array",
+        }
+    }
+}
+
+/// Documentation for one built-in function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionMeta {
+    pub name: &'static str,
+    pub category: FunctionCategory,
+    /// Fewest arguments the evaluator accepts.
+    pub min_args: usize,
+    /// Most arguments the evaluator accepts; `None` for variadic functions.
+    pub max_args: Option<usize>,
+    /// One sentence, ending in a period.
+    pub description: &'static str,
+}
+
+impl FunctionMeta {
+    pub fn is_volatile(&self) -> bool {
+        is_volatile(self.name)
+    }
+}
+
+const fn meta(
+    name: &'static str,
+    category: FunctionCategory,
+    min_args: usize,
+    max_args: Option<usize>,
+    description: &'static str,
+) -> FunctionMeta {
+    FunctionMeta { name, category, min_args, max_args, description }
+}
+
+/// Metadata for every name in `FUNCTION_NAMES`, in the same order. Arity
+/// mirrors the argument checks in the evaluator.
+const FUNCTION_META: &[FunctionMeta] = &[
+    meta("ABS", FunctionCategory::Math, 1, Some(1), "Returns the absolute value of a number."),
+    meta("ACOS", FunctionCategory::Trigonometry, 1, Some(1), "Returns the arccosine of a number (in radians)."),
+    meta("AND", FunctionCategory::Logical, 1, None, "Returns TRUE if all arguments are TRUE."),
+    meta("ASIN", FunctionCategory::Trigonometry, 1, Some(1), "Returns the arcsine of a number (in radians)."),
+    meta("ATAN", FunctionCategory::Trigonometry, 1, Some(1), "Returns the arctangent of a number (in radians)."),
+    meta("ATAN2", FunctionCategory::Trigonometry, 2, Some(2), "Returns the arctangent from x and y coordinates."),
+    meta("AVERAGE", FunctionCategory::Math, 1, None, "Returns the average of the arguments."),
+    meta("AVERAGEIF", FunctionCategory::Conditional, 2, Some(3), "Returns the average of the cells in a range that meet a condition."),
+    meta("AVERAGEIFS", FunctionCategory::Conditional, 3, None, "Returns the average of the cells that meet multiple conditions."),
+    meta("AVG", FunctionCategory::Math, 1, None, "Alias for AVERAGE: returns the average of the arguments."),
+    meta("CEILING", FunctionCategory::Math, 1, Some(1), "Rounds a number up to the nearest integer."),
+    meta("CHOOSE", FunctionCategory::Logical, 2, None, "Chooses a value from a list based on an index number."),
+    meta("COLUMN", FunctionCategory::Lookup, 0, Some(1), "Returns the column number of a reference."),
+    meta("COLUMNS", FunctionCategory::Lookup, 1, Some(1), "Returns the number of columns in a reference."),
+    meta("CONCAT", FunctionCategory::Text, 1, None, "Joins text strings (modern version of CONCATENATE)."),
+    meta("CONCATENATE", FunctionCategory::Text, 1, None, "Joins several text strings into one string."),
+    meta("COS", FunctionCategory::Trigonometry, 1, Some(1), "Returns the cosine of an angle (in radians)."),
+    meta("COUNT", FunctionCategory::Math, 1, None, "Counts the number of cells that contain numbers."),
+    meta("COUNTA", FunctionCategory::Math, 1, None, "Counts the number of non-empty cells."),
+    meta("COUNTBLANK", FunctionCategory::Conditional, 1, Some(1), "Counts the number of blank cells in a range."),
+    meta("COUNTIF", FunctionCategory::Conditional, 2, Some(2), "Counts cells that meet a criteria."),
+    meta("COUNTIFS", FunctionCategory::Conditional, 2, None, "Counts the cells that meet multiple conditions."),
+    meta("CUMIPMT", FunctionCategory::Financial, 6, Some(6), "Returns the cumulative interest paid on a loan between two periods."),
+    meta("CUMPRINC", FunctionCategory::Financial, 6, Some(6), "Returns the cumulative principal paid on a loan between two periods."),
+    meta("DATE", FunctionCategory::DateTime, 3, Some(3), "Creates a date from year, month, and day."),
+    meta("DATEDIF", FunctionCategory::DateTime, 3, Some(3), "Calculates the difference between two dates."),
+    meta("DAY", FunctionCategory::DateTime, 1, Some(1), "Returns the day from a date."),
+    meta("DEGREES", FunctionCategory::Trigonometry, 1, Some(1), "Converts radians to degrees."),
+    meta("EDATE", FunctionCategory::DateTime, 2, Some(2), "Returns a date a specified number of months away."),
+    meta("EOMONTH", FunctionCategory::DateTime, 2, Some(2), "Returns the last day of a month a specified number of months away."),
+    meta("EXP", FunctionCategory::Math, 1, Some(1), "Returns e raised to the power of a number."),
+    meta("FILTER", FunctionCategory::Array, 2, Some(2), "Filters a range based on criteria."),
+    meta("FIND", FunctionCategory::Text, 2, Some(3), "Finds one text string within another (case-sensitive)."),
+    meta("FLOOR", FunctionCategory::Math, 1, Some(1), "Rounds a number down to the nearest integer."),
+    meta("FV", FunctionCategory::Financial, 3, Some(5), "Returns the future value of an investment."),
+    meta("HLOOKUP", FunctionCategory::Lookup, 3, Some(4), "Looks for a value in the top row and returns a value in the same column."),
+    meta("HOUR", FunctionCategory::DateTime, 1, Some(1), "Returns the hour from a time value."),
+    meta("IF", FunctionCategory::Logical, 2, Some(3), "Returns one value if a condition is true and another if false."),
+    meta("IFERROR", FunctionCategory::Logical, 2, Some(2), "Returns a value if an expression results in an error."),
+    meta("IFNA", FunctionCategory::Logical, 2, Some(2), "Returns a fallback value if the expression is #N/A, otherwise the expression's result."),
+    meta("IFS", FunctionCategory::Logical, 2, None, "Checks multiple conditions and returns the first true result."),
+    meta("INDEX", FunctionCategory::Lookup, 2, Some(3), "Returns the value at a given position in a range."),
+    meta("INT", FunctionCategory::Math, 1, Some(1), "Rounds a number down to the nearest integer."),
+    meta("IPMT", FunctionCategory::Financial, 4, Some(6), "Returns the interest portion of a loan payment for a given period."),
+    meta("IRR", FunctionCategory::Financial, 1, Some(2), "Returns the internal rate of return for a series of cash flows."),
+    meta("ISBLANK", FunctionCategory::Logical, 1, Some(1), "Returns TRUE if the value is blank."),
+    meta("ISERROR", FunctionCategory::Logical, 1, Some(1), "Returns TRUE if the value is an error."),
+    meta("ISNA", FunctionCategory::Logical, 1, Some(1), "Returns TRUE if the value is the #N/A error."),
+    meta("ISNUMBER", FunctionCategory::Logical, 1, Some(1), "Returns TRUE if the value is a number."),
+    meta("ISTEXT", FunctionCategory::Logical, 1, Some(1), "Returns TRUE if the value is text."),
+    meta("LEFT", FunctionCategory::Text, 1, Some(2), "Returns the leftmost characters from a text string."),
+    meta("LEN", FunctionCategory::Text, 1, Some(1), "Returns the number of characters in a text string."),
+    meta("LN", FunctionCategory::Math, 1, Some(1), "Returns the natural logarithm of a number."),
+    meta("LOG", FunctionCategory::Math, 1, Some(2), "Returns the logarithm of a number to a specified base."),
+    meta("LOG10", FunctionCategory::Math, 1, Some(1), "Returns the base-10 logarithm of a number."),
+    meta("LOWER", FunctionCategory::Text, 1, Some(1), "Converts text to lowercase."),
+    meta("MATCH", FunctionCategory::Lookup, 2, Some(3), "Returns the position of a value in a range."),
+    meta("MAX", FunctionCategory::Math, 1, None, "Returns the largest value in a set of values."),
+    meta("MEDIAN", FunctionCategory::Math, 1, None, "Returns the median of the given numbers."),
+    meta("MID", FunctionCategory::Text, 3, Some(3), "Returns characters from the middle of a text string."),
+    meta("MIN", FunctionCategory::Math, 1, None, "Returns the smallest value in a set of values."),
+    meta("MINUTE", FunctionCategory::DateTime, 1, Some(1), "Returns the minute from a time value."),
+    meta("MMULT", FunctionCategory::Array, 2, Some(2), "Returns the matrix product of two arrays."),
+    meta("MOD", FunctionCategory::Math, 2, Some(2), "Returns the remainder after division."),
+    meta("MONTH", FunctionCategory::DateTime, 1, Some(1), "Returns the month from a date."),
+    meta("MROUND", FunctionCategory::Math, 2, Some(2), "Rounds a number to the nearest multiple."),
+    meta("NORM.S.DIST", FunctionCategory::Statistical, 1, Some(2), "Returns the standard normal distribution."),
+    meta("NORMSDIST", FunctionCategory::Statistical, 1, Some(1), "Returns the standard normal cumulative distribution."),
+    meta("NOT", FunctionCategory::Logical, 1, Some(1), "Reverses the logic of its argument."),
+    meta("NOW", FunctionCategory::DateTime, 0, Some(0), "Returns the current date and time."),
+    meta("NPV", FunctionCategory::Financial, 2, None, "Returns the net present value of cash flows at a discount rate."),
+    meta("OR", FunctionCategory::Logical, 1, None, "Returns TRUE if any argument is TRUE."),
+    meta("PERCENTILE", FunctionCategory::Statistical, 2, Some(2), "Returns the k-th percentile of the values in a range."),
+    meta("PI", FunctionCategory::Trigonometry, 0, Some(0), "Returns the value of pi (3.14159...)."),
+    meta("PMT", FunctionCategory::Financial, 3, Some(5), "Returns the periodic payment for a loan."),
+    meta("POWER", FunctionCategory::Math, 2, Some(2), "Returns the result of a number raised to a power."),
+    meta("PPMT", FunctionCategory::Financial, 4, Some(6), "Returns the principal portion of a loan payment for a given period."),
+    meta("PRODUCT", FunctionCategory::Math, 1, None, "Multiplies all the numbers given as arguments."),
+    meta("PV", FunctionCategory::Financial, 3, Some(5), "Returns the present value of an investment."),
+    meta("RADIANS", FunctionCategory::Trigonometry, 1, Some(1), "Converts degrees to radians."),
+    meta("RAND", FunctionCategory::Math, 0, Some(0), "Returns a random number between 0 and 1."),
+    meta("RANDBETWEEN", FunctionCategory::Math, 2, Some(2), "Returns a random integer between two numbers."),
+    meta("RANK", FunctionCategory::Statistical, 2, Some(3), "Returns the rank of a number in a list of numbers."),
+    meta("REPT", FunctionCategory::Text, 2, Some(2), "Repeats text a given number of times."),
+    meta("RIGHT", FunctionCategory::Text, 1, Some(2), "Returns the rightmost characters from a text string."),
+    meta("ROUND", FunctionCategory::Math, 1, Some(2), "Rounds a number to a specified number of digits."),
+    meta("ROUNDDOWN", FunctionCategory::Math, 1, Some(2), "Rounds a number toward zero."),
+    meta("ROUNDUP", FunctionCategory::Math, 1, Some(2), "Rounds a number away from zero."),
+    meta("ROW", FunctionCategory::Lookup, 0, Some(1), "Returns the row number of a reference."),
+    meta("ROWS", FunctionCategory::Lookup, 1, Some(1), "Returns the number of rows in a reference."),
+    meta("SECOND", FunctionCategory::DateTime, 1, Some(1), "Returns the second from a time value."),
+    meta("SEQUENCE", FunctionCategory::Array, 1, Some(4), "Generates a sequence of numbers."),
+    meta("SIN", FunctionCategory::Trigonometry, 1, Some(1), "Returns the sine of an angle (in radians)."),
+    meta("SORT", FunctionCategory::Array, 1, Some(3), "Sorts the contents of a range or array."),
+    meta("SPARKLINE", FunctionCategory::Array, 1, Some(2), "Draws a small in-cell chart of a data range."),
+    meta("SQRT", FunctionCategory::Math, 1, Some(1), "Returns the square root of a number."),
+    meta("STDEV", FunctionCategory::Statistical, 1, None, "Estimates standard deviation based on a sample."),
+    meta("STDEV.P", FunctionCategory::Statistical, 1, None, "Calculates standard deviation based on the entire population."),
+    meta("STDEV.S", FunctionCategory::Statistical, 1, None, "Estimates standard deviation based on a sample."),
+    meta("STDEVP", FunctionCategory::Statistical, 1, None, "Calculates standard deviation based on the entire population."),
+    meta("SUBSTITUTE", FunctionCategory::Text, 3, Some(4), "Replaces old text with new text in a string."),
+    meta("SUM", FunctionCategory::Math, 1, None, "Adds all the numbers in a range of cells."),
+    meta("SUMIF", FunctionCategory::Conditional, 2, Some(3), "Sums cells that meet a criteria."),
+    meta("SUMIFS", FunctionCategory::Conditional, 3, None, "Adds the cells that meet multiple conditions."),
+    meta("SUMPRODUCT", FunctionCategory::Math, 1, None, "Multiplies corresponding cells in the ranges and returns the sum of the products."),
+    meta("SWITCH", FunctionCategory::Logical, 3, None, "Evaluates an expression against a list of values."),
+    meta("TAN", FunctionCategory::Trigonometry, 1, Some(1), "Returns the tangent of an angle (in radians)."),
+    meta("TEXT", FunctionCategory::Text, 2, Some(2), "Formats a number as text with a specified format."),
+    meta("TEXTJOIN", FunctionCategory::Text, 3, None, "Joins text from ranges with a delimiter, optionally skipping empty cells."),
+    meta("TODAY", FunctionCategory::DateTime, 0, Some(0), "Returns the current date."),
+    meta("TRANSPOSE", FunctionCategory::Array, 1, Some(1), "Transposes the rows and columns of an array."),
+    meta("TRIM", FunctionCategory::Text, 1, Some(1), "Removes extra spaces from text."),
+    meta("TRUNC", FunctionCategory::Math, 1, Some(2), "Truncates a number to an integer or to a number of decimal places."),
+    meta("UNIQUE", FunctionCategory::Array, 1, Some(1), "Returns the unique values from a range."),
+    meta("UPPER", FunctionCategory::Text, 1, Some(1), "Converts text to uppercase."),
+    meta("VALUE", FunctionCategory::Text, 1, Some(1), "Converts a text string to a number."),
+    meta("VAR", FunctionCategory::Statistical, 1, None, "Estimates variance based on a sample."),
+    meta("VAR.P", FunctionCategory::Statistical, 1, None, "Calculates variance based on the entire population."),
+    meta("VAR.S", FunctionCategory::Statistical, 1, None, "Estimates variance based on a sample."),
+    meta("VARP", FunctionCategory::Statistical, 1, None, "Calculates variance based on the entire population."),
+    meta("VLOOKUP", FunctionCategory::Lookup, 3, Some(4), "Looks for a value in the leftmost column and returns a value in the same row."),
+    meta("WEEKDAY", FunctionCategory::DateTime, 1, Some(2), "Returns the day of the week from a date."),
+    meta("XLOOKUP", FunctionCategory::Lookup, 3, Some(6), "Searches a range for a match and returns the corresponding item from a second range."),
+    meta("YEAR", FunctionCategory::DateTime, 1, Some(1), "Returns the year from a date."),
+];
+
 /// Returns all supported function names, sorted alphabetically.
 pub fn list_functions() -> &'static [&'static str] {
     FUNCTION_NAMES
 }
 
+/// Returns metadata for all supported functions, sorted by name.
+pub fn list_function_meta() -> &'static [FunctionMeta] {
+    FUNCTION_META
+}
+
+/// Metadata for a built-in function. Case-insensitive.
+pub fn function_meta(name: &str) -> Option<&'static FunctionMeta> {
+    let upper = name.to_ascii_uppercase();
+    FUNCTION_META
+        .binary_search_by(|m| m.name.cmp(upper.as_str()))
+        .ok()
+        .map(|i| &FUNCTION_META[i])
+}
+
 /// Check if a function name is a known built-in function.
 pub fn is_known_function(name: &str) -> bool {
     FUNCTION_NAMES.binary_search(&name).is_ok()
 }
 
+/// Returns the volatile (nondeterministic) function names, sorted alphabetically.
+pub fn list_volatile_functions() -> &'static [&'static str] {
+    VOLATILE_FUNCTIONS
+}
+
+/// Check if a function is volatile: its result may differ between two
+/// evaluations of the same inputs (NOW, RAND, ...). Case-insensitive.
+pub fn is_volatile(name: &str) -> bool {
+    VOLATILE_FUNCTIONS.iter().any(|f| f.eq_ignore_ascii_case(name))
+}
+
 /// Check if a name is valid for a user-defined custom function.
 /// Must be non-empty, start with uppercase, and contain only uppercase + digits + underscores.
 pub fn is_valid_custom_function_name(name: &str) -> bool {
@@ -111,3 +362,46 @@ pub fn max(args: &[EvalResult]) -> EvalResult {
     }
     result.map(EvalResult::Number).unwrap_or(EvalResult::Number(0.0))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volatile_functions() {
+        assert!(is_volatile("NOW"));
+        assert!(is_volatile("RAND"));
+        assert!(is_volatile("rand"));
+        assert!(!is_volatile("SUM"));
+        assert!(!is_volatile("RANDOM"));
+        // Every volatile function is a known built-in, and the list stays sorted
+        assert!(VOLATILE_FUNCTIONS.iter().all(|f| is_known_function(f)));
+        assert!(VOLATILE_FUNCTIONS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_every_function_has_meta() {
+        let names: Vec<&str> = FUNCTION_META.iter().map(|m| m.name).collect();
+        assert_eq!(names, FUNCTION_NAMES, "FUNCTION_META must list FUNCTION_NAMES in order");
+        for m in FUNCTION_META {
+            assert!(m.max_args.unwrap_or(usize::MAX) >= m.min_args, "{}: max < min", m.name);
+            assert!(m.description.ends_with('.'), "{}: description should be a sentence", m.name);
+        }
+    }
+
+    #[test]
+    fn test_function_meta_lookup() {
+        let vlookup = function_meta("vlookup").unwrap();
+        assert_eq!(vlookup.category, FunctionCategory::Lookup);
+        assert_eq!((vlookup.min_args, vlookup.max_args), (3, Some(4)));
+
+        let sum = function_meta("SUM").unwrap();
+        assert_eq!(sum.category.as_str(), "math");
+        assert_eq!((sum.min_args, sum.max_args), (1, None));
+
+        assert!(function_meta("NOW").unwrap().is_volatile());
+        assert!(function_meta("NOPE").is_none());
+        // Every category is used, and ALL covers each one
+        assert!(FunctionCategory::ALL.iter().all(|c| FUNCTION_META.iter().any(|m| m.category == *c)));
+    }
+}
@@ -5,6 +5,7 @@ pub mod eval;
 pub mod functions;
 pub mod analyze;
 pub mod refs;
+pub mod rounding;
 
 pub(crate) mod eval_helpers;
 pub(crate) mod eval_math;
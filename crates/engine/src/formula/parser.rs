@@ -1,6 +1,7 @@
 // Formula parser - converts formula strings into AST
 // Supports: numbers, cell refs (A1), ranges (A1:A5), functions (SUM), basic math (+, -, *, /)
 // Also supports: comparison operators (<, >, =, <=, >=, <>), string literals, concatenation (&)
+// Also supports: array constants ({1,2,3} and {1,2;3,4})
 
 use crate::sheet::{SheetId, SheetRef, UnboundSheetRef};
 
@@ -44,6 +45,19 @@ pub enum Expr<S> {
     },
     /// Named range reference (resolved at evaluation time)
     NamedRange(String),
+    /// Array constant (e.g. `{1,2;3,4}`), stored row-major.
+    /// Elements are literals only (Number, Text, Boolean); rows are equal length.
+    Array(Vec<Vec<Expr<S>>>),
+    /// Spill-range reference (`A1#`): the whole array spilled from the anchor cell
+    SpillRef {
+        sheet: S,
+        col: usize,
+        row: usize,
+        col_abs: bool,
+        row_abs: bool,
+    },
+    /// Implicit intersection (`@X`): coerce a range or array to a single value
+    ImplicitIntersection(Box<Expr<S>>),
     /// Empty/omitted argument (e.g. the trailing slot in `=IF(a,b,)`)
     Empty,
 }
@@ -112,6 +126,13 @@ enum Token {
     RParen,
     Colon,
     Comma,
+    // Array constants
+    LBrace,
+    RBrace,
+    Semicolon,
+    // Dynamic-array operators
+    At,   // @ (implicit intersection)
+    Hash, // # (spill range, after a cell reference)
     // Comparison operators
     Lt,      // <
     Gt,      // >
@@ -141,6 +162,11 @@ fn tokenize(input: &str) -> Result<Vec<Token>, String> {
             ')' => { tokens.push(Token::RParen); chars.next(); }
             ':' => { tokens.push(Token::Colon); chars.next(); }
             ',' => { tokens.push(Token::Comma); chars.next(); }
+            '{' => { tokens.push(Token::LBrace); chars.next(); }
+            '}' => { tokens.push(Token::RBrace); chars.next(); }
+            ';' => { tokens.push(Token::Semicolon); chars.next(); }
+            '@' => { tokens.push(Token::At); chars.next(); }
+            '#' => { tokens.push(Token::Hash); chars.next(); }
             '&' => { tokens.push(Token::Ampersand); chars.next(); }
             '^' => { tokens.push(Token::Caret); chars.next(); }
             '%' => { tokens.push(Token::Percent); chars.next(); }
@@ -544,6 +570,9 @@ fn parse_primary(tokens: &[Token], pos: usize) -> Result<(ParsedExpr, usize), St
                             }
                         }
                     }
+                    if let Some(Token::Hash) = tokens.get(pos + 2) {
+                        return Ok((Expr::SpillRef { sheet, col: *col, row: *row, col_abs: *col_abs, row_abs: *row_abs }, pos + 3));
+                    }
                     Ok((Expr::CellRef { sheet, col: *col, row: *row, col_abs: *col_abs, row_abs: *row_abs }, pos + 2))
                 }
                 _ => Err("Sheet reference must be followed by cell reference".to_string()),
@@ -571,6 +600,10 @@ fn parse_primary(tokens: &[Token], pos: usize) -> Result<(ParsedExpr, usize), St
                     }
                 }
             }
+            // Spill range (A1#)
+            if let Some(Token::Hash) = tokens.get(pos + 1) {
+                return Ok((Expr::SpillRef { sheet: UnboundSheetRef::Current, col: *col, row: *row, col_abs: *col_abs, row_abs: *row_abs }, pos + 2));
+            }
             Ok((Expr::CellRef { sheet: UnboundSheetRef::Current, col: *col, row: *row, col_abs: *col_abs, row_abs: *row_abs }, pos + 1))
         }
         Token::Ident(name) => {
@@ -607,6 +640,12 @@ fn parse_primary(tokens: &[Token], pos: usize) -> Result<(ParsedExpr, usize), St
                 _ => Err("Expected closing parenthesis".to_string()),
             }
         }
+        Token::LBrace => parse_array_constant(tokens, pos + 1),
+        Token::At => {
+            // Implicit intersection applies to the operand that follows (@A1:A10, @F(x))
+            let (expr, pos) = parse_primary(tokens, pos + 1)?;
+            Ok((Expr::ImplicitIntersection(Box::new(expr)), pos))
+        }
         Token::Plus => {
             // Unary plus (no-op, just parse the next expression)
             parse_primary(tokens, pos + 1)
@@ -627,6 +666,63 @@ fn parse_primary(tokens: &[Token], pos: usize) -> Result<(ParsedExpr, usize), St
     }
 }
 
+/// Parse the body of an array constant after `{`: comma separates columns,
+/// semicolon separates rows. Every row must have the same number of columns.
+fn parse_array_constant(tokens: &[Token], pos: usize) -> Result<(ParsedExpr, usize), String> {
+    let mut rows: Vec<Vec<ParsedExpr>> = Vec::new();
+    let mut row = Vec::new();
+    let mut pos = pos;
+
+    loop {
+        let (element, new_pos) = parse_array_element(tokens, pos)?;
+        row.push(element);
+        pos = new_pos;
+
+        if pos >= tokens.len() {
+            return Err("Missing closing brace in array constant".to_string());
+        }
+
+        match &tokens[pos] {
+            Token::Comma => pos += 1,
+            Token::Semicolon | Token::RBrace => {
+                if let Some(first) = rows.first() {
+                    if first.len() != row.len() {
+                        return Err("Array constant rows must have the same number of columns".to_string());
+                    }
+                }
+                rows.push(std::mem::take(&mut row));
+                let done = matches!(&tokens[pos], Token::RBrace);
+                pos += 1;
+                if done {
+                    return Ok((Expr::Array(rows), pos));
+                }
+            }
+            _ => return Err("Expected comma, semicolon, or closing brace in array constant".to_string()),
+        }
+    }
+}
+
+/// Parse a single array constant element: a number (optionally signed),
+/// a string literal, or TRUE/FALSE. References and expressions are not allowed.
+fn parse_array_element(tokens: &[Token], pos: usize) -> Result<(ParsedExpr, usize), String> {
+    match tokens.get(pos) {
+        Some(Token::Number(n)) => Ok((Expr::Number(*n), pos + 1)),
+        Some(Token::StringLit(s)) => Ok((Expr::Text(s.clone()), pos + 1)),
+        Some(Token::Ident(name)) if name == "TRUE" => Ok((Expr::Boolean(true), pos + 1)),
+        Some(Token::Ident(name)) if name == "FALSE" => Ok((Expr::Boolean(false), pos + 1)),
+        Some(Token::Minus) => match tokens.get(pos + 1) {
+            Some(Token::Number(n)) => Ok((Expr::Number(-*n), pos + 2)),
+            _ => Err("Array constants may only contain numbers, text, or booleans".to_string()),
+        },
+        Some(Token::Plus) => match tokens.get(pos + 1) {
+            Some(Token::Number(n)) => Ok((Expr::Number(*n), pos + 2)),
+            _ => Err("Array constants may only contain numbers, text, or booleans".to_string()),
+        },
+        None => Err("Missing closing brace in array constant".to_string()),
+        _ => Err("Array constants may only contain numbers, text, or booleans".to_string()),
+    }
+}
+
 fn parse_function_args(tokens: &[Token], pos: usize) -> Result<(Vec<ParsedExpr>, usize), String> {
     let mut args = Vec::new();
     let mut pos = pos;
@@ -722,6 +818,23 @@ where
                 right: Box::new(bind_expr(right, resolver)),
             }
         }
+        Expr::Array(rows) => {
+            Expr::Array(rows.iter()
+                .map(|row| row.iter().map(|el| bind_expr(el, resolver)).collect())
+                .collect())
+        }
+        Expr::SpillRef { sheet, col, row, col_abs, row_abs } => {
+            Expr::SpillRef {
+                sheet: bind_sheet_ref(sheet, resolver),
+                col: *col,
+                row: *row,
+                col_abs: *col_abs,
+                row_abs: *row_abs,
+            }
+        }
+        Expr::ImplicitIntersection(inner) => {
+            Expr::ImplicitIntersection(Box::new(bind_expr(inner, resolver)))
+        }
     }
 }
 
@@ -818,6 +931,23 @@ where
             };
             format!("{}{}{}", left_str, op_str, right_str)
         }
+        Expr::Array(rows) => {
+            let rows_str: Vec<String> = rows.iter()
+                .map(|row| row.iter()
+                    .map(|el| format_expr_inner(el, name_resolver))
+                    .collect::<Vec<_>>()
+                    .join(","))
+                .collect();
+            format!("{{{}}}", rows_str.join(";"))
+        }
+        Expr::SpillRef { sheet, col, row, col_abs, row_abs } => {
+            let prefix = format_sheet_prefix(sheet, name_resolver);
+            let addr = format_cell_addr(*col, *row, *col_abs, *row_abs);
+            format!("{}{}#", prefix, addr)
+        }
+        Expr::ImplicitIntersection(inner) => {
+            format!("@{}", format_expr_inner(inner, name_resolver))
+        }
     }
 }
 
@@ -903,12 +1033,16 @@ pub fn extract_cell_refs<S>(expr: &Expr<S>) -> Vec<(usize, usize)> {
 
 fn collect_cell_refs<S>(expr: &Expr<S>, refs: &mut Vec<(usize, usize)>) {
     match expr {
-        Expr::Number(_) | Expr::Text(_) | Expr::Boolean(_) | Expr::NamedRange(_) | Expr::Empty => {
+        Expr::Number(_) | Expr::Text(_) | Expr::Boolean(_) | Expr::NamedRange(_) | Expr::Array(_) | Expr::Empty => {
             // NamedRange refs are resolved at evaluation time with access to NamedRangeStore
         }
-        Expr::CellRef { col, row, .. } => {
+        Expr::CellRef { col, row, .. } | Expr::SpillRef { col, row, .. } => {
+            // A spill range depends on its anchor, which owns the spilled values
             refs.push((*row, *col));
         }
+        Expr::ImplicitIntersection(inner) => {
+            collect_cell_refs(inner, refs);
+        }
         Expr::Range { start_col, start_row, end_col, end_row, .. } => {
             // Expand range to individual cells
             for r in *start_row..=*end_row {
@@ -1387,4 +1521,124 @@ mod tests {
             _ => panic!("Expected Mul op, got {:?}", expr),
         }
     }
+
+    // ── Array constant tests ─────────────────────────────────────
+
+    #[test]
+    fn test_array_constant_1d() {
+        let expr = parse("={1,2,3}").unwrap();
+        match &expr {
+            Expr::Array(rows) => {
+                assert_eq!(rows.len(), 1);
+                assert_eq!(rows[0].len(), 3);
+                assert!(matches!(rows[0][2], Expr::Number(n) if n == 3.0));
+            }
+            _ => panic!("Expected Array, got {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn test_array_constant_2d() {
+        let expr = parse("={1,2;3,4}").unwrap();
+        match &expr {
+            Expr::Array(rows) => {
+                assert_eq!(rows.len(), 2);
+                assert!(rows.iter().all(|r| r.len() == 2));
+                assert!(matches!(rows[1][0], Expr::Number(n) if n == 3.0));
+            }
+            _ => panic!("Expected Array, got {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn test_array_constant_mixed_literals() {
+        let expr = parse("={-1,\"a\";TRUE,+2.5}").unwrap();
+        match &expr {
+            Expr::Array(rows) => {
+                assert!(matches!(rows[0][0], Expr::Number(n) if n == -1.0));
+                assert!(matches!(&rows[0][1], Expr::Text(s) if s == "a"));
+                assert!(matches!(rows[1][0], Expr::Boolean(true)));
+                assert!(matches!(rows[1][1], Expr::Number(n) if n == 2.5));
+            }
+            _ => panic!("Expected Array, got {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn test_array_constant_as_function_arg() {
+        let expr = parse("=SUM({1,2,3},A1)").unwrap();
+        match &expr {
+            Expr::Function { name, args } => {
+                assert_eq!(name, "SUM");
+                assert_eq!(args.len(), 2);
+                assert!(matches!(&args[0], Expr::Array(_)));
+            }
+            _ => panic!("Expected Function, got {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn test_array_constant_errors() {
+        assert!(parse("={1,2;3}").is_err(), "ragged rows");
+        assert!(parse("={1,2").is_err(), "unterminated");
+        assert!(parse("={A1,2}").is_err(), "references not allowed");
+        assert!(parse("={}").is_err(), "empty array");
+    }
+
+    #[test]
+    fn test_array_constant_roundtrip() {
+        let parsed = parse("=MMULT({1,2;3,4},{-1;\"x\"})").unwrap();
+        let bound = bind_expr_same_sheet(&parsed);
+        assert_eq!(format_expr(&bound, |_| None), "=MMULT({1,2;3,4},{-1;\"x\"})");
+    }
+
+    // =========================================================================
+    // Dynamic-array operators: A1# spill range and @ implicit intersection
+    // =========================================================================
+
+    #[test]
+    fn test_parse_spill_ref() {
+        match parse("=SUM($B$2#)").unwrap() {
+            Expr::Function { args, .. } => match &args[0] {
+                Expr::SpillRef { sheet: UnboundSheetRef::Current, col, row, col_abs, row_abs } => {
+                    assert_eq!((*row, *col), (1, 1));
+                    assert!(*col_abs && *row_abs);
+                }
+                other => panic!("Expected SpillRef, got {:?}", other),
+            },
+            other => panic!("Expected Function, got {:?}", other),
+        }
+        match parse("=Data!A1#").unwrap() {
+            Expr::SpillRef { sheet: UnboundSheetRef::Named(name), .. } => assert_eq!(name, "Data"),
+            other => panic!("Expected cross-sheet SpillRef, got {:?}", other),
+        }
+        assert!(parse("=#").is_err(), "# needs an anchor cell");
+    }
+
+    #[test]
+    fn test_parse_implicit_intersection() {
+        match parse("=@A1:A10*2").unwrap() {
+            Expr::BinaryOp { op: Op::Mul, left, .. } => match left.as_ref() {
+                Expr::ImplicitIntersection(inner) => assert!(matches!(inner.as_ref(), Expr::Range { .. })),
+                other => panic!("Expected ImplicitIntersection, got {:?}", other),
+            },
+            other => panic!("Expected Mul, got {:?}", other),
+        }
+        match parse("=@INDEX(A1:B2,1,1)").unwrap() {
+            Expr::ImplicitIntersection(inner) => assert!(matches!(inner.as_ref(), Expr::Function { .. })),
+            other => panic!("Expected ImplicitIntersection, got {:?}", other),
+        }
+        assert!(parse("=@").is_err());
+    }
+
+    #[test]
+    fn test_dynamic_array_operators_roundtrip() {
+        for formula in ["=SUM(A1#)", "=@A1:A10+'My Sheet'!$C$3#", "=-@B2#"] {
+            let parsed = parse(formula).unwrap();
+            let bound = bind_expr(&parsed, |name| (name == "My Sheet").then_some(SheetId::from_raw(2)));
+            let formatted = format_expr(&bound, |id| (id == SheetId::from_raw(2)).then(|| "My Sheet".to_string()));
+            let expected = formula.replace("=-@B2#", "=0-@B2#");
+            assert_eq!(formatted, expected);
+        }
+    }
 }
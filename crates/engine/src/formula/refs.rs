@@ -54,8 +54,8 @@ fn collect_refs<F>(
     use super::parser::Expr;
 
     match expr {
-        Expr::Number(_) | Expr::Text(_) | Expr::Boolean(_) | Expr::Empty => {
-            // Literals have no dependencies
+        Expr::Number(_) | Expr::Text(_) | Expr::Boolean(_) | Expr::Array(_) | Expr::Empty => {
+            // Literals (including array constants) have no dependencies
         }
 
         Expr::CellRef { sheet, row, col, .. } => {
@@ -65,6 +65,17 @@ fn collect_refs<F>(
             // If SheetRef::RefError, skip (formula will error anyway)
         }
 
+        Expr::SpillRef { sheet, row, col, .. } => {
+            // The spilled values are produced by the anchor, so depend on it alone
+            if let Some(sheet_id) = resolve_sheet_ref(sheet, context_sheet) {
+                refs.insert(CellId::new(sheet_id, *row, *col));
+            }
+        }
+
+        Expr::ImplicitIntersection(inner) => {
+            collect_refs(inner, context_sheet, named_ranges, sheet_id_at_idx, refs);
+        }
+
         Expr::Range {
             sheet,
             start_row,
@@ -187,6 +198,21 @@ mod tests {
                 }
             }
             Expr::NamedRange(name) => Expr::NamedRange(name.clone()),
+            Expr::Array(rows) => {
+                Expr::Array(rows.iter().map(|row| row.iter().map(bind_simple).collect()).collect())
+            }
+            Expr::SpillRef { sheet: _, row, col, col_abs, row_abs } => {
+                Expr::SpillRef {
+                    sheet: SheetRef::Current,
+                    row: *row,
+                    col: *col,
+                    col_abs: *col_abs,
+                    row_abs: *row_abs,
+                }
+            }
+            Expr::ImplicitIntersection(inner) => {
+                Expr::ImplicitIntersection(Box::new(bind_simple(inner)))
+            }
             Expr::Function { name, args } => {
                 Expr::Function {
                     name: name.clone(),
@@ -0,0 +1,141 @@
+// Decimal rounding for ROUND, ROUNDUP, ROUNDDOWN, and MROUND
+//
+// Rounding works on the value's shortest decimal representation rather than
+// on `value * 10^n` in binary, so 2.675 rounds to 2.68 (as written) instead
+// of 2.67 (as stored), identically on every platform.
+
+/// How ROUND and MROUND break ties at exactly .5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundMode {
+    /// Ties round away from zero (Excel).
+    #[default]
+    HalfUp,
+    /// Ties round to the even neighbour (banker's rounding).
+    HalfEven,
+}
+
+/// Which way to move when digits are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rule {
+    HalfUp,
+    HalfEven,
+    /// Away from zero (ROUNDUP).
+    Up,
+    /// Toward zero (ROUNDDOWN).
+    Down,
+}
+
+impl From<RoundMode> for Rule {
+    fn from(mode: RoundMode) -> Self {
+        match mode {
+            RoundMode::HalfUp => Rule::HalfUp,
+            RoundMode::HalfEven => Rule::HalfEven,
+        }
+    }
+}
+
+/// Significant digits a value is reduced to before rounding, so binary noise
+/// such as 0.1 + 0.2 = 0.30000000000000004 doesn't count as a dropped digit.
+const SIGNIFICANT_DIGITS: i32 = 15;
+
+/// Round `value` to `decimals` places (negative rounds left of the point).
+pub(crate) fn round_decimal(value: f64, decimals: i32, rule: Rule) -> f64 {
+    if !value.is_finite() || value == 0.0 {
+        return value;
+    }
+    let value = round_significant(value, SIGNIFICANT_DIGITS);
+    round_digits(value, decimals, rule)
+}
+
+/// Round `number` to the nearest multiple of `multiple`, breaking ties with `mode`.
+/// Errors (`#NUM!`) when the signs differ, like Excel.
+pub(crate) fn mround(number: f64, multiple: f64, mode: RoundMode) -> Result<f64, String> {
+    if multiple == 0.0 || number == 0.0 {
+        return Ok(0.0);
+    }
+    if (number < 0.0) != (multiple < 0.0) {
+        return Err("#NUM!".to_string());
+    }
+    let quotient = round_decimal(number / multiple, 0, mode.into());
+    // Trim binary noise from the product to the multiple's own precision
+    let places = decimal_places(multiple);
+    Ok(round_decimal(quotient * multiple, places, Rule::HalfUp))
+}
+
+fn round_significant(value: f64, digits: i32) -> f64 {
+    let (_, exponent) = decompose(value);
+    round_digits(value, digits - 1 - exponent, Rule::HalfUp)
+}
+
+/// Digits after the decimal point in `value`'s shortest representation.
+fn decimal_places(value: f64) -> i32 {
+    let (digits, exponent) = decompose(value);
+    (digits.len() as i32 - 1 - exponent).max(0)
+}
+
+/// Shortest round-trip digits of |value| (no leading zeros) and the decimal
+/// exponent of the first digit: 2.675 → ("2675", 0), 0.05 → ("5", -2).
+fn decompose(value: f64) -> (String, i32) {
+    let sci = format!("{:e}", value.abs());
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(|c| c.is_ascii_digit()).collect();
+    (digits, exponent.parse().unwrap_or(0))
+}
+
+fn round_digits(value: f64, decimals: i32, rule: Rule) -> f64 {
+    let (digits, exponent) = decompose(value);
+    let digits = digits.as_bytes();
+    // Number of leading digits kept
+    let keep = exponent + 1 + decimals;
+    if keep >= digits.len() as i32 {
+        return value;
+    }
+
+    let (mut kept, first_dropped, rest_nonzero) = if keep < 0 {
+        (0u64, 0, true)
+    } else {
+        let keep = keep as usize;
+        let kept = digits[..keep].iter().fold(0u64, |acc, d| acc * 10 + u64::from(d - b'0'));
+        (kept, digits[keep] - b'0', digits[keep + 1..].iter().any(|d| *d != b'0'))
+    };
+
+    let round_away = match rule {
+        Rule::HalfUp => first_dropped >= 5,
+        Rule::HalfEven => first_dropped > 5 || (first_dropped == 5 && (rest_nonzero || kept % 2 == 1)),
+        Rule::Up => first_dropped > 0 || rest_nonzero,
+        Rule::Down => false,
+    };
+    if round_away {
+        kept += 1;
+    }
+
+    let magnitude: f64 = format!("{}e{}", kept, -decimals).parse().unwrap_or(0.0);
+    if value < 0.0 { -magnitude } else { magnitude }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_decimal_uses_written_digits() {
+        // 2.675 is stored as 2.67499999...; rounds as written
+        assert_eq!(round_decimal(2.675, 2, Rule::HalfUp), 2.68);
+        assert_eq!(round_decimal(1.005, 2, Rule::HalfUp), 1.01);
+        assert_eq!(round_decimal(1234.5, -2, Rule::HalfUp), 1200.0);
+        assert_eq!(round_decimal(0.004, 2, Rule::HalfUp), 0.0);
+        assert_eq!(round_decimal(0.004, 2, Rule::Up), 0.01);
+        assert_eq!(round_decimal(0.1 + 0.2, 1, Rule::Up), 0.3);
+        assert_eq!(round_decimal(12.5, 3, Rule::HalfEven), 12.5);
+    }
+
+    #[test]
+    fn test_mround() {
+        assert_eq!(mround(10.0, 3.0, RoundMode::HalfUp), Ok(9.0));
+        assert_eq!(mround(0.15, 0.1, RoundMode::HalfUp), Ok(0.2));
+        assert_eq!(mround(0.25, 0.1, RoundMode::HalfEven), Ok(0.2));
+        assert_eq!(mround(-7.5, -5.0, RoundMode::HalfUp), Ok(-10.0));
+        assert_eq!(mround(5.0, -2.0, RoundMode::HalfUp), Err("#NUM!".to_string()));
+        assert_eq!(mround(5.0, 0.0, RoundMode::HalfUp), Ok(0.0));
+    }
+}
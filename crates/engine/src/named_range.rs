@@ -226,7 +226,7 @@ fn is_function_name(upper_name: &str) -> bool {
         "AVERAGE", "AVERAGEA", "AVERAGEIF", "AVERAGEIFS",
         "COUNT", "COUNTA", "COUNTBLANK", "COUNTIF", "COUNTIFS",
         "MIN", "MINA", "MAX", "MAXA", "MEDIAN", "MODE",
-        "ABS", "ROUND", "ROUNDUP", "ROUNDDOWN", "TRUNC", "INT", "FLOOR", "CEILING",
+        "ABS", "ROUND", "ROUNDUP", "ROUNDDOWN", "MROUND", "TRUNC", "INT", "FLOOR", "CEILING",
         "MOD", "POWER", "SQRT", "EXP", "LN", "LOG", "LOG10",
         "PI", "RAND", "RANDBETWEEN",
         "SIN", "COS", "TAN", "ASIN", "ACOS", "ATAN", "ATAN2",
@@ -1,11 +1,15 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
-use super::cell::{Alignment, Cell, CellBorder, CellFormat, CellStyle, CellValue, NumberFormat, SpillError, SpillInfo, TextOverflow, VerticalAlignment, max_border};
+use super::cell::{Alignment, Cell, CellBorder, CellFormat, CellStyle, CellValue, ErrKind, NumberFormat, bool_display, SpillError, SpillInfo, TextOverflow, VerticalAlignment, max_border};
+use super::conditional_format::ConditionalFormat;
 use super::formula::eval::{self, Array2D, CellLookup, EvalResult, LookupWithContext, Value};
 use super::formula::parser::{bind_expr_same_sheet, Expr as ExprAst};
+use super::formula::refs::extract_cell_ids;
+use super::formula::rounding::RoundMode;
+use super::named_range::NamedRangeStore;
 use super::validation::ValidationStore;
 
 // Thread-local set to track cells currently being evaluated (for cycle detection)
@@ -137,6 +141,8 @@ fn has_cross_sheet_refs(expr: &ExprAst<UnboundSheetRef>) -> bool {
     match expr {
         ExprAst::CellRef { sheet: UnboundSheetRef::Named(_), .. } => true,
         ExprAst::Range { sheet: UnboundSheetRef::Named(_), .. } => true,
+        ExprAst::SpillRef { sheet: UnboundSheetRef::Named(_), .. } => true,
+        ExprAst::ImplicitIntersection(inner) => has_cross_sheet_refs(inner),
         ExprAst::Function { args, .. } => args.iter().any(has_cross_sheet_refs),
         ExprAst::BinaryOp { left, right, .. } => {
             has_cross_sheet_refs(left) || has_cross_sheet_refs(right)
@@ -145,6 +151,16 @@ fn has_cross_sheet_refs(expr: &ExprAst<UnboundSheetRef>) -> bool {
     }
 }
 
+/// A number as `Sheet::get_display` shows it: whole numbers without a
+/// decimal point, anything else to two decimals.
+pub fn number_display(n: f64) -> String {
+    if n.fract() == 0.0 {
+        format!("{}", n as i64)
+    } else {
+        format!("{:.2}", n)
+    }
+}
+
 /// Check if a sheet name is valid.
 ///
 /// Rules:
@@ -246,6 +262,72 @@ impl MergedRegion {
     }
 }
 
+/// A rectangular range whose cells session clients may not edit.
+///
+/// Persisted in the .sheet file but excluded from the semantic fingerprint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtectedRange {
+    /// Top-left corner (row, col)
+    pub start: (usize, usize),
+    /// Bottom-right corner (row, col)
+    pub end: (usize, usize),
+}
+
+impl ProtectedRange {
+    pub fn new(start_row: usize, start_col: usize, end_row: usize, end_col: usize) -> Self {
+        Self {
+            start: (start_row.min(end_row), start_col.min(end_col)),
+            end: (start_row.max(end_row), start_col.max(end_col)),
+        }
+    }
+
+    /// Whether (row, col) is inside this range
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        row >= self.start.0 && row <= self.end.0 && col >= self.start.1 && col <= self.end.1
+    }
+}
+
+/// Grid-line adjustment of the span `start..=end` for `count` lines inserted
+/// at `at`: an insertion at or before the span shifts it, one inside grows it.
+fn span_after_insert(start: usize, end: usize, at: usize, count: usize) -> (usize, usize) {
+    if at <= start {
+        (start + count, end + count)
+    } else if at <= end {
+        (start, end + count)
+    } else {
+        (start, end)
+    }
+}
+
+/// Grid-line adjustment of the span `start..=end` for the `count` lines at
+/// `at` being deleted. `None` when the deletion covers the whole span.
+fn span_after_delete(start: usize, end: usize, at: usize, count: usize) -> Option<(usize, usize)> {
+    let del_end = at + count; // exclusive
+    if del_end <= start {
+        Some((start - count, end - count))
+    } else if at > end {
+        Some((start, end))
+    } else if at <= start && del_end > end {
+        None
+    } else if at <= start {
+        Some((at, end - count))
+    } else if del_end > end {
+        Some((start, at - 1))
+    } else {
+        Some((start, end - count))
+    }
+}
+
+/// A note attached to a cell (Excel comment).
+///
+/// Persisted in the .sheet file but excluded from the semantic fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellComment {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
 // =============================================================================
 // Sheet
 // =============================================================================
@@ -280,11 +362,28 @@ pub struct Sheet {
     /// Fast lookup: (row, col) → index into merged_regions
     #[serde(skip)]
     merge_index: HashMap<(usize, usize), usize>,
+    /// Ranges that reject edits from session clients
+    #[serde(default)]
+    pub protected_ranges: Vec<ProtectedRange>,
+    /// Cell comments: (row, col) -> comment, ordered for stable output
+    #[serde(default)]
+    pub comments: BTreeMap<(usize, usize), CellComment>,
+    /// Conditional formatting rules, in priority order (first wins).
+    #[serde(default)]
+    pub conditional_formats: Vec<ConditionalFormat>,
+    /// Frozen panes: rows pinned at the top and columns pinned at the left.
+    #[serde(default)]
+    pub frozen_rows: usize,
+    #[serde(default)]
+    pub frozen_cols: usize,
     /// Conservative flag: true once any cell has had a non-None border set.
     /// Never cleared (except by `scan_border_flag()`). Used by the renderer
     /// to skip border computation on sheets that have never had borders.
     #[serde(skip)]
     pub has_any_borders: bool,
+    /// How ROUND and MROUND break ties when formulas evaluate against this sheet.
+    #[serde(skip)]
+    round_mode: RoundMode,
 }
 
 impl CellLookup for Sheet {
@@ -309,6 +408,8 @@ impl CellLookup for Sheet {
                 CellValue::Empty => 0.0,
                 CellValue::Number(n) => *n,
                 CellValue::Text(s) => s.parse().unwrap_or(0.0),
+                CellValue::Boolean(b) => if *b { 1.0 } else { 0.0 },
+                CellValue::Error(_) => 0.0,
                 CellValue::Formula { ast: Some(_), .. } => {
                     // Cache-only: never evaluate on cache miss.
                     // Topo recalc populates the cache; miss means not yet computed.
@@ -349,6 +450,8 @@ impl CellLookup for Sheet {
                         format!("{}", n)
                     }
                 }
+                CellValue::Boolean(b) => bool_display(*b).to_string(),
+                CellValue::Error(kind) => kind.as_str().to_string(),
                 CellValue::Formula { ast: Some(_), .. } => {
                     // Cache-only: never evaluate on cache miss.
                     // Topo recalc populates the cache; miss means not yet computed.
@@ -363,6 +466,12 @@ impl CellLookup for Sheet {
         }
     }
 
+    fn get_spill_extent(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        self.cells.get(&(row, col))
+            .and_then(|cell| cell.spill_info.as_ref())
+            .map(|info| (info.rows, info.cols))
+    }
+
     fn debug_context(&self) -> String {
         format!(
             "Sheet(name=\"{}\", ptr={:p}, cache_len={})",
@@ -377,6 +486,10 @@ impl CellLookup for Sheet {
     fn get_cell_value(&self, row: usize, col: usize) -> Value {
         self.get_computed_value(row, col)
     }
+
+    fn round_mode(&self) -> RoundMode {
+        self.round_mode
+    }
 }
 
 impl Sheet {
@@ -396,7 +509,13 @@ impl Sheet {
             validations: ValidationStore::new(),
             merged_regions: Vec::new(),
             merge_index: HashMap::new(),
+            protected_ranges: Vec::new(),
+            comments: BTreeMap::new(),
+            conditional_formats: Vec::new(),
+            frozen_rows: 0,
+            frozen_cols: 0,
             has_any_borders: false,
+            round_mode: RoundMode::default(),
         }
     }
 
@@ -416,10 +535,22 @@ impl Sheet {
             validations: ValidationStore::new(),
             merged_regions: Vec::new(),
             merge_index: HashMap::new(),
+            protected_ranges: Vec::new(),
+            comments: BTreeMap::new(),
+            conditional_formats: Vec::new(),
+            frozen_rows: 0,
+            frozen_cols: 0,
             has_any_borders: false,
+            round_mode: RoundMode::default(),
         }
     }
 
+    /// Set how ROUND and MROUND break ties in formulas on this sheet.
+    /// Applies to formulas evaluated after the call.
+    pub fn set_round_mode(&mut self, mode: RoundMode) {
+        self.round_mode = mode;
+    }
+
     /// Cache a computed Value for a formula cell.
     /// Called ONLY during topological recalc (workbook.evaluate_cell).
     /// Getters read from this cache but never write to it.
@@ -471,6 +602,89 @@ impl Sheet {
         self.evaluate_and_spill(row, col);
     }
 
+    /// Store `text` as a text cell exactly as given, skipping the number,
+    /// boolean and formula detection of `set_value`. For inputs whose
+    /// spelling matters, like ids with leading zeros.
+    pub fn set_text(&mut self, row: usize, col: usize, text: &str) {
+        let (row, col) = self.merge_origin_coord(row, col);
+        self.clear_spill_from(row, col);
+        self.computed_cache.borrow_mut().remove(&(row, col));
+
+        let cell = self.cells.entry((row, col)).or_insert_with(Cell::new);
+        cell.value = if text.is_empty() { CellValue::Empty } else { CellValue::Text(text.to_string()) };
+        cell.clear_spill_state();
+        cell.frozen_formula = None;
+    }
+
+    /// Set many cells at once (CSV import, fill).
+    ///
+    /// Same result as calling `set_value` for each cell, but formulas are
+    /// evaluated once after every value is written, precedents first, so a
+    /// formula sees values that appear later in the input. Returns the written
+    /// positions (after merge redirection) for the caller's dependency tracking.
+    pub fn set_values_bulk(
+        &mut self,
+        cells: impl Iterator<Item = (usize, usize, String)>,
+    ) -> Vec<(usize, usize)> {
+        let mut written = Vec::new();
+        for (row, col, value) in cells {
+            let (row, col) = self.merge_origin_coord(row, col);
+            self.clear_spill_from(row, col);
+            self.computed_cache.borrow_mut().remove(&(row, col));
+            self.cells.entry((row, col)).or_default().set(&value);
+            written.push((row, col));
+        }
+
+        let pending: HashSet<(usize, usize)> = written
+            .iter()
+            .copied()
+            .filter(|pos| self.cells.get(pos).is_some_and(|c| c.value.formula_ast().is_some()))
+            .collect();
+        if pending.is_empty() {
+            return written;
+        }
+
+        // Post-order DFS over same-sheet refs. Iterative because imported
+        // formula chains can be far deeper than the call stack allows.
+        let mut visited: HashSet<(usize, usize)> = HashSet::new();
+        for &start in &written {
+            if !pending.contains(&start) || visited.contains(&start) {
+                continue;
+            }
+            let mut stack = vec![(start, false)];
+            while let Some((pos, expanded)) = stack.pop() {
+                if expanded {
+                    self.evaluate_and_spill(pos.0, pos.1);
+                    continue;
+                }
+                if !visited.insert(pos) {
+                    continue;
+                }
+                stack.push((pos, true));
+                for dep in self.same_sheet_precedents(pos.0, pos.1) {
+                    if pending.contains(&dep) && !visited.contains(&dep) {
+                        stack.push((dep, false));
+                    }
+                }
+            }
+        }
+        written
+    }
+
+    /// Cells on this sheet that the formula at (row, col) reads.
+    fn same_sheet_precedents(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let ast = match self.cells.get(&(row, col)).and_then(|c| c.value.formula_ast()) {
+            Some(ast) => ast,
+            None => return Vec::new(),
+        };
+        let bound = bind_expr_same_sheet(ast);
+        extract_cell_ids(&bound, self.id, &NamedRangeStore::default(), |_| None)
+            .into_iter()
+            .filter(|id| id.sheet == self.id)
+            .map(|id| (id.row, id.col))
+            .collect()
+    }
+
     /// Mark a cell as having a cycle error.
     ///
     /// Used when loading workbooks with circular references to mark
@@ -479,11 +693,10 @@ impl Sheet {
         // Redirect hidden merge cells to the merge origin
         let (row, col) = self.merge_origin_coord(row, col);
 
-        // Store #CYCLE! as the cell value while preserving the formula source
-        // For now, we just set a text value - the original formula is lost
-        // A future improvement could preserve the formula for editing
+        // Store #CYCLE! as the cell value. The original formula is lost;
+        // a future improvement could preserve it for editing
         let cell = self.cells.entry((row, col)).or_insert_with(Cell::new);
-        cell.value = CellValue::Text("#CYCLE!".to_string());
+        cell.value = CellValue::Error(ErrKind::Cycle);
     }
 
     /// Replace a formula cell with a static cached value, preserving the
@@ -523,6 +736,32 @@ impl Sheet {
         (max_row, max_col)
     }
 
+    /// Positions that can display something: stored cells holding a value or
+    /// a spill error, plus spilled values. Sorted row-major, no duplicates.
+    ///
+    /// Cost is proportional to the number of stored cells, not the sheet's
+    /// dimensions, so callers can walk sparse sheets without a dense scan.
+    pub fn occupied_cells(&self) -> Vec<(usize, usize)> {
+        let mut positions: Vec<(usize, usize)> = self.cells
+            .iter()
+            .filter(|(_, cell)| !matches!(cell.value, CellValue::Empty) || cell.spill_error.is_some())
+            .map(|(&pos, _)| pos)
+            .chain(self.spill_values.keys().copied())
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// Exclusive (rows, cols) bounds of `occupied_cells`, or `None` when
+    /// nothing is occupied.
+    pub fn occupied_bounds(&self) -> Option<(usize, usize)> {
+        let occupied = self.occupied_cells();
+        let rows = occupied.last()?.0 + 1;
+        let cols = occupied.iter().map(|&(_, c)| c + 1).max()?;
+        Some((rows, cols))
+    }
+
     /// Get a reference to a cell if it exists, without creating one.
     pub fn get_cell_opt(&self, row: usize, col: usize) -> Option<&Cell> {
         self.cells.get(&(row, col))
@@ -801,6 +1040,8 @@ impl Sheet {
                         cache.get(&(row, col)).cloned().unwrap_or(Value::Empty)
                     }
                     CellValue::Text(s) => Value::Text(s.clone()),
+                    CellValue::Boolean(b) => Value::Boolean(*b),
+                    CellValue::Error(kind) => Value::Error(kind.as_str().to_string()),
                     CellValue::Empty => return String::new(),
                     CellValue::Formula { ast: None, .. } => return "#ERR".to_string(),
                 };
@@ -811,7 +1052,7 @@ impl Sheet {
                         CellValue::format_number(n, &cell.format.number_format)
                     }
                     Value::Text(s) => s,
-                    Value::Boolean(b) => if b { "TRUE".to_string() } else { "FALSE".to_string() },
+                    Value::Boolean(b) => bool_display(b).to_string(),
                     Value::Error(e) => e,
                     Value::Empty => String::new(),
                 }
@@ -846,6 +1087,8 @@ impl Sheet {
                     CellValue::Empty => Value::Empty,
                     CellValue::Text(s) => Value::Text(s.clone()),
                     CellValue::Number(n) => Value::Number(*n),
+                    CellValue::Boolean(b) => Value::Boolean(*b),
+                    CellValue::Error(kind) => Value::Error(kind.as_str().to_string()),
                     CellValue::Formula { ast: Some(_), .. } => {
                         // Cache-only: never evaluate on cache miss.
                         let cache = self.computed_cache.borrow();
@@ -870,24 +1113,14 @@ impl Sheet {
         match value {
             CellValue::Empty => String::new(),
             CellValue::Text(s) => s.clone(),
-            CellValue::Number(n) => {
-                if n.fract() == 0.0 {
-                    format!("{}", *n as i64)
-                } else {
-                    format!("{:.2}", n)
-                }
-            }
+            CellValue::Number(n) => number_display(*n),
+            CellValue::Boolean(b) => bool_display(*b).to_string(),
+            CellValue::Error(kind) => kind.as_str().to_string(),
             CellValue::Formula { ast: Some(_), .. } => {
                 // Cache-only: never evaluate on cache miss.
                 let cache = self.computed_cache.borrow();
                 match cache.get(&(row, col)) {
-                    Some(Value::Number(n)) => {
-                        if n.fract() == 0.0 {
-                            format!("{}", *n as i64)
-                        } else {
-                            format!("{:.2}", n)
-                        }
-                    }
+                    Some(Value::Number(n)) => number_display(*n),
                     Some(Value::Error(e)) => e.clone(),
                     Some(v) => v.to_text(),
                     None => String::new(),
@@ -1222,6 +1455,60 @@ impl Sheet {
         }
     }
 
+    /// Protect a range against session edits. Duplicate ranges are ignored.
+    pub fn protect(&mut self, range: ProtectedRange) {
+        if !self.protected_ranges.contains(&range) {
+            self.protected_ranges.push(range);
+        }
+    }
+
+    /// Is (row, col) inside any protected range?
+    pub fn is_protected(&self, row: usize, col: usize) -> bool {
+        self.protected_ranges.iter().any(|r| r.contains(row, col))
+    }
+
+    /// Comment attached to (row, col), if any.
+    pub fn comment(&self, row: usize, col: usize) -> Option<&CellComment> {
+        self.comments.get(&(row, col))
+    }
+
+    /// Attach a comment to (row, col), or remove it with `None` or empty text.
+    /// Returns the previous comment.
+    pub fn set_comment(&mut self, row: usize, col: usize, comment: Option<CellComment>) -> Option<CellComment> {
+        match comment {
+            Some(c) if !c.text.is_empty() => self.comments.insert((row, col), c),
+            _ => self.comments.remove(&(row, col)),
+        }
+    }
+
+    /// Move each comment to `remap(row, col)`, dropping those it maps to `None`.
+    fn remap_comments(&mut self, remap: impl Fn(usize, usize) -> Option<(usize, usize)>) {
+        self.comments = std::mem::take(&mut self.comments)
+            .into_iter()
+            .filter_map(|((row, col), comment)| remap(row, col).map(|pos| (pos, comment)))
+            .collect();
+    }
+
+    /// Conditional formats whose range covers (row, col), in priority order.
+    pub fn conditional_formats_at(&self, row: usize, col: usize) -> impl Iterator<Item = &ConditionalFormat> {
+        self.conditional_formats.iter().filter(move |cf| cf.contains(row, col))
+    }
+
+    /// Add a conditional format after the existing ones (lowest priority).
+    pub fn add_conditional_format(&mut self, format: ConditionalFormat) {
+        self.conditional_formats.push(format);
+    }
+
+    /// Remove the conditional formats whose range is exactly
+    /// (start_row, start_col)..(end_row, end_col). Returns how many were removed.
+    pub fn clear_conditional_formats(&mut self, start_row: usize, start_col: usize, end_row: usize, end_col: usize) -> usize {
+        let start = (start_row.min(end_row), start_col.min(end_col));
+        let end = (start_row.max(end_row), start_col.max(end_col));
+        let before = self.conditional_formats.len();
+        self.conditional_formats.retain(|cf| cf.start != start || cf.end != end);
+        before - self.conditional_formats.len()
+    }
+
     /// Remove degenerate (1×1) merges and rebuild the index.
     pub fn normalize_merges(&mut self) {
         self.merged_regions.retain(|m| !m.is_degenerate());
@@ -1318,6 +1605,19 @@ impl Sheet {
             }
         }
         self.normalize_merges();
+
+        for p in &mut self.protected_ranges {
+            (p.start.0, p.end.0) = span_after_insert(p.start.0, p.end.0, at_row, count);
+        }
+        for cf in &mut self.conditional_formats {
+            (cf.start.0, cf.end.0) = span_after_insert(cf.start.0, cf.end.0, at_row, count);
+        }
+        let rows = self.rows;
+        self.remap_comments(|r, c| match r {
+            r if r < at_row => Some((r, c)),
+            r if r + count < rows => Some((r + count, c)),
+            _ => None,
+        });
     }
 
     /// Delete rows at the specified position, shifting remaining rows up
@@ -1374,6 +1674,27 @@ impl Sheet {
             }
         }
         self.normalize_merges();
+
+        // A range whose rows were all deleted protects nothing
+        self.protected_ranges.retain_mut(|p| match span_after_delete(p.start.0, p.end.0, start_row, count) {
+            Some(span) => {
+                (p.start.0, p.end.0) = span;
+                true
+            }
+            None => false,
+        });
+        self.conditional_formats.retain_mut(|cf| match span_after_delete(cf.start.0, cf.end.0, start_row, count) {
+            Some(span) => {
+                (cf.start.0, cf.end.0) = span;
+                true
+            }
+            None => false,
+        });
+        self.remap_comments(|r, c| match r {
+            r if r < start_row => Some((r, c)),
+            r if r < end_row => None,
+            r => Some((r - count, c)),
+        });
         // Deleted rows may have removed the only bordered cells.
         // Only rescan when the flag is currently true (can't flip false→false).
         // TODO(perf): if delete_rows on a 50k+ row bordered sheet causes >16ms frame hitch
@@ -1415,6 +1736,19 @@ impl Sheet {
             }
         }
         self.normalize_merges();
+
+        for p in &mut self.protected_ranges {
+            (p.start.1, p.end.1) = span_after_insert(p.start.1, p.end.1, at_col, count);
+        }
+        for cf in &mut self.conditional_formats {
+            (cf.start.1, cf.end.1) = span_after_insert(cf.start.1, cf.end.1, at_col, count);
+        }
+        let cols = self.cols;
+        self.remap_comments(|r, c| match c {
+            c if c < at_col => Some((r, c)),
+            c if c + count < cols => Some((r, c + count)),
+            _ => None,
+        });
     }
 
     /// Delete columns at the specified position, shifting remaining columns left
@@ -1471,6 +1805,26 @@ impl Sheet {
             }
         }
         self.normalize_merges();
+
+        self.protected_ranges.retain_mut(|p| match span_after_delete(p.start.1, p.end.1, start_col, count) {
+            Some(span) => {
+                (p.start.1, p.end.1) = span;
+                true
+            }
+            None => false,
+        });
+        self.conditional_formats.retain_mut(|cf| match span_after_delete(cf.start.1, cf.end.1, start_col, count) {
+            Some(span) => {
+                (cf.start.1, cf.end.1) = span;
+                true
+            }
+            None => false,
+        });
+        self.remap_comments(|r, c| match c {
+            c if c < start_col => Some((r, c)),
+            c if c < end_col => None,
+            c => Some((r, c - count)),
+        });
         // Deleted columns may have removed the only bordered cells.
         // Only rescan when the flag is currently true (can't flip false→false).
         // TODO(perf): if delete_cols on a 50k+ col bordered sheet causes >16ms frame hitch
@@ -2014,6 +2368,25 @@ mod tests {
         assert_eq!(sheet.get_format(10, 49).text_overflow, TextOverflow::Overflow);
     }
 
+    #[test]
+    fn test_occupied_cells_sparse() {
+        let mut sheet = Sheet::new(SheetId(1), 1_048_576, 16_384);
+        assert_eq!(sheet.occupied_bounds(), None);
+
+        // One far-off value, plus a formatted-but-empty cell that doesn't count
+        sheet.set_value(9_999, 25, "x");
+        sheet.set_format(5, 5, CellFormat::default());
+        assert_eq!(sheet.occupied_cells(), vec![(9_999, 25)]);
+        assert_eq!(sheet.occupied_bounds(), Some((10_000, 26)));
+
+        // Spilled values are occupied too, in row-major order
+        sheet.set_value(0, 0, "=SEQUENCE(2,2)");
+        assert_eq!(
+            sheet.occupied_cells(),
+            vec![(0, 0), (0, 1), (1, 0), (1, 1), (9_999, 25)],
+        );
+    }
+
     #[test]
     fn test_sequence_spill() {
         let mut sheet = Sheet::new(SheetId(1), 10, 10);
@@ -2101,6 +2474,39 @@ mod tests {
         assert_eq!(sheet.get_display(0, 1), "");
     }
 
+    #[test]
+    fn test_set_values_bulk_matches_single_set() {
+        // Precedents first, as single-set needs them
+        let mut single = Sheet::new(SheetId(1), 10, 10);
+        single.set_value(0, 0, "5");
+        single.set_value(1, 0, "7");
+        single.set_value(0, 1, "=A1*2");
+        single.set_value(1, 1, "=B1+A2");
+        single.set_value(2, 0, "=SEQUENCE(2)");
+
+        // Bulk input lists the dependent formulas before their inputs
+        let mut bulk = Sheet::new(SheetId(1), 10, 10);
+        let cells = [
+            (1, 1, "=B1+A2"),
+            (0, 1, "=A1*2"),
+            (2, 0, "=SEQUENCE(2)"),
+            (0, 0, "5"),
+            (1, 0, "7"),
+        ];
+        let written = bulk.set_values_bulk(cells.iter().map(|&(r, c, v)| (r, c, v.to_string())));
+        assert_eq!(written.len(), 5);
+
+        for row in 0..4 {
+            for col in 0..2 {
+                assert_eq!(bulk.get_display(row, col), single.get_display(row, col), "cell ({}, {})", row, col);
+            }
+        }
+        assert_eq!(bulk.get_display(0, 1), "10");
+        assert_eq!(bulk.get_display(1, 1), "17");
+        assert!(bulk.is_spill_parent(2, 0));
+        assert_eq!(bulk.get_display(3, 0), "2");
+    }
+
     #[test]
     fn test_transpose_spill() {
         let mut sheet = Sheet::new(SheetId(1), 10, 10);
@@ -3333,6 +3739,118 @@ mod tests {
         assert!(sheet.add_merge(MergedRegion::new(0, 3, 2, 5)).is_ok());
     }
 
+    #[test]
+    fn test_protected_range_covers_only_its_cells() {
+        let mut sheet = Sheet::new(SheetId(1), 10, 10);
+        sheet.protect(ProtectedRange::new(1, 1, 2, 2));
+        sheet.protect(ProtectedRange::new(2, 2, 1, 1)); // same range, reversed corners
+        assert_eq!(sheet.protected_ranges.len(), 1);
+
+        assert!(sheet.is_protected(1, 1));
+        assert!(sheet.is_protected(2, 2));
+        assert!(!sheet.is_protected(0, 1));
+        assert!(!sheet.is_protected(1, 3));
+    }
+
+    #[test]
+    fn test_protected_ranges_follow_row_and_col_edits() {
+        let mut sheet = Sheet::new(SheetId(1), 20, 20);
+        sheet.protect(ProtectedRange::new(2, 2, 4, 4));
+
+        // Insert above shifts; insert inside grows
+        sheet.insert_rows(0, 2);
+        assert_eq!(sheet.protected_ranges, vec![ProtectedRange::new(4, 2, 6, 4)]);
+        sheet.insert_cols(3, 1);
+        assert_eq!(sheet.protected_ranges, vec![ProtectedRange::new(4, 2, 6, 5)]);
+        assert!(sheet.is_protected(4, 3));
+
+        // Deleting part of the range shrinks it; deleting before it shifts it
+        sheet.delete_rows(5, 1);
+        assert_eq!(sheet.protected_ranges, vec![ProtectedRange::new(4, 2, 5, 5)]);
+        sheet.delete_cols(0, 1);
+        assert_eq!(sheet.protected_ranges, vec![ProtectedRange::new(4, 1, 5, 4)]);
+
+        // Deleting every protected row drops the range
+        sheet.delete_rows(3, 4);
+        assert!(sheet.protected_ranges.is_empty());
+    }
+
+    #[test]
+    fn test_set_comment_replaces_and_clears() {
+        let mut sheet = Sheet::new(SheetId(1), 10, 10);
+        let note = |text: &str| CellComment { text: text.to_string(), author: Some("Dana".to_string()) };
+
+        assert_eq!(sheet.set_comment(1, 2, Some(note("check rate"))), None);
+        assert_eq!(sheet.comment(1, 2).map(|c| c.text.as_str()), Some("check rate"));
+        assert_eq!(sheet.set_comment(1, 2, Some(note("rate confirmed"))), Some(note("check rate")));
+
+        // Empty text clears, like None
+        assert_eq!(sheet.set_comment(1, 2, Some(note(""))), Some(note("rate confirmed")));
+        assert!(sheet.comment(1, 2).is_none());
+        assert_eq!(sheet.set_comment(1, 2, None), None);
+    }
+
+    #[test]
+    fn test_comments_follow_row_and_col_edits() {
+        let mut sheet = Sheet::new(SheetId(1), 10, 10);
+        let note = |text: &str| Some(CellComment { text: text.to_string(), author: None });
+        sheet.set_comment(1, 1, note("above"));
+        sheet.set_comment(4, 4, note("moves"));
+        sheet.set_comment(9, 2, note("pushed off"));
+
+        sheet.insert_rows(2, 1);
+        assert_eq!(sheet.comment(1, 1).map(|c| c.text.as_str()), Some("above"));
+        assert_eq!(sheet.comment(5, 4).map(|c| c.text.as_str()), Some("moves"));
+        assert_eq!(sheet.comments.len(), 2, "a comment pushed past the last row is dropped");
+
+        sheet.insert_cols(0, 2);
+        assert_eq!(sheet.comment(5, 6).map(|c| c.text.as_str()), Some("moves"));
+
+        // Deleting a comment's row drops it; later rows shift up
+        sheet.delete_rows(1, 1);
+        sheet.delete_cols(0, 1);
+        assert_eq!(sheet.comments.len(), 1);
+        assert_eq!(sheet.comment(4, 5).map(|c| c.text.as_str()), Some("moves"));
+    }
+
+    #[test]
+    fn test_conditional_formats_at_and_clear() {
+        use crate::conditional_format::{ConditionalFormat, ConditionalRule};
+
+        let mut sheet = Sheet::new(SheetId(1), 10, 10);
+        let scale = ConditionalRule::color_scale(&[[255, 0, 0, 255], [0, 255, 0, 255]]).unwrap();
+        sheet.add_conditional_format(ConditionalFormat::new(1, 1, 5, 1, scale.clone()));
+        sheet.add_conditional_format(ConditionalFormat::new(0, 0, 9, 9, scale));
+
+        assert_eq!(sheet.conditional_formats_at(3, 1).count(), 2);
+        assert_eq!(sheet.conditional_formats_at(3, 2).count(), 1);
+
+        // Only the exact range is cleared
+        assert_eq!(sheet.clear_conditional_formats(5, 1, 1, 1), 1);
+        assert_eq!(sheet.conditional_formats_at(3, 1).count(), 1);
+        assert_eq!(sheet.clear_conditional_formats(1, 1, 5, 1), 0);
+    }
+
+    #[test]
+    fn test_conditional_formats_follow_row_and_col_edits() {
+        use crate::conditional_format::{ConditionalFormat, ConditionalRule};
+
+        let mut sheet = Sheet::new(SheetId(1), 20, 20);
+        let scale = ConditionalRule::color_scale(&[[255, 0, 0, 255], [0, 255, 0, 255]]).unwrap();
+        sheet.add_conditional_format(ConditionalFormat::new(2, 1, 6, 1, scale.clone()));
+        sheet.add_conditional_format(ConditionalFormat::new(10, 3, 10, 3, scale));
+
+        sheet.insert_rows(4, 2);
+        sheet.insert_cols(0, 1);
+        assert_eq!((sheet.conditional_formats[0].start, sheet.conditional_formats[0].end), ((2, 2), (8, 2)));
+        assert_eq!((sheet.conditional_formats[1].start, sheet.conditional_formats[1].end), ((12, 4), (12, 4)));
+
+        // Deleting the single-cell rule's row drops it; the column rule shrinks
+        sheet.delete_rows(8, 5);
+        assert_eq!(sheet.conditional_formats.len(), 1);
+        assert_eq!((sheet.conditional_formats[0].start, sheet.conditional_formats[0].end), ((2, 2), (7, 2)));
+    }
+
     #[test]
     fn test_add_merge_degenerate_ignored() {
         let mut sheet = Sheet::new(SheetId(1), 10, 10);
@@ -8,6 +8,7 @@ use crate::named_range::{NamedRange, NamedRangeStore};
 use crate::formula::eval::{CellLookup, EvalArg, EvalResult, NamedRangeResolution, Value};
 use crate::formula::parser::bind_expr;
 use crate::formula::refs::extract_cell_ids;
+use crate::formula::rounding::RoundMode;
 
 /// Impact analysis for a cell change (Phase 3.5a).
 ///
@@ -84,6 +85,16 @@ pub struct Workbook {
     #[serde(skip)]
     pub(crate) batch_changed: Vec<CellId>,
 
+    /// A structural edit (rows/cols, sheets) happened during the current
+    /// batch. Drained at end_batch().
+    #[serde(skip)]
+    batch_structure_changed: bool,
+
+    /// A structural edit happened since the last `recompute_dirty`, so cell
+    /// positions and references may have moved under the dep graph.
+    #[serde(skip)]
+    structure_dirty: bool,
+
     /// Maps to `CalculationMode` from document settings:
     ///   `true`  = `CalculationMode::Automatic` — recalc on every edit (default)
     ///   `false` = `CalculationMode::Manual`    — recalc only on F9
@@ -107,12 +118,20 @@ pub struct Workbook {
     #[serde(skip)]
     iterative_tolerance: f64,
 
+    /// How ROUND and MROUND break ties during recalc.
+    #[serde(skip)]
+    round_mode: RoundMode,
+
     /// Monotonically increasing revision number. Incremented once per successful
     /// batch completion (or single-cell edit outside batch).
     /// Used for optimistic concurrency control in session server protocol.
     #[serde(skip)]
     revision: u64,
 
+    /// Channel subscribers notified of each tracked cell edit.
+    #[serde(skip)]
+    subscribers: crate::events::Subscribers,
+
     /// Test instrumentation: counts how many times recalc_dirty_set was called.
     /// Only present in test builds. Reset manually via reset_recalc_count().
     #[cfg(test)]
@@ -143,10 +162,14 @@ impl Workbook {
             dep_graph: DepGraph::new(),
             batch_depth: 0,
             batch_changed: Vec::new(),
+            batch_structure_changed: false,
+            structure_dirty: false,
+            subscribers: Default::default(),
             auto_recalc: true,
             iterative_enabled: false,
             iterative_max_iters: 100,
             iterative_tolerance: 1e-9,
+            round_mode: RoundMode::default(),
             revision: 0,
             #[cfg(test)]
             recalc_count: std::cell::Cell::new(0),
@@ -391,30 +414,26 @@ impl Workbook {
         &mut self.sheets
     }
 
-    /// Create a workbook from sheets (for deserialization)
-    /// Note: next_sheet_id should be set separately via set_next_sheet_id if loading from file
-    /// Call `rebuild_dep_graph()` after loading to populate the dependency graph.
+    /// Create a workbook from sheets, with the dependency graph built and every
+    /// formula recomputed in order, so cross-sheet references resolve against
+    /// the sibling sheets.
     pub fn from_sheets(sheets: Vec<Sheet>, active: usize) -> Self {
-        let active_sheet = active.min(sheets.len().saturating_sub(1));
-        // Calculate next_sheet_id as max existing id + 1
+        let mut workbook = Self::from_sheets_deferred(sheets, active);
+        workbook.rebuild_dep_graph();
+        workbook.recompute_full_ordered();
+        workbook
+    }
+
+    /// Create a workbook from sheets, keeping each sheet's cached values as-is.
+    ///
+    /// For loaders that attach named ranges or formulas afterwards, and for
+    /// extracting one sheet whose cross-sheet values must survive without
+    /// their source sheets. Call `rebuild_dep_graph()` and
+    /// `recompute_full_ordered()` once the workbook is complete.
+    pub fn from_sheets_deferred(sheets: Vec<Sheet>, active: usize) -> Self {
+        // next_sheet_id is max existing id + 1
         let max_id = sheets.iter().map(|s| s.id.raw()).max().unwrap_or(0);
-        Self {
-            sheets,
-            active_sheet,
-            next_sheet_id: max_id + 1,
-            named_ranges: NamedRangeStore::new(),
-            style_table: Vec::new(),
-            dep_graph: DepGraph::new(),
-            batch_depth: 0,
-            batch_changed: Vec::new(),
-            auto_recalc: true,
-            iterative_enabled: false,
-            iterative_max_iters: 100,
-            iterative_tolerance: 1e-9,
-            revision: 0,
-            #[cfg(test)]
-            recalc_count: std::cell::Cell::new(0),
-        }
+        Self::from_sheets_with_meta(sheets, active, max_id + 1)
     }
 
     /// Create a workbook from sheets with explicit next_sheet_id (for full deserialization)
@@ -430,10 +449,14 @@ impl Workbook {
             dep_graph: DepGraph::new(),
             batch_depth: 0,
             batch_changed: Vec::new(),
+            batch_structure_changed: false,
+            structure_dirty: false,
+            subscribers: Default::default(),
             auto_recalc: true,
             iterative_enabled: false,
             iterative_max_iters: 100,
             iterative_tolerance: 1e-9,
+            round_mode: RoundMode::default(),
             revision: 0,
             #[cfg(test)]
             recalc_count: std::cell::Cell::new(0),
@@ -1714,6 +1737,28 @@ impl Workbook {
         self.note_cell_changed(cell_id);
     }
 
+    /// Bulk variant of `set_cell_value_tracked`: writes every cell via
+    /// `Sheet::set_values_bulk`, then updates deps and runs one recalc
+    /// for the whole set. Returns the number of cells written.
+    pub fn set_cell_values_bulk_tracked(
+        &mut self,
+        sheet_index: usize,
+        cells: impl Iterator<Item = (usize, usize, String)>,
+    ) -> usize {
+        let sheet_id = match self.sheets.get(sheet_index) {
+            Some(sheet) => sheet.id,
+            None => return 0,
+        };
+        let written = self.sheets[sheet_index].set_values_bulk(cells);
+        self.begin_batch();
+        for &(row, col) in &written {
+            self.update_cell_deps(sheet_id, row, col);
+            self.note_cell_changed(CellId::new(sheet_id, row, col));
+        }
+        self.end_batch();
+        written.len()
+    }
+
     /// Clear a cell on a specific sheet with dep tracking + recalc notification.
     /// Removes the cell entirely (including spill state), unlike set_value("").
     pub fn clear_cell_tracked(&mut self, sheet_index: usize, row: usize, col: usize) {
@@ -1747,7 +1792,7 @@ impl Workbook {
     }
 
     /// End a batch edit. If this is the outermost end, run a single
-    /// incremental recalc for the union of all changed cells, then
+    /// `recompute_dirty` for the union of all changed cells, then
     /// increment the revision number once.
     ///
     /// Returns the list of changed cells (empty if nested batch or no changes).
@@ -1757,24 +1802,64 @@ impl Workbook {
         self.batch_depth -= 1;
         if self.batch_depth == 0 {
             let changed = std::mem::take(&mut self.batch_changed);
-            if !changed.is_empty() {
-                self.recalc_dirty_set(&changed);
+            let structure_changed = std::mem::take(&mut self.batch_structure_changed);
+            if self.auto_recalc && (!changed.is_empty() || structure_changed) {
+                self.recompute_dirty(&changed);
                 self.increment_revision();
-                return changed;
+            } else if structure_changed {
+                self.increment_revision();
+            }
+            if !changed.is_empty() {
+                self.subscribers.publish(self.revision, &changed);
             }
+            return changed;
         }
         Vec::new()
     }
 
-    /// Record a cell change. If batching, defers recalc.
-    /// If not batching, recalcs immediately and increments revision.
-    /// No-op when `auto_recalc` is false (manual calculation mode).
-    pub fn note_cell_changed(&mut self, cell_id: CellId) {
-        if !self.auto_recalc {
-            return;
+    /// Recompute only what `changed` made stale: the formulas that
+    /// transitively depend on those cells, in topological order. Formulas
+    /// outside that subgraph keep their cached values.
+    ///
+    /// After a structural edit (see `note_structure_changed`) references may
+    /// have shifted under the dep graph, so this rebuilds the graph and falls
+    /// back to `recompute_full_ordered` instead.
+    pub fn recompute_dirty(&mut self, changed: &[CellId]) -> crate::recalc::RecalcReport {
+        if std::mem::take(&mut self.structure_dirty) {
+            self.rebuild_dep_graph();
+            return self.recompute_full_ordered();
         }
+
+        let start = std::time::Instant::now();
+        let mut report = crate::recalc::RecalcReport::new();
+        report.cells_recomputed = self.recalc_dirty_set(changed);
+        report.duration_ms = start.elapsed().as_millis() as u64;
+        report
+    }
+
+    /// Record a structural edit: rows or columns inserted or deleted, or a
+    /// sheet added or removed. Bumps the revision so readers polling it see
+    /// the new layout; inside a batch the bump waits for `end_batch`. The
+    /// next `recompute_dirty` does a full recompute.
+    pub fn note_structure_changed(&mut self) {
+        self.structure_dirty = true;
+        if self.batch_depth > 0 {
+            self.batch_structure_changed = true;
+        } else {
+            self.increment_revision();
+        }
+    }
+
+    /// Record a cell change. If batching, defers recalc and the subscriber
+    /// event to `end_batch`. If not batching, recalcs immediately and
+    /// increments revision. With `auto_recalc` false (manual calculation
+    /// mode) nothing is recalculated and the revision stays put, but
+    /// subscribers still hear about the edit.
+    pub fn note_cell_changed(&mut self, cell_id: CellId) {
         if self.batch_depth > 0 {
             self.batch_changed.push(cell_id);
+        } else if !self.auto_recalc {
+            self.subscribers.publish(self.revision, &[cell_id]);
         } else {
             // If the changed cell is itself a formula (e.g., a newly-entered
             // cross-sheet formula), evaluate it at the workbook level. This
@@ -1787,11 +1872,24 @@ impl Workbook {
             if is_formula {
                 let _ = self.evaluate_cell(cell_id);
             }
-            self.recalc_dirty_set(&[cell_id]);
+            // Goes through recompute_dirty so a pending structural edit
+            // still forces the full recompute
+            self.recompute_dirty(&[cell_id]);
             self.increment_revision();
+            self.subscribers.publish(self.revision, &[cell_id]);
         }
     }
 
+    /// Subscribe to tracked cell edits.
+    ///
+    /// Every edit that goes through `note_cell_changed` (directly or via
+    /// `set_cell_value_tracked` / `clear_cell_tracked`) is delivered in edit
+    /// order. Batched edits arrive together when the outermost batch ends,
+    /// tagged with the batch's revision. Dropping the receiver unsubscribes.
+    pub fn subscribe(&mut self) -> std::sync::mpsc::Receiver<crate::events::CellChange> {
+        self.subscribers.subscribe()
+    }
+
     /// Set whether incremental recalc runs automatically after edits.
     /// `true` = Automatic (default), `false` = Manual (F9 to recalc).
     pub fn set_auto_recalc(&mut self, auto: bool) {
@@ -1833,6 +1931,16 @@ impl Workbook {
         self.iterative_tolerance
     }
 
+    /// Set how ROUND and MROUND break ties. Takes effect at the next recalc.
+    pub fn set_round_mode(&mut self, mode: RoundMode) {
+        self.round_mode = mode;
+    }
+
+    /// Returns how ROUND and MROUND break ties.
+    pub fn round_mode(&self) -> RoundMode {
+        self.round_mode
+    }
+
     /// Returns the current revision number.
     /// Revision increments once per successful batch or single-cell edit.
     pub fn revision(&self) -> u64 {
@@ -1858,19 +1966,24 @@ impl Workbook {
 
     /// Incremental recalc: re-evaluate only cells that transitively depend
     /// on any cell in `changed`. BFS to collect dirty subgraph, then
-    /// evaluate in global topo order.
-    fn recalc_dirty_set(&self, changed: &[CellId]) {
+    /// evaluate in global topo order. Returns the number of cells evaluated.
+    fn recalc_dirty_set(&self, changed: &[CellId]) -> usize {
         use std::collections::VecDeque;
 
         // Test instrumentation: count recalc calls
         #[cfg(test)]
         self.recalc_count.set(self.recalc_count.get() + 1);
 
-        // 1. BFS forward from all changed cells to collect dirty set
+        // 1. BFS forward from all changed cells to collect dirty set.
+        // A changed formula is dirty itself: cross-sheet formulas are not
+        // evaluated when they are set.
         let mut dirty_set = FxHashSet::default();
         let mut queue = VecDeque::new();
 
         for &cell_id in changed {
+            if self.dep_graph.is_formula_cell(cell_id) {
+                dirty_set.insert(cell_id);
+            }
             for dep in self.dep_graph.dependents(cell_id) {
                 if dirty_set.insert(dep) {
                     queue.push_back(dep);
@@ -1886,7 +1999,7 @@ impl Workbook {
         }
 
         if dirty_set.is_empty() {
-            return;
+            return 0;
         }
 
         // 2. Clear cached values for dirty cells
@@ -1916,13 +2029,16 @@ impl Workbook {
         // On recalc: if valid, use cached order. If not, recompute and cache.
         // For small-to-medium models the current approach is fine. Profile before
         // optimizing — the BFS dirty-set collection is likely the bigger cost.
+        let mut evaluated = 0;
         if let Ok(order) = self.dep_graph.topo_order_all_formulas() {
             for cell_id in order {
                 if dirty_set.contains(&cell_id) {
                     let _ = self.evaluate_cell(cell_id);
+                    evaluated += 1;
                 }
             }
         }
+        evaluated
     }
 
     /// Check if setting a formula at the given cell would create a cycle.
@@ -1986,6 +2102,7 @@ impl Workbook {
             CellValue::Number(n) => {
                 CellValue::format_number(*n, &cell.format.number_format)
             }
+            CellValue::Boolean(_) | CellValue::Error(_) => cell.value.raw_display(),
             CellValue::Formula { ast: Some(ast), .. } => {
                 // Bind with workbook context for cross-sheet refs
                 let bound = bind_expr(ast, |name| self.sheet_id_by_name(name));
@@ -2029,6 +2146,7 @@ impl Workbook {
                     format!("{}", n)
                 }
             }
+            CellValue::Boolean(_) | CellValue::Error(_) => cell.value.raw_display(),
             CellValue::Formula { ast: Some(ast), .. } => {
                 let bound = bind_expr(ast, |name| self.sheet_id_by_name(name));
                 let sheet_id = sheet.id;
@@ -2205,6 +2323,14 @@ impl<'a> CellLookup for WorkbookLookup<'a> {
             .map(|m| m.start)
     }
 
+    fn get_spill_extent(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        self.current_sheet().and_then(|s| s.get_spill_extent(row, col))
+    }
+
+    fn get_spill_extent_sheet(&self, sheet_id: SheetId, row: usize, col: usize) -> Option<(usize, usize)> {
+        self.workbook.sheet_by_id(sheet_id).and_then(|s| s.get_spill_extent(row, col))
+    }
+
     fn get_cell_value(&self, row: usize, col: usize) -> Value {
         self.current_sheet()
             .map(|sheet| sheet.get_computed_value(row, col))
@@ -2214,6 +2340,10 @@ impl<'a> CellLookup for WorkbookLookup<'a> {
     fn try_custom_function(&self, name: &str, args: &[EvalArg]) -> Option<EvalResult> {
         self.custom_fn_handler.as_ref().and_then(|handler| handler(name, args))
     }
+
+    fn round_mode(&self) -> RoundMode {
+        self.workbook.round_mode
+    }
 }
 
 #[cfg(test)]
@@ -2298,6 +2428,29 @@ mod tests {
         assert_eq!(result.to_text(), "42");
     }
 
+    #[test]
+    fn test_boolean_cell_is_typed() {
+        let mut wb = Workbook::new();
+        let sheet = wb.sheet_mut(0).unwrap();
+        sheet.set_value(0, 0, "TRUE");
+        sheet.set_value(0, 1, "true");
+        assert_eq!(sheet.get_computed_value(0, 0), Value::Boolean(true));
+        assert_eq!(sheet.get_computed_value(0, 1), Value::Text("true".to_string()));
+
+        let sheet1_id = wb.sheet_id_at_idx(0).unwrap();
+        let lookup = WorkbookLookup::new(&wb, sheet1_id);
+        let eval = |formula: &str| {
+            let bound = bind_expr(&parse(formula).unwrap(), |name| wb.sheet_id_by_name(name));
+            evaluate(&bound, &lookup).to_text()
+        };
+
+        // A boolean is not the text "TRUE", and text that reads "true" is not a boolean
+        assert_eq!(eval("=A1=TRUE"), "TRUE");
+        assert_eq!(eval("=A1=\"TRUE\""), "FALSE");
+        assert_eq!(eval("=B1=TRUE"), "FALSE");
+        assert_eq!(eval("=B1=\"TRUE\""), "TRUE");
+    }
+
     #[test]
     fn test_cross_sheet_quoted_name() {
         // ='My Sheet'!A1 works
@@ -2809,6 +2962,23 @@ mod tests {
         assert!(!report.had_cycles);
     }
 
+    #[test]
+    fn test_from_sheets_recomputes_cross_sheet() {
+        let mut sheet1 = Sheet::new_with_name(SheetId(1), 100, 26, "Sheet1");
+        sheet1.set_value(0, 0, "5");
+        let mut sheet2 = Sheet::new_with_name(SheetId(2), 100, 26, "Sheet2");
+        sheet2.set_value(0, 0, "=Sheet1!A1+1");
+
+        // Cached values, not a live evaluation
+        let mut wb = Workbook::from_sheets(vec![sheet1, sheet2], 0);
+        assert_eq!(wb.sheet(1).unwrap().get_display(0, 0), "6");
+        assert_eq!(wb.next_sheet_id(), 3);
+
+        // The graph is live: an edit on Sheet1 flows to Sheet2
+        wb.set_cell_value_tracked(0, 0, 0, "41");
+        assert_eq!(wb.sheet(1).unwrap().get_display(0, 0), "42");
+    }
+
     #[test]
     fn test_check_formula_cycle_self_reference() {
         let wb = Workbook::new();
@@ -3454,6 +3624,138 @@ mod tests {
         assert_eq!(wb.sheet(0).unwrap().get_display(0, 1), "30");
     }
 
+    #[test]
+    fn test_recompute_dirty_matches_full_and_skips_independent_cells() {
+        // Chain A1 → B1 → C1, plus an independent subgraph D1 → E1:E20
+        let mut wb = Workbook::new();
+        let sid = wb.sheet_id_at_idx(0).unwrap();
+
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "1");       // A1
+        wb.sheet_mut(0).unwrap().set_value(0, 1, "=A1*2");   // B1
+        wb.sheet_mut(0).unwrap().set_value(0, 2, "=B1+1");   // C1
+        wb.sheet_mut(0).unwrap().set_value(0, 3, "5");       // D1
+        for row in 0..20 {
+            wb.sheet_mut(0).unwrap().set_value(row, 4, &format!("=D1*{}", row + 1));
+        }
+        wb.rebuild_dep_graph();
+        wb.recompute_full_ordered();
+
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "10");
+        let report = wb.recompute_dirty(&[CellId::new(sid, 0, 0)]);
+        assert_eq!(report.cells_recomputed, 2, "only B1 and C1 depend on A1");
+        assert_eq!(wb.sheet(0).unwrap().get_display(0, 2), "21");
+
+        let mut full = wb.clone();
+        let full_report = full.recompute_full_ordered();
+        assert_eq!(full_report.cells_recomputed, 22);
+        for row in 0..20 {
+            for col in 0..5 {
+                assert_eq!(
+                    wb.sheet(0).unwrap().get_display(row, col),
+                    full.sheet(0).unwrap().get_display(row, col),
+                    "row {} col {}", row, col
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_recompute_dirty_falls_back_to_full_after_structure_change() {
+        // B1 = A1*2; inserting a row moves it to B2 without rewriting its
+        // reference, so the dep graph still points at the old positions
+        let mut wb = Workbook::new();
+        let sid = wb.sheet_id_at_idx(0).unwrap();
+
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "5");
+        wb.sheet_mut(0).unwrap().set_value(0, 1, "=A1*2");
+        wb.rebuild_dep_graph();
+        wb.recompute_full_ordered();
+
+        wb.sheet_mut(0).unwrap().insert_rows(0, 1);
+        wb.note_structure_changed();
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "7");
+
+        let report = wb.recompute_dirty(&[CellId::new(sid, 0, 0)]);
+        assert_eq!(report.cells_recomputed, 1);
+        assert_eq!(wb.sheet(0).unwrap().get_display(1, 1), "14");
+
+        // The flag is consumed: the next call is incremental again
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "8");
+        let report = wb.recompute_dirty(&[CellId::new(sid, 0, 0)]);
+        assert_eq!(report.cells_recomputed, 1);
+        assert_eq!(wb.sheet(0).unwrap().get_display(1, 1), "16");
+    }
+
+    #[test]
+    fn test_tracked_edit_after_structure_change_recomputes_full() {
+        // Same setup outside a batch: the tracked edit must not trust the
+        // stale dep graph, which still has B1 (now empty) depending on A1
+        let mut wb = Workbook::new();
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "5");
+        wb.sheet_mut(0).unwrap().set_value(0, 1, "=A1*2");
+        wb.rebuild_dep_graph();
+        wb.recompute_full_ordered();
+
+        wb.sheet_mut(0).unwrap().insert_rows(0, 1);
+        wb.note_structure_changed();
+        wb.set_cell_value_tracked(0, 0, 0, "7");
+        assert_eq!(wb.sheet(0).unwrap().get_display(1, 1), "14");
+    }
+
+    #[test]
+    fn test_subscribe_receives_changes_in_order() {
+        use crate::events::CellChange;
+
+        let mut wb = Workbook::new();
+        let sid = wb.sheet_id_at_idx(0).unwrap();
+        let rx = wb.subscribe();
+        let start = wb.revision();
+
+        wb.set_cell_value_tracked(0, 0, 0, "1");  // A1
+        wb.set_cell_value_tracked(0, 1, 0, "2");  // A2
+
+        wb.begin_batch();
+        wb.set_cell_value_tracked(0, 0, 1, "x");  // B1
+        wb.clear_cell_tracked(0, 0, 0);           // A1
+        wb.end_batch();
+
+        let got: Vec<CellChange> = rx.try_iter().collect();
+        assert_eq!(got, vec![
+            CellChange { revision: start + 1, cell: CellId::new(sid, 0, 0) },
+            CellChange { revision: start + 2, cell: CellId::new(sid, 1, 0) },
+            CellChange { revision: start + 3, cell: CellId::new(sid, 0, 1) },
+            CellChange { revision: start + 3, cell: CellId::new(sid, 0, 0) },
+        ]);
+
+        // Dropping the receiver unsubscribes without disturbing edits.
+        drop(rx);
+        wb.set_cell_value_tracked(0, 2, 0, "3");
+        assert_eq!(wb.sheet(0).unwrap().get_display(2, 0), "3");
+    }
+
+    #[test]
+    fn test_subscribe_manual_mode_waits_for_batch_end() {
+        use crate::events::CellChange;
+
+        let mut wb = Workbook::new();
+        let sid = wb.sheet_id_at_idx(0).unwrap();
+        wb.set_auto_recalc(false);
+        let rx = wb.subscribe();
+        let rev = wb.revision();
+
+        wb.begin_batch();
+        wb.set_cell_value_tracked(0, 0, 0, "1");  // A1
+        wb.set_cell_value_tracked(0, 1, 0, "2");  // A2
+        assert_eq!(rx.try_iter().count(), 0);
+        assert_eq!(wb.end_batch(), vec![CellId::new(sid, 0, 0), CellId::new(sid, 1, 0)]);
+
+        let got: Vec<CellChange> = rx.try_iter().collect();
+        assert_eq!(got, vec![
+            CellChange { revision: rev, cell: CellId::new(sid, 0, 0) },
+            CellChange { revision: rev, cell: CellId::new(sid, 1, 0) },
+        ]);
+    }
+
     #[test]
     fn test_incremental_recalc_diamond() {
         // A1=5, B1=A1*2, C1=A1*3, D1=B1+C1
@@ -3512,6 +3814,24 @@ mod tests {
         assert_eq!(wb.sheet(0).unwrap().get_display(0, 1), "51");
     }
 
+    #[test]
+    fn test_structure_change_bumps_revision_once_per_batch() {
+        let mut wb = Workbook::new();
+        let rev = wb.revision();
+
+        wb.note_structure_changed();
+        assert_eq!(wb.revision(), rev + 1);
+
+        {
+            let mut guard = wb.batch_guard();
+            guard.sheet_mut(0).unwrap().insert_rows(0, 2);
+            guard.note_structure_changed();
+            guard.set_cell_value_tracked(0, 0, 0, "x");
+            assert_eq!(guard.revision(), rev + 1, "deferred until end_batch");
+        }
+        assert_eq!(wb.revision(), rev + 2);
+    }
+
     #[test]
     fn test_cycle_error_propagation() {
         // A1==B1, B1==A1 — creates a cycle. After recalc, both should show #CYCLE!
@@ -1,8 +1,10 @@
 //! Hub HTTP client.
 //!
 //! Blocking reqwest client (no Tokio runtime required).
-//! Covers the full publish flow: create revision → upload → complete → poll.
+//! Covers the full publish flow: create revision → upload → complete → poll,
+//! and the reverse: download a published revision's artifact.
 
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::thread;
 use std::time::Duration;
@@ -34,6 +36,8 @@ pub enum HubError {
     Validation(String),
     /// Timeout waiting for processing
     Timeout(String),
+    /// Downloaded bytes don't match the revision's recorded content hash
+    HashMismatch { expected: String, actual: String },
 }
 
 impl std::fmt::Display for HubError {
@@ -46,6 +50,9 @@ impl std::fmt::Display for HubError {
             HubError::Io(msg) => write!(f, "I/O error: {}", msg),
             HubError::Validation(msg) => write!(f, "{}", msg),
             HubError::Timeout(msg) => write!(f, "Timeout: {}", msg),
+            HubError::HashMismatch { expected, actual } => write!(
+                f, "Content hash mismatch: revision records {}, downloaded {}", expected, actual
+            ),
         }
     }
 }
@@ -114,6 +121,8 @@ pub struct CreateRevisionOptions {
     pub source_metadata: Option<serde_json::Value>,
     /// Commit message for the revision.
     pub message: Option<String>,
+    /// Revision labels (e.g. `env=prod`, `period=2024Q4`) for later filtering.
+    pub tags: BTreeMap<String, String>,
 }
 
 /// Status of a run (from the runs API).
@@ -130,9 +139,87 @@ pub struct RunResult {
     pub source_metadata: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub assertions: Option<Vec<AssertionResult>>,
+    /// What the integrity check found, when the server ran one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_report: Option<CheckReport>,
+    /// Tags the server accepted for this revision.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
     pub proof_url: String,
 }
 
+/// Details of the server-side integrity check for a run.
+///
+/// Read from the run's `check_report` object when the server sends one,
+/// otherwise from `diff_summary`, so older servers still produce a report.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct CheckReport {
+    /// "pass", "warn", "fail", or "baseline_created"
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_count_change: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub col_count_change: Option<i64>,
+    pub columns_added: Vec<String>,
+    pub columns_removed: Vec<String>,
+    pub columns_type_changed: Vec<ColumnTypeChange>,
+    /// Policy rules that did not pass (e.g. "row_count", "columns_added")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<String>,
+}
+
+/// A column whose inferred type differs from the baseline.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ColumnTypeChange {
+    pub column: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+impl CheckReport {
+    /// Build the report from a run object. `None` if no check ran.
+    pub fn from_run(run: &serde_json::Value) -> Option<Self> {
+        let status = run["check_status"].as_str()?;
+        let source = match run.get("check_report") {
+            Some(report) if report.is_object() => report,
+            _ => &run["diff_summary"],
+        };
+
+        let names = |key: &str| -> Vec<String> {
+            source[key].as_array()
+                .map(|items| items.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default()
+        };
+        // Entries are bare column names or {column, from, to} objects
+        let columns_type_changed = source["columns_type_changed"].as_array()
+            .map(|items| items.iter().filter_map(|v| match v {
+                serde_json::Value::String(column) => Some(ColumnTypeChange {
+                    column: column.clone(),
+                    from: None,
+                    to: None,
+                }),
+                _ => Some(ColumnTypeChange {
+                    column: v["column"].as_str()?.to_string(),
+                    from: v["from"].as_str().map(String::from),
+                    to: v["to"].as_str().map(String::from),
+                }),
+            }).collect())
+            .unwrap_or_default();
+
+        Some(CheckReport {
+            status: source["status"].as_str().unwrap_or(status).to_string(),
+            row_count_change: source["row_count_change"].as_i64(),
+            col_count_change: source["col_count_change"].as_i64(),
+            columns_added: names("columns_added"),
+            columns_removed: names("columns_removed"),
+            columns_type_changed,
+            violations: names("violations"),
+        })
+    }
+}
+
 /// User info from /api/desktop/me
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct UserInfo {
@@ -166,6 +253,25 @@ pub struct DatasetStatus {
     pub source_metadata: Option<serde_json::Value>,
 }
 
+impl DatasetStatus {
+    /// Whether the current revision holds exactly `content_hash`.
+    ///
+    /// `content_hash` must come from `hash_file`/`hash_bytes`: that is the
+    /// value `create_revision` sends and the server records.
+    pub fn has_content(&self, content_hash: &str) -> bool {
+        self.current_revision_id.is_some() && self.content_hash.as_deref() == Some(content_hash)
+    }
+}
+
+/// A revision artifact written to disk by `download_revision`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DownloadedRevision {
+    pub revision_id: String,
+    pub version: Option<u64>,
+    pub content_hash: String,
+    pub byte_size: u64,
+}
+
 impl HubClient {
     /// Create a new client using saved auth credentials.
     pub fn from_saved_auth() -> Result<Self, HubError> {
@@ -310,6 +416,10 @@ impl HubClient {
             body["format"] = serde_json::json!(fmt);
         }
 
+        if !opts.tags.is_empty() {
+            body["tags"] = serde_json::json!(opts.tags);
+        }
+
         let resp = self.post_json(&url, &body)?;
         let json: serde_json::Value = resp.json().map_err(|e| HubError::Parse(e.to_string()))?;
 
@@ -391,6 +501,9 @@ impl HubClient {
                             // Parse assertions if present
                             let assertions: Option<Vec<AssertionResult>> = run.get("assertions")
                                 .and_then(|v| serde_json::from_value(v.clone()).ok());
+                            let tags: BTreeMap<String, String> = run.get("tags")
+                                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                                .unwrap_or_default();
 
                             return Ok(RunResult {
                                 run_id: revision_id.to_string(),
@@ -403,6 +516,8 @@ impl HubClient {
                                 content_hash: run["content_hash"].as_str().map(String::from),
                                 source_metadata: run.get("source_metadata").cloned(),
                                 assertions,
+                                check_report: CheckReport::from_run(run),
+                                tags,
                                 proof_url: proof_url.clone(),
                             });
                         }
@@ -420,6 +535,52 @@ impl HubClient {
         }
     }
 
+    /// Download the stored artifact of a published revision to `out_path`.
+    ///
+    /// `revision` is a revision id, a version label like `v5`, or `latest`.
+    /// The bytes are hashed and compared against the revision's recorded
+    /// content hash before anything is written; a mismatch leaves `out_path`
+    /// untouched.
+    pub fn download_revision(
+        &self,
+        dataset_id: &str,
+        revision: &str,
+        out_path: &Path,
+    ) -> Result<DownloadedRevision, HubError> {
+        let url = format!("{}/api/desktop/datasets/{}/revisions/{}", self.api_base, dataset_id, revision);
+        let resp = self.get(&url)?;
+        let json: serde_json::Value = resp.json().map_err(|e| HubError::Parse(e.to_string()))?;
+
+        let revision_id = json_str(&json, "revision_id")?;
+        let expected = json_str(&json, "content_hash")?;
+        let download_url = json_str(&json, "download_url")?;
+
+        // Signed URL: no bearer token, mirroring upload_bytes
+        let response = self.http.get(&download_url)
+            .send()
+            .map_err(|e| HubError::Network(e.to_string()))?;
+        let status = response.status().as_u16();
+        if !response.status().is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(HubError::Http(status, body));
+        }
+        let data = response.bytes().map_err(|e| HubError::Network(e.to_string()))?;
+
+        let actual = hash_bytes(&data);
+        if actual != expected {
+            return Err(HubError::HashMismatch { expected, actual });
+        }
+
+        std::fs::write(out_path, &data).map_err(|e| HubError::Io(e.to_string()))?;
+
+        Ok(DownloadedRevision {
+            revision_id,
+            version: json["version"].as_u64(),
+            content_hash: actual,
+            byte_size: data.len() as u64,
+        })
+    }
+
     /// Build the proof URL for a run.
     pub fn proof_url(&self, owner: &str, slug: &str, run_id: &str) -> String {
         format!("{}/api/repos/{}/{}/runs/{}/proof", self.api_base, owner, slug, run_id)
@@ -467,6 +628,26 @@ impl HubClient {
 
 // ── Free functions ──────────────────────────────────────────────────
 
+/// Parse repeatable `key=value` tag specs into a sorted map.
+///
+/// The value may contain `=`; the key may not be empty. Repeating a key is an
+/// error rather than a silent overwrite.
+pub fn parse_tags(specs: &[String]) -> Result<BTreeMap<String, String>, String> {
+    let mut tags = BTreeMap::new();
+    for spec in specs {
+        let (key, value) = spec.split_once('=')
+            .ok_or_else(|| format!("invalid tag {:?} (expected key=value)", spec))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("invalid tag {:?}: key is empty", spec));
+        }
+        if tags.insert(key.to_string(), value.trim().to_string()).is_some() {
+            return Err(format!("duplicate tag key {:?}", key));
+        }
+    }
+    Ok(tags)
+}
+
 /// Compute blake3 hash of a file (with algorithm prefix).
 pub fn hash_file(path: &Path) -> Result<String, HubError> {
     let contents = std::fs::read(path)
@@ -590,6 +771,8 @@ mod tests {
             content_hash: Some("blake3:deadbeef".into()),
             source_metadata: Some(serde_json::json!({"type": "dbt", "identity": "models/payments"})),
             assertions: None,
+            check_report: None,
+            tags: BTreeMap::new(),
             proof_url: "https://api.visiapi.com/api/repos/acme/payments/runs/99/proof".into(),
         };
 
@@ -608,6 +791,48 @@ mod tests {
         assert_ne!(passing.check_status.as_deref(), Some("fail"));
     }
 
+    #[test]
+    fn test_check_report_from_run() {
+        // Detailed report from the server wins over diff_summary
+        let run = serde_json::json!({
+            "check_status": "fail",
+            "diff_summary": { "row_count_change": -50 },
+            "check_report": {
+                "row_count_change": -50,
+                "col_count_change": 1,
+                "columns_added": ["new_fee"],
+                "columns_removed": [],
+                "columns_type_changed": [{ "column": "amount", "from": "number", "to": "text" }],
+                "violations": ["row_count"],
+            },
+        });
+        let report = CheckReport::from_run(&run).unwrap();
+        assert_eq!(report.status, "fail");
+        assert_eq!(report.row_count_change, Some(-50));
+        assert_eq!(report.columns_added, vec!["new_fee"]);
+        assert_eq!(report.columns_type_changed, vec![ColumnTypeChange {
+            column: "amount".into(),
+            from: Some("number".into()),
+            to: Some("text".into()),
+        }]);
+        assert_eq!(report.violations, vec!["row_count"]);
+
+        // Older servers: fall back to diff_summary, type changes as bare names
+        let run = serde_json::json!({
+            "check_status": "warn",
+            "diff_summary": { "col_count_change": -1, "columns_removed": ["memo"], "columns_type_changed": ["date"] },
+        });
+        let report = CheckReport::from_run(&run).unwrap();
+        assert_eq!(report.status, "warn");
+        assert_eq!(report.row_count_change, None);
+        assert_eq!(report.columns_removed, vec!["memo"]);
+        assert_eq!(report.columns_type_changed[0].column, "date");
+        assert_eq!(report.columns_type_changed[0].from, None);
+
+        // No check ran
+        assert_eq!(CheckReport::from_run(&serde_json::json!({ "status": "verified" })), None);
+    }
+
     #[test]
     fn test_run_result_json_schema() {
         let result = RunResult {
@@ -624,6 +849,8 @@ mod tests {
             content_hash: Some("blake3:abc123".into()),
             source_metadata: Some(serde_json::json!({"type": "dbt"})),
             assertions: None,
+            check_report: None,
+            tags: BTreeMap::new(),
             proof_url: "https://api.visiapi.com/api/repos/acme/payments/runs/42/proof".into(),
         };
 
@@ -636,4 +863,158 @@ mod tests {
         assert!(json["proof_url"].is_string());
         assert!(json["source_metadata"].is_object());
     }
+
+    #[test]
+    fn test_parse_tags() {
+        let specs = vec!["period=2024Q4".to_string(), "env = prod".to_string(), "filter=a=b".to_string()];
+        let tags = parse_tags(&specs).unwrap();
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags["env"], "prod");
+        assert_eq!(tags["period"], "2024Q4");
+        assert_eq!(tags["filter"], "a=b");
+
+        // Empty value is allowed; empty key, missing '=', and repeats are not
+        assert_eq!(parse_tags(&["reviewed=".to_string()]).unwrap()["reviewed"], "");
+        assert!(parse_tags(&["=prod".to_string()]).is_err());
+        assert!(parse_tags(&["env".to_string()]).is_err());
+        assert!(parse_tags(&["env=prod".to_string(), "env=dev".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_create_revision_sends_tags() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let create = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/desktop/datasets/7/revisions")
+                .json_body_includes(r#"{"tags": {"env": "prod", "period": "2024Q4"}}"#);
+            then.status(200).json_body(serde_json::json!({
+                "revision_id": 12,
+                "upload_url": "https://upload.example/12",
+            }));
+        });
+
+        let client = HubClient::new(AuthCredentials::new("tok".into(), server.base_url()));
+        let opts = CreateRevisionOptions {
+            tags: parse_tags(&["env=prod".to_string(), "period=2024Q4".to_string()]).unwrap(),
+            ..Default::default()
+        };
+        let (revision_id, _, _) = client.create_revision("7", "blake3:abc", 10, &opts).unwrap();
+
+        create.assert();
+        assert_eq!(revision_id, "12");
+    }
+
+    #[test]
+    fn test_dataset_status_has_content() {
+        use httpmock::prelude::*;
+
+        let data = b"id,amount\n1,100\n";
+        let server = MockServer::start();
+        let status = server.mock(|when, then| {
+            when.method(GET).path("/api/desktop/datasets/7/status");
+            then.status(200).json_body(serde_json::json!({
+                "current_revision_id": 12,
+                "content_hash": hash_bytes(data),
+                "byte_size": data.len(),
+            }));
+        });
+
+        let client = HubClient::new(AuthCredentials::new("tok".into(), server.base_url()));
+        let st = client.get_dataset_status("7").unwrap();
+        status.assert();
+        assert!(st.has_content(&hash_bytes(data)));
+        assert!(!st.has_content(&hash_bytes(b"id,amount\n1,101\n")));
+
+        // A dataset with no revisions never matches
+        let empty = DatasetStatus { current_revision_id: None, content_hash: None, byte_size: None, source_metadata: None };
+        assert!(!empty.has_content(&hash_bytes(data)));
+    }
+
+    #[test]
+    fn test_download_revision_verifies_hash() {
+        use httpmock::prelude::*;
+
+        let data = b"id,amount\n1,100\n";
+        let server = MockServer::start();
+        let meta = server.mock(|when, then| {
+            when.method(GET).path("/api/desktop/datasets/7/revisions/v5");
+            then.status(200).json_body(serde_json::json!({
+                "revision_id": 12,
+                "version": 5,
+                "content_hash": hash_bytes(data),
+                "download_url": server.url("/blobs/12"),
+            }));
+        });
+        let blob = server.mock(|when, then| {
+            when.method(GET).path("/blobs/12");
+            then.status(200).body(data);
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("data.csv");
+        let client = HubClient::new(AuthCredentials::new("tok".into(), server.base_url()));
+        let downloaded = client.download_revision("7", "v5", &out).unwrap();
+
+        meta.assert();
+        blob.assert();
+        assert_eq!(downloaded.revision_id, "12");
+        assert_eq!(downloaded.version, Some(5));
+        assert_eq!(downloaded.byte_size, data.len() as u64);
+        assert_eq!(std::fs::read(&out).unwrap(), data);
+    }
+
+    #[test]
+    fn test_download_revision_hash_mismatch() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/api/desktop/datasets/7/revisions/v5");
+            then.status(200).json_body(serde_json::json!({
+                "revision_id": 12,
+                "content_hash": hash_bytes(b"what was published"),
+                "download_url": server.url("/blobs/12"),
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/blobs/12");
+            then.status(200).body("something else");
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("data.csv");
+        let client = HubClient::new(AuthCredentials::new("tok".into(), server.base_url()));
+        let err = client.download_revision("7", "v5", &out).unwrap_err();
+
+        match err {
+            HubError::HashMismatch { expected, actual } => {
+                assert_eq!(expected, hash_bytes(b"what was published"));
+                assert_eq!(actual, hash_bytes(b"something else"));
+            }
+            other => panic!("expected HashMismatch, got {:?}", other),
+        }
+        assert!(!out.exists(), "mismatched download must not be written");
+    }
+
+    #[test]
+    fn test_create_revision_omits_empty_tags() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let create = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/desktop/datasets/7/revisions")
+                .body_excludes("\"tags\"");
+            then.status(200).json_body(serde_json::json!({
+                "revision_id": 13,
+                "upload_url": "https://upload.example/13",
+            }));
+        });
+
+        let client = HubClient::new(AuthCredentials::new("tok".into(), server.base_url()));
+        client.create_revision("7", "blake3:abc", 10, &CreateRevisionOptions::default()).unwrap();
+        create.assert();
+    }
 }
@@ -7,7 +7,7 @@
 //! The golden files are the public contract. CI scripts parse this JSON.
 //! Breaking it without versioning breaks customers.
 
-use visigrid_hub_client::{RunResult, AssertionResult, EngineMetadata, CreateRevisionOptions};
+use visigrid_hub_client::{RunResult, AssertionResult, CheckReport, ColumnTypeChange, EngineMetadata, CreateRevisionOptions};
 
 /// Validate that every key in the golden JSON is present in RunResult serialization.
 fn validate_golden_keys(golden_path: &str, result: &RunResult) {
@@ -63,6 +63,8 @@ fn test_golden_publish_pass() {
             origin: None,
             engine: None,
         }]),
+        check_report: None,
+        tags: Default::default(),
         proof_url: "https://api.visiapi.com/api/repos/acme/payments/runs/42/proof".into(),
     };
 
@@ -102,6 +104,20 @@ fn test_golden_publish_fail() {
             origin: None,
             engine: None,
         }]),
+        check_report: Some(CheckReport {
+            status: "fail".into(),
+            row_count_change: Some(-50),
+            col_count_change: Some(2),
+            columns_added: vec!["new_fee".into(), "tax_rate".into()],
+            columns_removed: vec![],
+            columns_type_changed: vec![ColumnTypeChange {
+                column: "amount".into(),
+                from: Some("number".into()),
+                to: Some("text".into()),
+            }],
+            violations: vec!["row_count".into()],
+        }),
+        tags: Default::default(),
         proof_url: "https://api.visiapi.com/api/repos/acme/payments/runs/99/proof".into(),
     };
 
@@ -111,6 +127,9 @@ fn test_golden_publish_fail() {
     assert_eq!(json["check_status"], "fail");
     assert_eq!(json["assertions"][0]["status"], "fail");
     assert!(json["assertions"][0]["delta"].is_string());
+    assert_eq!(json["check_report"]["row_count_change"], -50);
+    assert_eq!(json["check_report"]["columns_type_changed"][0]["column"], "amount");
+    assert_eq!(json["check_report"]["violations"][0], "row_count");
 }
 
 #[test]
@@ -137,6 +156,8 @@ fn test_golden_publish_baseline() {
             origin: None,
             engine: None,
         }]),
+        check_report: None,
+        tags: Default::default(),
         proof_url: "https://api.visiapi.com/api/repos/acme/payments/runs/1/proof".into(),
     };
 
@@ -166,6 +187,8 @@ fn test_golden_publish_warn() {
         content_hash: Some("blake3:b8ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a".into()),
         source_metadata: Some(serde_json::json!({"type": "dbt", "identity": "models/payments"})),
         assertions: None,
+        check_report: None,
+        tags: Default::default(),
         proof_url: "https://api.visiapi.com/api/repos/acme/payments/runs/55/proof".into(),
     };
 
@@ -202,6 +225,8 @@ fn test_run_result_required_fields_never_null() {
         content_hash: None,
         source_metadata: None,
         assertions: None,
+        check_report: None,
+        tags: Default::default(),
         proof_url: "https://example.com/proof".into(),
     };
 
@@ -283,6 +308,8 @@ fn test_golden_publish_cell_assertion_pass() {
                 fingerprint: None,
             }),
         }]),
+        check_report: None,
+        tags: Default::default(),
         proof_url: "https://api.visiapi.com/api/repos/acme/recon/runs/100/proof".into(),
     };
 
@@ -11,6 +11,21 @@ pub fn import(path: &Path) -> Result<Sheet, String> {
     import_from_string(&content, delimiter)
 }
 
+/// Import a delimited file (sniffing the delimiter when `None`), also returning
+/// notes about input quirks that were tolerated, e.g. mixed line endings, and
+/// the ragged-row diagnostic behind one of those notes.
+pub fn import_with_notes(
+    path: &Path,
+    delimiter: Option<u8>,
+) -> Result<(Sheet, Vec<String>, Option<RaggedRows>), String> {
+    let content = read_file_as_utf8(path)?;
+    let delimiter = delimiter.unwrap_or_else(|| sniff_delimiter(&content));
+    let ragged = ragged_rows(&content, delimiter);
+    let mut notes: Vec<String> = line_ending_note(&content).into_iter().collect();
+    notes.extend(ragged.as_ref().map(RaggedRows::note));
+    Ok((import_from_string(&content, delimiter)?, notes, ragged))
+}
+
 pub fn import_tsv(path: &Path) -> Result<Sheet, String> {
     let content = read_file_as_utf8(path)?;
     import_from_string(&content, b'\t')
@@ -98,11 +113,138 @@ pub fn sniff_delimiter(content: &str) -> u8 {
     best
 }
 
+/// UTF-8 byte order mark. Excel's "CSV UTF-8" export writes one; left in
+/// place it ends up glued to the first header name.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Strip a leading byte order mark from already-decoded text (e.g. stdin).
+pub fn strip_bom(content: &str) -> &str {
+    content.strip_prefix('\u{feff}').unwrap_or(content)
+}
+
+/// Describe mixed CRLF/LF line endings, if the text has both.
+///
+/// The csv reader accepts either terminator, so such files parse; but they
+/// usually mean two exports were concatenated, and bare `\r` left inside
+/// quoted fields shows up as stray characters.
+pub fn line_ending_note(content: &str) -> Option<String> {
+    let bytes = content.as_bytes();
+    let lf = bytes.iter().filter(|&&b| b == b'\n').count();
+    let crlf = bytes.windows(2).filter(|w| w == b"\r\n").count();
+    if crlf > 0 && crlf < lf {
+        Some(format!("mixed line endings ({} CRLF, {} LF)", crlf, lf - crlf))
+    } else {
+        None
+    }
+}
+
+/// Records whose field count differs from the file's usual width.
+///
+/// The importer is flexible about ragged input, so these rows load with
+/// their fields shifted under the wrong columns or padded with blanks.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RaggedRows {
+    /// Modal field count; ties go to the first record's count (the header).
+    pub expected_fields: usize,
+    /// Number of records with any other field count.
+    pub count: usize,
+    /// 1-based line number where the first offending record starts.
+    pub first_line: u64,
+}
+
+impl RaggedRows {
+    /// One-line description for stderr warnings and import notes.
+    pub fn note(&self) -> String {
+        format!(
+            "{} ragged row{} (expected {} fields; first at line {})",
+            self.count,
+            if self.count == 1 { "" } else { "s" },
+            self.expected_fields,
+            self.first_line,
+        )
+    }
+}
+
+/// Find records whose field count differs from the header/modal count.
+///
+/// Returns `None` when every record has the same width. Unparseable input
+/// is left for the importer to report.
+pub fn ragged_rows(content: &str, delimiter: u8) -> Option<RaggedRows> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(strip_bom(content).as_bytes());
+
+    // (field count, line) per record
+    let mut widths: Vec<(usize, u64)> = Vec::new();
+    for result in reader.records() {
+        let record = result.ok()?;
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        widths.push((record.len(), line));
+    }
+    let header_width = widths.first()?.0;
+
+    let mut counts: std::collections::BTreeMap<usize, usize> = std::collections::BTreeMap::new();
+    for &(width, _) in &widths {
+        *counts.entry(width).or_insert(0) += 1;
+    }
+    let expected_fields = counts
+        .iter()
+        .max_by_key(|&(&width, &n)| (n, width == header_width))
+        .map(|(&width, _)| width)?;
+
+    let mut offending = widths.iter().filter(|&&(width, _)| width != expected_fields);
+    let first_line = offending.next()?.1;
+    Some(RaggedRows { expected_fields, count: 1 + offending.count(), first_line })
+}
+
+/// gzip member header (RFC 1952).
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Lowercased extension that names the file's data format, looking through a
+/// trailing `.gz` (`data.csv.gz` → `csv`).
+pub fn data_extension(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    if ext != "gz" {
+        return Some(ext);
+    }
+    let stem = Path::new(path.file_stem()?);
+    stem.extension()?.to_str().map(|e| e.to_lowercase())
+}
+
+/// Open a file for reading, transparently decompressing gzip.
+///
+/// Detection is by magic bytes, not extension, so a mislabeled `.csv` that is
+/// actually gzip still reads, and a `.gz` that is plain text is passed through.
+pub fn open_decompressed(path: &Path) -> std::io::Result<Box<dyn Read>> {
+    let mut file = std::fs::File::open(path)?;
+    let mut magic = [0u8; 2];
+    let mut n = 0;
+    while n < magic.len() {
+        match file.read(&mut magic[n..])? {
+            0 => break,
+            k => n += k,
+        }
+    }
+    let head = std::io::Cursor::new(magic[..n].to_vec()).chain(file);
+    if magic[..n] == GZIP_MAGIC {
+        Ok(Box::new(flate2::read::MultiGzDecoder::new(head)))
+    } else {
+        Ok(Box::new(head))
+    }
+}
+
 /// Read file and convert to UTF-8 if needed (handles Windows-1252, Latin-1, etc.)
+///
+/// gzip-compressed files are decompressed first (see `open_decompressed`).
 pub fn read_file_as_utf8(path: &Path) -> Result<String, String> {
-    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut file = open_decompressed(path).map_err(|e| e.to_string())?;
     let mut bytes = Vec::new();
     file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    if bytes.starts_with(UTF8_BOM) {
+        bytes.drain(..UTF8_BOM.len());
+    }
 
     // Try UTF-8 first; on failure, recover the buffer from the error
     match String::from_utf8(bytes) {
@@ -116,7 +258,45 @@ pub fn read_file_as_utf8(path: &Path) -> Result<String, String> {
     }
 }
 
-fn import_from_string(content: &str, delimiter: u8) -> Result<Sheet, String> {
+/// An explicitly named source encoding, for inputs where the UTF-8 /
+/// Windows-1252 guess in `read_file_as_utf8` is not good enough.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    /// ISO-8859-1: every byte maps to the code point of the same value.
+    Latin1,
+    Windows1252,
+    /// UTF-16 with a BOM selecting the byte order; little-endian without one.
+    Utf16,
+}
+
+/// Decode `bytes` from `encoding` into UTF-8. Malformed sequences become
+/// U+FFFD; a leading UTF-8 or UTF-16 BOM is honored and dropped, except for
+/// Latin-1, where every byte sequence is valid text.
+pub fn decode_bytes(bytes: &[u8], encoding: TextEncoding) -> String {
+    let encoding = match encoding {
+        // encoding_rs follows WHATWG, which aliases ISO-8859-1 to Windows-1252;
+        // decode it byte-for-byte so 0x80-0x9F stay C1 controls
+        TextEncoding::Latin1 => return bytes.iter().map(|&b| b as char).collect(),
+        TextEncoding::Utf8 => encoding_rs::UTF_8,
+        TextEncoding::Windows1252 => encoding_rs::WINDOWS_1252,
+        TextEncoding::Utf16 => encoding_rs::UTF_16LE,
+    };
+    // `decode` sniffs a BOM, which also switches UTF-16LE to BE when present
+    let (decoded, _, _) = encoding.decode(bytes);
+    decoded.into_owned()
+}
+
+/// Read a (possibly gzip-compressed) file and decode it from `encoding`.
+pub fn read_file_with_encoding(path: &Path, encoding: TextEncoding) -> Result<String, String> {
+    let mut file = open_decompressed(path).map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(decode_bytes(&bytes, encoding))
+}
+
+/// Import already-decoded delimited text; `import` without the file read.
+pub fn import_from_string(content: &str, delimiter: u8) -> Result<Sheet, String> {
     let mut reader = csv::ReaderBuilder::new()
         .delimiter(delimiter)
         .has_headers(false)
@@ -127,17 +307,19 @@ fn import_from_string(content: &str, delimiter: u8) -> Result<Sheet, String> {
     let mut sheet = Sheet::new(SheetId(1), 65536, 256);
     let mut max_row = 0usize;
     let mut max_col = 0usize;
+    let mut cells = Vec::new();
 
     for (row_idx, result) in reader.records().enumerate() {
         let record = result.map_err(|e| e.to_string())?;
         for (col_idx, field) in record.iter().enumerate() {
             if !field.is_empty() {
-                sheet.set_value(row_idx, col_idx, field);
+                cells.push((row_idx, col_idx, field.to_string()));
                 max_col = max_col.max(col_idx);
             }
         }
         max_row = row_idx;
     }
+    sheet.set_values_bulk(cells.into_iter());
 
     // Update sheet dimensions to actual data extent (for export efficiency)
     sheet.rows = (max_row + 1).max(1000);
@@ -243,6 +425,32 @@ mod tests {
         assert_eq!(records[1].get(2), Some("C"));
     }
 
+    #[test]
+    fn test_decode_windows_1252() {
+        // "£5,café" as exported by Excel on Windows
+        let bytes = b"\xA35,caf\xE9\n";
+        assert_eq!(decode_bytes(bytes, TextEncoding::Windows1252), "£5,café\n");
+        // 0x80 is the euro sign in Windows-1252 but a C1 control in Latin-1
+        assert_eq!(decode_bytes(b"\x80", TextEncoding::Windows1252), "€");
+        assert_eq!(decode_bytes(b"\x80", TextEncoding::Latin1), "\u{80}");
+        assert_eq!(decode_bytes(bytes, TextEncoding::Latin1), "£5,café\n");
+    }
+
+    #[test]
+    fn test_decode_utf16_bom() {
+        let text = "name,city\nZoë,Zürich\n";
+        let le: Vec<u8> = [0xFF, 0xFE].into_iter()
+            .chain(text.encode_utf16().flat_map(|u| u.to_le_bytes()))
+            .collect();
+        let be: Vec<u8> = [0xFE, 0xFF].into_iter()
+            .chain(text.encode_utf16().flat_map(|u| u.to_be_bytes()))
+            .collect();
+        let bare: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        assert_eq!(decode_bytes(&le, TextEncoding::Utf16), text);
+        assert_eq!(decode_bytes(&be, TextEncoding::Utf16), text);
+        assert_eq!(decode_bytes(&bare, TextEncoding::Utf16), text);
+    }
+
     #[test]
     fn test_sniff_semicolon_delimiter() {
         let content = "Name;Age;City\nAlice;30;Paris\nBob;25;London\n";
@@ -357,4 +565,82 @@ mod tests {
         assert_eq!(imported.get_display(2, 0), "Bob");
         assert_eq!(imported.get_display(2, 1), "17");
     }
+
+    #[test]
+    fn test_import_gzip_csv() {
+        use std::io::Write;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("data.csv.gz");
+        let mut encoder = flate2::write::GzEncoder::new(fs::File::create(&path).unwrap(), flate2::Compression::default());
+        encoder.write_all(b"Name;Age\nAlice;30\n").unwrap();
+        encoder.finish().unwrap();
+
+        let sheet = import(&path).unwrap();
+        assert_eq!(sheet.get_display(0, 0), "Name");
+        assert_eq!(sheet.get_display(1, 1), "30");
+
+        // Detection is by magic bytes: plain text behind a .gz name passes through
+        let plain = dir.path().join("plain.csv.gz");
+        fs::write(&plain, "a,b\n1,2\n").unwrap();
+        assert_eq!(import(&plain).unwrap().get_display(1, 1), "2");
+    }
+
+    #[test]
+    fn test_import_strips_bom() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bom.csv");
+        fs::write(&path, b"\xEF\xBB\xBFName,Amount\nAlice,30\n").unwrap();
+
+        let sheet = import(&path).unwrap();
+        assert_eq!(sheet.get_display(0, 0), "Name");
+        assert_eq!(sheet.get_display(1, 1), "30");
+
+        // Non-UTF-8 body behind a BOM falls back to Windows-1252 without the BOM
+        let latin = dir.path().join("bom-latin.csv");
+        fs::write(&latin, b"\xEF\xBB\xBFName\nCaf\xE9\n").unwrap();
+        let sheet = import(&latin).unwrap();
+        assert_eq!(sheet.get_display(0, 0), "Name");
+        assert_eq!(sheet.get_display(1, 0), "Café");
+
+        assert_eq!(strip_bom("\u{feff}id,val"), "id,val");
+        assert_eq!(strip_bom("id,val"), "id,val");
+    }
+
+    #[test]
+    fn test_mixed_line_endings_note() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("mixed.csv");
+        fs::write(&path, "a,b\r\n1,2\r\n3,4\n").unwrap();
+
+        let (sheet, notes, ragged) = import_with_notes(&path, None).unwrap();
+        assert_eq!(ragged, None);
+        assert_eq!(sheet.get_display(2, 1), "4");
+        assert_eq!(notes, vec!["mixed line endings (2 CRLF, 1 LF)".to_string()]);
+
+        // Consistent endings are quiet
+        assert_eq!(line_ending_note("a,b\r\n1,2\r\n"), None);
+        assert_eq!(line_ending_note("a,b\n1,2\n"), None);
+    }
+
+    #[test]
+    fn test_ragged_rows_short_and_long() {
+        let content = "id,name,qty\n1,a,5\n2,b\n3,c,7\n4,d,8,extra\n5,e,9\n";
+        let ragged = ragged_rows(content, b',').unwrap();
+        assert_eq!(ragged, RaggedRows { expected_fields: 3, count: 2, first_line: 3 });
+        assert_eq!(ragged.note(), "2 ragged rows (expected 3 fields; first at line 3)");
+
+        assert_eq!(ragged_rows("a,b\n1,2\n3,4\n", b','), None);
+        // A quoted newline doesn't make a record ragged, and lines count from the record start
+        let quoted = ragged_rows("a,b\n\"x\ny\",2\n3\n", b',').unwrap();
+        assert_eq!((quoted.count, quoted.first_line), (1, 4));
+    }
+
+    #[test]
+    fn test_data_extension() {
+        assert_eq!(data_extension(Path::new("data.csv.gz")).as_deref(), Some("csv"));
+        assert_eq!(data_extension(Path::new("DATA.TSV.GZ")).as_deref(), Some("tsv"));
+        assert_eq!(data_extension(Path::new("data.csv")).as_deref(), Some("csv"));
+        assert_eq!(data_extension(Path::new("data.gz")), None);
+    }
 }
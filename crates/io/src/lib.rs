@@ -6,9 +6,10 @@ pub mod native;
 pub mod scripting;
 pub mod truth;
 pub mod xlsx;
+pub mod xlsx_conditional;
 pub mod xlsx_styles;
 pub mod xlsx_validation;
 
-/// Native .sheet format version
-/// Increment when schema changes in a way that old versions can't read
-pub const NATIVE_FORMAT_VERSION: u32 = 1;
+/// Native .sheet format version: the schema version this build writes
+/// (see [`native::SCHEMA_VERSION`]).
+pub const NATIVE_FORMAT_VERSION: u32 = native::SCHEMA_VERSION as u32;
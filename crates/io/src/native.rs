@@ -3,11 +3,12 @@
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, ErrorCode, params};
 
-use visigrid_engine::cell::{Alignment, BorderStyle, CellBorder, CellFormat, CellStyle, CellValue, DateStyle, NegativeStyle, NumberFormat, TextOverflow, VerticalAlignment};
+use visigrid_engine::cell::{Alignment, BorderStyle, CellBorder, CellFormat, CellStyle, CellValue, DateStyle, bool_display, NegativeStyle, NumberFormat, TextOverflow, VerticalAlignment};
+use visigrid_engine::conditional_format::ConditionalFormat;
 use visigrid_engine::formula::eval::Value;
-use visigrid_engine::sheet::{MergedRegion, Sheet, SheetId};
+use visigrid_engine::sheet::{CellComment, MergedRegion, ProtectedRange, Sheet, SheetId};
 use visigrid_engine::workbook::Workbook;
 use visigrid_engine::named_range::{NamedRange, NamedRangeTarget};
 
@@ -21,7 +22,7 @@ use visigrid_engine::named_range::{NamedRange, NamedRangeTarget};
 /// hasn't been modified since it was stamped/approved.
 #[derive(Debug, Clone, Default)]
 pub struct SemanticVerification {
-    /// Expected semantic fingerprint (e.g., "v2:186:abc123...")
+    /// Expected semantic fingerprint (e.g., "v3:186:abc123...")
     pub fingerprint: Option<String>,
     /// Optional label (e.g., "MSFT SEC v1")
     pub label: Option<String>,
@@ -31,12 +32,15 @@ pub struct SemanticVerification {
 
 /// Fingerprint format version. Increment on breaking changes to fingerprint computation.
 /// v2: includes iteration settings (enabled, max_iters, tolerance).
-const FINGERPRINT_VERSION: u32 = 2;
+/// v3: includes named ranges.
+const FINGERPRINT_VERSION: u32 = 3;
 
 /// Compute semantic fingerprint of a workbook.
 ///
 /// The fingerprint includes:
-/// - Cell values/formulas (semantic content)
+/// - Cell values/formulas (semantic content), with literal text that would
+///   re-type on entry kept distinct from the typed value
+/// - Named ranges (formulas and checks resolve through them)
 /// - Does NOT include style (presentation only)
 ///
 /// Format: `v1:N:HASH` where:
@@ -63,18 +67,18 @@ pub fn compute_semantic_fingerprint(workbook: &Workbook) -> String {
     for sheet_idx in 0..workbook.sheet_count() {
         if let Some(sheet) = workbook.sheet(sheet_idx) {
             // Collect cells and sort for deterministic order
-            let mut cells: Vec<((usize, usize), String)> = Vec::new();
+            let mut cells: Vec<((usize, usize), &str, String)> = Vec::new();
             for (&(row, col), cell) in sheet.cells_iter() {
                 let raw = cell.value.raw_display();
                 if !raw.is_empty() {
-                    cells.push(((row, col), raw.to_string()));
+                    cells.push(((row, col), fingerprint_verb(&cell.value), raw.to_string()));
                 }
             }
             // Sort by (row, col) for deterministic order
-            cells.sort_by_key(|((r, c), _)| (*r, *c));
+            cells.sort_by_key(|((r, c), _, _)| (*r, *c));
 
-            for ((row, col), value) in cells {
-                let op = format!("set:{}:{}:{}", row, col, value);
+            for ((row, col), verb, value) in cells {
+                let op = format!("{}:{}:{}:{}", verb, row, col, value);
                 hasher.update(op.as_bytes());
                 hasher.update(b"\n");
                 op_count += 1;
@@ -82,11 +86,45 @@ pub fn compute_semantic_fingerprint(workbook: &Workbook) -> String {
         }
     }
 
+    for op in named_range_fingerprint_ops(workbook) {
+        hasher.update(op.as_bytes());
+        hasher.update(b"\n");
+        op_count += 1;
+    }
+
     let hash = hasher.finalize();
     let hash_hex = &hash.to_hex()[0..16]; // First 16 hex chars (64 bits)
     format!("v{}:{}:{}", FINGERPRINT_VERSION, op_count, hash_hex)
 }
 
+/// Op name for a cell in the fingerprint: `set` when entering the raw text
+/// recreates the cell, `text` for text that would enter as something else
+/// ("TRUE", "#N/A", "00123"), so text and the typed value hash differently.
+pub fn fingerprint_verb(value: &CellValue) -> &'static str {
+    match value {
+        CellValue::Text(s) if !matches!(CellValue::from_input(s), CellValue::Text(ref t) if t == s) => "text",
+        _ => "set",
+    }
+}
+
+/// Fingerprint ops for a workbook's named ranges, sorted by lowercase name.
+///
+/// Format: `name:<lowercase name>:<sheet_idx>:<reference>`. Empty when the
+/// workbook defines no names.
+pub fn named_range_fingerprint_ops(workbook: &Workbook) -> Vec<String> {
+    let mut ops: Vec<String> = workbook.list_named_ranges()
+        .into_iter()
+        .map(|nr| {
+            let sheet = match nr.target {
+                NamedRangeTarget::Cell { sheet, .. } | NamedRangeTarget::Range { sheet, .. } => sheet,
+            };
+            format!("name:{}:{}:{}", nr.name.to_lowercase(), sheet, nr.reference_string())
+        })
+        .collect();
+    ops.sort();
+    ops
+}
+
 const SCHEMA: &str = r#"
 CREATE TABLE IF NOT EXISTS sheets (
     sheet_idx INTEGER PRIMARY KEY,
@@ -246,6 +284,8 @@ const TYPE_EMPTY: i32 = 0;
 const TYPE_NUMBER: i32 = 1;
 const TYPE_TEXT: i32 = 2;
 const TYPE_FORMULA: i32 = 3;
+const TYPE_BOOLEAN: i32 = 4;
+const TYPE_ERROR: i32 = 5;
 
 /// Encode Alignment → DB integer. Every variant has an explicit arm.
 fn alignment_to_db(a: Alignment) -> i32 {
@@ -321,25 +361,32 @@ fn border_from_db(style: i32, color: Option<i64>) -> CellBorder {
 }
 
 /// Current schema version. Increment for each migration.
-const SCHEMA_VERSION: i32 = 9;
+pub const SCHEMA_VERSION: i32 = 10;
 
-/// Run schema migrations for existing databases.
-fn migrate(conn: &Connection) -> rusqlite::Result<()> {
-    let version: i32 = conn.pragma_query_value(None, "user_version", |r| r.get(0))?;
+/// One registered schema step: the SQL that brings a file from the previous
+/// version up to `version`.
+struct SchemaStep {
+    version: i32,
+    sql: &'static str,
+}
 
-    if version < 1 {
-        // Add thousands separator, negative style, and currency symbol columns
-        conn.execute_batch("
+/// Stepwise migrations in version order. To change the schema, update
+/// `SCHEMA`, append a step here, and bump `SCHEMA_VERSION` to match.
+/// Versions need not be contiguous: there is no v3 step.
+const MIGRATIONS: &[SchemaStep] = &[
+    // Thousands separator, negative style, and currency symbol columns
+    SchemaStep {
+        version: 1,
+        sql: "
             ALTER TABLE cells ADD COLUMN fmt_thousands INTEGER DEFAULT 0;
             ALTER TABLE cells ADD COLUMN fmt_negative INTEGER DEFAULT 0;
             ALTER TABLE cells ADD COLUMN fmt_currency_symbol TEXT;
-            PRAGMA user_version = 1;
-        ")?;
-    }
-
-    if version < 2 {
-        // Add border style and color columns
-        conn.execute_batch("
+        ",
+    },
+    // Border style and color columns
+    SchemaStep {
+        version: 2,
+        sql: "
             ALTER TABLE cells ADD COLUMN fmt_border_top INTEGER DEFAULT 0;
             ALTER TABLE cells ADD COLUMN fmt_border_right INTEGER DEFAULT 0;
             ALTER TABLE cells ADD COLUMN fmt_border_bottom INTEGER DEFAULT 0;
@@ -348,28 +395,23 @@ fn migrate(conn: &Connection) -> rusqlite::Result<()> {
             ALTER TABLE cells ADD COLUMN fmt_border_right_color INTEGER;
             ALTER TABLE cells ADD COLUMN fmt_border_bottom_color INTEGER;
             ALTER TABLE cells ADD COLUMN fmt_border_left_color INTEGER;
-            PRAGMA user_version = 2;
-        ")?;
-    }
-
-    if version < 4 {
-        conn.execute_batch(
-            "ALTER TABLE cells ADD COLUMN fmt_cell_style INTEGER DEFAULT 0;
-             PRAGMA user_version = 4;"
-        )?;
-    }
-
-    if version < 5 {
-        conn.execute_batch(
-            "ALTER TABLE cells ADD COLUMN fmt_font_color INTEGER;
-             ALTER TABLE cells ADD COLUMN fmt_background_color INTEGER;
-             PRAGMA user_version = 5;"
-        )?;
-    }
-
-    if version < 6 {
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS col_widths (
+        ",
+    },
+    SchemaStep {
+        version: 4,
+        sql: "ALTER TABLE cells ADD COLUMN fmt_cell_style INTEGER DEFAULT 0;",
+    },
+    SchemaStep {
+        version: 5,
+        sql: "
+            ALTER TABLE cells ADD COLUMN fmt_font_color INTEGER;
+            ALTER TABLE cells ADD COLUMN fmt_background_color INTEGER;
+        ",
+    },
+    SchemaStep {
+        version: 6,
+        sql: "
+            CREATE TABLE IF NOT EXISTS col_widths (
                 sheet_idx INTEGER NOT NULL,
                 col INTEGER NOT NULL,
                 width REAL NOT NULL,
@@ -381,13 +423,12 @@ fn migrate(conn: &Connection) -> rusqlite::Result<()> {
                 height REAL NOT NULL,
                 PRIMARY KEY (sheet_idx, row)
             );
-            PRAGMA user_version = 6;"
-        )?;
-    }
-
-    if version < 7 {
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS hidden_rows (
+        ",
+    },
+    SchemaStep {
+        version: 7,
+        sql: "
+            CREATE TABLE IF NOT EXISTS hidden_rows (
                 sheet_idx INTEGER NOT NULL,
                 row INTEGER NOT NULL,
                 PRIMARY KEY (sheet_idx, row)
@@ -397,13 +438,12 @@ fn migrate(conn: &Connection) -> rusqlite::Result<()> {
                 col INTEGER NOT NULL,
                 PRIMARY KEY (sheet_idx, col)
             );
-            PRAGMA user_version = 7;"
-        )?;
-    }
-
-    if version < 8 {
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS scripts (
+        ",
+    },
+    SchemaStep {
+        version: 8,
+        sql: "
+            CREATE TABLE IF NOT EXISTS scripts (
                 name TEXT PRIMARY KEY,
                 schema_version INTEGER NOT NULL DEFAULT 1,
                 description TEXT,
@@ -440,20 +480,137 @@ fn migrate(conn: &Connection) -> rusqlite::Result<()> {
             );
             CREATE INDEX IF NOT EXISTS idx_run_records_ran_at ON run_records(ran_at);
             CREATE INDEX IF NOT EXISTS idx_run_records_script_name ON run_records(script_name);
-            PRAGMA user_version = 8;"
-        )?;
-    }
+        ",
+    },
+    SchemaStep {
+        version: 9,
+        sql: "ALTER TABLE cells ADD COLUMN formula_source TEXT;",
+    },
+    // Booleans and error codes get their own value types; older files stored
+    // them as text and re-typed them on load
+    SchemaStep {
+        version: 10,
+        sql: "
+            UPDATE cells SET value_type = 4, value_num = 1, value_text = NULL
+                WHERE value_type = 2 AND TRIM(value_text) = 'TRUE';
+            UPDATE cells SET value_type = 4, value_num = 0, value_text = NULL
+                WHERE value_type = 2 AND TRIM(value_text) = 'FALSE';
+            UPDATE cells SET value_type = 5, value_text = TRIM(value_text)
+                WHERE value_type = 2 AND TRIM(value_text) IN (
+                    '#NULL!', '#DIV/0!', '#VALUE!', '#REF!', '#NAME?',
+                    '#NUM!', '#N/A', '#SPILL!', '#CALC!', '#CYCLE!'
+                );
+        ",
+    },
+];
+
+/// Schema versions a `.sheet` file moved between in [`migrate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchemaMigration {
+    pub from: i32,
+    pub to: i32,
+}
 
-    if version < 9 {
-        conn.execute_batch(
-            "ALTER TABLE cells ADD COLUMN formula_source TEXT;
-             PRAGMA user_version = 9;"
-        )?;
+impl SchemaMigration {
+    pub fn changed(&self) -> bool {
+        self.from != self.to
     }
+}
+
+/// Bring a `.sheet` file up to [`SCHEMA_VERSION`] in place.
+///
+/// Reads the stored version and applies each registered step above it, one
+/// transaction per step, so an interrupted migration leaves the file at the
+/// last completed version. Errors if the file is from a newer build.
+pub fn migrate(path: &Path) -> Result<SchemaMigration, String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    let from = stored_schema_version(&conn)?;
+    check_schema_supported(from)?;
+    let to = migrate_schema(&conn, from)
+        .map_err(|e| format!("cannot migrate {} from schema v{}: {}", path.display(), from, e))?;
+    Ok(SchemaMigration { from, to })
+}
+
+fn stored_schema_version(conn: &Connection) -> Result<i32, String> {
+    conn.pragma_query_value(None, "user_version", |r| r.get(0))
+        .map_err(|e| e.to_string())
+}
 
+fn check_schema_supported(version: i32) -> Result<(), String> {
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "file uses .sheet schema v{}, but this version of VisiGrid only reads up to v{}; upgrade VisiGrid to open it",
+            version, SCHEMA_VERSION
+        ));
+    }
     Ok(())
 }
 
+/// Apply every step above `from`, returning the version reached.
+fn migrate_schema(conn: &Connection, from: i32) -> rusqlite::Result<i32> {
+    let mut version = from;
+    for step in MIGRATIONS.iter().filter(|step| step.version > from) {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(step.sql)?;
+        tx.pragma_update(None, "user_version", step.version)?;
+        tx.commit()?;
+        version = step.version;
+    }
+    Ok(version)
+}
+
+/// Migrate an opened file before reading it (the one-time rewrite on load).
+/// Returns the schema version the file is at afterwards.
+///
+/// A file from a newer build is an error, and so is a migration that fails
+/// in SQL. A file we may not write to still loads: readers tolerate the
+/// older schema.
+fn migrate_for_load(conn: &Connection) -> Result<i32, String> {
+    let version = stored_schema_version(conn)?;
+    check_schema_supported(version)?;
+    match migrate_schema(conn, version) {
+        Ok(_) => {}
+        Err(e) if matches!(e.sqlite_error_code(), Some(ErrorCode::ReadOnly | ErrorCode::PermissionDenied)) => {}
+        Err(e) => return Err(format!("failed to migrate schema from v{}: {}", version, e)),
+    }
+    stored_schema_version(conn)
+}
+
+/// First schema version whose `TYPE_TEXT` cells are literal text. Older files
+/// kept booleans and error codes as text and re-typed them on load.
+const LITERAL_TEXT_SCHEMA: i32 = 10;
+
+/// Inverse of [`cell_save_values`]: write a stored cell back into `sheet`.
+///
+/// Text goes in verbatim, so a text cell reading "TRUE", "#N/A" or "00123"
+/// stays text; everything else is re-entered through `set_value`.
+fn load_cell_values(
+    sheet: &mut Sheet,
+    row: usize,
+    col: usize,
+    (value_type, value_num, value_text, formula_source): (i32, Option<f64>, Option<String>, Option<String>),
+    schema_version: i32,
+) {
+    let value = match value_type {
+        // For formulas: prefer formula_source (v9+), fall back to value_text (pre-v9)
+        TYPE_FORMULA => formula_source.or(value_text).unwrap_or_default(),
+        TYPE_NUMBER => value_num.map(|n| {
+            if n.fract() == 0.0 { (n as i64).to_string() } else { n.to_string() }
+        }).unwrap_or_default(),
+        TYPE_BOOLEAN => value_num.map(|n| bool_display(n != 0.0).to_string()).unwrap_or_default(),
+        TYPE_TEXT | TYPE_ERROR => value_text.unwrap_or_default(),
+        _ => String::new(),
+    };
+    if value.is_empty() {
+        return;
+    }
+    if value_type == TYPE_TEXT && schema_version >= LITERAL_TEXT_SCHEMA {
+        sheet.set_text(row, col, &value);
+    } else {
+        sheet.set_value(row, col, &value);
+    }
+}
+
 /// Determine value_type, value_num, value_text, and formula_source for a cell during save.
 /// For formula cells, persists the computed cached value in value_num/value_text
 /// and the formula source text in formula_source.
@@ -489,16 +646,150 @@ fn cell_save_values(
             }
         }
         CellValue::Text(_) => (TYPE_TEXT, None, Some(raw), None),
+        CellValue::Boolean(b) => (TYPE_BOOLEAN, Some(if *b { 1.0 } else { 0.0 }), None, None),
+        CellValue::Error(_) => (TYPE_ERROR, None, Some(raw), None),
         CellValue::Empty => (TYPE_EMPTY, None, None, None),
     }
 }
 
-pub fn save(sheet: &Sheet, path: &Path) -> Result<(), String> {
-    // Delete existing file if present (SQLite will create fresh)
-    if path.exists() {
-        std::fs::remove_file(path).map_err(|e| e.to_string())?;
+// ============================================================================
+// Write Locking
+// ============================================================================
+
+/// Sidecar lock file guarding full rewrites of `path` (`model.sheet.lock`).
+///
+/// The lock can't live on the `.sheet` itself because a save replaces that
+/// file. The holder removes the sidecar when it releases the lock.
+pub fn lock_path(path: &Path) -> std::path::PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// Advisory write lock on a `.sheet` file, released on drop.
+///
+/// Take it before loading when a command loads, modifies and saves the same
+/// file, and save through it (`save_workbook`, `replace`) so no other writer
+/// can slip in between the load and the save.
+pub struct WriteLock {
+    file: std::fs::File,
+    path: std::path::PathBuf,
+    lock_path: std::path::PathBuf,
+}
+
+/// Take the advisory write lock for `path`, failing fast if another
+/// process (or another handle in this process) already holds it.
+///
+/// Readers don't take the lock: saves write a temp file and rename it into
+/// place, so a reader sees either the old or the new file.
+pub fn lock_for_write(path: &Path) -> Result<WriteLock, String> {
+    let lock_path = lock_path(path);
+    loop {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| format!("failed to open lock file {}: {}", lock_path.display(), e))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(std::fs::TryLockError::WouldBlock) => {
+                return Err(format!(
+                    "{} is locked by another process (lock file: {})",
+                    path.display(),
+                    lock_path.display()
+                ))
+            }
+            Err(std::fs::TryLockError::Error(e)) => {
+                return Err(format!("failed to lock {}: {}", path.display(), e))
+            }
+        }
+        // The previous holder may have removed the sidecar between our open
+        // and our lock; a lock on an unlinked file guards nothing, so retry.
+        if is_same_file(&file, &lock_path) {
+            return Ok(WriteLock { file, path: path.to_path_buf(), lock_path });
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &std::fs::File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), std::fs::metadata(path)) {
+        (Ok(held), Ok(current)) => held.dev() == current.dev() && held.ino() == current.ino(),
+        _ => false,
+    }
+}
+
+/// Windows refuses to delete a file another handle has open, so the
+/// sidecar can't be swapped out from under a waiting writer.
+#[cfg(not(unix))]
+fn is_same_file(_file: &std::fs::File, path: &Path) -> bool {
+    path.exists()
+}
+
+impl WriteLock {
+    /// The locked `.sheet` path.
+    pub fn path(&self) -> &Path {
+        &self.path
     }
 
+    /// Replace the locked file: `write` builds a fresh file at a temp path,
+    /// which is renamed over the `.sheet` only if it succeeds.
+    pub fn replace(&self, write: impl FnOnce(&Path) -> Result<(), String>) -> Result<(), String> {
+        let mut tmp_name = self.path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp = self.path.with_file_name(tmp_name);
+        // Only the lock holder writes the temp file, so any leftover is stale.
+        if tmp.exists() {
+            std::fs::remove_file(&tmp).map_err(|e| e.to_string())?;
+        }
+
+        match write(&tmp) {
+            Ok(()) => std::fs::rename(&tmp, &self.path).map_err(|e| {
+                let _ = std::fs::remove_file(&tmp);
+                format!("failed to replace {}: {}", self.path.display(), e)
+            }),
+            Err(e) => {
+                let _ = std::fs::remove_file(&tmp);
+                Err(e)
+            }
+        }
+    }
+
+    pub fn save_workbook(&self, workbook: &Workbook) -> Result<(), String> {
+        self.replace(|tmp| write_workbook(workbook, tmp))
+    }
+
+    pub fn save_workbook_with_metadata(&self, workbook: &Workbook, metadata: &CellMetadata) -> Result<(), String> {
+        self.replace(|tmp| write_workbook_with_metadata(workbook, metadata, tmp))
+    }
+
+    pub fn save_workbook_full(
+        &self,
+        workbook: &Workbook,
+        metadata: &CellMetadata,
+        scripts: &[ScriptMeta],
+        run_records: &[RunRecord],
+    ) -> Result<(), String> {
+        self.replace(|tmp| write_workbook_full(workbook, metadata, scripts, run_records, tmp))
+    }
+}
+
+impl Drop for WriteLock {
+    fn drop(&mut self) {
+        // Unlink while still holding the lock; a writer that opened the old
+        // sidecar notices it is gone once it gets the lock, and retries.
+        let _ = std::fs::remove_file(&self.lock_path);
+        let _ = self.file.unlock();
+    }
+}
+
+pub fn save(sheet: &Sheet, path: &Path) -> Result<(), String> {
+    lock_for_write(path)?.replace(|tmp| save_unlocked(sheet, tmp))
+}
+
+fn save_unlocked(sheet: &Sheet, path: &Path) -> Result<(), String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
 
     // Create schema
@@ -635,7 +926,7 @@ pub fn load(path: &Path) -> Result<Sheet, String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
 
     // Run migrations (adds new columns if missing)
-    let _ = migrate(&conn); // Ignore errors (read-only DBs)
+    let schema_version = migrate_for_load(&conn)?;
 
     // Load metadata
     let sheet_name: String = conn
@@ -707,25 +998,7 @@ pub fn load(path: &Path) -> Result<Sheet, String> {
         let (row, col, value_type, value_num, value_text, fmt_bold, fmt_italic, fmt_underline, fmt_alignment, fmt_number_type, fmt_decimals, fmt_font_family, fmt_thousands, fmt_negative, fmt_currency_symbol) =
             cell_result.map_err(|e| e.to_string())?;
 
-        let value = match value_type {
-            TYPE_NUMBER => {
-                if let Some(n) = value_num {
-                    if n.fract() == 0.0 {
-                        format!("{}", n as i64)
-                    } else {
-                        format!("{}", n)
-                    }
-                } else {
-                    String::new()
-                }
-            }
-            TYPE_TEXT | TYPE_FORMULA => value_text.unwrap_or_default(),
-            _ => String::new(),
-        };
-
-        if !value.is_empty() {
-            sheet.set_value(row, col, &value);
-        }
+        load_cell_values(&mut sheet, row, col, (value_type, value_num, value_text, None), schema_version);
 
         // Apply formatting
         let alignment = alignment_from_db(fmt_alignment);
@@ -778,11 +1051,12 @@ pub fn load(path: &Path) -> Result<Sheet, String> {
 
 /// Save a complete workbook including all sheets and named ranges
 pub fn save_workbook(workbook: &Workbook, path: &Path) -> Result<(), String> {
-    // Delete existing file if present (SQLite will create fresh)
-    if path.exists() {
-        std::fs::remove_file(path).map_err(|e| e.to_string())?;
-    }
+    lock_for_write(path)?.save_workbook(workbook)
+}
 
+/// Write `workbook` as a fresh file at `path` without taking the write lock,
+/// for building the temp file inside `WriteLock::replace`.
+pub fn write_workbook(workbook: &Workbook, path: &Path) -> Result<(), String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
 
     // Create schema (includes named_ranges table)
@@ -917,6 +1191,11 @@ pub fn save_workbook(workbook: &Workbook, path: &Path) -> Result<(), String> {
         }
     }
 
+    save_protected_ranges(&conn, workbook)?;
+    save_cell_comments(&conn, workbook)?;
+    save_frozen_panes(&conn, workbook)?;
+    save_conditional_formats(&conn, workbook)?;
+
     conn.execute("COMMIT", []).map_err(|e| e.to_string())?;
 
     Ok(())
@@ -931,11 +1210,15 @@ pub fn save_workbook_with_metadata(
     metadata: &CellMetadata,
     path: &Path,
 ) -> Result<(), String> {
-    // Delete existing file if present (SQLite will create fresh)
-    if path.exists() {
-        std::fs::remove_file(path).map_err(|e| e.to_string())?;
-    }
+    lock_for_write(path)?.save_workbook_with_metadata(workbook, metadata)
+}
 
+/// `write_workbook` with semantic metadata.
+pub fn write_workbook_with_metadata(
+    workbook: &Workbook,
+    metadata: &CellMetadata,
+    path: &Path,
+) -> Result<(), String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
 
     // Create schema (includes cell_metadata table)
@@ -1081,13 +1364,18 @@ pub fn save_workbook_with_metadata(
         }
     }
 
+    save_protected_ranges(&conn, workbook)?;
+    save_cell_comments(&conn, workbook)?;
+    save_frozen_panes(&conn, workbook)?;
+    save_conditional_formats(&conn, workbook)?;
+
     conn.execute("COMMIT", []).map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
 /// Load workbook from v2 multi-sheet format
-fn load_workbook_v2(conn: &Connection) -> Result<Workbook, String> {
+fn load_workbook_v2(conn: &Connection, schema_version: i32) -> Result<Workbook, String> {
     // Load active sheet index from meta
     let active_sheet: usize = conn
         .query_row(
@@ -1206,19 +1494,7 @@ fn load_workbook_v2(conn: &Connection) -> Result<Workbook, String> {
             let sheet = &mut sheets[sheet_idx];
 
             // Set cell value
-            // For formulas: prefer formula_source (v9+), fall back to value_text (pre-v9)
-            let value_str = match value_type {
-                TYPE_FORMULA => formula_source.or(value_text).unwrap_or_default(),
-                TYPE_NUMBER => value_num.map(|n| {
-                    if n.fract() == 0.0 { (n as i64).to_string() } else { n.to_string() }
-                }).unwrap_or_default(),
-                TYPE_TEXT => value_text.unwrap_or_default(),
-                _ => String::new(),
-            };
-
-            if !value_str.is_empty() {
-                sheet.set_value(row, col, &value_str);
-            }
+            load_cell_values(sheet, row, col, (value_type, value_num, value_text, formula_source), schema_version);
 
             // Set formatting
             let format = CellFormat {
@@ -1244,7 +1520,8 @@ fn load_workbook_v2(conn: &Connection) -> Result<Workbook, String> {
         }
     }
 
-    let workbook = Workbook::from_sheets(sheets, active_sheet);
+    // load_workbook rebuilds and recomputes once named ranges are attached
+    let workbook = Workbook::from_sheets_deferred(sheets, active_sheet);
     Ok(workbook)
 }
 
@@ -1253,7 +1530,7 @@ pub fn load_workbook(path: &Path) -> Result<Workbook, String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
 
     // Run migrations (adds new columns if missing from older files)
-    let _ = migrate(&conn);
+    let schema_version = migrate_for_load(&conn)?;
 
     // Check if this is the new multi-sheet format (v2+)
     let has_sheets_table = conn
@@ -1262,11 +1539,11 @@ pub fn load_workbook(path: &Path) -> Result<Workbook, String> {
 
     let mut workbook = if has_sheets_table {
         // New multi-sheet format
-        load_workbook_v2(&conn)?
+        load_workbook_v2(&conn, schema_version)?
     } else {
         // Legacy single-sheet format - use existing load function
         let sheet = load(path)?;
-        Workbook::from_sheets(vec![sheet], 0)
+        Workbook::from_sheets_deferred(vec![sheet], 0)
     };
 
     // Check if named_ranges table exists (for backward compatibility)
@@ -1358,6 +1635,11 @@ pub fn load_workbook(path: &Path) -> Result<Workbook, String> {
         }
     }
 
+    load_protected_ranges(&conn, &mut workbook)?;
+    load_cell_comments(&conn, &mut workbook)?;
+    load_frozen_panes(&conn, &mut workbook)?;
+    load_conditional_formats(&conn, &mut workbook)?;
+
     // Rebuild dependency graph and compute all formulas after loading
     workbook.rebuild_dep_graph();
     workbook.recompute_full_ordered();
@@ -1365,6 +1647,211 @@ pub fn load_workbook(path: &Path) -> Result<Workbook, String> {
     Ok(workbook)
 }
 
+/// Protected ranges keyed by sheet index, as stored in meta 'protected_ranges'.
+type ProtectedRanges = std::collections::BTreeMap<usize, Vec<ProtectedRange>>;
+
+/// Write every sheet's protected ranges to the meta table (nothing if none).
+/// Not part of the semantic fingerprint.
+fn save_protected_ranges(conn: &Connection, workbook: &Workbook) -> Result<(), String> {
+    let ranges: ProtectedRanges = workbook
+        .sheets()
+        .iter()
+        .enumerate()
+        .filter(|(_, sheet)| !sheet.protected_ranges.is_empty())
+        .map(|(idx, sheet)| (idx, sheet.protected_ranges.clone()))
+        .collect();
+
+    if ranges.is_empty() {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string(&ranges).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('protected_ranges', ?1)",
+        params![json],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn load_protected_ranges(conn: &Connection, workbook: &mut Workbook) -> Result<(), String> {
+    let raw = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'protected_ranges'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok();
+
+    let Some(json) = raw else { return Ok(()) };
+    let ranges: ProtectedRanges = serde_json::from_str(&json)
+        .map_err(|e| format!("invalid protected ranges: {}", e))?;
+
+    for (idx, sheet_ranges) in ranges {
+        if let Some(sheet) = workbook.sheet_mut(idx) {
+            for range in sheet_ranges {
+                sheet.protect(range);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A cell comment as stored in meta 'cell_comments'.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredComment {
+    row: usize,
+    col: usize,
+    #[serde(flatten)]
+    comment: CellComment,
+}
+
+/// Write every sheet's cell comments to the meta table (nothing if none).
+/// Not part of the semantic fingerprint.
+fn save_cell_comments(conn: &Connection, workbook: &Workbook) -> Result<(), String> {
+    let comments: std::collections::BTreeMap<usize, Vec<StoredComment>> = workbook
+        .sheets()
+        .iter()
+        .enumerate()
+        .filter(|(_, sheet)| !sheet.comments.is_empty())
+        .map(|(idx, sheet)| {
+            let stored = sheet.comments.iter()
+                .map(|(&(row, col), comment)| StoredComment { row, col, comment: comment.clone() })
+                .collect();
+            (idx, stored)
+        })
+        .collect();
+
+    if comments.is_empty() {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string(&comments).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('cell_comments', ?1)",
+        params![json],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn load_cell_comments(conn: &Connection, workbook: &mut Workbook) -> Result<(), String> {
+    let raw = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'cell_comments'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok();
+
+    let Some(json) = raw else { return Ok(()) };
+    let comments: std::collections::BTreeMap<usize, Vec<StoredComment>> = serde_json::from_str(&json)
+        .map_err(|e| format!("invalid cell comments: {}", e))?;
+
+    for (idx, stored) in comments {
+        if let Some(sheet) = workbook.sheet_mut(idx) {
+            for c in stored {
+                sheet.set_comment(c.row, c.col, Some(c.comment));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Frozen panes keyed by sheet index, as stored in meta 'frozen_panes'.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredFrozenPanes {
+    rows: usize,
+    cols: usize,
+}
+
+/// Write every sheet's frozen panes to the meta table (nothing if none).
+/// Not part of the semantic fingerprint.
+fn save_frozen_panes(conn: &Connection, workbook: &Workbook) -> Result<(), String> {
+    let panes: std::collections::BTreeMap<usize, StoredFrozenPanes> = workbook
+        .sheets()
+        .iter()
+        .enumerate()
+        .filter(|(_, sheet)| sheet.frozen_rows > 0 || sheet.frozen_cols > 0)
+        .map(|(idx, sheet)| (idx, StoredFrozenPanes { rows: sheet.frozen_rows, cols: sheet.frozen_cols }))
+        .collect();
+
+    if panes.is_empty() {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string(&panes).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('frozen_panes', ?1)",
+        params![json],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn load_frozen_panes(conn: &Connection, workbook: &mut Workbook) -> Result<(), String> {
+    let raw = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'frozen_panes'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok();
+
+    let Some(json) = raw else { return Ok(()) };
+    let panes: std::collections::BTreeMap<usize, StoredFrozenPanes> = serde_json::from_str(&json)
+        .map_err(|e| format!("invalid frozen panes: {}", e))?;
+
+    for (idx, pane) in panes {
+        if let Some(sheet) = workbook.sheet_mut(idx) {
+            sheet.frozen_rows = pane.rows;
+            sheet.frozen_cols = pane.cols;
+        }
+    }
+    Ok(())
+}
+
+/// Write every sheet's conditional formats to meta 'conditional_formats'
+/// (nothing if none).
+fn save_conditional_formats(conn: &Connection, workbook: &Workbook) -> Result<(), String> {
+    let formats: std::collections::BTreeMap<usize, &Vec<ConditionalFormat>> = workbook
+        .sheets()
+        .iter()
+        .enumerate()
+        .filter(|(_, sheet)| !sheet.conditional_formats.is_empty())
+        .map(|(idx, sheet)| (idx, &sheet.conditional_formats))
+        .collect();
+
+    if formats.is_empty() {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string(&formats).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('conditional_formats', ?1)",
+        params![json],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn load_conditional_formats(conn: &Connection, workbook: &mut Workbook) -> Result<(), String> {
+    let raw = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'conditional_formats'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok();
+
+    let Some(json) = raw else { return Ok(()) };
+    let formats: std::collections::BTreeMap<usize, Vec<ConditionalFormat>> = serde_json::from_str(&json)
+        .map_err(|e| format!("invalid conditional formats: {}", e))?;
+
+    for (idx, stored) in formats {
+        if let Some(sheet) = workbook.sheet_mut(idx) {
+            sheet.conditional_formats = stored;
+        }
+    }
+    Ok(())
+}
+
 /// Load semantic metadata from a .sheet file.
 /// Returns an empty map if the cell_metadata table doesn't exist (backward compatibility).
 pub fn load_cell_metadata(path: &Path) -> Result<CellMetadata, String> {
@@ -1437,38 +1924,220 @@ pub fn load_semantic_verification(path: &Path) -> Result<SemanticVerification, S
     })
 }
 
+/// Replace the named ranges stored in an existing .sheet file.
+///
+/// Rewrites only the `named_ranges` table (in one transaction), leaving cells,
+/// metadata, layout, scripts, and run records untouched.
+pub fn save_named_ranges(path: &Path, workbook: &Workbook) -> Result<(), String> {
+    let mut conn = Connection::open(path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    // Older files may predate the table
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS named_ranges (
+            name TEXT PRIMARY KEY,
+            target_type INTEGER NOT NULL,
+            sheet INTEGER NOT NULL,
+            start_row INTEGER NOT NULL,
+            start_col INTEGER NOT NULL,
+            end_row INTEGER,
+            end_col INTEGER,
+            description TEXT
+        );
+        DELETE FROM named_ranges;"
+    ).map_err(|e| e.to_string())?;
+
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO named_ranges (name, target_type, sheet, start_row, start_col, end_row, end_col, description) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+        ).map_err(|e| e.to_string())?;
+
+        for nr in workbook.list_named_ranges() {
+            let (target_type, sheet_idx, start_row, start_col, end_row, end_col) = match &nr.target {
+                NamedRangeTarget::Cell { sheet, row, col } => {
+                    (0i32, *sheet as i64, *row as i64, *col as i64, None::<i64>, None::<i64>)
+                }
+                NamedRangeTarget::Range { sheet, start_row, start_col, end_row, end_col } => {
+                    (1i32, *sheet as i64, *start_row as i64, *start_col as i64, Some(*end_row as i64), Some(*end_col as i64))
+                }
+            };
+
+            stmt.execute(params![
+                &nr.name,
+                target_type,
+                sheet_idx,
+                start_row,
+                start_col,
+                end_row,
+                end_col,
+                nr.description.as_deref()
+            ]).map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
 /// Save semantic verification info to an existing .sheet file.
 /// This updates the meta table without rewriting the entire file.
-pub fn save_semantic_verification(path: &Path, verification: &SemanticVerification) -> Result<(), String> {
+pub fn save_semantic_verification(path: &Path, verification: &SemanticVerification) -> Result<(), String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+
+    // Delete existing verification entries
+    conn.execute("DELETE FROM meta WHERE key = 'expected_semantic_fingerprint'", [])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM meta WHERE key = 'expected_semantic_label'", [])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM meta WHERE key = 'expected_semantic_timestamp'", [])
+        .map_err(|e| e.to_string())?;
+
+    // Insert new verification entries
+    if let Some(ref fp) = verification.fingerprint {
+        conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('expected_semantic_fingerprint', ?1)",
+            params![fp],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(ref label) = verification.label {
+        conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('expected_semantic_label', ?1)",
+            params![label],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(ref timestamp) = verification.timestamp {
+        conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('expected_semantic_timestamp', ?1)",
+            params![timestamp],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Expected type of a column in a fill schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FillColumnType {
+    /// Any value, including numbers (no type check)
+    Text,
+    /// Whole number (`-?\d+`)
+    Integer,
+    /// Exact 2-decimal amount (`-?\d+\.\d{2}`)
+    Decimal,
+    /// Integer or 2-decimal amount
+    Number,
+}
+
+/// One expected column in a fill schema.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FillColumn {
+    pub name: String,
+    #[serde(rename = "type", default = "fill_column_text")]
+    pub kind: FillColumnType,
+}
+
+fn fill_column_text() -> FillColumnType {
+    FillColumnType::Text
+}
+
+/// Fill schemas keyed by sheet name: the columns `vgrid fill` expects
+/// when loading CSV data into that sheet.
+pub type FillSchema = std::collections::BTreeMap<String, Vec<FillColumn>>;
+
+/// Load the fill schema embedded in a .sheet template.
+/// Returns an empty schema if none has been set.
+pub fn load_fill_schema(path: &Path) -> Result<FillSchema, String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+
+    let raw = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'fill_schema'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok();
+
+    match raw {
+        Some(json) => serde_json::from_str(&json).map_err(|e| format!("invalid fill schema: {}", e)),
+        None => Ok(FillSchema::new()),
+    }
+}
+
+/// Save a fill schema to an existing .sheet file (empty schema removes it).
+/// This updates the meta table without rewriting the entire file.
+pub fn save_fill_schema(path: &Path, schema: &FillSchema) -> Result<(), String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM meta WHERE key = 'fill_schema'", [])
+        .map_err(|e| e.to_string())?;
+
+    if !schema.is_empty() {
+        let json = serde_json::to_string(schema).map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('fill_schema', ?1)",
+            params![json],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Workbook-level presentation metadata: title, description, authorship.
+/// Shown by tooling; never part of the semantic fingerprint.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WorkbookMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// RFC 3339 timestamp of when the metadata was first written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl WorkbookMeta {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Load the workbook metadata block from a .sheet file.
+/// Returns empty metadata if none has been set.
+pub fn load_workbook_meta(path: &Path) -> Result<WorkbookMeta, String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+
+    let raw = conn.query_row(
+        "SELECT value FROM meta WHERE key = 'workbook_meta'",
+        [],
+        |row| row.get::<_, String>(0),
+    );
+
+    match raw {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| format!("invalid workbook metadata: {}", e)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(WorkbookMeta::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Save the workbook metadata block to an existing .sheet file (empty removes it).
+/// This updates the meta table without rewriting the entire file.
+pub fn save_workbook_meta(path: &Path, meta: &WorkbookMeta) -> Result<(), String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
 
-    // Delete existing verification entries
-    conn.execute("DELETE FROM meta WHERE key = 'expected_semantic_fingerprint'", [])
-        .map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM meta WHERE key = 'expected_semantic_label'", [])
-        .map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM meta WHERE key = 'expected_semantic_timestamp'", [])
+    conn.execute("DELETE FROM meta WHERE key = 'workbook_meta'", [])
         .map_err(|e| e.to_string())?;
 
-    // Insert new verification entries
-    if let Some(ref fp) = verification.fingerprint {
-        conn.execute(
-            "INSERT INTO meta (key, value) VALUES ('expected_semantic_fingerprint', ?1)",
-            params![fp],
-        ).map_err(|e| e.to_string())?;
-    }
-
-    if let Some(ref label) = verification.label {
-        conn.execute(
-            "INSERT INTO meta (key, value) VALUES ('expected_semantic_label', ?1)",
-            params![label],
-        ).map_err(|e| e.to_string())?;
-    }
-
-    if let Some(ref timestamp) = verification.timestamp {
+    if !meta.is_empty() {
+        let json = serde_json::to_string(meta).map_err(|e| e.to_string())?;
         conn.execute(
-            "INSERT INTO meta (key, value) VALUES ('expected_semantic_timestamp', ?1)",
-            params![timestamp],
+            "INSERT INTO meta (key, value) VALUES ('workbook_meta', ?1)",
+            params![json],
         ).map_err(|e| e.to_string())?;
     }
 
@@ -1869,7 +2538,7 @@ use crate::scripting::{ScriptMeta, RunRecord, Capability};
 /// Load all attached scripts from a .sheet file.
 pub fn load_scripts(path: &Path) -> Result<Vec<ScriptMeta>, String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    let _ = migrate(&conn);
+    migrate_for_load(&conn)?;
 
     let has_scripts = conn.prepare("SELECT name FROM scripts LIMIT 1").is_ok();
     if !has_scripts {
@@ -1953,7 +2622,7 @@ pub fn count_scripts(path: &Path) -> Result<usize, String> {
 /// Load all run records from a .sheet file.
 pub fn load_run_records(path: &Path) -> Result<Vec<RunRecord>, String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    let _ = migrate(&conn);
+    migrate_for_load(&conn)?;
 
     let has_records = conn.prepare("SELECT run_id FROM run_records LIMIT 1").is_ok();
     if !has_records {
@@ -2009,7 +2678,7 @@ pub fn load_run_records_paginated(
     offset: usize,
 ) -> Result<Vec<RunRecord>, String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    let _ = migrate(&conn);
+    migrate_for_load(&conn)?;
 
     let has_records = conn.prepare("SELECT run_id FROM run_records LIMIT 1").is_ok();
     if !has_records {
@@ -2058,7 +2727,7 @@ pub fn load_run_records_paginated(
 /// Count total run records in a .sheet file.
 pub fn count_run_records(path: &Path) -> Result<usize, String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    let _ = migrate(&conn);
+    migrate_for_load(&conn)?;
 
     conn.query_row(
         "SELECT COUNT(*) FROM run_records",
@@ -2072,7 +2741,7 @@ pub fn count_run_records(path: &Path) -> Result<usize, String> {
 /// Load a single run record by ID (or prefix match).
 pub fn load_run_record(path: &Path, run_id: &str) -> Result<Option<RunRecord>, String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    let _ = migrate(&conn);
+    migrate_for_load(&conn)?;
 
     let has_records = conn.prepare("SELECT run_id FROM run_records LIMIT 1").is_ok();
     if !has_records {
@@ -2155,11 +2824,16 @@ pub fn save_workbook_full(
     run_records: &[RunRecord],
     path: &Path,
 ) -> Result<(), String> {
-    // Delete existing file if present (SQLite will create fresh)
-    if path.exists() {
-        std::fs::remove_file(path).map_err(|e| e.to_string())?;
-    }
+    lock_for_write(path)?.save_workbook_full(workbook, metadata, scripts, run_records)
+}
 
+fn write_workbook_full(
+    workbook: &Workbook,
+    metadata: &CellMetadata,
+    scripts: &[ScriptMeta],
+    run_records: &[RunRecord],
+    path: &Path,
+) -> Result<(), String> {
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
 
     // Create schema (includes scripts + run_records tables)
@@ -2285,6 +2959,11 @@ pub fn save_workbook_full(
         }
     }
 
+    save_protected_ranges(&conn, workbook)?;
+    save_cell_comments(&conn, workbook)?;
+    save_frozen_panes(&conn, workbook)?;
+    save_conditional_formats(&conn, workbook)?;
+
     // Save scripts
     save_scripts(&conn, scripts).map_err(|e| e.to_string())?;
 
@@ -2451,6 +3130,11 @@ pub fn inspect_range_lightweight(
                     (v, "number", None)
                 }
                 TYPE_TEXT => (vtext.unwrap_or_default(), "text", None),
+                TYPE_BOOLEAN => {
+                    let v = vnum.map(|n| bool_display(n != 0.0).to_string()).unwrap_or_default();
+                    (v, "boolean", None)
+                }
+                TYPE_ERROR => (vtext.unwrap_or_default(), "error", None),
                 TYPE_FORMULA => {
                     // Use cached computed value from value_num/value_text.
                     // formula_source holds the original formula (v9+).
@@ -2578,7 +3262,9 @@ pub fn upgrade_sheet(path: &Path, out_path: Option<&Path>) -> Result<UpgradeResu
         }
     }
 
-    // Full load + recompute
+    // Full load + recompute, holding the target's lock through the save
+    let target = out_path.unwrap_or(path);
+    let lock = lock_for_write(target)?;
     let workbook = load_workbook(path)?;
 
     // Count formula cells
@@ -2594,8 +3280,8 @@ pub fn upgrade_sheet(path: &Path, out_path: Option<&Path>) -> Result<UpgradeResu
     }
 
     // Save to target path (in-place or out_path)
-    let target = out_path.unwrap_or(path);
-    save_workbook(&workbook, target)?;
+    lock.save_workbook(&workbook)?;
+    drop(lock);
 
     let schema_after = sheet_schema_version(target)?;
 
@@ -2609,11 +3295,317 @@ pub fn upgrade_sheet(path: &Path, out_path: Option<&Path>) -> Result<UpgradeResu
     })
 }
 
+/// Consecutive read errors tolerated per table before `repair_sheet` gives
+/// up on the rest of it.
+const REPAIR_MAX_READ_ERRORS: usize = 64;
+
+/// How much of one table `repair_sheet` could copy.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TableSalvage {
+    pub table: String,
+    /// Rows copied into the repaired file
+    pub recovered: usize,
+    /// Rows the damaged file claims to hold, if it could still count them
+    pub expected: Option<usize>,
+    /// Read errors hit while scanning (each may skip past a damaged page)
+    pub read_errors: usize,
+}
+
+/// Result of a repair, suitable for JSON serialization.
+#[derive(Debug, serde::Serialize)]
+pub struct RepairResult {
+    /// Problems reported by SQLite's integrity check (empty = none found)
+    pub integrity_errors: Vec<String>,
+    pub tables: Vec<TableSalvage>,
+    /// Tables whose rows could not be read at all
+    pub unreadable_tables: Vec<String>,
+    /// Sheets and non-empty cells in the repaired workbook
+    pub sheets: usize,
+    pub cells: usize,
+}
+
+impl RepairResult {
+    /// True when the file passed the integrity check and every row was copied.
+    pub fn is_clean(&self) -> bool {
+        self.integrity_errors.is_empty()
+            && self.unreadable_tables.is_empty()
+            && self.tables.iter().all(|t| t.read_errors == 0)
+    }
+
+    /// Rows known to be lost, over the tables that could still be counted.
+    pub fn rows_lost(&self) -> usize {
+        self.tables
+            .iter()
+            .filter_map(|t| t.expected.map(|e| e.saturating_sub(t.recovered)))
+            .sum()
+    }
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", quote_ident(table)))
+        .map_err(|e| e.to_string())?;
+    let cols = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(cols)
+}
+
+/// Copy every readable row of `table` from `src` into `dest`, scanning in
+/// rowid order. A read error skips ahead (1, 2, 4, ... rowids) and resumes,
+/// so rows past a damaged page can still be reached.
+fn salvage_table(src: &Connection, dest: &Connection, table: &str, columns: &[String]) -> Result<TableSalvage, String> {
+    let col_list = columns.iter().map(|c| quote_ident(c)).collect::<Vec<_>>().join(", ");
+    let select = format!(
+        "SELECT rowid, {} FROM {} WHERE rowid > ?1 ORDER BY rowid",
+        col_list, quote_ident(table)
+    );
+    let placeholders = (1..=columns.len()).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ");
+    let mut insert = dest
+        .prepare(&format!("INSERT OR IGNORE INTO {} ({}) VALUES ({})", quote_ident(table), col_list, placeholders))
+        .map_err(|e| e.to_string())?;
+
+    let expected = src
+        .query_row(&format!("SELECT COUNT(*) FROM {}", quote_ident(table)), [], |row| row.get::<_, i64>(0))
+        .ok()
+        .map(|n| n as usize);
+
+    let mut salvage = TableSalvage { table: table.to_string(), recovered: 0, expected, read_errors: 0 };
+    // Rowids here are never negative; `sheets` uses sheet_idx 0 as its rowid
+    let mut after: i64 = -1;
+    let mut skip: i64 = 1;
+    let mut consecutive_errors = 0;
+    loop {
+        let scan = src.prepare(&select).and_then(|mut stmt| {
+            let mut rows = stmt.query([after])?;
+            while let Some(row) = rows.next()? {
+                let rowid: i64 = row.get(0)?;
+                let values = (1..=columns.len())
+                    .map(|i| row.get::<_, rusqlite::types::Value>(i))
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                after = rowid;
+                skip = 1;
+                consecutive_errors = 0;
+                if insert.execute(rusqlite::params_from_iter(values)).is_ok_and(|n| n > 0) {
+                    salvage.recovered += 1;
+                }
+            }
+            Ok(())
+        });
+        if scan.is_ok() {
+            break;
+        }
+        salvage.read_errors += 1;
+        consecutive_errors += 1;
+        if consecutive_errors >= REPAIR_MAX_READ_ERRORS || after == i64::MAX {
+            break;
+        }
+        after = after.saturating_add(skip);
+        skip = skip.saturating_mul(2);
+    }
+    Ok(salvage)
+}
+
+/// Copy `path` to `scratch` trimmed to whole pages, with the header's page
+/// count set to match. SQLite refuses a file cut off mid-page outright;
+/// after this the intact leading pages are readable again.
+fn page_aligned_copy(path: &Path, scratch: &Path) -> Result<(), String> {
+    let mut bytes = std::fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    if bytes.len() >= 100 && bytes.starts_with(b"SQLite format 3\0") {
+        let page_size = match u16::from_be_bytes([bytes[16], bytes[17]]) {
+            1 => 65536,
+            n => n as usize,
+        };
+        if page_size >= 512 {
+            let pages = bytes.len() / page_size;
+            bytes.truncate(pages * page_size);
+            if pages > 0 {
+                bytes[28..32].copy_from_slice(&(pages as u32).to_be_bytes());
+            }
+        }
+    }
+    std::fs::write(scratch, &bytes).map_err(|e| format!("cannot write {}: {}", scratch.display(), e))
+}
+
+/// Salvage a damaged .sheet file into a new file at `out_path`.
+///
+/// Runs SQLite's integrity check on `path`, then copies every row it can
+/// still read, table by table, into a fresh file with the current schema.
+/// Works from a scratch copy, so `path` is never modified. Fails only when
+/// the source has no readable schema at all, or `out_path` already exists.
+pub fn repair_sheet(path: &Path, out_path: &Path) -> Result<RepairResult, String> {
+    if out_path.exists() {
+        return Err(format!("{} already exists", out_path.display()));
+    }
+    let mut scratch = out_path.as_os_str().to_owned();
+    scratch.push(".scratch");
+    let scratch = std::path::PathBuf::from(scratch);
+    page_aligned_copy(path, &scratch)?;
+    let result = repair_from(&scratch, out_path);
+    let _ = std::fs::remove_file(&scratch);
+    if result.is_err() {
+        let _ = std::fs::remove_file(out_path);
+    }
+    result
+}
+
+fn repair_from(path: &Path, out_path: &Path) -> Result<RepairResult, String> {
+    let src = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| e.to_string())?;
+
+    let integrity_errors = match src.prepare("PRAGMA integrity_check(100)").and_then(|mut stmt| {
+        stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<rusqlite::Result<Vec<_>>>()
+    }) {
+        Ok(lines) if lines == ["ok"] => Vec::new(),
+        Ok(lines) => lines,
+        Err(e) => vec![e.to_string()],
+    };
+
+    let tables: Vec<(String, String)> = src
+        .prepare("SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect()
+        })
+        .map_err(|e| format!("no readable schema: {}", e))?;
+
+    let dest = Connection::open(out_path).map_err(|e| e.to_string())?;
+    dest.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+    dest.pragma_update(None, "user_version", SCHEMA_VERSION).map_err(|e| e.to_string())?;
+    dest.execute("BEGIN TRANSACTION", []).map_err(|e| e.to_string())?;
+
+    let mut result = RepairResult {
+        integrity_errors,
+        tables: Vec::new(),
+        unreadable_tables: Vec::new(),
+        sheets: 0,
+        cells: 0,
+    };
+    for (table, sql) in &tables {
+        // Tables outside SCHEMA (added by later migrations) keep their original definition
+        let dest_cols = match table_columns(&dest, table)? {
+            cols if cols.is_empty() => {
+                dest.execute_batch(sql).map_err(|e| e.to_string())?;
+                table_columns(&dest, table)?
+            }
+            cols => cols,
+        };
+        let columns: Vec<String> = match table_columns(&src, table) {
+            Ok(cols) => cols.into_iter().filter(|c| dest_cols.contains(c)).collect(),
+            Err(_) => Vec::new(),
+        };
+        if columns.is_empty() {
+            result.unreadable_tables.push(table.clone());
+            continue;
+        }
+        let salvage = salvage_table(&src, &dest, table, &columns)?;
+        if salvage.recovered == 0 && salvage.read_errors > 0 {
+            result.unreadable_tables.push(table.clone());
+        }
+        result.tables.push(salvage);
+    }
+    dest.execute("COMMIT", []).map_err(|e| e.to_string())?;
+    drop(dest);
+
+    let workbook = load_workbook(out_path)
+        .map_err(|e| format!("repaired file does not load: {}", e))?;
+    result.sheets = workbook.sheet_count();
+    result.cells = workbook
+        .sheets()
+        .iter()
+        .map(|sheet| sheet.cells_iter().filter(|(_, cell)| !cell.value.raw_display().is_empty()).count())
+        .sum();
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_save_fails_fast_while_locked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.sheet");
+        let mut workbook = Workbook::new();
+        workbook.active_sheet_mut().set_value(0, 0, "1");
+        save_workbook(&workbook, &path).unwrap();
+
+        let held = lock_for_write(&path).unwrap();
+        workbook.active_sheet_mut().set_value(0, 0, "2");
+        let err = save_workbook(&workbook, &path).unwrap_err();
+        assert!(err.contains("locked by another process"), "got: {}", err);
+
+        // Reads proceed while locked and see the last complete save.
+        let loaded = load_workbook(&path).unwrap();
+        assert_eq!(loaded.active_sheet().get_display(0, 0), "1");
+
+        drop(held);
+        save_workbook(&workbook, &path).unwrap();
+        let loaded = load_workbook(&path).unwrap();
+        assert_eq!(loaded.active_sheet().get_display(0, 0), "2");
+        assert!(!dir.path().join("model.sheet.tmp").exists());
+        assert!(!dir.path().join("model.sheet.lock").exists());
+    }
+
+    #[test]
+    fn test_lock_held_across_load_and_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.sheet");
+        save_workbook(&Workbook::new(), &path).unwrap();
+
+        let lock = lock_for_write(&path).unwrap();
+        let mut workbook = load_workbook(&path).unwrap();
+        workbook.active_sheet_mut().set_value(0, 0, "edited");
+        // Another writer is shut out for the whole load→save span
+        assert!(save_workbook(&Workbook::new(), &path).is_err());
+        lock.save_workbook(&workbook).unwrap();
+        assert!(dir.path().join("model.sheet.lock").exists());
+        drop(lock);
+
+        assert!(!dir.path().join("model.sheet.lock").exists());
+        assert_eq!(load_workbook(&path).unwrap().active_sheet().get_display(0, 0), "edited");
+    }
+
+    #[test]
+    fn test_concurrent_writers_do_not_interleave() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared.sheet");
+
+        let writers: Vec<_> = ["a", "b"].into_iter().map(|tag| {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let mut workbook = Workbook::new();
+                for row in 0..200 {
+                    workbook.active_sheet_mut().set_value(row, 0, &format!("{}{}", tag, row));
+                }
+                let mut saved = 0;
+                for _ in 0..20 {
+                    match save_workbook(&workbook, &path) {
+                        Ok(()) => saved += 1,
+                        Err(e) => assert!(e.contains("locked by another process"), "got: {}", e),
+                    }
+                }
+                saved
+            })
+        }).collect();
+        let saved: usize = writers.into_iter().map(|w| w.join().unwrap()).sum();
+        assert!(saved > 0);
+
+        // The file is exactly one writer's workbook, never a mix.
+        let loaded = load_workbook(&path).unwrap();
+        let sheet = loaded.active_sheet();
+        let tag = &sheet.get_display(0, 0)[..1];
+        for row in 0..200 {
+            assert_eq!(sheet.get_display(row, 0), format!("{}{}", tag, row));
+        }
+    }
+
     #[test]
     fn test_named_range_persistence_cell() {
         // Create a workbook with a named cell
@@ -2915,6 +3907,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_migrate_v0_fixture_then_load() {
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+
+        // Synthetic v0 file: original single-sheet schema, no user_version
+        {
+            let conn = Connection::open(path).unwrap();
+            conn.execute_batch(r#"
+                CREATE TABLE cells (
+                    row INTEGER NOT NULL,
+                    col INTEGER NOT NULL,
+                    value_type INTEGER NOT NULL,
+                    value_num REAL,
+                    value_text TEXT,
+                    fmt_bold INTEGER DEFAULT 0,
+                    fmt_italic INTEGER DEFAULT 0,
+                    fmt_underline INTEGER DEFAULT 0,
+                    fmt_alignment INTEGER DEFAULT 0,
+                    fmt_number_type INTEGER DEFAULT 0,
+                    fmt_decimals INTEGER DEFAULT 2,
+                    fmt_font_family TEXT,
+                    PRIMARY KEY (row, col)
+                );
+                CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+                INSERT INTO meta (key, value) VALUES ('sheet_name', 'Legacy');
+                INSERT INTO meta (key, value) VALUES ('rows', '1000');
+                INSERT INTO meta (key, value) VALUES ('cols', '26');
+                INSERT INTO cells (row, col, value_type, value_num) VALUES (0, 0, 1, 7.0);
+                INSERT INTO cells (row, col, value_type, value_text) VALUES (0, 1, 2, 'seven');
+            "#).unwrap();
+        }
+
+        let migration = migrate(path).unwrap();
+        assert_eq!(migration, SchemaMigration { from: 0, to: SCHEMA_VERSION });
+        assert!(migration.changed());
+        assert_eq!(sheet_schema_version(path).unwrap(), SCHEMA_VERSION);
+
+        // One-time rewrite: a second pass has nothing to do
+        assert!(!migrate(path).unwrap().changed());
+
+        let workbook = load_workbook(path).expect("migrated file loads");
+        let sheet = workbook.sheet(0).unwrap();
+        assert_eq!(sheet.name, "Legacy");
+        assert_eq!(sheet.get_raw(0, 0), "7");
+        assert_eq!(sheet.get_raw(0, 1), "seven");
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+
+        let mut wb = Workbook::new();
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "1");
+        save_workbook(&wb, path).unwrap();
+        Connection::open(path).unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+
+        let err = migrate(path).unwrap_err();
+        assert!(err.contains(&format!("schema v{}", SCHEMA_VERSION + 1)), "{}", err);
+        let err = load_workbook(path).unwrap_err();
+        assert!(err.contains("upgrade VisiGrid"), "{}", err);
+        assert_eq!(sheet_schema_version(path).unwrap(), SCHEMA_VERSION + 1, "rejected file is left untouched");
+    }
+
     #[test]
     fn test_migration_roundtrip() {
         let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
@@ -2994,6 +4052,78 @@ mod tests {
         assert_eq!(count, 3, "Should only save 3 populated cells, got {}", count);
     }
 
+    #[test]
+    fn test_text_that_reads_as_a_value_stays_text() {
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.active_sheet_mut();
+        sheet.set_value(0, 0, "TRUE");
+        sheet.set_text(0, 1, "TRUE");
+        sheet.set_value(1, 0, "#N/A");
+        sheet.set_text(1, 1, "#N/A");
+        sheet.set_text(2, 0, "00123");
+        save_workbook(&workbook, path).expect("Save should succeed");
+
+        let loaded = load_workbook(path).expect("Load should succeed");
+        let sheet = loaded.active_sheet();
+        assert_eq!(sheet.get_computed_value(0, 0), Value::Boolean(true));
+        assert_eq!(sheet.get_computed_value(0, 1), Value::Text("TRUE".to_string()));
+        assert_eq!(sheet.get_computed_value(1, 0), Value::Error("#N/A".to_string()));
+        assert_eq!(sheet.get_computed_value(1, 1), Value::Text("#N/A".to_string()));
+        assert_eq!(sheet.get_computed_value(2, 0), Value::Text("00123".to_string()));
+        assert_eq!(compute_semantic_fingerprint(&loaded), compute_semantic_fingerprint(&workbook));
+    }
+
+    #[test]
+    fn test_v9_boolean_text_loads_as_boolean() {
+        // Before v10, booleans and error codes were stored as text
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+        {
+            let conn = Connection::open(path).unwrap();
+            conn.execute_batch(SCHEMA).unwrap();
+            conn.pragma_update(None, "user_version", 9).unwrap();
+            conn.execute_batch(r#"
+                INSERT INTO meta (key, value) VALUES ('sheet_name', 'Sheet1');
+                INSERT INTO meta (key, value) VALUES ('rows', '100');
+                INSERT INTO meta (key, value) VALUES ('cols', '26');
+                INSERT INTO cells (row, col, value_type, value_text) VALUES (0, 0, 2, 'TRUE');
+                INSERT INTO cells (row, col, value_type, value_text) VALUES (0, 1, 2, '#DIV/0!');
+                INSERT INTO cells (row, col, value_type, value_text) VALUES (0, 2, 2, 'true');
+            "#).unwrap();
+        }
+
+        let sheet = load(path).expect("Load should succeed");
+        assert_eq!(sheet.get_computed_value(0, 0), Value::Boolean(true));
+        assert_eq!(sheet.get_computed_value(0, 1), Value::Error("#DIV/0!".to_string()));
+        assert_eq!(sheet.get_computed_value(0, 2), Value::Text("true".to_string()));
+        assert_eq!(sheet_schema_version(path).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_for_load_skips_read_only_and_reports_sql_errors() {
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+        {
+            let conn = Connection::open(path).unwrap();
+            conn.execute_batch(SCHEMA).unwrap();
+            conn.pragma_update(None, "user_version", 9).unwrap();
+        }
+
+        // Opened read-only: stays at v9 and still loads
+        let conn = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+        assert_eq!(migrate_for_load(&conn), Ok(9));
+        drop(conn);
+
+        // A migration step that fails in SQL is an error
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch("DROP TABLE cells").unwrap();
+        let err = migrate_for_load(&conn).unwrap_err();
+        assert!(err.contains("failed to migrate schema from v9"), "{}", err);
+    }
+
     #[test]
     fn test_alignment_general_roundtrip() {
         // Directly insert a DB row with fmt_alignment=3 (General) and a value,
@@ -3209,6 +4339,20 @@ mod tests {
             "Value change MUST change fingerprint!");
     }
 
+    #[test]
+    fn test_fingerprint_text_vs_typed_value() {
+        // Text reading "TRUE" is not the boolean TRUE
+        let mut wb = Workbook::new();
+        wb.active_sheet_mut().set_value(0, 0, "TRUE");
+        let fp_boolean = compute_semantic_fingerprint(&wb);
+
+        wb.active_sheet_mut().set_text(0, 0, "TRUE");
+        let fp_text = compute_semantic_fingerprint(&wb);
+
+        assert_ne!(fp_boolean, fp_text,
+            "Text \"TRUE\" MUST fingerprint differently from boolean TRUE!");
+    }
+
     #[test]
     fn test_fingerprint_add_cell_drift() {
         // Adding a new cell changes fingerprint
@@ -3251,7 +4395,7 @@ mod tests {
         wb.active_sheet_mut().set_value(0, 0, "test");
 
         let fp = compute_semantic_fingerprint(&wb);
-        assert!(fp.starts_with("v2:"), "Fingerprint should start with version prefix, got: {}", fp);
+        assert!(fp.starts_with("v3:"), "Fingerprint should start with version prefix, got: {}", fp);
     }
 
     #[test]
@@ -3263,7 +4407,7 @@ mod tests {
         wb.active_sheet_mut().set_value(0, 2, "c");
 
         let fp = compute_semantic_fingerprint(&wb);
-        assert!(fp.starts_with("v2:3:"),
+        assert!(fp.starts_with("v3:3:"),
             "Fingerprint should show 3 operations, got: {}", fp);
     }
 
@@ -3293,6 +4437,28 @@ mod tests {
             "Fingerprint must change when tolerance changes");
     }
 
+    #[test]
+    fn test_fingerprint_named_range_drift() {
+        // Defining, retargeting, or removing a name changes fingerprint
+        let mut wb = Workbook::new();
+        wb.active_sheet_mut().set_value(0, 0, "100");
+
+        let fp_none = compute_semantic_fingerprint(&wb);
+
+        wb.define_name_for_cell("Revenue", 0, 0, 0).unwrap();
+        let fp_named = compute_semantic_fingerprint(&wb);
+        assert_ne!(fp_none, fp_named, "Adding a name MUST change fingerprint!");
+        assert!(fp_named.starts_with("v3:2:"), "Name counts as an operation, got: {}", fp_named);
+
+        wb.define_name_for_range("Revenue", 0, 0, 0, 9, 0).unwrap();
+        let fp_retargeted = compute_semantic_fingerprint(&wb);
+        assert_ne!(fp_named, fp_retargeted, "Retargeting a name MUST change fingerprint!");
+
+        wb.delete_named_range("Revenue");
+        assert_eq!(fp_none, compute_semantic_fingerprint(&wb),
+            "Removing the name restores the original fingerprint");
+    }
+
     #[test]
     fn test_verification_persistence_roundtrip() {
         // Full end-to-end: create file, stamp it, reload, verify status
@@ -3365,6 +4531,116 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fill_schema_roundtrip() {
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+        save_workbook(&Workbook::new(), path).expect("Save should succeed");
+
+        // No schema set yet
+        assert!(load_fill_schema(path).unwrap().is_empty());
+
+        let mut schema = FillSchema::new();
+        schema.insert("tx".to_string(), vec![
+            FillColumn { name: "date".to_string(), kind: FillColumnType::Text },
+            FillColumn { name: "amount".to_string(), kind: FillColumnType::Decimal },
+        ]);
+        save_fill_schema(path, &schema).expect("Save schema should succeed");
+        assert_eq!(load_fill_schema(path).unwrap(), schema);
+
+        // Empty schema clears it
+        save_fill_schema(path, &FillSchema::new()).unwrap();
+        assert!(load_fill_schema(path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_protected_ranges_roundtrip_outside_fingerprint() {
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+
+        let mut wb = Workbook::new();
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "locked");
+        let unprotected_fp = compute_semantic_fingerprint(&wb);
+        wb.sheet_mut(0).unwrap().protect(ProtectedRange::new(0, 0, 1, 1));
+        assert_eq!(compute_semantic_fingerprint(&wb), unprotected_fp);
+
+        save_workbook(&wb, path).expect("Save should succeed");
+        let loaded = load_workbook(path).expect("Load should succeed");
+        let sheet = loaded.sheet(0).unwrap();
+        assert_eq!(sheet.protected_ranges, vec![ProtectedRange::new(0, 0, 1, 1)]);
+        assert!(sheet.is_protected(1, 1));
+        assert!(!sheet.is_protected(0, 2));
+    }
+
+    #[test]
+    fn test_cell_comments_roundtrip_outside_fingerprint() {
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+
+        let mut wb = Workbook::new();
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "0.21");
+        let plain_fp = compute_semantic_fingerprint(&wb);
+        let note = CellComment { text: "Rate per 2025 filing".to_string(), author: Some("Reviewer".to_string()) };
+        wb.sheet_mut(0).unwrap().set_comment(0, 0, Some(note.clone()));
+        assert_eq!(compute_semantic_fingerprint(&wb), plain_fp);
+
+        save_workbook(&wb, path).expect("Save should succeed");
+        let loaded = load_workbook(path).expect("Load should succeed");
+        let sheet = loaded.sheet(0).unwrap();
+        assert_eq!(sheet.comment(0, 0), Some(&note));
+        assert_eq!(sheet.comments.len(), 1);
+    }
+
+    #[test]
+    fn test_conditional_formats_roundtrip_outside_fingerprint() {
+        use visigrid_engine::conditional_format::{CompareOperator, ConditionalRule, ConditionalStyle};
+
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+
+        let mut wb = Workbook::new();
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "-5");
+        let plain_fp = compute_semantic_fingerprint(&wb);
+        let negative_red = ConditionalFormat::new(0, 0, 9, 0, ConditionalRule::Compare {
+            operator: CompareOperator::LessThan,
+            values: vec!["0".to_string()],
+            style: ConditionalStyle { font_color: Some([255, 0, 0, 255]), ..Default::default() },
+        });
+        wb.sheet_mut(0).unwrap().add_conditional_format(negative_red.clone());
+        assert_eq!(compute_semantic_fingerprint(&wb), plain_fp);
+
+        save_workbook(&wb, path).expect("Save should succeed");
+        let loaded = load_workbook(path).expect("Load should succeed");
+        assert_eq!(loaded.sheet(0).unwrap().conditional_formats, vec![negative_red]);
+    }
+
+    #[test]
+    fn test_workbook_meta_roundtrip_outside_fingerprint() {
+        let temp_file = NamedTempFile::with_suffix(".sheet").unwrap();
+        let path = temp_file.path();
+
+        let mut wb = Workbook::new();
+        wb.sheet_mut(0).unwrap().set_value(0, 0, "Revenue");
+        save_workbook(&wb, path).expect("Save should succeed");
+        let before = compute_semantic_fingerprint(&load_workbook(path).unwrap());
+        assert!(load_workbook_meta(path).unwrap().is_empty());
+
+        let meta = WorkbookMeta {
+            title: Some("Q4 Revenue Model".to_string()),
+            description: Some("Quarterly close\nsecond line".to_string()),
+            author: Some("finance@acme.test".to_string()),
+            created_at: Some("2026-01-05T09:00:00+00:00".to_string()),
+            tags: vec!["q4".to_string(), "close".to_string()],
+        };
+        save_workbook_meta(path, &meta).expect("Save meta should succeed");
+        assert_eq!(load_workbook_meta(path).unwrap(), meta);
+        assert_eq!(compute_semantic_fingerprint(&load_workbook(path).unwrap()), before);
+
+        // Empty metadata clears it
+        save_workbook_meta(path, &WorkbookMeta::default()).unwrap();
+        assert!(load_workbook_meta(path).unwrap().is_empty());
+    }
+
     // ========================================================================
     // Border persistence tests
     // ========================================================================
@@ -3646,4 +4922,42 @@ mod tests {
         assert_eq!(format_f64_clean(-216.59699999999998), "-216.597");
         assert_eq!(format_f64_clean(-934.1020000000001), "-934.102");
     }
+
+    #[test]
+    fn test_repair_truncated_sheet_recovers_leading_cells() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.sheet");
+        let mut workbook = Workbook::new();
+        for row in 0..2000 {
+            workbook.active_sheet_mut().set_value(row, 0, &format!("row {} {}", row, "x".repeat(40)));
+        }
+        save_workbook(&workbook, &path).unwrap();
+
+        // An intact file repairs cleanly with every cell
+        let clean = repair_sheet(&path, &dir.path().join("clean.sheet")).unwrap();
+        assert!(clean.is_clean(), "{:?}", clean);
+        assert_eq!(clean.cells, 2000);
+        assert_eq!(clean.rows_lost(), 0);
+
+        // Chop the file as an interrupted write would
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() * 6 / 10]).unwrap();
+
+        let out = dir.path().join("repaired.sheet");
+        let result = repair_sheet(&path, &out).unwrap();
+        assert!(!result.is_clean());
+        assert!(!result.integrity_errors.is_empty());
+        assert!(result.cells > 0 && result.cells < 2000, "recovered {} cells", result.cells);
+
+        // Whatever was recovered is intact (cells are saved in hash order, so
+        // which rows survive is arbitrary)
+        let repaired = load_workbook(&out).unwrap();
+        for (&(row, col), cell) in repaired.active_sheet().cells_iter() {
+            assert_eq!(col, 0);
+            assert_eq!(cell.value.raw_display(), format!("row {} {}", row, "x".repeat(40)));
+        }
+
+        // Never overwrites an existing file
+        assert!(repair_sheet(&path, &out).unwrap_err().contains("already exists"));
+    }
 }
@@ -9,12 +9,13 @@ use std::path::Path;
 use std::time::Instant;
 
 use calamine::{open_workbook_auto, Data, Reader, Sheets};
-use rust_xlsxwriter::{Format, FormatAlign, FormatBorder, FormatUnderline, Workbook as XlsxWorkbook, Worksheet};
+use rust_xlsxwriter::{Format, FormatAlign, FormatBorder, FormatUnderline, Note, Workbook as XlsxWorkbook, Worksheet};
 use visigrid_engine::cell::{Alignment, BorderStyle, CellFormat, CellValue, DateStyle, NumberFormat, VerticalAlignment};
+use visigrid_engine::conditional_format::ConditionalFormat;
 use visigrid_engine::formula::analyze::tally_unknown_functions;
 use visigrid_engine::formula::eval::Value;
 use visigrid_engine::formula::parser::parse as parse_formula;
-use visigrid_engine::sheet::{MergedRegion, Sheet, SheetId};
+use visigrid_engine::sheet::{CellComment, MergedRegion, Sheet, SheetId};
 use visigrid_engine::workbook::Workbook;
 use crate::xlsx_styles;
 
@@ -112,6 +113,12 @@ pub struct ImportResult {
     pub merges_dropped_overlap: usize,
     /// Merged regions dropped due to invalid cell references
     pub merges_dropped_invalid: usize,
+    /// Cell comments (notes) imported
+    pub comments_imported: usize,
+    /// Conditional formatting rules imported (cellIs comparisons, color scales)
+    pub conditional_formats_imported: usize,
+    /// Conditional formatting rules skipped (data bars, icon sets, expressions, ...)
+    pub conditional_formats_skipped: usize,
     /// Cycle cells frozen to cached values (freeze_cycles option)
     pub cycles_frozen: usize,
     /// Cycle cells with no cached value (remain #CYCLE!)
@@ -155,6 +162,14 @@ impl ImportResult {
                 parts.push(format!("{} merged regions", self.merges_imported));
             }
         }
+        if self.comments_imported > 0 {
+            parts.push(format!("{} comment{}", self.comments_imported,
+                if self.comments_imported == 1 { "" } else { "s" }));
+        }
+        if self.conditional_formats_imported > 0 {
+            parts.push(format!("{} conditional format{}", self.conditional_formats_imported,
+                if self.conditional_formats_imported == 1 { "" } else { "s" }));
+        }
         parts.join(" · ")
     }
 
@@ -373,8 +388,9 @@ pub fn import_with_options(path: &Path, options: &ImportOptions) -> Result<(Work
                         // millions of phantom cells in the dense calamine range.
                     }
                     Data::String(s) => {
+                        // A string cell stays text even when it reads "TRUE" or "00123"
                         if !s.is_empty() {
-                            sheet.set_value(target_row, target_col, s);
+                            sheet.set_text(target_row, target_col, s);
                             stats.cells_imported += 1;
                             total_cells += 1;
                         }
@@ -397,7 +413,7 @@ pub fn import_with_options(path: &Path, options: &ImportOptions) -> Result<(Work
                         total_cells += 1;
                     }
                     Data::Bool(b) => {
-                        // Store as TRUE/FALSE text which will be recognized by formulas
+                        // TRUE/FALSE parse back to a boolean cell
                         sheet.set_value(target_row, target_col, if *b { "TRUE" } else { "FALSE" });
                         stats.cells_imported += 1;
                         total_cells += 1;
@@ -599,11 +615,38 @@ pub fn import_with_options(path: &Path, options: &ImportOptions) -> Result<(Work
     // Record import duration
     result.import_duration_ms = start_time.elapsed().as_millis();
 
-    let mut workbook = Workbook::from_sheets(sheets, 0);
+    // Formulas are backfilled below; the dep graph is built after that
+    let mut workbook = Workbook::from_sheets_deferred(sheets, 0);
 
     // Import formatting from styles.xml and per-cell style IDs
     import_formatting(path, &sheet_names, &mut workbook, &mut result);
 
+    // Cell comments (non-semantic, so imported in values_only mode too)
+    for (sheet_idx, row, col, comment) in extract_xml_comments(path) {
+        if let Some(sheet) = workbook.sheet_mut(sheet_idx) {
+            sheet.set_comment(row, col, Some(comment));
+            result.comments_imported += 1;
+        }
+    }
+
+    // Frozen panes (presentation only, like comments)
+    for (sheet_idx, rows, cols) in extract_xml_frozen_panes(path) {
+        if let Some(sheet) = workbook.sheet_mut(sheet_idx) {
+            sheet.frozen_rows = rows;
+            sheet.frozen_cols = cols;
+        }
+    }
+
+    // Conditional formatting (presentation only, like comments)
+    let (conditional_formats, skipped) = extract_xml_conditional_formats(path);
+    result.conditional_formats_skipped += skipped;
+    for (sheet_idx, cf) in conditional_formats {
+        if let Some(sheet) = workbook.sheet_mut(sheet_idx) {
+            sheet.add_conditional_format(cf);
+            result.conditional_formats_imported += 1;
+        }
+    }
+
     if !options.values_only {
         // Detect shared formula groups from XLSX XML (diagnostic guardrail)
         result.shared_formula_groups = count_shared_formula_groups(path);
@@ -963,6 +1006,10 @@ pub struct ExportResult {
     pub autofilter_exported: bool,
     /// Number of hidden rows exported
     pub hidden_rows_exported: usize,
+    /// Cell comments exported as notes
+    pub comments_exported: usize,
+    /// Conditional formatting rules exported
+    pub conditional_formats_exported: usize,
 }
 
 impl ExportResult {
@@ -1192,6 +1239,32 @@ pub fn export(
             apply_layout(worksheet, layout)?;
         }
 
+        // The sheet's own frozen panes, unless the layout already set some
+        let layout_frozen = layout.is_some_and(|l| l.frozen_rows > 0 || l.frozen_cols > 0);
+        if !layout_frozen && (sheet.frozen_rows > 0 || sheet.frozen_cols > 0) {
+            worksheet.set_freeze_panes(sheet.frozen_rows as u32, sheet.frozen_cols as u16)
+                .map_err(|e| format!("Failed to set freeze panes: {}", e))?;
+        }
+
+        // Export cell comments as notes (text kept verbatim, no "Author:" prefix)
+        for (&(row, col), comment) in &sheet.comments {
+            let mut note = Note::new(&comment.text).add_author_prefix(false);
+            if let Some(author) = &comment.author {
+                note = note.set_author(author);
+            }
+            worksheet
+                .insert_note(row as u32, col as u16, &note)
+                .map_err(|e| format!("Failed to write comment: {}", e))?;
+            result.comments_exported += 1;
+        }
+
+        // Export conditional formatting rules
+        for cf in &sheet.conditional_formats {
+            if crate::xlsx_conditional::add_to_worksheet(worksheet, cf)? {
+                result.conditional_formats_exported += 1;
+            }
+        }
+
         // Export validation rules
         let (exported, skipped) = export_validation_rules(worksheet, sheet)?;
         result.validations_exported += exported;
@@ -1347,6 +1420,19 @@ fn export_sheet_cells(
                     .map_err(|e| format!("Failed to write cell ({}, {}): {}", row, col, e))?;
                 cells_exported += 1;
             }
+            CellValue::Boolean(b) => {
+                worksheet
+                    .write_boolean_with_format(row32, col16, *b, &format)
+                    .map_err(|e| format!("Failed to write cell ({}, {}): {}", row, col, e))?;
+                cells_exported += 1;
+            }
+            CellValue::Error(kind) => {
+                // No literal error cell type in the writer; keep the code as text
+                worksheet
+                    .write_string_with_format(row32, col16, kind.as_str(), &format)
+                    .map_err(|e| format!("Failed to write cell ({}, {}): {}", row, col, e))?;
+                cells_exported += 1;
+            }
             CellValue::Number(n) => {
                 // Check for precision loss (>15 significant digits)
                 if exceeds_excel_precision(*n) {
@@ -1978,6 +2064,312 @@ fn extract_xml_value_cells(path: &Path) -> Vec<(usize, usize, usize, String)> {
     all_values
 }
 
+/// Extract cell comments (legacy notes) from each worksheet's comments part.
+/// Returns: Vec<(sheet_index, row, col, comment)>, sheet_index in workbook.xml order.
+fn extract_xml_comments(path: &Path) -> Vec<(usize, usize, usize, CellComment)> {
+    use zip::ZipArchive;
+
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+    let mut archive = match ZipArchive::new(file) {
+        Ok(a) => a,
+        Err(_) => return Vec::new(),
+    };
+
+    let workbook_xml = match read_zip_file_for_shared(&mut archive, "xl/workbook.xml") {
+        Some(s) => s,
+        None => return Vec::new(),
+    };
+    let rels_xml = match read_zip_file_for_shared(&mut archive, "xl/_rels/workbook.xml.rels") {
+        Some(s) => s,
+        None => return Vec::new(),
+    };
+
+    let worksheet_paths = resolve_worksheet_paths(&workbook_xml, &rels_xml);
+
+    let mut all_comments = Vec::new();
+
+    for (sheet_idx, ws_path) in worksheet_paths.iter().enumerate() {
+        // xl/worksheets/sheet1.xml -> xl/worksheets/_rels/sheet1.xml.rels
+        let (dir, file_name) = ws_path.rsplit_once('/').unwrap_or(("", ws_path));
+        let ws_rels = match read_zip_file_for_shared(&mut archive, &format!("{}/_rels/{}.rels", dir, file_name)) {
+            Some(s) => s,
+            None => continue,
+        };
+        let Some(comments_path) = comments_part_path(dir, &ws_rels) else { continue };
+        let xml = match read_zip_file_for_shared(&mut archive, &comments_path) {
+            Some(s) => s,
+            None => continue,
+        };
+        for (row, col, comment) in parse_comments_xml(&xml) {
+            all_comments.push((sheet_idx, row, col, comment));
+        }
+    }
+
+    all_comments
+}
+
+/// Extract frozen panes from each worksheet's `<sheetView><pane state="frozen">`.
+/// Returns: Vec<(sheet_index, frozen_rows, frozen_cols)>, only for sheets with a freeze.
+fn extract_xml_frozen_panes(path: &Path) -> Vec<(usize, usize, usize)> {
+    use zip::ZipArchive;
+
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+    let mut archive = match ZipArchive::new(file) {
+        Ok(a) => a,
+        Err(_) => return Vec::new(),
+    };
+
+    let workbook_xml = match read_zip_file_for_shared(&mut archive, "xl/workbook.xml") {
+        Some(s) => s,
+        None => return Vec::new(),
+    };
+    let rels_xml = match read_zip_file_for_shared(&mut archive, "xl/_rels/workbook.xml.rels") {
+        Some(s) => s,
+        None => return Vec::new(),
+    };
+
+    let worksheet_paths = resolve_worksheet_paths(&workbook_xml, &rels_xml);
+
+    let mut panes = Vec::new();
+    for (sheet_idx, ws_path) in worksheet_paths.iter().enumerate() {
+        let Some(xml) = read_zip_file_for_shared(&mut archive, ws_path) else { continue };
+        if let Some((rows, cols)) = parse_frozen_pane(&xml) {
+            panes.push((sheet_idx, rows, cols));
+        }
+    }
+
+    panes
+}
+
+/// Extract conditional formatting rules from each worksheet, styled with the
+/// `<dxfs>` from styles.xml.
+/// Returns: (Vec<(sheet_index, rule)>, skipped_count), rules in priority order per sheet.
+fn extract_xml_conditional_formats(path: &Path) -> (Vec<(usize, ConditionalFormat)>, usize) {
+    use zip::ZipArchive;
+
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(_) => return (Vec::new(), 0),
+    };
+    let mut archive = match ZipArchive::new(file) {
+        Ok(a) => a,
+        Err(_) => return (Vec::new(), 0),
+    };
+
+    let workbook_xml = match read_zip_file_for_shared(&mut archive, "xl/workbook.xml") {
+        Some(s) => s,
+        None => return (Vec::new(), 0),
+    };
+    let rels_xml = match read_zip_file_for_shared(&mut archive, "xl/_rels/workbook.xml.rels") {
+        Some(s) => s,
+        None => return (Vec::new(), 0),
+    };
+    let dxfs = read_zip_file_for_shared(&mut archive, "xl/styles.xml")
+        .map(|xml| xlsx_styles::parse_dxfs(&xml))
+        .unwrap_or_default();
+
+    let worksheet_paths = resolve_worksheet_paths(&workbook_xml, &rels_xml);
+
+    let mut formats = Vec::new();
+    let mut skipped = 0;
+    for (sheet_idx, ws_path) in worksheet_paths.iter().enumerate() {
+        let Some(xml) = read_zip_file_for_shared(&mut archive, ws_path) else { continue };
+        if !xml.contains("<conditionalFormatting") {
+            continue;
+        }
+        let (sheet_formats, sheet_skipped) =
+            crate::xlsx_conditional::parse_worksheet_conditional_formats(&xml, &dxfs);
+        formats.extend(sheet_formats.into_iter().map(|cf| (sheet_idx, cf)));
+        skipped += sheet_skipped;
+    }
+
+    (formats, skipped)
+}
+
+/// Frozen (rows, cols) from a worksheet's first `<pane>`. Split (non-frozen)
+/// panes are ignored. Stops at `<sheetData>`, since `<sheetViews>` precedes it.
+fn parse_frozen_pane(xml: &str) -> Option<(usize, usize)> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e)) => match e.name().as_ref() {
+                b"pane" => {
+                    let mut frozen = false;
+                    let (mut rows, mut cols) = (0usize, 0usize);
+                    for attr in e.attributes().flatten() {
+                        let value = String::from_utf8_lossy(&attr.value);
+                        match attr.key.as_ref() {
+                            b"state" => frozen = value == "frozen" || value == "frozenSplit",
+                            // Split positions are cell counts when frozen
+                            b"ySplit" => rows = value.parse::<f64>().map(|v| v as usize).unwrap_or(0),
+                            b"xSplit" => cols = value.parse::<f64>().map(|v| v as usize).unwrap_or(0),
+                            _ => {}
+                        }
+                    }
+                    return (frozen && (rows > 0 || cols > 0)).then_some((rows, cols));
+                }
+                b"sheetData" => return None,
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => return None,
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// Find the comments relationship in a worksheet's rels and resolve its
+/// target (relative to the worksheet's directory `dir`) to a zip path.
+fn comments_part_path(dir: &str, ws_rels: &str) -> Option<String> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let mut reader = Reader::from_str(ws_rels);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut target = None;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e))
+                if e.name().as_ref() == b"Relationship" =>
+            {
+                let mut is_comments = false;
+                let mut t = None;
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"Type" => is_comments = attr.value.ends_with(b"/comments"),
+                        b"Target" => t = Some(String::from_utf8_lossy(&attr.value).to_string()),
+                        _ => {}
+                    }
+                }
+                if is_comments {
+                    target = t;
+                    break;
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let target = target?;
+    if let Some(absolute) = target.strip_prefix('/') {
+        return Some(absolute.to_string());
+    }
+    let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    for segment in target.split('/') {
+        match segment {
+            ".." => { parts.pop(); }
+            "." | "" => {}
+            s => parts.push(s),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Parse a comments part (xl/commentsN.xml) into (row, col, comment).
+/// Rich-text runs are concatenated; authors are resolved from `<authors>`.
+fn parse_comments_xml(xml: &str) -> Vec<(usize, usize, CellComment)> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let mut authors: Vec<String> = Vec::new();
+    let mut comments = Vec::new();
+
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(false); // preserve whitespace in comment text
+    let mut buf = Vec::new();
+
+    let mut in_author = false;
+    let mut in_t = false;
+    let mut in_phonetic = false;
+    let mut text = String::new();
+    // (row, col, authorId) of the <comment> being read
+    let mut current: Option<(usize, usize, Option<usize>)> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.name().as_ref() {
+                b"author" => {
+                    in_author = true;
+                    text.clear();
+                }
+                b"comment" => {
+                    let mut cell = None;
+                    let mut author_id = None;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"ref" => cell = parse_xlsx_cell_ref(&String::from_utf8_lossy(&attr.value)),
+                            b"authorId" => author_id = String::from_utf8_lossy(&attr.value).parse().ok(),
+                            _ => {}
+                        }
+                    }
+                    current = cell.map(|(row, col)| (row, col, author_id));
+                    text.clear();
+                }
+                b"rPh" => in_phonetic = true,
+                b"t" if !in_phonetic => in_t = true,
+                _ => {}
+            },
+            Ok(Event::Text(ref e)) if in_t || in_author => {
+                if let Ok(s) = e.decode() {
+                    text.push_str(&s);
+                }
+            }
+            Ok(Event::GeneralRef(ref e)) if in_t || in_author => {
+                if let Ok(Some(ch)) = e.resolve_char_ref() {
+                    text.push(ch);
+                } else {
+                    match e.as_ref() {
+                        b"amp" => text.push('&'),
+                        b"lt" => text.push('<'),
+                        b"gt" => text.push('>'),
+                        b"quot" => text.push('"'),
+                        b"apos" => text.push('\''),
+                        _ => {}
+                    }
+                }
+            }
+            Ok(Event::End(ref e)) => match e.name().as_ref() {
+                b"author" => {
+                    authors.push(std::mem::take(&mut text));
+                    in_author = false;
+                }
+                b"t" => in_t = false,
+                b"rPh" => in_phonetic = false,
+                b"comment" => {
+                    if let Some((row, col, author_id)) = current.take() {
+                        let author = author_id
+                            .and_then(|i| authors.get(i))
+                            .filter(|a| !a.is_empty())
+                            .cloned();
+                        comments.push((row, col, CellComment { text: std::mem::take(&mut text), author }));
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    comments
+}
+
 /// Parse xl/sharedStrings.xml into a Vec of strings indexed by position.
 /// Each <si> element contains either <t>text</t> or <r><t>text</t></r> (rich text).
 fn parse_shared_strings(xml: &str) -> Vec<String> {
@@ -2905,6 +3297,172 @@ mod tests {
         assert!(imported.is_merge_hidden(0, 2), "C1 should be merge-hidden after import");
     }
 
+    // ========================================================================
+    // Comment round-trip tests
+    // ========================================================================
+
+    #[test]
+    fn test_xlsx_comment_survives_sheet_save_load() {
+        let mut workbook = Workbook::new();
+        let sheet = workbook.active_sheet_mut();
+        sheet.set_value(0, 0, "Rate");
+        sheet.set_value(0, 1, "0.21");
+        let note = CellComment {
+            text: "Statutory rate & 2025 filing <see p.4>".to_string(),
+            author: Some("Reviewer".to_string()),
+        };
+        sheet.set_comment(0, 1, Some(note.clone()));
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let xlsx_path = temp_dir.path().join("commented.xlsx");
+        let result = export(&workbook, &xlsx_path, None).unwrap();
+        assert_eq!(result.comments_exported, 1);
+
+        let (imported_wb, import_result) = import(&xlsx_path).expect("Import should succeed");
+        assert_eq!(import_result.comments_imported, 1);
+        assert_eq!(imported_wb.sheets()[0].comment(0, 1), Some(&note));
+
+        // Through .sheet save/load, without touching the fingerprint
+        let sheet_path = temp_dir.path().join("commented.sheet");
+        crate::native::save_workbook(&imported_wb, &sheet_path).unwrap();
+        let loaded = crate::native::load_workbook(&sheet_path).unwrap();
+        assert_eq!(loaded.sheets()[0].comment(0, 1), Some(&note));
+        assert!(loaded.sheets()[0].comment(0, 0).is_none());
+
+        let mut uncommented = loaded.clone();
+        uncommented.sheet_mut(0).unwrap().set_comment(0, 1, None);
+        assert_eq!(
+            crate::native::compute_semantic_fingerprint(&loaded),
+            crate::native::compute_semantic_fingerprint(&uncommented),
+        );
+    }
+
+    #[test]
+    fn test_xlsx_conditional_formats_survive_sheet_save_load() {
+        use visigrid_engine::conditional_format::{CompareOperator, ConditionalRule, ConditionalStyle};
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.active_sheet_mut();
+        for (row, value) in ["12", "-3", "40", "-7"].iter().enumerate() {
+            sheet.set_value(row, 0, value);
+        }
+        let negative_red = ConditionalFormat::new(0, 0, 3, 0, ConditionalRule::Compare {
+            operator: CompareOperator::LessThan,
+            values: vec!["0".to_string()],
+            style: ConditionalStyle {
+                font_color: Some([0x9C, 0x00, 0x06, 255]),
+                background_color: Some([0xFF, 0xC7, 0xCE, 255]),
+                ..Default::default()
+            },
+        });
+        let scale = ConditionalFormat::new(0, 0, 3, 0,
+            ConditionalRule::color_scale(&[[0xF8, 0x69, 0x6B, 255], [0x63, 0xBE, 0x7B, 255]]).unwrap());
+        sheet.add_conditional_format(negative_red.clone());
+        sheet.add_conditional_format(scale.clone());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let xlsx_path = temp_dir.path().join("conditional.xlsx");
+        let result = export(&workbook, &xlsx_path, None).unwrap();
+        assert_eq!(result.conditional_formats_exported, 2);
+
+        let (imported_wb, import_result) = import(&xlsx_path).expect("Import should succeed");
+        assert_eq!(import_result.conditional_formats_imported, 2);
+        assert_eq!(import_result.conditional_formats_skipped, 0);
+        assert_eq!(imported_wb.sheets()[0].conditional_formats, vec![negative_red.clone(), scale.clone()]);
+
+        // Through .sheet save/load, without touching the fingerprint
+        let sheet_path = temp_dir.path().join("conditional.sheet");
+        crate::native::save_workbook(&imported_wb, &sheet_path).unwrap();
+        let loaded = crate::native::load_workbook(&sheet_path).unwrap();
+        assert_eq!(loaded.sheets()[0].conditional_formats, vec![negative_red, scale]);
+
+        let mut unformatted = loaded.clone();
+        unformatted.sheet_mut(0).unwrap().conditional_formats.clear();
+        assert_eq!(
+            crate::native::compute_semantic_fingerprint(&loaded),
+            crate::native::compute_semantic_fingerprint(&unformatted),
+        );
+    }
+
+    #[test]
+    fn test_xlsx_frozen_top_row_survives_sheet_save_load() {
+        let mut workbook = Workbook::new();
+        let sheet = workbook.active_sheet_mut();
+        sheet.set_value(0, 0, "Account");
+        sheet.set_value(0, 1, "Balance");
+        sheet.set_value(1, 0, "Cash");
+        sheet.set_value(1, 1, "1200");
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let xlsx_path = temp_dir.path().join("frozen.xlsx");
+        let layout = ExportLayout { frozen_rows: 1, ..Default::default() };
+        export(&workbook, &xlsx_path, Some(&[layout])).unwrap();
+
+        let (imported_wb, _) = import(&xlsx_path).expect("Import should succeed");
+        let imported = &imported_wb.sheets()[0];
+        assert_eq!((imported.frozen_rows, imported.frozen_cols), (1, 0));
+
+        // Through .sheet save/load, without touching the fingerprint
+        let sheet_path = temp_dir.path().join("frozen.sheet");
+        crate::native::save_workbook(&imported_wb, &sheet_path).unwrap();
+        let loaded = crate::native::load_workbook(&sheet_path).unwrap();
+        assert_eq!((loaded.sheets()[0].frozen_rows, loaded.sheets()[0].frozen_cols), (1, 0));
+
+        let mut unfrozen = loaded.clone();
+        unfrozen.sheet_mut(0).unwrap().frozen_rows = 0;
+        assert_eq!(
+            crate::native::compute_semantic_fingerprint(&loaded),
+            crate::native::compute_semantic_fingerprint(&unfrozen),
+        );
+
+        // And back out to xlsx from the sheet itself (no layout)
+        let again = temp_dir.path().join("again.xlsx");
+        export(&loaded, &again, None).unwrap();
+        let (reimported, _) = import(&again).unwrap();
+        assert_eq!(reimported.sheets()[0].frozen_rows, 1);
+    }
+
+    #[test]
+    fn test_parse_frozen_pane_ignores_split() {
+        let frozen = r#"<worksheet><sheetViews><sheetView workbookViewId="0"><pane xSplit="2" ySplit="1" topLeftCell="C2" activePane="bottomRight" state="frozen"/></sheetView></sheetViews><sheetData/></worksheet>"#;
+        assert_eq!(parse_frozen_pane(frozen), Some((1, 2)));
+        let split = r#"<worksheet><sheetViews><sheetView workbookViewId="0"><pane xSplit="2400" ySplit="1800" topLeftCell="C4"/></sheetView></sheetViews><sheetData/></worksheet>"#;
+        assert_eq!(parse_frozen_pane(split), None);
+        assert_eq!(parse_frozen_pane("<worksheet><sheetData/></worksheet>"), None);
+    }
+
+    #[test]
+    fn test_parse_comments_xml_excel_rich_text() {
+        // As written by Excel: bold author run, then the note body
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<authors><author>Ana</author><author>Ben</author></authors>
+<commentList>
+<comment ref="C5" authorId="1"><text><r><rPr><b/></rPr><t>Ben:</t></r><r><t xml:space="preserve">
+Check FX &amp; fees</t></r></text></comment>
+<comment ref="A1" authorId="0"><text><t>plain</t></text></comment>
+</commentList></comments>"#;
+        let comments = parse_comments_xml(xml);
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].0, 4);
+        assert_eq!(comments[0].1, 2);
+        assert_eq!(comments[0].2.text, "Ben:\nCheck FX & fees");
+        assert_eq!(comments[0].2.author.as_deref(), Some("Ben"));
+        assert_eq!(comments[1].2, CellComment { text: "plain".to_string(), author: Some("Ana".to_string()) });
+    }
+
+    #[test]
+    fn test_comments_part_path_resolution() {
+        let rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/vmlDrawing" Target="../drawings/vmlDrawing1.vml"/>
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments1.xml"/>
+</Relationships>"#;
+        assert_eq!(comments_part_path("xl/worksheets", rels).as_deref(), Some("xl/comments1.xml"));
+        let absolute = rels.replace("../comments1.xml", "/xl/comments7.xml");
+        assert_eq!(comments_part_path("xl/worksheets", &absolute).as_deref(), Some("xl/comments7.xml"));
+        assert_eq!(comments_part_path("xl/worksheets", "<Relationships/>"), None);
+    }
+
     // ========================================================================
     // Validation round-trip tests
     // ========================================================================
@@ -0,0 +1,378 @@
+//! XLSX Conditional Formatting import/export support
+//!
+//! Maps between VisiGrid's conditional format model and Excel's
+//! `<conditionalFormatting>` worksheet XML. Import and export share the
+//! operator and stop mappings below.
+//!
+//! ## Scope
+//! - `cellIs` rules (all operators), styled via a `<dxf>` from styles.xml
+//! - 2- and 3-color scales
+//!
+//! Other rule types (data bars, icon sets, expression, top-N, ...) are
+//! skipped on import and counted, as are rules over ranges that aren't a
+//! cell or a bounded area (whole columns like `A:A`, whole rows).
+//!
+//! ## Key gotchas
+//! - One `<conditionalFormatting>` can cover several ranges (`sqref="A1:A5 C1:C5"`);
+//!   each range becomes its own VisiGrid rule.
+//! - Rules are ordered by `priority` (1 = highest), not by document order.
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use rust_xlsxwriter::{
+    Color, ConditionalFormat2ColorScale, ConditionalFormat3ColorScale, ConditionalFormatCell,
+    ConditionalFormatCellRule, ConditionalFormatType, Format, Formula, Worksheet,
+};
+use visigrid_engine::conditional_format::{
+    ColorStop, CompareOperator, ConditionalFormat, ConditionalRule, ConditionalStyle, StopKind,
+};
+
+use crate::xlsx_styles::{parse_argb_hex, parse_merge_ref};
+
+// ============================================================================
+// Export: VisiGrid -> Excel
+// ============================================================================
+
+/// Write one conditional format to the worksheet. Returns false (and writes
+/// nothing) for a color scale without 2 or 3 stops, or a comparison with the
+/// wrong number of operands.
+pub fn add_to_worksheet(worksheet: &mut Worksheet, cf: &ConditionalFormat) -> Result<bool, String> {
+    let (first_row, first_col) = (cf.start.0 as u32, cf.start.1 as u16);
+    let (last_row, last_col) = (cf.end.0 as u32, cf.end.1 as u16);
+
+    let added = match &cf.rule {
+        ConditionalRule::Compare { operator, values, style } => {
+            let Some(rule) = cell_rule_to_xlsx(*operator, values) else { return Ok(false) };
+            let xlsx = ConditionalFormatCell::new().set_rule(rule).set_format(style_to_format(style));
+            worksheet.add_conditional_format(first_row, first_col, last_row, last_col, &xlsx)
+        }
+        ConditionalRule::ColorScale { stops } => match stops.as_slice() {
+            [min, max] => {
+                let (min_type, min_value) = stop_to_xlsx(min);
+                let (max_type, max_value) = stop_to_xlsx(max);
+                let xlsx = ConditionalFormat2ColorScale::new()
+                    .set_minimum(min_type, min_value)
+                    .set_maximum(max_type, max_value)
+                    .set_minimum_color(to_xlsx_color(min.color))
+                    .set_maximum_color(to_xlsx_color(max.color));
+                worksheet.add_conditional_format(first_row, first_col, last_row, last_col, &xlsx)
+            }
+            [min, mid, max] => {
+                let (min_type, min_value) = stop_to_xlsx(min);
+                let (mid_type, mid_value) = stop_to_xlsx(mid);
+                let (max_type, max_value) = stop_to_xlsx(max);
+                let xlsx = ConditionalFormat3ColorScale::new()
+                    .set_minimum(min_type, min_value)
+                    .set_midpoint(mid_type, mid_value)
+                    .set_maximum(max_type, max_value)
+                    .set_minimum_color(to_xlsx_color(min.color))
+                    .set_midpoint_color(to_xlsx_color(mid.color))
+                    .set_maximum_color(to_xlsx_color(max.color));
+                worksheet.add_conditional_format(first_row, first_col, last_row, last_col, &xlsx)
+            }
+            _ => return Ok(false),
+        },
+    };
+
+    added.map_err(|e| format!("Failed to add conditional format: {}", e))?;
+    Ok(true)
+}
+
+/// Operands are written as formula text, so `0`, `$B$1`, and `"Closed"`
+/// all reach the file verbatim.
+fn cell_rule_to_xlsx(operator: CompareOperator, values: &[String]) -> Option<ConditionalFormatCellRule<Formula>> {
+    if values.len() != operator.operand_count() {
+        return None;
+    }
+    let v = |i: usize| Formula::new(&values[i]);
+    Some(match operator {
+        CompareOperator::Equal => ConditionalFormatCellRule::EqualTo(v(0)),
+        CompareOperator::NotEqual => ConditionalFormatCellRule::NotEqualTo(v(0)),
+        CompareOperator::GreaterThan => ConditionalFormatCellRule::GreaterThan(v(0)),
+        CompareOperator::GreaterThanOrEqual => ConditionalFormatCellRule::GreaterThanOrEqualTo(v(0)),
+        CompareOperator::LessThan => ConditionalFormatCellRule::LessThan(v(0)),
+        CompareOperator::LessThanOrEqual => ConditionalFormatCellRule::LessThanOrEqualTo(v(0)),
+        CompareOperator::Between => ConditionalFormatCellRule::Between(v(0), v(1)),
+        CompareOperator::NotBetween => ConditionalFormatCellRule::NotBetween(v(0), v(1)),
+    })
+}
+
+fn stop_to_xlsx(stop: &ColorStop) -> (ConditionalFormatType, Formula) {
+    let kind = match stop.kind {
+        StopKind::Min => ConditionalFormatType::Lowest,
+        StopKind::Max => ConditionalFormatType::Highest,
+        StopKind::Number => ConditionalFormatType::Number,
+        StopKind::Percent => ConditionalFormatType::Percent,
+        StopKind::Percentile => ConditionalFormatType::Percentile,
+        StopKind::Formula => ConditionalFormatType::Formula,
+    };
+    (kind, Formula::new(stop.value.as_deref().unwrap_or("0")))
+}
+
+fn style_to_format(style: &ConditionalStyle) -> Format {
+    let mut format = Format::new();
+    if let Some(color) = style.font_color {
+        format = format.set_font_color(to_xlsx_color(color));
+    }
+    if let Some(color) = style.background_color {
+        format = format.set_background_color(to_xlsx_color(color));
+    }
+    if style.bold {
+        format = format.set_bold();
+    }
+    if style.italic {
+        format = format.set_italic();
+    }
+    format
+}
+
+fn to_xlsx_color([r, g, b, _]: [u8; 4]) -> Color {
+    Color::RGB(((r as u32) << 16) | ((g as u32) << 8) | (b as u32))
+}
+
+// ============================================================================
+// Import: Excel -> VisiGrid
+// ============================================================================
+
+/// Parse every `<conditionalFormatting>` block of a worksheet.
+///
+/// `dxfs` are the differential styles from styles.xml (see
+/// [`crate::xlsx_styles::parse_dxfs`]). Returns the rules in priority order
+/// and the number skipped as unsupported. A rule counts once per range it
+/// would have covered when the range itself can't be parsed.
+pub fn parse_worksheet_conditional_formats(xml: &str, dxfs: &[ConditionalStyle]) -> (Vec<ConditionalFormat>, usize) {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+
+    let mut parsed: Vec<(u32, ConditionalFormat)> = Vec::new();
+    let mut skipped = 0;
+
+    // State for the enclosing <conditionalFormatting> and current <cfRule>
+    let mut ranges: Vec<(usize, usize, usize, usize)> = Vec::new();
+    let mut unparsed_ranges = 0;
+    let mut rule_type = String::new();
+    let mut operator: Option<CompareOperator> = None;
+    let mut dxf_id: Option<usize> = None;
+    let mut priority = u32::MAX;
+    let mut formulas: Vec<String> = Vec::new();
+    let mut stop_kinds: Vec<(StopKind, Option<String>)> = Vec::new();
+    let mut colors: Vec<[u8; 4]> = Vec::new();
+    let mut in_formula = false;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
+                let attr = |name: &[u8]| {
+                    e.attributes().flatten()
+                        .find(|a| a.key.as_ref() == name)
+                        .map(|a| String::from_utf8_lossy(&a.value).into_owned())
+                };
+                match e.name().as_ref() {
+                    b"conditionalFormatting" => {
+                        let sqref = attr(b"sqref").unwrap_or_default();
+                        let refs: Vec<&str> = sqref.split_whitespace().collect();
+                        ranges = refs.iter().filter_map(|r| parse_sqref_range(r)).collect();
+                        unparsed_ranges = refs.len() - ranges.len();
+                    }
+                    b"cfRule" => {
+                        rule_type = attr(b"type").unwrap_or_default();
+                        operator = attr(b"operator").as_deref().and_then(operator_from_xlsx);
+                        dxf_id = attr(b"dxfId").and_then(|s| s.parse().ok());
+                        priority = attr(b"priority").and_then(|s| s.parse().ok()).unwrap_or(u32::MAX);
+                        formulas.clear();
+                        stop_kinds.clear();
+                        colors.clear();
+                    }
+                    b"formula" => {
+                        in_formula = true;
+                        text.clear();
+                    }
+                    b"cfvo" => {
+                        let kind = attr(b"type").as_deref().and_then(stop_kind_from_xlsx);
+                        stop_kinds.push((kind.unwrap_or(StopKind::Min), attr(b"val")));
+                    }
+                    b"color" => {
+                        let rgb = attr(b"rgb").unwrap_or_default();
+                        colors.push(parse_argb_hex(rgb.as_bytes()).unwrap_or([0, 0, 0, 255]));
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Text(ref e)) if in_formula => {
+                if let Ok(s) = e.decode() {
+                    text.push_str(&s);
+                }
+            }
+            Ok(Event::GeneralRef(ref e)) if in_formula => match e.as_ref() {
+                b"amp" => text.push('&'),
+                b"lt" => text.push('<'),
+                b"gt" => text.push('>'),
+                b"quot" => text.push('"'),
+                b"apos" => text.push('\''),
+                _ => {}
+            },
+            Ok(Event::End(ref e)) => match e.name().as_ref() {
+                b"formula" => {
+                    in_formula = false;
+                    formulas.push(std::mem::take(&mut text));
+                }
+                b"cfRule" => {
+                    let rule = build_rule(&rule_type, operator, dxf_id, dxfs, &formulas, &stop_kinds, &colors);
+                    match rule {
+                        Some(rule) => {
+                            for &(sr, sc, er, ec) in &ranges {
+                                parsed.push((priority, ConditionalFormat::new(sr, sc, er, ec, rule.clone())));
+                            }
+                            skipped += unparsed_ranges;
+                        }
+                        None => skipped += 1,
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Stable sort keeps document order among equal priorities
+    parsed.sort_by_key(|(priority, _)| *priority);
+    (parsed.into_iter().map(|(_, cf)| cf).collect(), skipped)
+}
+
+fn build_rule(
+    rule_type: &str,
+    operator: Option<CompareOperator>,
+    dxf_id: Option<usize>,
+    dxfs: &[ConditionalStyle],
+    formulas: &[String],
+    stop_kinds: &[(StopKind, Option<String>)],
+    colors: &[[u8; 4]],
+) -> Option<ConditionalRule> {
+    match rule_type {
+        "cellIs" => {
+            let operator = operator?;
+            if formulas.len() != operator.operand_count() {
+                return None;
+            }
+            let style = dxf_id.and_then(|id| dxfs.get(id)).cloned().unwrap_or_default();
+            Some(ConditionalRule::Compare { operator, values: formulas.to_vec(), style })
+        }
+        "colorScale" => {
+            if !(2..=3).contains(&stop_kinds.len()) || stop_kinds.len() != colors.len() {
+                return None;
+            }
+            let stops = stop_kinds
+                .iter()
+                .zip(colors)
+                .map(|((kind, value), color)| ColorStop {
+                    kind: *kind,
+                    // min/max stops carry no threshold
+                    value: value.clone().filter(|_| !matches!(kind, StopKind::Min | StopKind::Max)),
+                    color: *color,
+                })
+                .collect();
+            Some(ConditionalRule::ColorScale { stops })
+        }
+        _ => None,
+    }
+}
+
+fn operator_from_xlsx(s: &str) -> Option<CompareOperator> {
+    Some(match s {
+        "equal" => CompareOperator::Equal,
+        "notEqual" => CompareOperator::NotEqual,
+        "greaterThan" => CompareOperator::GreaterThan,
+        "greaterThanOrEqual" => CompareOperator::GreaterThanOrEqual,
+        "lessThan" => CompareOperator::LessThan,
+        "lessThanOrEqual" => CompareOperator::LessThanOrEqual,
+        "between" => CompareOperator::Between,
+        "notBetween" => CompareOperator::NotBetween,
+        _ => return None,
+    })
+}
+
+fn stop_kind_from_xlsx(s: &str) -> Option<StopKind> {
+    Some(match s {
+        "min" => StopKind::Min,
+        "max" => StopKind::Max,
+        "num" => StopKind::Number,
+        "percent" => StopKind::Percent,
+        "percentile" => StopKind::Percentile,
+        "formula" => StopKind::Formula,
+        _ => return None,
+    })
+}
+
+/// `A1:B5` or a single cell `C3`, as (start_row, start_col, end_row, end_col).
+fn parse_sqref_range(r: &str) -> Option<(usize, usize, usize, usize)> {
+    if r.contains(':') {
+        parse_merge_ref(r)
+    } else {
+        parse_merge_ref(&format!("{}:{}", r, r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cell_is_and_color_scale() {
+        let xml = r#"<worksheet><sheetData/>
+<conditionalFormatting sqref="B2:B10 D4">
+  <cfRule type="cellIs" dxfId="0" priority="2" operator="lessThan"><formula>0</formula></cfRule>
+</conditionalFormatting>
+<conditionalFormatting sqref="C2:C10">
+  <cfRule type="colorScale" priority="1"><colorScale>
+    <cfvo type="min"/><cfvo type="percentile" val="50"/><cfvo type="max"/>
+    <color rgb="FFF8696B"/><color rgb="FFFFEB84"/><color rgb="FF63BE7B"/>
+  </colorScale></cfRule>
+  <cfRule type="dataBar" priority="3"><dataBar><cfvo type="min"/><cfvo type="max"/><color rgb="FF638EC6"/></dataBar></cfRule>
+</conditionalFormatting>
+</worksheet>"#;
+        let red = ConditionalStyle { font_color: Some([0x9C, 0, 0x06, 255]), ..Default::default() };
+        let (formats, skipped) = parse_worksheet_conditional_formats(xml, std::slice::from_ref(&red));
+        assert_eq!(skipped, 1, "data bar is unsupported");
+        assert_eq!(formats.len(), 3);
+
+        // Priority 1 (the color scale) sorts first
+        let ConditionalRule::ColorScale { stops } = &formats[0].rule else { panic!("expected color scale") };
+        assert_eq!((formats[0].start, formats[0].end), ((1, 2), (9, 2)));
+        assert_eq!(stops.len(), 3);
+        assert_eq!(stops[1].kind, StopKind::Percentile);
+        assert_eq!(stops[1].value.as_deref(), Some("50"));
+        assert_eq!(stops[2].color, [0x63, 0xBE, 0x7B, 255]);
+
+        let expected = ConditionalRule::Compare {
+            operator: CompareOperator::LessThan,
+            values: vec!["0".to_string()],
+            style: red,
+        };
+        assert_eq!(formats[1], ConditionalFormat::new(1, 1, 9, 1, expected.clone()));
+        assert_eq!(formats[2], ConditionalFormat::new(3, 3, 3, 3, expected));
+    }
+
+    #[test]
+    fn test_between_needs_two_operands() {
+        let xml = r#"<worksheet><conditionalFormatting sqref="A1:A3">
+<cfRule type="cellIs" priority="1" operator="between"><formula>1</formula></cfRule>
+</conditionalFormatting></worksheet>"#;
+        let (formats, skipped) = parse_worksheet_conditional_formats(xml, &[]);
+        assert!(formats.is_empty());
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_unbounded_sqref_counts_as_skipped() {
+        let xml = r#"<worksheet><conditionalFormatting sqref="A:A B2:B4">
+<cfRule type="cellIs" priority="1" operator="lessThan"><formula>0</formula></cfRule>
+</conditionalFormatting></worksheet>"#;
+        let (formats, skipped) = parse_worksheet_conditional_formats(xml, &[]);
+        assert_eq!(formats.len(), 1, "the bounded range still imports");
+        assert_eq!((formats[0].start, formats[0].end), ((1, 1), (3, 1)));
+        assert_eq!(skipped, 1);
+    }
+}
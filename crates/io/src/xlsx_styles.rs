@@ -12,6 +12,7 @@ use visigrid_engine::cell::{
     Alignment, BorderStyle, CellBorder, CellFormat, NumberFormat, TextOverflow,
     VerticalAlignment,
 };
+use visigrid_engine::conditional_format::ConditionalStyle;
 use zip::ZipArchive;
 
 // =============================================================================
@@ -264,7 +265,7 @@ fn parse_color_attrs(
 }
 
 /// Parse AARRGGBB hex string to RGBA [u8; 4].
-fn parse_argb_hex(hex: &[u8]) -> Option<[u8; 4]> {
+pub(crate) fn parse_argb_hex(hex: &[u8]) -> Option<[u8; 4]> {
     let s = std::str::from_utf8(hex).ok()?;
     let s = s.trim_start_matches('#');
 
@@ -547,6 +548,74 @@ fn parse_fills(xml: &str, unsupported: &mut Vec<String>) -> Vec<ParsedFill> {
     fills
 }
 
+/// Parse <dxfs> (the differential formats used by conditional formatting)
+/// into one ConditionalStyle per <dxf>, in index order. Only font color,
+/// bold/italic, and solid fill color are kept.
+pub fn parse_dxfs(xml: &str) -> Vec<ConditionalStyle> {
+    let mut dxfs = Vec::new();
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut unsupported = Vec::new();
+    let mut in_dxfs = false;
+    let mut in_dxf = false;
+    let mut section: &[u8] = b"";
+    let mut current = ConditionalStyle::default();
+
+    loop {
+        buf.clear();
+        let (e, is_start) = match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => (e.into_owned(), true),
+            Ok(Event::Empty(e)) => (e.into_owned(), false),
+            Ok(Event::End(ref e)) => {
+                match e.name().as_ref() {
+                    b"dxf" if in_dxf => {
+                        dxfs.push(std::mem::take(&mut current));
+                        in_dxf = false;
+                    }
+                    b"dxfs" => break,
+                    b"font" | b"fill" => section = b"",
+                    _ => {}
+                }
+                continue;
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => continue,
+        };
+        match e.name().as_ref() {
+            b"dxfs" if is_start => in_dxfs = true,
+            b"dxf" if in_dxfs => {
+                current = ConditionalStyle::default();
+                if is_start {
+                    in_dxf = true;
+                } else {
+                    dxfs.push(ConditionalStyle::default());
+                }
+            }
+            b"font" if in_dxf && is_start => section = b"font",
+            b"fill" if in_dxf && is_start => section = b"fill",
+            b"b" | b"i" if section == b"font" => {
+                let on = !e.attributes().flatten()
+                    .any(|a| a.key.as_ref() == b"val" && matches!(a.value.as_ref(), b"0" | b"false"));
+                if e.name().as_ref() == b"b" { current.bold = on } else { current.italic = on }
+            }
+            b"color" if section == b"font" => {
+                current.font_color = parse_color_attrs(&collect_attrs(&e), &mut unsupported);
+            }
+            // Solid dxf fills carry their color in bgColor; fgColor is a fallback
+            b"bgColor" if section == b"fill" => {
+                current.background_color = parse_color_attrs(&collect_attrs(&e), &mut unsupported);
+            }
+            b"fgColor" if section == b"fill" && current.background_color.is_none() => {
+                current.background_color = parse_color_attrs(&collect_attrs(&e), &mut unsupported);
+            }
+            _ => {}
+        }
+    }
+
+    dxfs
+}
+
 /// Parse <borders> section into Vec<ParsedBorder>.
 fn parse_borders(xml: &str, unsupported: &mut Vec<String>) -> Vec<ParsedBorder> {
     let mut borders = Vec::new();
@@ -1485,6 +1554,26 @@ mod tests {
         assert_eq!(s2.border_right.style, BorderStyle::Thin);
     }
 
+    #[test]
+    fn test_parse_dxfs() {
+        let xml = r#"<styleSheet>
+<fonts count="1"><font><b/><color rgb="FF000000"/></font></fonts>
+<dxfs count="2">
+  <dxf><font><b/><color rgb="FF9C0006"/></font><fill><patternFill><bgColor rgb="FFFFC7CE"/></patternFill></fill></dxf>
+  <dxf><font><i val="0"/></font></dxf>
+</dxfs>
+</styleSheet>"#;
+        let dxfs = parse_dxfs(xml);
+        assert_eq!(dxfs.len(), 2);
+        assert_eq!(dxfs[0], ConditionalStyle {
+            font_color: Some([0x9C, 0x00, 0x06, 255]),
+            background_color: Some([0xFF, 0xC7, 0xCE, 255]),
+            bold: true,
+            italic: false,
+        });
+        assert_eq!(dxfs[1], ConditionalStyle::default());
+    }
+
     #[test]
     fn test_parse_custom_number_format() {
         let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
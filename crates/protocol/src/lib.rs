@@ -23,6 +23,8 @@
 //! let response: ServerMessage = serde_json::from_str(&line)?;
 //! ```
 
+use std::io::{self, BufRead, Read, Write};
+
 use serde::{Deserialize, Serialize};
 
 /// Current protocol version. Increment for breaking changes.
@@ -43,6 +45,8 @@ pub enum ClientMessage {
     Subscribe(SubscribeMessage),
     Unsubscribe(UnsubscribeMessage),
     Stats(StatsMessage),
+    SessionInfo(SessionInfoMessage),
+    Bye(ByeMessage),
 }
 
 /// Initial handshake from client.
@@ -54,6 +58,10 @@ pub struct HelloMessage {
     pub token: String,
     #[serde(default = "default_protocol_version")]
     pub protocol_version: u32,
+    /// Framing the client wants after `welcome`. It takes effect only if the
+    /// server advertises the matching capability; otherwise both sides stay on JSONL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub framing: Option<Framing>,
 }
 
 fn default_protocol_version() -> u32 {
@@ -69,6 +77,11 @@ pub struct ApplyOpsMessage {
     pub atomic: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expected_revision: Option<u64>,
+    /// Client-chosen key for safe retries: a repeat of a recently seen key
+    /// returns the original result without applying again. Reusing a key
+    /// with different ops is rejected with `idempotency_key_reused`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
 /// A single operation to apply.
@@ -118,6 +131,43 @@ pub enum Op {
         #[serde(skip_serializing_if = "Option::is_none")]
         underline: Option<bool>,
     },
+    SetComment {
+        #[serde(default)]
+        sheet: usize,
+        row: usize,
+        col: usize,
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        author: Option<String>,
+    },
+    SetConditionalFormat {
+        #[serde(default)]
+        sheet: usize,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+        rule: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        values: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        font_color: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        background_color: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bold: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        italic: Option<bool>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        colors: Vec<String>,
+    },
+    /// Insert `count` empty rows before row `at`.
+    InsertRows {
+        #[serde(default)]
+        sheet: usize,
+        at: usize,
+        count: usize,
+    },
 }
 
 /// Request to inspect cell/range/workbook state.
@@ -153,6 +203,11 @@ pub struct PingMessage {
 }
 
 /// Subscribe to event topics.
+///
+/// `cells` receives every `cells_changed` event. `cells:<sheet>:<range>`
+/// (0-based sheet index, A1 range, e.g. `cells:0:A1:D20`) receives only
+/// changes overlapping that range. A malformed `cells:` topic falls back
+/// to `cells`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubscribeMessage {
     pub id: String,
@@ -172,6 +227,19 @@ pub struct StatsMessage {
     pub id: String,
 }
 
+/// Re-query session identity and capabilities without reconnecting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfoMessage {
+    pub id: String,
+}
+
+/// Graceful disconnect. The server closes the connection without a reply
+/// and counts it as a clean close rather than a dropped connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ByeMessage {
+    pub id: String,
+}
+
 // =============================================================================
 // Server → Client Messages
 // =============================================================================
@@ -189,6 +257,7 @@ pub enum ServerMessage {
     Unsubscribed(UnsubscribedMessage),
     Event(EventMessage),
     StatsResult(StatsResultMessage),
+    SessionInfoResult(SessionInfoResultMessage),
 }
 
 /// Welcome response after successful hello.
@@ -198,7 +267,128 @@ pub struct WelcomeMessage {
     pub session_id: String,
     pub protocol_version: u32,
     pub revision: u64,
-    pub capabilities: Vec<String>,
+    pub capabilities: Capabilities,
+    /// Largest message (in bytes, excluding the newline) the server accepts.
+    /// Absent from older servers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_message_bytes: Option<u64>,
+}
+
+/// A feature the server may advertise in `welcome.capabilities`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    Apply,
+    Inspect,
+    Subscribe,
+    Stats,
+}
+
+impl Capability {
+    /// All known capabilities, in wire order.
+    pub const ALL: [Capability; 4] = [
+        Capability::Apply,
+        Capability::Inspect,
+        Capability::Subscribe,
+        Capability::Stats,
+    ];
+
+    /// Wire name of the capability.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Capability::Apply => "apply",
+            Capability::Inspect => "inspect",
+            Capability::Subscribe => "subscribe",
+            Capability::Stats => "stats",
+        }
+    }
+
+    /// Parse a wire name. `apply_ops` is accepted as an alias for `apply`.
+    pub fn parse(s: &str) -> Option<Capability> {
+        match s {
+            "apply" | "apply_ops" => Some(Capability::Apply),
+            "inspect" => Some(Capability::Inspect),
+            "subscribe" => Some(Capability::Subscribe),
+            "stats" => Some(Capability::Stats),
+            _ => None,
+        }
+    }
+}
+
+/// Server capabilities as typed flags.
+///
+/// On the wire this is a plain string array (`["apply", "inspect"]`). Known
+/// names map to flags; unknown names are kept in `other` so newer servers
+/// round-trip through older clients without loss.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<String>", into = "Vec<String>")]
+pub struct Capabilities {
+    pub apply: bool,
+    pub inspect: bool,
+    pub subscribe: bool,
+    pub stats: bool,
+    /// Capabilities this version of the protocol crate does not know about.
+    pub other: Vec<String>,
+}
+
+impl Capabilities {
+    /// Whether the server advertised `cap`.
+    pub fn supports(&self, cap: Capability) -> bool {
+        match cap {
+            Capability::Apply => self.apply,
+            Capability::Inspect => self.inspect,
+            Capability::Subscribe => self.subscribe,
+            Capability::Stats => self.stats,
+        }
+    }
+
+    /// Set or clear the flag for `cap`.
+    pub fn set(&mut self, cap: Capability, enabled: bool) {
+        match cap {
+            Capability::Apply => self.apply = enabled,
+            Capability::Inspect => self.inspect = enabled,
+            Capability::Subscribe => self.subscribe = enabled,
+            Capability::Stats => self.stats = enabled,
+        }
+    }
+
+    /// Wire names: known capabilities in `Capability::ALL` order, then `other`.
+    pub fn names(&self) -> Vec<String> {
+        Capability::ALL
+            .iter()
+            .filter(|cap| self.supports(**cap))
+            .map(|cap| cap.as_str().to_string())
+            .chain(self.other.iter().cloned())
+            .collect()
+    }
+}
+
+impl FromIterator<Capability> for Capabilities {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+        let mut caps = Capabilities::default();
+        for cap in iter {
+            caps.set(cap, true);
+        }
+        caps
+    }
+}
+
+impl From<Vec<String>> for Capabilities {
+    fn from(names: Vec<String>) -> Self {
+        let mut caps = Capabilities::default();
+        for name in names {
+            match Capability::parse(&name) {
+                Some(cap) => caps.set(cap, true),
+                None => caps.other.push(name),
+            }
+        }
+        caps
+    }
+}
+
+impl From<Capabilities> for Vec<String> {
+    fn from(caps: Capabilities) -> Self {
+        caps.names()
+    }
 }
 
 /// Result of apply_ops request.
@@ -216,13 +406,111 @@ pub struct ApplyOpsResultMessage {
 /// Error applying a specific operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpError {
-    pub code: String,
+    pub code: ProtocolErrorCode,
     pub message: String,
     pub op_index: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suggestion: Option<String>,
 }
 
+/// Machine-readable error code carried by `ErrorMessage` and `OpError`.
+///
+/// On the wire this is the plain snake_case string (`"writer_conflict"`).
+/// Codes this version of the crate does not know are kept in `Other` so
+/// newer servers round-trip through older clients without loss.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ProtocolErrorCode {
+    AuthFailed,
+    ProtocolMismatch,
+    RateLimited,
+    RevisionMismatch,
+    FormulaParseError,
+    InvalidReference,
+    ProtectedCell,
+    MessageTooLarge,
+    MalformedMessage,
+    ReadOnlyMode,
+    WriterConflict,
+    /// Interim reply: the request is still running on the server.
+    Timeout,
+    InternalError,
+    /// A code this version of the protocol crate does not know about.
+    Other(String),
+}
+
+impl ProtocolErrorCode {
+    /// All known codes, in the server's declaration order.
+    pub const ALL: [ProtocolErrorCode; 13] = [
+        ProtocolErrorCode::AuthFailed,
+        ProtocolErrorCode::ProtocolMismatch,
+        ProtocolErrorCode::RateLimited,
+        ProtocolErrorCode::RevisionMismatch,
+        ProtocolErrorCode::FormulaParseError,
+        ProtocolErrorCode::InvalidReference,
+        ProtocolErrorCode::ProtectedCell,
+        ProtocolErrorCode::MessageTooLarge,
+        ProtocolErrorCode::MalformedMessage,
+        ProtocolErrorCode::ReadOnlyMode,
+        ProtocolErrorCode::WriterConflict,
+        ProtocolErrorCode::Timeout,
+        ProtocolErrorCode::InternalError,
+    ];
+
+    /// Wire name of the code.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ProtocolErrorCode::AuthFailed => "auth_failed",
+            ProtocolErrorCode::ProtocolMismatch => "protocol_mismatch",
+            ProtocolErrorCode::RateLimited => "rate_limited",
+            ProtocolErrorCode::RevisionMismatch => "revision_mismatch",
+            ProtocolErrorCode::FormulaParseError => "formula_parse_error",
+            ProtocolErrorCode::InvalidReference => "invalid_reference",
+            ProtocolErrorCode::ProtectedCell => "protected_cell",
+            ProtocolErrorCode::MessageTooLarge => "message_too_large",
+            ProtocolErrorCode::MalformedMessage => "malformed_message",
+            ProtocolErrorCode::ReadOnlyMode => "read_only_mode",
+            ProtocolErrorCode::WriterConflict => "writer_conflict",
+            ProtocolErrorCode::Timeout => "timeout",
+            ProtocolErrorCode::InternalError => "internal_error",
+            ProtocolErrorCode::Other(code) => code,
+        }
+    }
+}
+
+impl std::str::FromStr for ProtocolErrorCode {
+    type Err = std::convert::Infallible;
+
+    /// Parse a wire name. Never fails: unknown names become `Other`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(ProtocolErrorCode::from(s.to_string()))
+    }
+}
+
+impl std::fmt::Display for ProtocolErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for ProtocolErrorCode {
+    fn from(s: String) -> Self {
+        ProtocolErrorCode::ALL
+            .into_iter()
+            .find(|code| code.as_str() == s)
+            .unwrap_or(ProtocolErrorCode::Other(s))
+    }
+}
+
+impl From<ProtocolErrorCode> for String {
+    fn from(code: ProtocolErrorCode) -> Self {
+        match code {
+            ProtocolErrorCode::Other(s) => s,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
 /// Result of inspect request.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InspectResultMessage {
@@ -264,10 +552,13 @@ pub struct PongMessage {
 }
 
 /// Error response.
+///
+/// `code: "timeout"` is an interim reply: the request with this `id` is still
+/// running on the server (not lost), and `retry_after_ms` says when to check again.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorMessage {
     pub id: String,
-    pub code: String,
+    pub code: ProtocolErrorCode,
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry_after_ms: Option<u64>,
@@ -304,18 +595,106 @@ pub enum EventPayload {
         dropped_count: u64,
         current_revision: u64,
     },
+    /// Rows/columns inserted or deleted, or a sheet added or removed.
+    /// Cached layout for `sheet` is stale.
+    SheetStructureChanged {
+        sheet: usize,
+        change: StructureChange,
+    },
+}
+
+/// A structural change to a sheet. Positions are 0-based.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StructureChange {
+    AddedSheet,
+    RemovedSheet,
+    InsertedRows { at: usize, count: usize },
+    DeletedRows { at: usize, count: usize },
+    InsertedCols { at: usize, count: usize },
+    DeletedCols { at: usize, count: usize },
 }
 
 /// A rectangular range of cells.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// # Coordinate System
+///
+/// **All bounds are INCLUSIVE.** This differs from many APIs that use
+/// end-exclusive ranges.
+///
+/// A range `{r1: 0, c1: 0, r2: 2, c2: 3}` covers:
+/// - Rows 0, 1, 2 (3 rows total)
+/// - Columns 0, 1, 2, 3 (4 columns total)
+/// - Total: 12 cells
+///
+/// A single cell at (5, 3) is represented as `{r1: 5, c1: 3, r2: 5, c2: 3}`.
+///
+/// Invariants: `r1 <= r2` and `c1 <= c2` (enforced by constructor).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CellRange {
+    /// Sheet index (0-based).
     pub sheet: usize,
+    /// First row, INCLUSIVE (0-based).
     pub r1: usize,
+    /// First column, INCLUSIVE (0-based).
     pub c1: usize,
+    /// Last row, INCLUSIVE (0-based). Must be >= r1.
     pub r2: usize,
+    /// Last column, INCLUSIVE (0-based). Must be >= c1.
     pub c2: usize,
 }
 
+impl CellRange {
+    /// Create a range covering a single cell.
+    pub fn single(sheet: usize, row: usize, col: usize) -> Self {
+        Self { sheet, r1: row, c1: col, r2: row, c2: col }
+    }
+
+    /// Create a range from bounds.
+    pub fn new(sheet: usize, r1: usize, c1: usize, r2: usize, c2: usize) -> Self {
+        debug_assert!(r1 <= r2 && c1 <= c2, "Invalid range bounds");
+        Self { sheet, r1, c1, r2, c2 }
+    }
+
+    /// Number of cells covered by this range.
+    pub fn cell_count(&self) -> usize {
+        (self.r2 - self.r1 + 1) * (self.c2 - self.c1 + 1)
+    }
+
+    /// Parse a `cells:<sheet>:<range>` subscription topic, e.g. `cells:0:A1:D20`.
+    pub fn from_topic(topic: &str) -> Option<Self> {
+        let (sheet, range) = topic.strip_prefix("cells:")?.split_once(':')?;
+        let sheet = sheet.parse().ok()?;
+        let (start, end) = range.split_once(':').unwrap_or((range, range));
+        let (r1, c1) = parse_a1(start)?;
+        let (r2, c2) = parse_a1(end)?;
+        Some(Self::new(sheet, r1.min(r2), c1.min(c2), r1.max(r2), c1.max(c2)))
+    }
+
+    /// True if the two ranges share at least one cell.
+    pub fn overlaps(&self, other: &CellRange) -> bool {
+        self.sheet == other.sheet
+            && self.r1 <= other.r2
+            && other.r1 <= self.r2
+            && self.c1 <= other.c2
+            && other.c1 <= self.c2
+    }
+}
+
+/// Parse an A1 cell reference into 0-based (row, col).
+pub fn parse_a1(s: &str) -> Option<(usize, usize)> {
+    let split = s.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = s.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let col = letters.chars().try_fold(0usize, |acc, c| {
+        acc.checked_mul(26)?.checked_add((c.to_ascii_uppercase() as u8 - b'A') as usize + 1)
+    })?;
+    let row: usize = digits.parse().ok()?;
+    Some((row.checked_sub(1)?, col - 1))
+}
+
 /// Server statistics result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatsResultMessage {
@@ -326,6 +705,146 @@ pub struct StatsResultMessage {
     pub connections_refused_limit: u64,
     pub dropped_events_total: u64,
     pub active_connections: u64,
+    /// Seconds since the server started (0 from older servers)
+    #[serde(default)]
+    pub uptime_seconds: u64,
+    /// Revisions committed through the session since start (0 from older servers)
+    #[serde(default)]
+    pub revision_count: u64,
+    /// Connections closed with a `bye` (0 from older servers)
+    #[serde(default)]
+    pub connections_closed_graceful: u64,
+}
+
+/// Session info result: what `welcome` reported, as of now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfoResultMessage {
+    pub id: String,
+    pub session_id: String,
+    pub protocol_version: u32,
+    pub capabilities: Capabilities,
+    pub workbook_title: String,
+    pub revision: u64,
+}
+
+// =============================================================================
+// Framing
+// =============================================================================
+
+/// Capability name a server lists in `welcome.capabilities` once it honors
+/// `hello.framing = "length_prefixed"`.
+///
+/// Not a [`Capability`]: no VisiGrid server implements the framing yet, so
+/// the name lands in [`Capabilities::other`] and clients fall back to JSONL
+/// unless a server actually lists it.
+pub const LENGTH_PREFIXED: &str = "length_prefixed";
+
+/// How messages are delimited on the wire.
+///
+/// `hello` and `welcome` always travel as JSONL. If the hello asked for
+/// `LengthPrefixed` and the welcome lists [`LENGTH_PREFIXED`], every later
+/// message in both directions is a big-endian `u32` byte count followed by
+/// that many bytes of JSON, so payloads may contain raw newlines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    #[default]
+    Jsonl,
+    LengthPrefixed,
+}
+
+impl Framing {
+    /// The framing both sides use after `welcome`: what the client asked for,
+    /// if the server supports it, else JSONL.
+    pub fn negotiated(requested: Option<Framing>, capabilities: &Capabilities) -> Framing {
+        match requested {
+            Some(Framing::LengthPrefixed) if capabilities.other.iter().any(|c| c == LENGTH_PREFIXED) => {
+                Framing::LengthPrefixed
+            }
+            _ => Framing::Jsonl,
+        }
+    }
+}
+
+/// Write one message. JSONL rejects a payload containing a newline, since
+/// the reader would split it in two.
+pub fn write_frame<W: Write>(writer: &mut W, framing: Framing, payload: &[u8]) -> io::Result<()> {
+    match framing {
+        Framing::Jsonl => {
+            if payload.contains(&b'\n') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "payload contains a newline; use length-prefixed framing",
+                ));
+            }
+            writer.write_all(payload)?;
+            writer.write_all(b"\n")?;
+        }
+        Framing::LengthPrefixed => {
+            let len = u32::try_from(payload.len()).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, "payload exceeds u32::MAX bytes")
+            })?;
+            writer.write_all(&len.to_be_bytes())?;
+            writer.write_all(payload)?;
+        }
+    }
+    writer.flush()
+}
+
+/// Read one message of at most `max_len` bytes (excluding the newline or
+/// length prefix). Returns `Ok(None)` on a clean close between messages;
+/// a close mid-message is `UnexpectedEof`, an oversize message `InvalidData`.
+pub fn read_frame<R: BufRead>(reader: &mut R, framing: Framing, max_len: usize) -> io::Result<Option<Vec<u8>>> {
+    match framing {
+        Framing::Jsonl => {
+            let mut buf = Vec::new();
+            reader.by_ref().take(max_len as u64 + 1).read_until(b'\n', &mut buf)?;
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            if buf.last() != Some(&b'\n') {
+                return Err(if buf.len() > max_len {
+                    frame_too_large(max_len)
+                } else {
+                    io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed mid-frame (no newline)")
+                });
+            }
+            buf.pop();
+            if buf.last() == Some(&b'\r') {
+                buf.pop();
+            }
+            Ok(Some(buf))
+        }
+        Framing::LengthPrefixed => {
+            let mut prefix = [0u8; 4];
+            let mut filled = 0;
+            while filled < prefix.len() {
+                match reader.read(&mut prefix[filled..]) {
+                    Ok(0) if filled == 0 => return Ok(None),
+                    Ok(0) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "connection closed mid-frame (partial length prefix)",
+                        ))
+                    }
+                    Ok(n) => filled += n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            let len = u32::from_be_bytes(prefix) as usize;
+            if len > max_len {
+                return Err(frame_too_large(max_len));
+            }
+            let mut buf = vec![0u8; len];
+            reader.read_exact(&mut buf)?;
+            Ok(Some(buf))
+        }
+    }
+}
+
+fn frame_too_large(max_len: usize) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("message exceeds {} byte limit", max_len))
 }
 
 // =============================================================================
@@ -4,27 +4,31 @@ use chrono::NaiveDate;
 
 use crate::model::{Aggregate, AggregateKey, ReconRow};
 
+/// Running (total_cents, earliest date, count, record_ids, source_indices).
+type Group = (i64, NaiveDate, usize, Vec<String>, Vec<usize>);
+
 /// Group records by (match_key, currency), sum amounts, track earliest date.
 pub fn aggregate_records(role: &str, rows: &[ReconRow]) -> Vec<Aggregate> {
-    let mut groups: BTreeMap<AggregateKey, (i64, NaiveDate, usize, Vec<String>)> = BTreeMap::new();
+    let mut groups: BTreeMap<AggregateKey, Group> = BTreeMap::new();
 
     for row in rows {
         let key = AggregateKey {
             match_key: row.match_key.clone(),
             currency: row.currency.clone(),
         };
-        let entry = groups.entry(key).or_insert_with(|| (0, row.date, 0, Vec::new()));
+        let entry = groups.entry(key).or_insert_with(|| (0, row.date, 0, Vec::new(), Vec::new()));
         entry.0 += row.amount_cents;
         if row.date < entry.1 {
             entry.1 = row.date;
         }
         entry.2 += 1;
         entry.3.push(row.record_id.clone());
+        entry.4.push(row.source_index);
     }
 
     groups
         .into_iter()
-        .map(|(key, (total_cents, date, count, ids))| Aggregate {
+        .map(|(key, (total_cents, date, count, ids, lines))| Aggregate {
             role: role.to_string(),
             match_key: key.match_key,
             currency: key.currency,
@@ -32,6 +36,7 @@ pub fn aggregate_records(role: &str, rows: &[ReconRow]) -> Vec<Aggregate> {
             total_cents,
             record_count: count,
             record_ids: ids,
+            source_indices: lines,
         })
         .collect()
 }
@@ -52,6 +57,7 @@ mod tests {
             currency: currency.into(),
             kind: "payment".into(),
             raw_fields: HashMap::new(),
+            source_index: 0,
         }
     }
 
@@ -0,0 +1,98 @@
+//! Reconciliation certificates.
+//!
+//! A certificate records that a specific reconciliation ran over specific
+//! inputs: BLAKE3 hashes of each input, the settings used, summary counts,
+//! the outcome, when it ran, and which tool produced it. It is the payload of
+//! a signed envelope (`schema`, `payload`, `payload_blake3`, `signature`,
+//! `public_key`, `key_id`), the same layout as other vgrid proofs.
+//!
+//! Signing and verification stay with the caller, which holds the keys and
+//! the envelope format; this module only describes what is attested.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Schema identifier written to every certificate.
+pub const CERTIFICATE_SCHEMA: &str = "vgrid.recon_certificate.v1";
+
+/// The tool that ran the reconciliation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateSigner {
+    pub name: String,
+    pub version: String,
+}
+
+/// One input file, identified by content hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateInput {
+    /// Role of the input in the reconciliation (e.g. "truth", "warehouse")
+    pub role: String,
+    pub path: String,
+    pub blake3: String,
+    pub size_bytes: u64,
+}
+
+impl CertificateInput {
+    /// Describe an input from its raw bytes.
+    pub fn from_bytes(role: impl Into<String>, path: impl Into<String>, bytes: &[u8]) -> Self {
+        Self {
+            role: role.into(),
+            path: path.into(),
+            blake3: blake3::hash(bytes).to_hex().to_string(),
+            size_bytes: bytes.len() as u64,
+        }
+    }
+}
+
+/// What the certificate attests to. This is the signed payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconCertificate {
+    /// Which reconciliation produced it (e.g. "verify_totals", "recon")
+    pub kind: String,
+    pub signer: CertificateSigner,
+    /// RFC 3339 timestamp of the run
+    pub issued_at: String,
+    pub inputs: Vec<CertificateInput>,
+    /// Settings that affect the outcome (tolerances, flags, config hash)
+    pub config: BTreeMap<String, serde_json::Value>,
+    /// Summary counts (matched, mismatched, ...)
+    pub summary: BTreeMap<String, u64>,
+    /// "pass" or "fail"
+    pub status: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn certificate() -> ReconCertificate {
+        ReconCertificate {
+            kind: "verify_totals".into(),
+            signer: CertificateSigner { name: "vgrid".into(), version: "test".into() },
+            issued_at: "2026-01-01T00:00:00Z".into(),
+            inputs: vec![
+                CertificateInput::from_bytes("truth", "truth.csv", b"date,net\n2026-01-01,10\n"),
+                CertificateInput::from_bytes("warehouse", "warehouse.csv", b"date,net\n2026-01-01,10\n"),
+            ],
+            config: BTreeMap::from([("tolerance_micro".to_string(), serde_json::json!(0))]),
+            summary: BTreeMap::from([("matched".to_string(), 1), ("mismatched".to_string(), 0)]),
+            status: "pass".into(),
+        }
+    }
+
+    #[test]
+    fn inputs_are_identified_by_content_hash() {
+        let input = CertificateInput::from_bytes("truth", "truth.csv", b"abc");
+        assert_eq!(input.blake3, blake3::hash(b"abc").to_hex().to_string());
+        assert_eq!(input.size_bytes, 3);
+    }
+
+    #[test]
+    fn certificate_round_trips_through_json() {
+        let json = serde_json::to_value(certificate()).unwrap();
+        assert_eq!(json["summary"]["matched"], 1);
+        let reread: ReconCertificate = serde_json::from_value(json).unwrap();
+        assert_eq!(reread, certificate());
+    }
+}
@@ -146,6 +146,7 @@ mod tests {
                     total_cents: 10000,
                     record_count: 3,
                     record_ids: vec!["a".into(), "b".into(), "c".into()],
+                    source_indices: vec![0, 0, 0],
                 }),
             ]),
             deltas: Deltas { delta_cents: Some(0), date_offset_days: None },
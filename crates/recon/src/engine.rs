@@ -210,6 +210,36 @@ fn identify_three_way_roles<'a>(
     Ok((pl_pair, pb_pair, processor_role, ledger_role, bank_role))
 }
 
+/// Parse a date in the formats exports commonly use.
+///
+/// Accepts ISO `2026-01-15` (with or without a time part), `2026/01/15`,
+/// compact `20260115`, US `01/15/2026`, and month-name forms such as
+/// `15-Jan-2026`, `15 Jan 2026`, and `Jan 15, 2026`. Slash dates with the
+/// year last are read month-first.
+pub fn parse_date(value: &str) -> Option<chrono::NaiveDate> {
+    use chrono::NaiveDate;
+
+    let value = value.trim();
+    // Drop a trailing time from ISO timestamps (2026-01-15T09:30:00Z, 2026-01-15 09:30)
+    let date_part = match value.get(..10) {
+        Some(head) if value.len() > 10 && matches!(value.as_bytes()[10], b'T' | b' ') => head,
+        _ => value,
+    };
+    const FORMATS: &[&str] = &[
+        "%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%d-%b-%Y", "%d %b %Y", "%b %d, %Y", "%B %d, %Y",
+    ];
+    FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(date_part, fmt).ok())
+        .or_else(|| {
+            if date_part.len() == 8 && date_part.bytes().all(|b| b.is_ascii_digit()) {
+                NaiveDate::parse_from_str(date_part, "%Y%m%d").ok()
+            } else {
+                None
+            }
+        })
+}
+
 /// Load CSV rows into ReconRows, applying column mapping, filter, and transform.
 pub fn load_csv_rows(
     role_name: &str,
@@ -276,13 +306,14 @@ pub fn load_csv_rows(
             }
         }
 
+        let source_index = record.position().map_or(0, |p| p.line() as usize);
         let record_id = record.get(record_id_idx).unwrap_or("").to_string();
         let match_key = record.get(match_key_idx).unwrap_or("").to_string();
         let currency = record.get(currency_idx).unwrap_or("").to_string();
         let kind = record.get(kind_idx).unwrap_or("").to_string();
 
         let date_str = record.get(date_idx).unwrap_or("");
-        let date = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|_| {
+        let date = parse_date(date_str).ok_or_else(|| {
             ReconError::DateParse {
                 role: role_name.into(),
                 record_id: record_id.clone(),
@@ -332,6 +363,7 @@ pub fn load_csv_rows(
             currency,
             kind,
             raw_fields,
+            source_index,
         });
     }
 
@@ -373,6 +405,46 @@ txn_3,po_1,-7210,2026-01-17,USD,payout
         assert_eq!(rows[2].amount_cents, -7210);
     }
 
+    #[test]
+    fn load_csv_tracks_source_lines() {
+        // Header is line 1; a filtered-out row and a multi-line quoted field
+        // must not shift the reported lines of the rows after them.
+        let csv = "\
+source_id,group_id,amount_minor,effective_date,currency,type
+txn_1,po_1,10000,2026-01-15,USD,charge
+txn_2,po_1,-290,2026-01-15,USD,fee
+\"txn
+3\",po_1,-7210,2026-01-17,USD,charge
+txn_4,po_2,500,2026-01-18,USD,charge
+";
+        let role_config = crate::config::RoleConfig {
+            kind: crate::config::RoleKind::Processor,
+            file: "stripe.csv".into(),
+            columns: crate::config::ColumnMapping {
+                record_id: "source_id".into(),
+                match_key: "group_id".into(),
+                amount: "amount_minor".into(),
+                date: "effective_date".into(),
+                currency: "currency".into(),
+                kind: "type".into(),
+            },
+            filter: Some(crate::config::RowFilter {
+                column: "type".into(),
+                values: vec!["charge".into()],
+            }),
+            transform: None,
+        };
+
+        let rows = load_csv_rows("processor", csv, &role_config).unwrap();
+        let lines: Vec<usize> = rows.iter().map(|r| r.source_index).collect();
+        assert_eq!(lines, vec![2, 4, 6]);
+
+        let aggs = crate::aggregate::aggregate_records("processor", &rows);
+        assert_eq!(aggs[0].match_key, "po_1");
+        assert_eq!(aggs[0].source_indices, vec![2, 4]);
+        assert_eq!(aggs[1].source_indices, vec![6]);
+    }
+
     #[test]
     fn load_csv_with_filter() {
         let csv = "\
@@ -438,6 +510,102 @@ txn_2,po_1,10000,2026-01-15,USD,charge
         assert_eq!(rows[1].amount_cents, 10000);
     }
 
+    #[test]
+    fn parse_date_common_formats() {
+        let jan15 = chrono::NaiveDate::from_ymd_opt(2026, 1, 15);
+        for value in [
+            "2026-01-15", "2026-01-15T09:30:00Z", "2026-01-15 09:30", "2026/01/15", "20260115",
+            "01/15/2026", "1/15/2026", "15-Jan-2026", "15 Jan 2026", "Jan 15, 2026", "January 15, 2026",
+            " 2026-01-15 ",
+        ] {
+            assert_eq!(parse_date(value), jan15, "{value}");
+        }
+        for value in ["", "15/01/2026", "2026-13-01", "Q1 2026", "2026011"] {
+            assert_eq!(parse_date(value), None, "{value}");
+        }
+    }
+
+    /// Invoices (US dates) settle a few days later (ISO dates) in the bank.
+    fn date_window_recon(window_days: u32) -> ReconResult {
+        let invoices_csv = "\
+id,amount,date,currency,type
+inv_1,5000,01/15/2026,USD,invoice
+inv_2,7500,01/20/2026,USD,invoice
+";
+        let bank_csv = "\
+id,ref,amount,date,currency,type
+b_1,inv_1,5000,2026-01-18,USD,deposit
+b_2,inv_2,7500,2026-01-27T00:00:00Z,USD,deposit
+";
+        let config_toml = format!(
+            r#"
+name = "Date window"
+way = 2
+
+[roles.ledger]
+kind = "ledger"
+file = "invoices.csv"
+[roles.ledger.columns]
+record_id = "id"
+match_key = "id"
+amount    = "amount"
+date      = "date"
+currency  = "currency"
+kind      = "type"
+
+[roles.bank]
+kind = "bank"
+file = "bank.csv"
+[roles.bank.columns]
+record_id = "id"
+match_key = "ref"
+amount    = "amount"
+date      = "date"
+currency  = "currency"
+kind      = "type"
+
+[pairs.ledger_bank]
+left = "ledger"
+right = "bank"
+strategy = "exact_key"
+
+[tolerance]
+date_window_days = {window_days}
+"#
+        );
+        let config = crate::config::ReconConfig::from_toml(&config_toml).unwrap();
+        let input = ReconInput {
+            records: HashMap::from([
+                ("ledger".into(), load_csv_rows("ledger", invoices_csv, &config.roles["ledger"]).unwrap()),
+                ("bank".into(), load_csv_rows("bank", bank_csv, &config.roles["bank"]).unwrap()),
+            ]),
+        };
+        run(&config, &input).unwrap()
+    }
+
+    fn group<'a>(result: &'a ReconResult, key: &str) -> &'a crate::model::ClassifiedResult {
+        result.groups.iter().find(|g| g.match_key == key).unwrap()
+    }
+
+    #[test]
+    fn date_window_matches_shifted_dates() {
+        let result = date_window_recon(7);
+        assert_eq!(result.summary.matched, 2);
+        assert_eq!(group(&result, "inv_1").deltas.date_offset_days, Some(-3));
+        assert_eq!(group(&result, "inv_2").deltas.date_offset_days, Some(-7));
+    }
+
+    #[test]
+    fn date_window_exceeded_is_timing_mismatch() {
+        let result = date_window_recon(3);
+        assert_eq!(result.summary.matched, 1);
+        assert_eq!(result.summary.timing_mismatches, 1);
+        let late = group(&result, "inv_2");
+        assert_eq!(late.bucket, crate::model::ReconBucket::TimingMismatch);
+        assert_eq!(late.deltas.date_offset_days, Some(-7));
+        assert_eq!(late.deltas.delta_cents, Some(0));
+    }
+
     #[test]
     fn integration_two_way() {
         let stripe_csv = "\
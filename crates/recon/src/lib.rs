@@ -4,6 +4,7 @@
 //! No CLI or IO dependencies.
 
 pub mod aggregate;
+pub mod certificate;
 pub mod classify;
 pub mod config;
 pub mod derived;
@@ -15,6 +16,7 @@ pub mod model;
 pub mod settlement;
 pub mod windowed_nm;
 
+pub use certificate::ReconCertificate;
 pub use config::{CompositeConfig, ReconConfig};
 pub use engine::run;
 pub use error::ReconError;
@@ -154,6 +154,7 @@ mod tests {
             total_cents: cents,
             record_count: 1,
             record_ids: vec![format!("{key}_1")],
+            source_indices: vec![0],
         }
     }
 
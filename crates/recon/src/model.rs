@@ -18,6 +18,9 @@ pub struct ReconRow {
     pub currency: String,
     pub kind: String,
     pub raw_fields: HashMap<String, String>,
+    /// 1-based line in the source CSV where this record starts (the header
+    /// is line 1, so the first data row is line 2). 0 when not loaded from a file.
+    pub source_index: usize,
 }
 
 /// Pre-loaded records grouped by role name.
@@ -46,6 +49,8 @@ pub struct Aggregate {
     pub total_cents: i64,
     pub record_count: usize,
     pub record_ids: Vec<String>,
+    /// Source line of each record, parallel to `record_ids`.
+    pub source_indices: Vec<usize>,
 }
 
 // ---------------------------------------------------------------------------
@@ -141,6 +141,7 @@ mod tests {
             total_cents: 1000,
             record_count: 1,
             record_ids: vec!["id_1".into()],
+            source_indices: vec![0],
         }
     }
 
@@ -1149,6 +1149,7 @@ fn row_to_aggregate(row: &ReconRow, counter: &mut usize) -> Aggregate {
         total_cents: row.amount_cents,
         record_count: 1,
         record_ids: vec![row.record_id.clone()],
+        source_indices: vec![row.source_index],
     }
 }
 
@@ -1159,6 +1160,7 @@ fn rows_to_aggregate(rows: &[&ReconRow], counter: &mut usize) -> Aggregate {
     let date = rows.iter().map(|r| r.date).min().unwrap_or(NaiveDate::MIN);
     let role = rows.first().map(|r| r.role.as_str()).unwrap_or("unknown");
     let record_ids: Vec<String> = rows.iter().map(|r| r.record_id.clone()).collect();
+    let source_indices: Vec<usize> = rows.iter().map(|r| r.source_index).collect();
     Aggregate {
         role: role.to_string(),
         match_key: id,
@@ -1167,6 +1169,7 @@ fn rows_to_aggregate(rows: &[&ReconRow], counter: &mut usize) -> Aggregate {
         total_cents: total,
         record_count: rows.len(),
         record_ids,
+        source_indices,
     }
 }
 
@@ -1212,6 +1215,7 @@ mod tests {
             currency: currency.into(),
             kind: "payment".into(),
             raw_fields: HashMap::new(),
+            source_index: 0,
         }
     }
 
@@ -1614,6 +1618,7 @@ mod tests {
                 currency: currency.into(),
                 kind: "payment".into(),
                 raw_fields,
+                source_index: 0,
             }
         }
 
@@ -545,24 +545,24 @@ fn adversarial_tolerance_exact_boundary() {
         ReconRow {
             role: "proc".into(), record_id: "p1".into(), match_key: "k1".into(),
             date: d, amount_cents: 10000, currency: "USD".into(),
-            kind: "payout".into(), raw_fields: HashMap::new(),
+            kind: "payout".into(), raw_fields: HashMap::new(), source_index: 0,
         },
         ReconRow {
             role: "proc".into(), record_id: "p2".into(), match_key: "k2".into(),
             date: d, amount_cents: 10000, currency: "USD".into(),
-            kind: "payout".into(), raw_fields: HashMap::new(),
+            kind: "payout".into(), raw_fields: HashMap::new(), source_index: 0,
         },
     ];
     let ledger_rows = vec![
         ReconRow {
             role: "ledger".into(), record_id: "l1".into(), match_key: "k1".into(),
             date: d, amount_cents: 10001, currency: "USD".into(),
-            kind: "deposit".into(), raw_fields: HashMap::new(),
+            kind: "deposit".into(), raw_fields: HashMap::new(), source_index: 0,
         },
         ReconRow {
             role: "ledger".into(), record_id: "l2".into(), match_key: "k2".into(),
             date: d, amount_cents: 10002, currency: "USD".into(),
-            kind: "deposit".into(), raw_fields: HashMap::new(),
+            kind: "deposit".into(), raw_fields: HashMap::new(), source_index: 0,
         },
     ];
 
@@ -173,7 +173,7 @@ pub fn ask_ai(
     context: &AIContext,
 ) -> Result<AskResponse, AskError> {
     // Check provider is configured and implemented
-    let api_url = match config.provider.chat_completions_url() {
+    let api_url = match config.chat_completions_url() {
         Some(url) => url,
         None if config.provider == AIProvider::None => {
             return Err(AskError::NotConfigured("AI is disabled".to_string()));
@@ -186,15 +186,19 @@ pub fn ask_ai(
         }
     };
 
-    // Check API key
-    let api_key = config.api_key.as_ref().ok_or(AskError::MissingKey)?;
+    // Check API key (Local endpoints may run without one)
+    let api_key = match &config.api_key {
+        Some(key) => Some(key.as_str()),
+        None if config.provider.needs_api_key() => return Err(AskError::MissingKey),
+        None => None,
+    };
 
     // Build prompt
     let system_prompt = build_system_prompt();
     let user_prompt = build_user_prompt(question, context);
 
     // Call OpenAI-compatible API
-    call_openai(api_key, &config.model, &system_prompt, &user_prompt, api_url)
+    call_openai(api_key, &config.model, &system_prompt, &user_prompt, &api_url)
 }
 
 fn build_system_prompt() -> String {
@@ -240,7 +244,7 @@ fn build_user_prompt(question: &str, context: &AIContext) -> String {
 }
 
 fn call_openai(
-    api_key: &str,
+    api_key: Option<&str>,
     model: &str,
     system_prompt: &str,
     user_prompt: &str,
@@ -270,9 +274,11 @@ fn call_openai(
         }),
     };
 
-    let response = client
-        .post(api_url)
-        .header("Authorization", format!("Bearer {}", api_key))
+    let mut builder = client.post(api_url);
+    if let Some(key) = api_key {
+        builder = builder.header("Authorization", format!("Bearer {}", key));
+    }
+    let response = builder
         .header("Content-Type", "application/json")
         .json(&request)
         .send()
@@ -379,7 +385,7 @@ pub fn analyze(
     context: &AIContext,
 ) -> Result<AnalyzeResponse, AskError> {
     // Check provider is configured and implemented
-    let api_url = match config.provider.chat_completions_url() {
+    let api_url = match config.chat_completions_url() {
         Some(url) => url,
         None if config.provider == AIProvider::None => {
             return Err(AskError::NotConfigured("AI is disabled".to_string()));
@@ -392,15 +398,19 @@ pub fn analyze(
         }
     };
 
-    // Check API key
-    let api_key = config.api_key.as_ref().ok_or(AskError::MissingKey)?;
+    // Check API key (Local endpoints may run without one)
+    let api_key = match &config.api_key {
+        Some(key) => Some(key.as_str()),
+        None if config.provider.needs_api_key() => return Err(AskError::MissingKey),
+        None => None,
+    };
 
     // Build prompt
     let system_prompt = build_analyze_system_prompt();
     let user_prompt = build_analyze_user_prompt(question, context);
 
     // Call OpenAI-compatible API and parse as analyze response
-    call_openai_analyze(api_key, &config.model, &system_prompt, &user_prompt, api_url)
+    call_openai_analyze(api_key, &config.model, &system_prompt, &user_prompt, &api_url)
 }
 
 fn build_analyze_system_prompt() -> String {
@@ -441,7 +451,7 @@ fn build_analyze_user_prompt(question: &str, context: &AIContext) -> String {
 }
 
 fn call_openai_analyze(
-    api_key: &str,
+    api_key: Option<&str>,
     model: &str,
     system_prompt: &str,
     user_prompt: &str,
@@ -471,9 +481,11 @@ fn call_openai_analyze(
         }),
     };
 
-    let response = client
-        .post(api_url)
-        .header("Authorization", format!("Bearer {}", api_key))
+    let mut builder = client.post(api_url);
+    if let Some(key) = api_key {
+        builder = builder.header("Authorization", format!("Bearer {}", key));
+    }
+    let response = builder
         .header("Content-Type", "application/json")
         .json(&request)
         .send()
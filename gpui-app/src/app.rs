@@ -1475,7 +1475,7 @@ impl AIProviderOption {
     pub fn label(&self) -> &'static str {
         match self {
             AIProviderOption::None => "Disabled",
-            AIProviderOption::Local => "Local (Ollama)",
+            AIProviderOption::Local => "Local (OpenAI-compatible)",
             AIProviderOption::OpenAI => "OpenAI",
             AIProviderOption::Anthropic => "Anthropic",
             AIProviderOption::Gemini => "Google Gemini",
@@ -1537,7 +1537,7 @@ pub struct AISettingsDialogState {
     /// Model identifier (empty = use provider default)
     pub model: String,
 
-    /// Custom endpoint for Local provider (Ollama)
+    /// Custom endpoint for Local provider (OpenAI-compatible server)
     pub endpoint: String,
 
     /// Privacy mode: minimize data sent to AI
@@ -1596,7 +1596,7 @@ impl AISettingsDialogState {
 
     /// Save current state to config
     pub fn save_to_config(&self) -> Result<(), String> {
-        let mut settings = visigrid_config::settings::Settings::load();
+        let mut settings = visigrid_config::settings::Settings::load_file();
 
         settings.ai.provider = self.provider.to_config();
         settings.ai.model = self.model.clone();
@@ -3262,160 +3262,51 @@ impl Spreadsheet {
 
     /// Handle an apply_ops request from the session server.
     ///
-    /// Uses proper batching: all ops are applied within a single batch_guard,
-    /// ensuring exactly one recalc and one revision increment for the entire batch.
+    /// The batch is applied and broadcast by `apply_session_ops`; this records
+    /// undo history and keeps the view in step with inserted rows.
     fn handle_session_apply_ops(
         &mut self,
         req: &crate::session_server::ApplyOpsRequest,
         cx: &mut Context<Self>,
     ) -> crate::session_server::ApplyOpsResponse {
-        use crate::session_server::{ApplyOpsResponse, ApplyOpsError, Op};
-        use crate::history::CellChange;
-        use visigrid_engine::cell_id::CellId;
-
-        // Check expected_revision if provided
-        let current_rev = self.workbook.read(cx).revision();
-        if let Some(expected) = req.expected_revision {
-            if expected != current_rev {
-                return ApplyOpsResponse {
-                    applied: 0,
-                    total: req.ops.len(),
-                    current_revision: current_rev,
-                    error: Some(ApplyOpsError::RevisionMismatch {
-                        expected,
-                        actual: current_rev,
-                    }),
-                };
-            }
-        }
-
-        if req.ops.is_empty() {
-            return ApplyOpsResponse {
-                applied: 0,
-                total: 0,
-                current_revision: current_rev,
-                error: None,
-            };
-        }
-
-        // Apply all ops within a single batch_guard, collecting changes for history
-        let (applied, error, changes_by_sheet) = self.workbook.update(cx, |wb, _| {
-            let mut guard = wb.batch_guard();
-            let mut applied = 0;
-            let mut error: Option<crate::session_server::ApplyOpsError> = None;
-            // Group changes by sheet for history recording
-            let mut changes_by_sheet: std::collections::HashMap<usize, Vec<CellChange>> =
-                std::collections::HashMap::new();
-
-            for (_i, op) in req.ops.iter().enumerate() {
-                let sheet_count = guard.sheets().len();
-
-                match op {
-                    Op::SetCellValue { sheet, row, col, value } => {
-                        let sheet_idx = if *sheet < sheet_count { *sheet } else { guard.active_sheet_index() };
-
-                        // Capture old value for history
-                        let old_value = guard.sheets()[sheet_idx].get_raw(*row, *col);
-                        changes_by_sheet.entry(sheet_idx).or_default().push(CellChange {
-                            row: *row,
-                            col: *col,
-                            old_value,
-                            new_value: value.clone(),
-                        });
-
-                        // Apply the mutation via tracked method
-                        guard.set_cell_value_tracked(sheet_idx, *row, *col, value);
-                        applied += 1;
-                    }
-                    Op::SetCellFormula { sheet, row, col, formula } => {
-                        let sheet_idx = if *sheet < sheet_count { *sheet } else { guard.active_sheet_index() };
-
-                        let old_value = guard.sheets()[sheet_idx].get_raw(*row, *col);
-                        changes_by_sheet.entry(sheet_idx).or_default().push(CellChange {
-                            row: *row,
-                            col: *col,
-                            old_value,
-                            new_value: formula.clone(),
-                        });
-
-                        guard.set_cell_value_tracked(sheet_idx, *row, *col, formula);
-                        applied += 1;
-                    }
-                    Op::ClearCell { sheet, row, col } => {
-                        let sheet_idx = if *sheet < sheet_count { *sheet } else { guard.active_sheet_index() };
-
-                        let old_value = guard.sheets()[sheet_idx].get_raw(*row, *col);
-                        changes_by_sheet.entry(sheet_idx).or_default().push(CellChange {
-                            row: *row,
-                            col: *col,
-                            old_value,
-                            new_value: String::new(),
-                        });
-
-                        guard.clear_cell_tracked(sheet_idx, *row, *col);
-                        applied += 1;
-                    }
-                    Op::SetNumberFormat { .. } => {
-                        // TODO: Parse format string and apply to range
-                        applied += 1;
-                    }
-                    Op::SetStyle { .. } => {
-                        // TODO: Apply style changes to range
-                        applied += 1;
-                    }
-                }
-
-                // If atomic and there was an error, stop
-                if req.atomic && error.is_some() {
-                    break;
-                }
-            }
-
-            (applied, error, changes_by_sheet)
+        let session_server = &self.session_server;
+        let outcome = self.workbook.update(cx, |wb, _| {
+            crate::session_server::apply_session_ops(wb, session_server, req)
         });
-        // batch_guard dropped here → single recalc + revision increment
-
-        // Build changed cells list BEFORE history takes ownership of changes_by_sheet
-        let changed_cells: Vec<crate::session_server::CellRef> = if applied > 0 && error.is_none() {
-            changes_by_sheet
-                .iter()
-                .flat_map(|(sheet_idx, changes)| {
-                    changes.iter().map(move |c| crate::session_server::CellRef {
-                        sheet: *sheet_idx,
-                        row: c.row,
-                        col: c.col,
-                    })
-                })
-                .collect()
-        } else {
-            Vec::new()
-        };
+        if outcome.response.applied == 0 {
+            return outcome.response;
+        }
 
         // Record history entries for undo (one per sheet that had changes)
-        for (sheet_idx, changes) in changes_by_sheet {
+        for (sheet_idx, changes) in outcome.changes_by_sheet {
             if !changes.is_empty() {
                 self.history.record_batch(sheet_idx, changes);
             }
         }
 
+        // Inserted rows: same view bookkeeping and undo entry as Ctrl+=
+        let active_sheet = self.sheet_index(cx);
+        for (sheet_idx, change) in &outcome.structure {
+            if let crate::session_server::StructureChange::InsertedRows { at, count } = *change {
+                if *sheet_idx == active_sheet {
+                    self.shift_rows_for_insert(at, count);
+                }
+                self.history.record_named_range_action(crate::history::UndoAction::RowsInserted {
+                    sheet_index: *sheet_idx,
+                    at_row: at,
+                    count,
+                });
+            }
+        }
+        if !outcome.structure.is_empty() {
+            self.bump_cells_rev();
+        }
+
         // Mark document as modified
         self.is_modified = true;
         self.cached_title = None;
 
-        let new_rev = self.workbook.read(cx).revision();
-
-        // Broadcast cell changes to subscribed connections
-        // This happens in the same transaction boundary as revision increment
-        if !changed_cells.is_empty() {
-            self.session_server.broadcast_cells(new_rev, changed_cells);
-        }
-
-        ApplyOpsResponse {
-            applied,
-            total: req.ops.len(),
-            current_revision: new_rev,
-            error,
-        }
+        outcome.response
     }
 
     /// Handle an inspect request from the session server.
@@ -3543,6 +3434,21 @@ impl Spreadsheet {
         count
     }
 
+    /// Record a structural edit (rows/cols, sheets): bump the revision and
+    /// tell session subscribers, so viewers re-fetch the sheet's bounds.
+    pub(crate) fn note_structure_change(
+        &mut self,
+        sheet: usize,
+        change: crate::session_server::StructureChange,
+        cx: &mut Context<Self>,
+    ) {
+        let revision = self.workbook.update(cx, |wb, _| {
+            wb.note_structure_changed();
+            wb.revision()
+        });
+        self.session_server.broadcast_structure(revision, vec![(sheet, change)]);
+    }
+
     /// Get the active theme (preview if set, otherwise current)
     pub fn active_theme(&self) -> &Theme {
         self.theme_preview.as_ref().unwrap_or(&self.theme)
@@ -1504,7 +1504,7 @@ impl Spreadsheet {
                 cx.notify();
                 return;
             }
-            AIConfigStatus::Error => {
+            AIConfigStatus::Misconfigured | AIConfigStatus::Error => {
                 self.status_message = Some(config.blocking_reason.unwrap_or_else(|| "AI configuration error".to_string()));
                 cx.notify();
                 return;
@@ -1558,7 +1558,7 @@ impl Spreadsheet {
                 cx.notify();
                 return;
             }
-            AIConfigStatus::Error => {
+            AIConfigStatus::Misconfigured | AIConfigStatus::Error => {
                 self.status_message = Some(config.blocking_reason.unwrap_or_else(|| "AI configuration error".to_string()));
                 cx.notify();
                 return;
@@ -2426,10 +2426,14 @@ fn truncate_value(val: &str, max_len: usize) -> String {
 fn call_diff_summary_ai(config: &visigrid_config::ai::ResolvedAIConfig, prompt: &str) -> Result<String, String> {
     use visigrid_config::settings::AIProvider;
 
-    let api_url = config.provider.chat_completions_url()
+    let api_url = config.chat_completions_url()
         .ok_or_else(|| format!("{} not implemented for summaries", config.provider.name()))?;
 
-    let api_key = config.api_key.as_ref().ok_or("API key not configured")?;
+    let api_key = match &config.api_key {
+        Some(key) => Some(key.as_str()),
+        None if config.provider.needs_api_key() => return Err("API key not configured".to_string()),
+        None => None,
+    };
 
     // Build request (simpler than Ask AI - just want plain text response)
     let request = serde_json::json!({
@@ -2453,9 +2457,11 @@ fn call_diff_summary_ai(config: &visigrid_config::ai::ResolvedAIConfig, prompt:
         .build()
         .map_err(|e| e.to_string())?;
 
-    let response = client
-        .post(api_url)
-        .header("Authorization", format!("Bearer {}", api_key))
+    let mut builder = client.post(api_url);
+    if let Some(key) = api_key {
+        builder = builder.header("Authorization", format!("Bearer {}", key));
+    }
+    let response = builder
         .header("Content-Type", "application/json")
         .json(&request)
         .send()
@@ -2541,10 +2547,14 @@ fn call_entry_explanation_ai(
 ) -> Result<String, String> {
     use visigrid_config::settings::AIProvider;
 
-    let api_url = config.provider.chat_completions_url()
+    let api_url = config.chat_completions_url()
         .ok_or_else(|| format!("{} not implemented for explanations", config.provider.name()))?;
 
-    let api_key = config.api_key.as_ref().ok_or("API key not configured")?;
+    let api_key = match &config.api_key {
+        Some(key) => Some(key.as_str()),
+        None if config.provider.needs_api_key() => return Err("API key not configured".to_string()),
+        None => None,
+    };
 
     let request = serde_json::json!({
         "model": config.model,
@@ -2567,9 +2577,11 @@ fn call_entry_explanation_ai(
         .build()
         .map_err(|e| e.to_string())?;
 
-    let response = client
-        .post(api_url)
-        .header("Authorization", format!("Bearer {}", api_key))
+    let mut builder = client.post(api_url);
+    if let Some(key) = api_key {
+        builder = builder.header("Authorization", format!("Bearer {}", key));
+    }
+    let response = builder
         .header("Content-Type", "application/json")
         .json(&request)
         .send()
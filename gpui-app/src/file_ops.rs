@@ -42,6 +42,7 @@ impl Spreadsheet {
         self.view_state.selection_end = None;
         self.view_state.scroll_row = 0;
         self.view_state.scroll_col = 0;
+        self.load_frozen_panes(cx);
         self.history.clear();
         self.bump_cells_rev();  // Invalidate cell search cache
 
@@ -162,6 +163,7 @@ impl Spreadsheet {
                 self.view_state.selection_end = None;
                 self.view_state.scroll_row = 0;
                 self.view_state.scroll_col = 0;
+                self.load_frozen_panes(cx);
                 self.history.clear();
                 self.bump_cells_rev();
                 self.add_recent_file(path);
@@ -332,6 +334,7 @@ impl Spreadsheet {
                         this.view_state.selection_end = None;
                         this.view_state.scroll_row = 0;
                         this.view_state.scroll_col = 0;
+                        this.load_frozen_panes(cx);
                         this.history.clear();
                         this.bump_cells_rev();
                         this.add_recent_file(&path_for_recent);
@@ -437,10 +440,7 @@ impl Spreadsheet {
                     } else {
                         csv::import(&path_for_import)
                     }?;
-                    let mut workbook = Workbook::from_sheets(vec![sheet], 0);
-                    workbook.rebuild_dep_graph();
-                    workbook.recompute_full_ordered();
-                    Ok::<Workbook, String>(workbook)
+                    Ok::<Workbook, String>(Workbook::from_sheets(vec![sheet], 0))
                 })
                 .await;
 
@@ -470,6 +470,7 @@ impl Spreadsheet {
                         this.view_state.selection_end = None;
                         this.view_state.scroll_row = 0;
                         this.view_state.scroll_col = 0;
+                        this.load_frozen_panes(cx);
                         this.history.clear();
                         this.bump_cells_rev();
                         this.add_recent_file(&path_for_recent);
@@ -534,6 +535,7 @@ impl Spreadsheet {
                 self.view_state.selection_end = None;
                 self.view_state.scroll_row = 0;
                 self.view_state.scroll_col = 0;
+                self.load_frozen_panes(cx);
                 self.history.clear();
                 self.bump_cells_rev();
                 self.add_recent_file(path);
@@ -711,6 +713,7 @@ impl Spreadsheet {
                         this.view_state.selection_end = None;
                         this.view_state.scroll_row = 0;
                         this.view_state.scroll_col = 0;
+                        this.load_frozen_panes(cx);
                         this.history.clear();
                         this.bump_cells_rev();
                         this.add_recent_file(&path_for_recent);
@@ -809,6 +812,7 @@ impl Spreadsheet {
                 self.view_state.selection_end = None;
                 self.view_state.scroll_row = 0;
                 self.view_state.scroll_col = 0;
+                self.load_frozen_panes(cx);
                 self.history.clear();
                 self.bump_cells_rev();
                 self.add_recent_file(path);
@@ -1033,6 +1037,7 @@ impl Spreadsheet {
 
     fn save_to_path(&mut self, path: &PathBuf, cx: &mut Context<Self>) {
         let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("sheet");
+        self.store_frozen_panes(cx);
 
         // For .sheet files, use full save (workbook + scripts + run records)
         // For CSV, use sheet-level export (named ranges not supported in CSV)
@@ -1261,8 +1266,7 @@ impl Spreadsheet {
         .detach();
     }
 
-    /// Build ExportLayout for each sheet (column widths, row heights)
-    /// Note: Frozen panes will be added when that feature is implemented (see roadmap)
+    /// Build ExportLayout for each sheet (column widths, row heights, frozen panes)
     fn build_export_layouts(&self, cx: &App) -> Vec<xlsx::ExportLayout> {
         let mut layouts = Vec::new();
         let wb = self.wb(cx);
@@ -1289,9 +1293,14 @@ impl Spreadsheet {
                 }
             }
 
-            // Frozen panes: Not yet implemented in VisiGrid (see roadmap)
-            // layout.frozen_rows = ...;
-            // layout.frozen_cols = ...;
+            // Frozen panes: the view's for the active sheet, stored ones elsewhere
+            if sheet_idx == wb.active_sheet_index() {
+                layout.frozen_rows = self.view_state.frozen_rows;
+                layout.frozen_cols = self.view_state.frozen_cols;
+            } else if let Some(sheet) = wb.sheets().get(sheet_idx) {
+                layout.frozen_rows = sheet.frozen_rows;
+                layout.frozen_cols = sheet.frozen_cols;
+            }
 
             // AutoFilter state (only on active sheet — per-sheet filter persistence not yet implemented)
             if sheet_idx == wb.active_sheet_index() && self.filter_state.is_enabled() {
@@ -1363,6 +1372,22 @@ impl Spreadsheet {
         self.recent_files.truncate(MAX_RECENT);
     }
 
+    /// Adopt the active sheet's frozen panes (from xlsx import or a saved .sheet).
+    fn load_frozen_panes(&mut self, cx: &App) {
+        let sheet = self.wb(cx).active_sheet();
+        self.view_state.frozen_rows = sheet.frozen_rows;
+        self.view_state.frozen_cols = sheet.frozen_cols;
+    }
+
+    /// Record the view's frozen panes on the active sheet so saving keeps them.
+    fn store_frozen_panes(&mut self, cx: &mut Context<Self>) {
+        let (rows, cols) = (self.view_state.frozen_rows, self.view_state.frozen_cols);
+        self.active_sheet_mut(cx, |sheet| {
+            sheet.frozen_rows = rows;
+            sheet.frozen_cols = cols;
+        });
+    }
+
     /// Build a SheetLayout from the app's col_widths/row_heights (SheetId-keyed)
     /// mapped to sheet_idx for database storage.
     fn build_sheet_layout(&self, cx: &App) -> native::SheetLayout {
@@ -8,6 +8,10 @@
 
 use std::ops::Range;
 
+use visigrid_engine::formula::functions::{self, FunctionMeta};
+
+pub use visigrid_engine::formula::functions::FunctionCategory;
+
 // ============================================================================
 // Core Types
 // ============================================================================
@@ -104,11 +108,31 @@ pub enum TokenType {
 pub struct FunctionInfo {
     pub name: &'static str,
     pub signature: &'static str,
-    pub description: &'static str,
-    pub category: FunctionCategory,
     pub parameters: &'static [ParameterInfo],
 }
 
+impl FunctionInfo {
+    /// The engine's metadata for this function, the single source of its
+    /// description and category.
+    pub fn meta(&self) -> &'static FunctionMeta {
+        functions::function_meta(self.name)
+            .unwrap_or_else(|| panic!("{} is not an engine function", self.name))
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.meta().description
+    }
+
+    pub fn category(&self) -> FunctionCategory {
+        self.meta().category
+    }
+
+    /// Whether the function is volatile (NOW, RAND, ...), per the engine's list.
+    pub fn is_volatile(&self) -> bool {
+        functions::is_volatile(self.name)
+    }
+}
+
 /// Parameter information for signature help
 #[derive(Debug, Clone)]
 pub struct ParameterInfo {
@@ -118,20 +142,6 @@ pub struct ParameterInfo {
     pub repeatable: bool,
 }
 
-/// Function categories
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum FunctionCategory {
-    Math,
-    Logical,
-    Text,
-    Lookup,
-    DateTime,
-    Statistical,
-    Array,
-    Conditional,
-    Trigonometry,
-}
-
 // ============================================================================
 // Function Database (96 functions)
 // ============================================================================
@@ -142,8 +152,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "SUM",
         signature: "SUM(number1, [number2], ...)",
-        description: "Adds all the numbers in a range of cells.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range to add.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges to add.", optional: true, repeatable: true },
@@ -152,8 +160,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "AVERAGE",
         signature: "AVERAGE(number1, [number2], ...)",
-        description: "Returns the average of the arguments.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -162,8 +168,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "MIN",
         signature: "MIN(number1, [number2], ...)",
-        description: "Returns the smallest value in a set of values.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -172,8 +176,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "MAX",
         signature: "MAX(number1, [number2], ...)",
-        description: "Returns the largest value in a set of values.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -182,8 +184,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "COUNT",
         signature: "COUNT(value1, [value2], ...)",
-        description: "Counts the number of cells that contain numbers.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "value1", description: "The first value or range.", optional: false, repeatable: false },
             ParameterInfo { name: "value2", description: "Additional values or ranges.", optional: true, repeatable: true },
@@ -192,8 +192,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "COUNTA",
         signature: "COUNTA(value1, [value2], ...)",
-        description: "Counts the number of non-empty cells.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "value1", description: "The first value or range.", optional: false, repeatable: false },
             ParameterInfo { name: "value2", description: "Additional values or ranges.", optional: true, repeatable: true },
@@ -202,8 +200,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ABS",
         signature: "ABS(number)",
-        description: "Returns the absolute value of a number.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The number to get the absolute value of.", optional: false, repeatable: false },
         ],
@@ -211,8 +207,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ROUND",
         signature: "ROUND(number, num_digits)",
-        description: "Rounds a number to a specified number of digits.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The number to round.", optional: false, repeatable: false },
             ParameterInfo { name: "num_digits", description: "The number of digits to round to.", optional: false, repeatable: false },
@@ -221,8 +215,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "INT",
         signature: "INT(number)",
-        description: "Rounds a number down to the nearest integer.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The number to round down.", optional: false, repeatable: false },
         ],
@@ -230,8 +222,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "MOD",
         signature: "MOD(number, divisor)",
-        description: "Returns the remainder after division.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The number to divide.", optional: false, repeatable: false },
             ParameterInfo { name: "divisor", description: "The number to divide by.", optional: false, repeatable: false },
@@ -240,8 +230,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "POWER",
         signature: "POWER(number, power)",
-        description: "Returns the result of a number raised to a power.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The base number.", optional: false, repeatable: false },
             ParameterInfo { name: "power", description: "The exponent.", optional: false, repeatable: false },
@@ -250,8 +238,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "SQRT",
         signature: "SQRT(number)",
-        description: "Returns the square root of a number.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The number to get the square root of.", optional: false, repeatable: false },
         ],
@@ -259,8 +245,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "CEILING",
         signature: "CEILING(number, significance)",
-        description: "Rounds a number up to the nearest multiple of significance.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The number to round.", optional: false, repeatable: false },
             ParameterInfo { name: "significance", description: "The multiple to round to.", optional: false, repeatable: false },
@@ -269,8 +253,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "FLOOR",
         signature: "FLOOR(number, significance)",
-        description: "Rounds a number down to the nearest multiple of significance.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The number to round.", optional: false, repeatable: false },
             ParameterInfo { name: "significance", description: "The multiple to round to.", optional: false, repeatable: false },
@@ -279,8 +261,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "PRODUCT",
         signature: "PRODUCT(number1, [number2], ...)",
-        description: "Multiplies all the numbers given as arguments.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers.", optional: true, repeatable: true },
@@ -289,8 +269,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "MEDIAN",
         signature: "MEDIAN(number1, [number2], ...)",
-        description: "Returns the median of the given numbers.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -299,8 +277,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "LOG",
         signature: "LOG(number, [base])",
-        description: "Returns the logarithm of a number to a specified base.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The positive number.", optional: false, repeatable: false },
             ParameterInfo { name: "base", description: "The base (default 10).", optional: true, repeatable: false },
@@ -309,8 +285,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "LOG10",
         signature: "LOG10(number)",
-        description: "Returns the base-10 logarithm of a number.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The positive number.", optional: false, repeatable: false },
         ],
@@ -318,8 +292,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "LN",
         signature: "LN(number)",
-        description: "Returns the natural logarithm of a number.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The positive number.", optional: false, repeatable: false },
         ],
@@ -327,8 +299,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "EXP",
         signature: "EXP(number)",
-        description: "Returns e raised to the power of a number.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "number", description: "The exponent.", optional: false, repeatable: false },
         ],
@@ -336,15 +306,11 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "RAND",
         signature: "RAND()",
-        description: "Returns a random number between 0 and 1.",
-        category: FunctionCategory::Math,
         parameters: &[],
     },
     FunctionInfo {
         name: "RANDBETWEEN",
         signature: "RANDBETWEEN(bottom, top)",
-        description: "Returns a random integer between two numbers.",
-        category: FunctionCategory::Math,
         parameters: &[
             ParameterInfo { name: "bottom", description: "The smallest integer.", optional: false, repeatable: false },
             ParameterInfo { name: "top", description: "The largest integer.", optional: false, repeatable: false },
@@ -355,8 +321,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "IF",
         signature: "IF(logical_test, value_if_true, [value_if_false])",
-        description: "Returns one value if a condition is true and another if false.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "logical_test", description: "The condition to test.", optional: false, repeatable: false },
             ParameterInfo { name: "value_if_true", description: "The value if true.", optional: false, repeatable: false },
@@ -366,8 +330,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "AND",
         signature: "AND(logical1, [logical2], ...)",
-        description: "Returns TRUE if all arguments are TRUE.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "logical1", description: "The first condition.", optional: false, repeatable: false },
             ParameterInfo { name: "logical2", description: "Additional conditions.", optional: true, repeatable: true },
@@ -376,8 +338,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "OR",
         signature: "OR(logical1, [logical2], ...)",
-        description: "Returns TRUE if any argument is TRUE.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "logical1", description: "The first condition.", optional: false, repeatable: false },
             ParameterInfo { name: "logical2", description: "Additional conditions.", optional: true, repeatable: true },
@@ -386,8 +346,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "NOT",
         signature: "NOT(logical)",
-        description: "Reverses the logic of its argument.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "logical", description: "The value to reverse.", optional: false, repeatable: false },
         ],
@@ -395,8 +353,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "IFERROR",
         signature: "IFERROR(value, value_if_error)",
-        description: "Returns a value if an expression results in an error.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "value", description: "The value to check for error.", optional: false, repeatable: false },
             ParameterInfo { name: "value_if_error", description: "The value to return if error.", optional: false, repeatable: false },
@@ -405,8 +361,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ISBLANK",
         signature: "ISBLANK(value)",
-        description: "Returns TRUE if the value is blank.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "value", description: "The value to check.", optional: false, repeatable: false },
         ],
@@ -414,8 +368,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ISNUMBER",
         signature: "ISNUMBER(value)",
-        description: "Returns TRUE if the value is a number.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "value", description: "The value to check.", optional: false, repeatable: false },
         ],
@@ -423,8 +375,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ISTEXT",
         signature: "ISTEXT(value)",
-        description: "Returns TRUE if the value is text.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "value", description: "The value to check.", optional: false, repeatable: false },
         ],
@@ -432,8 +382,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ISERROR",
         signature: "ISERROR(value)",
-        description: "Returns TRUE if the value is an error.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "value", description: "The value to check.", optional: false, repeatable: false },
         ],
@@ -441,8 +389,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ISNA",
         signature: "ISNA(value)",
-        description: "Returns TRUE if the value is the #N/A error.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "value", description: "The value to check.", optional: false, repeatable: false },
         ],
@@ -450,8 +396,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "IFS",
         signature: "IFS(logical_test1, value_if_true1, [logical_test2, value_if_true2], ...)",
-        description: "Checks multiple conditions and returns the first true result.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "logical_test1", description: "First condition.", optional: false, repeatable: false },
             ParameterInfo { name: "value_if_true1", description: "Value if first condition is true.", optional: false, repeatable: false },
@@ -461,8 +405,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "SWITCH",
         signature: "SWITCH(expression, value1, result1, [value2, result2], ..., [default])",
-        description: "Evaluates an expression against a list of values.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "expression", description: "The value to match.", optional: false, repeatable: false },
             ParameterInfo { name: "value1", description: "First value to match against.", optional: false, repeatable: false },
@@ -473,8 +415,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "CHOOSE",
         signature: "CHOOSE(index_num, value1, [value2], ...)",
-        description: "Chooses a value from a list based on an index number.",
-        category: FunctionCategory::Logical,
         parameters: &[
             ParameterInfo { name: "index_num", description: "The index (1-based).", optional: false, repeatable: false },
             ParameterInfo { name: "value1", description: "The first value.", optional: false, repeatable: false },
@@ -486,8 +426,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "CONCATENATE",
         signature: "CONCATENATE(text1, [text2], ...)",
-        description: "Joins several text strings into one string.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text1", description: "The first text.", optional: false, repeatable: false },
             ParameterInfo { name: "text2", description: "Additional text.", optional: true, repeatable: true },
@@ -496,8 +434,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "CONCAT",
         signature: "CONCAT(text1, [text2], ...)",
-        description: "Joins text strings (modern version of CONCATENATE).",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text1", description: "The first text or range.", optional: false, repeatable: false },
             ParameterInfo { name: "text2", description: "Additional text or ranges.", optional: true, repeatable: true },
@@ -506,8 +442,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "LEFT",
         signature: "LEFT(text, [num_chars])",
-        description: "Returns the leftmost characters from a text string.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text string.", optional: false, repeatable: false },
             ParameterInfo { name: "num_chars", description: "Number of characters (default 1).", optional: true, repeatable: false },
@@ -516,8 +450,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "RIGHT",
         signature: "RIGHT(text, [num_chars])",
-        description: "Returns the rightmost characters from a text string.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text string.", optional: false, repeatable: false },
             ParameterInfo { name: "num_chars", description: "Number of characters (default 1).", optional: true, repeatable: false },
@@ -526,8 +458,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "MID",
         signature: "MID(text, start_num, num_chars)",
-        description: "Returns characters from the middle of a text string.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text string.", optional: false, repeatable: false },
             ParameterInfo { name: "start_num", description: "The starting position (1-based).", optional: false, repeatable: false },
@@ -537,8 +467,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "LEN",
         signature: "LEN(text)",
-        description: "Returns the number of characters in a text string.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text string.", optional: false, repeatable: false },
         ],
@@ -546,8 +474,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "UPPER",
         signature: "UPPER(text)",
-        description: "Converts text to uppercase.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text to convert.", optional: false, repeatable: false },
         ],
@@ -555,8 +481,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "LOWER",
         signature: "LOWER(text)",
-        description: "Converts text to lowercase.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text to convert.", optional: false, repeatable: false },
         ],
@@ -564,8 +488,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "TRIM",
         signature: "TRIM(text)",
-        description: "Removes extra spaces from text.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text to trim.", optional: false, repeatable: false },
         ],
@@ -573,18 +495,14 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "TEXT",
         signature: "TEXT(value, format_text)",
-        description: "Formats a number as text with a specified format.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "value", description: "The number to format.", optional: false, repeatable: false },
-            ParameterInfo { name: "format_text", description: "The format code.", optional: false, repeatable: false },
+            ParameterInfo { name: "format_text", description: "The format code, e.g. \"0.00%\", \"#,##0\" or \"yyyy-mm-dd\".", optional: false, repeatable: false },
         ],
     },
     FunctionInfo {
         name: "VALUE",
         signature: "VALUE(text)",
-        description: "Converts a text string to a number.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text to convert.", optional: false, repeatable: false },
         ],
@@ -592,8 +510,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "FIND",
         signature: "FIND(find_text, within_text, [start_num])",
-        description: "Finds one text string within another (case-sensitive).",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "find_text", description: "The text to find.", optional: false, repeatable: false },
             ParameterInfo { name: "within_text", description: "The text to search in.", optional: false, repeatable: false },
@@ -603,8 +519,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "SUBSTITUTE",
         signature: "SUBSTITUTE(text, old_text, new_text, [instance_num])",
-        description: "Replaces old text with new text in a string.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text to modify.", optional: false, repeatable: false },
             ParameterInfo { name: "old_text", description: "The text to replace.", optional: false, repeatable: false },
@@ -615,8 +529,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "REPT",
         signature: "REPT(text, number_times)",
-        description: "Repeats text a given number of times.",
-        category: FunctionCategory::Text,
         parameters: &[
             ParameterInfo { name: "text", description: "The text to repeat.", optional: false, repeatable: false },
             ParameterInfo { name: "number_times", description: "Number of repetitions.", optional: false, repeatable: false },
@@ -627,8 +539,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "SUMIF",
         signature: "SUMIF(range, criteria, [sum_range])",
-        description: "Sums cells that meet a criteria.",
-        category: FunctionCategory::Conditional,
         parameters: &[
             ParameterInfo { name: "range", description: "The range to evaluate.", optional: false, repeatable: false },
             ParameterInfo { name: "criteria", description: "The criteria to match.", optional: false, repeatable: false },
@@ -638,8 +548,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "COUNTIF",
         signature: "COUNTIF(range, criteria)",
-        description: "Counts cells that meet a criteria.",
-        category: FunctionCategory::Conditional,
         parameters: &[
             ParameterInfo { name: "range", description: "The range to evaluate.", optional: false, repeatable: false },
             ParameterInfo { name: "criteria", description: "The criteria to match.", optional: false, repeatable: false },
@@ -648,8 +556,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "COUNTBLANK",
         signature: "COUNTBLANK(range)",
-        description: "Counts the number of blank cells in a range.",
-        category: FunctionCategory::Conditional,
         parameters: &[
             ParameterInfo { name: "range", description: "The range to count.", optional: false, repeatable: false },
         ],
@@ -659,8 +565,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "VLOOKUP",
         signature: "VLOOKUP(lookup_value, table_array, col_index_num, [range_lookup])",
-        description: "Looks for a value in the leftmost column and returns a value in the same row.",
-        category: FunctionCategory::Lookup,
         parameters: &[
             ParameterInfo { name: "lookup_value", description: "The value to search for.", optional: false, repeatable: false },
             ParameterInfo { name: "table_array", description: "The range containing the data.", optional: false, repeatable: false },
@@ -671,8 +575,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "HLOOKUP",
         signature: "HLOOKUP(lookup_value, table_array, row_index_num, [range_lookup])",
-        description: "Looks for a value in the top row and returns a value in the same column.",
-        category: FunctionCategory::Lookup,
         parameters: &[
             ParameterInfo { name: "lookup_value", description: "The value to search for.", optional: false, repeatable: false },
             ParameterInfo { name: "table_array", description: "The range containing the data.", optional: false, repeatable: false },
@@ -683,8 +585,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "INDEX",
         signature: "INDEX(array, row_num, [column_num])",
-        description: "Returns the value at a given position in a range.",
-        category: FunctionCategory::Lookup,
         parameters: &[
             ParameterInfo { name: "array", description: "The range of cells.", optional: false, repeatable: false },
             ParameterInfo { name: "row_num", description: "The row number.", optional: false, repeatable: false },
@@ -694,8 +594,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "MATCH",
         signature: "MATCH(lookup_value, lookup_array, [match_type])",
-        description: "Returns the position of a value in a range.",
-        category: FunctionCategory::Lookup,
         parameters: &[
             ParameterInfo { name: "lookup_value", description: "The value to find.", optional: false, repeatable: false },
             ParameterInfo { name: "lookup_array", description: "The range to search.", optional: false, repeatable: false },
@@ -705,8 +603,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ROW",
         signature: "ROW([reference])",
-        description: "Returns the row number of a reference.",
-        category: FunctionCategory::Lookup,
         parameters: &[
             ParameterInfo { name: "reference", description: "The cell reference.", optional: true, repeatable: false },
         ],
@@ -714,8 +610,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "COLUMN",
         signature: "COLUMN([reference])",
-        description: "Returns the column number of a reference.",
-        category: FunctionCategory::Lookup,
         parameters: &[
             ParameterInfo { name: "reference", description: "The cell reference.", optional: true, repeatable: false },
         ],
@@ -723,8 +617,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ROWS",
         signature: "ROWS(array)",
-        description: "Returns the number of rows in a reference.",
-        category: FunctionCategory::Lookup,
         parameters: &[
             ParameterInfo { name: "array", description: "The range.", optional: false, repeatable: false },
         ],
@@ -732,8 +624,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "COLUMNS",
         signature: "COLUMNS(array)",
-        description: "Returns the number of columns in a reference.",
-        category: FunctionCategory::Lookup,
         parameters: &[
             ParameterInfo { name: "array", description: "The range.", optional: false, repeatable: false },
         ],
@@ -743,22 +633,16 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "TODAY",
         signature: "TODAY()",
-        description: "Returns the current date.",
-        category: FunctionCategory::DateTime,
         parameters: &[],
     },
     FunctionInfo {
         name: "NOW",
         signature: "NOW()",
-        description: "Returns the current date and time.",
-        category: FunctionCategory::DateTime,
         parameters: &[],
     },
     FunctionInfo {
         name: "DATE",
         signature: "DATE(year, month, day)",
-        description: "Creates a date from year, month, and day.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "year", description: "The year.", optional: false, repeatable: false },
             ParameterInfo { name: "month", description: "The month (1-12).", optional: false, repeatable: false },
@@ -768,8 +652,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "YEAR",
         signature: "YEAR(serial_number)",
-        description: "Returns the year from a date.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "serial_number", description: "The date.", optional: false, repeatable: false },
         ],
@@ -777,8 +659,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "MONTH",
         signature: "MONTH(serial_number)",
-        description: "Returns the month from a date.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "serial_number", description: "The date.", optional: false, repeatable: false },
         ],
@@ -786,8 +666,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "DAY",
         signature: "DAY(serial_number)",
-        description: "Returns the day from a date.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "serial_number", description: "The date.", optional: false, repeatable: false },
         ],
@@ -795,8 +673,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "WEEKDAY",
         signature: "WEEKDAY(serial_number, [return_type])",
-        description: "Returns the day of the week from a date.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "serial_number", description: "The date.", optional: false, repeatable: false },
             ParameterInfo { name: "return_type", description: "1=Sun-Sat, 2=Mon-Sun, 3=0-6.", optional: true, repeatable: false },
@@ -805,8 +681,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "DATEDIF",
         signature: "DATEDIF(start_date, end_date, unit)",
-        description: "Calculates the difference between two dates.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "start_date", description: "The start date.", optional: false, repeatable: false },
             ParameterInfo { name: "end_date", description: "The end date.", optional: false, repeatable: false },
@@ -816,8 +690,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "EDATE",
         signature: "EDATE(start_date, months)",
-        description: "Returns a date a specified number of months away.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "start_date", description: "The start date.", optional: false, repeatable: false },
             ParameterInfo { name: "months", description: "Number of months to add.", optional: false, repeatable: false },
@@ -826,8 +698,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "EOMONTH",
         signature: "EOMONTH(start_date, months)",
-        description: "Returns the last day of a month a specified number of months away.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "start_date", description: "The start date.", optional: false, repeatable: false },
             ParameterInfo { name: "months", description: "Number of months to add.", optional: false, repeatable: false },
@@ -836,8 +706,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "HOUR",
         signature: "HOUR(serial_number)",
-        description: "Returns the hour from a time value.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "serial_number", description: "The time.", optional: false, repeatable: false },
         ],
@@ -845,8 +713,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "MINUTE",
         signature: "MINUTE(serial_number)",
-        description: "Returns the minute from a time value.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "serial_number", description: "The time.", optional: false, repeatable: false },
         ],
@@ -854,8 +720,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "SECOND",
         signature: "SECOND(serial_number)",
-        description: "Returns the second from a time value.",
-        category: FunctionCategory::DateTime,
         parameters: &[
             ParameterInfo { name: "serial_number", description: "The time.", optional: false, repeatable: false },
         ],
@@ -865,15 +729,11 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "PI",
         signature: "PI()",
-        description: "Returns the value of pi (3.14159...).",
-        category: FunctionCategory::Trigonometry,
         parameters: &[],
     },
     FunctionInfo {
         name: "SIN",
         signature: "SIN(number)",
-        description: "Returns the sine of an angle (in radians).",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "number", description: "The angle in radians.", optional: false, repeatable: false },
         ],
@@ -881,8 +741,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "COS",
         signature: "COS(number)",
-        description: "Returns the cosine of an angle (in radians).",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "number", description: "The angle in radians.", optional: false, repeatable: false },
         ],
@@ -890,8 +748,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "TAN",
         signature: "TAN(number)",
-        description: "Returns the tangent of an angle (in radians).",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "number", description: "The angle in radians.", optional: false, repeatable: false },
         ],
@@ -899,8 +755,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ASIN",
         signature: "ASIN(number)",
-        description: "Returns the arcsine of a number (in radians).",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "number", description: "The sine value (-1 to 1).", optional: false, repeatable: false },
         ],
@@ -908,8 +762,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ACOS",
         signature: "ACOS(number)",
-        description: "Returns the arccosine of a number (in radians).",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "number", description: "The cosine value (-1 to 1).", optional: false, repeatable: false },
         ],
@@ -917,8 +769,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ATAN",
         signature: "ATAN(number)",
-        description: "Returns the arctangent of a number (in radians).",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "number", description: "The tangent value.", optional: false, repeatable: false },
         ],
@@ -926,8 +776,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "ATAN2",
         signature: "ATAN2(x_num, y_num)",
-        description: "Returns the arctangent from x and y coordinates.",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "x_num", description: "The x coordinate.", optional: false, repeatable: false },
             ParameterInfo { name: "y_num", description: "The y coordinate.", optional: false, repeatable: false },
@@ -936,8 +784,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "DEGREES",
         signature: "DEGREES(angle)",
-        description: "Converts radians to degrees.",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "angle", description: "The angle in radians.", optional: false, repeatable: false },
         ],
@@ -945,8 +791,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "RADIANS",
         signature: "RADIANS(angle)",
-        description: "Converts degrees to radians.",
-        category: FunctionCategory::Trigonometry,
         parameters: &[
             ParameterInfo { name: "angle", description: "The angle in degrees.", optional: false, repeatable: false },
         ],
@@ -956,8 +800,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "STDEV",
         signature: "STDEV(number1, [number2], ...)",
-        description: "Estimates standard deviation based on a sample.",
-        category: FunctionCategory::Statistical,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -966,8 +808,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "STDEV.S",
         signature: "STDEV.S(number1, [number2], ...)",
-        description: "Estimates standard deviation based on a sample.",
-        category: FunctionCategory::Statistical,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -976,8 +816,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "STDEV.P",
         signature: "STDEV.P(number1, [number2], ...)",
-        description: "Calculates standard deviation based on an entire population.",
-        category: FunctionCategory::Statistical,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -986,8 +824,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "STDEVP",
         signature: "STDEVP(number1, [number2], ...)",
-        description: "Calculates standard deviation based on an entire population.",
-        category: FunctionCategory::Statistical,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -996,8 +832,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "VAR",
         signature: "VAR(number1, [number2], ...)",
-        description: "Estimates variance based on a sample.",
-        category: FunctionCategory::Statistical,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -1006,8 +840,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "VAR.S",
         signature: "VAR.S(number1, [number2], ...)",
-        description: "Estimates variance based on a sample.",
-        category: FunctionCategory::Statistical,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -1016,8 +848,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "VAR.P",
         signature: "VAR.P(number1, [number2], ...)",
-        description: "Calculates variance based on an entire population.",
-        category: FunctionCategory::Statistical,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
@@ -1026,20 +856,16 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "VARP",
         signature: "VARP(number1, [number2], ...)",
-        description: "Calculates variance based on an entire population.",
-        category: FunctionCategory::Statistical,
         parameters: &[
             ParameterInfo { name: "number1", description: "The first number or range.", optional: false, repeatable: false },
             ParameterInfo { name: "number2", description: "Additional numbers or ranges.", optional: true, repeatable: true },
         ],
     },
 
-    // Array (5)
+    // Array (6)
     FunctionInfo {
         name: "SEQUENCE",
         signature: "SEQUENCE(rows, [columns], [start], [step])",
-        description: "Generates a sequence of numbers.",
-        category: FunctionCategory::Array,
         parameters: &[
             ParameterInfo { name: "rows", description: "Number of rows.", optional: false, repeatable: false },
             ParameterInfo { name: "columns", description: "Number of columns (default 1).", optional: true, repeatable: false },
@@ -1050,8 +876,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "TRANSPOSE",
         signature: "TRANSPOSE(array)",
-        description: "Transposes the rows and columns of an array.",
-        category: FunctionCategory::Array,
         parameters: &[
             ParameterInfo { name: "array", description: "The array to transpose.", optional: false, repeatable: false },
         ],
@@ -1059,8 +883,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "SORT",
         signature: "SORT(array, [sort_index], [sort_order], [by_col])",
-        description: "Sorts the contents of a range or array.",
-        category: FunctionCategory::Array,
         parameters: &[
             ParameterInfo { name: "array", description: "The range to sort.", optional: false, repeatable: false },
             ParameterInfo { name: "sort_index", description: "Column/row to sort by.", optional: true, repeatable: false },
@@ -1071,8 +893,6 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "UNIQUE",
         signature: "UNIQUE(array, [by_col], [exactly_once])",
-        description: "Returns the unique values from a range.",
-        category: FunctionCategory::Array,
         parameters: &[
             ParameterInfo { name: "array", description: "The range.", optional: false, repeatable: false },
             ParameterInfo { name: "by_col", description: "TRUE to compare columns.", optional: true, repeatable: false },
@@ -1082,14 +902,20 @@ pub static FUNCTIONS: &[FunctionInfo] = &[
     FunctionInfo {
         name: "FILTER",
         signature: "FILTER(array, include, [if_empty])",
-        description: "Filters a range based on criteria.",
-        category: FunctionCategory::Array,
         parameters: &[
             ParameterInfo { name: "array", description: "The range to filter.", optional: false, repeatable: false },
             ParameterInfo { name: "include", description: "Boolean array of same height/width.", optional: false, repeatable: false },
             ParameterInfo { name: "if_empty", description: "Value if no results.", optional: true, repeatable: false },
         ],
     },
+    FunctionInfo {
+        name: "MMULT",
+        signature: "MMULT(array1, array2)",
+        parameters: &[
+            ParameterInfo { name: "array1", description: "Left matrix; its column count must match array2's row count.", optional: false, repeatable: false },
+            ParameterInfo { name: "array2", description: "Right matrix.", optional: false, repeatable: false },
+        ],
+    },
 ];
 
 // ============================================================================
@@ -1866,6 +1692,14 @@ mod tests {
         assert!(funcs.iter().any(|f| f.name == "SUBSTITUTE"));
     }
 
+    #[test]
+    fn test_every_function_has_engine_meta() {
+        for func in FUNCTIONS {
+            assert!(functions::function_meta(func.name).is_some(), "{} has no engine metadata", func.name);
+        }
+        assert_eq!(get_function("STDEV.P").unwrap().category(), FunctionCategory::Statistical);
+    }
+
     // =========================================================================
     // Regression tests for formula reference highlighting (Phase 2.1)
     // =========================================================================
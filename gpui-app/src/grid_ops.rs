@@ -8,6 +8,7 @@
 
 use gpui::*;
 use crate::app::{Spreadsheet, NUM_ROWS, NUM_COLS};
+use crate::session_server::StructureChange;
 
 impl Spreadsheet {
     // =========================================================================
@@ -97,6 +98,25 @@ impl Spreadsheet {
             sheet.insert_rows(at_row, count);
         });
 
+        self.shift_rows_for_insert(at_row, count);
+
+        // Record undo entry
+        self.history.record_named_range_action(crate::history::UndoAction::RowsInserted {
+            sheet_index,
+            at_row,
+            count,
+        });
+
+        self.bump_cells_rev();
+        self.is_modified = true;
+        self.note_structure_change(sheet_index, StructureChange::InsertedRows { at: at_row, count }, cx);
+        self.status_message = Some(format!("Inserted {} row(s)", count));
+        cx.notify();
+    }
+
+    /// Move the active sheet's row view and row heights down after `count`
+    /// rows were inserted at `at_row` in the sheet data.
+    pub(crate) fn shift_rows_for_insert(&mut self, at_row: usize, count: usize) {
         // Update row_view to track new data rows
         for i in 0..count {
             self.row_view.insert_row(at_row + i);
@@ -118,18 +138,6 @@ impl Spreadsheet {
                 sheet_heights.insert(new_row, h);
             }
         }
-
-        // Record undo entry
-        self.history.record_named_range_action(crate::history::UndoAction::RowsInserted {
-            sheet_index,
-            at_row,
-            count,
-        });
-
-        self.bump_cells_rev();
-        self.is_modified = true;
-        self.status_message = Some(format!("Inserted {} row(s)", count));
-        cx.notify();
     }
 
     /// Delete rows at position with undo support
@@ -201,6 +209,7 @@ impl Spreadsheet {
 
         self.bump_cells_rev();
         self.is_modified = true;
+        self.note_structure_change(sheet_index, StructureChange::DeletedRows { at: at_row, count }, cx);
         self.status_message = Some(format!("Deleted {} row(s)", count));
         cx.notify();
     }
@@ -240,6 +249,7 @@ impl Spreadsheet {
 
         self.bump_cells_rev();
         self.is_modified = true;
+        self.note_structure_change(sheet_index, StructureChange::InsertedCols { at: at_col, count }, cx);
         self.status_message = Some(format!("Inserted {} column(s)", count));
         cx.notify();
     }
@@ -308,6 +318,7 @@ impl Spreadsheet {
 
         self.bump_cells_rev();
         self.is_modified = true;
+        self.note_structure_change(sheet_index, StructureChange::DeletedCols { at: at_col, count }, cx);
         self.status_message = Some(format!("Deleted {} column(s)", count));
         cx.notify();
     }
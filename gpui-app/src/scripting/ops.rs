@@ -328,33 +328,8 @@ impl<'a, R: SheetReader> LuaOpSink<'a, R> {
 
 /// Parse A1 notation to (row, col) - 1-indexed for Lua
 pub fn parse_a1(a1: &str) -> Option<(usize, usize)> {
-    let a1 = a1.trim().to_uppercase();
-    if a1.is_empty() {
-        return None;
-    }
-
-    // Find where letters end and digits begin
-    let col_end = a1.chars().take_while(|c| c.is_ascii_alphabetic()).count();
-    if col_end == 0 || col_end >= a1.len() {
-        return None;
-    }
-
-    let col_str = &a1[..col_end];
-    let row_str = &a1[col_end..];
-
-    // Parse column (A=1, B=2, ..., Z=26, AA=27, etc.)
-    let mut col: usize = 0;
-    for c in col_str.chars() {
-        col = col * 26 + (c as usize - 'A' as usize + 1);
-    }
-
-    // Parse row (1-indexed in A1, we keep 1-indexed for Lua)
-    let row: usize = row_str.parse().ok()?;
-    if row == 0 {
-        return None;
-    }
-
-    Some((row, col))
+    let (row, col) = visigrid_protocol::parse_a1(a1.trim())?;
+    Some((row + 1, col + 1))
 }
 
 /// Format (row, col) as A1 notation - expects 1-indexed
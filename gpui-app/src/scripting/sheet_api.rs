@@ -969,6 +969,8 @@ fn cell_value_to_lua_cell_value(value: &CellValue, sheet: &Sheet, row: usize, co
         CellValue::Empty => LuaCellValue::Nil,
         CellValue::Number(n) => LuaCellValue::Number(*n),
         CellValue::Text(s) => LuaCellValue::String(s.clone()),
+        CellValue::Boolean(b) => LuaCellValue::Bool(*b),
+        CellValue::Error(kind) => LuaCellValue::Error(kind.as_str().to_string()),
         CellValue::Formula { .. } => {
             // For formulas, return the evaluated display value
             let display = sheet.get_display(row, col);
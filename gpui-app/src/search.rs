@@ -1434,13 +1434,13 @@ impl SearchProvider for FormulaSearchProvider {
                             signature: func.signature.to_string(),
                         },
                     )
-                    .with_subtitle(func.description)
+                    .with_subtitle(func.description())
                     .with_score(score)
                     .with_highlights(highlights)
                     .with_secondary_action(SearchAction::ShowFunctionHelp {
                         name: func.name.to_string(),
                         signature: func.signature.to_string(),
-                        description: func.description.to_string(),
+                        description: func.description().to_string(),
                     }))
                 } else {
                     None
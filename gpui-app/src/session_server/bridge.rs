@@ -10,9 +10,17 @@
 //! 2. No deadlocks from cross-thread workbook access
 //! 3. Proper revision tracking and event emission
 
+use std::collections::HashMap;
 use std::sync::mpsc;
 
-use super::protocol::{Op, InspectTarget, InspectResult, OpError};
+use visigrid_engine::conditional_format::{color_from_hex, CompareOperator, ConditionalFormat, ConditionalRule, ConditionalStyle};
+use visigrid_engine::sheet::{CellComment, Sheet};
+use visigrid_engine::workbook::Workbook;
+
+use crate::history::CellChange;
+
+use super::protocol::{CellRef, Op, InspectTarget, InspectResult, OpError, ProtocolErrorCode, ProtocolErrorExt, StructureChange};
+use super::server::SessionServer;
 
 /// A simple oneshot channel for single-use responses.
 /// Uses std::sync::mpsc under the hood.
@@ -170,6 +178,311 @@ pub enum ApplyOpsError {
     OpFailed(OpError),
 }
 
+/// Find the first op that writes a cell (or its comment) inside a protected
+/// range, or that inserts rows at or above one (which would move its cells).
+///
+/// Sheet indices past the end resolve to `active_sheet`, matching how ops
+/// are applied. Returns a `protected_cell` error for that op.
+pub fn check_protected_ops(ops: &[Op], sheets: &[Sheet], active_sheet: usize) -> Option<OpError> {
+    ops.iter().enumerate().find_map(|(op_index, op)| {
+        let resolve = |sheet: usize| if sheet < sheets.len() { sheet } else { active_sheet };
+        let (sheet_idx, row, col) = match op {
+            Op::SetCellValue { sheet, row, col, .. }
+            | Op::SetCellFormula { sheet, row, col, .. }
+            | Op::ClearCell { sheet, row, col }
+            | Op::SetComment { sheet, row, col, .. } => {
+                let sheet_idx = resolve(*sheet);
+                if !sheets.get(sheet_idx)?.is_protected(*row, *col) {
+                    return None;
+                }
+                (sheet_idx, *row, *col)
+            }
+            Op::InsertRows { sheet, at, count } if *count > 0 => {
+                let sheet_idx = resolve(*sheet);
+                let moved = sheets.get(sheet_idx)?.protected_ranges.iter().find(|r| *at <= r.end.0)?;
+                (sheet_idx, moved.start.0.max(*at), moved.start.1)
+            }
+            _ => return None,
+        };
+        Some(OpError {
+            code: ProtocolErrorCode::ProtectedCell,
+            message: format!(
+                "{} (sheet {}, row {}, col {})",
+                ProtocolErrorCode::ProtectedCell.message(),
+                sheet_idx,
+                row,
+                col,
+            ),
+            op_index,
+            suggestion: Some("Protected ranges are read-only for session clients".to_string()),
+        })
+    })
+}
+
+/// The structural change an op makes, as (sheet, change), if it makes one.
+///
+/// Sheet indices past the end resolve to `active_sheet`, matching how ops
+/// are applied. Inserting zero rows changes nothing.
+pub fn op_structure_change(op: &Op, sheet_count: usize, active_sheet: usize) -> Option<(usize, StructureChange)> {
+    match op {
+        Op::InsertRows { sheet, at, count } if *count > 0 => {
+            let sheet_idx = if *sheet < sheet_count { *sheet } else { active_sheet };
+            Some((sheet_idx, StructureChange::InsertedRows { at: *at, count: *count }))
+        }
+        _ => None,
+    }
+}
+
+/// The rule a `set_conditional_format` op adds: `Ok(None)` for `rule: "clear"`
+/// (and for any other op). Errors say which field doesn't fit the rule.
+pub fn op_conditional_rule(op: &Op) -> Result<Option<ConditionalRule>, String> {
+    let Op::SetConditionalFormat { rule, values, font_color, background_color, bold, italic, colors, .. } = op else {
+        return Ok(None);
+    };
+    let color = |field: &str, hex: &str| {
+        color_from_hex(hex).ok_or_else(|| format!("invalid {} {:?} (expected #RRGGBB)", field, hex))
+    };
+
+    match rule.as_str() {
+        "clear" => Ok(None),
+        "color_scale" => {
+            let colors = colors.iter().map(|c| color("color", c)).collect::<Result<Vec<_>, _>>()?;
+            ConditionalRule::color_scale(&colors)
+                .map(Some)
+                .ok_or_else(|| format!("color_scale takes 2 or 3 colors, got {}", colors.len()))
+        }
+        name => {
+            let operator = CompareOperator::parse(name)
+                .ok_or_else(|| format!("unknown conditional format rule {:?}", name))?;
+            if values.len() != operator.operand_count() {
+                return Err(format!("{} takes {} value(s), got {}", name, operator.operand_count(), values.len()));
+            }
+            let style = ConditionalStyle {
+                font_color: font_color.as_deref().map(|c| color("font_color", c)).transpose()?,
+                background_color: background_color.as_deref().map(|c| color("background_color", c)).transpose()?,
+                bold: bold.unwrap_or(false),
+                italic: italic.unwrap_or(false),
+            };
+            Ok(Some(ConditionalRule::Compare { operator, values: values.clone(), style }))
+        }
+    }
+}
+
+/// Outcome of [`apply_session_ops`]: the reply for the client, and the edits
+/// the app records for undo and view bookkeeping.
+pub struct AppliedOps {
+    pub response: ApplyOpsResponse,
+    /// Cell edits by sheet, with old and new values.
+    pub changes_by_sheet: HashMap<usize, Vec<CellChange>>,
+    /// Structural changes as (sheet, change), in apply order.
+    pub structure: Vec<(usize, StructureChange)>,
+}
+
+/// Apply an apply_ops batch to `wb` and broadcast the resulting structure
+/// and cell events to `server`'s subscribers.
+///
+/// All ops run within a single batch_guard, ensuring exactly one recalc and
+/// one revision increment for the entire batch. The recalc only touches
+/// dependents of the edited cells unless an op changed the sheet structure.
+pub fn apply_session_ops(wb: &mut Workbook, server: &SessionServer, req: &ApplyOpsRequest) -> AppliedOps {
+    let unchanged = |response| AppliedOps { response, changes_by_sheet: HashMap::new(), structure: Vec::new() };
+
+    // Check expected_revision if provided
+    let current_rev = wb.revision();
+    if let Some(expected) = req.expected_revision {
+        if expected != current_rev {
+            return unchanged(ApplyOpsResponse {
+                applied: 0,
+                total: req.ops.len(),
+                current_revision: current_rev,
+                error: Some(ApplyOpsError::RevisionMismatch {
+                    expected,
+                    actual: current_rev,
+                }),
+            });
+        }
+    }
+
+    if req.ops.is_empty() {
+        return unchanged(ApplyOpsResponse {
+            applied: 0,
+            total: 0,
+            current_revision: current_rev,
+            error: None,
+        });
+    }
+
+    // Writes into protected ranges are rejected. Atomic batches fail before
+    // touching anything; otherwise the ops ahead of the first rejected one apply.
+    let protected_error = check_protected_ops(&req.ops, wb.sheets(), wb.active_sheet_index());
+    let ops_to_apply = match &protected_error {
+        Some(err) if req.atomic => {
+            return unchanged(ApplyOpsResponse {
+                applied: 0,
+                total: req.ops.len(),
+                current_revision: current_rev,
+                error: Some(ApplyOpsError::OpFailed(err.clone())),
+            });
+        }
+        Some(err) => &req.ops[..err.op_index],
+        None => &req.ops[..],
+    };
+
+    let mut applied = 0;
+    let mut error: Option<ApplyOpsError> = None;
+    // Group changes by sheet for history recording
+    let mut changes_by_sheet: HashMap<usize, Vec<CellChange>> = HashMap::new();
+    // Structural changes, in apply order, for history and subscribers
+    let mut structure: Vec<(usize, StructureChange)> = Vec::new();
+    {
+        let mut guard = wb.batch_guard();
+
+        for (i, op) in ops_to_apply.iter().enumerate() {
+            let sheet_count = guard.sheets().len();
+
+            match op {
+                Op::SetCellValue { sheet, row, col, value } => {
+                    let sheet_idx = if *sheet < sheet_count { *sheet } else { guard.active_sheet_index() };
+
+                    // Capture old value for history
+                    let old_value = guard.sheets()[sheet_idx].get_raw(*row, *col);
+                    changes_by_sheet.entry(sheet_idx).or_default().push(CellChange {
+                        row: *row,
+                        col: *col,
+                        old_value,
+                        new_value: value.clone(),
+                    });
+
+                    // Apply the mutation via tracked method
+                    guard.set_cell_value_tracked(sheet_idx, *row, *col, value);
+                    applied += 1;
+                }
+                Op::SetCellFormula { sheet, row, col, formula } => {
+                    let sheet_idx = if *sheet < sheet_count { *sheet } else { guard.active_sheet_index() };
+
+                    let old_value = guard.sheets()[sheet_idx].get_raw(*row, *col);
+                    changes_by_sheet.entry(sheet_idx).or_default().push(CellChange {
+                        row: *row,
+                        col: *col,
+                        old_value,
+                        new_value: formula.clone(),
+                    });
+
+                    guard.set_cell_value_tracked(sheet_idx, *row, *col, formula);
+                    applied += 1;
+                }
+                Op::ClearCell { sheet, row, col } => {
+                    let sheet_idx = if *sheet < sheet_count { *sheet } else { guard.active_sheet_index() };
+
+                    let old_value = guard.sheets()[sheet_idx].get_raw(*row, *col);
+                    changes_by_sheet.entry(sheet_idx).or_default().push(CellChange {
+                        row: *row,
+                        col: *col,
+                        old_value,
+                        new_value: String::new(),
+                    });
+
+                    guard.clear_cell_tracked(sheet_idx, *row, *col);
+                    applied += 1;
+                }
+                Op::SetNumberFormat { .. } => {
+                    // TODO: Parse format string and apply to range
+                    applied += 1;
+                }
+                Op::SetStyle { .. } => {
+                    // TODO: Apply style changes to range
+                    applied += 1;
+                }
+                Op::SetComment { sheet, row, col, text, author } => {
+                    let sheet_idx = if *sheet < sheet_count { *sheet } else { guard.active_sheet_index() };
+                    let comment = CellComment {
+                        text: text.clone(),
+                        author: author.clone(),
+                    };
+                    if let Some(target) = guard.sheet_mut(sheet_idx) {
+                        target.set_comment(*row, *col, Some(comment));
+                    }
+                    applied += 1;
+                }
+                Op::SetConditionalFormat { sheet, start_row, start_col, end_row, end_col, .. } => {
+                    let sheet_idx = if *sheet < sheet_count { *sheet } else { guard.active_sheet_index() };
+                    match op_conditional_rule(op) {
+                        Ok(rule) => {
+                            if let Some(target) = guard.sheet_mut(sheet_idx) {
+                                match rule {
+                                    Some(rule) => target.add_conditional_format(
+                                        ConditionalFormat::new(*start_row, *start_col, *end_row, *end_col, rule),
+                                    ),
+                                    None => {
+                                        target.clear_conditional_formats(*start_row, *start_col, *end_row, *end_col);
+                                    }
+                                }
+                            }
+                            applied += 1;
+                        }
+                        Err(message) => {
+                            error = Some(ApplyOpsError::OpFailed(OpError {
+                                code: ProtocolErrorCode::MalformedMessage,
+                                message,
+                                op_index: i,
+                                suggestion: Some("rule is a comparison (less_than, between, ...), color_scale, or clear".to_string()),
+                            }));
+                        }
+                    }
+                }
+                Op::InsertRows { at, count, .. } => {
+                    let active = guard.active_sheet_index();
+                    if let Some((sheet_idx, change)) = op_structure_change(op, sheet_count, active) {
+                        if let Some(target) = guard.sheet_mut(sheet_idx) {
+                            target.insert_rows(*at, *count);
+                        }
+                        guard.note_structure_changed();
+                        structure.push((sheet_idx, change));
+                    }
+                    applied += 1;
+                }
+            }
+
+            // If atomic and there was an error, stop
+            if req.atomic && error.is_some() {
+                break;
+            }
+        }
+    }
+    // batch_guard dropped here → one recompute_dirty (full recompute if
+    // rows were inserted) + revision increment
+    let error = error.or(protected_error.map(ApplyOpsError::OpFailed));
+    let new_rev = wb.revision();
+
+    // Broadcast to subscribed connections in the same transaction boundary
+    // as the revision increment
+    server.broadcast_structure(new_rev, structure.clone());
+    if applied > 0 && error.is_none() {
+        let changed_cells: Vec<CellRef> = changes_by_sheet
+            .iter()
+            .flat_map(|(sheet_idx, changes)| {
+                changes.iter().map(move |c| CellRef {
+                    sheet: *sheet_idx,
+                    row: c.row,
+                    col: c.col,
+                })
+            })
+            .collect();
+        server.broadcast_cells(new_rev, changed_cells);
+    }
+
+    AppliedOps {
+        response: ApplyOpsResponse {
+            applied,
+            total: req.ops.len(),
+            current_revision: new_rev,
+            error,
+        },
+        changes_by_sheet,
+        structure,
+    }
+}
+
 // ============================================================================
 // Inspect
 // ============================================================================
@@ -299,4 +612,95 @@ mod tests {
         };
         assert_eq!(response.current_revision, 42);
     }
+
+    #[test]
+    fn test_protected_cell_rejected_neighbor_accepted() {
+        use visigrid_engine::sheet::{ProtectedRange, SheetId};
+
+        let mut sheet = Sheet::new(SheetId(1), 10, 10);
+        sheet.protect(ProtectedRange::new(0, 0, 0, 0));
+        let sheets = vec![sheet];
+
+        let neighbor = vec![
+            Op::SetCellValue { sheet: 0, row: 0, col: 1, value: "ok".to_string() },
+            Op::ClearCell { sheet: 0, row: 1, col: 0 },
+        ];
+        assert!(check_protected_ops(&neighbor, &sheets, 0).is_none());
+
+        let mut writes = neighbor.clone();
+        writes.push(Op::ClearCell { sheet: 0, row: 0, col: 0 });
+        let err = check_protected_ops(&writes, &sheets, 0).expect("protected write must fail");
+        assert_eq!(err.code, ProtocolErrorCode::ProtectedCell);
+        assert_eq!(err.op_index, 2);
+
+        let note = vec![Op::SetComment { sheet: 0, row: 0, col: 0, text: "x".to_string(), author: None }];
+        assert_eq!(check_protected_ops(&note, &sheets, 0).map(|e| e.code), Some(ProtocolErrorCode::ProtectedCell));
+
+        // Out-of-range sheet index resolves to the active sheet
+        let stray = vec![Op::SetCellValue { sheet: 7, row: 0, col: 0, value: "x".to_string() }];
+        assert_eq!(check_protected_ops(&stray, &sheets, 0).map(|e| e.op_index), Some(0));
+    }
+
+    #[test]
+    fn test_insert_rows_above_protected_range_rejected() {
+        use visigrid_engine::sheet::{ProtectedRange, SheetId};
+
+        let mut sheet = Sheet::new(SheetId(1), 20, 10);
+        sheet.protect(ProtectedRange::new(3, 1, 5, 2));
+        let sheets = vec![sheet];
+
+        // Below the range (or inserting nothing) leaves protected cells in place
+        assert!(check_protected_ops(&[Op::InsertRows { sheet: 0, at: 6, count: 2 }], &sheets, 0).is_none());
+        assert!(check_protected_ops(&[Op::InsertRows { sheet: 0, at: 0, count: 0 }], &sheets, 0).is_none());
+
+        // Above or inside the range would move protected cells
+        for at in [0, 3, 5] {
+            let err = check_protected_ops(&[Op::InsertRows { sheet: 0, at, count: 1 }], &sheets, 0)
+                .expect("insert moving protected cells must fail");
+            assert_eq!(err.code, ProtocolErrorCode::ProtectedCell);
+            assert_eq!(err.op_index, 0);
+        }
+    }
+
+    #[test]
+    fn test_op_structure_change() {
+        let insert = Op::InsertRows { sheet: 1, at: 4, count: 2 };
+        assert_eq!(
+            op_structure_change(&insert, 2, 0),
+            Some((1, StructureChange::InsertedRows { at: 4, count: 2 })),
+        );
+        // Out-of-range sheet index resolves to the active sheet
+        assert_eq!(op_structure_change(&insert, 1, 0).map(|(sheet, _)| sheet), Some(0));
+
+        assert_eq!(op_structure_change(&Op::InsertRows { sheet: 0, at: 4, count: 0 }, 1, 0), None);
+        assert_eq!(op_structure_change(&Op::ClearCell { sheet: 0, row: 0, col: 0 }, 1, 0), None);
+    }
+
+    #[test]
+    fn test_op_conditional_rule() {
+        let op = |rule: &str, values: &[&str], colors: &[&str]| Op::SetConditionalFormat {
+            sheet: 0, start_row: 0, start_col: 0, end_row: 9, end_col: 0,
+            rule: rule.to_string(),
+            values: values.iter().map(|v| v.to_string()).collect(),
+            font_color: Some("#9C0006".to_string()),
+            background_color: None,
+            bold: Some(true),
+            italic: None,
+            colors: colors.iter().map(|c| c.to_string()).collect(),
+        };
+
+        let Ok(Some(ConditionalRule::Compare { operator, style, .. })) = op_conditional_rule(&op("less_than", &["0"], &[])) else {
+            panic!("expected a comparison");
+        };
+        assert_eq!(operator, CompareOperator::LessThan);
+        assert_eq!(style.font_color, Some([0x9C, 0x00, 0x06, 255]));
+        assert!(style.bold);
+
+        assert!(matches!(op_conditional_rule(&op("color_scale", &[], &["#F8696B", "#63BE7B"])), Ok(Some(ConditionalRule::ColorScale { .. }))));
+        assert_eq!(op_conditional_rule(&op("clear", &[], &[])), Ok(None));
+
+        assert!(op_conditional_rule(&op("between", &["1"], &[])).unwrap_err().contains("2 value(s)"));
+        assert!(op_conditional_rule(&op("color_scale", &[], &["red", "green"])).is_err());
+        assert!(op_conditional_rule(&op("bigger", &["1"], &[])).is_err());
+    }
 }
@@ -8,11 +8,14 @@
 //! - GUI thread broadcasts events through a channel
 //! - Connection threads poll for events and forward to subscribed clients
 //! - Cell changes are coalesced into ranges before broadcasting (efficient)
+//! - `cells:<sheet>:<range>` topics narrow delivery to overlapping ranges
+//! - Structure changes (rows/cols/sheets) go to every `cells` subscriber,
+//!   range filters included: an insert moves what a filter was watching
 
 use std::collections::HashSet;
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
 
-use super::protocol::{CellRange, EventMessage, EventPayload, ServerMessage};
+use super::protocol::{CellRange, EventMessage, EventPayload, ServerMessage, StructureChange};
 
 /// Topic for cell change events.
 pub const TOPIC_CELLS: &str = "cells";
@@ -28,6 +31,8 @@ pub struct BroadcastEvent {
     pub revision: u64,
     /// Coalesced ranges covering all changed cells.
     pub ranges: Vec<CellRange>,
+    /// Structural changes as (sheet, change), in the order they were applied.
+    pub structure: Vec<(usize, StructureChange)>,
 }
 
 /// Handle for broadcasting events from the GUI thread.
@@ -54,7 +59,7 @@ impl EventBroadcaster {
     /// No-op if no listeners are connected.
     pub fn broadcast_ranges(&self, revision: u64, ranges: Vec<CellRange>) {
         if let Some(tx) = &self.tx {
-            let _ = tx.send(BroadcastEvent { revision, ranges });
+            let _ = tx.send(BroadcastEvent { revision, ranges, structure: Vec::new() });
         }
     }
 
@@ -88,13 +93,21 @@ impl ConnectionSubscriptions {
     }
 
     /// Subscribe to topics. Returns list of successfully subscribed topics.
+    ///
+    /// A `cells:` topic that doesn't parse as a range filter subscribes to
+    /// all cells instead, so a client never silently misses changes.
     pub fn subscribe(&mut self, topics: &[String]) -> Vec<String> {
         let mut subscribed = Vec::new();
         for topic in topics {
-            if VALID_TOPICS.contains(&topic.as_str()) {
-                if self.topics.insert(topic.clone()) {
-                    subscribed.push(topic.clone());
-                }
+            let topic = if VALID_TOPICS.contains(&topic.as_str()) || CellRange::from_topic(topic).is_some() {
+                topic.as_str()
+            } else if topic.starts_with("cells:") {
+                TOPIC_CELLS
+            } else {
+                continue;
+            };
+            if self.topics.insert(topic.to_string()) {
+                subscribed.push(topic.to_string());
             }
         }
         subscribed
@@ -126,8 +139,12 @@ impl ConnectionSubscriptions {
     pub fn poll_events(&self) -> Vec<ServerMessage> {
         let mut messages = Vec::new();
 
+        // `cells` takes every change; range filters only take overlapping ones
+        let all_cells = self.is_subscribed(TOPIC_CELLS);
+        let filters: Vec<CellRange> = self.topics.iter().filter_map(|t| CellRange::from_topic(t)).collect();
+
         // Only process if subscribed to cells
-        if !self.is_subscribed(TOPIC_CELLS) {
+        if !all_cells && filters.is_empty() {
             // Drain the channel but don't produce messages
             while self.event_rx.try_recv().is_ok() {}
             return messages;
@@ -137,11 +154,28 @@ impl ConnectionSubscriptions {
         loop {
             match self.event_rx.try_recv() {
                 Ok(event) => {
-                    if !event.ranges.is_empty() {
+                    // Layout first, so a client re-fetches bounds before
+                    // applying any cell ranges from the same revision
+                    for (sheet, change) in event.structure {
+                        messages.push(ServerMessage::Event(EventMessage {
+                            topic: TOPIC_CELLS.to_string(),
+                            revision: event.revision,
+                            payload: EventPayload::SheetStructureChanged { sheet, change },
+                        }));
+                    }
+
+                    let ranges: Vec<CellRange> = if all_cells {
+                        event.ranges
+                    } else {
+                        event.ranges.into_iter()
+                            .filter(|r| filters.iter().any(|f| f.overlaps(r)))
+                            .collect()
+                    };
+                    if !ranges.is_empty() {
                         messages.push(ServerMessage::Event(EventMessage {
                             topic: TOPIC_CELLS.to_string(),
                             revision: event.revision,
-                            payload: EventPayload::CellsChanged { ranges: event.ranges },
+                            payload: EventPayload::CellsChanged { ranges },
                         }));
                     }
                 }
@@ -243,6 +277,7 @@ mod tests {
                 r2: 0,
                 c2: 0,
             }],
+            structure: Vec::new(),
         })
         .unwrap();
 
@@ -282,6 +317,7 @@ mod tests {
                 r2: 0,
                 c2: 0,
             }],
+            structure: Vec::new(),
         })
         .unwrap();
 
@@ -290,6 +326,91 @@ mod tests {
         assert!(messages.is_empty());
     }
 
+    #[test]
+    fn test_range_filter_topics() {
+        let (_tx, rx) = mpsc::channel();
+        let mut subs = ConnectionSubscriptions::new(rx);
+
+        let subscribed = subs.subscribe(&[
+            "cells:0:B2:D4".to_string(),
+            "cells:0:not-a-range".to_string(),
+            "bogus".to_string(),
+        ]);
+        // Malformed cells: topic degrades to every change
+        assert_eq!(subscribed, vec!["cells:0:B2:D4", "cells"]);
+
+        assert_eq!(CellRange::from_topic("cells:1:C5"), Some(CellRange::single(1, 4, 2)));
+        assert_eq!(CellRange::from_topic("cells:0:D4:B2"), Some(CellRange::new(0, 1, 1, 3, 3)));
+        assert_eq!(CellRange::from_topic("cells:0:A0"), None);
+    }
+
+    #[test]
+    fn test_poll_events_range_filter() {
+        let (tx, rx) = mpsc::channel();
+        let mut subs = ConnectionSubscriptions::new(rx);
+        subs.subscribe(&["cells:0:B2:D4".to_string()]);
+
+        // Outside the range (other column, other sheet): no event
+        tx.send(BroadcastEvent { revision: 1, ranges: vec![CellRange::single(0, 1, 5)], structure: Vec::new() }).unwrap();
+        tx.send(BroadcastEvent { revision: 2, ranges: vec![CellRange::single(1, 2, 2)], structure: Vec::new() }).unwrap();
+        assert!(subs.poll_events().is_empty());
+
+        // Inside: only the overlapping range is delivered
+        tx.send(BroadcastEvent {
+            revision: 3,
+            ranges: vec![CellRange::new(0, 3, 0, 5, 1), CellRange::single(0, 9, 9)],
+            structure: Vec::new(),
+        })
+        .unwrap();
+        let messages = subs.poll_events();
+        assert_eq!(messages.len(), 1);
+        match &messages[0] {
+            ServerMessage::Event(EventMessage { revision, payload: EventPayload::CellsChanged { ranges }, .. }) => {
+                assert_eq!(*revision, 3);
+                assert_eq!(ranges, &vec![CellRange::new(0, 3, 0, 5, 1)]);
+            }
+            other => panic!("Expected CellsChanged event, got {:?}", other),
+        }
+
+        // Adding plain `cells` widens back to every change
+        subs.subscribe(&["cells".to_string()]);
+        tx.send(BroadcastEvent { revision: 4, ranges: vec![CellRange::single(1, 2, 2)], structure: Vec::new() }).unwrap();
+        assert_eq!(subs.poll_events().len(), 1);
+    }
+
+    #[test]
+    fn test_poll_events_structure_change() {
+        let (tx, rx) = mpsc::channel();
+        let mut subs = ConnectionSubscriptions::new(rx);
+        // A range filter far from the change still hears about it: rows
+        // inserted above B2 move what the filter was watching
+        subs.subscribe(&["cells:0:B20:D24".to_string()]);
+
+        tx.send(BroadcastEvent {
+            revision: 7,
+            ranges: vec![CellRange::single(0, 2, 0)],
+            structure: vec![(0, StructureChange::InsertedRows { at: 2, count: 3 })],
+        })
+        .unwrap();
+
+        let messages = subs.poll_events();
+        assert_eq!(messages.len(), 1, "the cell range doesn't overlap the filter");
+        match &messages[0] {
+            ServerMessage::Event(EventMessage { topic, revision, payload }) => {
+                assert_eq!(topic, "cells");
+                assert_eq!(*revision, 7);
+                match payload {
+                    EventPayload::SheetStructureChanged { sheet, change } => {
+                        assert_eq!(*sheet, 0);
+                        assert_eq!(change, &StructureChange::InsertedRows { at: 2, count: 3 });
+                    }
+                    other => panic!("Expected SheetStructureChanged, got {:?}", other),
+                }
+            }
+            other => panic!("Expected Event message, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_broadcaster() {
         let (tx, rx) = mpsc::channel::<BroadcastEvent>();
@@ -5,7 +5,7 @@
 //!
 //! - Discover running sessions via discovery files
 //! - Apply batches of spreadsheet operations
-//! - Subscribe to live cell change events
+//! - Subscribe to live cell and sheet structure change events
 //!
 //! See: docs/future/phase-1-session-server.md
 
@@ -19,7 +19,8 @@ mod server;
 
 pub use bridge::{
     SessionBridgeHandle, SessionRequest, BridgeError,
-    ApplyOpsRequest, ApplyOpsResponse, ApplyOpsError,
+    ApplyOpsRequest, ApplyOpsResponse, ApplyOpsError, AppliedOps, apply_session_ops,
+    check_protected_ops, op_conditional_rule, op_structure_change,
     InspectRequest, InspectResponse,
     SubscribeRequest, SubscribeResponse,
     UnsubscribeRequest, UnsubscribeResponse,
@@ -27,11 +28,11 @@ pub use bridge::{
 pub use coalesce::coalesce_cells_to_ranges;
 pub use discovery::{DiscoveryFile, DiscoveryManager, discovery_dir, list_sessions};
 pub use protocol::{
-    ClientMessage, ServerMessage, ProtocolError, Op,
+    ClientMessage, ServerMessage, ProtocolErrorCode, Op, OpError,
     InspectTarget, InspectResult, CellInfo, WorkbookInfo,
     PROTOCOL_VERSION, MAX_MESSAGE_SIZE,
 };
 pub use server::{SessionServer, SessionServerConfig, ServerMode, EventRegistry};
 pub use rate_limiter::{RateLimiter, RateLimiterConfig, RateLimitedError};
 pub use events::{EventBroadcaster, BroadcastEvent, ConnectionSubscriptions, TOPIC_CELLS};
-pub use protocol::{CellRef, CellRange};
+pub use protocol::{CellRef, CellRange, StructureChange};
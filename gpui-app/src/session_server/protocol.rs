@@ -35,6 +35,12 @@ pub enum ClientMessage {
 
     /// Request server stats (for diagnostics).
     Stats(StatsMessage),
+
+    /// Re-query session identity and capabilities.
+    SessionInfo(SessionInfoMessage),
+
+    /// Graceful disconnect (no reply; the server closes the connection).
+    Bye(ByeMessage),
 }
 
 /// Messages from server to client.
@@ -62,6 +68,9 @@ pub enum ServerMessage {
     /// Response to Stats.
     StatsResult(StatsResultMessage),
 
+    /// Response to SessionInfo.
+    SessionInfoResult(SessionInfoResultMessage),
+
     /// Push event (cells changed, etc.).
     Event(EventMessage),
 
@@ -104,6 +113,10 @@ pub struct WelcomeMessage {
     pub revision: u64,
     /// Server capabilities.
     pub capabilities: Vec<String>,
+    /// Largest accepted message in bytes (excluding the newline).
+    /// Clients should refuse to send anything larger.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_message_bytes: Option<u64>,
 }
 
 // ============================================================================
@@ -123,6 +136,11 @@ pub struct ApplyOpsMessage {
     /// the request is rejected with revision_mismatch.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expected_revision: Option<u64>,
+    /// Client-chosen key for safe retries. If the server has seen this key
+    /// recently, it returns the original result without re-applying. Reusing
+    /// the key with different ops is rejected with `idempotency_key_reused`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
 /// A single operation to apply.
@@ -177,6 +195,50 @@ pub enum Op {
         #[serde(skip_serializing_if = "Option::is_none")]
         underline: Option<bool>,
     },
+    /// Set or clear a cell's comment. Empty `text` removes it.
+    /// Comments are metadata: they don't trigger recalc or affect the fingerprint.
+    SetComment {
+        #[serde(default)]
+        sheet: usize,
+        row: usize,
+        col: usize,
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        author: Option<String>,
+    },
+    /// Add a conditional format to a range, or remove the range's formats
+    /// with `rule: "clear"`. `rule` is a comparison (`less_than`, `between`,
+    /// ...) styled by the color/bold/italic fields, or `color_scale` with 2-3
+    /// `#RRGGBB` `colors`, lowest first.
+    SetConditionalFormat {
+        #[serde(default)]
+        sheet: usize,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+        rule: String,
+        /// Operands for a comparison, as formula text (`0`, `$B$1`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        values: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        font_color: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        background_color: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bold: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        italic: Option<bool>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        colors: Vec<String>,
+    },
+    /// Insert `count` empty rows before row `at`, shifting the rows below down.
+    InsertRows {
+        #[serde(default)]
+        sheet: usize,
+        at: usize,
+        count: usize,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -197,8 +259,8 @@ pub struct ApplyOpsResultMessage {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpError {
-    /// Error code (e.g., "formula_parse_error", "revision_mismatch").
-    pub code: String,
+    /// Error code (e.g., `FormulaParseError`, `RevisionMismatch`).
+    pub code: ProtocolErrorCode,
     /// Human-readable message.
     pub message: String,
     /// Index of the failing op (0-based).
@@ -216,7 +278,8 @@ pub struct OpError {
 pub struct SubscribeMessage {
     /// Request ID for correlation.
     pub id: String,
-    /// Topics to subscribe to.
+    /// Topics to subscribe to: `cells` for every change, or
+    /// `cells:<sheet>:<range>` for changes overlapping one range.
     pub topics: Vec<String>,
 }
 
@@ -296,6 +359,29 @@ pub enum EventPayload {
         /// Current revision (client should inspect if out of sync).
         current_revision: u64,
     },
+    /// Rows or columns were inserted or deleted, or a sheet was added or
+    /// removed. Cached layout for `sheet` is stale: re-fetch its bounds.
+    SheetStructureChanged {
+        /// Sheet index (0-based) the change applies to.
+        sheet: usize,
+        /// What changed.
+        change: StructureChange,
+    },
+}
+
+/// A structural change to a sheet, carried by `sheet_structure_changed`.
+///
+/// Row and column positions are 0-based; `at` is the first inserted or
+/// deleted index.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StructureChange {
+    AddedSheet,
+    RemovedSheet,
+    InsertedRows { at: usize, count: usize },
+    DeletedRows { at: usize, count: usize },
+    InsertedCols { at: usize, count: usize },
+    DeletedCols { at: usize, count: usize },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -306,51 +392,7 @@ pub struct CellRef {
 }
 
 /// A rectangular range of cells. Used in coalesced event payloads.
-///
-/// # Coordinate System
-///
-/// **All bounds are INCLUSIVE.** This differs from many APIs that use
-/// end-exclusive ranges.
-///
-/// A range `{r1: 0, c1: 0, r2: 2, c2: 3}` covers:
-/// - Rows 0, 1, 2 (3 rows total)
-/// - Columns 0, 1, 2, 3 (4 columns total)
-/// - Total: 12 cells
-///
-/// A single cell at (5, 3) is represented as `{r1: 5, c1: 3, r2: 5, c2: 3}`.
-///
-/// Invariants: `r1 <= r2` and `c1 <= c2` (enforced by constructor).
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct CellRange {
-    /// Sheet index (0-based).
-    pub sheet: usize,
-    /// First row, INCLUSIVE (0-based).
-    pub r1: usize,
-    /// First column, INCLUSIVE (0-based).
-    pub c1: usize,
-    /// Last row, INCLUSIVE (0-based). Must be >= r1.
-    pub r2: usize,
-    /// Last column, INCLUSIVE (0-based). Must be >= c1.
-    pub c2: usize,
-}
-
-impl CellRange {
-    /// Create a range covering a single cell.
-    pub fn single(sheet: usize, row: usize, col: usize) -> Self {
-        Self { sheet, r1: row, c1: col, r2: row, c2: col }
-    }
-
-    /// Create a range from bounds.
-    pub fn new(sheet: usize, r1: usize, c1: usize, r2: usize, c2: usize) -> Self {
-        debug_assert!(r1 <= r2 && c1 <= c2, "Invalid range bounds");
-        Self { sheet, r1, c1, r2, c2 }
-    }
-
-    /// Number of cells covered by this range.
-    pub fn cell_count(&self) -> usize {
-        (self.r2 - self.r1 + 1) * (self.c2 - self.c1 + 1)
-    }
-}
+pub use visigrid_protocol::CellRange;
 
 // ============================================================================
 // Inspect
@@ -464,6 +506,51 @@ pub struct StatsResultMessage {
     pub dropped_events_total: u64,
     /// Current number of connected clients.
     pub active_connections: u64,
+    /// Seconds since the server started.
+    #[serde(default)]
+    pub uptime_seconds: u64,
+    /// Revisions committed through the session since the server started.
+    #[serde(default)]
+    pub revision_count: u64,
+    /// Connections closed by the client with a `bye`.
+    #[serde(default)]
+    pub connections_closed_graceful: u64,
+}
+
+// ============================================================================
+// Session Info
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfoMessage {
+    /// Request ID for correlation.
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfoResultMessage {
+    /// Echoed request ID.
+    pub id: String,
+    /// Session ID (same as in Welcome).
+    pub session_id: String,
+    /// Protocol version in use.
+    pub protocol_version: u32,
+    /// Server capabilities.
+    pub capabilities: Vec<String>,
+    /// Workbook title (same as in the discovery file).
+    pub workbook_title: String,
+    /// Current revision number.
+    pub revision: u64,
+}
+
+// ============================================================================
+// Bye
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ByeMessage {
+    /// Request ID (for logging; there is no reply).
+    pub id: String,
 }
 
 // ============================================================================
@@ -476,7 +563,7 @@ pub struct ErrorMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     /// Error code.
-    pub code: String,
+    pub code: ProtocolErrorCode,
     /// Human-readable message.
     pub message: String,
     /// Milliseconds until retry is allowed (for rate limiting).
@@ -484,67 +571,20 @@ pub struct ErrorMessage {
     pub retry_after_ms: Option<u64>,
 }
 
-/// Protocol error codes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ProtocolError {
-    /// Invalid or missing token.
-    AuthFailed,
-    /// Unsupported protocol version.
-    ProtocolMismatch,
-    /// Rate limit exceeded.
-    RateLimited,
-    /// expected_revision doesn't match current.
-    RevisionMismatch,
-    /// Formula syntax error.
-    FormulaParseError,
-    /// Invalid sheet/row/col reference.
-    InvalidReference,
-    /// Message too large.
-    MessageTooLarge,
-    /// Malformed JSON.
-    MalformedMessage,
-    /// Server is in read-only mode.
-    ReadOnlyMode,
-    /// Writer lease held by another connection.
-    WriterConflict,
-    /// Unknown error.
-    InternalError,
-}
-
-/// All protocol error variants. Used for exhaustive testing.
-/// IMPORTANT: Update this array when adding/removing error codes.
-pub const ALL_ERROR_CODES: &[ProtocolError] = &[
-    ProtocolError::AuthFailed,
-    ProtocolError::ProtocolMismatch,
-    ProtocolError::RateLimited,
-    ProtocolError::RevisionMismatch,
-    ProtocolError::FormulaParseError,
-    ProtocolError::InvalidReference,
-    ProtocolError::MessageTooLarge,
-    ProtocolError::MalformedMessage,
-    ProtocolError::ReadOnlyMode,
-    ProtocolError::WriterConflict,
-    ProtocolError::InternalError,
-];
-
-impl ProtocolError {
-    pub fn code(&self) -> &'static str {
-        match self {
-            Self::AuthFailed => "auth_failed",
-            Self::ProtocolMismatch => "protocol_mismatch",
-            Self::RateLimited => "rate_limited",
-            Self::RevisionMismatch => "revision_mismatch",
-            Self::FormulaParseError => "formula_parse_error",
-            Self::InvalidReference => "invalid_reference",
-            Self::MessageTooLarge => "message_too_large",
-            Self::MalformedMessage => "malformed_message",
-            Self::ReadOnlyMode => "read_only_mode",
-            Self::WriterConflict => "writer_conflict",
-            Self::InternalError => "internal_error",
-        }
-    }
+/// Protocol error codes, shared with clients via `visigrid-protocol` so both
+/// sides agree on the wire names.
+pub use visigrid_protocol::ProtocolErrorCode;
+
+/// Server-side helpers for building replies from an error code.
+pub trait ProtocolErrorExt {
+    /// Default human-readable message for the code.
+    fn message(&self) -> &'static str;
+
+    fn to_error_message(&self, id: Option<String>) -> ErrorMessage;
+}
 
-    pub fn message(&self) -> &'static str {
+impl ProtocolErrorExt for ProtocolErrorCode {
+    fn message(&self) -> &'static str {
         match self {
             Self::AuthFailed => "Invalid or missing authentication token",
             Self::ProtocolMismatch => "Unsupported protocol version",
@@ -552,31 +592,34 @@ impl ProtocolError {
             Self::RevisionMismatch => "Expected revision does not match current revision",
             Self::FormulaParseError => "Formula syntax error",
             Self::InvalidReference => "Invalid sheet, row, or column reference",
+            Self::ProtectedCell => "Cell is in a protected range",
             Self::MessageTooLarge => "Message exceeds maximum size",
             Self::MalformedMessage => "Malformed JSON message",
             Self::ReadOnlyMode => "Server is in read-only mode",
             Self::WriterConflict => "Write lease held by another connection",
+            Self::Timeout => "Request is still running",
             Self::InternalError => "Internal server error",
+            Self::Other(_) => "Unknown error",
         }
     }
 
-    pub fn to_error_message(&self, id: Option<String>) -> ErrorMessage {
+    fn to_error_message(&self, id: Option<String>) -> ErrorMessage {
         ErrorMessage {
             id,
-            code: self.code().to_string(),
+            code: self.clone(),
             message: self.message().to_string(),
             retry_after_ms: None,
         }
     }
+}
 
-    /// Create a rate limited error message with retry information.
-    pub fn rate_limited_error(id: Option<String>, retry_after_ms: u64) -> ErrorMessage {
-        ErrorMessage {
-            id,
-            code: Self::RateLimited.code().to_string(),
-            message: format!("Rate limit exceeded. Retry after {} ms", retry_after_ms),
-            retry_after_ms: Some(retry_after_ms),
-        }
+/// Create a rate limited error message with retry information.
+pub fn rate_limited_error(id: Option<String>, retry_after_ms: u64) -> ErrorMessage {
+    ErrorMessage {
+        id,
+        code: ProtocolErrorCode::RateLimited,
+        message: format!("Rate limit exceeded. Retry after {} ms", retry_after_ms),
+        retry_after_ms: Some(retry_after_ms),
     }
 }
 
@@ -585,7 +628,7 @@ mod tests {
     use super::*;
 
     /// Client message types (for golden vector parsing).
-    const CLIENT_TYPES: &[&str] = &["hello", "apply_ops", "subscribe", "unsubscribe", "inspect", "ping"];
+    const CLIENT_TYPES: &[&str] = &["hello", "apply_ops", "subscribe", "unsubscribe", "inspect", "ping", "bye"];
 
     /// Server message types (for golden vector parsing).
     const SERVER_TYPES: &[&str] = &[
@@ -623,14 +666,18 @@ mod tests {
 
         let golden_files = [
             "hello_ok.jsonl",
+            "hello_max_message.jsonl",
             "hello_protocol_mismatch.jsonl",
             "apply_ops_ok.jsonl",
+            "apply_ops_idempotent.jsonl",
             "writer_conflict.jsonl",
             "errors.jsonl",
             "subscribe_events.jsonl",
             "subscribe_events_large_paste.jsonl",
+            "sheet_structure_changed.jsonl",
             "inspect.jsonl",
             "ping_pong.jsonl",
+            "bye.jsonl",
         ];
 
         let mut failures = Vec::new();
@@ -736,31 +783,31 @@ mod tests {
         }
     }
 
-    /// Test that ALL_ERROR_CODES array contains all enum variants.
+    /// Test that ProtocolErrorCode::ALL array contains all enum variants.
     /// This catches "added variant but forgot to add to array" errors.
     #[test]
     fn test_all_error_codes_exhaustive() {
-        // This test relies on the match in code() being exhaustive.
-        // If a variant is missing from ALL_ERROR_CODES, we'll catch it here.
-        let codes_from_array: std::collections::HashSet<&'static str> =
-            ALL_ERROR_CODES.iter().map(|e| e.code()).collect();
+        // This test relies on the match in message() being exhaustive.
+        // If a variant is missing from ProtocolErrorCode::ALL, we'll catch it here.
+        let codes_from_array: std::collections::HashSet<&str> =
+            ProtocolErrorCode::ALL.iter().map(|e| e.as_str()).collect();
 
         // Verify count matches expected (update this when adding codes)
         assert_eq!(
-            ALL_ERROR_CODES.len(),
-            11,
-            "ALL_ERROR_CODES count changed. Update this test and errors.jsonl golden."
+            ProtocolErrorCode::ALL.len(),
+            13,
+            "ProtocolErrorCode::ALL count changed. Update this test and errors.jsonl golden."
         );
 
         // Verify no duplicates
         assert_eq!(
             codes_from_array.len(),
-            ALL_ERROR_CODES.len(),
-            "ALL_ERROR_CODES contains duplicate codes"
+            ProtocolErrorCode::ALL.len(),
+            "ProtocolErrorCode::ALL contains duplicate codes"
         );
     }
 
-    /// Test that errors.jsonl golden contains exactly the codes from ALL_ERROR_CODES.
+    /// Test that errors.jsonl golden contains exactly the codes from ProtocolErrorCode::ALL.
     /// Prevents silent contract drift where golden and enum diverge.
     #[test]
     fn test_error_codes_golden_coverage() {
@@ -788,7 +835,7 @@ mod tests {
 
         // Get all codes from the enum
         let enum_codes: std::collections::HashSet<String> =
-            ALL_ERROR_CODES.iter().map(|e| e.code().to_string()).collect();
+            ProtocolErrorCode::ALL.iter().map(|e| e.as_str().to_string()).collect();
 
         // Check for codes in enum but missing from golden
         let missing_from_golden: Vec<_> = enum_codes.difference(&golden_codes).collect();
@@ -816,23 +863,25 @@ mod tests {
     fn test_error_code_strings_stable() {
         // Canonical error code strings. NEVER change these without bumping PROTOCOL_VERSION.
         let expected_codes = [
-            (ProtocolError::AuthFailed, "auth_failed"),
-            (ProtocolError::ProtocolMismatch, "protocol_mismatch"),
-            (ProtocolError::RateLimited, "rate_limited"),
-            (ProtocolError::RevisionMismatch, "revision_mismatch"),
-            (ProtocolError::FormulaParseError, "formula_parse_error"),
-            (ProtocolError::InvalidReference, "invalid_reference"),
-            (ProtocolError::MessageTooLarge, "message_too_large"),
-            (ProtocolError::MalformedMessage, "malformed_message"),
-            (ProtocolError::ReadOnlyMode, "read_only_mode"),
-            (ProtocolError::WriterConflict, "writer_conflict"),
-            (ProtocolError::InternalError, "internal_error"),
+            (ProtocolErrorCode::AuthFailed, "auth_failed"),
+            (ProtocolErrorCode::ProtocolMismatch, "protocol_mismatch"),
+            (ProtocolErrorCode::RateLimited, "rate_limited"),
+            (ProtocolErrorCode::RevisionMismatch, "revision_mismatch"),
+            (ProtocolErrorCode::FormulaParseError, "formula_parse_error"),
+            (ProtocolErrorCode::InvalidReference, "invalid_reference"),
+            (ProtocolErrorCode::ProtectedCell, "protected_cell"),
+            (ProtocolErrorCode::MessageTooLarge, "message_too_large"),
+            (ProtocolErrorCode::MalformedMessage, "malformed_message"),
+            (ProtocolErrorCode::ReadOnlyMode, "read_only_mode"),
+            (ProtocolErrorCode::WriterConflict, "writer_conflict"),
+            (ProtocolErrorCode::Timeout, "timeout"),
+            (ProtocolErrorCode::InternalError, "internal_error"),
         ];
 
-        for (error, expected_code) in expected_codes {
+        for (error, expected_code) in &expected_codes {
             assert_eq!(
-                error.code(),
-                expected_code,
+                error.as_str(),
+                *expected_code,
                 "Error code string changed for {:?}. This breaks wire protocol compatibility!",
                 error
             );
@@ -841,7 +890,7 @@ mod tests {
         // Verify we tested all codes
         assert_eq!(
             expected_codes.len(),
-            ALL_ERROR_CODES.len(),
+            ProtocolErrorCode::ALL.len(),
             "test_error_code_strings_stable doesn't cover all error codes"
         );
     }
@@ -909,6 +958,7 @@ mod tests {
             ],
             atomic: true,
             expected_revision: Some(5),
+            idempotency_key: None,
         };
 
         let json = serde_json::to_string_pretty(&msg).unwrap();
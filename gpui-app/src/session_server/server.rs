@@ -2,6 +2,7 @@
 //!
 //! Binds to 127.0.0.1:<random_port> and handles JSONL messages.
 
+use std::collections::VecDeque;
 use std::io::{BufRead, BufReader, Write};
 use std::net::{TcpListener, TcpStream, SocketAddr};
 use std::path::PathBuf;
@@ -227,18 +228,115 @@ impl Default for WriterLease {
     }
 }
 
+/// Number of recent idempotency keys remembered for apply_ops retries.
+const IDEMPOTENCY_CACHE_SIZE: usize = 256;
+
+/// Retry-after hint for a keyed apply whose first attempt is still running.
+const IN_FLIGHT_RETRY_MS: u64 = 1000;
+
+/// Results of recent keyed apply_ops requests, each with a hash of the ops
+/// sent under its key. Shared across connections, so a client that reconnects
+/// and resends gets the original result back instead of applying the same ops
+/// twice.
+#[derive(Clone, Default)]
+pub struct IdempotencyCache {
+    inner: Arc<Mutex<VecDeque<IdempotencyEntry>>>,
+}
+
+struct IdempotencyEntry {
+    key: String,
+    ops_hash: blake3::Hash,
+    /// None while the first apply under this key is still running.
+    result: Option<ApplyOpsResultMessage>,
+}
+
+/// What a keyed apply_ops request should do, per [`IdempotencyCache`].
+#[derive(Debug)]
+pub enum IdempotencyLookup {
+    /// The key is new (and now claimed, for [`IdempotencyCache::claim`]).
+    Apply,
+    /// These ops were already applied under the key; reply with this result.
+    Done(ApplyOpsResultMessage),
+    /// These ops are being applied under the key by another request.
+    InFlight,
+    /// The key was used with different ops.
+    Mismatch,
+}
+
+impl IdempotencyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hash of an apply's ops, compared when a key is reused.
+    pub fn ops_hash(ops: &[Op]) -> blake3::Hash {
+        blake3::hash(&serde_json::to_vec(ops).unwrap_or_default())
+    }
+
+    /// Look up `key` without claiming it.
+    pub fn get(&self, key: &str, ops_hash: blake3::Hash) -> IdempotencyLookup {
+        let inner = self.inner.lock().unwrap();
+        Self::lookup(&inner, key, ops_hash)
+    }
+
+    /// Look up `key` and, if it is new, mark it in flight so a retry that
+    /// arrives before [`complete`](Self::complete) does not apply it again.
+    pub fn claim(&self, key: &str, ops_hash: blake3::Hash) -> IdempotencyLookup {
+        let mut inner = self.inner.lock().unwrap();
+        let lookup = Self::lookup(&inner, key, ops_hash);
+        if matches!(lookup, IdempotencyLookup::Apply) {
+            if inner.len() >= IDEMPOTENCY_CACHE_SIZE {
+                inner.pop_front();
+            }
+            inner.push_back(IdempotencyEntry { key: key.to_string(), ops_hash, result: None });
+        }
+        lookup
+    }
+
+    /// Record the result for a claimed key.
+    pub fn complete(&self, key: &str, result: ApplyOpsResultMessage) {
+        let mut inner = self.inner.lock().unwrap();
+        // Gone if evicted while in flight; a later retry then applies afresh
+        if let Some(entry) = inner.iter_mut().find(|entry| entry.key == key) {
+            entry.result = Some(result);
+        }
+    }
+
+    /// Drop a claimed key whose apply never reached the engine, so a retry
+    /// runs it.
+    pub fn abandon(&self, key: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.retain(|entry| entry.key != key || entry.result.is_some());
+    }
+
+    fn lookup(inner: &VecDeque<IdempotencyEntry>, key: &str, ops_hash: blake3::Hash) -> IdempotencyLookup {
+        match inner.iter().find(|entry| entry.key == key) {
+            None => IdempotencyLookup::Apply,
+            Some(entry) if entry.ops_hash != ops_hash => IdempotencyLookup::Mismatch,
+            Some(IdempotencyEntry { result: Some(result), .. }) => IdempotencyLookup::Done(result.clone()),
+            Some(_) => IdempotencyLookup::InFlight,
+        }
+    }
+}
+
 /// Maximum concurrent connections per session server.
 /// Prevents resource exhaustion from runaway scripts/agents.
 pub const MAX_CONNECTIONS: usize = 5;
 
 /// Operational metrics for the session server.
 /// Used for debugging and monitoring.
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct ServerMetrics {
+    /// When the server started (reset on each `start`).
+    pub started_at: Instant,
+    /// Apply batches that committed a new revision since start.
+    pub revision_count: Arc<std::sync::atomic::AtomicU64>,
     /// Connections closed due to parse failure limit.
     pub connections_closed_parse_failures: Arc<std::sync::atomic::AtomicU64>,
     /// Connections closed due to oversized message.
     pub connections_closed_oversize: Arc<std::sync::atomic::AtomicU64>,
+    /// Connections the client closed with a `bye`.
+    pub connections_closed_graceful: Arc<std::sync::atomic::AtomicU64>,
     /// Writer conflict errors returned.
     pub writer_conflict_count: Arc<std::sync::atomic::AtomicU64>,
     /// Connections refused due to connection limit.
@@ -251,6 +349,20 @@ impl ServerMetrics {
     }
 }
 
+impl Default for ServerMetrics {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            revision_count: Default::default(),
+            connections_closed_parse_failures: Default::default(),
+            connections_closed_oversize: Default::default(),
+            connections_closed_graceful: Default::default(),
+            writer_conflict_count: Default::default(),
+            connections_refused_limit: Default::default(),
+        }
+    }
+}
+
 /// The session server - manages TCP listener and client connections.
 pub struct SessionServer {
     /// Discovery manager (writes discovery file).
@@ -269,6 +381,22 @@ pub struct SessionServer {
     writer_lease: WriterLease,
     /// Operational metrics.
     metrics: ServerMetrics,
+    /// Recent apply_ops results by idempotency key.
+    idempotency_cache: IdempotencyCache,
+    /// Workbook title, shared with connections for session_info.
+    workbook_title: Arc<Mutex<String>>,
+}
+
+/// Session identity reported by session_info.
+#[derive(Clone)]
+struct SessionIdentity {
+    session_id: String,
+    workbook_title: Arc<Mutex<String>>,
+}
+
+/// Capabilities advertised in welcome and session_info.
+fn server_capabilities() -> Vec<String> {
+    vec!["apply_ops".to_string(), "inspect".to_string()]
 }
 
 impl SessionServer {
@@ -283,6 +411,8 @@ impl SessionServer {
             event_registry: EventRegistry::new(),
             writer_lease: WriterLease::new(),
             metrics: ServerMetrics::new(),
+            idempotency_cache: IdempotencyCache::new(),
+            workbook_title: Arc::new(Mutex::new(String::new())),
         }
     }
 
@@ -317,6 +447,8 @@ impl SessionServer {
         // Set non-blocking so we can check shutdown flag
         listener.set_nonblocking(true)?;
 
+        *self.workbook_title.lock().unwrap() = config.workbook_title.clone();
+
         // Create discovery file
         self.discovery = Some(DiscoveryManager::new(
             addr.port(),
@@ -329,14 +461,19 @@ impl SessionServer {
         let shutdown = Arc::clone(&self.shutdown);
         let mode = self.mode;
         let token = self.discovery.as_ref().unwrap().token().to_string();
-        let session_id = self.discovery.as_ref().unwrap().session_id().to_string();
+        let identity = SessionIdentity {
+            session_id: self.discovery.as_ref().unwrap().session_id().to_string(),
+            workbook_title: Arc::clone(&self.workbook_title),
+        };
         let rate_limiter_config = config.rate_limiter_config;
         let event_registry = self.event_registry.clone();
         let writer_lease = self.writer_lease.clone();
+        self.metrics.started_at = Instant::now();
         let metrics = self.metrics.clone();
+        let idempotency_cache = self.idempotency_cache.clone();
 
         self.listener_handle = Some(thread::spawn(move || {
-            run_listener(listener, shutdown, mode, token, session_id, bridge, rate_limiter_config, event_registry, writer_lease, metrics);
+            run_listener(listener, shutdown, mode, token, identity, bridge, rate_limiter_config, event_registry, writer_lease, metrics, idempotency_cache);
         }));
 
         log::info!(
@@ -400,6 +537,7 @@ impl SessionServer {
 
     /// Update workbook info (called when file is saved/opened).
     pub fn update_workbook(&mut self, path: Option<PathBuf>, title: String) {
+        *self.workbook_title.lock().unwrap() = title.clone();
         if let Some(discovery) = &mut self.discovery {
             let _ = discovery.update_workbook(path, title);
         }
@@ -414,7 +552,17 @@ impl SessionServer {
         }
         // Coalesce cells into ranges at broadcast point (not in network threads)
         let ranges = super::coalesce::coalesce_cells_to_ranges(&cells);
-        self.event_registry.broadcast(BroadcastEvent { revision, ranges });
+        self.event_registry.broadcast(BroadcastEvent { revision, ranges, structure: Vec::new() });
+    }
+
+    /// Broadcast structural changes (sheet, change) to subscribed connections.
+    /// Called from GUI thread after rows/cols are inserted or deleted and
+    /// after sheets are added or removed.
+    pub fn broadcast_structure(&self, revision: u64, structure: Vec<(usize, StructureChange)>) {
+        if !self.is_running() || structure.is_empty() {
+            return;
+        }
+        self.event_registry.broadcast(BroadcastEvent { revision, ranges: Vec::new(), structure });
     }
 
     /// Get the number of connected clients.
@@ -463,12 +611,13 @@ fn run_listener(
     shutdown: Arc<AtomicBool>,
     mode: ServerMode,
     token: String,
-    session_id: String,
+    identity: SessionIdentity,
     bridge: SessionBridgeHandle,
     rate_limiter_config: RateLimiterConfig,
     event_registry: EventRegistry,
     writer_lease: WriterLease,
     metrics: ServerMetrics,
+    idempotency_cache: IdempotencyCache,
 ) {
     while !shutdown.load(Ordering::SeqCst) {
         match listener.accept() {
@@ -490,19 +639,20 @@ fn run_listener(
 
                 log::debug!("Accepted connection from {}", addr);
                 let token = token.clone();
-                let session_id = session_id.clone();
+                let identity = identity.clone();
                 let bridge = bridge.clone();
                 let mode = mode;
                 let rl_config = rate_limiter_config;
                 let registry = event_registry.clone();
                 let lease = writer_lease.clone();
                 let conn_metrics = metrics.clone();
+                let cache = idempotency_cache.clone();
 
                 // Handle each connection in its own thread
                 thread::spawn(move || {
                     // Register connection with event registry
                     let (conn_id, event_rx) = registry.register();
-                    let result = handle_connection(stream, conn_id, mode, &token, &session_id, &bridge, rl_config, event_rx, &lease, &conn_metrics, &registry);
+                    let result = handle_connection(stream, conn_id, mode, &token, &identity, &bridge, rl_config, event_rx, &lease, &conn_metrics, &registry, &cache);
                     // Release writer lease if this connection held it
                     lease.release(conn_id);
                     // Unregister on disconnect
@@ -530,13 +680,14 @@ fn handle_connection(
     conn_id: u64,
     mode: ServerMode,
     expected_token: &str,
-    session_id: &str,
+    identity: &SessionIdentity,
     bridge: &SessionBridgeHandle,
     rate_limiter_config: RateLimiterConfig,
     event_rx: mpsc::Receiver<BroadcastEvent>,
     writer_lease: &WriterLease,
     metrics: &ServerMetrics,
     registry: &EventRegistry,
+    idempotency_cache: &IdempotencyCache,
 ) -> std::io::Result<()> {
     // Use shorter read timeout to allow event polling
     stream.set_nonblocking(false)?;
@@ -545,6 +696,7 @@ fn handle_connection(
 
     let reader = BufReader::new(stream.try_clone()?);
     let mut authenticated = false;
+    let mut protocol_version = PROTOCOL_VERSION;
     let mut rate_limiter = RateLimiter::new(rate_limiter_config);
     let mut subscriptions = ConnectionSubscriptions::new(event_rx);
     let mut lines = reader.lines();
@@ -570,7 +722,7 @@ fn handle_connection(
 
         // Check message size - disconnect immediately for oversized messages
         if line.len() > MAX_MESSAGE_SIZE {
-            send_error(&mut stream, None, ProtocolError::MessageTooLarge)?;
+            send_error(&mut stream, None, ProtocolErrorCode::MessageTooLarge)?;
             log::warn!("Connection {} sent oversized message ({}), disconnecting", conn_id, line.len());
             metrics.connections_closed_oversize.fetch_add(1, Ordering::Relaxed);
             return Ok(());
@@ -586,7 +738,7 @@ fn handle_connection(
             Err(e) => {
                 parse_failures += 1;
                 log::debug!("Malformed message ({}/{}): {}", parse_failures, MAX_PARSE_FAILURES, e);
-                send_error(&mut stream, None, ProtocolError::MalformedMessage)?;
+                send_error(&mut stream, None, ProtocolErrorCode::MalformedMessage)?;
 
                 // Disconnect after too many consecutive parse failures
                 if parse_failures >= MAX_PARSE_FAILURES {
@@ -598,22 +750,30 @@ fn handle_connection(
             }
         };
 
+        // Graceful close, allowed at any point: count it and hang up without a reply
+        if let ClientMessage::Bye(bye) = &msg {
+            log::debug!("Connection {} said bye ({})", conn_id, bye.id);
+            metrics.connections_closed_graceful.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+
         // First message must be Hello
         if !authenticated {
             match msg {
                 ClientMessage::Hello(hello) => {
                     if hello.token != expected_token {
-                        send_error(&mut stream, Some(hello.id), ProtocolError::AuthFailed)?;
+                        send_error(&mut stream, Some(hello.id), ProtocolErrorCode::AuthFailed)?;
                         return Ok(());
                     }
 
                     // Check protocol version
                     if hello.protocol_version > PROTOCOL_VERSION {
-                        send_error(&mut stream, Some(hello.id), ProtocolError::ProtocolMismatch)?;
+                        send_error(&mut stream, Some(hello.id), ProtocolErrorCode::ProtocolMismatch)?;
                         return Ok(());
                     }
 
                     authenticated = true;
+                    protocol_version = hello.protocol_version.min(PROTOCOL_VERSION);
 
                     // Get current revision from engine via inspect
                     let revision = match bridge.inspect(InspectRequest {
@@ -626,15 +786,16 @@ fn handle_connection(
 
                     let response = ServerMessage::Welcome(WelcomeMessage {
                         id: hello.id,
-                        session_id: session_id.to_string(),
-                        protocol_version: hello.protocol_version.min(PROTOCOL_VERSION),
+                        session_id: identity.session_id.clone(),
+                        protocol_version,
                         revision,
-                        capabilities: vec!["apply_ops".to_string(), "inspect".to_string()],
+                        capabilities: server_capabilities(),
+                        max_message_bytes: Some(MAX_MESSAGE_SIZE as u64),
                     });
                     send_message(&mut stream, &response)?;
                 }
                 _ => {
-                    send_error(&mut stream, None, ProtocolError::AuthFailed)?;
+                    send_error(&mut stream, None, ProtocolErrorCode::AuthFailed)?;
                     return Ok(());
                 }
             }
@@ -642,7 +803,7 @@ fn handle_connection(
         }
 
         // Check rate limit and handle authenticated messages
-        let response = handle_message_with_rate_limit(msg, conn_id, mode, bridge, &mut rate_limiter, &mut subscriptions, writer_lease, metrics, registry);
+        let response = handle_message_with_rate_limit(msg, conn_id, mode, identity, protocol_version, bridge, &mut rate_limiter, &mut subscriptions, writer_lease, metrics, registry, idempotency_cache);
         send_message(&mut stream, &response)?;
     }
 }
@@ -652,12 +813,15 @@ fn handle_message_with_rate_limit(
     msg: ClientMessage,
     conn_id: u64,
     mode: ServerMode,
+    identity: &SessionIdentity,
+    protocol_version: u32,
     bridge: &SessionBridgeHandle,
     rate_limiter: &mut RateLimiter,
     subscriptions: &mut ConnectionSubscriptions,
     writer_lease: &WriterLease,
     metrics: &ServerMetrics,
     registry: &EventRegistry,
+    idempotency_cache: &IdempotencyCache,
 ) -> ServerMessage {
     // Extract request ID for error responses
     let request_id = match &msg {
@@ -668,6 +832,8 @@ fn handle_message_with_rate_limit(
         ClientMessage::Inspect(i) => Some(i.id.clone()),
         ClientMessage::Ping(p) => Some(p.id.clone()),
         ClientMessage::Stats(s) => Some(s.id.clone()),
+        ClientMessage::SessionInfo(s) => Some(s.id.clone()),
+        ClientMessage::Bye(b) => Some(b.id.clone()),
     };
 
     // Check rate limit based on message type
@@ -679,6 +845,8 @@ fn handle_message_with_rate_limit(
         ClientMessage::Inspect(_) => rate_limiter.try_inspect(),
         ClientMessage::Ping(_) => rate_limiter.try_ping(),
         ClientMessage::Stats(_) => rate_limiter.try_ping(), // Stats is cheap like ping
+        ClientMessage::SessionInfo(_) => rate_limiter.try_ping(),
+        ClientMessage::Bye(_) => Ok(()),
     };
 
     if let Err(e) = rate_check {
@@ -688,10 +856,32 @@ fn handle_message_with_rate_limit(
             e.available,
             e.retry_after_ms
         );
-        return ServerMessage::Error(ProtocolError::rate_limited_error(request_id, e.retry_after_ms));
+        return ServerMessage::Error(rate_limited_error(request_id, e.retry_after_ms));
     }
 
-    handle_message(msg, conn_id, mode, bridge, subscriptions, writer_lease, metrics, registry)
+    handle_message(msg, conn_id, mode, identity, protocol_version, bridge, subscriptions, writer_lease, metrics, registry, idempotency_cache)
+}
+
+/// The reply for a keyed apply_ops the cache has already seen, or None to
+/// apply it.
+fn idempotency_reply(lookup: IdempotencyLookup, id: &str) -> Option<ServerMessage> {
+    match lookup {
+        IdempotencyLookup::Apply => None,
+        IdempotencyLookup::Done(cached) => {
+            Some(ServerMessage::ApplyOpsResult(ApplyOpsResultMessage { id: id.to_string(), ..cached }))
+        }
+        IdempotencyLookup::InFlight => Some(ServerMessage::Error(ErrorMessage {
+            retry_after_ms: Some(IN_FLIGHT_RETRY_MS),
+            ..ProtocolErrorCode::Timeout.to_error_message(Some(id.to_string()))
+        })),
+        // Not in the frozen code set; clients see it as `Other`.
+        IdempotencyLookup::Mismatch => Some(ServerMessage::Error(ErrorMessage {
+            id: Some(id.to_string()),
+            code: ProtocolErrorCode::Other("idempotency_key_reused".to_string()),
+            message: "Idempotency key was already used with different ops".to_string(),
+            retry_after_ms: None,
+        })),
+    }
 }
 
 /// Handle a single message and return the response.
@@ -699,18 +889,22 @@ fn handle_message(
     msg: ClientMessage,
     conn_id: u64,
     mode: ServerMode,
+    identity: &SessionIdentity,
+    protocol_version: u32,
     bridge: &SessionBridgeHandle,
     subscriptions: &mut ConnectionSubscriptions,
     writer_lease: &WriterLease,
     metrics: &ServerMetrics,
     registry: &EventRegistry,
+    idempotency_cache: &IdempotencyCache,
 ) -> ServerMessage {
     match msg {
         ClientMessage::Hello(h) => {
             // Already authenticated, treat as error
             ServerMessage::Error(ErrorMessage {
                 id: Some(h.id),
-                code: "already_authenticated".to_string(),
+                // Not in the frozen code set; clients see it as `Other`.
+                code: ProtocolErrorCode::Other("already_authenticated".to_string()),
                 message: "Already authenticated".to_string(),
                 retry_after_ms: None,
             })
@@ -718,21 +912,39 @@ fn handle_message(
         ClientMessage::ApplyOps(apply) => {
             if mode == ServerMode::ReadOnly {
                 return ServerMessage::Error(
-                    ProtocolError::ReadOnlyMode.to_error_message(Some(apply.id)),
+                    ProtocolErrorCode::ReadOnlyMode.to_error_message(Some(apply.id)),
                 );
             }
 
+            // A retry of a recent keyed apply gets the original result, without
+            // needing the lease (the first attempt's connection may still hold it)
+            let idempotency = apply.idempotency_key.as_deref()
+                .map(|key| (key, IdempotencyCache::ops_hash(&apply.ops)));
+            if let Some((key, ops_hash)) = idempotency {
+                if let Some(reply) = idempotency_reply(idempotency_cache.get(key, ops_hash), &apply.id) {
+                    return reply;
+                }
+            }
+
             // Try to acquire writer lease
             if let Err(retry_after_ms) = writer_lease.try_acquire(conn_id) {
                 metrics.writer_conflict_count.fetch_add(1, Ordering::Relaxed);
                 return ServerMessage::Error(ErrorMessage {
                     id: Some(apply.id),
-                    code: ProtocolError::WriterConflict.code().to_string(),
-                    message: ProtocolError::WriterConflict.message().to_string(),
+                    code: ProtocolErrorCode::WriterConflict,
+                    message: ProtocolErrorCode::WriterConflict.message().to_string(),
                     retry_after_ms: Some(retry_after_ms),
                 });
             }
 
+            // Claim the key under the lease: a first attempt that outlived its
+            // lease may still be applying, and must not be applied twice
+            if let Some((key, ops_hash)) = idempotency {
+                if let Some(reply) = idempotency_reply(idempotency_cache.claim(key, ops_hash), &apply.id) {
+                    return reply;
+                }
+            }
+
             // Send through bridge to engine thread
             let req = ApplyOpsRequest {
                 request_id: apply.id.clone(),
@@ -744,7 +956,11 @@ fn handle_message(
 
             match bridge.apply_ops(req) {
                 Ok(resp) => {
-                    ServerMessage::ApplyOpsResult(ApplyOpsResultMessage {
+                    // Each batch that applied anything commits one revision
+                    if resp.applied > 0 {
+                        metrics.revision_count.fetch_add(1, Ordering::Relaxed);
+                    }
+                    let result = ApplyOpsResultMessage {
                         id: apply.id,
                         applied: resp.applied,
                         total: resp.total,
@@ -752,7 +968,7 @@ fn handle_message(
                         error: resp.error.map(|e| match e {
                             crate::session_server::bridge::ApplyOpsError::RevisionMismatch { expected, actual } => {
                                 OpError {
-                                    code: "revision_mismatch".to_string(),
+                                    code: ProtocolErrorCode::RevisionMismatch,
                                     message: format!("Expected revision {} but current is {}", expected, actual),
                                     op_index: 0,
                                     suggestion: Some("Retry with updated revision".to_string()),
@@ -760,12 +976,19 @@ fn handle_message(
                             }
                             crate::session_server::bridge::ApplyOpsError::OpFailed(op_err) => op_err,
                         }),
-                    })
+                    };
+                    if let Some((key, _)) = idempotency {
+                        idempotency_cache.complete(key, result.clone());
+                    }
+                    ServerMessage::ApplyOpsResult(result)
                 }
                 Err(_) => {
+                    if let Some((key, _)) = idempotency {
+                        idempotency_cache.abandon(key);
+                    }
                     ServerMessage::Error(ErrorMessage {
                         id: Some(apply.id),
-                        code: "internal_error".to_string(),
+                        code: ProtocolErrorCode::InternalError,
                         message: "Bridge communication failed".to_string(),
                         retry_after_ms: None,
                     })
@@ -802,7 +1025,7 @@ fn handle_message(
                 }),
                 Err(_) => ServerMessage::Error(ErrorMessage {
                     id: Some(inspect.id),
-                    code: "internal_error".to_string(),
+                    code: ProtocolErrorCode::InternalError,
                     message: "Bridge communication failed".to_string(),
                     retry_after_ms: None,
                 }),
@@ -817,7 +1040,35 @@ fn handle_message(
             connections_refused_limit: metrics.connections_refused_limit.load(Ordering::Relaxed),
             dropped_events_total: registry.dropped_events_count(),
             active_connections: registry.connection_count() as u64,
+            uptime_seconds: metrics.started_at.elapsed().as_secs(),
+            revision_count: metrics.revision_count.load(Ordering::Relaxed),
+            connections_closed_graceful: metrics.connections_closed_graceful.load(Ordering::Relaxed),
         }),
+        ClientMessage::SessionInfo(info) => {
+            let revision = match bridge.inspect(InspectRequest {
+                request_id: info.id.clone(),
+                target: InspectTarget::Workbook,
+            }) {
+                Ok(resp) => resp.current_revision,
+                Err(_) => {
+                    return ServerMessage::Error(ErrorMessage {
+                        id: Some(info.id),
+                        code: ProtocolErrorCode::InternalError,
+                        message: "Bridge communication failed".to_string(),
+                        retry_after_ms: None,
+                    })
+                }
+            };
+            ServerMessage::SessionInfoResult(SessionInfoResultMessage {
+                id: info.id,
+                session_id: identity.session_id.clone(),
+                protocol_version,
+                capabilities: server_capabilities(),
+                workbook_title: identity.workbook_title.lock().unwrap().clone(),
+                revision,
+            })
+        }
+        ClientMessage::Bye(_) => unreachable!("bye is handled by the connection loop"),
     }
 }
 
@@ -830,7 +1081,7 @@ fn send_message(stream: &mut TcpStream, msg: &ServerMessage) -> std::io::Result<
 }
 
 /// Send an error message to the client.
-fn send_error(stream: &mut TcpStream, id: Option<String>, error: ProtocolError) -> std::io::Result<()> {
+fn send_error(stream: &mut TcpStream, id: Option<String>, error: ProtocolErrorCode) -> std::io::Result<()> {
     let msg = ServerMessage::Error(error.to_error_message(id));
     send_message(stream, &msg)
 }
@@ -840,7 +1091,7 @@ mod tests {
     use super::*;
     use crate::session_server::bridge::{
         SessionRequest, ApplyOpsResponse, InspectResponse,
-        SubscribeResponse, UnsubscribeResponse,
+        SubscribeResponse, UnsubscribeResponse, apply_session_ops,
     };
     use std::io::{BufRead, BufReader, Write};
     use std::net::TcpStream;
@@ -1032,7 +1283,7 @@ mod tests {
 
         let msg: ServerMessage = serde_json::from_str(&response).unwrap();
         if let ServerMessage::Error(e) = msg {
-            assert_eq!(e.code, "auth_failed");
+            assert_eq!(e.code, ProtocolErrorCode::AuthFailed);
         } else {
             panic!("Expected error message");
         }
@@ -1238,7 +1489,7 @@ mod tests {
         reader.read_line(&mut response).unwrap();
         let msg: ServerMessage = serde_json::from_str(&response).unwrap();
         if let ServerMessage::Error(e) = msg {
-            assert_eq!(e.code, "rate_limited");
+            assert_eq!(e.code, ProtocolErrorCode::RateLimited);
             assert!(e.retry_after_ms.is_some(), "Should include retry_after_ms");
         } else {
             panic!("Expected rate_limited error, got {:?}", msg);
@@ -1334,6 +1585,103 @@ mod tests {
         server.stop();
     }
 
+    #[test]
+    fn test_insert_rows_op_emits_structure_event() {
+        use visigrid_engine::workbook::Workbook;
+
+        let mut server = SessionServer::new();
+        let (tx, rx) = mpsc::channel::<SessionRequest>();
+        server
+            .start(SessionServerConfig {
+                mode: ServerMode::Apply,
+                workbook_path: None,
+                workbook_title: "Test".to_string(),
+                bridge: Some(SessionBridgeHandle::new(tx)),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let addr = server.bound_addr().unwrap();
+        let token = server.token().unwrap().to_string();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut response = String::new();
+
+            let hello = serde_json::json!({
+                "type": "hello",
+                "id": "1",
+                "client": "test",
+                "version": "1.0.0",
+                "token": token,
+                "protocol_version": 1
+            });
+            writeln!(stream, "{}", hello).unwrap();
+            reader.read_line(&mut response).unwrap();
+
+            // A range filter below the insert point still gets the event
+            writeln!(stream, r#"{{"type":"subscribe","id":"2","topics":["cells:0:A10:C20"]}}"#).unwrap();
+            response.clear();
+            reader.read_line(&mut response).unwrap();
+            assert!(response.contains("subscribed"), "got {}", response);
+
+            writeln!(
+                stream,
+                r#"{{"type":"apply_ops","id":"3","ops":[{{"op":"insert_rows","at":2,"count":3}}],"atomic":true}}"#
+            )
+            .unwrap();
+
+            // The result and the event can arrive in either order
+            let mut result = None;
+            let mut structure = None;
+            while result.is_none() || structure.is_none() {
+                response.clear();
+                reader.read_line(&mut response).expect("expected apply result and event");
+                match serde_json::from_str::<ServerMessage>(&response).unwrap() {
+                    ServerMessage::ApplyOpsResult(r) => result = Some(r),
+                    ServerMessage::Event(EventMessage { revision, payload: EventPayload::SheetStructureChanged { sheet, change }, .. }) => {
+                        structure = Some((revision, sheet, change));
+                    }
+                    other => panic!("Unexpected message: {:?}", other),
+                }
+            }
+            (result.unwrap(), structure.unwrap())
+        });
+
+        // This thread stands in for the GUI thread: it owns the workbook and
+        // the server and applies requests the way the app does
+        let mut workbook = Workbook::new();
+        workbook.active_sheet_mut().set_value(2, 0, "moved");
+        while !client.is_finished() {
+            match rx.recv_timeout(std::time::Duration::from_millis(20)) {
+                Ok(SessionRequest::ApplyOps { req, reply }) => {
+                    let _ = reply.send(apply_session_ops(&mut workbook, &server, &req).response);
+                }
+                Ok(SessionRequest::Inspect { reply, .. }) => {
+                    let _ = reply.send(InspectResponse {
+                        current_revision: workbook.revision(),
+                        result: InspectResult::Workbook(WorkbookInfo {
+                            sheet_count: 1,
+                            active_sheet: 0,
+                            title: "Test".to_string(),
+                        }),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let (result, (revision, sheet, change)) = client.join().unwrap();
+        assert_eq!(result.applied, 1);
+        assert_eq!(result.revision, workbook.revision());
+        assert_eq!(revision, workbook.revision());
+        assert_eq!((sheet, change), (0, StructureChange::InsertedRows { at: 2, count: 3 }));
+        assert_eq!(workbook.active_sheet().get_raw(5, 0), "moved");
+
+        server.stop();
+    }
+
     #[test]
     fn test_server_subscribe_unsubscribe() {
         let (bridge, _handler) = create_test_bridge();
@@ -1660,6 +2008,7 @@ mod tests {
             registry.broadcast(BroadcastEvent {
                 revision: i,
                 ranges: vec![],
+                structure: vec![],
             });
         }
 
@@ -1679,6 +2028,7 @@ mod tests {
         registry.broadcast(BroadcastEvent {
             revision: 1,
             ranges: vec![],
+            structure: vec![],
         });
 
         // No drops because no connections
@@ -1691,6 +2041,7 @@ mod tests {
             registry.broadcast(BroadcastEvent {
                 revision: 1,
                 ranges: vec![],
+                structure: vec![],
             });
         }
 
@@ -1832,6 +2183,55 @@ mod tests {
         server.stop();
     }
 
+    #[test]
+    fn test_bye_counts_as_graceful_close() {
+        let (bridge, _handler) = create_test_bridge();
+        let mut server = SessionServer::new();
+        server
+            .start(SessionServerConfig {
+                mode: ServerMode::Apply,
+                workbook_path: None,
+                workbook_title: "Test".to_string(),
+                bridge: Some(bridge),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let addr = server.bound_addr().unwrap();
+        let token = server.token().unwrap().to_string();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(2))).unwrap();
+
+        let hello = serde_json::json!({
+            "type": "hello",
+            "id": "1",
+            "client": "test",
+            "version": "1.0.0",
+            "token": token,
+            "protocol_version": 1
+        });
+        writeln!(stream, "{}", hello).unwrap();
+
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut response = String::new();
+        reader.read_line(&mut response).unwrap();
+
+        // Bye gets no reply: the server just closes the connection
+        writeln!(stream, "{}", serde_json::json!({ "type": "bye", "id": "2" })).unwrap();
+        response.clear();
+        assert_eq!(reader.read_line(&mut response).unwrap(), 0, "expected EOF, got {:?}", response);
+
+        thread::sleep(std::time::Duration::from_millis(100));
+
+        let metrics = server.metrics();
+        assert_eq!(metrics.connections_closed_graceful.load(Ordering::Relaxed), 1);
+        assert_eq!(metrics.connections_closed_parse_failures.load(Ordering::Relaxed), 0);
+        assert_eq!(metrics.connections_closed_oversize.load(Ordering::Relaxed), 0);
+
+        server.stop();
+    }
+
     #[test]
     fn test_metrics_writer_conflict_incremented() {
         let (bridge, _handler) = create_test_bridge();
@@ -1965,6 +2365,7 @@ mod tests {
             assert_eq!(result.dropped_events_total, 0);
             // At least one connection (ourselves)
             assert!(result.active_connections >= 1);
+            assert_eq!(result.revision_count, 0);
         } else {
             panic!("Expected StatsResult, got {:?}", msg);
         }
@@ -1972,6 +2373,132 @@ mod tests {
         server.stop();
     }
 
+    #[test]
+    fn test_stats_revision_count_increases_after_applies() {
+        let (bridge, _handler) = create_test_bridge();
+        let mut server = SessionServer::new();
+        server
+            .start(SessionServerConfig {
+                mode: ServerMode::Apply,
+                workbook_path: None,
+                workbook_title: "Test".to_string(),
+                bridge: Some(bridge),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let addr = server.bound_addr().unwrap();
+        let token = server.token().unwrap().to_string();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(2))).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut response = String::new();
+
+        let hello = serde_json::json!({
+            "type": "hello",
+            "id": "1",
+            "client": "test",
+            "version": "1.0.0",
+            "token": token,
+            "protocol_version": 1
+        });
+        writeln!(stream, "{}", hello).unwrap();
+        reader.read_line(&mut response).unwrap();
+
+        let stats = |id: &str, stream: &mut TcpStream, reader: &mut BufReader<TcpStream>| {
+            writeln!(stream, "{}", serde_json::json!({"type": "stats", "id": id})).unwrap();
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            match serde_json::from_str::<ServerMessage>(&line).unwrap() {
+                ServerMessage::StatsResult(result) => result,
+                other => panic!("Expected StatsResult, got {:?}", other),
+            }
+        };
+
+        let before = stats("2", &mut stream, &mut reader);
+        assert_eq!(before.revision_count, 0);
+
+        for id in ["3", "4"] {
+            let apply = serde_json::json!({
+                "type": "apply_ops",
+                "id": id,
+                "ops": [{"op": "set_cell_value", "row": 0, "col": 0, "value": id}],
+                "atomic": true
+            });
+            writeln!(stream, "{}", apply).unwrap();
+            response.clear();
+            reader.read_line(&mut response).unwrap();
+            assert!(response.contains("apply_ops_result"), "{}", response);
+        }
+
+        let after = stats("5", &mut stream, &mut reader);
+        assert_eq!(after.revision_count, 2);
+        assert!(after.uptime_seconds >= before.uptime_seconds);
+
+        server.stop();
+    }
+
+    #[test]
+    fn test_session_info_matches_welcome() {
+        let (bridge, _handler) = create_test_bridge();
+        let mut server = SessionServer::new();
+        server
+            .start(SessionServerConfig {
+                mode: ServerMode::Apply,
+                workbook_path: None,
+                workbook_title: "Test".to_string(),
+                bridge: Some(bridge),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let addr = server.bound_addr().unwrap();
+        let token = server.token().unwrap().to_string();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(2))).unwrap();
+
+        let hello = serde_json::json!({
+            "type": "hello",
+            "id": "1",
+            "client": "test",
+            "version": "1.0.0",
+            "token": token,
+            "protocol_version": 1
+        });
+        writeln!(stream, "{}", hello).unwrap();
+
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut response = String::new();
+        reader.read_line(&mut response).unwrap();
+        let welcome = match serde_json::from_str::<ServerMessage>(&response).unwrap() {
+            ServerMessage::Welcome(w) => w,
+            other => panic!("Expected Welcome, got {:?}", other),
+        };
+
+        // A renamed workbook shows up without reconnecting
+        server.update_workbook(None, "Renamed".to_string());
+
+        writeln!(stream, "{}", serde_json::json!({"type": "session_info", "id": "2"})).unwrap();
+        response.clear();
+        reader.read_line(&mut response).unwrap();
+
+        match serde_json::from_str::<ServerMessage>(&response).unwrap() {
+            ServerMessage::SessionInfoResult(info) => {
+                assert_eq!(info.id, "2");
+                assert_eq!(info.session_id, welcome.session_id);
+                assert_eq!(info.protocol_version, welcome.protocol_version);
+                assert_eq!(info.capabilities, welcome.capabilities);
+                assert_eq!(info.revision, welcome.revision);
+                assert_eq!(info.workbook_title, "Renamed");
+            }
+            other => panic!("Expected SessionInfoResult, got {:?}", other),
+        }
+
+        server.stop();
+    }
+
     // ========================================================================
     // Connection Limit Tests
     // ========================================================================
@@ -2120,4 +2647,131 @@ mod tests {
 
         server.stop();
     }
+
+    #[test]
+    fn test_duplicate_idempotency_key_not_reapplied() {
+        use std::sync::atomic::AtomicUsize;
+
+        // Bridge that counts applies and reports the count as the revision
+        let (tx, rx) = mpsc::channel::<SessionRequest>();
+        let bridge = SessionBridgeHandle::new(tx);
+        let applies = Arc::new(AtomicUsize::new(0));
+        let handler_applies = applies.clone();
+        let _handler = thread::spawn(move || {
+            while let Ok(req) = rx.recv() {
+                if let SessionRequest::ApplyOps { req, reply } = req {
+                    let revision = handler_applies.fetch_add(1, Ordering::SeqCst) as u64 + 1;
+                    let _ = reply.send(ApplyOpsResponse {
+                        applied: req.ops.len(),
+                        total: req.ops.len(),
+                        current_revision: revision,
+                        error: None,
+                    });
+                }
+            }
+        });
+
+        let mut server = SessionServer::new();
+        server
+            .start(SessionServerConfig {
+                mode: ServerMode::Apply,
+                workbook_path: None,
+                workbook_title: "Test".to_string(),
+                bridge: Some(bridge),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let addr = server.bound_addr().unwrap();
+        let token = server.token().unwrap().to_string();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+        let hello = serde_json::json!({
+            "type": "hello",
+            "id": "1",
+            "client": "test",
+            "version": "1.0.0",
+            "token": token,
+            "protocol_version": 1
+        });
+        writeln!(stream, "{}", hello).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut response = String::new();
+        reader.read_line(&mut response).unwrap();
+
+        let mut send = |id: &str, key: &str, value: &str| -> ServerMessage {
+            let msg = serde_json::json!({
+                "type": "apply_ops",
+                "id": id,
+                "ops": [
+                    {"op": "set_cell_value", "row": 0, "col": 0, "value": value}
+                ],
+                "atomic": true,
+                "idempotency_key": key
+            });
+            writeln!(stream, "{}", msg).unwrap();
+            response.clear();
+            reader.read_line(&mut response).unwrap();
+            serde_json::from_str::<ServerMessage>(&response).unwrap()
+        };
+        let mut apply = |id: &str, key: &str| -> ApplyOpsResultMessage {
+            match send(id, key, "Hello") {
+                ServerMessage::ApplyOpsResult(result) => result,
+                other => panic!("Expected ApplyOpsResult, got {:?}", other),
+            }
+        };
+
+        let first = apply("2", "k1");
+        assert_eq!(first.revision, 1);
+
+        // Retry with the same key replays the result under the new id
+        let retry = apply("3", "k1");
+        assert_eq!(retry.id, "3");
+        assert_eq!(retry.revision, 1);
+        assert_eq!(retry.applied, 1);
+        assert_eq!(applies.load(Ordering::SeqCst), 1, "Duplicate key must not re-apply");
+
+        // A new key applies normally
+        let other = apply("4", "k2");
+        assert_eq!(other.revision, 2);
+        assert_eq!(applies.load(Ordering::SeqCst), 2);
+
+        // Reusing a key with different ops is rejected, not answered with the old result
+        match send("5", "k1", "Goodbye") {
+            ServerMessage::Error(err) => {
+                assert_eq!(err.id.as_deref(), Some("5"));
+                assert_eq!(err.code.as_str(), "idempotency_key_reused");
+            }
+            other => panic!("Expected Error, got {:?}", other),
+        }
+        assert_eq!(applies.load(Ordering::SeqCst), 2);
+
+        server.stop();
+    }
+
+    #[test]
+    fn test_idempotency_cache_claims_keys_in_flight() {
+        let cache = IdempotencyCache::new();
+        let hello = IdempotencyCache::ops_hash(&[Op::SetCellValue { sheet: 0, row: 0, col: 0, value: "Hello".to_string() }]);
+        let other = IdempotencyCache::ops_hash(&[Op::SetCellValue { sheet: 0, row: 0, col: 0, value: "Other".to_string() }]);
+        let result = |revision| ApplyOpsResultMessage { id: "1".to_string(), applied: 1, total: 1, revision, error: None };
+
+        assert!(matches!(cache.claim("k", hello), IdempotencyLookup::Apply));
+        // A retry that took the lease while the first attempt runs waits for it
+        assert!(matches!(cache.claim("k", hello), IdempotencyLookup::InFlight));
+        assert!(matches!(cache.get("k", other), IdempotencyLookup::Mismatch));
+
+        cache.complete("k", result(1));
+        assert!(matches!(cache.get("k", hello), IdempotencyLookup::Done(r) if r.revision == 1));
+        // Completing again replaces the entry rather than adding a second one
+        cache.complete("k", result(2));
+        assert!(matches!(cache.claim("k", hello), IdempotencyLookup::Done(r) if r.revision == 2));
+        assert_eq!(cache.inner.lock().unwrap().len(), 1);
+
+        // An apply that never reached the engine frees its key
+        assert!(matches!(cache.claim("lost", hello), IdempotencyLookup::Apply));
+        cache.abandon("lost");
+        assert!(matches!(cache.claim("lost", hello), IdempotencyLookup::Apply));
+    }
 }
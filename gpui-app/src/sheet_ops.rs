@@ -565,6 +565,7 @@ impl Spreadsheet {
     /// Add a new sheet and switch to it
     pub fn add_sheet(&mut self, cx: &mut Context<Self>) {
         let new_index = self.wb_mut(cx, |wb| wb.add_sheet());
+        self.note_structure_change(new_index, crate::session_server::StructureChange::AddedSheet, cx);
         self.wb_mut(cx, |wb| wb.set_active_sheet(new_index));
         self.update_cached_sheet_id(cx);  // Keep per-sheet sizing cache in sync
         self.debug_assert_sheet_cache_sync(cx);  // Catch desync immediately
@@ -830,6 +831,7 @@ impl Spreadsheet {
     pub fn delete_sheet(&mut self, index: usize, cx: &mut Context<Self>) {
         if self.wb_mut(cx, |wb| wb.delete_sheet(index)) {
             self.is_modified = true;
+            self.note_structure_change(index, crate::session_server::StructureChange::RemovedSheet, cx);
             self.sheet_context_menu = None;
             self.request_title_refresh(cx);
         } else {
@@ -78,7 +78,7 @@ pub(crate) fn render_f1_help_overlay(app: &Spreadsheet, cx: &App) -> impl IntoEl
                     .pb(px(8.0))
                     .text_size(px(12.0))
                     .text_color(text_disabled)
-                    .child(func.description)
+                    .child(func.description())
             )
             // Parameters
             .when(!func.parameters.is_empty(), |d| {
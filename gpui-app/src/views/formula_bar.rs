@@ -1209,6 +1209,14 @@ pub fn render_hover_docs(
                         .font_weight(FontWeight::BOLD)
                         .child(func.name)
                 )
+                .when(func.is_volatile(), |el| {
+                    el.child(
+                        div()
+                            .text_color(text_muted)
+                            .text_size(px(11.0))
+                            .child("volatile · recalculates every time")
+                    )
+                })
         )
         // Signature
         .child(
@@ -1223,7 +1231,7 @@ pub fn render_hover_docs(
             div()
                 .text_color(text_muted)
                 .text_size(px(12.0))
-                .child(func.description)
+                .child(func.description())
         )
         // Parameter list
         .when(!param_descs.is_empty(), |parent| {